@@ -6,6 +6,9 @@ let version = "0.3.0"
 
 // Checksum is updated by release automation
 let checksum = "bd64574f9081890c8e340600cd68fdad0ff2434b4a12842520783b00ab6d2558"
+// Checksum of IrohSwiftFFIMinimal.xcframework.zip, empty until a release
+// publishes it
+let minimalChecksum = ""
 
 // Check if using local development mode
 // Set IROH_LOCAL_DEV=1 environment variable to use local XCFramework
@@ -25,6 +28,25 @@ let binaryTarget: Target = useLocalBinary
         checksum: checksum
     )
 
+// The minimal library (IROH_MINIMAL=1 ./scripts/build-xcframework.sh), for
+// targets that only put and get blobs
+let useLocalMinimalBinary = FileManager.default.fileExists(atPath: "IrohSwiftFFIMinimal.xcframework")
+let minimalBinaryTarget: Target?
+if useLocalMinimalBinary {
+    minimalBinaryTarget = .binaryTarget(
+        name: "IrohSwiftFFIMinimal",
+        path: "IrohSwiftFFIMinimal.xcframework"
+    )
+} else if !useLocalBinary && !minimalChecksum.isEmpty {
+    minimalBinaryTarget = .binaryTarget(
+        name: "IrohSwiftFFIMinimal",
+        url: "https://github.com/arkavo-org/iroh-swift/releases/download/\(version)/IrohSwiftFFIMinimal.xcframework.zip",
+        checksum: minimalChecksum
+    )
+} else {
+    minimalBinaryTarget = nil
+}
+
 let package = Package(
     name: "IrohSwift",
     platforms: [
@@ -68,3 +90,28 @@ let package = Package(
         ),
     ]
 )
+
+if let minimalBinaryTarget {
+    package.products.append(
+        .library(
+            name: "IrohSwiftMinimal",
+            targets: ["IrohSwiftMinimal"]
+        )
+    )
+    package.targets += [
+        minimalBinaryTarget,
+        .target(
+            name: "IrohSwiftMinimal",
+            dependencies: ["IrohSwiftFFIMinimal"],
+            path: "Sources/IrohSwiftMinimal",
+            swiftSettings: [
+                .enableExperimentalFeature("StrictConcurrency")
+            ],
+            linkerSettings: [
+                .linkedFramework("SystemConfiguration"),
+                .linkedFramework("Security"),
+                .linkedLibrary("resolv"),
+            ]
+        ),
+    ]
+}
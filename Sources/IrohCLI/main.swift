@@ -144,8 +144,8 @@ struct IrohCLI {
         print("Subscribing to events (Ctrl+C to exit)...")
         print("")
 
-        // Subscribe and print events
-        let subscription = try await doc.subscribe()
+        // Subscribe and print events, with the content of small messages
+        let subscription = try await doc.subscribe(fetchContent: true, maxContentSize: 1024)
         for try await event in subscription {
             switch event {
             case .insertLocal(let entry):
@@ -156,16 +156,10 @@ struct IrohCLI {
                 let key = String(data: entry.key, encoding: .utf8) ?? "raw"
                 print("[REMOTE from \(peer.prefix(8))] Received '\(key)'")
 
-                // Fetch and print the actual text content for small messages
-                if entry.contentSize < 1024 {
-                    do {
-                        let data = try await entry.content(from: doc)
-                        let text = String(data: data, encoding: .utf8) ?? "binary"
-                        print("   -> Content: \"\(text)\"")
-                    } catch {
-                        print("   -> (content pending)")
-                    }
-                }
+            case .contentFetched(_, let entry, let content):
+                let key = String(data: entry.key, encoding: .utf8) ?? "raw"
+                let text = String(data: content, encoding: .utf8) ?? "binary"
+                print("   -> Content of '\(key)': \"\(text)\"")
 
             case .neighborUp(let peer):
                 print("[PEER UP] \(peer.prefix(8))...")
@@ -181,6 +175,9 @@ struct IrohCLI {
 
             case .pendingContentReady:
                 print("[ALL CONTENT READY]")
+
+            case .replayed:
+                break
             }
         }
     }
@@ -127,9 +127,56 @@ public struct IrohAuthor: Sendable, Hashable {
     ///
     /// Use this when you want to manage key storage yourself.
     ///
+    /// - Parameter entropy: Source of the 32 secret key bytes, such as
+    ///   `SecRandomCopyBytes` or an HSM-provided seed. If nil, uses the system RNG.
     /// - Returns: A newly created author.
+    /// - Throws: `IrohError.authorCreationFailed` if creation fails or the
+    ///           entropy source returns false.
+    public static func create(entropy: EntropySource? = nil) async throws -> IrohAuthor {
+        try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                AuthorContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohAuthorCreateCallback(
+                userdata: box,
+                on_success: { userdata, secret, id in
+                    let box = Unmanaged<AuthorContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+
+                    let secretData = withUnsafeBytes(of: secret.bytes) { Data($0) }
+                    let publicKeyData = withUnsafeBytes(of: id.bytes) { Data($0) }
+
+                    let author = IrohAuthor(secret: secretData, publicKey: publicKeyData)
+                    box.continuation.resume(returning: author)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<AuthorContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.authorCreationFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            withEntropySource(entropy) { source in
+                iroh_author_create_with_entropy(source, callback)
+            }
+        }
+    }
+
+    /// Create the deterministic test author with `seed`.
+    ///
+    /// The same seed always gives the same author, so tests get the same
+    /// author IDs on every run. Pair with `IrohConfig.testing(seed:)`.
+    /// Never use this for real authors: anyone who knows the seed has the key.
+    ///
+    /// - Parameter seed: Any string, such as "alice".
+    /// - Returns: The author for `seed`.
     /// - Throws: `IrohError.authorCreationFailed` if creation fails.
-    public static func create() async throws -> IrohAuthor {
+    public static func testing(seed: String) async throws -> IrohAuthor {
         try await withCheckedThrowingContinuation { continuation in
             let box = Unmanaged.passRetained(
                 AuthorContinuationBox(continuation)
@@ -154,11 +201,11 @@ public struct IrohAuthor: Sendable, Hashable {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.authorCreationFailed(message))
+                    box.continuation.resume(throwing: IrohError.authorCreationFailed(message, operationId: currentOperationId()))
                 }
             )
 
-            iroh_author_create(callback)
+            iroh_author_create_from_seed(seed, callback)
         }
     }
 
@@ -201,7 +248,7 @@ public struct IrohAuthor: Sendable, Hashable {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.authorCreationFailed(message))
+                    box.continuation.resume(throwing: IrohError.authorCreationFailed(message, operationId: currentOperationId()))
                 }
             )
 
@@ -218,6 +265,65 @@ public struct IrohAuthor: Sendable, Hashable {
         return author
     }
 
+    // MARK: - Signing
+
+    /// Sign an arbitrary payload, such as an invitation or message, as this author.
+    ///
+    /// Payload signatures are bound to this API and can never be used as
+    /// signatures on document entries.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let signature = author.sign(invitation)
+    /// // On the receiving side:
+    /// let trusted = IrohAuthor.verify(signature, of: invitation, publicKey: senderKey)
+    /// ```
+    ///
+    /// - Parameter payload: The bytes to sign.
+    /// - Returns: The 64-byte signature.
+    public func sign(_ payload: Data) -> Data {
+        let signature = payload.withUnsafeBytes { buffer in
+            let payloadBytes = IrohBytes(
+                data: buffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                len: UInt(buffer.count)
+            )
+            return iroh_author_sign(ffiSecret, payloadBytes)
+        }
+        return withUnsafeBytes(of: signature.bytes) { Data($0) }
+    }
+
+    /// Check a signature made with `sign(_:)`.
+    ///
+    /// - Parameters:
+    ///   - signature: The 64-byte signature.
+    ///   - payload: The signed bytes.
+    ///   - publicKey: The signing author's 32-byte public key.
+    /// - Returns: True if `publicKey`'s author signed `payload`.
+    public static func verify(_ signature: Data, of payload: Data, publicKey: Data) -> Bool {
+        guard signature.count == 64, publicKey.count == 32 else { return false }
+
+        var ffiSignature = IrohAuthorSignature()
+        signature.withUnsafeBytes { buffer in
+            withUnsafeMutableBytes(of: &ffiSignature.bytes) { destBuffer in
+                destBuffer.copyMemory(from: buffer)
+            }
+        }
+        var ffiId = IrohAuthorId()
+        publicKey.withUnsafeBytes { buffer in
+            withUnsafeMutableBytes(of: &ffiId.bytes) { destBuffer in
+                destBuffer.copyMemory(from: buffer)
+            }
+        }
+
+        return payload.withUnsafeBytes { buffer in
+            let payloadBytes = IrohBytes(
+                data: buffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                len: UInt(buffer.count)
+            )
+            return iroh_author_verify(ffiId, payloadBytes, ffiSignature)
+        }
+    }
+
     // MARK: - Export
 
     /// Export the secret key as a hex string.
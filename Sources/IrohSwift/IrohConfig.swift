@@ -1,5 +1,248 @@
 import Foundation
 
+/// Supplies random bytes for key generation.
+///
+/// Fill the whole buffer and return true, or return false to make key
+/// generation fail. Called synchronously while the key is created.
+///
+/// Example using the Security framework:
+/// ```swift
+/// let secRandom: EntropySource = { buffer in
+///     SecRandomCopyBytes(kSecRandomDefault, buffer.count, buffer.baseAddress!) == errSecSuccess
+/// }
+/// ```
+public typealias EntropySource = @Sendable (_ buffer: UnsafeMutableRawBufferPointer) -> Bool
+
+/// Alert when the store's size on disk crosses a threshold.
+///
+/// The handler is called on a background thread with `exceeded` true when
+/// the store reaches the threshold, and false when it drops below again.
+///
+/// Example prompting the user to clean up:
+/// ```swift
+/// let alert = StorageAlert(thresholdBytes: 2_000_000_000) { exceeded, sizeBytes in
+///     Task { @MainActor in
+///         storageBanner.isVisible = exceeded
+///     }
+/// }
+/// ```
+public struct StorageAlert: Sendable {
+    /// Size in bytes at which the store counts as full.
+    public var thresholdBytes: UInt64
+
+    /// Time between measurements of the store.
+    /// Default: 60 seconds
+    public var checkInterval: Duration
+
+    /// Told whether the threshold is exceeded and the measured size in bytes.
+    public var handler: @Sendable (_ exceeded: Bool, _ sizeBytes: UInt64) -> Void
+
+    public init(
+        thresholdBytes: UInt64,
+        checkInterval: Duration = .seconds(60),
+        handler: @escaping @Sendable (_ exceeded: Bool, _ sizeBytes: UInt64) -> Void
+    ) {
+        self.thresholdBytes = thresholdBytes
+        self.checkInterval = checkInterval
+        self.handler = handler
+    }
+}
+
+/// Report operations that run longer than a threshold.
+///
+/// Each slow put, get or document operation is logged once as a warning,
+/// with its operation id and the stage it is stuck in. Set `emitsEvents`
+/// to also receive them as `NodeEvent.slowOperation` from `events()`.
+public struct SlowOperationWatchdog: Sendable {
+    /// Running time after which an operation counts as slow.
+    public var threshold: Duration
+
+    /// Whether slow operations are also published as node events.
+    /// Default: false
+    public var emitsEvents: Bool
+
+    public init(threshold: Duration, emitsEvents: Bool = false) {
+        self.threshold = threshold
+        self.emitsEvents = emitsEvents
+    }
+}
+
+/// Keep selected content discoverable while the node runs.
+///
+/// Discovery records expire and tickets handed out earlier point at
+/// addresses the node may no longer have. The announcer republishes the
+/// node's discovery records every interval and whenever its addresses
+/// change, and calls the handler on a background thread with a fresh ticket
+/// for every tag starting with `tagPrefix`, for example to store next to
+/// your own discovery records. Each round is also published as
+/// `NodeEvent.contentAnnounced`.
+///
+/// Example keeping pinned albums findable:
+/// ```swift
+/// let announcer = ContentAnnouncer(interval: .seconds(15 * 60), tagPrefix: "albums/") { tag, ticket in
+///     Task { try? await backend.storeTicket(ticket, for: tag) }
+/// }
+/// ```
+public struct ContentAnnouncer: Sendable {
+    /// Time between rounds.
+    /// Default: 30 minutes
+    public var interval: Duration
+
+    /// Prefix of the names of the tags whose content is announced; empty
+    /// for every tag.
+    /// Default: ""
+    public var tagPrefix: String
+
+    /// Told each announced tag's name and a ticket for its content at the
+    /// node's current addresses. If nil, only discovery records are
+    /// republished.
+    public var handler: (@Sendable (_ tag: String, _ ticket: String) -> Void)?
+
+    public init(
+        interval: Duration = .seconds(30 * 60),
+        tagPrefix: String = "",
+        handler: (@Sendable (_ tag: String, _ ticket: String) -> Void)? = nil
+    ) {
+        self.interval = interval
+        self.tagPrefix = tagPrefix
+        self.handler = handler
+    }
+}
+
+/// Per-peer limits on blob requests served by this node.
+///
+/// Protects a node acting as a provider from peers that request too much.
+/// Requests over a limit are refused and reported as
+/// `NodeEvent.fetchLimited`; bandwidth is enforced by pacing instead.
+public struct RequestLimits: Sendable {
+    /// Requests a peer may start per second, with bursts of up to one
+    /// second's worth. If nil, unlimited.
+    public var requestsPerSecond: Int?
+
+    /// Transfers a peer may have in progress at once. If nil, unlimited.
+    public var maxConcurrentTransfers: Int?
+
+    /// Bytes per second sent to a peer across all its transfers.
+    /// If nil, unlimited.
+    public var bytesPerSecond: UInt64?
+
+    public init(
+        requestsPerSecond: Int? = nil,
+        maxConcurrentTransfers: Int? = nil,
+        bytesPerSecond: UInt64? = nil
+    ) {
+        self.requestsPerSecond = requestsPerSecond
+        self.maxConcurrentTransfers = maxConcurrentTransfers
+        self.bytesPerSecond = bytesPerSecond
+    }
+}
+
+/// Bandwidth caps on one kind of network.
+public struct BandwidthCaps: Sendable, Equatable {
+    /// Bytes per second sent to peers across all transfers.
+    /// If nil, unlimited.
+    public var uploadBytesPerSecond: UInt64?
+
+    /// Bytes per second received by downloads across all transfers, except
+    /// document content synced in the background. If nil, unlimited.
+    public var downloadBytesPerSecond: UInt64?
+
+    public init(
+        uploadBytesPerSecond: UInt64? = nil,
+        downloadBytesPerSecond: UInt64? = nil
+    ) {
+        self.uploadBytesPerSecond = uploadBytesPerSecond
+        self.downloadBytesPerSecond = downloadBytesPerSecond
+    }
+}
+
+/// Node-wide transfer bandwidth caps for Wi-Fi and cellular.
+///
+/// Caps apply to blobs served to peers and to downloads, so a sync over a
+/// metered connection does not use up the user's data plan. Document
+/// content downloaded in the background while syncing is only held back by
+/// the sending peer's upload cap, not by the download cap. The node
+/// starts on Wi-Fi; report network changes with
+/// `IrohNode.setNetworkKind(_:)`, for example from an `NWPathMonitor`.
+public struct BandwidthLimits: Sendable, Equatable {
+    /// Caps while on Wi-Fi or another unmetered network.
+    public var wifi: BandwidthCaps
+
+    /// Caps while on cellular.
+    public var cellular: BandwidthCaps
+
+    public init(wifi: BandwidthCaps = BandwidthCaps(), cellular: BandwidthCaps = BandwidthCaps()) {
+        self.wifi = wifi
+        self.cellular = cellular
+    }
+}
+
+/// Size limits for keeping blobs inside the store's database.
+///
+/// Blobs up to `maxInlineDataSize` are stored in the database instead of
+/// files of their own. Raise it when storing millions of tiny document
+/// values to create fewer files; lower it for large media, which is then
+/// streamed from files. Only content stored afterwards is affected.
+public struct StoreInlining: Sendable {
+    /// Largest blob kept in the database, in bytes. If nil, 16 KiB.
+    public var maxInlineDataSize: UInt64?
+
+    /// Largest outboard (verification data, 64 bytes per 16 KiB of blob)
+    /// kept in the database, in bytes. If nil, 16 KiB.
+    public var maxInlineOutboardSize: UInt64?
+
+    public init(
+        maxInlineDataSize: UInt64? = nil,
+        maxInlineOutboardSize: UInt64? = nil
+    ) {
+        self.maxInlineDataSize = maxInlineDataSize
+        self.maxInlineOutboardSize = maxInlineOutboardSize
+    }
+}
+
+/// What a node does when its blob store database is found corrupt at
+/// startup, for example after a crash during a write. What recovery sets
+/// aside is moved to `quarantine/` in the storage directory.
+public enum BlobStoreRecovery: Sendable, Equatable {
+    /// Fail node creation with `IrohError.storeCorrupted`.
+    case fail
+    /// Rebuild the database from the blob files. Complete files matching
+    /// their hash are added back under automatic tags; tag names and blobs
+    /// small enough to be kept inside the database are lost.
+    case reindex
+    /// Set the database and blob files aside and start with an empty store.
+    case quarantine
+
+    var ffiRecovery: IrohBlobStoreRecovery {
+        switch self {
+        case .fail:
+            return BlobStoreRecoveryFail
+        case .reindex:
+            return BlobStoreRecoveryReindex
+        case .quarantine:
+            return BlobStoreRecoveryQuarantine
+        }
+    }
+}
+
+/// What a node does when its docs database is found corrupt at startup.
+public enum DocsStoreRecovery: Sendable, Equatable {
+    /// Fail node creation with `IrohError.storeCorrupted`.
+    case fail
+    /// Move the database to `quarantine/` in the storage directory and
+    /// start without documents or authors; documents must be joined again.
+    case discard
+
+    var ffiRecovery: IrohDocsStoreRecovery {
+        switch self {
+        case .fail:
+            return DocsStoreRecoveryFail
+        case .discard:
+            return DocsStoreRecoveryDiscard
+        }
+    }
+}
+
 /// Configuration for creating an IrohNode.
 public struct IrohConfig: Sendable {
     /// Path to the blob store directory.
@@ -18,6 +261,177 @@ public struct IrohConfig: Sendable {
     /// Default: false
     public var docsEnabled: Bool
 
+    /// Directory for temporary files exported by the node.
+    /// If nil, uses the system temporary directory.
+    public var tempDirectory: URL?
+
+    /// How often the node removes expired document entries in the background.
+    /// If nil, expired entries are only removed by `pruneExpiredEntries()`.
+    /// Default: 60 seconds
+    public var expiryPruneInterval: Duration?
+
+    /// Source of entropy for the node's secret key.
+    /// If nil, the system RNG is used.
+    /// Default: nil
+    public var entropySource: EntropySource?
+
+    /// Number of TLS session tickets to cache for resuming connections.
+    /// Reconnecting to a peer with a cached ticket resumes the earlier TLS
+    /// session instead of authenticating from scratch, which keeps
+    /// reconnects after brief network changes cheaper. Raise this when
+    /// talking to many peers.
+    /// If nil, uses iroh's default of 256.
+    /// Default: nil
+    public var tlsTicketCacheSize: Int?
+
+    /// How long a connection may stay silent before it is dropped.
+    /// A longer timeout lets connections survive short network blips
+    /// without reconnecting, at the cost of noticing dead peers later.
+    /// If nil, uses the QUIC default of 30 seconds.
+    /// Default: nil
+    public var idleTimeout: Duration?
+
+    /// Maximum number of connections served to peers at once.
+    /// When the limit is reached, the connection that has been idle the
+    /// longest is closed to admit a new one. This bounds memory on small
+    /// devices when many peers fetch a popular blob. Connections this node
+    /// opens for its own downloads are not counted.
+    /// If nil, connections are unlimited.
+    /// Default: nil
+    public var maxConnections: Int?
+
+    /// App-provided discovery, asked for the addresses of nodes being
+    /// dialed and told whenever this node's addresses change.
+    /// If nil, only the default discovery is used.
+    /// Default: nil
+    public var discovery: (any DiscoveryProvider)?
+
+    /// Whether to use n0's DNS/pkarr discovery.
+    /// Set to false to rely on `discovery` alone.
+    /// Default: true
+    public var defaultDiscoveryEnabled: Bool
+
+    /// Alert when the store's size on disk crosses a threshold, so users
+    /// can be asked to clean up before the device is full.
+    /// If nil, the store is not measured.
+    /// Default: nil
+    public var storageAlert: StorageAlert?
+
+    /// OTLP/HTTP collector to export operation spans and metrics to,
+    /// like "https://otel.example.com:4318". Export is shared by all nodes
+    /// in the process and needs the library built with the `otel` feature.
+    /// If nil, nothing is exported.
+    /// Default: nil
+    public var telemetryEndpoint: URL?
+
+    /// Report operations that run longer than a threshold, to diagnose
+    /// operations that never seem to finish.
+    /// If nil, operations are not watched.
+    /// Default: nil
+    public var slowOperationWatchdog: SlowOperationWatchdog?
+
+    /// Serve a debug control server on a Unix socket path, like
+    /// "/tmp/iroh.sock", or a loopback address, like "127.0.0.1:7777".
+    /// Node info, blobs, documents and transfers can then be queried with
+    /// line-delimited JSON-RPC from the command line. Needs the library
+    /// built with the `control` feature; meant for development only.
+    /// If nil, no server is started.
+    /// Default: nil
+    public var controlSocket: String?
+
+    /// HTTPS gateway serving content by hash, like
+    /// "https://gateway.example.com/blobs". When a blob cannot be fetched
+    /// from peers, for example on networks that block QUIC and the relays,
+    /// `get(ticket:)` requests `<gatewayUrl>/<hash>` instead. The response
+    /// is checked against the ticket's hash, so the gateway need not be
+    /// trusted. Collections are not fetched from the gateway.
+    /// If nil, there is no fallback.
+    /// Default: nil
+    public var gatewayUrl: URL?
+
+    /// Who may fetch blobs from this node before any peer rules are set.
+    /// Use `.allowList` for private shares, then allow peers with
+    /// `IrohNode.setBlobAccess(_:for:)`.
+    /// Default: .open
+    public var blobAccess: BlobAccessMode
+
+    /// Per-peer limits on blob requests served by this node.
+    /// If nil, peers are not limited.
+    /// Default: nil
+    public var requestLimits: RequestLimits?
+
+    /// Transfer bandwidth caps for Wi-Fi and cellular.
+    /// If nil, transfers are not capped.
+    /// Default: nil
+    public var bandwidthLimits: BandwidthLimits?
+
+    /// Whether to run background callbacks on one dedicated thread.
+    /// Subscription events, conflict resolution, write filters, discovery,
+    /// storage alerts, slow operation reports and content announcements
+    /// then run one at a time, in
+    /// the order they were made, instead of concurrently on the node's
+    /// worker threads. Callbacks should return quickly and must not wait for
+    /// other node operations.
+    /// Default: false
+    public var dedicatedCallbackThread: Bool
+
+    /// Identifies the app running the node, like "com.example.photos/2.1".
+    /// Attached to exported telemetry, reported by `IrohNode.info()` and the
+    /// control server, and published with the node's discovery records so
+    /// peers sharing a relay can tell apps apart. At most 245 bytes.
+    /// If nil, the node is anonymous.
+    /// Default: nil
+    public var appId: String?
+
+    /// Which blobs the store keeps in its database instead of files.
+    /// If nil, blobs and outboards up to 16 KiB are kept in the database.
+    /// Default: nil
+    public var storeInlining: StoreInlining?
+
+    /// Periodically republish discovery records and announce selected
+    /// content, so long-lived shares stay reachable after address changes.
+    /// If nil, records are only published when the addresses change.
+    /// Default: nil
+    public var contentAnnouncer: ContentAnnouncer?
+
+    /// Seed of a deterministic test node, usually set through `testing(seed:)`.
+    /// The node ID derives from the seed; the node binds to loopback only,
+    /// uses neither relays nor network discovery, and is found by the other
+    /// test nodes in the process. Its tickets leave out addresses, so they
+    /// only depend on the seed and the content. Blobs and documents are kept
+    /// in memory and are gone once the node closes.
+    /// If nil, the node is a normal node.
+    /// Default: nil
+    public var testSeed: String?
+
+    /// Worker threads of the node's runtime.
+    /// A worker per core wastes memory and wakeups on a phone running a
+    /// background sync, so iOS defaults to 2.
+    /// If nil, 2 on iOS and one per core on macOS.
+    /// Default: nil
+    public var workerThreads: Int?
+
+    /// Blocking threads the node's runtime may start for file I/O.
+    /// If nil, 16 on iOS and 512 on macOS.
+    /// Default: nil
+    public var maxBlockingThreads: Int?
+
+    /// What to do when the blob store database is found corrupt at startup.
+    /// Default: .fail
+    public var blobStoreRecovery: BlobStoreRecovery
+
+    /// What to do when the docs database is found corrupt at startup.
+    /// Default: .fail
+    public var docsStoreRecovery: DocsStoreRecovery
+
+    /// Keep all of the node's data in a fresh temporary directory that is
+    /// removed when the node is closed or deinitialized, for share
+    /// extensions and previews that must not leave data behind.
+    /// `storagePath` is not used; `IrohNode.storageURL` reports the
+    /// directory in use.
+    /// Default: false
+    public var ephemeral: Bool
+
     /// Create a new IrohConfig with the specified options.
     ///
     /// - Parameters:
@@ -26,47 +440,172 @@ public struct IrohConfig: Sendable {
     ///   - relayEnabled: Whether to use relay servers. Default: true.
     ///   - customRelayUrl: Custom relay server URL. If nil, uses n0's public relays.
     ///   - docsEnabled: Whether to enable the Docs engine. Default: false.
+    ///   - tempDirectory: Directory for exported temporary files. If nil, uses
+    ///                    the system temporary directory.
+    ///   - expiryPruneInterval: How often to remove expired document entries.
+    ///                          If nil, only on demand. Default: 60 seconds.
+    ///   - entropySource: Source of entropy for the node's secret key.
+    ///                    If nil, uses the system RNG.
+    ///   - tlsTicketCacheSize: TLS session tickets cached for resuming sessions.
+    ///                         If nil, uses iroh's default of 256.
+    ///   - idleTimeout: How long a silent connection survives. If nil, uses
+    ///                  the QUIC default of 30 seconds.
+    ///   - maxConnections: Maximum connections served to peers at once.
+    ///                     If nil, unlimited.
+    ///   - discovery: App-provided discovery. If nil, only the default is used.
+    ///   - defaultDiscoveryEnabled: Whether to use n0's DNS/pkarr discovery.
+    ///                              Default: true.
+    ///   - storageAlert: Alert when the store crosses a size. If nil, no alerts.
+    ///   - telemetryEndpoint: OTLP/HTTP collector to export to. If nil, none.
+    ///   - slowOperationWatchdog: Report slow operations. If nil, none.
+    ///   - controlSocket: Debug control server address. If nil, none.
+    ///   - gatewayUrl: HTTPS gateway to fall back to for fetches. If nil, none.
+    ///   - blobAccess: Who may fetch blobs before peer rules are set. Default: .open.
+    ///   - requestLimits: Per-peer limits on blob requests. If nil, unlimited.
+    ///   - bandwidthLimits: Transfer bandwidth caps for Wi-Fi and cellular.
+    ///                      If nil, uncapped.
+    ///   - dedicatedCallbackThread: Run background callbacks on one thread.
+    ///                              Default: false.
+    ///   - appId: Identifier of the app running the node. Default: nil.
+    ///   - storeInlining: Which blobs are kept in the store's database. Default: nil.
+    ///   - contentAnnouncer: Periodic re-announcement of content. If nil, none.
+    ///   - testSeed: Seed of a deterministic test node. If nil, a normal node.
+    ///   - workerThreads: Worker threads of the node's runtime. If nil, 2 on
+    ///                    iOS and one per core on macOS.
+    ///   - maxBlockingThreads: Blocking threads for file I/O. If nil, 16 on
+    ///                         iOS and 512 on macOS.
+    ///   - blobStoreRecovery: Recovery from a corrupt blob store. Default: .fail.
+    ///   - docsStoreRecovery: Recovery from a corrupt docs database. Default: .fail.
+    ///   - ephemeral: Keep data in a temporary directory removed on close.
+    ///                Default: false.
     public init(
         storagePath: URL? = nil,
         relayEnabled: Bool = true,
         customRelayUrl: String? = nil,
-        docsEnabled: Bool = false
+        docsEnabled: Bool = false,
+        tempDirectory: URL? = nil,
+        expiryPruneInterval: Duration? = .seconds(60),
+        entropySource: EntropySource? = nil,
+        tlsTicketCacheSize: Int? = nil,
+        idleTimeout: Duration? = nil,
+        maxConnections: Int? = nil,
+        discovery: (any DiscoveryProvider)? = nil,
+        defaultDiscoveryEnabled: Bool = true,
+        storageAlert: StorageAlert? = nil,
+        telemetryEndpoint: URL? = nil,
+        slowOperationWatchdog: SlowOperationWatchdog? = nil,
+        controlSocket: String? = nil,
+        gatewayUrl: URL? = nil,
+        blobAccess: BlobAccessMode = .open,
+        requestLimits: RequestLimits? = nil,
+        bandwidthLimits: BandwidthLimits? = nil,
+        dedicatedCallbackThread: Bool = false,
+        appId: String? = nil,
+        storeInlining: StoreInlining? = nil,
+        contentAnnouncer: ContentAnnouncer? = nil,
+        testSeed: String? = nil,
+        workerThreads: Int? = nil,
+        maxBlockingThreads: Int? = nil,
+        blobStoreRecovery: BlobStoreRecovery = .fail,
+        docsStoreRecovery: DocsStoreRecovery = .fail,
+        ephemeral: Bool = false
     ) {
-        self.storagePath = storagePath ?? Self.defaultStoragePath()
+        // Creating the default directory would leave it behind
+        self.storagePath = storagePath
+            ?? (ephemeral ? FileManager.default.temporaryDirectory : Self.defaultStoragePath())
         self.relayEnabled = relayEnabled
         self.customRelayUrl = customRelayUrl
         self.docsEnabled = docsEnabled
+        self.tempDirectory = tempDirectory
+        self.expiryPruneInterval = expiryPruneInterval
+        self.entropySource = entropySource
+        self.tlsTicketCacheSize = tlsTicketCacheSize
+        self.idleTimeout = idleTimeout
+        self.maxConnections = maxConnections
+        self.discovery = discovery
+        self.defaultDiscoveryEnabled = defaultDiscoveryEnabled
+        self.storageAlert = storageAlert
+        self.telemetryEndpoint = telemetryEndpoint
+        self.slowOperationWatchdog = slowOperationWatchdog
+        self.controlSocket = controlSocket
+        self.gatewayUrl = gatewayUrl
+        self.blobAccess = blobAccess
+        self.requestLimits = requestLimits
+        self.bandwidthLimits = bandwidthLimits
+        self.dedicatedCallbackThread = dedicatedCallbackThread
+        self.appId = appId
+        self.storeInlining = storeInlining
+        self.contentAnnouncer = contentAnnouncer
+        self.testSeed = testSeed
+        self.workerThreads = workerThreads
+        self.maxBlockingThreads = maxBlockingThreads
+        self.blobStoreRecovery = blobStoreRecovery
+        self.docsStoreRecovery = docsStoreRecovery
+        self.ephemeral = ephemeral
+    }
+
+    /// Configuration for a deterministic test node.
+    ///
+    /// The node gets the same node ID and tickets on every run. Its blobs
+    /// and documents are kept in memory, and its remaining state in a fresh
+    /// temporary directory that is removed when the node closes (see
+    /// `ephemeral`). Test nodes in one
+    /// process reach each other over loopback without relays or discovery,
+    /// so two nodes in one XCTest case work offline.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let provider = try await IrohNode(config: .testing(seed: "provider"))
+    /// let fetcher = try await IrohNode(config: .testing(seed: "fetcher"))
+    /// let ticket = try await provider.put(Data("hello".utf8))
+    /// let data = try await fetcher.get(ticket: ticket)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - seed: Any string, such as the test's name. Nodes alive at the
+    ///           same time need different seeds.
+    ///   - docsEnabled: Whether to enable document sync. Default: false.
+    public static func testing(seed: String, docsEnabled: Bool = false) -> IrohConfig {
+        IrohConfig(
+            relayEnabled: false,
+            docsEnabled: docsEnabled,
+            defaultDiscoveryEnabled: false,
+            testSeed: seed,
+            ephemeral: true
+        )
     }
 
     /// Validate the configuration before node creation.
     ///
     /// - Throws: `IrohError.invalidConfiguration` if validation fails.
     public func validate() throws {
-        // Check storage path is writable
-        let fm = FileManager.default
+        // Ephemeral nodes never touch the storage path
+        if !ephemeral {
+            let fm = FileManager.default
+
+            // Create directory if it doesn't exist
+            if !fm.fileExists(atPath: storagePath.path) {
+                do {
+                    try fm.createDirectory(at: storagePath, withIntermediateDirectories: true)
+                } catch {
+                    throw IrohError.invalidConfiguration(
+                        "Cannot create storage directory: \(storagePath.path) - \(error.localizedDescription)"
+                    )
+                }
+            }
 
-        // Create directory if it doesn't exist
-        if !fm.fileExists(atPath: storagePath.path) {
+            // Test writability
+            let testFile = storagePath.appendingPathComponent(".iroh-write-test")
             do {
-                try fm.createDirectory(at: storagePath, withIntermediateDirectories: true)
+                try Data().write(to: testFile)
+                try fm.removeItem(at: testFile)
             } catch {
                 throw IrohError.invalidConfiguration(
-                    "Cannot create storage directory: \(storagePath.path) - \(error.localizedDescription)"
+                    "Storage path not writable: \(storagePath.path)"
                 )
             }
         }
 
-        // Test writability
-        let testFile = storagePath.appendingPathComponent(".iroh-write-test")
-        do {
-            try Data().write(to: testFile)
-            try fm.removeItem(at: testFile)
-        } catch {
-            throw IrohError.invalidConfiguration(
-                "Storage path not writable: \(storagePath.path)"
-            )
-        }
-
         // Validate custom relay URL format if provided
         if let relayUrl = customRelayUrl {
             guard let url = URL(string: relayUrl),
@@ -77,6 +616,151 @@ public struct IrohConfig: Sendable {
                 )
             }
         }
+
+        if let size = tlsTicketCacheSize, size <= 0 || size > Int(UInt32.max) {
+            throw IrohError.invalidConfiguration(
+                "TLS ticket cache size must be between 1 and \(UInt32.max)"
+            )
+        }
+
+        if let timeout = idleTimeout, timeout <= .zero {
+            throw IrohError.invalidConfiguration(
+                "Idle timeout must be positive"
+            )
+        }
+
+        if let limit = maxConnections, limit <= 0 || limit > Int(UInt32.max) {
+            throw IrohError.invalidConfiguration(
+                "Maximum connections must be between 1 and \(UInt32.max)"
+            )
+        }
+
+        if let threads = workerThreads, threads <= 0 || threads > Int(UInt32.max) {
+            throw IrohError.invalidConfiguration(
+                "Worker threads must be between 1 and \(UInt32.max)"
+            )
+        }
+
+        if let threads = maxBlockingThreads, threads <= 0 || threads > Int(UInt32.max) {
+            throw IrohError.invalidConfiguration(
+                "Maximum blocking threads must be between 1 and \(UInt32.max)"
+            )
+        }
+
+        if let alert = storageAlert {
+            guard alert.thresholdBytes > 0 else {
+                throw IrohError.invalidConfiguration(
+                    "Storage alert threshold must be positive"
+                )
+            }
+            guard alert.checkInterval > .zero else {
+                throw IrohError.invalidConfiguration(
+                    "Storage alert check interval must be positive"
+                )
+            }
+        }
+
+        if let inlining = storeInlining {
+            if inlining.maxInlineDataSize == 0 || inlining.maxInlineOutboardSize == 0 {
+                throw IrohError.invalidConfiguration(
+                    "Store inlining limits must be at least 1 byte"
+                )
+            }
+        }
+
+        if let appId = appId {
+            guard !appId.isEmpty, appId.utf8.count <= 245 else {
+                throw IrohError.invalidConfiguration(
+                    "App identifier must be between 1 and 245 bytes"
+                )
+            }
+            guard !appId.unicodeScalars.contains(where: { $0.properties.generalCategory == .control }) else {
+                throw IrohError.invalidConfiguration(
+                    "App identifier cannot contain control characters"
+                )
+            }
+        }
+
+        if let endpoint = telemetryEndpoint {
+            guard let scheme = endpoint.scheme?.lowercased(),
+                  scheme == "https" || scheme == "http" else {
+                throw IrohError.invalidConfiguration(
+                    "Invalid telemetry endpoint: must be a valid HTTP/HTTPS URL"
+                )
+            }
+        }
+
+        if let watchdog = slowOperationWatchdog, watchdog.threshold.milliseconds == 0 {
+            throw IrohError.invalidConfiguration(
+                "Slow operation threshold must be at least 1 millisecond"
+            )
+        }
+
+        if let announcer = contentAnnouncer, announcer.interval.milliseconds == 0 {
+            throw IrohError.invalidConfiguration(
+                "Content announcement interval must be at least 1 millisecond"
+            )
+        }
+
+        if let seed = testSeed {
+            guard !seed.isEmpty else {
+                throw IrohError.invalidConfiguration(
+                    "Test seed cannot be empty"
+                )
+            }
+            guard entropySource == nil else {
+                throw IrohError.invalidConfiguration(
+                    "A test node derives its key from the seed, not an entropy source"
+                )
+            }
+        }
+
+        if let socket = controlSocket, socket.isEmpty {
+            throw IrohError.invalidConfiguration(
+                "Control socket must be a socket path or a loopback address"
+            )
+        }
+
+        // Plain HTTP is only accepted for local testing
+        if let gateway = gatewayUrl {
+            let scheme = gateway.scheme?.lowercased()
+            let loopback = ["localhost", "127.0.0.1", "::1"].contains(gateway.host ?? "")
+            guard scheme == "https" || (scheme == "http" && loopback) else {
+                throw IrohError.invalidConfiguration(
+                    "Invalid gateway URL: must be an HTTPS URL"
+                )
+            }
+        }
+
+        if let limits = requestLimits {
+            if let rate = limits.requestsPerSecond, rate <= 0 || rate > Int(UInt32.max) {
+                throw IrohError.invalidConfiguration(
+                    "Request rate limit must be between 1 and \(UInt32.max)"
+                )
+            }
+            if let limit = limits.maxConcurrentTransfers, limit <= 0 || limit > Int(UInt32.max) {
+                throw IrohError.invalidConfiguration(
+                    "Concurrent transfer limit must be between 1 and \(UInt32.max)"
+                )
+            }
+            if limits.bytesPerSecond == 0 {
+                throw IrohError.invalidConfiguration(
+                    "Bandwidth limit must be positive"
+                )
+            }
+        }
+
+        if let limits = bandwidthLimits {
+            let caps = [
+                limits.wifi.uploadBytesPerSecond, limits.wifi.downloadBytesPerSecond,
+                limits.cellular.uploadBytesPerSecond, limits.cellular.downloadBytesPerSecond,
+            ]
+            if caps.contains(0) {
+                throw IrohError.invalidConfiguration(
+                    "Bandwidth caps must be positive"
+                )
+            }
+        }
     }
 
     /// Default storage path in Application Support, excluded from iCloud backup.
@@ -0,0 +1,140 @@
+import Foundation
+import IrohSwiftFFI
+
+/// Addresses at which a node can be reached.
+public struct NodeAddress: Sendable, Equatable {
+    /// The node's unique identifier.
+    public let nodeId: String
+    /// The node's home relay URL, if it has one.
+    public let relayUrl: String?
+    /// Direct addresses as "ip:port" strings.
+    public let directAddresses: [String]
+
+    public init(nodeId: String, relayUrl: String? = nil, directAddresses: [String] = []) {
+        self.nodeId = nodeId
+        self.relayUrl = relayUrl
+        self.directAddresses = directAddresses
+    }
+}
+
+/// App-provided node discovery.
+///
+/// Back discovery with your own service, for example a backend that stores
+/// the addresses of your users' nodes. Set it on `IrohConfig.discovery`,
+/// next to the default DNS/pkarr discovery or instead of it when
+/// `IrohConfig.defaultDiscoveryEnabled` is false.
+///
+/// Example usage:
+/// ```swift
+/// struct BackendDiscovery: DiscoveryProvider {
+///     let api: BackendAPI
+///
+///     func resolve(nodeId: String) async throws -> NodeAddress? {
+///         try await api.lookupNode(nodeId)
+///     }
+///
+///     func publish(_ address: NodeAddress) async {
+///         try? await api.storeNode(address)
+///     }
+/// }
+///
+/// let config = IrohConfig(discovery: BackendDiscovery(api: api))
+/// ```
+public protocol DiscoveryProvider: Sendable {
+    /// Look up the addresses of a node being dialed.
+    ///
+    /// Return nil if the node is unknown. Errors are treated as unknown.
+    func resolve(nodeId: String) async throws -> NodeAddress?
+
+    /// Publish this node's addresses.
+    ///
+    /// Called whenever the addresses change.
+    func publish(_ address: NodeAddress) async
+}
+
+// MARK: - FFI Bridge
+
+/// Build the FFI provider for `provider`, or an empty one if nil.
+///
+/// The provider is retained until Rust releases it.
+func makeDiscoveryProvider(_ provider: (any DiscoveryProvider)?) -> IrohDiscoveryProvider {
+    guard let provider else {
+        return IrohDiscoveryProvider(userdata: nil, resolve: nil, publish: nil, release: nil)
+    }
+
+    return IrohDiscoveryProvider(
+        userdata: Unmanaged.passRetained(DiscoveryProviderBox(provider)).toOpaque(),
+        resolve: { userdata, nodeIdPtr, request in
+            // takeUnretainedValue - called once per lookup
+            let box = Unmanaged<DiscoveryProviderBox>
+                .fromOpaque(userdata!)
+                .takeUnretainedValue()
+            let nodeId = String(cString: nodeIdPtr!)
+            let pending = DiscoveryRequest(pointer: request)
+            Task {
+                let address = try? await box.provider.resolve(nodeId: nodeId)
+                pending.complete(with: address)
+            }
+        },
+        publish: { userdata, addr in
+            let box = Unmanaged<DiscoveryProviderBox>
+                .fromOpaque(userdata!)
+                .takeUnretainedValue()
+            let buffer = UnsafeBufferPointer(start: addr.direct_addrs, count: Int(addr.direct_addrs_len))
+            let address = NodeAddress(
+                nodeId: String(cString: addr.node_id!),
+                relayUrl: addr.relay_url.map { String(cString: $0) },
+                directAddresses: buffer.map { String(cString: $0!) }
+            )
+            Task {
+                await box.provider.publish(address)
+            }
+        },
+        release: { userdata in
+            // release - consume on terminal
+            Unmanaged<DiscoveryProviderBox>
+                .fromOpaque(userdata!)
+                .release()
+        }
+    )
+}
+
+/// Box for passing a discovery provider through FFI callbacks.
+private final class DiscoveryProviderBox: Sendable {
+    let provider: any DiscoveryProvider
+
+    init(_ provider: any DiscoveryProvider) {
+        self.provider = provider
+    }
+}
+
+/// Sendable wrapper for a pending lookup, answered exactly once.
+private struct DiscoveryRequest: @unchecked Sendable {
+    let pointer: UnsafeMutablePointer<IrohDiscoveryRequest>?
+
+    func complete(with address: NodeAddress?) {
+        guard let address else {
+            iroh_discovery_request_complete(pointer, nil, nil, 0)
+            return
+        }
+
+        var cStrings = address.directAddresses.map { strdup($0) }
+        defer { cStrings.forEach { free($0) } }
+
+        withOptionalCString(address.relayUrl) { relayUrlPtr in
+            cStrings.withUnsafeMutableBufferPointer { buffer in
+                buffer.withMemoryRebound(to: UnsafePointer<CChar>?.self) { addrs in
+                    let ok = iroh_discovery_request_complete(
+                        pointer,
+                        relayUrlPtr,
+                        addrs.baseAddress,
+                        UInt(addrs.count)
+                    )
+                    if !ok {
+                        IrohLogger.node.error("discovery returned an invalid address for \(address.nodeId, privacy: .public)")
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+import Foundation
+import IrohSwiftFFI
+
+/// Decides the value of a key whose authors hold different values.
+///
+/// Receives the key and every author's latest value for it. Return the
+/// value to write, or nil to leave the entries as they are.
+public typealias ConflictResolver = @Sendable (_ key: Data, _ candidates: [ConflictCandidate]) -> Data?
+
+extension IrohDoc {
+    /// Resolve keys that diverge after remote writes.
+    ///
+    /// Whenever a peer's write leaves several authors with different latest
+    /// values for a key, `resolve` is called on a background thread with all
+    /// of them. The returned value is written by `author` in Rust, under the
+    /// same lock as `compareAndSet`, and only if no other write reached the
+    /// key while the resolver ran. Local writes never invoke the resolver.
+    ///
+    /// The resolver should be deterministic and idempotent, so peers running
+    /// the same resolver converge instead of rewriting each other's merges.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let resolver = try await doc.setConflictResolver(author: author) { _, candidates in
+    ///     // Keep the union of comma-separated tags
+    ///     let tags = candidates.flatMap { String(decoding: $0.value, as: UTF8.self).split(separator: ",") }
+    ///     return Data(Set(tags).sorted().joined(separator: ",").utf8)
+    /// }
+    /// // Later:
+    /// resolver.cancel()
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - author: The author that writes resolved values.
+    ///   - resolve: Picks or merges the value for a conflicting key.
+    /// - Returns: A handle that stops the resolver when cancelled or deallocated.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docReadOnly` if the handle is read-only.
+    public func setConflictResolver(
+        author: IrohAuthor,
+        _ resolve: @escaping ConflictResolver
+    ) throws -> ConflictResolverHandle {
+        try ensureNotClosed()
+        try ensureWritable()
+
+        let context = ConflictResolverContext(resolve: resolve)
+        let contextPtr = Unmanaged.passRetained(context).toOpaque()
+
+        let resolver = IrohDocConflictResolver(
+            userdata: contextPtr,
+            resolve: { userdata, key, candidates, candidatesLen, resolution in
+                // takeUnretainedValue - called once per conflict
+                let ctx = Unmanaged<ConflictResolverContext>
+                    .fromOpaque(userdata!)
+                    .takeUnretainedValue()
+
+                let keyData = Data(bytes: key.data, count: Int(key.len))
+                let buffer = UnsafeBufferPointer(start: candidates, count: Int(candidatesLen))
+                let values = buffer.map { ConflictCandidate(from: $0) }
+
+                guard let value = ctx.resolve(keyData, values) else { return }
+                value.withUnsafeBytes { valueBuffer in
+                    let valueBytes = IrohBytes(
+                        data: valueBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(valueBuffer.count)
+                    )
+                    iroh_conflict_resolution_set(resolution, valueBytes)
+                }
+            },
+            on_complete: { userdata in
+                // release - consume on terminal
+                Unmanaged<ConflictResolverContext>
+                    .fromOpaque(userdata!)
+                    .release()
+            },
+            on_failure: { userdata, errorPtr in
+                // release - consume on terminal
+                Unmanaged<ConflictResolverContext>
+                    .fromOpaque(userdata!)
+                    .release()
+                let message = String(cString: errorPtr!)
+                iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                IrohLogger.node.error("conflict resolver stopped: \(message, privacy: .public)")
+            }
+        )
+
+        let subHandle = iroh_doc_set_conflict_resolver(handle.pointer, author.ffiSecret, resolver)
+        return ConflictResolverHandle(pointer: subHandle)
+    }
+}
+
+// MARK: - Resolver Handle
+
+/// A registered conflict resolver.
+///
+/// The resolver stops when `cancel()` is called or the handle is
+/// deallocated, whichever comes first.
+public final class ConflictResolverHandle: @unchecked Sendable {
+    private let lock = NSLock()
+    private var pointer: UnsafeMutablePointer<IrohSubscriptionHandle>?
+
+    init(pointer: UnsafeMutablePointer<IrohSubscriptionHandle>?) {
+        self.pointer = pointer
+    }
+
+    deinit {
+        cancel()
+    }
+
+    /// Stop resolving conflicts.
+    ///
+    /// Safe to call more than once.
+    public func cancel() {
+        lock.lock()
+        let toCancel = pointer
+        pointer = nil
+        lock.unlock()
+
+        if let toCancel {
+            iroh_subscription_cancel(toCancel)
+        }
+    }
+}
+
+// MARK: - Resolver Context
+
+/// Internal context holding the resolver closure for the FFI callbacks.
+private final class ConflictResolverContext: @unchecked Sendable {
+    let resolve: ConflictResolver
+
+    init(resolve: @escaping ConflictResolver) {
+        self.resolve = resolve
+    }
+}
@@ -0,0 +1,197 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohDoc {
+    // MARK: - Durable Peers
+
+    /// Designate the peers that back up this document, such as a server.
+    ///
+    /// A local write counts as backed up once a sync with every durable
+    /// peer, started after the write, succeeded. The node syncs with the
+    /// durable peers shortly after local writes, retrying with backoff while
+    /// they are unreachable, and reports each confirmation as a
+    /// `.durableSync` node event. Designations are stored on the node and
+    /// resume after a restart. Pass an empty list to stop tracking.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// try await doc.setDurablePeers([serverNodeId])
+    /// try await doc.set(author: author, key: "note", value: data)
+    /// for try await event in try node.events() {
+    ///     if case .durableSync(_, _, _, backedUp: true) = event { break }
+    /// }
+    /// ```
+    ///
+    /// - Parameter nodeIds: Node IDs of the durable peers.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.durablePeersFailed` if a node ID is invalid or
+    ///           the designation cannot be stored.
+    public func setDurablePeers(_ nodeIds: [String]) async throws {
+        try ensureNotClosed()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                DurableSetContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCloseCallback(
+                userdata: box,
+                on_complete: { userdata in
+                    let box = Unmanaged<DurableSetContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume()
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<DurableSetContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.durablePeersFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            // Duplicate the strings so the pointers stay valid for the whole call
+            let idPtrs = nodeIds.map { UnsafePointer<CChar>(strdup($0)) }
+            defer { idPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) } }
+            idPtrs.withUnsafeBufferPointer { buffer in
+                iroh_doc_set_durable_peers(handle.pointer, buffer.baseAddress, UInt(buffer.count), callback)
+            }
+        }
+    }
+
+    /// How far this document's local writes are backed up on its durable peers.
+    ///
+    /// - Returns: Each durable peer's confirmation and the overall status.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.durablePeersFailed` if the document cannot be read.
+    public func durableStatus() async throws -> DurableStatus {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                DurableStatusContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDurableStatusCallback(
+                userdata: box,
+                on_peer: { userdata, peer in
+                    let box = Unmanaged<DurableStatusContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more peers coming
+                    box.peers.append(
+                        DurablePeer(
+                            nodeId: String(cString: peer.node_id),
+                            confirmedThrough: peer.confirmed_through
+                        )
+                    )
+                },
+                on_complete: { userdata, status in
+                    let box = Unmanaged<DurableStatusContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(
+                        returning: DurableStatus(
+                            peers: box.peers,
+                            confirmedThrough: status.confirmed_through,
+                            lastLocalWrite: status.last_local_write,
+                            isBackedUp: status.backed_up
+                        )
+                    )
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<DurableStatusContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.durablePeersFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_doc_durable_status(handle.pointer, callback)
+        }
+    }
+
+    // MARK: - Durability
+
+    /// Set how this document's writes are stored before they return, for
+    /// as long as the node runs.
+    ///
+    /// Applies to sets, deletes, compare-and-set, counter and TTL writes
+    /// that do not choose a durability of their own. Documents start with
+    /// `.batched`, whose writes are committed within half a second; use
+    /// `.immediate` for documents whose every write must survive a crash.
+    ///
+    /// - Parameter durability: How writes are stored before returning.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docDurabilityFailed` if the node is closed.
+    public func setDurability(_ durability: DocDurability) async throws {
+        try ensureNotClosed()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                DurableSetContinuationBox(continuation)
+            ).toOpaque()
+            iroh_doc_set_durability(handle.pointer, durability.ffiDurability, durabilityCallback(box))
+        }
+    }
+
+    /// Commit every batched write and sync it to disk.
+    ///
+    /// Call after a bulk import to make it crash-safe at once. Writes to
+    /// the node's other documents are flushed too.
+    ///
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docDurabilityFailed` if the writes cannot be synced.
+    public func flush() async throws {
+        try ensureNotClosed()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                DurableSetContinuationBox(continuation)
+            ).toOpaque()
+            iroh_doc_flush(handle.pointer, durabilityCallback(box))
+        }
+    }
+}
+
+private func durabilityCallback(_ box: UnsafeMutableRawPointer) -> IrohCloseCallback {
+    IrohCloseCallback(
+        userdata: box,
+        on_complete: { userdata in
+            let box = Unmanaged<DurableSetContinuationBox>
+                .fromOpaque(userdata!)
+                .takeRetainedValue()
+            box.continuation.resume()
+        },
+        on_failure: { userdata, errorPtr in
+            let box = Unmanaged<DurableSetContinuationBox>
+                .fromOpaque(userdata!)
+                .takeRetainedValue()
+            let message = String(cString: errorPtr!)
+            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+            box.continuation.resume(throwing: IrohError.docDurabilityFailed(message, operationId: currentOperationId()))
+        }
+    )
+}
+
+// MARK: - Continuation Boxes
+
+private final class DurableSetContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<Void, Error>
+
+    init(_ continuation: CheckedContinuation<Void, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class DurableStatusContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<DurableStatus, Error>
+    var peers: [DurablePeer] = []
+
+    init(_ continuation: CheckedContinuation<DurableStatus, Error>) {
+        self.continuation = continuation
+    }
+}
@@ -0,0 +1,134 @@
+import Foundation
+import IrohSwiftFFI
+
+/// Progress callback type for file imports.
+public typealias ImportProgressHandler = @Sendable (ImportProgress) -> Void
+
+extension IrohDoc {
+    // MARK: - Files
+
+    /// Set a key to the contents of a file.
+    ///
+    /// The file is imported into the blob store and the entry set to its
+    /// hash in one step, without loading the file into memory, so large
+    /// photos and videos can be stored straight from disk. The content is
+    /// copied, so the file can be moved or deleted afterwards.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let hash = try await doc.setFile(author: author, key: "videos/trip.mov", at: videoURL) { progress in
+    ///     Task { @MainActor in bar.value = progress.fraction ?? 0 }
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - author: The author signing this entry.
+    ///   - key: The key as a string (UTF-8 encoded).
+    ///   - url: A local file URL.
+    ///   - onProgress: Called while the file is copied and hashed.
+    /// - Returns: The content hash of the file.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docReadOnly` if the handle is read-only,
+    ///           `IrohError.docSetFailed` if the file cannot be imported or the entry set.
+    @discardableResult
+    public func setFile(
+        author: IrohAuthor,
+        key: String,
+        at url: URL,
+        onProgress: @escaping ImportProgressHandler = { _ in }
+    ) async throws -> String {
+        try ensureNotClosed()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await setFile(author: author, key: keyData, at: url, onProgress: onProgress)
+    }
+
+    /// Set a key given as raw bytes to the contents of a file.
+    ///
+    /// - Parameters:
+    ///   - author: The author signing this entry.
+    ///   - key: The key bytes.
+    ///   - url: A local file URL.
+    ///   - onProgress: Called while the file is copied and hashed.
+    /// - Returns: The content hash of the file.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docReadOnly` if the handle is read-only,
+    ///           `IrohError.docSetFailed` if the file cannot be imported or the entry set.
+    @discardableResult
+    public func setFile(
+        author: IrohAuthor,
+        key: Data,
+        at url: URL,
+        onProgress: @escaping ImportProgressHandler = { _ in }
+    ) async throws -> String {
+        try ensureNotClosed()
+        try ensureWritable()
+        guard url.isFileURL else {
+            throw IrohError.docSetFailed("Not a file URL: \(url)")
+        }
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            key.withUnsafeBytes { keyBuffer in
+                url.withUnsafeFileSystemRepresentation { pathPtr in
+                    let keyBytes = IrohBytes(
+                        data: keyBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(keyBuffer.count)
+                    )
+                    let context = ImportContext(
+                        continuation: continuation,
+                        onProgress: onProgress
+                    )
+                    let box = Unmanaged.passRetained(context).toOpaque()
+
+                    let callback = IrohDocSetFileCallback(
+                        userdata: box,
+                        on_progress: { userdata, progress in
+                            let ctx = Unmanaged<ImportContext>
+                                .fromOpaque(userdata!)
+                                .takeUnretainedValue()
+                            ctx.onProgress(ImportProgress(
+                                copied: progress.copied,
+                                hashed: progress.hashed,
+                                total: progress.total
+                            ))
+                        },
+                        on_success: { userdata, hash in
+                            let ctx = Unmanaged<ImportContext>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            ctx.continuation.resume(returning: hexHash(hash))
+                        },
+                        on_failure: { userdata, errorPtr in
+                            let ctx = Unmanaged<ImportContext>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            let message = String(cString: errorPtr!)
+                            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                            ctx.continuation.resume(throwing: IrohError.docSetFailed(message, operationId: currentOperationId()))
+                        }
+                    )
+
+                    iroh_doc_set_file(handle.pointer, author.ffiSecret, keyBytes, pathPtr, callback)
+                }
+            }
+        }
+    }
+}
+
+// MARK: - Internal Helpers
+
+/// Context for file imports, holding the continuation and progress handler.
+final class ImportContext: @unchecked Sendable {
+    let continuation: CheckedContinuation<String, Error>
+    let onProgress: ImportProgressHandler
+
+    init(
+        continuation: CheckedContinuation<String, Error>,
+        onProgress: @escaping ImportProgressHandler
+    ) {
+        self.continuation = continuation
+        self.onProgress = onProgress
+    }
+}
@@ -0,0 +1,271 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohDoc {
+    // MARK: - Backup Manifest
+
+    /// Back up an asset: store its data, pin the blob and list it in this
+    /// document under `id`.
+    ///
+    /// The entry value holds the blob's hash, size and `metadata`, so other
+    /// devices syncing the document learn which assets exist. Adding an
+    /// existing ID replaces the asset. The blob is pinned before it is
+    /// listed, so the document never lists a blob that may be garbage
+    /// collected.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let missing = Set(try await doc.missingAssets(among: library.map(\.id)))
+    /// for photo in library where missing.contains(photo.id) {
+    ///     try await doc.addAsset(
+    ///         author: author,
+    ///         id: photo.id,
+    ///         data: photo.data,
+    ///         metadata: BlobMetadata(filename: photo.filename, contentType: "image/heic")
+    ///     )
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - author: The author to sign the entry with.
+    ///   - id: The app-defined asset ID (not empty).
+    ///   - data: The asset's content.
+    ///   - metadata: Filename, content type and creation date (default: now).
+    /// - Returns: The content hash of the asset's blob.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docReadOnly` if the handle is read-only,
+    ///           `IrohError.manifestFailed` if the asset cannot be stored.
+    @discardableResult
+    public func addAsset(
+        author: IrohAuthor,
+        id: String,
+        data: Data,
+        metadata: BlobMetadata = BlobMetadata()
+    ) async throws -> String {
+        try ensureNotClosed()
+        try ensureWritable()
+        try Task.checkCancellation()
+
+        let createdAt = metadata.createdAt.map { UInt64(max(0, $0.timeIntervalSince1970)) } ?? 0
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ManifestAddContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohHashCallback(
+                userdata: box,
+                on_success: { userdata, hash in
+                    let box = Unmanaged<ManifestAddContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: hexHash(hash))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ManifestAddContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.manifestFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            data.withUnsafeBytes { buffer in
+                let bytes = IrohBytes(
+                    data: buffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                    len: UInt(buffer.count)
+                )
+                id.withCString { idPtr in
+                    withOptionalCString(metadata.filename) { filenamePtr in
+                        withOptionalCString(metadata.contentType) { contentTypePtr in
+                            iroh_manifest_add(
+                                handle.pointer, author.ffiSecret, idPtr, bytes,
+                                filenamePtr, contentTypePtr, createdAt, callback
+                            )
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Every asset listed in this document's backup manifest.
+    ///
+    /// Entries whose value has not been synced yet are left out.
+    ///
+    /// - Returns: The assets, sorted by asset ID.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.manifestFailed` if an entry is not a manifest entry.
+    public func manifestAssets() async throws -> [ManifestAsset] {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ManifestListContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohManifestCallback(
+                userdata: box,
+                on_entry: { userdata, asset in
+                    let box = Unmanaged<ManifestListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more assets coming
+                    box.assets.append(ManifestAsset(from: asset))
+                },
+                on_complete: { userdata in
+                    let box = Unmanaged<ManifestListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: box.assets)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ManifestListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.manifestFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_manifest_list(handle.pointer, callback)
+        }
+    }
+
+    /// Asset IDs that are not fully backed up on this node.
+    ///
+    /// On the device taking backups, pass the IDs in the library to learn
+    /// which still need `addAsset`. On a device restoring, pass nothing to
+    /// learn which listed assets still need downloading. Listed assets
+    /// whose blob is already stored, for example after a download, are
+    /// pinned as a side effect.
+    ///
+    /// - Parameter ids: Asset IDs that should be listed.
+    /// - Returns: IDs from `ids` that are not listed, and listed IDs whose
+    ///            blob is not stored on this node, sorted.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.manifestFailed` if the check fails.
+    public func missingAssets(among ids: [String] = []) async throws -> [String] {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ManifestMissingContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohManifestMissingCallback(
+                userdata: box,
+                on_success: { userdata, idsPtr, count in
+                    let box = Unmanaged<ManifestMissingContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let ids = UnsafeBufferPointer(start: idsPtr, count: Int(count))
+                    box.continuation.resume(returning: ids.map { String(cString: $0!) })
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ManifestMissingContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.manifestFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            // Duplicate the strings so the pointers stay valid for the whole call
+            let idPtrs = ids.map { UnsafePointer<CChar>(strdup($0)) }
+            defer { idPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) } }
+            idPtrs.withUnsafeBufferPointer { buffer in
+                iroh_manifest_missing(handle.pointer, buffer.baseAddress, UInt(buffer.count), callback)
+            }
+        }
+    }
+
+    /// Remove deleted assets from the backup manifest and unpin their blobs.
+    ///
+    /// Each entry is removed before its blob is unpinned, and pins left by
+    /// interrupted calls or by assets replaced on other devices are removed
+    /// too, so the manifest and pins stay consistent if the app is killed.
+    ///
+    /// - Parameters:
+    ///   - author: The author to sign the deletions with.
+    ///   - ids: Asset IDs that were deleted.
+    /// - Returns: The number of listed assets that were removed.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docReadOnly` if the handle is read-only,
+    ///           `IrohError.manifestFailed` if pruning fails.
+    @discardableResult
+    public func pruneAssets(author: IrohAuthor, ids: [String]) async throws -> UInt64 {
+        try ensureNotClosed()
+        try ensureWritable()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ManifestPruneContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDocDelCallback(
+                userdata: box,
+                on_success: { userdata, removed in
+                    let box = Unmanaged<ManifestPruneContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: removed)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ManifestPruneContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.manifestFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            // Duplicate the strings so the pointers stay valid for the whole call
+            let idPtrs = ids.map { UnsafePointer<CChar>(strdup($0)) }
+            defer { idPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) } }
+            idPtrs.withUnsafeBufferPointer { buffer in
+                iroh_manifest_prune(
+                    handle.pointer, author.ffiSecret, buffer.baseAddress, UInt(buffer.count), callback
+                )
+            }
+        }
+    }
+}
+
+// MARK: - Continuation Boxes
+
+private final class ManifestAddContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<String, Error>
+
+    init(_ continuation: CheckedContinuation<String, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ManifestListContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<[ManifestAsset], Error>
+    var assets: [ManifestAsset] = []
+
+    init(_ continuation: CheckedContinuation<[ManifestAsset], Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ManifestMissingContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<[String], Error>
+
+    init(_ continuation: CheckedContinuation<[String], Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ManifestPruneContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<UInt64, Error>
+
+    init(_ continuation: CheckedContinuation<UInt64, Error>) {
+        self.continuation = continuation
+    }
+}
@@ -0,0 +1,111 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohDoc {
+    // MARK: - Outbox
+
+    /// Set a key and keep syncing until a peer has the write.
+    ///
+    /// Like `set(author:key:value:)`, the entry is stored locally before
+    /// this returns, so reads see it at once and it survives a crash. The
+    /// write is also recorded in the node's outbox, and the node syncs the
+    /// document with its known peers until one has it: right away, with
+    /// backoff while peers are unreachable, and again when connectivity
+    /// changes. Pending writes are picked up again when the node restarts.
+    ///
+    /// Progress is reported as `.outbox` node events: `.delivered` once a
+    /// sync that started after the write succeeded, or `.superseded(by:)`
+    /// if a newer write by another author won the key, so the app can merge
+    /// the two and write again.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let id = try await doc.enqueue(author: author, key: "draft", value: data)
+    /// for try await event in try node.events() {
+    ///     if case .outbox(id, _, _, .delivered) = event { break }
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - author: The author to sign the entry with.
+    ///   - key: The key as a string (UTF-8 encoded).
+    ///   - value: The value data.
+    /// - Returns: The outbox item ID used in events.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docReadOnly` if the handle is read-only,
+    ///           `IrohError.outboxFailed` if the write fails.
+    @discardableResult
+    public func enqueue(author: IrohAuthor, key: String, value: Data) async throws -> UInt64 {
+        try ensureNotClosed()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await enqueue(author: author, key: keyData, value: value)
+    }
+
+    /// Set a key using raw key bytes and keep syncing until a peer has the write.
+    ///
+    /// - Parameters:
+    ///   - author: The author to sign the entry with.
+    ///   - key: The key bytes.
+    ///   - value: The value data.
+    /// - Returns: The outbox item ID used in events.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docReadOnly` if the handle is read-only,
+    ///           `IrohError.outboxFailed` if the write fails.
+    @discardableResult
+    public func enqueue(author: IrohAuthor, key: Data, value: Data) async throws -> UInt64 {
+        try ensureNotClosed()
+        try ensureWritable()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            key.withUnsafeBytes { keyBuffer in
+                value.withUnsafeBytes { valueBuffer in
+                    let keyBytes = IrohBytes(
+                        data: keyBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(keyBuffer.count)
+                    )
+                    let valueBytes = IrohBytes(
+                        data: valueBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(valueBuffer.count)
+                    )
+
+                    let box = Unmanaged.passRetained(
+                        OutboxContinuationBox(continuation)
+                    ).toOpaque()
+
+                    let callback = IrohDocOutboxCallback(
+                        userdata: box,
+                        on_success: { userdata, itemId in
+                            let box = Unmanaged<OutboxContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            box.continuation.resume(returning: itemId)
+                        },
+                        on_failure: { userdata, errorPtr in
+                            let box = Unmanaged<OutboxContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            let message = String(cString: errorPtr!)
+                            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                            box.continuation.resume(throwing: IrohError.outboxFailed(message, operationId: currentOperationId()))
+                        }
+                    )
+
+                    iroh_doc_outbox_set(handle.pointer, author.ffiSecret, keyBytes, valueBytes, callback)
+                }
+            }
+        }
+    }
+}
+
+// MARK: - Continuation Boxes
+
+private final class OutboxContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<UInt64, Error>
+
+    init(_ continuation: CheckedContinuation<UInt64, Error>) {
+        self.continuation = continuation
+    }
+}
@@ -26,6 +26,10 @@ extension IrohDoc {
     ///         print("Content ready: \(hash)")
     ///     case .pendingContentReady:
     ///         print("All pending content ready")
+    ///     case .replayed(let entry):
+    ///         print("Missed while away: \(entry.keyString ?? "?")")
+    ///     case .contentFetched(_, let entry, let content):
+    ///         print("\(entry.keyString ?? "?") has \(content.count) bytes")
     ///     }
     /// }
     /// ```
@@ -33,51 +37,207 @@ extension IrohDoc {
     /// - Returns: An async throwing stream of document events.
     /// - Throws: `IrohError.docClosed` if the document is closed.
     public func subscribe() throws -> AsyncThrowingStream<DocEvent, Error> {
+        try subscribe(fetchContent: false)
+    }
+
+    /// Subscribe to document events, optionally with the content of remote
+    /// entries.
+    ///
+    /// With `fetchContent`, each remote insert matching the document's
+    /// download policy is followed by a `.contentFetched` event carrying the
+    /// entry and its value as soon as the content is local, so there is no
+    /// need to pair `.insertRemote` with `.contentReady` and read the value.
+    /// Content that fails to download is not reported.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// for try await event in try doc.subscribe(fetchContent: true, maxContentSize: 1 << 20) {
+    ///     if case .contentFetched(_, let entry, let content) = event {
+    ///         render(entry.keyString, content)
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - fetchContent: Whether to deliver `.contentFetched` events.
+    ///   - maxContentSize: Largest value delivered; larger entries only get
+    ///                     `.insertRemote` and `.contentReady`. If nil, no limit.
+    /// - Returns: An async throwing stream of document events.
+    /// - Throws: `IrohError.docClosed` if the document is closed.
+    public func subscribe(
+        fetchContent: Bool,
+        maxContentSize: Int? = nil
+    ) throws -> AsyncThrowingStream<DocEvent, Error> {
         try ensureNotClosed()
 
         return AsyncThrowingStream(bufferingPolicy: .bufferingNewest(100)) { continuation in
-            let context = SubscriptionContext(continuation: continuation)
-            let contextPtr = Unmanaged.passRetained(context).toOpaque()
-
-            // Set up cancellation handler
-            continuation.onTermination = { @Sendable termination in
+            let context = SubscriptionContext(
+                onEvent: { event in
+                    continuation.yield(DocEvent.from(event))
+                },
+                onFinish: { error in
+                    continuation.finish(throwing: error)
+                }
+            )
+            continuation.onTermination = { @Sendable _ in
                 // Cancel the FFI subscription when the stream is terminated
                 context.cancel()
             }
+            startSubscription(
+                context: context,
+                cursor: nil,
+                fetchContent: fetchContent,
+                maxContentSize: maxContentSize
+            )
+        }
+    }
 
-            let callback = IrohDocSubscribeCallback(
-                userdata: contextPtr,
-                on_event: { userdata, event in
-                    // takeUnretainedValue - don't consume, more events coming
-                    let ctx = Unmanaged<SubscriptionContext>
-                        .fromOpaque(userdata!)
-                        .takeUnretainedValue()
-
-                    let docEvent = DocEvent.from(event)
-                    iroh_doc_event_free(event)
-                    ctx.continuation.yield(docEvent)
+    /// Subscribe to document events, resuming from a saved cursor.
+    ///
+    /// Each event comes with a cursor. Persist the cursor of the last event
+    /// you handled and pass it here after a relaunch: entries written in the
+    /// meantime are delivered first as `.replayed` events, oldest first,
+    /// followed by live events. Only the latest value per author and key is
+    /// replayed, and deletions are replayed as entries with empty content.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let saved = UserDefaults.standard.string(forKey: "docCursor").map(DocCursor.init)
+    /// for try await update in try await doc.subscribe(from: saved) {
+    ///     handle(update.event)
+    ///     UserDefaults.standard.set(update.cursor.rawValue, forKey: "docCursor")
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - cursor: Cursor of the last handled event, or nil to start from the
+    ///             document's current state.
+    ///   - fetchContent: Whether to deliver `.contentFetched` events, as in
+    ///                   `subscribe(fetchContent:maxContentSize:)`. Default: false.
+    ///   - maxContentSize: Largest value delivered. If nil, no limit.
+    /// - Returns: An async throwing stream of events with their document and cursor.
+    /// - Throws: `IrohError.docClosed` if the document is closed.
+    public func subscribe(
+        from cursor: DocCursor?,
+        fetchContent: Bool = false,
+        maxContentSize: Int? = nil
+    ) throws -> AsyncThrowingStream<CursoredDocEvent, Error> {
+        try ensureNotClosed()
+
+        return AsyncThrowingStream(bufferingPolicy: .bufferingNewest(100)) { continuation in
+            let context = SubscriptionContext(
+                onEvent: { event in
+                    continuation.yield(CursoredDocEvent(
+                        event: DocEvent.from(event),
+                        namespaceId: String(cString: event.namespace_id!),
+                        cursor: DocCursor(rawValue: String(cString: event.cursor!))
+                    ))
                 },
-                on_complete: { userdata in
-                    // takeRetainedValue - consume on terminal
-                    let ctx = Unmanaged<SubscriptionContext>
-                        .fromOpaque(userdata!)
-                        .takeRetainedValue()
-                    ctx.continuation.finish()
+                onFinish: { error in
+                    continuation.finish(throwing: error)
+                }
+            )
+            continuation.onTermination = { @Sendable _ in
+                context.cancel()
+            }
+            startSubscription(
+                context: context,
+                cursor: cursor,
+                fetchContent: fetchContent,
+                maxContentSize: maxContentSize
+            )
+        }
+    }
+
+    /// Subscribe to deletions only.
+    ///
+    /// Delivers one `DocDeletion` per deleted key, whether deleted locally
+    /// or by a peer, so list UIs can drop rows without telling deletions
+    /// apart from empty inserts in `subscribe()`.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// for try await deletion in try doc.subscribeDeletions() {
+    ///     rows.removeAll { $0.key.starts(with: deletion.key) }
+    /// }
+    /// ```
+    ///
+    /// - Returns: An async throwing stream of deletions.
+    /// - Throws: `IrohError.docClosed` if the document is closed.
+    public func subscribeDeletions() throws -> AsyncThrowingStream<DocDeletion, Error> {
+        try ensureNotClosed()
+
+        return AsyncThrowingStream(bufferingPolicy: .bufferingNewest(100)) { continuation in
+            let context = SubscriptionContext(
+                onEvent: { event in
+                    continuation.yield(DocDeletion(from: event))
                 },
-                on_failure: { userdata, errorPtr in
-                    // takeRetainedValue - consume on terminal
-                    let ctx = Unmanaged<SubscriptionContext>
-                        .fromOpaque(userdata!)
-                        .takeRetainedValue()
-                    let message = String(cString: errorPtr!)
-                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    ctx.continuation.finish(throwing: IrohError.docSubscribeFailed(message))
+                onFinish: { error in
+                    continuation.finish(throwing: error)
                 }
             )
+            continuation.onTermination = { @Sendable _ in
+                context.cancel()
+            }
+            startSubscription(
+                context: context,
+                cursor: nil,
+                fetchContent: false,
+                maxContentSize: nil,
+                deletionsOnly: true
+            )
+        }
+    }
+
+    /// Start the FFI subscription that feeds `context`.
+    nonisolated private func startSubscription(
+        context: SubscriptionContext,
+        cursor: DocCursor?,
+        fetchContent: Bool,
+        maxContentSize: Int?,
+        deletionsOnly: Bool = false
+    ) {
+        let contextPtr = Unmanaged.passRetained(context).toOpaque()
+
+        let callback = IrohDocSubscribeCallback(
+            userdata: contextPtr,
+            on_event: { userdata, event in
+                // takeUnretainedValue - don't consume, more events coming
+                let ctx = Unmanaged<SubscriptionContext>
+                    .fromOpaque(userdata!)
+                    .takeUnretainedValue()
 
-            let subHandle = iroh_doc_subscribe(handle.pointer, callback)
-            context.subscriptionHandle = SubscriptionHandleWrapper(pointer: subHandle)
+                ctx.onEvent(event)
+                iroh_doc_event_free(event)
+            },
+            on_complete: { userdata in
+                // takeRetainedValue - consume on terminal
+                let ctx = Unmanaged<SubscriptionContext>
+                    .fromOpaque(userdata!)
+                    .takeRetainedValue()
+                ctx.onFinish(nil)
+            },
+            on_failure: { userdata, errorPtr in
+                // takeRetainedValue - consume on terminal
+                let ctx = Unmanaged<SubscriptionContext>
+                    .fromOpaque(userdata!)
+                    .takeRetainedValue()
+                let message = String(cString: errorPtr!)
+                iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                ctx.onFinish(IrohError.docSubscribeFailed(message, operationId: currentOperationId()))
+            }
+        )
+
+        let subHandle = withOptionalCString(cursor?.rawValue) { cursorPtr in
+            let options = IrohDocSubscribeOptions(
+                cursor: cursorPtr,
+                fetch_content: fetchContent,
+                max_content_size: UInt64(maxContentSize ?? 0),
+                deletions_only: deletionsOnly
+            )
+            return iroh_doc_subscribe_with_options(handle.pointer, options, callback)
         }
+        context.subscriptionHandle = SubscriptionHandleWrapper(pointer: subHandle)
     }
 }
 
@@ -85,11 +245,13 @@ extension IrohDoc {
 
 /// Internal context for managing a subscription.
 private final class SubscriptionContext: @unchecked Sendable {
-    let continuation: AsyncThrowingStream<DocEvent, Error>.Continuation
+    let onEvent: (IrohDocEvent) -> Void
+    let onFinish: (Error?) -> Void
     var subscriptionHandle: SubscriptionHandleWrapper?
 
-    init(continuation: AsyncThrowingStream<DocEvent, Error>.Continuation) {
-        self.continuation = continuation
+    init(onEvent: @escaping (IrohDocEvent) -> Void, onFinish: @escaping (Error?) -> Void) {
+        self.onEvent = onEvent
+        self.onFinish = onFinish
     }
 
     func cancel() {
@@ -0,0 +1,172 @@
+import Foundation
+import IrohSwiftFFI
+
+/// Decides what happens to an entry from an author outside the allow-list.
+public typealias WriteFilterDecision = @Sendable (_ entry: FilteredEntry) -> WriteVerdict
+
+/// Told about an entry a write filter rejected or quarantined.
+///
+/// Quarantined entries come with their content; rejected ones with nil.
+public typealias WriteFilterReport = @Sendable (_ entry: FilteredEntry, _ verdict: WriteVerdict, _ content: Data?) -> Void
+
+extension IrohDoc {
+    /// Keep writes from unexpected authors out of this document.
+    ///
+    /// Entries synced from peers are accepted if `author` or one of
+    /// `allowedAuthors` wrote them. Other entries go to `decide` on a
+    /// background thread, or are rejected if it is nil. Rejected and
+    /// quarantined entries are rolled back by `author`: the newest value from
+    /// an allowed author is written again, or the key is deleted if there is
+    /// none. A key is not deleted while `author` has older entries under
+    /// longer keys starting with it, since they would be deleted too.
+    /// `onFiltered` is then told about the entry, with its content if it was
+    /// quarantined.
+    ///
+    /// Sync cannot refuse entries, so a rejected entry is visible until it is
+    /// rolled back and stays in the replica, where peers can still fetch it.
+    /// Use this to contain a leaked write ticket, then share a new document.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let filter = try doc.setWriteFilter(
+    ///     author: author,
+    ///     allowedAuthors: [partnerAuthorId]
+    /// ) { entry, verdict, _ in
+    ///     print("Rolled back \(entry.key) from \(entry.authorId)")
+    /// }
+    /// // Later:
+    /// filter.cancel()
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - author: The author that rolls back filtered entries.
+    ///   - allowedAuthors: Author IDs whose entries are always accepted.
+    ///   - decide: Decides entries from other authors (default: reject them).
+    ///   - onFiltered: Called for every rejected or quarantined entry.
+    /// - Returns: A handle that stops the filter when cancelled or deallocated.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docReadOnly` if the handle is read-only,
+    ///           `IrohError.writeFilterFailed` if an author ID is invalid.
+    public func setWriteFilter(
+        author: IrohAuthor,
+        allowedAuthors: [String],
+        decide: WriteFilterDecision? = nil,
+        onFiltered: WriteFilterReport? = nil
+    ) throws -> WriteFilterHandle {
+        try ensureNotClosed()
+        try ensureWritable()
+
+        for authorId in allowedAuthors where authorId.count != 64 || !authorId.allSatisfy(\.isHexDigit) {
+            throw IrohError.writeFilterFailed("Invalid author ID: \(authorId)")
+        }
+
+        let context = WriteFilterContext(decide: decide, onFiltered: onFiltered)
+        let contextPtr = Unmanaged.passRetained(context).toOpaque()
+
+        // Duplicate the strings so the pointers stay valid for the whole call
+        let authorPtrs = allowedAuthors.map { UnsafePointer<CChar>(strdup($0)) }
+        defer { authorPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) } }
+
+        let decideCallback: (@convention(c) (UnsafeMutableRawPointer?, IrohFilteredEntry) -> IrohWriteVerdict)? =
+            decide == nil ? nil : { userdata, entry in
+                // takeUnretainedValue - called once per entry
+                let ctx = Unmanaged<WriteFilterContext>
+                    .fromOpaque(userdata!)
+                    .takeUnretainedValue()
+
+                switch ctx.decide?(FilteredEntry(from: entry)) {
+                case .accept:
+                    return WriteAccept
+                case .quarantine:
+                    return WriteQuarantine
+                case .reject, nil:
+                    return WriteReject
+                }
+            }
+
+        let subHandle = authorPtrs.withUnsafeBufferPointer { buffer in
+            let filter = IrohDocWriteFilter(
+                userdata: contextPtr,
+                allowed_authors: buffer.baseAddress,
+                allowed_authors_len: UInt(buffer.count),
+                decide: decideCallback,
+                on_filtered: { userdata, entry, verdict, content in
+                    // takeUnretainedValue - called once per filtered entry
+                    let ctx = Unmanaged<WriteFilterContext>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()
+
+                    if verdict == WriteQuarantine {
+                        let data = Data(bytes: content.data, count: Int(content.len))
+                        ctx.onFiltered?(FilteredEntry(from: entry), .quarantine, data)
+                    } else {
+                        ctx.onFiltered?(FilteredEntry(from: entry), .reject, nil)
+                    }
+                },
+                on_complete: { userdata in
+                    // release - consume on terminal
+                    Unmanaged<WriteFilterContext>
+                        .fromOpaque(userdata!)
+                        .release()
+                },
+                on_failure: { userdata, errorPtr in
+                    // release - consume on terminal
+                    Unmanaged<WriteFilterContext>
+                        .fromOpaque(userdata!)
+                        .release()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    IrohLogger.node.error("write filter stopped: \(message, privacy: .public)")
+                }
+            )
+            return iroh_doc_set_write_filter(handle.pointer, author.ffiSecret, filter)
+        }
+        return WriteFilterHandle(pointer: subHandle)
+    }
+}
+
+// MARK: - Filter Handle
+
+/// A registered document write filter.
+///
+/// The filter stops when `cancel()` is called or the handle is
+/// deallocated, whichever comes first.
+public final class WriteFilterHandle: @unchecked Sendable {
+    private let lock = NSLock()
+    private var pointer: UnsafeMutablePointer<IrohSubscriptionHandle>?
+
+    init(pointer: UnsafeMutablePointer<IrohSubscriptionHandle>?) {
+        self.pointer = pointer
+    }
+
+    deinit {
+        cancel()
+    }
+
+    /// Stop filtering writes.
+    ///
+    /// Safe to call more than once.
+    public func cancel() {
+        lock.lock()
+        let toCancel = pointer
+        pointer = nil
+        lock.unlock()
+
+        if let toCancel {
+            iroh_subscription_cancel(toCancel)
+        }
+    }
+}
+
+// MARK: - Filter Context
+
+/// Internal context holding the filter closures for the FFI callbacks.
+private final class WriteFilterContext: @unchecked Sendable {
+    let decide: WriteFilterDecision?
+    let onFiltered: WriteFilterReport?
+
+    init(decide: WriteFilterDecision?, onFiltered: WriteFilterReport?) {
+        self.decide = decide
+        self.onFiltered = onFiltered
+    }
+}
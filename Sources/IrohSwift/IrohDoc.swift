@@ -33,15 +33,23 @@ public actor IrohDoc {
     /// The namespace ID for this document.
     public let namespaceId: String
 
+    /// Whether writes through this handle are rejected.
+    ///
+    /// Set when the document was opened or joined with `.readOnly`. Writes
+    /// and write tickets then throw `IrohError.docReadOnly`; the Rust layer
+    /// enforces this as well, whatever the document capability.
+    public let isReadOnly: Bool
+
     private var isClosed = false
 
     /// Create a document from FFI handles.
     init(handle: DocHandleWrapper,
          nodeHandle: NodeHandleWrapper,
-         namespaceId: String) {
+         isReadOnly: Bool = false) {
         self.handle = handle
         self.nodeHandle = nodeHandle
-        self.namespaceId = namespaceId
+        self.namespaceId = takeFFIString(iroh_doc_namespace_id(handle.pointer)) ?? ""
+        self.isReadOnly = isReadOnly
     }
 
     deinit {
@@ -68,6 +76,13 @@ public actor IrohDoc {
         }
     }
 
+    /// Throw if this handle was opened read-only.
+    func ensureWritable() throws {
+        if isReadOnly {
+            throw IrohError.docReadOnly
+        }
+    }
+
     // MARK: - CRUD Operations
 
     /// Set a key-value pair in the document.
@@ -97,7 +112,194 @@ public actor IrohDoc {
     /// - Throws: `IrohError.docClosed` if the document is closed,
     ///           `IrohError.docSetFailed` if the operation fails.
     public func set(author: IrohAuthor, key: Data, value: Data) async throws -> String {
+        try await set(author: Optional(author), key: key, value: value, durability: nil)
+    }
+
+    /// Set a key-value pair as the node's default author.
+    ///
+    /// The author's secret stays in the node; see
+    /// `IrohNode.setDefaultAuthor(_:)`.
+    ///
+    /// - Parameters:
+    ///   - key: The key as a string (UTF-8 encoded).
+    ///   - value: The value data.
+    /// - Returns: The content hash of the stored value.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    public func set(key: String, value: Data) async throws -> String {
+        try ensureNotClosed()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await set(key: keyData, value: value)
+    }
+
+    /// Set a key-value pair using raw key bytes as the node's default author.
+    ///
+    /// - Parameters:
+    ///   - key: The key bytes.
+    ///   - value: The value data.
+    /// - Returns: The content hash of the stored value.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    public func set(key: Data, value: Data) async throws -> String {
+        try await set(author: nil, key: key, value: value, durability: nil)
+    }
+
+    /// Set a key-value pair with a durability of its own, rather than the
+    /// document's (see `setDurability(_:)`).
+    ///
+    /// With `.immediate`, the call only returns once the entry is synced to
+    /// disk, so it survives a crash right after.
+    ///
+    /// - Parameters:
+    ///   - author: The author signing this entry.
+    ///   - key: The key as a string (UTF-8 encoded).
+    ///   - value: The value data.
+    ///   - durability: How the write is stored before returning.
+    /// - Returns: The content hash of the stored value.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    public func set(
+        author: IrohAuthor,
+        key: String,
+        value: Data,
+        durability: DocDurability
+    ) async throws -> String {
+        try ensureNotClosed()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await set(author: author, key: keyData, value: value, durability: durability)
+    }
+
+    /// Set a key-value pair using raw key bytes with a durability of its own.
+    ///
+    /// - Parameters:
+    ///   - author: The author signing this entry.
+    ///   - key: The key bytes.
+    ///   - value: The value data.
+    ///   - durability: How the write is stored before returning.
+    /// - Returns: The content hash of the stored value.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    public func set(
+        author: IrohAuthor,
+        key: Data,
+        value: Data,
+        durability: DocDurability
+    ) async throws -> String {
+        try await set(author: Optional(author), key: key, value: value, durability: Optional(durability))
+    }
+
+    /// Set a key-value pair as `author`, or the node's default author if nil,
+    /// with `durability`, or the document's if nil.
+    private func set(
+        author: IrohAuthor?,
+        key: Data,
+        value: Data,
+        durability: DocDurability?
+    ) async throws -> String {
+        try ensureNotClosed()
+        try ensureWritable()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            key.withUnsafeBytes { keyBuffer in
+                value.withUnsafeBytes { valueBuffer in
+                    let keyBytes = IrohBytes(
+                        data: keyBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(keyBuffer.count)
+                    )
+                    let valueBytes = IrohBytes(
+                        data: valueBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(valueBuffer.count)
+                    )
+
+                    let box = Unmanaged.passRetained(
+                        StringContinuationBox(continuation)
+                    ).toOpaque()
+
+                    let callback = IrohDocSetCallback(
+                        userdata: box,
+                        on_success: { userdata, hashPtr in
+                            let box = Unmanaged<StringContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            let hash = String(cString: hashPtr!)
+                            iroh_string_free(UnsafeMutablePointer(mutating: hashPtr))
+                            box.continuation.resume(returning: hash)
+                        },
+                        on_failure: { userdata, errorPtr in
+                            let box = Unmanaged<StringContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            let message = String(cString: errorPtr!)
+                            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                            box.continuation.resume(throwing: IrohError.docSetFailed(message, operationId: currentOperationId()))
+                        }
+                    )
+
+                    switch (author, durability) {
+                    case let (author?, durability?):
+                        iroh_doc_set_with_durability(
+                            handle.pointer,
+                            author.ffiSecret,
+                            keyBytes,
+                            valueBytes,
+                            durability.ffiDurability,
+                            callback
+                        )
+                    case let (author?, nil):
+                        iroh_doc_set(handle.pointer, author.ffiSecret, keyBytes, valueBytes, callback)
+                    case (nil, _):
+                        iroh_doc_set_with_default_author(handle.pointer, keyBytes, valueBytes, callback)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Set a key-value pair that is removed after a time-to-live.
+    ///
+    /// Use this for ephemeral state such as invitations or presence
+    /// snapshots. The node keeps an expiry record next to the entry and
+    /// deletes the key in a background pass after it expires (see
+    /// `IrohConfig.expiryPruneInterval`); until then reads still return it.
+    /// Setting the key again without a TTL cancels the expiry.
+    ///
+    /// Only this key is deleted; longer keys that start with it are kept,
+    /// and the key stays while ones written before it don't expire.
+    ///
+    /// - Parameters:
+    ///   - author: The author signing this entry.
+    ///   - key: The key as a string (UTF-8 encoded). Must not be empty.
+    ///   - value: The value data.
+    ///   - ttl: How long the entry should live.
+    /// - Returns: The content hash of the stored value.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    public func set(author: IrohAuthor, key: String, value: Data, ttl: Duration) async throws -> String {
+        try ensureNotClosed()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await set(author: author, key: keyData, value: value, ttl: ttl)
+    }
+
+    /// Set a key-value pair that is removed after a time-to-live, using raw key bytes.
+    ///
+    /// - Parameters:
+    ///   - author: The author signing this entry.
+    ///   - key: The key bytes. Must not be empty.
+    ///   - value: The value data.
+    ///   - ttl: How long the entry should live.
+    /// - Returns: The content hash of the stored value.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    public func set(author: IrohAuthor, key: Data, value: Data, ttl: Duration) async throws -> String {
         try ensureNotClosed()
+        try ensureWritable()
         try Task.checkCancellation()
 
         return try await withCheckedThrowingContinuation { continuation in
@@ -132,11 +334,138 @@ public actor IrohDoc {
                                 .takeRetainedValue()
                             let message = String(cString: errorPtr!)
                             iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                            box.continuation.resume(throwing: IrohError.docSetFailed(message))
+                            box.continuation.resume(throwing: IrohError.docSetFailed(message, operationId: currentOperationId()))
+                        }
+                    )
+
+                    iroh_doc_set_with_ttl(
+                        handle.pointer, author.ffiSecret, keyBytes, valueBytes, ttl.milliseconds, callback
+                    )
+                }
+            }
+        }
+    }
+
+    /// Set a key only if its latest entry still has the expected content hash.
+    ///
+    /// Use this for optimistic concurrency on counters and settings: read
+    /// the entry, compute the new value, and retry if another write got in
+    /// first.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// while true {
+    ///     let entry = try await doc.get(key: "counter")
+    ///     let count = try await counterValue(entry)  // decode the current value
+    ///     do {
+    ///         try await doc.compareAndSet(
+    ///             author: author, key: "counter",
+    ///             expectedHash: entry?.contentHash, value: Data("\(count + 1)".utf8))
+    ///         break
+    ///     } catch IrohError.docConflict {
+    ///         continue  // another write got in first; re-read and retry
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - author: The author signing this entry.
+    ///   - key: The key as a string (UTF-8 encoded).
+    ///   - expectedHash: Content hash the latest entry must have, or nil to
+    ///                   require that the key is absent or deleted.
+    ///   - value: The value data.
+    /// - Returns: The content hash of the stored value.
+    /// - Throws: `IrohError.docConflict` if the latest entry does not match,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    @discardableResult
+    public func compareAndSet(
+        author: IrohAuthor,
+        key: String,
+        expectedHash: String?,
+        value: Data
+    ) async throws -> String {
+        try ensureNotClosed()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await compareAndSet(author: author, key: keyData, expectedHash: expectedHash, value: value)
+    }
+
+    /// Set a key only if its latest entry still has the expected content hash,
+    /// using raw key bytes.
+    ///
+    /// - Parameters:
+    ///   - author: The author signing this entry.
+    ///   - key: The key bytes.
+    ///   - expectedHash: Content hash the latest entry must have, or nil to
+    ///                   require that the key is absent or deleted.
+    ///   - value: The value data.
+    /// - Returns: The content hash of the stored value.
+    /// - Throws: `IrohError.docConflict` if the latest entry does not match,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    @discardableResult
+    public func compareAndSet(
+        author: IrohAuthor,
+        key: Data,
+        expectedHash: String?,
+        value: Data
+    ) async throws -> String {
+        try ensureNotClosed()
+        try ensureWritable()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            key.withUnsafeBytes { keyBuffer in
+                value.withUnsafeBytes { valueBuffer in
+                    let keyBytes = IrohBytes(
+                        data: keyBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(keyBuffer.count)
+                    )
+                    let valueBytes = IrohBytes(
+                        data: valueBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(valueBuffer.count)
+                    )
+
+                    let box = Unmanaged.passRetained(
+                        StringContinuationBox(continuation)
+                    ).toOpaque()
+
+                    let callback = IrohDocSetCasCallback(
+                        userdata: box,
+                        on_success: { userdata, hashPtr in
+                            let box = Unmanaged<StringContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            let hash = String(cString: hashPtr!)
+                            iroh_string_free(UnsafeMutablePointer(mutating: hashPtr))
+                            box.continuation.resume(returning: hash)
+                        },
+                        on_conflict: { userdata, currentPtr in
+                            let box = Unmanaged<StringContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            var current: String?
+                            if let currentPtr {
+                                current = String(cString: currentPtr)
+                                iroh_string_free(UnsafeMutablePointer(mutating: currentPtr))
+                            }
+                            box.continuation.resume(throwing: IrohError.docConflict(currentHash: current))
+                        },
+                        on_failure: { userdata, errorPtr in
+                            let box = Unmanaged<StringContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            let message = String(cString: errorPtr!)
+                            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                            box.continuation.resume(throwing: IrohError.docSetFailed(message, operationId: currentOperationId()))
                         }
                     )
 
-                    iroh_doc_set(handle.pointer, author.ffiSecret, keyBytes, valueBytes, callback)
+                    withOptionalCString(expectedHash) { expectedPtr in
+                        iroh_doc_set_cas(
+                            handle.pointer, author.ffiSecret, keyBytes, expectedPtr, valueBytes, callback
+                        )
+                    }
                 }
             }
         }
@@ -198,7 +527,7 @@ public actor IrohDoc {
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        box.continuation.resume(throwing: IrohError.docGetFailed(message))
+                        box.continuation.resume(throwing: IrohError.docGetFailed(message, operationId: currentOperationId()))
                     }
                 )
 
@@ -207,6 +536,85 @@ public actor IrohDoc {
         }
     }
 
+    /// Get the latest entries for several keys in one call.
+    ///
+    /// - Parameter keys: The keys as strings (UTF-8 encoded).
+    /// - Returns: One element per key, in the same order; nil where not found.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docGetFailed` if the operation fails.
+    public func get(keys: [String]) async throws -> [DocEntry?] {
+        try ensureNotClosed()
+        let keyData = try keys.map { key in
+            guard let data = key.data(using: .utf8) else {
+                throw IrohError.stringEncodingFailed(.utf8)
+            }
+            return data
+        }
+        return try await get(keys: keyData)
+    }
+
+    /// Get the latest entries for several raw keys in one call.
+    ///
+    /// - Parameter keys: The key bytes.
+    /// - Returns: One element per key, in the same order; nil where not found.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docGetFailed` if the operation fails.
+    public func get(keys: [Data]) async throws -> [DocEntry?] {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        // One buffer keeps every key alive for the duration of the call
+        let joined = keys.reduce(into: Data()) { $0.append($1) }
+
+        return try await withCheckedThrowingContinuation { continuation in
+            joined.withUnsafeBytes { joinedBuffer in
+                let base = joinedBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self)
+                var offset = 0
+                let keyBytes = keys.map { key -> IrohBytes in
+                    defer { offset += key.count }
+                    return IrohBytes(data: base.map { $0 + offset }, len: UInt(key.count))
+                }
+
+                let box = Unmanaged.passRetained(
+                    GetBatchContext(continuation, count: keys.count)
+                ).toOpaque()
+
+                let callback = IrohDocGetBatchCallback(
+                    userdata: box,
+                    on_entry: { userdata, index, entryPtr in
+                        // takeUnretainedValue - called once per key
+                        let ctx = Unmanaged<GetBatchContext>
+                            .fromOpaque(userdata!)
+                            .takeUnretainedValue()
+                        guard let entryPtr = entryPtr else { return }
+                        ctx.entries[Int(index)] = DocEntry(from: entryPtr.pointee)
+                        iroh_doc_entry_free(UnsafeMutablePointer(mutating: entryPtr))
+                    },
+                    on_complete: { userdata in
+                        let ctx = Unmanaged<GetBatchContext>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        ctx.continuation.resume(returning: ctx.entries)
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let ctx = Unmanaged<GetBatchContext>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        ctx.continuation.resume(throwing: IrohError.docGetFailed(message, operationId: currentOperationId()))
+                    }
+                )
+
+                keyBytes.withUnsafeBufferPointer { keysBuffer in
+                    iroh_doc_get_batch(
+                        handle.pointer, keysBuffer.baseAddress, UInt(keysBuffer.count), callback
+                    )
+                }
+            }
+        }
+    }
+
     /// Get entries by key prefix.
     ///
     /// - Parameter prefix: The key prefix as a string (UTF-8 encoded).
@@ -226,16 +634,35 @@ public actor IrohDoc {
     /// - Returns: An async stream of entries matching the prefix.
     /// - Throws: `IrohError.docClosed` if the document is closed.
     public func getMany(prefix: Data) async throws -> AsyncThrowingStream<DocEntry, Error> {
+        try await query(DocQuery(keyPrefix: prefix))
+    }
+
+    /// Get entries matching a query.
+    ///
+    /// Yields the latest entry per author and key, in key order, or only
+    /// each key's winning entry when `query.latestOnly` is set. Timestamp
+    /// bounds are checked against every key under the prefix, so narrow the
+    /// prefix where possible.
+    ///
+    /// - Parameter query: The key prefix and timestamp range to match.
+    /// - Returns: An async stream of matching entries.
+    /// - Throws: `IrohError.docClosed` if the document is closed.
+    public func query(_ query: DocQuery) async throws -> AsyncThrowingStream<DocEntry, Error> {
         try ensureNotClosed()
 
         return AsyncThrowingStream(bufferingPolicy: .bufferingNewest(100)) { continuation in
             let context = GetManyContext(continuation: continuation)
             let contextPtr = Unmanaged.passRetained(context).toOpaque()
 
-            prefix.withUnsafeBytes { prefixBuffer in
-                let prefixBytes = IrohBytes(
-                    data: prefixBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
-                    len: UInt(prefixBuffer.count)
+            query.keyPrefix.withUnsafeBytes { prefixBuffer in
+                let ffiQuery = IrohDocQuery(
+                    key_prefix: IrohBytes(
+                        data: prefixBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(prefixBuffer.count)
+                    ),
+                    timestamp_start: query.timestampStart ?? 0,
+                    timestamp_end: query.timestampEnd ?? 0,
+                    latest_only: query.latestOnly
                 )
 
                 let callback = IrohDocGetManyCallback(
@@ -260,11 +687,11 @@ public actor IrohDoc {
                             .takeRetainedValue()  // Consume on terminal
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        ctx.continuation.finish(throwing: IrohError.docGetFailed(message))
+                        ctx.continuation.finish(throwing: IrohError.docGetFailed(message, operationId: currentOperationId()))
                     }
                 )
 
-                iroh_doc_get_many(handle.pointer, prefixBytes, callback)
+                iroh_doc_query(handle.pointer, ffiQuery, callback)
             }
         }
     }
@@ -294,14 +721,44 @@ public actor IrohDoc {
     /// - Throws: `IrohError.docClosed` if the document is closed,
     ///           `IrohError.docDeleteFailed` if the operation fails.
     public func delete(author: IrohAuthor, key: Data) async throws -> UInt64 {
+        try await delete(author: Optional(author), key: key)
+    }
+
+    /// Delete an entry as the node's default author.
+    ///
+    /// - Parameter key: The key as a string (UTF-8 encoded).
+    /// - Returns: The number of entries deleted.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docDeleteFailed` if the operation fails.
+    public func delete(key: String) async throws -> UInt64 {
         try ensureNotClosed()
-        try Task.checkCancellation()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await delete(key: keyData)
+    }
 
-        return try await withCheckedThrowingContinuation { continuation in
-            key.withUnsafeBytes { keyBuffer in
-                let keyBytes = IrohBytes(
-                    data: keyBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
-                    len: UInt(keyBuffer.count)
+    /// Delete an entry using raw key bytes as the node's default author.
+    ///
+    /// - Parameter key: The key bytes.
+    /// - Returns: The number of entries deleted.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docDeleteFailed` if the operation fails.
+    public func delete(key: Data) async throws -> UInt64 {
+        try await delete(author: nil, key: key)
+    }
+
+    /// Delete an entry as `author`, or the node's default author if nil.
+    private func delete(author: IrohAuthor?, key: Data) async throws -> UInt64 {
+        try ensureNotClosed()
+        try ensureWritable()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            key.withUnsafeBytes { keyBuffer in
+                let keyBytes = IrohBytes(
+                    data: keyBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                    len: UInt(keyBuffer.count)
                 )
 
                 let box = Unmanaged.passRetained(
@@ -322,61 +779,324 @@ public actor IrohDoc {
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        box.continuation.resume(throwing: IrohError.docDeleteFailed(message))
+                        box.continuation.resume(throwing: IrohError.docDeleteFailed(message, operationId: currentOperationId()))
                     }
                 )
 
-                iroh_doc_del(handle.pointer, author.ffiSecret, keyBytes, callback)
+                if let author {
+                    iroh_doc_del(handle.pointer, author.ffiSecret, keyBytes, callback)
+                } else {
+                    iroh_doc_del_with_default_author(handle.pointer, keyBytes, callback)
+                }
             }
         }
     }
 
-    // MARK: - Content
+    /// Delete every key in a lexicographic range.
+    ///
+    /// Useful for pruning time-bucketed keys such as `events/2023/`.
+    /// Keys in the range that share a prefix are removed with a single tombstone;
+    /// keys outside the range are kept even when they extend a deleted key.
+    ///
+    /// - Parameters:
+    ///   - author: The author signing the deletion.
+    ///   - from: The first key in the range (inclusive).
+    ///   - to: The end of the range (exclusive), or `nil` for no upper bound.
+    /// - Returns: The number of entries deleted.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docDeleteFailed` if the operation fails.
+    public func delete(author: IrohAuthor, from start: String, to end: String?) async throws -> UInt64 {
+        try ensureNotClosed()
+        guard let startData = start.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        var endData: Data?
+        if let end {
+            guard let data = end.data(using: .utf8) else {
+                throw IrohError.stringEncodingFailed(.utf8)
+            }
+            endData = data
+        }
+        return try await delete(author: author, from: startData, to: endData)
+    }
 
-    /// Read content bytes by hash.
+    /// Delete every key in a lexicographic range using raw key bytes.
     ///
-    /// Entries only contain a hash of the content. Use this method to
-    /// retrieve the actual bytes.
+    /// - Parameters:
+    ///   - author: The author signing the deletion.
+    ///   - from: The first key in the range (inclusive).
+    ///   - to: The end of the range (exclusive), or `nil` for no upper bound.
+    /// - Returns: The number of entries deleted.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docDeleteFailed` if the operation fails.
+    public func delete(author: IrohAuthor, from start: Data, to end: Data?) async throws -> UInt64 {
+        try ensureNotClosed()
+        try ensureWritable()
+        try Task.checkCancellation()
+
+        let endData = end ?? Data()
+        return try await withCheckedThrowingContinuation { continuation in
+            start.withUnsafeBytes { startBuffer in
+                endData.withUnsafeBytes { endBuffer in
+                    let startBytes = IrohBytes(
+                        data: startBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(startBuffer.count)
+                    )
+                    let endBytes = IrohBytes(
+                        data: endBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(endBuffer.count)
+                    )
+
+                    let box = Unmanaged.passRetained(
+                        DeleteContinuationBox(continuation)
+                    ).toOpaque()
+
+                    let callback = IrohDocDelCallback(
+                        userdata: box,
+                        on_success: { userdata, deletedCount in
+                            let box = Unmanaged<DeleteContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            box.continuation.resume(returning: deletedCount)
+                        },
+                        on_failure: { userdata, errorPtr in
+                            let box = Unmanaged<DeleteContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            let message = String(cString: errorPtr!)
+                            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                            box.continuation.resume(throwing: IrohError.docDeleteFailed(message, operationId: currentOperationId()))
+                        }
+                    )
+
+                    iroh_doc_del_range(handle.pointer, author.ffiSecret, startBytes, endBytes, callback)
+                }
+            }
+        }
+    }
+
+    // MARK: - Counters
+
+    /// Add to a mergeable counter and return its new value.
     ///
-    /// - Parameter hash: The content hash as a hex string.
-    /// - Returns: The content data.
+    /// Each author keeps its own running total under the key and the
+    /// counter's value is the sum across authors, so increments made
+    /// concurrently on different devices are never lost. Use this for
+    /// unread counts and likes instead of `set(author:key:value:)`.
+    ///
+    /// - Parameters:
+    ///   - author: The author whose contribution is updated.
+    ///   - key: The counter key as a string (UTF-8 encoded).
+    ///   - amount: The amount to add (may be negative).
+    /// - Returns: The counter's value after the update.
     /// - Throws: `IrohError.docClosed` if the document is closed,
-    ///           `IrohError.contentReadFailed` if reading fails.
-    public func readContent(hash: String) async throws -> Data {
+    ///           `IrohError.docSetFailed` if the operation fails or the
+    ///           author's contribution synced from another device has not
+    ///           been downloaded yet.
+    @discardableResult
+    public func increment(author: IrohAuthor, key: String, by amount: Int64 = 1) async throws -> Int64 {
+        try ensureNotClosed()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await increment(author: author, key: keyData, by: amount)
+    }
+
+    /// Add to a mergeable counter using raw key bytes.
+    ///
+    /// - Parameters:
+    ///   - author: The author whose contribution is updated.
+    ///   - key: The counter key bytes.
+    ///   - amount: The amount to add (may be negative).
+    /// - Returns: The counter's value after the update.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    @discardableResult
+    public func increment(author: IrohAuthor, key: Data, by amount: Int64 = 1) async throws -> Int64 {
         try ensureNotClosed()
+        try ensureWritable()
         try Task.checkCancellation()
 
         return try await withCheckedThrowingContinuation { continuation in
-            hash.withCString { hashPtr in
+            key.withUnsafeBytes { keyBuffer in
+                let keyBytes = IrohBytes(
+                    data: keyBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                    len: UInt(keyBuffer.count)
+                )
+
                 let box = Unmanaged.passRetained(
-                    DataContinuationBox(continuation)
+                    CounterContinuationBox(continuation)
                 ).toOpaque()
 
-                let callback = IrohGetCallback(
+                let callback = IrohDocCounterCallback(
                     userdata: box,
-                    on_success: { userdata, ownedBytes in
-                        let box = Unmanaged<DataContinuationBox>
+                    on_success: { userdata, value in
+                        let box = Unmanaged<CounterContinuationBox>
                             .fromOpaque(userdata!)
                             .takeRetainedValue()
-                        let data = Data(bytes: ownedBytes.data, count: Int(ownedBytes.len))
-                        iroh_bytes_free(ownedBytes)
-                        box.continuation.resume(returning: data)
+                        box.continuation.resume(returning: value)
                     },
                     on_failure: { userdata, errorPtr in
-                        let box = Unmanaged<DataContinuationBox>
+                        let box = Unmanaged<CounterContinuationBox>
                             .fromOpaque(userdata!)
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        box.continuation.resume(throwing: IrohError.contentReadFailed(message))
+                        box.continuation.resume(throwing: IrohError.docSetFailed(message, operationId: currentOperationId()))
                     }
                 )
 
+                iroh_doc_counter_add(handle.pointer, author.ffiSecret, keyBytes, amount, callback)
+            }
+        }
+    }
+
+    /// Subtract from a mergeable counter and return its new value.
+    ///
+    /// - Parameters:
+    ///   - author: The author whose contribution is updated.
+    ///   - key: The counter key as a string (UTF-8 encoded).
+    ///   - amount: The amount to subtract.
+    /// - Returns: The counter's value after the update.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    @discardableResult
+    public func decrement(author: IrohAuthor, key: String, by amount: Int64 = 1) async throws -> Int64 {
+        try await increment(author: author, key: key, by: -amount)
+    }
+
+    /// Subtract from a mergeable counter using raw key bytes.
+    ///
+    /// - Parameters:
+    ///   - author: The author whose contribution is updated.
+    ///   - key: The counter key bytes.
+    ///   - amount: The amount to subtract.
+    /// - Returns: The counter's value after the update.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSetFailed` if the operation fails.
+    @discardableResult
+    public func decrement(author: IrohAuthor, key: Data, by amount: Int64 = 1) async throws -> Int64 {
+        try await increment(author: author, key: key, by: -amount)
+    }
+
+    /// Read the value of a mergeable counter.
+    ///
+    /// - Parameter key: The counter key as a string (UTF-8 encoded).
+    /// - Returns: The sum of all authors' contributions, or 0 if unset.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docGetFailed` if the operation fails.
+    public func counter(key: String) async throws -> Int64 {
+        try ensureNotClosed()
+        guard let keyData = key.data(using: .utf8) else {
+            throw IrohError.stringEncodingFailed(.utf8)
+        }
+        return try await counter(key: keyData)
+    }
+
+    /// Read the value of a mergeable counter using raw key bytes.
+    ///
+    /// - Parameter key: The counter key bytes.
+    /// - Returns: The sum of all authors' contributions, or 0 if unset.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docGetFailed` if the operation fails.
+    public func counter(key: Data) async throws -> Int64 {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            key.withUnsafeBytes { keyBuffer in
+                let keyBytes = IrohBytes(
+                    data: keyBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                    len: UInt(keyBuffer.count)
+                )
+
+                let box = Unmanaged.passRetained(
+                    CounterContinuationBox(continuation)
+                ).toOpaque()
+
+                let callback = IrohDocCounterCallback(
+                    userdata: box,
+                    on_success: { userdata, value in
+                        let box = Unmanaged<CounterContinuationBox>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        box.continuation.resume(returning: value)
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let box = Unmanaged<CounterContinuationBox>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        box.continuation.resume(throwing: IrohError.docGetFailed(message, operationId: currentOperationId()))
+                    }
+                )
+
+                iroh_doc_counter_get(handle.pointer, keyBytes, callback)
+            }
+        }
+    }
+
+    // MARK: - Content
+
+    /// Read content bytes by hash.
+    ///
+    /// Entries only contain a hash of the content. Use this method to
+    /// retrieve the actual bytes.
+    ///
+    /// - Parameter hash: The content hash as a hex string.
+    /// - Returns: The content data.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.contentReadFailed` if reading fails.
+    public func readContent(hash: String) async throws -> Data {
+        try await readContent { callback in
+            hash.withCString { hashPtr in
                 iroh_doc_read_content(nodeHandle.pointer, hashPtr, callback)
             }
         }
     }
 
+    /// Read content bytes by binary hash, as entries carry it.
+    func readContent(hash: IrohHash) async throws -> Data {
+        try await readContent { callback in
+            iroh_doc_read_content_by_hash(nodeHandle.pointer, hash, callback)
+        }
+    }
+
+    /// Read content bytes with `read`, which starts the FFI call.
+    private func readContent(_ read: (IrohGetCallback) -> Void) async throws -> Data {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                DataContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohGetCallback(
+                userdata: box,
+                on_success: { userdata, ownedBytes in
+                    let box = Unmanaged<DataContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let data = Data(bytes: ownedBytes.data, count: Int(ownedBytes.len))
+                    iroh_bytes_recycle(ownedBytes)
+                    box.continuation.resume(returning: data)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<DataContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.contentReadFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            read(callback)
+        }
+    }
+
     // MARK: - Sharing
 
     /// Get a share ticket for this document.
@@ -387,6 +1107,10 @@ public actor IrohDoc {
     ///           `IrohError.docShareFailed` if sharing fails.
     public func shareTicket(mode: DocShareMode = .read) async throws -> String {
         try ensureNotClosed()
+        if mode == .write {
+            // Handing out write access would bypass the read-only handle
+            try ensureWritable()
+        }
         try Task.checkCancellation()
 
         return try await withCheckedThrowingContinuation { continuation in
@@ -410,13 +1134,372 @@ public actor IrohDoc {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.docShareFailed(message))
+                    box.continuation.resume(throwing: IrohError.docShareFailed(message, operationId: currentOperationId()))
                 }
             )
 
             iroh_doc_share(handle.pointer, mode.ffiMode, callback)
         }
     }
+
+    /// Store a snapshot of this document as a blob and get its ticket.
+    ///
+    /// The snapshot holds every entry with its signature and the content
+    /// stored on this node, so a new device can bootstrap the document with
+    /// `IrohNode.restoreDoc(ticket:snapshot:)` in a single blob fetch instead
+    /// of syncing every entry. Anyone with the snapshot ticket can read the
+    /// document, so share it like a document ticket.
+    ///
+    /// - Returns: A blob ticket for the snapshot.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docSnapshotFailed` if the snapshot cannot be stored.
+    public func snapshotTicket() async throws -> String {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                StringContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCallback(
+                userdata: box,
+                on_success: { userdata, ticketPtr in
+                    let box = Unmanaged<StringContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let ticket = String(cString: ticketPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: ticketPtr))
+                    box.continuation.resume(returning: ticket)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<StringContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.docSnapshotFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_doc_snapshot_to_blob(handle.pointer, callback)
+        }
+    }
+
+    // MARK: - Swarm
+
+    /// Get statistics for the swarm syncing this document.
+    ///
+    /// Counting starts when the document is first created, joined or opened
+    /// on this node. Use it to monitor sync health, for example to start
+    /// syncing from a known server peer again when no neighbors remain.
+    ///
+    /// - Returns: Neighbor count, message counts and last activity.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docStatsFailed` if the operation fails.
+    public func swarmStats() async throws -> DocSwarmStats {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                SwarmStatsContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDocSwarmStatsCallback(
+                userdata: box,
+                on_success: { userdata, stats in
+                    let box = Unmanaged<SwarmStatsContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: DocSwarmStats(from: stats))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<SwarmStatsContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.docStatsFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_doc_swarm_stats(handle.pointer, callback)
+        }
+    }
+
+    /// Get how syncs with each peer of this document went, for sync health
+    /// displays.
+    ///
+    /// Peers are listed once they joined the document's swarm or synced,
+    /// counting from when the document was first created, joined or opened
+    /// on this node. `IrohNode.events()` delivers
+    /// `NodeEvent.peerSyncChanged` whenever a sync ends.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// for status in try await doc.peerSyncStatus() where status.failureStreak > 3 {
+    ///     print("\(status.peer) keeps failing: \(status.lastError ?? "")")
+    /// }
+    /// ```
+    ///
+    /// - Returns: The status of each peer, sorted by node ID.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docStatsFailed` if the operation fails.
+    public func peerSyncStatus() async throws -> [PeerSyncStatus] {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                PeerSyncStatusContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohPeerSyncStatsCallback(
+                userdata: box,
+                on_entry: { userdata, stats in
+                    let box = Unmanaged<PeerSyncStatusContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more peers coming
+                    box.peers.append(PeerSyncStatus(
+                        peer: String(cString: stats.peer_id!),
+                        lastSuccess: stats.last_success,
+                        pendingEntries: stats.pending_entries,
+                        failureStreak: stats.failure_streak,
+                        lastError: stats.last_error.map { String(cString: $0) }
+                    ))
+                },
+                on_complete: { userdata in
+                    let box = Unmanaged<PeerSyncStatusContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: box.peers)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<PeerSyncStatusContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.docStatsFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_doc_peer_sync_stats(handle.pointer, callback)
+        }
+    }
+
+    // MARK: - Export
+
+    /// Export the latest entries to a new SQLite file for offline queries.
+    ///
+    /// The file has an `entries` table with one row per author and key
+    /// (`key`, `author`, `content_hash`, `content_len`, `timestamp` in
+    /// microseconds, and `value`) and a `document` table with the namespace
+    /// and export time. Open it with GRDB or any other SQLite library.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let url = FileManager.default.temporaryDirectory.appendingPathComponent("notes.sqlite")
+    /// let count = try await doc.exportSQLite(to: url, maxValueSize: 4096)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - url: File URL to write; an existing file is replaced.
+    ///   - maxValueSize: Largest value in bytes to include in the `value`
+    ///     column; larger or missing content is left NULL. Pass 0 to export
+    ///     metadata only.
+    /// - Returns: The number of entries exported.
+    /// - Throws: `IrohError.docClosed` if the document is closed,
+    ///           `IrohError.docExportFailed` if the export fails.
+    @discardableResult
+    public func exportSQLite(to url: URL, maxValueSize: UInt64 = 0) async throws -> UInt64 {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ExportContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDocExportCallback(
+                userdata: box,
+                on_success: { userdata, count in
+                    let box = Unmanaged<ExportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: count)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ExportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.docExportFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            url.path.withCString { pathPtr in
+                iroh_doc_export_sqlite(handle.pointer, pathPtr, maxValueSize, callback)
+            }
+        }
+    }
+
+    /// Export entries matching a query as newline-delimited JSON.
+    ///
+    /// Entries are passed to `sink` in chunks of whole lines as they are
+    /// read, so exports of any size, such as a user's data download, never
+    /// sit in memory at once. Each line is a JSON object with `key`,
+    /// `author`, `content_hash`, `content_len`, `timestamp` in microseconds,
+    /// and `value`. Keys and values that are not valid UTF-8 are hex encoded
+    /// in `key_hex` and `value_hex` instead.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// FileManager.default.createFile(atPath: url.path, contents: nil)
+    /// let file = try FileHandle(forWritingTo: url)
+    /// defer { try? file.close() }
+    ///
+    /// try await doc.exportNDJSON(.latest(prefix: "users/\(userId)/"), maxValueSize: 1 << 20) { chunk in
+    ///     try file.write(contentsOf: chunk)
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - query: The entries to export. Default: every entry.
+    ///   - maxValueSize: Largest value in bytes to include; larger or missing
+    ///     content is left out. Pass 0 to export metadata only.
+    ///   - sink: Called with each chunk. Throw to abort the export.
+    /// - Returns: The number of entries exported.
+    /// - Throws: The error thrown by `sink`, `IrohError.docClosed` if the
+    ///           document is closed, or `IrohError.docExportFailed` if the
+    ///           export fails.
+    @discardableResult
+    public func exportNDJSON(
+        _ query: DocQuery = DocQuery(),
+        maxValueSize: UInt64 = 0,
+        into sink: @escaping SinkHandler
+    ) async throws -> UInt64 {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let context = NDJSONExportContext(continuation: continuation, sink: sink)
+            let box = Unmanaged.passRetained(context).toOpaque()
+
+            let writer = IrohSinkWriter(
+                userdata: box,
+                write: { userdata, bytes in
+                    let ctx = Unmanaged<NDJSONExportContext>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()
+                    let chunk = Data(bytes: bytes.data, count: Int(bytes.len))
+                    do {
+                        try ctx.sink(chunk)
+                        return true
+                    } catch {
+                        ctx.sinkError = error
+                        return false
+                    }
+                }
+            )
+
+            let callback = IrohDocExportCallback(
+                userdata: box,
+                on_success: { userdata, count in
+                    let ctx = Unmanaged<NDJSONExportContext>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    ctx.continuation.resume(returning: count)
+                },
+                on_failure: { userdata, errorPtr in
+                    let ctx = Unmanaged<NDJSONExportContext>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    if let sinkError = ctx.sinkError {
+                        ctx.continuation.resume(throwing: sinkError)
+                    } else {
+                        ctx.continuation.resume(throwing: IrohError.docExportFailed(message, operationId: currentOperationId()))
+                    }
+                }
+            )
+
+            query.keyPrefix.withUnsafeBytes { prefixBuffer in
+                let ffiQuery = IrohDocQuery(
+                    key_prefix: IrohBytes(
+                        data: prefixBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(prefixBuffer.count)
+                    ),
+                    timestamp_start: query.timestampStart ?? 0,
+                    timestamp_end: query.timestampEnd ?? 0,
+                    latest_only: query.latestOnly
+                )
+                iroh_doc_export_query(handle.pointer, ffiQuery, maxValueSize, writer, callback)
+            }
+        }
+    }
+}
+
+// MARK: - Entry Verification
+
+/// Verify a signed document entry received outside of sync.
+///
+/// Use this for entries another peer exported and passed along through an
+/// untrusted channel. The entry's author and namespace signatures are
+/// checked, so its fields can be trusted once this returns.
+///
+/// Example usage:
+/// ```swift
+/// let entry = try await verifyDocEntry(signedBytes, namespaceId: expectedNamespace)
+/// print("Written by \(entry.authorId)")
+/// ```
+///
+/// - Parameters:
+///   - signedEntry: A signed entry in the iroh-docs wire encoding.
+///   - namespaceId: The document the entry must belong to, or nil to accept any.
+/// - Returns: The verified entry.
+/// - Throws: `IrohError.entryVerificationFailed` if the entry is malformed,
+///           a signature is invalid, or it belongs to another document.
+public func verifyDocEntry(_ signedEntry: Data, namespaceId: String? = nil) async throws -> DocEntry {
+    try await withCheckedThrowingContinuation { continuation in
+        signedEntry.withUnsafeBytes { entryBuffer in
+            let entryBytes = IrohBytes(
+                data: entryBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                len: UInt(entryBuffer.count)
+            )
+
+            let box = Unmanaged.passRetained(
+                VerifiedEntryContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDocGetCallback(
+                userdata: box,
+                on_success: { userdata, entryPtr in
+                    let box = Unmanaged<VerifiedEntryContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let entry = DocEntry(from: entryPtr!.pointee)
+                    iroh_doc_entry_free(UnsafeMutablePointer(mutating: entryPtr))
+                    box.continuation.resume(returning: entry)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<VerifiedEntryContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.entryVerificationFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            withOptionalCString(namespaceId) { namespacePtr in
+                iroh_doc_entry_verify(entryBytes, namespacePtr, callback)
+            }
+        }
+    }
 }
 
 // MARK: - Handle Wrapper
@@ -452,6 +1535,31 @@ private final class DocEntryContinuationBox: @unchecked Sendable {
     }
 }
 
+private final class VerifiedEntryContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<DocEntry, Error>
+
+    init(_ continuation: CheckedContinuation<DocEntry, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class SwarmStatsContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<DocSwarmStats, Error>
+
+    init(_ continuation: CheckedContinuation<DocSwarmStats, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class PeerSyncStatusContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<[PeerSyncStatus], Error>
+    var peers: [PeerSyncStatus] = []
+
+    init(_ continuation: CheckedContinuation<[PeerSyncStatus], Error>) {
+        self.continuation = continuation
+    }
+}
+
 private final class DeleteContinuationBox: @unchecked Sendable {
     let continuation: CheckedContinuation<UInt64, Error>
 
@@ -460,6 +1568,38 @@ private final class DeleteContinuationBox: @unchecked Sendable {
     }
 }
 
+private final class ExportContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<UInt64, Error>
+
+    init(_ continuation: CheckedContinuation<UInt64, Error>) {
+        self.continuation = continuation
+    }
+}
+
+/// Context for NDJSON exports, holding the continuation, the sink, and any
+/// error the sink threw.
+private final class NDJSONExportContext: @unchecked Sendable {
+    let continuation: CheckedContinuation<UInt64, Error>
+    let sink: SinkHandler
+    var sinkError: Error?
+
+    init(
+        continuation: CheckedContinuation<UInt64, Error>,
+        sink: @escaping SinkHandler
+    ) {
+        self.continuation = continuation
+        self.sink = sink
+    }
+}
+
+private final class CounterContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<Int64, Error>
+
+    init(_ continuation: CheckedContinuation<Int64, Error>) {
+        self.continuation = continuation
+    }
+}
+
 private final class GetManyContext: @unchecked Sendable {
     let continuation: AsyncThrowingStream<DocEntry, Error>.Continuation
 
@@ -467,3 +1607,13 @@ private final class GetManyContext: @unchecked Sendable {
         self.continuation = continuation
     }
 }
+
+private final class GetBatchContext: @unchecked Sendable {
+    let continuation: CheckedContinuation<[DocEntry?], Error>
+    var entries: [DocEntry?]
+
+    init(_ continuation: CheckedContinuation<[DocEntry?], Error>, count: Int) {
+        self.continuation = continuation
+        self.entries = Array(repeating: nil, count: count)
+    }
+}
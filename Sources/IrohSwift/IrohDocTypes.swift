@@ -17,6 +17,64 @@ public enum DocShareMode: UInt8, Sendable {
     }
 }
 
+/// Access mode for opening or joining a document.
+public enum DocOpenMode: UInt8, Sendable {
+    /// Writes are allowed if the document capability permits them.
+    case readWrite = 0
+    /// Writes are rejected on this handle, even with a write capability.
+    ///
+    /// Use this to hand documents to plugin or other untrusted code.
+    case readOnly = 1
+
+    /// Convert to FFI type.
+    var ffiMode: IrohDocOpenMode {
+        switch self {
+        case .readWrite: return ReadWrite
+        case .readOnly: return ReadOnly
+        }
+    }
+}
+
+/// How a document write is stored before it is reported done.
+public enum DocDurability: Sendable, Equatable {
+    /// Committed together with other writes within half a second, so a
+    /// crash can lose the latest writes. Fastest for bulk imports.
+    case batched
+    /// Committed and synced to disk before the write returns. Use this for
+    /// records that must survive a crash, such as payments.
+    case immediate
+
+    /// Convert to FFI type.
+    var ffiDurability: IrohDurability {
+        switch self {
+        case .batched: return DurabilityBatched
+        case .immediate: return DurabilityImmediate
+        }
+    }
+}
+
+/// Information parsed from a document ticket before joining.
+public struct DocTicketInfo: Sendable, Equatable {
+    /// Whether the ticket string is a valid document ticket.
+    public let isValid: Bool
+
+    /// The document's namespace ID (64-character hex string).
+    /// Nil if the ticket is invalid.
+    public let namespaceId: String?
+
+    /// Access the ticket grants. `.write` lets whoever joins change the
+    /// document. Nil if the ticket is invalid.
+    public let capability: DocShareMode?
+
+    /// Number of peers the ticket names to sync with.
+    public let peerCount: Int
+
+    /// Whether joining with this ticket grants write access.
+    public var grantsWrite: Bool {
+        capability == .write
+    }
+}
+
 /// A document entry (key-value pair with metadata).
 ///
 /// Entries contain metadata about a key-value pair, including who wrote it,
@@ -30,7 +88,12 @@ public struct DocEntry: Sendable {
     public let key: Data
 
     /// The content hash as a hex string.
-    public let contentHash: String
+    public var contentHash: String {
+        hexHash(hash)
+    }
+
+    /// The content hash in binary form, as the FFI reports it.
+    let hash: IrohHash
 
     /// Size of the content in bytes.
     public let contentSize: UInt64
@@ -52,7 +115,7 @@ public struct DocEntry: Sendable {
     /// - Returns: The content data.
     /// - Throws: `IrohError.contentReadFailed` if reading fails.
     public func content(from doc: IrohDoc) async throws -> Data {
-        try await doc.readContent(hash: contentHash)
+        try await doc.readContent(hash: hash)
     }
 
     /// Create from FFI entry.
@@ -64,14 +127,272 @@ public struct DocEntry: Sendable {
         // Copy key bytes
         self.key = Data(bytes: ffiEntry.key.data, count: Int(ffiEntry.key.len))
 
-        // Copy content hash string
-        self.contentHash = String(cString: ffiEntry.content_hash)
+        self.hash = ffiEntry.hash
 
         self.contentSize = ffiEntry.content_size
         self.timestamp = ffiEntry.timestamp
     }
 }
 
+/// Filter for document entry queries.
+///
+/// Example usage:
+/// ```swift
+/// // Entries under "feed/" written since the last export
+/// let query = DocQuery(prefix: "feed/", newerThan: lastExported)
+/// for try await entry in try await doc.query(query) { ... }
+/// ```
+public struct DocQuery: Sendable {
+    /// Only keys starting with these bytes (empty for all keys).
+    public var keyPrefix: Data
+
+    /// Only entries written at or after this time (microseconds since epoch).
+    public var timestampStart: UInt64?
+
+    /// Only entries written before this time (microseconds since epoch).
+    public var timestampEnd: UInt64?
+
+    /// Only the winning entry per key: the latest across authors, ties
+    /// going to the lowest author ID. Keys whose winning entry is a deletion
+    /// are left out, and the timestamp range applies to the winning entry.
+    /// Use this for list views that show one value per key.
+    public var latestOnly: Bool
+
+    /// Create a query over a key prefix and timestamp range.
+    public init(
+        keyPrefix: Data = Data(),
+        timestampStart: UInt64? = nil,
+        timestampEnd: UInt64? = nil,
+        latestOnly: Bool = false
+    ) {
+        self.keyPrefix = keyPrefix
+        self.timestampStart = timestampStart
+        self.timestampEnd = timestampEnd
+        self.latestOnly = latestOnly
+    }
+
+    /// Create a query for the winning entry of each key under a prefix.
+    public static func latest(prefix: String = "") -> DocQuery {
+        DocQuery(keyPrefix: Data(prefix.utf8), latestOnly: true)
+    }
+
+    /// Create a query for entries under a prefix written after `timestamp`.
+    public init(prefix: String = "", newerThan timestamp: UInt64) {
+        self.init(keyPrefix: Data(prefix.utf8), timestampStart: timestamp + 1)
+    }
+}
+
+/// One author's latest value for a key whose authors disagree.
+public struct ConflictCandidate: Sendable {
+    /// The author ID who wrote this value (64-character hex string).
+    public let authorId: String
+
+    /// The content hash as a hex string.
+    public let contentHash: String
+
+    /// The value bytes.
+    public let value: Data
+
+    /// Timestamp when the value was written (microseconds since epoch).
+    public let timestamp: UInt64
+
+    /// Create from FFI candidate.
+    init(from ffiCandidate: IrohConflictCandidate) {
+        self.authorId = String(cString: ffiCandidate.author)
+        self.contentHash = String(cString: ffiCandidate.content_hash)
+        self.value = Data(bytes: ffiCandidate.content.data, count: Int(ffiCandidate.content.len))
+        self.timestamp = ffiCandidate.timestamp
+    }
+}
+
+/// What a write filter does with an entry synced from a peer.
+public enum WriteVerdict: Sendable {
+    /// Keep the entry.
+    case accept
+    /// Roll the entry back.
+    case reject
+    /// Roll the entry back and report it with its content for review.
+    case quarantine
+}
+
+/// An entry synced from a peer, as seen by a write filter.
+public struct FilteredEntry: Sendable {
+    /// The entry key.
+    public let key: Data
+
+    /// The author ID who wrote the entry (64-character hex string).
+    public let authorId: String
+
+    /// The content hash as a hex string.
+    public let contentHash: String
+
+    /// Content length in bytes.
+    public let contentLength: UInt64
+
+    /// Timestamp when the entry was written (microseconds since epoch).
+    public let timestamp: UInt64
+
+    /// Create from FFI entry.
+    init(from ffiEntry: IrohFilteredEntry) {
+        self.key = Data(bytes: ffiEntry.key.data, count: Int(ffiEntry.key.len))
+        self.authorId = String(cString: ffiEntry.author)
+        self.contentHash = String(cString: ffiEntry.content_hash)
+        self.contentLength = ffiEntry.content_len
+        self.timestamp = ffiEntry.timestamp
+    }
+}
+
+/// Where a write queued with `IrohDoc.enqueue(author:key:value:)` stands.
+public enum OutboxStatus: Sendable, Equatable {
+    /// Stored locally and waiting for a sync with a peer.
+    case queued
+    /// A peer synced the write.
+    case delivered
+    /// A peer synced the write, but a newer write by another author won.
+    case superseded(by: String)
+    /// The write can never be delivered, for example because the document
+    /// was deleted.
+    case failed(String)
+}
+
+/// A document write waiting for a peer (see `IrohNode.outboxItems()`).
+public struct OutboxItem: Sendable, Equatable {
+    /// The outbox item ID, as returned by `enqueue`.
+    public let id: UInt64
+
+    /// The namespace ID of the document.
+    public let namespaceId: String
+
+    /// The author ID who wrote the entry (64-character hex string).
+    public let authorId: String
+
+    /// The entry key.
+    public let key: Data
+
+    /// The content hash as a hex string.
+    public let contentHash: String
+
+    /// Timestamp when the entry was written (microseconds since epoch).
+    public let timestamp: UInt64
+
+    /// Create from FFI item.
+    init(from ffiItem: IrohOutboxItem) {
+        self.id = ffiItem.item_id
+        self.namespaceId = String(cString: ffiItem.namespace_id)
+        self.authorId = String(cString: ffiItem.author_id)
+        self.key = Data(bytes: ffiItem.key.data, count: Int(ffiItem.key.len))
+        self.contentHash = hexHash(ffiItem.content_hash)
+        self.timestamp = ffiItem.timestamp
+    }
+}
+
+/// An asset listed in a backup manifest (see `IrohDoc.manifestAssets()`).
+public struct ManifestAsset: Sendable, Equatable {
+    /// The app-defined asset ID, used as the entry key.
+    public let assetId: String
+
+    /// The content hash of the asset's blob.
+    public let hash: String
+
+    /// Content size in bytes.
+    public let size: UInt64
+
+    /// Filename, content type and creation date of the asset.
+    public let metadata: BlobMetadata
+
+    /// Create from FFI asset.
+    init(from ffiAsset: IrohManifestAsset) {
+        self.assetId = String(cString: ffiAsset.asset_id)
+        self.hash = hexHash(ffiAsset.hash)
+        self.size = ffiAsset.size
+        self.metadata = BlobMetadata(
+            filename: ffiAsset.filename.map { String(cString: $0) },
+            contentType: ffiAsset.content_type.map { String(cString: $0) },
+            createdAt: Date(timeIntervalSince1970: TimeInterval(ffiAsset.created_at))
+        )
+    }
+}
+
+/// Activity of the swarm syncing a document.
+public struct DocSwarmStats: Sendable, Equatable {
+    /// Peers currently connected for this document.
+    public let neighborCount: Int
+
+    /// Local writes announced to the swarm.
+    public let messagesSent: UInt64
+
+    /// Writes received from peers.
+    public let messagesReceived: UInt64
+
+    /// When the swarm was last active, or nil if it never was.
+    public let lastActivity: Date?
+
+    /// Create from FFI stats.
+    init(from ffiStats: IrohDocSwarmStats) {
+        self.neighborCount = Int(ffiStats.neighbors)
+        self.messagesSent = ffiStats.messages_sent
+        self.messagesReceived = ffiStats.messages_received
+        self.lastActivity = ffiStats.last_activity == 0
+            ? nil
+            : Date(timeIntervalSince1970: TimeInterval(ffiStats.last_activity) / 1_000_000)
+    }
+}
+
+/// How syncs of a document with one peer went.
+public struct PeerSyncStatus: Sendable, Equatable {
+    /// The peer's node ID.
+    public let peer: String
+
+    /// When the last successful sync finished, or nil if none did.
+    public let lastSuccess: Date?
+
+    /// Local writes since the last successful sync, which the peer may not
+    /// have yet.
+    public let pendingEntries: UInt64
+
+    /// Syncs that failed since the last successful one.
+    public let failureStreak: UInt64
+
+    /// Why the last sync failed, or nil if it succeeded.
+    public let lastError: String?
+
+    init(peer: String, lastSuccess: UInt64, pendingEntries: UInt64, failureStreak: UInt64, lastError: String?) {
+        self.peer = peer
+        self.lastSuccess = lastSuccess == 0
+            ? nil
+            : Date(timeIntervalSince1970: TimeInterval(lastSuccess) / 1_000_000)
+        self.pendingEntries = pendingEntries
+        self.failureStreak = failureStreak
+        self.lastError = lastError
+    }
+}
+
+/// A durable peer of a document and how far it has its local writes.
+public struct DurablePeer: Sendable, Equatable {
+    /// The peer's node ID.
+    public let nodeId: String
+
+    /// Time before which the peer confirmed every local write
+    /// (microseconds since epoch, 0 if it never synced).
+    public let confirmedThrough: UInt64
+}
+
+/// How far a document's local writes are backed up (see `IrohDoc.durableStatus()`).
+public struct DurableStatus: Sendable, Equatable {
+    /// The durable peers, sorted by node ID.
+    public let peers: [DurablePeer]
+
+    /// Time before which every durable peer confirmed every local write
+    /// (microseconds since epoch, 0 if one never synced or there are none).
+    public let confirmedThrough: UInt64
+
+    /// Time of the latest local write (microseconds since epoch, 0 if none).
+    public let lastLocalWrite: UInt64
+
+    /// Whether there are durable peers and all of them have every local write.
+    public let isBackedUp: Bool
+}
+
 /// Events from document subscriptions.
 public enum DocEvent: Sendable {
     /// A local entry was inserted.
@@ -95,6 +416,13 @@ public enum DocEvent: Sendable {
     /// Sync finished with a peer.
     case syncFinished(peerId: String)
 
+    /// An entry written while no subscription was active, replayed from a cursor.
+    case replayed(DocEntry)
+
+    /// The content of a remote entry is available locally. Only delivered
+    /// by subscriptions that fetch content.
+    case contentFetched(from: String, entry: DocEntry, content: Data)
+
     /// Create from FFI event.
     static func from(_ ffiEvent: IrohDocEvent) -> DocEvent {
         switch ffiEvent.event_type {
@@ -126,8 +454,81 @@ public enum DocEvent: Sendable {
             let peerId = String(cString: ffiEvent.peer_id!)
             return .syncFinished(peerId: peerId)
 
+        case Replayed:
+            let entry = DocEntry(from: ffiEvent.entry!.pointee)
+            return .replayed(entry)
+
+        case ContentFetched:
+            let entry = DocEntry(from: ffiEvent.entry!.pointee)
+            let peerId = String(cString: ffiEvent.peer_id!)
+            let content = ffiEvent.content.data.map {
+                Data(bytes: $0, count: Int(ffiEvent.content.len))
+            } ?? Data()
+            return .contentFetched(from: peerId, entry: entry, content: content)
+
         default:
             fatalError("Unknown document event type: \(ffiEvent.event_type)")
         }
     }
 }
+
+/// A deletion from a deletions-only subscription.
+///
+/// Deletes are prefix deletes: the author's entries under longer keys
+/// starting with `key` are deleted too.
+public struct DocDeletion: Sendable {
+    /// The deleted key, or prefix of deleted keys.
+    public let key: Data
+
+    /// The author ID who deleted the key (64-character hex string).
+    public let authorId: String
+
+    /// The peer the deletion was received from, or nil if it was written
+    /// locally or replayed from a cursor.
+    public let peerId: String?
+
+    /// Timestamp of the deletion (microseconds since epoch).
+    public let timestamp: UInt64
+
+    /// The key as a UTF-8 string, if valid.
+    public var keyString: String? {
+        String(data: key, encoding: .utf8)
+    }
+
+    /// Create from an FFI EntryDeleted event.
+    init(from ffiEvent: IrohDocEvent) {
+        let entry = DocEntry(from: ffiEvent.entry!.pointee)
+        self.key = entry.key
+        self.authorId = entry.authorId
+        self.peerId = ffiEvent.peer_id.map { String(cString: $0) }
+        self.timestamp = entry.timestamp
+    }
+}
+
+/// Position in a document's event history.
+///
+/// Persist the cursor of the last handled event and pass it to
+/// `subscribe(from:)` after a relaunch to replay what was missed.
+public struct DocCursor: RawRepresentable, Hashable, Codable, Sendable {
+    /// Opaque encoded form, suitable for storage.
+    public let rawValue: String
+
+    public init(rawValue: String) {
+        self.rawValue = rawValue
+    }
+}
+
+/// A document event with its document and the cursor to resume after it.
+///
+/// Streams from several documents can be merged into one pipeline and
+/// routed by `namespaceId`.
+public struct CursoredDocEvent: Sendable {
+    /// The event.
+    public let event: DocEvent
+
+    /// Namespace ID of the document the event belongs to.
+    public let namespaceId: String
+
+    /// Cursor positioned after this event.
+    public let cursor: DocCursor
+}
@@ -1,131 +1,346 @@
 import Foundation
 
 /// Errors that can occur when using IrohNode.
+///
+/// Failures reported by the Rust library carry the id of the failed
+/// operation in `operationId`.
 public enum IrohError: Error, Sendable {
     /// Failed to create the Iroh node.
-    case nodeCreationFailed(String)
+    case nodeCreationFailed(String, operationId: UInt64? = nil)
+    /// A store database is corrupt and its recovery mode is to fail.
+    /// Holds the database, the path of its file and what is wrong with it.
+    case storeCorrupted(database: StoreDatabase, path: String, reason: String)
     /// Failed to add bytes to the blob store.
-    case putFailed(String)
+    case putFailed(String, operationId: UInt64? = nil)
     /// Failed to download bytes from a ticket.
-    case getFailed(String)
+    case getFailed(String, operationId: UInt64? = nil)
     /// Invalid ticket format.
-    case invalidTicket(String)
+    case invalidTicket(String, operationId: UInt64? = nil)
     /// Failed to encode string with the specified encoding.
     case stringEncodingFailed(String.Encoding)
     /// Failed to decode data as string with the specified encoding.
     case stringDecodingFailed(String.Encoding)
     /// Failed to encode value to JSON.
-    case encodingFailed(String)
+    case encodingFailed(String, operationId: UInt64? = nil)
     /// Failed to decode JSON to value.
-    case decodingFailed(String)
+    case decodingFailed(String, operationId: UInt64? = nil)
     /// Operation failed after maximum retry attempts.
     case maxRetriesExceeded(attempts: Int, lastError: any Error)
     /// Invalid configuration.
-    case invalidConfiguration(String)
+    case invalidConfiguration(String, operationId: UInt64? = nil)
     /// Operation timed out.
     case timeout
     /// Node has been closed.
     case nodeClosed
     /// Failed to close the node.
-    case closeFailed(String)
+    case closeFailed(String, operationId: UInt64? = nil)
     // MARK: - Docs Errors
     /// Docs is not enabled on this node.
     case docsNotEnabled
     /// Failed to create a document.
-    case docCreationFailed(String)
+    case docCreationFailed(String, operationId: UInt64? = nil)
     /// Failed to join a document.
-    case docJoinFailed(String)
+    case docJoinFailed(String, operationId: UInt64? = nil)
+    /// Failed to open a document stored on this node.
+    case docOpenFailed(String, operationId: UInt64? = nil)
     /// Document has been closed.
     case docClosed
+    /// Document handle was opened read-only.
+    case docReadOnly
     /// Failed to get entry from document.
-    case docGetFailed(String)
+    case docGetFailed(String, operationId: UInt64? = nil)
     /// Failed to set entry in document.
-    case docSetFailed(String)
+    case docSetFailed(String, operationId: UInt64? = nil)
+    /// A compare-and-set write found a different current entry.
+    /// Holds the current content hash, or nil if the key is absent.
+    case docConflict(currentHash: String?)
     /// Failed to delete entry from document.
-    case docDeleteFailed(String)
+    case docDeleteFailed(String, operationId: UInt64? = nil)
     /// Failed to share document.
-    case docShareFailed(String)
+    case docShareFailed(String, operationId: UInt64? = nil)
+    /// Failed to store a document snapshot.
+    case docSnapshotFailed(String, operationId: UInt64? = nil)
     /// Failed to read content from store.
-    case contentReadFailed(String)
+    case contentReadFailed(String, operationId: UInt64? = nil)
     /// Failed to subscribe to document events.
-    case docSubscribeFailed(String)
+    case docSubscribeFailed(String, operationId: UInt64? = nil)
+    /// Failed to read document swarm statistics.
+    case docStatsFailed(String, operationId: UInt64? = nil)
+    /// Failed to export a document to a SQLite file.
+    case docExportFailed(String, operationId: UInt64? = nil)
+    /// A signed entry failed verification.
+    case entryVerificationFailed(String, operationId: UInt64? = nil)
     // MARK: - Author Errors
     /// Failed to create author.
-    case authorCreationFailed(String)
+    case authorCreationFailed(String, operationId: UInt64? = nil)
     /// Failed to import author into docs engine.
-    case authorImportFailed(String)
+    case authorImportFailed(String, operationId: UInt64? = nil)
+    /// Failed to set or get the node's default author.
+    case defaultAuthorFailed(String, operationId: UInt64? = nil)
     /// Keychain operation failed.
-    case keychainError(String)
+    case keychainError(String, operationId: UInt64? = nil)
     // MARK: - Blob Errors
     /// Failed to tag (pin) a blob.
-    case blobTagFailed(String)
+    case blobTagFailed(String, operationId: UInt64? = nil)
     /// Failed to remove a tag (unpin) from a blob.
-    case blobUntagFailed(String)
+    case blobUntagFailed(String, operationId: UInt64? = nil)
     /// Failed to create a ticket for a blob.
-    case ticketCreationFailed(String)
+    case ticketCreationFailed(String, operationId: UInt64? = nil)
+    /// Failed to compute a reference report for a tag or collection.
+    case referenceReportFailed(String, operationId: UInt64? = nil)
+    /// Failed to export a blob to a file.
+    case exportFailed(String, operationId: UInt64? = nil)
+    /// Failed to list blobs in the store.
+    case blobListFailed(String, operationId: UInt64? = nil)
+    /// Failed to list tags.
+    case tagListFailed(String, operationId: UInt64? = nil)
+    /// Failed to copy blobs into another store.
+    case storeCopyFailed(String, operationId: UInt64? = nil)
+    /// Failed to inspect or reclaim a profile's content.
+    case profileFailed(String, operationId: UInt64? = nil)
+    // MARK: - Background Errors
+    /// Failed to inspect pending work.
+    case pendingWorkFailed(String, operationId: UInt64? = nil)
+    /// Failed to run a background sync step.
+    case syncStepFailed(String, operationId: UInt64? = nil)
+    /// Failed to close a peer's connections.
+    case connectionCloseFailed(String, operationId: UInt64? = nil)
+    /// Failed to read per-peer traffic.
+    case peerTrafficFailed(String, operationId: UInt64? = nil)
+    /// Failed to change or read the blob access list.
+    case blobAccessFailed(String, operationId: UInt64? = nil)
+    /// Failed to watch the node's direct addresses.
+    case addressWatchFailed(String, operationId: UInt64? = nil)
+    /// Failed to report relay latencies.
+    case relayReportFailed(String, operationId: UInt64? = nil)
+    /// Failed to pin or unpin the home relay.
+    case homeRelayFailed(String, operationId: UInt64? = nil)
+    /// Failed to check whether a peer is reachable.
+    case reachabilityCheckFailed(String, operationId: UInt64? = nil)
+    /// Failed to probe providers for a blob.
+    case providerProbeFailed(String, operationId: UInt64? = nil)
+    /// Failed to check the node's health.
+    case healthCheckFailed(String, operationId: UInt64? = nil)
+    /// Failed to report the node's memory use.
+    case memoryReportFailed(String, operationId: UInt64? = nil)
+    /// Failed to subscribe to node events.
+    case eventSubscriptionFailed(String, operationId: UInt64? = nil)
+    /// Failed to set up a document write filter.
+    case writeFilterFailed(String, operationId: UInt64? = nil)
+    /// Failed to queue a write in the outbox or read the outbox.
+    case outboxFailed(String, operationId: UInt64? = nil)
+    /// Failed to update or read a backup manifest.
+    case manifestFailed(String, operationId: UInt64? = nil)
+    /// Failed to set or read a document's durable peers.
+    case durablePeersFailed(String, operationId: UInt64? = nil)
+    /// Failed to set a document's durability or flush its writes.
+    case docDurabilityFailed(String, operationId: UInt64? = nil)
+    /// Failed to send or subscribe to direct messages.
+    case messageFailed(String, operationId: UInt64? = nil)
+    /// Failed to inject faults into the node.
+    case faultInjectionFailed(String, operationId: UInt64? = nil)
 }
 
 extension IrohError: LocalizedError {
     public var errorDescription: String? {
         switch self {
-        case .nodeCreationFailed(let msg):
+        case .nodeCreationFailed(let msg, _):
             return "Failed to create Iroh node: \(msg)"
-        case .putFailed(let msg):
+        case .storeCorrupted(let database, let path, let reason):
+            return "The \(database) database at \(path) is corrupt: \(reason)"
+        case .putFailed(let msg, _):
             return "Failed to put bytes: \(msg)"
-        case .getFailed(let msg):
+        case .getFailed(let msg, _):
             return "Failed to get bytes: \(msg)"
-        case .invalidTicket(let msg):
+        case .invalidTicket(let msg, _):
             return "Invalid ticket: \(msg)"
         case .stringEncodingFailed(let encoding):
             return "Failed to encode string using \(encoding)"
         case .stringDecodingFailed(let encoding):
             return "Failed to decode data as string using \(encoding)"
-        case .encodingFailed(let msg):
+        case .encodingFailed(let msg, _):
             return "Failed to encode value: \(msg)"
-        case .decodingFailed(let msg):
+        case .decodingFailed(let msg, _):
             return "Failed to decode value: \(msg)"
         case .maxRetriesExceeded(let attempts, let lastError):
             return "Operation failed after \(attempts) attempts: \(lastError.localizedDescription)"
-        case .invalidConfiguration(let msg):
+        case .invalidConfiguration(let msg, _):
             return "Invalid configuration: \(msg)"
         case .timeout:
             return "Operation timed out"
         case .nodeClosed:
             return "Node has been closed"
-        case .closeFailed(let msg):
+        case .closeFailed(let msg, _):
             return "Failed to close node: \(msg)"
         case .docsNotEnabled:
             return "Docs is not enabled on this node"
-        case .docCreationFailed(let msg):
+        case .docCreationFailed(let msg, _):
             return "Failed to create document: \(msg)"
-        case .docJoinFailed(let msg):
+        case .docJoinFailed(let msg, _):
             return "Failed to join document: \(msg)"
+        case .docOpenFailed(let msg, _):
+            return "Failed to open document: \(msg)"
         case .docClosed:
             return "Document has been closed"
-        case .docGetFailed(let msg):
+        case .docReadOnly:
+            return "Document handle is read-only"
+        case .docGetFailed(let msg, _):
             return "Failed to get entry: \(msg)"
-        case .docSetFailed(let msg):
+        case .docSetFailed(let msg, _):
             return "Failed to set entry: \(msg)"
-        case .docDeleteFailed(let msg):
+        case .docConflict(let currentHash):
+            return "Entry was changed concurrently (current hash: \(currentHash ?? "none"))"
+        case .docDeleteFailed(let msg, _):
             return "Failed to delete entry: \(msg)"
-        case .docShareFailed(let msg):
+        case .docShareFailed(let msg, _):
             return "Failed to share document: \(msg)"
-        case .contentReadFailed(let msg):
+        case .docSnapshotFailed(let msg, _):
+            return "Failed to snapshot document: \(msg)"
+        case .contentReadFailed(let msg, _):
             return "Failed to read content: \(msg)"
-        case .docSubscribeFailed(let msg):
+        case .docSubscribeFailed(let msg, _):
             return "Failed to subscribe to document: \(msg)"
-        case .authorCreationFailed(let msg):
+        case .docStatsFailed(let msg, _):
+            return "Failed to read swarm statistics: \(msg)"
+        case .docExportFailed(let msg, _):
+            return "Failed to export document: \(msg)"
+        case .entryVerificationFailed(let msg, _):
+            return "Entry verification failed: \(msg)"
+        case .authorCreationFailed(let msg, _):
             return "Failed to create author: \(msg)"
-        case .authorImportFailed(let msg):
+        case .authorImportFailed(let msg, _):
             return "Failed to import author: \(msg)"
-        case .keychainError(let msg):
+        case .defaultAuthorFailed(let msg, _):
+            return "Default author operation failed: \(msg)"
+        case .keychainError(let msg, _):
             return "Keychain error: \(msg)"
-        case .blobTagFailed(let msg):
+        case .blobTagFailed(let msg, _):
             return "Failed to tag blob: \(msg)"
-        case .blobUntagFailed(let msg):
+        case .blobUntagFailed(let msg, _):
             return "Failed to untag blob: \(msg)"
-        case .ticketCreationFailed(let msg):
+        case .ticketCreationFailed(let msg, _):
             return "Failed to create ticket: \(msg)"
+        case .referenceReportFailed(let msg, _):
+            return "Failed to compute reference report: \(msg)"
+        case .exportFailed(let msg, _):
+            return "Failed to export blob: \(msg)"
+        case .blobListFailed(let msg, _):
+            return "Failed to list blobs: \(msg)"
+        case .tagListFailed(let msg, _):
+            return "Failed to list tags: \(msg)"
+        case .storeCopyFailed(let msg, _):
+            return "Failed to copy blobs: \(msg)"
+        case .profileFailed(let msg, _):
+            return "Profile operation failed: \(msg)"
+        case .pendingWorkFailed(let msg, _):
+            return "Failed to inspect pending work: \(msg)"
+        case .connectionCloseFailed(let msg, _):
+            return "Failed to close connections: \(msg)"
+        case .peerTrafficFailed(let msg, _):
+            return "Failed to read peer traffic: \(msg)"
+        case .blobAccessFailed(let msg, _):
+            return "Failed to update blob access: \(msg)"
+        case .addressWatchFailed(let msg, _):
+            return "Failed to watch direct addresses: \(msg)"
+        case .relayReportFailed(let msg, _):
+            return "Failed to report relay latencies: \(msg)"
+        case .homeRelayFailed(let msg, _):
+            return "Failed to set home relay: \(msg)"
+        case .providerProbeFailed(let msg, _):
+            return "Failed to probe providers: \(msg)"
+        case .reachabilityCheckFailed(let msg, _):
+            return "Failed to check reachability: \(msg)"
+        case .healthCheckFailed(let msg, _):
+            return "Failed to check node health: \(msg)"
+        case .memoryReportFailed(let msg, _):
+            return "Failed to report memory use: \(msg)"
+        case .eventSubscriptionFailed(let msg, _):
+            return "Failed to subscribe to node events: \(msg)"
+        case .writeFilterFailed(let msg, _):
+            return "Failed to set write filter: \(msg)"
+        case .outboxFailed(let msg, _):
+            return "Failed to use outbox: \(msg)"
+        case .manifestFailed(let msg, _):
+            return "Failed to use backup manifest: \(msg)"
+        case .durablePeersFailed(let msg, _):
+            return "Failed to use durable peers: \(msg)"
+        case .docDurabilityFailed(let msg, _):
+            return "Failed to make document writes durable: \(msg)"
+        case .messageFailed(let msg, _):
+            return "Failed to use direct messages: \(msg)"
+        case .faultInjectionFailed(let msg, _):
+            return "Failed to inject faults: \(msg)"
+        case .syncStepFailed(let msg, _):
+            return "Failed to run sync step: \(msg)"
+        }
+    }
+}
+
+extension IrohError {
+    /// Id of the failed operation, if the error came from the Rust library.
+    ///
+    /// The same id is carried by the download events the operation caused
+    /// and by the library's log spans, so include it in support logs to find
+    /// the matching internal activity.
+    public var operationId: UInt64? {
+        switch self {
+        case .nodeCreationFailed(_, let operationId),
+             .putFailed(_, let operationId),
+             .getFailed(_, let operationId),
+             .invalidTicket(_, let operationId),
+             .encodingFailed(_, let operationId),
+             .decodingFailed(_, let operationId),
+             .invalidConfiguration(_, let operationId),
+             .closeFailed(_, let operationId),
+             .docCreationFailed(_, let operationId),
+             .docJoinFailed(_, let operationId),
+             .docOpenFailed(_, let operationId),
+             .docGetFailed(_, let operationId),
+             .docSetFailed(_, let operationId),
+             .docDeleteFailed(_, let operationId),
+             .docShareFailed(_, let operationId),
+             .docSnapshotFailed(_, let operationId),
+             .contentReadFailed(_, let operationId),
+             .docSubscribeFailed(_, let operationId),
+             .docStatsFailed(_, let operationId),
+             .docExportFailed(_, let operationId),
+             .entryVerificationFailed(_, let operationId),
+             .authorCreationFailed(_, let operationId),
+             .authorImportFailed(_, let operationId),
+             .defaultAuthorFailed(_, let operationId),
+             .keychainError(_, let operationId),
+             .blobTagFailed(_, let operationId),
+             .blobUntagFailed(_, let operationId),
+             .ticketCreationFailed(_, let operationId),
+             .referenceReportFailed(_, let operationId),
+             .exportFailed(_, let operationId),
+             .blobListFailed(_, let operationId),
+             .tagListFailed(_, let operationId),
+             .storeCopyFailed(_, let operationId),
+             .profileFailed(_, let operationId),
+             .pendingWorkFailed(_, let operationId),
+             .syncStepFailed(_, let operationId),
+             .connectionCloseFailed(_, let operationId),
+             .peerTrafficFailed(_, let operationId),
+             .blobAccessFailed(_, let operationId),
+             .addressWatchFailed(_, let operationId),
+             .relayReportFailed(_, let operationId),
+             .homeRelayFailed(_, let operationId),
+             .reachabilityCheckFailed(_, let operationId),
+             .providerProbeFailed(_, let operationId),
+             .healthCheckFailed(_, let operationId),
+             .memoryReportFailed(_, let operationId),
+             .eventSubscriptionFailed(_, let operationId),
+             .writeFilterFailed(_, let operationId),
+             .outboxFailed(_, let operationId),
+             .manifestFailed(_, let operationId),
+             .durablePeersFailed(_, let operationId),
+             .docDurabilityFailed(_, let operationId),
+             .messageFailed(_, let operationId),
+             .faultInjectionFailed(_, let operationId):
+            return operationId
+        default:
+            return nil
         }
     }
 }
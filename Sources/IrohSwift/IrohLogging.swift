@@ -1,4 +1,5 @@
 import Foundation
+import IrohSwiftFFI
 import os
 
 /// Logger for Iroh operations.
@@ -9,10 +10,52 @@ enum IrohLogger {
     static let node = Logger(subsystem: "org.arkavo.iroh", category: "node")
 }
 
+/// Forward the Rust library's log messages to `handler`.
+///
+/// Once a handler is set, a panic in the library, including one in a
+/// background task that would otherwise vanish, is passed to the handler
+/// with its location and backtrace before it is contained or the process
+/// aborts. The handler runs on the panicking thread, so it should record
+/// the message without blocking. Setting a handler replaces the previous
+/// one for the whole process.
+///
+/// Example usage:
+/// ```swift
+/// setLogHandler { message in
+///     crashReporter.log(message)
+/// }
+/// ```
+///
+/// - Parameter handler: Called with each message, on any thread.
+public func setLogHandler(_ handler: @escaping @Sendable (String) -> Void) {
+    let box = Unmanaged.passRetained(LogHandlerBox(handler)).toOpaque()
+    let callback = IrohLogCallback(
+        userdata: box,
+        on_log: { userdata, message in
+            let box = Unmanaged<LogHandlerBox>.fromOpaque(userdata!).takeUnretainedValue()
+            box.handler(String(cString: message!))
+        },
+        release: { userdata in
+            Unmanaged<LogHandlerBox>.fromOpaque(userdata!).release()
+        }
+    )
+    iroh_log_init(callback)
+}
+
+/// Keeps a log handler alive while the library may call it.
+private final class LogHandlerBox: @unchecked Sendable {
+    let handler: @Sendable (String) -> Void
+
+    init(_ handler: @escaping @Sendable (String) -> Void) {
+        self.handler = handler
+    }
+}
+
 extension IrohNode {
     /// Store data with logging.
     ///
-    /// Logs the operation start, success with ticket prefix, or failure.
+    /// Logs the operation start, success with ticket prefix, or failure
+    /// with the failed operation's id.
     ///
     /// - Parameter data: The data to store.
     /// - Returns: A ticket string for retrieving the data.
@@ -29,14 +72,16 @@ extension IrohNode {
             IrohLogger.node.info("put: success, ticket=\(ticketPrefix, privacy: .public)...")
             return ticket
         } catch {
-            IrohLogger.node.error("put: failed, error=\(error.localizedDescription, privacy: .public)")
+            let operationId = (error as? IrohError)?.operationId ?? 0
+            IrohLogger.node.error("put: failed, operation=\(operationId), error=\(error.localizedDescription, privacy: .public)")
             throw error
         }
     }
 
     /// Retrieve data with logging.
     ///
-    /// Logs the operation start, success with data size, or failure.
+    /// Logs the operation start, success with data size, or failure
+    /// with the failed operation's id.
     ///
     /// - Parameter ticket: The ticket string from a `put` operation.
     /// - Returns: The downloaded data.
@@ -52,7 +97,8 @@ extension IrohNode {
             IrohLogger.node.info("get: success, size=\(data.count) bytes")
             return data
         } catch {
-            IrohLogger.node.error("get: failed, error=\(error.localizedDescription, privacy: .public)")
+            let operationId = (error as? IrohError)?.operationId ?? 0
+            IrohLogger.node.error("get: failed, operation=\(operationId), error=\(error.localizedDescription, privacy: .public)")
             throw error
         }
     }
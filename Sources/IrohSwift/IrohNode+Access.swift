@@ -0,0 +1,190 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohNode {
+    // MARK: - Blob Access Control
+
+    /// Change who may fetch blobs from this node when a peer has no rule.
+    ///
+    /// Applies to new connections; call `closeConnections(to:)` to stop
+    /// transfers already in progress.
+    ///
+    /// - Parameter mode: `.open` serves every peer that is not denied,
+    ///                   `.allowList` only peers that are allowed.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.blobAccessFailed` if the change fails.
+    public func setBlobAccessMode(_ mode: BlobAccessMode) async throws {
+        try ensureNotClosed()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                BlobAccessContinuationBox(continuation)
+            ).toOpaque()
+
+            iroh_blob_access_set_mode(handle.pointer, mode.ffiMode, blobAccessCallback(box))
+        }
+    }
+
+    /// Pause or resume serving blobs to peers.
+    ///
+    /// While paused, blob connections from peers are closed, including
+    /// transfers in progress, and new ones are turned away. Reading local
+    /// blobs and downloading from other nodes keep working. Not persisted;
+    /// a new node serves blobs.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// try await node.setProviderEnabled(false)  // on a metered network
+    /// ```
+    ///
+    /// - Parameter enabled: Whether to serve blobs to peers.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func setProviderEnabled(_ enabled: Bool) async throws {
+        try ensureNotClosed()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                BlobAccessContinuationBox(continuation)
+            ).toOpaque()
+
+            iroh_node_set_provider_enabled(handle.pointer, enabled, blobAccessCallback(box))
+        }
+    }
+
+    /// Whether blobs are served to peers. False once the node is closed.
+    public var isProviderEnabled: Bool {
+        iroh_node_provider_enabled(handle.pointer)
+    }
+
+    /// Allow or deny a peer fetching blobs from this node, or clear its rule.
+    ///
+    /// Rules are kept in memory only; restore them after creating the node,
+    /// ideally with `IrohConfig.blobAccess` set to `.allowList` so nothing
+    /// is served in between. Denying a peer also closes its open
+    /// connections. Refused attempts are reported as `.fetchRejected` events.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// try await node.setBlobAccess(.allow, for: friendNodeId)
+    /// try await node.setBlobAccess(.deny, for: blockedNodeId)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - rule: `.allow`, `.deny`, or nil to let the access mode decide.
+    ///   - nodeId: The peer's node ID.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.blobAccessFailed` if the node ID is invalid.
+    public func setBlobAccess(_ rule: BlobAccessRule?, for nodeId: String) async throws {
+        try ensureNotClosed()
+
+        let ffiRule: IrohBlobAccessRule
+        switch rule {
+        case .allow:
+            ffiRule = BlobAccessAllow
+        case .deny:
+            ffiRule = BlobAccessDeny
+        case nil:
+            ffiRule = BlobAccessDefault
+        }
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            nodeId.withCString { nodeIdPtr in
+                let box = Unmanaged.passRetained(
+                    BlobAccessContinuationBox(continuation)
+                ).toOpaque()
+
+                iroh_blob_access_set(handle.pointer, nodeIdPtr, ffiRule, blobAccessCallback(box))
+            }
+        }
+    }
+
+    /// The blob access mode and every peer rule.
+    ///
+    /// - Returns: The current access list.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func blobAccess() async throws -> BlobAccessList {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                BlobAccessListContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohBlobAccessCallback(
+                userdata: box,
+                on_entry: { userdata, entry in
+                    let box = Unmanaged<BlobAccessListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more entries coming
+                    let nodeId = String(cString: entry.node_id!)
+                    if entry.rule == BlobAccessDeny {
+                        box.denied.append(nodeId)
+                    } else {
+                        box.allowed.append(nodeId)
+                    }
+                },
+                on_complete: { userdata, mode in
+                    let box = Unmanaged<BlobAccessListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: BlobAccessList(
+                        mode: mode == BlobAccessAllowList ? .allowList : .open,
+                        allowed: box.allowed,
+                        denied: box.denied
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<BlobAccessListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.blobAccessFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_blob_access_list(handle.pointer, callback)
+        }
+    }
+}
+
+/// Build the callback shared by access mode and rule changes.
+private func blobAccessCallback(_ box: UnsafeMutableRawPointer) -> IrohCloseCallback {
+    IrohCloseCallback(
+        userdata: box,
+        on_complete: { userdata in
+            let box = Unmanaged<BlobAccessContinuationBox>
+                .fromOpaque(userdata!)
+                .takeRetainedValue()
+            box.continuation.resume()
+        },
+        on_failure: { userdata, errorPtr in
+            let box = Unmanaged<BlobAccessContinuationBox>
+                .fromOpaque(userdata!)
+                .takeRetainedValue()
+            let message = String(cString: errorPtr!)
+            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+            box.continuation.resume(throwing: IrohError.blobAccessFailed(message, operationId: currentOperationId()))
+        }
+    )
+}
+
+// MARK: - Internal Helpers
+
+private final class BlobAccessContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<Void, Error>
+
+    init(_ continuation: CheckedContinuation<Void, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class BlobAccessListContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<BlobAccessList, Error>
+    var allowed: [String] = []
+    var denied: [String] = []
+
+    init(_ continuation: CheckedContinuation<BlobAccessList, Error>) {
+        self.continuation = continuation
+    }
+}
@@ -0,0 +1,206 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohNode {
+    // MARK: - Background Operations
+
+    /// Report the pending work recorded in a store, without starting a node.
+    ///
+    /// Running nodes keep a record next to their store of document writes
+    /// no peer has synced and downloads that have not finished. This reads
+    /// only that record, so a silent push notification handler can decide
+    /// whether starting a node is worth it. The record is updated a moment
+    /// after changes, so a node that was killed may have lost its latest ones.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let pending = try await IrohNode.pendingWork(storagePath: storagePath)
+    /// if pending.hasPendingWork {
+    ///     // Start a node and spend the background budget on a sync
+    /// }
+    /// ```
+    ///
+    /// - Parameter storagePath: The storage path a node was configured with.
+    /// - Returns: A summary of unsynced documents and unfinished downloads.
+    /// - Throws: `IrohError.pendingWorkFailed` if the record cannot be read.
+    public static func pendingWork(storagePath: URL) async throws -> PendingWork {
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let callback = pendingWorkCallback(continuation)
+            storagePath.path.withCString { pathPtr in
+                iroh_pending_work(pathPtr, callback)
+            }
+        }
+    }
+
+    /// Report the pending work of this node.
+    ///
+    /// Reads counters the node keeps up to date, so it never scans the
+    /// stores or dials peers.
+    ///
+    /// - Returns: A summary of unsynced documents and unfinished downloads.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.pendingWorkFailed` if the work cannot be read.
+    public func pendingWork() async throws -> PendingWork {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            iroh_node_pending_work(handle.pointer, Self.pendingWorkCallback(continuation))
+        }
+    }
+
+    /// Callback resuming `continuation` with pending work.
+    private static func pendingWorkCallback(
+        _ continuation: CheckedContinuation<PendingWork, Error>
+    ) -> IrohPendingWorkCallback {
+        let box = Unmanaged.passRetained(
+            PendingWorkContinuationBox(continuation)
+        ).toOpaque()
+
+        return IrohPendingWorkCallback(
+            userdata: box,
+            on_success: { userdata, work in
+                let box = Unmanaged<PendingWorkContinuationBox>
+                    .fromOpaque(userdata!)
+                    .takeRetainedValue()
+                let pending = PendingWork(
+                    hasPendingWork: work.has_pending_work,
+                    unsyncedDocs: work.unsynced_docs,
+                    incompleteDownloads: work.incomplete_downloads
+                )
+                box.continuation.resume(returning: pending)
+            },
+            on_failure: { userdata, errorPtr in
+                let box = Unmanaged<PendingWorkContinuationBox>
+                    .fromOpaque(userdata!)
+                    .takeRetainedValue()
+                let message = String(cString: errorPtr!)
+                iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                box.continuation.resume(throwing: IrohError.pendingWorkFailed(message, operationId: currentOperationId()))
+            }
+        )
+    }
+
+    /// Run sync and download work for at most the given duration.
+    ///
+    /// Designed for short background windows such as `BGAppRefreshTask`.
+    /// Unfinished downloads are resumed, documents are synced with their
+    /// previously known peers and missing content is fetched until the budget
+    /// runs out; any sync started by this call is stopped before it returns.
+    /// Work finished before the deadline is persisted before it returns.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let report = try await node.syncStep(budget: .seconds(20))
+    /// task.setTaskCompleted(success: report.completed)
+    /// ```
+    ///
+    /// - Parameter budget: Maximum time to spend on sync work.
+    /// - Returns: A report of the work done and whether it left no pending work.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.syncStepFailed` if the step fails.
+    public func syncStep(budget: Duration) async throws -> SyncStepReport {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        let budgetMs = OperationOptions(timeout: budget).timeoutMs
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                SyncStepContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohSyncStepCallback(
+                userdata: box,
+                on_success: { userdata, report in
+                    let box = Unmanaged<SyncStepContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let stepReport = SyncStepReport(
+                        completed: report.completed,
+                        docsSynced: report.docs_synced,
+                        entriesReceived: report.entries_received,
+                        blobsDownloaded: report.blobs_downloaded
+                    )
+                    box.continuation.resume(returning: stepReport)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<SyncStepContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.syncStepFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_node_sync_step(handle.pointer, budgetMs, callback)
+        }
+    }
+
+    /// Tell the node which kind of network the device is on.
+    ///
+    /// The bandwidth caps configured for that network in
+    /// `IrohConfig.bandwidthLimits` apply from then on, including to
+    /// transfers in progress. The node starts on Wi-Fi.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let monitor = NWPathMonitor()
+    /// monitor.pathUpdateHandler = { path in
+    ///     Task { try? await node.setNetworkKind(path.isExpensive ? .cellular : .wifi) }
+    /// }
+    /// monitor.start(queue: .main)
+    /// ```
+    ///
+    /// - Parameter kind: The kind of network the device is on.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func setNetworkKind(_ kind: NetworkKind) async throws {
+        try ensureNotClosed()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<Void>(continuation)
+            ).toOpaque()
+
+            let callback = IrohCloseCallback(
+                userdata: box,
+                on_complete: { userdata in
+                    let box = Unmanaged<ContinuationBox<Void>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume()
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ContinuationBox<Void>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.nodeClosed)
+                }
+            )
+
+            iroh_node_set_network_kind(handle.pointer, kind.ffiKind, callback)
+        }
+    }
+}
+
+// MARK: - Continuation Boxes
+
+private final class PendingWorkContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<PendingWork, Error>
+
+    init(_ continuation: CheckedContinuation<PendingWork, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class SyncStepContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<SyncStepReport, Error>
+
+    init(_ continuation: CheckedContinuation<SyncStepReport, Error>) {
+        self.continuation = continuation
+    }
+}
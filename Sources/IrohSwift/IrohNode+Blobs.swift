@@ -47,7 +47,7 @@ extension IrohNode {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.blobTagFailed(message))
+                    box.continuation.resume(throwing: IrohError.blobTagFailed(message, operationId: currentOperationId()))
                 }
             )
 
@@ -61,6 +61,73 @@ extension IrohNode {
         }
     }
 
+    /// Tag (pin) a blob until an expiry time, for time-limited shares.
+    ///
+    /// At `expiresAt` the tag is removed, its content is no longer kept by
+    /// it, and `events()` delivers `NodeEvent.tagExpired`. Expiry times
+    /// survive restarts. Tagging or untagging `name` with `tagBlob(hash:name:format:)`
+    /// or `untagBlob(name:)` cancels the expiry.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// // Share for 24 hours
+    /// try await node.tagBlob(
+    ///     hash: ticketInfo.hash!,
+    ///     name: "shares/holiday",
+    ///     expiresAt: Date().addingTimeInterval(24 * 60 * 60)
+    /// )
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - hash: The blob hash (hex string from ticket or entry).
+    ///   - name: Tag name (e.g., "shares/holiday").
+    ///   - format: Blob format (default: .raw).
+    ///   - expiresAt: When to remove the tag.
+    /// - Throws: `IrohError.blobTagFailed` if tagging fails.
+    public func tagBlob(
+        hash: String,
+        name: String,
+        format: BlobFormat = .raw,
+        expiresAt: Date
+    ) async throws {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        let expiresAtMicros = UInt64(max(0, expiresAt.timeIntervalSince1970) * 1_000_000)
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                BlobTagContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCloseCallback(
+                userdata: box,
+                on_complete: { userdata in
+                    let box = Unmanaged<BlobTagContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume()
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<BlobTagContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.blobTagFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            let ffiFormat: IrohBlobFormat = format == .raw ? Raw : HashSeq
+
+            name.withCString { namePtr in
+                hash.withCString { hashPtr in
+                    iroh_blob_tag_set_until(handle.pointer, namePtr, hashPtr, ffiFormat, expiresAtMicros, callback)
+                }
+            }
+        }
+    }
+
     /// Remove a tag (unpin) from a blob, allowing garbage collection.
     ///
     /// After removing the tag, the blob may be garbage collected if no other
@@ -97,7 +164,7 @@ extension IrohNode {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.blobUntagFailed(message))
+                    box.continuation.resume(throwing: IrohError.blobUntagFailed(message, operationId: currentOperationId()))
                 }
             )
 
@@ -152,7 +219,7 @@ extension IrohNode {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.ticketCreationFailed(message))
+                    box.continuation.resume(throwing: IrohError.ticketCreationFailed(message, operationId: currentOperationId()))
                 }
             )
 
@@ -163,6 +230,578 @@ extension IrohNode {
             }
         }
     }
+
+    /// Report how much of a tag's content is shared with other references.
+    ///
+    /// Other tags, temporary tags and document entries all keep content, so
+    /// only blobs none of them reference count towards `uniqueSize`.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let report = try await node.referenceReport(tag: "albums/trip")
+    /// print("Deleting frees \(report.uniqueSize) of \(report.totalSize) bytes")
+    /// ```
+    ///
+    /// - Parameter tag: The tag name (e.g., "albums/trip").
+    /// - Returns: Blob counts and sizes, including the space deletion would free.
+    /// - Throws: `IrohError.referenceReportFailed` if the tag does not exist or
+    ///           the store cannot be inspected.
+    public func referenceReport(tag: String) async throws -> ReferenceReport {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ReferenceReportContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohReferenceReportCallback(
+                userdata: box,
+                on_success: { userdata, report in
+                    let box = Unmanaged<ReferenceReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let result = ReferenceReport(
+                        totalBlobs: report.total_blobs,
+                        sharedBlobs: report.shared_blobs,
+                        totalSize: report.total_size,
+                        uniqueSize: report.unique_size
+                    )
+                    box.continuation.resume(returning: result)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ReferenceReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.referenceReportFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            tag.withCString { tagPtr in
+                iroh_blob_reference_report(handle.pointer, tagPtr, callback)
+            }
+        }
+    }
+
+    /// Report how much of a collection's content is shared with other references.
+    ///
+    /// The collection and its children are counted. Tags naming the
+    /// collection itself are treated as deleted with it.
+    ///
+    /// - Parameter collection: The collection's hash, which must be stored locally.
+    /// - Returns: Blob counts and sizes, including the space deletion would free.
+    /// - Throws: `IrohError.referenceReportFailed` if the collection is not
+    ///           stored or the store cannot be inspected.
+    public func referenceReport(collection hash: String) async throws -> ReferenceReport {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ReferenceReportContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohReferenceReportCallback(
+                userdata: box,
+                on_success: { userdata, report in
+                    let box = Unmanaged<ReferenceReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let result = ReferenceReport(
+                        totalBlobs: report.total_blobs,
+                        sharedBlobs: report.shared_blobs,
+                        totalSize: report.total_size,
+                        uniqueSize: report.unique_size
+                    )
+                    box.continuation.resume(returning: result)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ReferenceReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.referenceReportFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            hash.withCString { hashPtr in
+                iroh_blob_collection_reference_report(handle.pointer, hashPtr, callback)
+            }
+        }
+    }
+
+    /// Report identical content referenced by several tags or collections.
+    ///
+    /// Every tag is followed into its collection, and each time a blob is
+    /// reached counts as a reference. Use this to show how much space
+    /// deduplication saves.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let report = try await node.dedupReport()
+    /// print("Saved \(report.savedSize) of \(report.referencedSize) bytes")
+    /// ```
+    ///
+    /// - Parameter limit: Maximum number of duplicates to list.
+    /// - Returns: Store-wide totals and the duplicates saving the most space.
+    /// - Throws: `IrohError.referenceReportFailed` if the store cannot be inspected.
+    public func dedupReport(limit: Int = 20) async throws -> DedupReport {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                DedupReportContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDedupReportCallback(
+                userdata: box,
+                on_duplicate: { userdata, duplicate in
+                    let box = Unmanaged<DedupReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more duplicates coming
+                    let tags = UnsafeBufferPointer(start: duplicate.tags, count: Int(duplicate.tags_len))
+                    box.duplicates.append(DuplicateBlob(
+                        hash: String(cString: duplicate.hash!),
+                        size: duplicate.size,
+                        references: duplicate.references,
+                        tags: tags.map { String(cString: $0!) }
+                    ))
+                },
+                on_complete: { userdata, summary in
+                    let box = Unmanaged<DedupReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: DedupReport(
+                        references: summary.references,
+                        uniqueBlobs: summary.unique_blobs,
+                        duplicateBlobs: summary.duplicate_blobs,
+                        referencedSize: summary.referenced_size,
+                        storedSize: summary.stored_size,
+                        duplicates: box.duplicates
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<DedupReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.referenceReportFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_blob_dedup_report(handle.pointer, UInt(max(0, limit)), callback)
+        }
+    }
+
+    // MARK: - Listing
+
+    /// List tags whose names start with a prefix, with their stored sizes.
+    ///
+    /// Use this to compute storage usage for apps that namespace tags per
+    /// feature, such as "chat/" or "profiles/".
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let chat = try await node.listTags(prefix: "chat/")
+    /// print("Chat uses \(chat.totalSize) bytes across \(chat.tags.count) tags")
+    /// ```
+    ///
+    /// - Parameter prefix: Tag name prefix. An empty prefix lists all tags.
+    /// - Returns: The matching tags and their combined size.
+    /// - Throws: `IrohError.tagListFailed` if the tags cannot be read.
+    public func listTags(prefix: String = "") async throws -> TagListing {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                TagListContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohTagListCallback(
+                userdata: box,
+                on_entry: { userdata, entry in
+                    let box = Unmanaged<TagListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more entries coming
+                    box.tags.append(TagSummary(
+                        name: String(cString: entry.name!),
+                        hash: String(cString: entry.hash!),
+                        format: entry.format == HashSeq ? .hashSeq : .raw,
+                        size: entry.size
+                    ))
+                },
+                on_complete: { userdata, totalSize in
+                    let box = Unmanaged<TagListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: TagListing(tags: box.tags, totalSize: totalSize))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<TagListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.tagListFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            prefix.withCString { prefixPtr in
+                iroh_blob_tag_list(handle.pointer, prefixPtr, callback)
+            }
+        }
+    }
+
+    /// List blobs in the local store, one page at a time.
+    ///
+    /// Without a cursor, the store is read into a sorted snapshot and the
+    /// page carries a cursor for it. Pages listed with that cursor are cut
+    /// from the same snapshot in its order, so paging never overlaps or
+    /// skips blobs, even while other listings are in progress. Start a new
+    /// listing to see blobs added or removed since. Only the latest 16
+    /// listings are kept; older cursors fail.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let page = try await node.listBlobs(sort: .sizeDescending, offset: 0, limit: 50)
+    /// let next = try await node.listBlobs(cursor: page.cursor, offset: 50, limit: 50)
+    /// for blob in page.blobs {
+    ///     print("\(blob.hash): \(blob.size) bytes")
+    /// }
+    /// let hasMore = page.totalCount > 50
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - sort: Sort order of a new listing (default: .hash).
+    ///   - cursor: Cursor of the listing to continue, or nil to start one.
+    ///   - offset: Number of blobs to skip.
+    ///   - limit: Maximum number of blobs to return.
+    /// - Returns: The requested page and the total number of blobs.
+    /// - Throws: `IrohError.blobListFailed` if the store cannot be read or the
+    ///           paging arguments are negative.
+    public func listBlobs(
+        sort: BlobSort = .hash,
+        cursor: UInt64? = nil,
+        offset: Int = 0,
+        limit: Int = 100
+    ) async throws -> BlobPage {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        guard offset >= 0, limit >= 0 else {
+            throw IrohError.blobListFailed("offset and limit must not be negative")
+        }
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                BlobListContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohBlobListCallback(
+                userdata: box,
+                on_entry: { userdata, entry in
+                    let box = Unmanaged<BlobListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more entries coming
+                    let addedAt = entry.added_at == 0
+                        ? nil
+                        : Date(timeIntervalSince1970: TimeInterval(entry.added_at) / 1_000_000)
+                    box.blobs.append(StoredBlob(
+                        hash: String(cString: entry.hash!),
+                        size: entry.size,
+                        isComplete: entry.is_complete,
+                        addedAt: addedAt
+                    ))
+                },
+                on_complete: { userdata, total, cursor in
+                    let box = Unmanaged<BlobListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: BlobPage(
+                        blobs: box.blobs,
+                        totalCount: total,
+                        cursor: cursor
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<BlobListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.blobListFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            let ffiSort: IrohBlobSort
+            switch sort {
+            case .hash: ffiSort = ByHash
+            case .sizeAscending: ffiSort = SizeAscending
+            case .sizeDescending: ffiSort = SizeDescending
+            case .newestFirst: ffiSort = NewestFirst
+            case .oldestFirst: ffiSort = OldestFirst
+            }
+
+            iroh_blob_list(handle.pointer, ffiSort, cursor ?? 0, UInt64(offset), UInt64(limit), callback)
+        }
+    }
+
+    /// Create a temporary tag that protects a blob from garbage collection.
+    ///
+    /// Use this during multi-step operations, such as building a collection,
+    /// so intermediate content survives until a permanent tag is set. The
+    /// protection ends when the tag is released or deallocated, or when the
+    /// node shuts down.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let tempTag = try await node.createTempTag(hash: partHash)
+    /// defer { tempTag.release() }
+    ///
+    /// // ... build the collection ...
+    /// try await node.tagBlob(hash: collectionHash, name: "albums/trip", format: .hashSeq)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - hash: The blob hash (hex string).
+    ///   - format: Blob format (default: .raw). `.hashSeq` also protects the children.
+    /// - Returns: A temporary tag that keeps the content alive while retained.
+    /// - Throws: `IrohError.blobTagFailed` if the tag cannot be created.
+    public func createTempTag(hash: String, format: BlobFormat = .raw) async throws -> BlobTempTag {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                TempTagContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohTempTagCallback(
+                userdata: box,
+                on_success: { userdata, tagPtr in
+                    let box = Unmanaged<TempTagContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: BlobTempTag(pointer: tagPtr!))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<TempTagContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.blobTagFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            let ffiFormat: IrohBlobFormat = format == .raw ? Raw : HashSeq
+
+            hash.withCString { hashPtr in
+                iroh_blob_temp_tag_create(handle.pointer, hashPtr, ffiFormat, callback)
+            }
+        }
+    }
+
+    // MARK: - Store Copy
+
+    /// Copy blobs from this node into another store directory.
+    ///
+    /// Use this to hand a subset of content to an app extension that opens
+    /// its own store. Each chunk is verified against its hash as it is
+    /// written, and each blob is tagged in the destination so it is kept
+    /// there. The destination must not be open in another node.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let shared = FileManager.default
+    ///     .containerURL(forSecurityApplicationGroupIdentifier: "group.com.example")!
+    ///     .appendingPathComponent("extension-store")
+    /// try await node.copyBlobs([hash], to: shared)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - hashes: Hashes of complete local blobs (hex strings).
+    ///   - destination: Directory of the destination store (created if needed).
+    /// - Returns: The number of bytes copied.
+    /// - Throws: `IrohError.storeCopyFailed` if a blob is missing locally or
+    ///           the destination cannot be written.
+    @discardableResult
+    public func copyBlobs(_ hashes: [String], to destination: URL) async throws -> UInt64 {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        // Duplicate the strings so the pointers stay valid for the whole call
+        let hashPtrs = hashes.map { UnsafePointer<CChar>(strdup($0)) }
+        defer { hashPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) } }
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                StoreCopyContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohStoreCopyCallback(
+                userdata: box,
+                on_success: { userdata, bytesCopied in
+                    let box = Unmanaged<StoreCopyContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: bytesCopied)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<StoreCopyContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.storeCopyFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            destination.path.withCString { pathPtr in
+                hashPtrs.withUnsafeBufferPointer { buffer in
+                    iroh_store_copy(
+                        handle.pointer, pathPtr, buffer.baseAddress, UInt(buffer.count), callback
+                    )
+                }
+            }
+        }
+    }
+
+    // MARK: - Temporary Files
+
+    /// Export a local blob to a temporary file managed by the node.
+    ///
+    /// Use this to hand content to APIs that need a file URL, such as
+    /// QuickLook or a share sheet. The file lives under
+    /// `IrohConfig.tempDirectory` and is deleted when the returned object is
+    /// released or deallocated.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let file = try await node.exportTemporaryFile(hash: hash, filename: "report.pdf")
+    /// defer { file.release() }
+    /// previewController.present(file.url)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - hash: The blob hash (hex string).
+    ///   - filename: Name for the exported file. If nil, the hash is used.
+    /// - Returns: A temporary file that exists while retained.
+    /// - Throws: `IrohError.exportFailed` if the blob is missing or cannot be written.
+    public func exportTemporaryFile(hash: String, filename: String? = nil) async throws -> TemporaryFile {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                TempFileContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohTempFileCallback(
+                userdata: box,
+                on_success: { userdata, filePtr, pathPtr in
+                    let box = Unmanaged<TempFileContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let path = String(cString: pathPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: pathPtr))
+                    box.continuation.resume(
+                        returning: TemporaryFile(pointer: filePtr!, url: URL(fileURLWithPath: path))
+                    )
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<TempFileContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.exportFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            hash.withCString { hashPtr in
+                withOptionalCString(filename) { filenamePtr in
+                    iroh_blob_export_temp(handle.pointer, hashPtr, filenamePtr, callback)
+                }
+            }
+        }
+    }
+}
+
+// MARK: - Temp Tags
+
+/// A temporary tag protecting content from garbage collection.
+///
+/// Temporary tags are not persisted. The protection ends when `release()`
+/// is called or the tag is deallocated.
+public final class BlobTempTag: @unchecked Sendable {
+    private let lock = NSLock()
+    private var pointer: UnsafeMutablePointer<IrohTempTagHandle>?
+
+    init(pointer: UnsafeMutablePointer<IrohTempTagHandle>) {
+        self.pointer = pointer
+    }
+
+    deinit {
+        release()
+    }
+
+    /// Release the tag, allowing the content to be garbage collected.
+    ///
+    /// Safe to call more than once.
+    public func release() {
+        lock.lock()
+        let toDrop = pointer
+        pointer = nil
+        lock.unlock()
+
+        if let toDrop {
+            iroh_blob_temp_tag_drop(toDrop)
+        }
+    }
+}
+
+// MARK: - Temporary Files
+
+/// A blob exported to a temporary file.
+///
+/// The file is deleted when `release()` is called or the object is
+/// deallocated, whichever comes first.
+public final class TemporaryFile: @unchecked Sendable {
+    /// Location of the exported file.
+    public let url: URL
+
+    private let lock = NSLock()
+    private var pointer: UnsafeMutablePointer<IrohTempFileHandle>?
+
+    init(pointer: UnsafeMutablePointer<IrohTempFileHandle>, url: URL) {
+        self.pointer = pointer
+        self.url = url
+    }
+
+    deinit {
+        release()
+    }
+
+    /// Delete the file.
+    ///
+    /// Safe to call more than once.
+    public func release() {
+        lock.lock()
+        let toRelease = pointer
+        pointer = nil
+        lock.unlock()
+
+        if let toRelease {
+            iroh_temp_file_release(toRelease)
+        }
+    }
 }
 
 // MARK: - Continuation Boxes
@@ -182,3 +821,62 @@ private final class TicketCreateContinuationBox: @unchecked Sendable {
         self.continuation = continuation
     }
 }
+
+private final class TempTagContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<BlobTempTag, Error>
+
+    init(_ continuation: CheckedContinuation<BlobTempTag, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ReferenceReportContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<ReferenceReport, Error>
+
+    init(_ continuation: CheckedContinuation<ReferenceReport, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class DedupReportContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<DedupReport, Error>
+    var duplicates: [DuplicateBlob] = []
+
+    init(_ continuation: CheckedContinuation<DedupReport, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class TempFileContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<TemporaryFile, Error>
+
+    init(_ continuation: CheckedContinuation<TemporaryFile, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class BlobListContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<BlobPage, Error>
+    var blobs: [StoredBlob] = []
+
+    init(_ continuation: CheckedContinuation<BlobPage, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class TagListContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<TagListing, Error>
+    var tags: [TagSummary] = []
+
+    init(_ continuation: CheckedContinuation<TagListing, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class StoreCopyContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<UInt64, Error>
+
+    init(_ continuation: CheckedContinuation<UInt64, Error>) {
+        self.continuation = continuation
+    }
+}
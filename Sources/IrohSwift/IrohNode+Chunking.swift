@@ -0,0 +1,127 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohNode {
+    // MARK: - Chunked Files
+
+    /// Upload a file split into content-defined chunks.
+    ///
+    /// Chunk boundaries follow the file's content, so an edit only changes
+    /// the chunks around it. Uploading a new version of a file reuses the
+    /// chunks stored for the previous one, and peers that fetched that
+    /// version only download the changed chunks. Use this for large files
+    /// that change in place, such as databases.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let upload = try await node.putChunkedFile(at: databaseURL)
+    /// print("Stored \(upload.stats.newChunks) of \(upload.stats.chunks) chunks")
+    /// ```
+    ///
+    /// - Parameter url: The file to upload.
+    /// - Returns: The file's ticket and the chunks that were stored.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.putFailed` if the file cannot be read or stored.
+    public func putChunkedFile(at url: URL) async throws -> ChunkedUpload {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ChunkedPutContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohChunkedPutCallback(
+                userdata: box,
+                on_success: { userdata, ticketPtr, stats in
+                    let box = Unmanaged<ChunkedPutContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let ticket = String(cString: ticketPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: ticketPtr))
+                    box.continuation.resume(
+                        returning: ChunkedUpload(ticket: ticket, stats: ChunkStats(from: stats))
+                    )
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ChunkedPutContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.putFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            url.path.withCString { pathPtr in
+                iroh_put_chunked_file(handle.pointer, pathPtr, callback)
+            }
+        }
+    }
+
+    /// Download a chunked file and write it to `destination`.
+    ///
+    /// Only chunks that are not stored on this node are downloaded, so
+    /// fetching a new version of a file after an earlier one transfers just
+    /// the changed chunks. The file is replaced once it is complete.
+    ///
+    /// - Parameters:
+    ///   - ticket: A ticket from `putChunkedFile(at:)`.
+    ///   - destination: Where to write the file.
+    /// - Returns: The file's size and the chunks that were downloaded.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.getFailed` if the download or write fails.
+    @discardableResult
+    public func getChunkedFile(ticket: String, to destination: URL) async throws -> ChunkStats {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ChunkedGetContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohChunkedGetCallback(
+                userdata: box,
+                on_success: { userdata, stats in
+                    let box = Unmanaged<ChunkedGetContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: ChunkStats(from: stats))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ChunkedGetContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.getFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            ticket.withCString { ticketPtr in
+                destination.path.withCString { pathPtr in
+                    iroh_get_chunked_file(handle.pointer, ticketPtr, pathPtr, callback)
+                }
+            }
+        }
+    }
+}
+
+// MARK: - Continuation Boxes
+
+private final class ChunkedPutContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<ChunkedUpload, Error>
+
+    init(_ continuation: CheckedContinuation<ChunkedUpload, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ChunkedGetContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<ChunkStats, Error>
+
+    init(_ continuation: CheckedContinuation<ChunkStats, Error>) {
+        self.continuation = continuation
+    }
+}
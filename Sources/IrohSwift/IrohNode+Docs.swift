@@ -37,7 +37,7 @@ extension IrohNode {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.authorImportFailed(message))
+                    box.continuation.resume(throwing: IrohError.authorImportFailed(message, operationId: currentOperationId()))
                 }
             )
 
@@ -45,6 +45,96 @@ extension IrohNode {
         }
     }
 
+    /// Make an imported author the node's default author.
+    ///
+    /// `IrohDoc.set(key:value:)` and `IrohDoc.delete(key:)` sign as the
+    /// default author, so writes no longer pass the author's secret to the
+    /// node. The choice is kept in the storage directory across restarts.
+    ///
+    /// - Parameter author: The author, already passed to `importAuthor(_:)`.
+    /// - Throws: `IrohError.docsNotEnabled` if docs were not enabled on init,
+    ///           `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.defaultAuthorFailed` if the author was not imported.
+    public func setDefaultAuthor(_ author: IrohAuthor) async throws {
+        try ensureNotClosed()
+        try ensureDocsEnabled()
+        try Task.checkCancellation()
+
+        var authorId = IrohAuthorId()
+        author.publicKey.withUnsafeBytes { buffer in
+            withUnsafeMutableBytes(of: &authorId.bytes) { destBuffer in
+                destBuffer.copyMemory(from: buffer)
+            }
+        }
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                AuthorImportContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCloseCallback(
+                userdata: box,
+                on_complete: { userdata in
+                    let box = Unmanaged<AuthorImportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume()
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<AuthorImportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.defaultAuthorFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_node_set_default_author(handle.pointer, authorId, callback)
+        }
+    }
+
+    /// ID of the node's default author, as set with `setDefaultAuthor(_:)`,
+    /// or else of an author the docs engine created for the node.
+    ///
+    /// - Returns: The hex-encoded author ID, comparable to `IrohAuthor.id`.
+    /// - Throws: `IrohError.docsNotEnabled` if docs were not enabled on init,
+    ///           `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.defaultAuthorFailed` if the lookup fails.
+    public func defaultAuthorId() async throws -> String {
+        try ensureNotClosed()
+        try ensureDocsEnabled()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                DefaultAuthorContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCallback(
+                userdata: box,
+                on_success: { userdata, resultPtr in
+                    let box = Unmanaged<DefaultAuthorContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let authorId = String(cString: resultPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: resultPtr))
+                    box.continuation.resume(returning: authorId)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<DefaultAuthorContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.defaultAuthorFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_node_default_author(handle.pointer, callback)
+        }
+    }
+
     // MARK: - Document Operations
 
     /// Create a new document.
@@ -72,12 +162,9 @@ extension IrohNode {
                     let box = Unmanaged<DocCreateContinuationBox>
                         .fromOpaque(userdata!)
                         .takeRetainedValue()
-                    let namespaceId = String(cString: namespaceIdPtr!)
+                    // The document reads its namespace ID from the handle
                     iroh_string_free(UnsafeMutablePointer(mutating: namespaceIdPtr))
-                    let result = DocCreateResult(
-                        handle: DocHandleWrapper(pointer: docHandlePtr!),
-                        namespaceId: namespaceId
-                    )
+                    let result = DocCreateResult(handle: DocHandleWrapper(pointer: docHandlePtr!))
                     box.continuation.resume(returning: result)
                 },
                 on_failure: { userdata, errorPtr in
@@ -86,7 +173,7 @@ extension IrohNode {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.docCreationFailed(message))
+                    box.continuation.resume(throwing: IrohError.docCreationFailed(message, operationId: currentOperationId()))
                 }
             )
 
@@ -95,8 +182,7 @@ extension IrohNode {
 
         return IrohDoc(
             handle: result.handle,
-            nodeHandle: NodeHandleWrapper(pointer: nodePtr),
-            namespaceId: result.namespaceId
+            nodeHandle: NodeHandleWrapper(pointer: nodePtr)
         )
     }
 
@@ -104,12 +190,79 @@ extension IrohNode {
     ///
     /// This connects to peers and downloads the document content.
     ///
-    /// - Parameter ticket: The document ticket string obtained from another node's `shareTicket()` call.
+    /// - Parameters:
+    ///   - ticket: The document ticket string obtained from another node's `shareTicket()` call.
+    ///   - mode: Use `.readOnly` to get a handle that rejects writes even if
+    ///           the ticket grants write access.
     /// - Returns: The joined document.
     /// - Throws: `IrohError.docsNotEnabled` if docs were not enabled on init,
     ///           `IrohError.nodeClosed` if the node is closed,
     ///           `IrohError.docJoinFailed` if joining fails.
-    public func joinDoc(ticket: String) async throws -> IrohDoc {
+    public func joinDoc(ticket: String, mode: DocOpenMode = .readWrite) async throws -> IrohDoc {
+        try ensureNotClosed()
+        try ensureDocsEnabled()
+        try Task.checkCancellation()
+
+        let nodePtr = handle.pointer
+        let result: DocCreateResult = try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                DocJoinContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDocCreateCallback(
+                userdata: box,
+                on_success: { userdata, docHandlePtr, namespaceIdPtr in
+                    let box = Unmanaged<DocJoinContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    // The document reads its namespace ID from the handle
+                    iroh_string_free(UnsafeMutablePointer(mutating: namespaceIdPtr))
+                    let result = DocCreateResult(handle: DocHandleWrapper(pointer: docHandlePtr!))
+                    box.continuation.resume(returning: result)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<DocJoinContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.docJoinFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            ticket.withCString { ticketPtr in
+                iroh_doc_join_with_mode(handle.pointer, ticketPtr, mode.ffiMode, callback)
+            }
+        }
+
+        return IrohDoc(
+            handle: result.handle,
+            nodeHandle: NodeHandleWrapper(pointer: nodePtr),
+            isReadOnly: mode == .readOnly
+        )
+    }
+
+    /// Join a document, bootstrapping it from a snapshot blob.
+    ///
+    /// The snapshot from `IrohDoc.snapshotTicket()` is fetched first and its
+    /// entries are inserted with their signatures checked, so the document
+    /// is readable after one blob fetch. Live sync with the peers in
+    /// `ticket` then catches up on writes made since the snapshot.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let doc = try await node.restoreDoc(ticket: docTicket, snapshot: snapshotTicket)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - ticket: The document ticket granting access.
+    ///   - snapshot: A snapshot blob ticket of the same document.
+    /// - Returns: The joined document.
+    /// - Throws: `IrohError.docsNotEnabled` if docs were not enabled on init,
+    ///           `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.docJoinFailed` if the snapshot cannot be fetched
+    ///           or restored, or joining fails.
+    public func restoreDoc(ticket: String, snapshot: String) async throws -> IrohDoc {
         try ensureNotClosed()
         try ensureDocsEnabled()
         try Task.checkCancellation()
@@ -126,12 +279,9 @@ extension IrohNode {
                     let box = Unmanaged<DocJoinContinuationBox>
                         .fromOpaque(userdata!)
                         .takeRetainedValue()
-                    let namespaceId = String(cString: namespaceIdPtr!)
+                    // The document reads its namespace ID from the handle
                     iroh_string_free(UnsafeMutablePointer(mutating: namespaceIdPtr))
-                    let result = DocCreateResult(
-                        handle: DocHandleWrapper(pointer: docHandlePtr!),
-                        namespaceId: namespaceId
-                    )
+                    let result = DocCreateResult(handle: DocHandleWrapper(pointer: docHandlePtr!))
                     box.continuation.resume(returning: result)
                 },
                 on_failure: { userdata, errorPtr in
@@ -140,22 +290,175 @@ extension IrohNode {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.docJoinFailed(message))
+                    box.continuation.resume(throwing: IrohError.docJoinFailed(message, operationId: currentOperationId()))
                 }
             )
 
             ticket.withCString { ticketPtr in
-                iroh_doc_join(handle.pointer, ticketPtr, callback)
+                snapshot.withCString { snapshotPtr in
+                    iroh_doc_restore_from_blob(handle.pointer, ticketPtr, snapshotPtr, callback)
+                }
+            }
+        }
+
+        return IrohDoc(
+            handle: result.handle,
+            nodeHandle: NodeHandleWrapper(pointer: nodePtr)
+        )
+    }
+
+    /// Open a document already stored on this node.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// // Give a plugin a handle it cannot write through
+    /// let pluginDoc = try await node.openDoc(namespaceId: doc.namespaceId, mode: .readOnly)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - namespaceId: The document's namespace ID.
+    ///   - mode: Use `.readOnly` to get a handle that rejects writes even if
+    ///           this node holds the write capability.
+    /// - Returns: A new handle to the document.
+    /// - Throws: `IrohError.docsNotEnabled` if docs were not enabled on init,
+    ///           `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.docOpenFailed` if the document is unknown or cannot be opened.
+    public func openDoc(namespaceId: String, mode: DocOpenMode = .readWrite) async throws -> IrohDoc {
+        try ensureNotClosed()
+        try ensureDocsEnabled()
+        try Task.checkCancellation()
+
+        let nodePtr = handle.pointer
+        let result: DocCreateResult = try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                DocOpenContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDocCreateCallback(
+                userdata: box,
+                on_success: { userdata, docHandlePtr, namespaceIdPtr in
+                    let box = Unmanaged<DocOpenContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    // The document reads its namespace ID from the handle
+                    iroh_string_free(UnsafeMutablePointer(mutating: namespaceIdPtr))
+                    let result = DocCreateResult(handle: DocHandleWrapper(pointer: docHandlePtr!))
+                    box.continuation.resume(returning: result)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<DocOpenContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.docOpenFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            namespaceId.withCString { namespacePtr in
+                iroh_doc_open(handle.pointer, namespacePtr, mode.ffiMode, callback)
             }
         }
 
         return IrohDoc(
             handle: result.handle,
             nodeHandle: NodeHandleWrapper(pointer: nodePtr),
-            namespaceId: result.namespaceId
+            isReadOnly: mode == .readOnly
         )
     }
 
+    // MARK: - Expiry
+
+    /// Remove expired document entries now.
+    ///
+    /// Entries written with `IrohDoc.set(author:key:value:ttl:)` are removed
+    /// in the background every `IrohConfig.expiryPruneInterval`; call this to
+    /// remove them immediately, for example before taking a snapshot.
+    ///
+    /// - Returns: The number of keys removed.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.docDeleteFailed` if pruning fails.
+    @discardableResult
+    public func pruneExpiredEntries() async throws -> UInt64 {
+        try ensureNotClosed()
+        try ensureDocsEnabled()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                PruneContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDocDelCallback(
+                userdata: box,
+                on_success: { userdata, pruned in
+                    let box = Unmanaged<PruneContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: pruned)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<PruneContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.docDeleteFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_docs_prune_expired(handle.pointer, callback)
+        }
+    }
+
+    // MARK: - Outbox
+
+    /// Document writes queued with `IrohDoc.enqueue(author:key:value:)`
+    /// that no peer has synced yet.
+    ///
+    /// Items stay in the outbox across restarts until they are delivered,
+    /// superseded or failed; each change is reported as a `.outbox` event.
+    ///
+    /// - Returns: The pending items, oldest first.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.outboxFailed` if the outbox cannot be read.
+    public func outboxItems() async throws -> [OutboxItem] {
+        try ensureNotClosed()
+        try ensureDocsEnabled()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                OutboxListContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohOutboxCallback(
+                userdata: box,
+                on_entry: { userdata, item in
+                    let box = Unmanaged<OutboxListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more items coming
+                    box.items.append(OutboxItem(from: item))
+                },
+                on_complete: { userdata in
+                    let box = Unmanaged<OutboxListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: box.items)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<OutboxListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.outboxFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_outbox_list(handle.pointer, callback)
+        }
+    }
+
     // MARK: - Private Helpers
 
     /// Check that docs were enabled during node initialization.
@@ -172,7 +475,6 @@ extension IrohNode {
 /// Internal result type for document creation/join.
 private struct DocCreateResult: @unchecked Sendable {
     let handle: DocHandleWrapper
-    let namespaceId: String
 }
 
 // MARK: - Continuation Boxes
@@ -193,6 +495,22 @@ private final class DocJoinContinuationBox: @unchecked Sendable {
     }
 }
 
+private final class DocOpenContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<DocCreateResult, Error>
+
+    init(_ continuation: CheckedContinuation<DocCreateResult, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class PruneContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<UInt64, Error>
+
+    init(_ continuation: CheckedContinuation<UInt64, Error>) {
+        self.continuation = continuation
+    }
+}
+
 private final class AuthorImportContinuationBox: @unchecked Sendable {
     let continuation: CheckedContinuation<Void, Error>
 
@@ -200,3 +518,20 @@ private final class AuthorImportContinuationBox: @unchecked Sendable {
         self.continuation = continuation
     }
 }
+
+private final class DefaultAuthorContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<String, Error>
+
+    init(_ continuation: CheckedContinuation<String, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class OutboxListContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<[OutboxItem], Error>
+    var items: [OutboxItem] = []
+
+    init(_ continuation: CheckedContinuation<[OutboxItem], Error>) {
+        self.continuation = continuation
+    }
+}
@@ -0,0 +1,99 @@
+import Foundation
+import IrohSwiftFFI
+
+/// Faults to inject into a node so tests can exercise error handling.
+public struct FaultInjection: Sendable, Equatable {
+    /// Number of upcoming puts that fail.
+    public var failNextPuts: UInt64
+    /// Time each download waits before it starts.
+    public var downloadDelay: Duration
+    /// Percentage of document syncs started by peers that are dropped,
+    /// from 0 to 100.
+    public var syncDropPercent: UInt8
+
+    /// No faults.
+    public static let none = FaultInjection()
+
+    /// Create a set of faults.
+    ///
+    /// - Parameters:
+    ///   - failNextPuts: Number of upcoming puts that fail.
+    ///   - downloadDelay: Time each download waits before it starts.
+    ///   - syncDropPercent: Percentage of document syncs started by peers
+    ///                      that are dropped, from 0 to 100.
+    public init(
+        failNextPuts: UInt64 = 0,
+        downloadDelay: Duration = .zero,
+        syncDropPercent: UInt8 = 0
+    ) {
+        self.failNextPuts = failNextPuts
+        self.downloadDelay = downloadDelay
+        self.syncDropPercent = syncDropPercent
+    }
+}
+
+extension IrohNode {
+    // MARK: - Fault Injection
+
+    /// Replace the faults injected into this node.
+    ///
+    /// Lets automated tests check retry and error handling without real
+    /// network trouble. Puts fail while `failNextPuts` lasts, downloads
+    /// start late, and peers starting a document sync are dropped at the
+    /// given rate. Pass `.none` to stop injecting faults. Only available
+    /// when the library is built with the `faults` feature; never ship it.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// try await node.injectFaults(FaultInjection(failNextPuts: 2))
+    /// let ticket = try await node.putWithRetry(data)
+    /// ```
+    ///
+    /// - Parameter faults: The faults to inject from now on.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.faultInjectionFailed` if the library was built
+    ///           without the `faults` feature.
+    public func injectFaults(_ faults: FaultInjection) async throws {
+        try ensureNotClosed()
+
+        let ffiFaults = IrohFaults(
+            fail_puts: faults.failNextPuts,
+            download_delay_ms: faults.downloadDelay.milliseconds,
+            sync_drop_percent: faults.syncDropPercent
+        )
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                FaultsContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCloseCallback(
+                userdata: box,
+                on_complete: { userdata in
+                    let box = Unmanaged<FaultsContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume()
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<FaultsContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.faultInjectionFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_node_inject_faults(handle.pointer, ffiFaults, callback)
+        }
+    }
+}
+
+private final class FaultsContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<Void, Error>
+
+    init(_ continuation: CheckedContinuation<Void, Error>) {
+        self.continuation = continuation
+    }
+}
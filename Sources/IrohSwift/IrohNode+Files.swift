@@ -0,0 +1,79 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohNode {
+    // MARK: - Files
+
+    /// Add a file to the blob store and return a shareable ticket.
+    ///
+    /// Like `put(_:)`, but the file is streamed from disk and hashed without
+    /// being loaded into memory, so videos hundreds of megabytes large can
+    /// be shared without the app being terminated for memory use. The
+    /// content is copied, so the file can be moved or deleted afterwards.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let ticket = try await node.putFile(at: videoURL) { progress in
+    ///     Task { @MainActor in bar.value = progress.fraction ?? 0 }
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - url: A local file URL.
+    ///   - onProgress: Called while the file is copied and hashed.
+    /// - Returns: A ticket string that can be used to retrieve the file.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.putFailed` if the file cannot be read or stored.
+    public func putFile(
+        at url: URL,
+        onProgress: @escaping ImportProgressHandler = { _ in }
+    ) async throws -> String {
+        try ensureNotClosed()
+        guard url.isFileURL else {
+            throw IrohError.putFailed("Not a file URL: \(url)")
+        }
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            url.withUnsafeFileSystemRepresentation { pathPtr in
+                let context = ImportContext(
+                    continuation: continuation,
+                    onProgress: onProgress
+                )
+                let box = Unmanaged.passRetained(context).toOpaque()
+
+                let callback = IrohPutFileCallback(
+                    userdata: box,
+                    on_progress: { userdata, progress in
+                        let ctx = Unmanaged<ImportContext>
+                            .fromOpaque(userdata!)
+                            .takeUnretainedValue()
+                        ctx.onProgress(ImportProgress(
+                            copied: progress.copied,
+                            hashed: progress.hashed,
+                            total: progress.total
+                        ))
+                    },
+                    on_success: { userdata, ticketPtr in
+                        let ctx = Unmanaged<ImportContext>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let ticket = String(cString: ticketPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: ticketPtr))
+                        ctx.continuation.resume(returning: ticket)
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let ctx = Unmanaged<ImportContext>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        ctx.continuation.resume(throwing: IrohError.putFailed(message, operationId: currentOperationId()))
+                    }
+                )
+
+                iroh_put_file(handle.pointer, pathPtr, callback)
+            }
+        }
+    }
+}
@@ -30,10 +30,20 @@ extension IrohNode {
                         relayUrl = nil
                     }
 
+                    let appId: String?
+                    if info.app_id != nil {
+                        appId = String(cString: info.app_id)
+                        iroh_string_free(UnsafeMutablePointer(mutating: info.app_id))
+                    } else {
+                        appId = nil
+                    }
+
                     let nodeInfo = NodeInfo(
                         nodeId: nodeId,
                         relayUrl: relayUrl,
-                        isConnected: info.is_connected
+                        isConnected: info.is_connected,
+                        appId: appId,
+                        networkCondition: NetworkCondition(from: info.network_condition)
                     )
                     box.continuation.resume(returning: nodeInfo)
                 },
@@ -43,13 +53,606 @@ extension IrohNode {
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.nodeCreationFailed(message))
+                    box.continuation.resume(throwing: IrohError.nodeCreationFailed(message, operationId: currentOperationId()))
                 }
             )
 
             iroh_node_info(handle.pointer, callback)
         }
     }
+
+    /// Close every connection a peer has open to this node.
+    ///
+    /// Blob transfers and document syncs served to the peer stop
+    /// immediately, for example after blocking the peer's account. The peer
+    /// is not prevented from connecting again.
+    ///
+    /// - Parameter nodeId: The peer's node ID.
+    /// - Returns: The number of connections that were closed.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.connectionCloseFailed` if the node ID is invalid.
+    @discardableResult
+    public func closeConnections(to nodeId: String) async throws -> Int {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            nodeId.withCString { nodeIdPtr in
+                let box = Unmanaged.passRetained(
+                    ContinuationBox<Int>(continuation)
+                ).toOpaque()
+
+                let callback = IrohConnectionCloseCallback(
+                    userdata: box,
+                    on_success: { userdata, closed in
+                        let box = Unmanaged<ContinuationBox<Int>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        box.continuation.resume(returning: Int(closed))
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let box = Unmanaged<ContinuationBox<Int>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        box.continuation.resume(throwing: IrohError.connectionCloseFailed(message, operationId: currentOperationId()))
+                    }
+                )
+
+                iroh_connection_close(handle.pointer, nodeIdPtr, callback)
+            }
+        }
+    }
+
+    /// Check that the node's store, runtime and endpoint still work.
+    ///
+    /// Each check gives up after a few seconds, so a wedged node produces an
+    /// unhealthy report instead of an endless wait. Recreate the node when
+    /// the report is unhealthy.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let health = try await node.health()
+    /// if !health.isHealthy {
+    ///     try? await node.close()
+    ///     node = try await IrohNode(config: config)
+    /// }
+    /// ```
+    ///
+    /// - Returns: The outcome of each check.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func health() async throws -> NodeHealth {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<NodeHealth>(continuation)
+            ).toOpaque()
+
+            let callback = IrohNodeHealthCallback(
+                userdata: box,
+                on_success: { userdata, health in
+                    let box = Unmanaged<ContinuationBox<NodeHealth>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: NodeHealth(
+                        storeError: health.store_error.map { String(cString: $0) },
+                        runtimeError: health.runtime_error.map { String(cString: $0) },
+                        endpointError: health.endpoint_error.map { String(cString: $0) }
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ContinuationBox<NodeHealth>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.healthCheckFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_node_health(handle.pointer, callback)
+        }
+    }
+
+    /// Report what the node is holding on to right now.
+    ///
+    /// Only reads counters and file sizes, so it is cheap enough to call
+    /// whenever the app sees memory pressure. Log it next to the app's own
+    /// footprint to tell whether a spike comes from the P2P layer, such as
+    /// a pile of runtime tasks, subscribers falling behind on events, or
+    /// many peers connected at once.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let report = try await node.memoryReport()
+    /// logger.info("iroh tasks=\(report.runtimeTasks) queued=\(report.queuedEvents)")
+    /// ```
+    ///
+    /// - Returns: The store's size on disk and the node's tasks, queues and
+    ///            connections.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func memoryReport() async throws -> MemoryReport {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<MemoryReport>(continuation)
+            ).toOpaque()
+
+            let callback = IrohMemoryReportCallback(
+                userdata: box,
+                on_success: { userdata, report in
+                    let box = Unmanaged<ContinuationBox<MemoryReport>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: MemoryReport(
+                        storeDiskBytes: report.store_disk_bytes,
+                        runtimeTasks: report.runtime_tasks,
+                        queuedEvents: report.queued_events,
+                        queuedCallbacks: report.queued_callbacks,
+                        openConnections: report.open_connections
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ContinuationBox<MemoryReport>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.memoryReportFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_node_memory_report(handle.pointer, callback)
+        }
+    }
+
+    /// Dial a peer without transferring content and report how it was reached.
+    ///
+    /// Use this before a large transfer to warn users when the provider
+    /// cannot be reached, or is only reachable through a relay. The
+    /// connection is closed right after the handshake.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// switch try await node.canReach(ticket) {
+    /// case .direct:
+    ///     break
+    /// case .relay:
+    ///     showWarning("This download may be slow")
+    /// case .unreachable(let reason):
+    ///     showError("The provider is offline: \(reason)")
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - nodeAddr: The peer's node ID, or a blob ticket whose address hints are used.
+    ///   - timeout: How long to wait for the dial. If nil, waits until the dial fails.
+    ///              Default: 10 seconds.
+    /// - Returns: How the peer was reached.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.reachabilityCheckFailed` if `nodeAddr` cannot be parsed.
+    public func canReach(_ nodeAddr: String, timeout: Duration? = .seconds(10)) async throws -> Reachability {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            nodeAddr.withCString { nodeAddrPtr in
+                let box = Unmanaged.passRetained(
+                    ContinuationBox<Reachability>(continuation)
+                ).toOpaque()
+
+                let callback = IrohCanReachCallback(
+                    userdata: box,
+                    on_success: { userdata, result in
+                        let box = Unmanaged<ContinuationBox<Reachability>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let latency = Duration.microseconds(Int64(result.latency_us))
+                        let reachability: Reachability
+                        switch result.reachability {
+                        case Direct:
+                            reachability = .direct(latency: latency)
+                        case Relay:
+                            reachability = .relay(latency: latency)
+                        default:
+                            reachability = .unreachable(reason: result.reason.map { String(cString: $0) } ?? "")
+                        }
+                        box.continuation.resume(returning: reachability)
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let box = Unmanaged<ContinuationBox<Reachability>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        box.continuation.resume(throwing: IrohError.reachabilityCheckFailed(message, operationId: currentOperationId()))
+                    }
+                )
+
+                iroh_can_reach(handle.pointer, nodeAddrPtr, timeout?.milliseconds ?? 0, callback)
+            }
+        }
+    }
+
+    /// Ask providers how much of a blob they have, without downloading it.
+    ///
+    /// Use this to pick download sources and to show accurate availability
+    /// before a transfer. Providers are probed at the same time and found by
+    /// node ID through discovery; one that cannot be reached, refuses the
+    /// request or does not answer in time is reported as `.unknown`.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let probes = try await node.probeProviders(hash: hash, nodeIds: candidates)
+    /// let sources = probes.filter { $0.availability.isComplete }.map(\.nodeId)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - hash: The blob's hash.
+    ///   - nodeIds: Node IDs of the candidate providers.
+    ///   - timeout: How long to wait for each provider. Default: 10 seconds.
+    /// - Returns: One answer per provider, in the order of `nodeIds`.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.providerProbeFailed` if the hash or a node ID is invalid.
+    public func probeProviders(
+        hash: String,
+        nodeIds: [String],
+        timeout: Duration = .seconds(10)
+    ) async throws -> [ProviderProbe] {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+        guard let probeHash = ffiHash(hash) else {
+            throw IrohError.providerProbeFailed("Invalid hash: \(hash)")
+        }
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ProviderProbeContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohProviderProbeCallback(
+                userdata: box,
+                on_provider: { userdata, probe in
+                    let box = Unmanaged<ProviderProbeContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more providers coming
+                    let availability: BlobAvailability
+                    switch probe.availability {
+                    case BlobComplete:
+                        availability = .complete(size: probe.size)
+                    case BlobPartial:
+                        availability = .partial(size: probe.size == 0 ? nil : probe.size)
+                    case BlobMissing:
+                        availability = .missing
+                    default:
+                        availability = .unknown(reason: probe.reason.map { String(cString: $0) } ?? "")
+                    }
+                    box.probes.append(ProviderProbe(
+                        nodeId: String(cString: probe.node_id!),
+                        availability: availability
+                    ))
+                },
+                on_complete: { userdata in
+                    let box = Unmanaged<ProviderProbeContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: box.probes)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ProviderProbeContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.providerProbeFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            // Duplicate the strings so the pointers stay valid for the whole call
+            let idPtrs = nodeIds.map { UnsafePointer<CChar>(strdup($0)) }
+            defer { idPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) } }
+            idPtrs.withUnsafeBufferPointer { buffer in
+                iroh_blob_probe_providers(
+                    handle.pointer, probeHash, buffer.baseAddress, UInt(buffer.count),
+                    max(timeout.milliseconds, 1), callback
+                )
+            }
+        }
+    }
+
+    /// Bytes exchanged with each peer since the node started.
+    ///
+    /// Counts connections that peers opened to this node, such as blob
+    /// fetches and document syncs they initiated, so an unknown peer pulling
+    /// large amounts of data shows up here. Connections this node opens,
+    /// such as its own downloads and syncs, are not counted, so
+    /// `bytesDownloaded` only covers what peers sent on their connections.
+    /// Byte counts are measured on the wire and include protocol overhead.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// for peer in try await node.peerTraffic() where peer.bytesUploaded > 100_000_000 {
+    ///     print("\(peer.nodeId) fetched \(peer.bytesUploaded) bytes")
+    /// }
+    /// ```
+    ///
+    /// - Returns: One entry per peer, largest upload first.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func peerTraffic() async throws -> [PeerTraffic] {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                PeerTrafficContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohPeerTrafficCallback(
+                userdata: box,
+                on_entry: { userdata, entry in
+                    let box = Unmanaged<PeerTrafficContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more entries coming
+                    box.peers.append(PeerTraffic(
+                        nodeId: String(cString: entry.node_id!),
+                        bytesUploaded: entry.bytes_uploaded,
+                        bytesDownloaded: entry.bytes_downloaded
+                    ))
+                },
+                on_complete: { userdata in
+                    let box = Unmanaged<PeerTrafficContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: box.peers)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<PeerTrafficContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.peerTrafficFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_peer_traffic_list(handle.pointer, callback)
+        }
+    }
+
+    /// Report the latency to each configured relay and the home relay.
+    ///
+    /// Use this to check that users land on the nearest relay in
+    /// multi-region deployments. Latencies come from the node's most recent
+    /// network report, which is refreshed periodically and after network
+    /// changes; relays stay unmeasured until the first report completes.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let report = try await node.relayReport()
+    /// if !report.isHomeRelayNearest {
+    ///     logger.warning("home relay \(report.homeRelay ?? "none") is not the nearest")
+    /// }
+    /// ```
+    ///
+    /// - Returns: The relay latencies and the home relay.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func relayReport() async throws -> RelayReport {
+        try ensureNotClosed()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                RelayReportContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohRelayReportCallback(
+                userdata: box,
+                on_relay: { userdata, relay in
+                    let box = Unmanaged<RelayReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more relays coming
+                    box.relays.append(RelayLatency(
+                        url: String(cString: relay.url!),
+                        latency: relay.is_measured ? .microseconds(Int64(relay.latency_us)) : nil,
+                        isPinned: relay.is_pinned
+                    ))
+                },
+                on_complete: { userdata, homeRelay in
+                    let box = Unmanaged<RelayReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: RelayReport(
+                        homeRelay: homeRelay.map { String(cString: $0) },
+                        relays: box.relays
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<RelayReportContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.relayReportFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_node_relay_report(handle.pointer, callback)
+        }
+    }
+
+    /// Pin the node to one of its relays regardless of latency, or unpin it.
+    ///
+    /// Use this where compliance requires relayed traffic to stay in a
+    /// region. While pinned, the node neither uses nor measures the other
+    /// relays; it moves to the pinned relay after its next network report,
+    /// which starts right away. The pin lasts until the node is closed, so
+    /// set it again after creating the node.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let report = try await node.relayReport()
+    /// if let euRelay = report.relays.first(where: { $0.url.contains(".eu") }) {
+    ///     try await node.setHomeRelay(euRelay.url)
+    /// }
+    /// ```
+    ///
+    /// - Parameter relayURL: URL of one of the relays in `relayReport()`, or
+    ///                       nil to let the node choose the fastest again.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.homeRelayFailed` if the relay is not configured.
+    public func setHomeRelay(_ relayURL: String?) async throws {
+        try ensureNotClosed()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<Void>(continuation)
+            ).toOpaque()
+
+            let callback = IrohCloseCallback(
+                userdata: box,
+                on_complete: { userdata in
+                    let box = Unmanaged<ContinuationBox<Void>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume()
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ContinuationBox<Void>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.homeRelayFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            withOptionalCString(relayURL) { relayPtr in
+                iroh_node_set_home_relay(handle.pointer, relayPtr, callback)
+            }
+        }
+    }
+
+    /// Watch the direct addresses peers can use to reach this node.
+    ///
+    /// Yields the current "ip:port" addresses first, then the full set
+    /// again whenever it changes, for example after a network change or a
+    /// new STUN observation. Use it to keep node address records published
+    /// elsewhere up to date. The stream finishes when the node closes.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// for try await addresses in try await node.directAddresses() {
+    ///     try await backend.publish(nodeId: nodeId, addresses: addresses)
+    /// }
+    /// ```
+    ///
+    /// - Returns: An async throwing stream of address sets.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func directAddresses() throws -> AsyncThrowingStream<[String], Error> {
+        try ensureNotClosed()
+
+        return AsyncThrowingStream(bufferingPolicy: .bufferingNewest(1)) { continuation in
+            let context = NodeStreamContext(continuation: continuation)
+            continuation.onTermination = { @Sendable _ in
+                context.cancel()
+            }
+
+            let contextPtr = Unmanaged.passRetained(context).toOpaque()
+            let callback = IrohDirectAddrsCallback(
+                userdata: contextPtr,
+                on_addrs: { userdata, addrs, len in
+                    // takeUnretainedValue - don't consume, more updates coming
+                    let ctx = Unmanaged<NodeStreamContext<[String]>>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()
+                    let buffer = UnsafeBufferPointer(start: addrs, count: Int(len))
+                    ctx.continuation.yield(buffer.map { String(cString: $0!) })
+                },
+                on_complete: { userdata in
+                    // takeRetainedValue - consume on terminal
+                    let ctx = Unmanaged<NodeStreamContext<[String]>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    ctx.continuation.finish()
+                },
+                on_failure: { userdata, errorPtr in
+                    // takeRetainedValue - consume on terminal
+                    let ctx = Unmanaged<NodeStreamContext<[String]>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    ctx.continuation.finish(throwing: IrohError.addressWatchFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            context.setHandle(iroh_node_watch_direct_addrs(handle.pointer, callback))
+        }
+    }
+
+    /// Follow everything happening on the node through one stream.
+    ///
+    /// Delivers connectivity changes, downloads starting and finishing,
+    /// expired entry prune passes, and neighbor and sync events of documents
+    /// opened on this node. Only events after subscribing are delivered. The
+    /// stream finishes when the node closes.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// for try await event in try await node.events() {
+    ///     switch event {
+    ///     case .connectivityChanged(let relayUrl, let addresses):
+    ///         status.isOnline = relayUrl != nil || !addresses.isEmpty
+    ///     case .downloadFinished(let hash, let operationId, let error?):
+    ///         logger.error("download of \(hash) failed: \(error), operation=\(operationId ?? 0)")
+    ///     default:
+    ///         break
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// - Returns: An async throwing stream of node events.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func events() throws -> AsyncThrowingStream<NodeEvent, Error> {
+        try ensureNotClosed()
+
+        return AsyncThrowingStream(bufferingPolicy: .bufferingNewest(100)) { continuation in
+            let context = NodeStreamContext(continuation: continuation)
+            continuation.onTermination = { @Sendable _ in
+                context.cancel()
+            }
+
+            let contextPtr = Unmanaged.passRetained(context).toOpaque()
+            let callback = IrohNodeEventCallback(
+                userdata: contextPtr,
+                on_event: { userdata, event in
+                    // takeUnretainedValue - don't consume, more events coming
+                    let ctx = Unmanaged<NodeStreamContext<NodeEvent>>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()
+                    ctx.continuation.yield(NodeEvent(from: event))
+                },
+                on_complete: { userdata in
+                    // takeRetainedValue - consume on terminal
+                    let ctx = Unmanaged<NodeStreamContext<NodeEvent>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    ctx.continuation.finish()
+                },
+                on_failure: { userdata, errorPtr in
+                    // takeRetainedValue - consume on terminal
+                    let ctx = Unmanaged<NodeStreamContext<NodeEvent>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    ctx.continuation.finish(throwing: IrohError.eventSubscriptionFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            context.setHandle(iroh_node_subscribe_events(handle.pointer, callback))
+        }
+    }
 }
 
 /// Validate and parse a ticket string without requiring a node.
@@ -81,6 +684,8 @@ public func validateTicket(_ ticket: String) async -> TicketInfo {
 
                     var hash: String?
                     var nodeId: String?
+                    var relayUrls: [String] = []
+                    var directAddresses: [String] = []
 
                     if info.is_valid {
                         if info.hash != nil {
@@ -91,13 +696,23 @@ public func validateTicket(_ ticket: String) async -> TicketInfo {
                             nodeId = String(cString: info.node_id)
                             iroh_string_free(UnsafeMutablePointer(mutating: info.node_id))
                         }
+                        // Borrowed for the duration of the callback
+                        for i in 0..<Int(info.relay_urls_len) {
+                            relayUrls.append(String(cString: info.relay_urls[i]!))
+                        }
+                        for i in 0..<Int(info.direct_addrs_len) {
+                            directAddresses.append(String(cString: info.direct_addrs[i]!))
+                        }
                     }
 
                     let ticketInfo = TicketInfo(
                         isValid: info.is_valid,
                         hash: hash,
                         nodeId: nodeId,
-                        isRecursive: info.is_recursive
+                        isRecursive: info.is_recursive,
+                        format: info.is_valid ? (info.format == HashSeq ? .hashSeq : .raw) : nil,
+                        relayUrls: relayUrls,
+                        directAddresses: directAddresses
                     )
                     box.continuation.resume(returning: ticketInfo)
                 }
@@ -108,6 +723,361 @@ public func validateTicket(_ ticket: String) async -> TicketInfo {
     }
 }
 
+/// Inspect a document ticket without joining the document.
+///
+/// Use this to warn users before they accept write access to someone
+/// else's document:
+/// ```swift
+/// let info = await inspectDocTicket(ticket)
+/// if info.grantsWrite {
+///     // Ask before joining with write access
+/// }
+/// ```
+///
+/// - Parameter ticket: The document ticket to inspect.
+/// - Returns: Information about the ticket.
+public func inspectDocTicket(_ ticket: String) async -> DocTicketInfo {
+    await withCheckedContinuation { continuation in
+        ticket.withCString { ticketPtr in
+            let box = Unmanaged.passRetained(
+                DocTicketContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohDocTicketInspectCallback(
+                userdata: box,
+                on_complete: { userdata, info in
+                    let box = Unmanaged<DocTicketContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+
+                    var namespaceId: String?
+                    if info.is_valid, info.namespace_id != nil {
+                        namespaceId = String(cString: info.namespace_id)
+                        iroh_string_free(UnsafeMutablePointer(mutating: info.namespace_id))
+                    }
+
+                    box.continuation.resume(returning: DocTicketInfo(
+                        isValid: info.is_valid,
+                        namespaceId: namespaceId,
+                        capability: info.is_valid ? (info.capability == Write ? .write : .read) : nil,
+                        peerCount: Int(info.peers_len)
+                    ))
+                }
+            )
+
+            iroh_doc_ticket_inspect(ticketPtr, callback)
+        }
+    }
+}
+
+/// Re-encode a ticket so it fits a lower-density QR code or is easier to read aloud.
+///
+/// Example usage:
+/// ```swift
+/// let qrText = try await encodeTicket(ticket, as: .qrAlphanumeric)
+/// ```
+///
+/// - Parameters:
+///   - ticket: A blob or doc ticket in any supported encoding.
+///   - encoding: The encoding to produce.
+/// - Returns: The encoded ticket string.
+/// - Throws: `IrohError.invalidTicket` if the ticket cannot be parsed.
+public func encodeTicket(_ ticket: String, as encoding: TicketEncoding) async throws -> String {
+    try await withCheckedThrowingContinuation { continuation in
+        ticket.withCString { ticketPtr in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<String>(continuation)
+            ).toOpaque()
+
+            let ffiEncoding: IrohTicketEncoding
+            switch encoding {
+            case .standard:
+                ffiEncoding = Standard
+            case .qrAlphanumeric:
+                ffiEncoding = QrAlphanumeric
+            case .zBase32:
+                ffiEncoding = ZBase32
+            }
+
+            iroh_ticket_encode(ticketPtr, ffiEncoding, ticketStringCallback(box))
+        }
+    }
+}
+
+/// Convert a ticket in any supported encoding back to the standard form.
+///
+/// - Parameter encoded: A ticket produced by `encodeTicket(_:as:)` or a standard ticket.
+/// - Returns: The standard ticket string, usable with `get(ticket:)` or `joinDoc`.
+/// - Throws: `IrohError.invalidTicket` if the string is not a valid ticket.
+public func decodeTicket(_ encoded: String) async throws -> String {
+    try await withCheckedThrowingContinuation { continuation in
+        encoded.withCString { encodedPtr in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<String>(continuation)
+            ).toOpaque()
+
+            iroh_ticket_decode(encodedPtr, ticketStringCallback(box))
+        }
+    }
+}
+
+/// Encrypt a ticket with a passphrase before sharing it over chat or email.
+///
+/// Anyone who intercepts the locked ticket also needs the passphrase, which
+/// should be shared another way, for example in person or by phone. The
+/// passphrase is stretched to slow down guessing, which takes a noticeable
+/// fraction of a second.
+///
+/// Example usage:
+/// ```swift
+/// let locked = try await lockTicket(ticket, passphrase: "correct horse")
+/// // Recipient:
+/// let ticket = try await unlockTicket(locked, passphrase: "correct horse")
+/// ```
+///
+/// - Parameters:
+///   - ticket: A blob or doc ticket in any supported encoding.
+///   - passphrase: The passphrase needed to unlock the ticket. Must not be empty.
+/// - Returns: The locked ticket string.
+/// - Throws: `IrohError.invalidTicket` if the ticket cannot be parsed or the
+///           passphrase is empty.
+public func lockTicket(_ ticket: String, passphrase: String) async throws -> String {
+    try await withCheckedThrowingContinuation { continuation in
+        ticket.withCString { ticketPtr in
+            passphrase.withCString { passphrasePtr in
+                let box = Unmanaged.passRetained(
+                    ContinuationBox<String>(continuation)
+                ).toOpaque()
+
+                iroh_ticket_lock(ticketPtr, passphrasePtr, ticketStringCallback(box))
+            }
+        }
+    }
+}
+
+/// Decrypt a ticket produced by `lockTicket(_:passphrase:)`.
+///
+/// - Parameters:
+///   - locked: The locked ticket string.
+///   - passphrase: The passphrase the ticket was locked with.
+/// - Returns: The standard ticket string, usable with `get(ticket:)` or `joinDoc`.
+/// - Throws: `IrohError.invalidTicket` if the passphrase is wrong or the
+///           locked ticket is damaged.
+public func unlockTicket(_ locked: String, passphrase: String) async throws -> String {
+    try await withCheckedThrowingContinuation { continuation in
+        locked.withCString { lockedPtr in
+            passphrase.withCString { passphrasePtr in
+                let box = Unmanaged.passRetained(
+                    ContinuationBox<String>(continuation)
+                ).toOpaque()
+
+                iroh_ticket_unlock(lockedPtr, passphrasePtr, ticketStringCallback(box))
+            }
+        }
+    }
+}
+
+/// Pack several tickets into one string, so a share that needs more than
+/// one ticket cannot lose any of them.
+///
+/// Example usage:
+/// ```swift
+/// let bundle = try await encodeTicketBundle([
+///     (label: "profile", ticket: profileDocTicket),
+///     (label: "avatar", ticket: avatarTicket),
+/// ])
+/// // Recipient:
+/// for entry in try await decodeTicketBundle(bundle) where entry.label == "avatar" {
+///     avatar = try await node.get(ticket: entry.ticket)
+/// }
+/// ```
+///
+/// - Parameter tickets: Blob or doc tickets in any supported encoding, each
+///   with a label of at most 255 bytes (empty for none). At most 255.
+/// - Returns: The bundle string.
+/// - Throws: `IrohError.invalidTicket` if a ticket cannot be parsed, a label
+///           is too long, or there are no or too many tickets.
+public func encodeTicketBundle(_ tickets: [(label: String, ticket: String)]) async throws -> String {
+    try await withCheckedThrowingContinuation { continuation in
+        let box = Unmanaged.passRetained(
+            ContinuationBox<String>(continuation)
+        ).toOpaque()
+
+        // Duplicate the strings so the pointers stay valid for the whole call
+        let labelPtrs = tickets.map { UnsafePointer<CChar>(strdup($0.label)) }
+        let ticketPtrs = tickets.map { UnsafePointer<CChar>(strdup($0.ticket)) }
+        defer {
+            labelPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) }
+            ticketPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) }
+        }
+        labelPtrs.withUnsafeBufferPointer { labels in
+            ticketPtrs.withUnsafeBufferPointer { ticketsBuffer in
+                iroh_ticket_bundle_encode(
+                    labels.baseAddress, ticketsBuffer.baseAddress, UInt(ticketsBuffer.count),
+                    ticketStringCallback(box)
+                )
+            }
+        }
+    }
+}
+
+/// Unpack the tickets of a bundle made with `encodeTicketBundle(_:)`.
+///
+/// - Parameter bundle: The bundle string, also accepted uppercased.
+/// - Returns: The tickets in bundle order, in their standard form.
+/// - Throws: `IrohError.invalidTicket` if the bundle is damaged.
+public func decodeTicketBundle(_ bundle: String) async throws -> [BundledTicket] {
+    try await withCheckedThrowingContinuation { continuation in
+        bundle.withCString { bundlePtr in
+            let box = Unmanaged.passRetained(
+                TicketBundleContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohTicketBundleCallback(
+                userdata: box,
+                on_entry: { userdata, entry in
+                    let box = Unmanaged<TicketBundleContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more tickets coming
+                    box.tickets.append(BundledTicket(
+                        label: String(cString: entry.label!),
+                        kind: entry.kind == BundledDoc ? .doc : .blob,
+                        ticket: String(cString: entry.ticket!)
+                    ))
+                },
+                on_complete: { userdata in
+                    let box = Unmanaged<TicketBundleContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: box.tickets)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<TicketBundleContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.invalidTicket(message, operationId: currentOperationId()))
+                }
+            )
+
+            iroh_ticket_bundle_decode(bundlePtr, callback)
+        }
+    }
+}
+
+/// Build the callback shared by ticket encode, decode, lock, unlock and bundling.
+private func ticketStringCallback(_ box: UnsafeMutableRawPointer) -> IrohCallback {
+    IrohCallback(
+        userdata: box,
+        on_success: { userdata, resultPtr in
+            let box = Unmanaged<ContinuationBox<String>>
+                .fromOpaque(userdata!)
+                .takeRetainedValue()
+            let result = String(cString: resultPtr!)
+            iroh_string_free(UnsafeMutablePointer(mutating: resultPtr))
+            box.continuation.resume(returning: result)
+        },
+        on_failure: { userdata, errorPtr in
+            let box = Unmanaged<ContinuationBox<String>>
+                .fromOpaque(userdata!)
+                .takeRetainedValue()
+            let message = String(cString: errorPtr!)
+            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+            box.continuation.resume(throwing: IrohError.invalidTicket(message, operationId: currentOperationId()))
+        }
+    )
+}
+
+/// List node, document and subscription handles that are still live.
+///
+/// Use this while debugging to find handles leaked across the Swift/Rust
+/// boundary, for example a document that was never closed or a
+/// subscription that was never cancelled. Debug builds of the Rust library
+/// record where each handle was created.
+///
+/// Example usage:
+/// ```swift
+/// for handle in liveHandles() where handle.kind == .subscription {
+///     print(handle.backtrace ?? "no backtrace in release builds")
+/// }
+/// ```
+///
+/// - Returns: Every live handle, oldest first.
+public func liveHandles() -> [LiveHandle] {
+    var handles: [LiveHandle] = []
+    withUnsafeMutablePointer(to: &handles) { handlesPtr in
+        let callback = IrohLiveHandlesCallback(
+            userdata: UnsafeMutableRawPointer(handlesPtr),
+            on_handle: { userdata, handle in
+                let handles = userdata!.assumingMemoryBound(to: [LiveHandle].self)
+                let kind: LiveHandle.Kind
+                switch handle.kind {
+                case NodeHandle:
+                    kind = .node
+                case DocHandle:
+                    kind = .doc
+                default:
+                    kind = .subscription
+                }
+                handles.pointee.append(LiveHandle(
+                    kind: kind,
+                    address: UInt(handle.address),
+                    createdAt: Date(timeIntervalSince1970: TimeInterval(handle.created_at) / 1_000_000),
+                    backtrace: handle.backtrace.map { String(cString: $0) }
+                ))
+            },
+            on_complete: { _ in }
+        )
+        iroh_debug_handles(callback)
+    }
+    return handles
+}
+
+/// Read operation counters without waiting.
+///
+/// Cheap enough to call whenever an analytics event is sent.
+///
+/// Example usage:
+/// ```swift
+/// let counters = operationCounters()
+/// analytics.track("sync", ["gets": counters.gets, "get_failures": counters.failures["iroh_get"] ?? 0])
+/// ```
+///
+/// - Returns: Totals since the process started, across all nodes.
+public func operationCounters() -> OperationCounters {
+    var collected = CollectedCounters()
+    withUnsafeMutablePointer(to: &collected) { collectedPtr in
+        let callback = IrohOperationCountersCallback(
+            userdata: UnsafeMutableRawPointer(collectedPtr),
+            on_failure_count: { userdata, operation, count in
+                let collected = userdata!.assumingMemoryBound(to: CollectedCounters.self)
+                collected.pointee.failures[String(cString: operation!)] = count
+            },
+            on_complete: { userdata, counters in
+                let collected = userdata!.assumingMemoryBound(to: CollectedCounters.self)
+                collected.pointee.totals = counters
+            }
+        )
+        iroh_operation_counters(callback)
+    }
+    let totals = collected.totals
+    return OperationCounters(
+        puts: totals.puts,
+        gets: totals.gets,
+        docSets: totals.doc_sets,
+        bytesIn: totals.bytes_in,
+        bytesOut: totals.bytes_out,
+        failures: collected.failures
+    )
+}
+
+/// What `operationCounters()` collects from the FFI callback.
+private struct CollectedCounters {
+    var failures: [String: UInt64] = [:]
+    var totals = IrohOperationCounters()
+}
+
 // MARK: - Internal Helpers
 
 /// Box for passing Swift continuations through FFI callbacks (for NodeInfo).
@@ -119,3 +1089,85 @@ private final class ContinuationBox<T>: @unchecked Sendable {
         self.continuation = continuation
     }
 }
+
+private final class PeerTrafficContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<[PeerTraffic], Error>
+    var peers: [PeerTraffic] = []
+
+    init(_ continuation: CheckedContinuation<[PeerTraffic], Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ProviderProbeContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<[ProviderProbe], Error>
+    var probes: [ProviderProbe] = []
+
+    init(_ continuation: CheckedContinuation<[ProviderProbe], Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class TicketBundleContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<[BundledTicket], Error>
+    var tickets: [BundledTicket] = []
+
+    init(_ continuation: CheckedContinuation<[BundledTicket], Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class RelayReportContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<RelayReport, Error>
+    var relays: [RelayLatency] = []
+
+    init(_ continuation: CheckedContinuation<RelayReport, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class DocTicketContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<DocTicketInfo, Never>
+
+    init(_ continuation: CheckedContinuation<DocTicketInfo, Never>) {
+        self.continuation = continuation
+    }
+}
+
+/// Internal context for a node stream backed by a subscription handle.
+private final class NodeStreamContext<Element: Sendable>: @unchecked Sendable {
+    let continuation: AsyncThrowingStream<Element, Error>.Continuation
+    private let lock = NSLock()
+    private var pointer: UnsafeMutablePointer<IrohSubscriptionHandle>?
+    private var cancelled = false
+
+    init(continuation: AsyncThrowingStream<Element, Error>.Continuation) {
+        self.continuation = continuation
+    }
+
+    func setHandle(_ handle: UnsafeMutablePointer<IrohSubscriptionHandle>?) {
+        lock.lock()
+        let cancelNow = cancelled
+        if !cancelNow {
+            pointer = handle
+        }
+        lock.unlock()
+
+        // The stream ended before the subscription started
+        if cancelNow, let handle {
+            iroh_subscription_cancel(handle)
+        }
+    }
+
+    func cancel() {
+        lock.lock()
+        cancelled = true
+        let toCancel = pointer
+        pointer = nil
+        lock.unlock()
+
+        if let toCancel {
+            iroh_subscription_cancel(toCancel)
+        }
+    }
+}
@@ -66,7 +66,8 @@ extension IrohNode {
 
         // Final progress notification
         Task { @MainActor in
-            onProgress(DownloadProgress(downloaded: UInt64(data.count), total: UInt64(data.count)))
+            let size = UInt64(data.count)
+            onProgress(DownloadProgress(downloaded: size, total: size, verified: size, stage: .verified))
         }
 
         return data
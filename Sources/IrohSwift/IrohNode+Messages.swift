@@ -0,0 +1,171 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohNode {
+    // MARK: - Direct Messages
+
+    /// Send a small message to a known peer running this library.
+    ///
+    /// Meant for control-plane signals between your own devices, such as
+    /// asking another device to sync a document you just changed. The call
+    /// returns once a subscriber on the peer received the message (see
+    /// `messages()`). Messages are not queued: a peer that is offline, does
+    /// not answer within 10 seconds or is not subscribed makes this throw.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// try await doc.set(author: author, key: "note", value: data)
+    /// try? await node.sendMessage(Data("sync".utf8), to: laptopNodeId)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - data: The payload, at most 64 KiB.
+    ///   - nodeAddr: The peer's node ID, or a blob ticket whose address hints are used.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.messageFailed` if the message was not delivered.
+    public func sendMessage(_ data: Data, to nodeAddr: String) async throws {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            nodeAddr.withCString { nodeAddrPtr in
+                data.withUnsafeBytes { buffer in
+                    let bytes = IrohBytes(
+                        data: buffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                        len: UInt(buffer.count)
+                    )
+
+                    let box = Unmanaged.passRetained(
+                        MessageSendContinuationBox(continuation)
+                    ).toOpaque()
+
+                    let callback = IrohCloseCallback(
+                        userdata: box,
+                        on_complete: { userdata in
+                            let box = Unmanaged<MessageSendContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            box.continuation.resume()
+                        },
+                        on_failure: { userdata, errorPtr in
+                            let box = Unmanaged<MessageSendContinuationBox>
+                                .fromOpaque(userdata!)
+                                .takeRetainedValue()
+                            let message = String(cString: errorPtr!)
+                            iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                            box.continuation.resume(throwing: IrohError.messageFailed(message, operationId: currentOperationId()))
+                        }
+                    )
+
+                    iroh_message_send(handle.pointer, nodeAddrPtr, bytes, callback)
+                }
+            }
+        }
+    }
+
+    /// Receive the messages peers send with `sendMessage(_:to:)`.
+    ///
+    /// Only messages received while subscribed are delivered; senders are
+    /// told when nobody is listening. Check `from` against the devices you
+    /// expect, since any peer that can reach this node may send. The stream
+    /// finishes when the node closes.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// for try await message in try node.messages() where trustedDevices.contains(message.from) {
+    ///     _ = try await node.syncStep(budget: .seconds(10))
+    /// }
+    /// ```
+    ///
+    /// - Returns: An async throwing stream of messages.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed.
+    public func messages() throws -> AsyncThrowingStream<DirectMessage, Error> {
+        try ensureNotClosed()
+
+        return AsyncThrowingStream(bufferingPolicy: .bufferingNewest(100)) { continuation in
+            let context = MessageStreamContext(continuation: continuation)
+            continuation.onTermination = { @Sendable _ in
+                context.cancel()
+            }
+
+            let contextPtr = Unmanaged.passRetained(context).toOpaque()
+            let callback = IrohMessageCallback(
+                userdata: contextPtr,
+                on_message: { userdata, from, payload in
+                    // takeUnretainedValue - don't consume, more messages coming
+                    let ctx = Unmanaged<MessageStreamContext>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()
+                    let data = payload.data.map { Data(bytes: $0, count: Int(payload.len)) } ?? Data()
+                    ctx.continuation.yield(DirectMessage(from: String(cString: from!), data: data))
+                },
+                on_complete: { userdata in
+                    // takeRetainedValue - consume on terminal
+                    let ctx = Unmanaged<MessageStreamContext>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    ctx.continuation.finish()
+                },
+                on_failure: { userdata, errorPtr in
+                    // takeRetainedValue - consume on terminal
+                    let ctx = Unmanaged<MessageStreamContext>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    ctx.continuation.finish(throwing: IrohError.messageFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            context.setHandle(iroh_node_subscribe_messages(handle.pointer, callback))
+        }
+    }
+}
+
+// MARK: - Internal Helpers
+
+private final class MessageSendContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<Void, Error>
+
+    init(_ continuation: CheckedContinuation<Void, Error>) {
+        self.continuation = continuation
+    }
+}
+
+/// Internal context for a message stream backed by a subscription handle.
+private final class MessageStreamContext: @unchecked Sendable {
+    let continuation: AsyncThrowingStream<DirectMessage, Error>.Continuation
+    private let lock = NSLock()
+    private var pointer: UnsafeMutablePointer<IrohSubscriptionHandle>?
+    private var cancelled = false
+
+    init(continuation: AsyncThrowingStream<DirectMessage, Error>.Continuation) {
+        self.continuation = continuation
+    }
+
+    func setHandle(_ handle: UnsafeMutablePointer<IrohSubscriptionHandle>?) {
+        lock.lock()
+        let cancelNow = cancelled
+        if !cancelNow {
+            pointer = handle
+        }
+        lock.unlock()
+
+        // The stream ended before the subscription started
+        if cancelNow, let handle {
+            iroh_subscription_cancel(handle)
+        }
+    }
+
+    func cancel() {
+        lock.lock()
+        cancelled = true
+        let toCancel = pointer
+        pointer = nil
+        lock.unlock()
+
+        if let toCancel {
+            iroh_subscription_cancel(toCancel)
+        }
+    }
+}
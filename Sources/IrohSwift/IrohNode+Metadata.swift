@@ -0,0 +1,202 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohNode {
+    // MARK: - Blob Metadata Operations
+
+    /// Add data with a metadata record and return a shareable ticket.
+    ///
+    /// The content and metadata are wrapped in a collection, so the returned
+    /// ticket is recursive. Receivers can read the metadata with
+    /// `getWithMetadata(ticket:)` or `metadata(forTicket:)`. With
+    /// `metadata.compression` set to `.zstd`, the content is stored and sent
+    /// compressed and `getWithMetadata(ticket:)` returns it decompressed.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let metadata = BlobMetadata(filename: "photo.jpg", contentType: "image/jpeg")
+    /// let ticket = try await node.put(imageData, metadata: metadata)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - data: The data to store.
+    ///   - metadata: The metadata record to attach.
+    /// - Returns: A ticket string for the wrapping collection.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.putFailed` if the data cannot be stored.
+    public func put(_ data: Data, metadata: BlobMetadata) async throws -> String {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        let createdAt = metadata.createdAt.map { UInt64(max(0, $0.timeIntervalSince1970)) } ?? 0
+        let compression: IrohCompression = metadata.compression == .zstd ? Zstd : Uncompressed
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                MetadataPutContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCallback(
+                userdata: box,
+                on_success: { userdata, ticketPtr in
+                    let box = Unmanaged<MetadataPutContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let ticket = String(cString: ticketPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: ticketPtr))
+                    box.continuation.resume(returning: ticket)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<MetadataPutContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.putFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            data.withUnsafeBytes { buffer in
+                let bytes = IrohBytes(
+                    data: buffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                    len: UInt(buffer.count)
+                )
+                withOptionalCString(metadata.filename) { filenamePtr in
+                    withOptionalCString(metadata.contentType) { contentTypePtr in
+                        iroh_put_with_metadata(
+                            handle.pointer, bytes, filenamePtr, contentTypePtr, createdAt,
+                            compression, callback
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    /// Download data from a ticket together with its metadata record.
+    ///
+    /// Plain tickets from `put(_:)` return their data with nil metadata.
+    ///
+    /// - Parameter ticket: The ticket string.
+    /// - Returns: The downloaded data and its metadata, if any.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.getFailed` if the download fails.
+    public func getWithMetadata(ticket: String) async throws -> BlobWithMetadata {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                MetadataGetContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohGetWithMetadataCallback(
+                userdata: box,
+                on_success: { userdata, ownedBytes, metadataPtr in
+                    let box = Unmanaged<MetadataGetContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let data = Data(bytes: ownedBytes.data, count: Int(ownedBytes.len))
+                    iroh_bytes_free(ownedBytes)
+                    let metadata = takeBlobMetadata(metadataPtr)
+                    box.continuation.resume(returning: BlobWithMetadata(data: data, metadata: metadata))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<MetadataGetContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.getFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            ticket.withCString { ticketPtr in
+                iroh_get_with_metadata(handle.pointer, ticketPtr, callback)
+            }
+        }
+    }
+
+    /// Fetch the metadata record for a ticket without downloading its content.
+    ///
+    /// Useful for showing a filename and type before the user accepts a download.
+    ///
+    /// - Parameter ticket: The ticket string.
+    /// - Returns: The metadata record, or nil if the ticket has none.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.getFailed` if the metadata cannot be fetched.
+    public func metadata(forTicket ticket: String) async throws -> BlobMetadata? {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                MetadataLookupContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohBlobMetadataCallback(
+                userdata: box,
+                on_success: { userdata, metadataPtr in
+                    let box = Unmanaged<MetadataLookupContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: takeBlobMetadata(metadataPtr))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<MetadataLookupContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.getFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            ticket.withCString { ticketPtr in
+                iroh_ticket_metadata(handle.pointer, ticketPtr, callback)
+            }
+        }
+    }
+}
+
+// MARK: - Helpers
+
+/// Convert and free a metadata record returned by the FFI layer.
+private func takeBlobMetadata(_ ptr: UnsafeMutablePointer<IrohBlobMetadata>?) -> BlobMetadata? {
+    guard let ptr else { return nil }
+    defer { iroh_blob_metadata_free(ptr) }
+
+    let record = ptr.pointee
+    return BlobMetadata(
+        filename: record.filename.map { String(cString: $0) },
+        contentType: record.content_type.map { String(cString: $0) },
+        createdAt: Date(timeIntervalSince1970: TimeInterval(record.created_at)),
+        compression: record.compression == Zstd ? .zstd : .uncompressed
+    )
+}
+
+// MARK: - Continuation Boxes
+
+private final class MetadataPutContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<String, Error>
+
+    init(_ continuation: CheckedContinuation<String, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class MetadataGetContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<BlobWithMetadata, Error>
+
+    init(_ continuation: CheckedContinuation<BlobWithMetadata, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class MetadataLookupContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<BlobMetadata?, Error>
+
+    init(_ continuation: CheckedContinuation<BlobMetadata?, Error>) {
+        self.continuation = continuation
+    }
+}
@@ -0,0 +1,379 @@
+import Foundation
+import IrohSwiftFFI
+
+extension IrohNode {
+    // MARK: - Profile Operations
+
+    /// Add bytes to the blob store for a profile.
+    ///
+    /// Profiles keep the content of several user accounts on one device
+    /// apart. The content is tagged `auto/<hash>` in the profile, so only
+    /// the profile keeps it; identical content of several profiles is
+    /// stored once.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let ticket = try await node.put(photo, profile: account.id)
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - data: The data to store.
+    ///   - profile: Profile ID of ASCII letters, digits, `-` and `_`, at most 64 bytes.
+    /// - Returns: A ticket string that can be used to retrieve the data.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.putFailed` if the profile ID is invalid or the operation fails.
+    public func put(_ data: Data, profile: String) async throws -> String {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            data.withUnsafeBytes { buffer in
+                let bytes = IrohBytes(
+                    data: buffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                    len: UInt(buffer.count)
+                )
+
+                let box = Unmanaged.passRetained(
+                    ProfilePutContinuationBox(continuation)
+                ).toOpaque()
+
+                let callback = IrohCallback(
+                    userdata: box,
+                    on_success: { userdata, ticketPtr in
+                        let box = Unmanaged<ProfilePutContinuationBox>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let ticket = String(cString: ticketPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: ticketPtr))
+                        box.continuation.resume(returning: ticket)
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let box = Unmanaged<ProfilePutContinuationBox>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        box.continuation.resume(throwing: IrohError.putFailed(message, operationId: currentOperationId()))
+                    }
+                )
+
+                profile.withCString { profilePtr in
+                    iroh_profile_put(handle.pointer, profilePtr, bytes, callback)
+                }
+            }
+        }
+    }
+
+    /// Tag a blob in a profile, so the profile keeps it.
+    ///
+    /// Use this for content that arrived by download. The store names the
+    /// tag `profiles/<profile>/<name>`.
+    ///
+    /// - Parameters:
+    ///   - hash: The blob hash (hex string from ticket or entry).
+    ///   - name: Tag name within the profile (e.g., "photos/beach").
+    ///   - format: Blob format (default: .raw).
+    ///   - profile: The profile ID.
+    /// - Throws: `IrohError.blobTagFailed` if tagging fails.
+    public func tagBlob(
+        hash: String,
+        name: String,
+        format: BlobFormat = .raw,
+        profile: String
+    ) async throws {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                ProfileTagContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCloseCallback(
+                userdata: box,
+                on_complete: { userdata in
+                    let box = Unmanaged<ProfileTagContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume()
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ProfileTagContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.blobTagFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            let ffiFormat: IrohBlobFormat = format == .raw ? Raw : HashSeq
+
+            profile.withCString { profilePtr in
+                name.withCString { namePtr in
+                    hash.withCString { hashPtr in
+                        iroh_profile_tag_set(handle.pointer, profilePtr, namePtr, hashPtr, ffiFormat, callback)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove a tag from a profile.
+    ///
+    /// The content stays stored until `collectGarbage(profile:)` reclaims it.
+    ///
+    /// - Parameters:
+    ///   - name: Tag name within the profile.
+    ///   - profile: The profile ID.
+    /// - Throws: `IrohError.blobUntagFailed` if removal fails.
+    public func untagBlob(name: String, profile: String) async throws {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+            let box = Unmanaged.passRetained(
+                ProfileTagContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohCloseCallback(
+                userdata: box,
+                on_complete: { userdata in
+                    let box = Unmanaged<ProfileTagContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume()
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ProfileTagContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.blobUntagFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            profile.withCString { profilePtr in
+                name.withCString { namePtr in
+                    iroh_profile_tag_delete(handle.pointer, profilePtr, namePtr, callback)
+                }
+            }
+        }
+    }
+
+    /// List a profile's tags with their stored sizes.
+    ///
+    /// Names are relative to the profile, as passed to
+    /// `tagBlob(hash:name:format:profile:)`.
+    ///
+    /// - Parameter profile: The profile ID.
+    /// - Returns: The profile's tags and their combined size.
+    /// - Throws: `IrohError.tagListFailed` if the tags cannot be read.
+    public func listTags(profile: String) async throws -> TagListing {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ProfileTagListContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohTagListCallback(
+                userdata: box,
+                on_entry: { userdata, entry in
+                    let box = Unmanaged<ProfileTagListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeUnretainedValue()  // Don't consume - more entries coming
+                    box.tags.append(TagSummary(
+                        name: String(cString: entry.name!),
+                        hash: String(cString: entry.hash!),
+                        format: entry.format == HashSeq ? .hashSeq : .raw,
+                        size: entry.size
+                    ))
+                },
+                on_complete: { userdata, totalSize in
+                    let box = Unmanaged<ProfileTagListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: TagListing(tags: box.tags, totalSize: totalSize))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ProfileTagListContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.tagListFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            profile.withCString { profilePtr in
+                iroh_profile_tag_list(handle.pointer, profilePtr, callback)
+            }
+        }
+    }
+
+    /// Count a profile's content and how much of it is the profile's alone.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let stats = try await node.profileStats(account.id)
+    /// print("Removing the account frees \(stats.uniqueSize) bytes")
+    /// ```
+    ///
+    /// - Parameter profile: The profile ID.
+    /// - Returns: Tag and blob counts and sizes.
+    /// - Throws: `IrohError.profileFailed` if the store cannot be inspected.
+    public func profileStats(_ profile: String) async throws -> ProfileStats {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ProfileStatsContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohProfileStatsCallback(
+                userdata: box,
+                on_success: { userdata, stats in
+                    let box = Unmanaged<ProfileStatsContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: ProfileStats(
+                        tags: stats.tags,
+                        blobs: stats.blobs,
+                        totalSize: stats.total_size,
+                        uniqueSize: stats.unique_size
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ProfileStatsContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.profileFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            profile.withCString { profilePtr in
+                iroh_profile_stats(handle.pointer, profilePtr, callback)
+            }
+        }
+    }
+
+    /// Delete content added to a profile that no tag references anymore.
+    ///
+    /// Content still used by other profiles, other tags or documents is
+    /// kept. Returns after the store's next garbage collection run, within
+    /// about a second. The run is also published as `NodeEvent.gcStarted`
+    /// and `NodeEvent.gcFinished`.
+    ///
+    /// - Parameter profile: The profile ID.
+    /// - Returns: The number and size of the deleted blobs.
+    /// - Throws: `IrohError.profileFailed` if the store cannot be inspected.
+    public func collectGarbage(profile: String) async throws -> ReclaimedContent {
+        try await reclaim(profile: profile, wipe: false)
+    }
+
+    /// Delete all of a profile's tags and the content only they referenced.
+    ///
+    /// Use this when a user account is removed from the device.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let reclaimed = try await node.wipeProfile(account.id)
+    /// print("Freed \(reclaimed.size) bytes")
+    /// ```
+    ///
+    /// - Parameter profile: The profile ID.
+    /// - Returns: The number and size of the deleted blobs.
+    /// - Throws: `IrohError.profileFailed` if the store cannot be inspected.
+    public func wipeProfile(_ profile: String) async throws -> ReclaimedContent {
+        try await reclaim(profile: profile, wipe: true)
+    }
+
+    private func reclaim(profile: String, wipe: Bool) async throws -> ReclaimedContent {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ReclaimContinuationBox(continuation)
+            ).toOpaque()
+
+            let callback = IrohReclaimCallback(
+                userdata: box,
+                on_success: { userdata, reclaimed in
+                    let box = Unmanaged<ReclaimContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: ReclaimedContent(
+                        blobs: reclaimed.blobs,
+                        size: reclaimed.size
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ReclaimContinuationBox>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.profileFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            profile.withCString { profilePtr in
+                if wipe {
+                    iroh_profile_wipe(handle.pointer, profilePtr, callback)
+                } else {
+                    iroh_profile_gc(handle.pointer, profilePtr, callback)
+                }
+            }
+        }
+    }
+}
+
+// MARK: - Continuation Boxes
+
+private final class ProfilePutContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<String, Error>
+
+    init(_ continuation: CheckedContinuation<String, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ProfileTagContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<Void, Error>
+
+    init(_ continuation: CheckedContinuation<Void, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ProfileTagListContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<TagListing, Error>
+    var tags: [TagSummary] = []
+
+    init(_ continuation: CheckedContinuation<TagListing, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ProfileStatsContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<ProfileStats, Error>
+
+    init(_ continuation: CheckedContinuation<ProfileStats, Error>) {
+        self.continuation = continuation
+    }
+}
+
+private final class ReclaimContinuationBox: @unchecked Sendable {
+    let continuation: CheckedContinuation<ReclaimedContent, Error>
+
+    init(_ continuation: CheckedContinuation<ReclaimedContent, Error>) {
+        self.continuation = continuation
+    }
+}
@@ -4,9 +4,29 @@ import IrohSwiftFFI
 /// Progress callback type for download operations.
 public typealias ProgressHandler = @Sendable (DownloadProgress) -> Void
 
+/// Sink that receives verified chunks during a streaming download.
+/// Throwing aborts the download and the error is rethrown to the caller.
+public typealias SinkHandler = @Sendable (Data) throws -> Void
+
 extension IrohNode {
     /// Download bytes from a ticket with progress reporting.
     ///
+    /// Progress reports both received and verified bytes. Once every byte
+    /// has arrived, a `.verifying` update is sent while the blob is
+    /// confirmed against its hash, and a final `.verified` update right
+    /// before the data is returned, so a progress bar can show a
+    /// "Verifying" state instead of sitting at 100%.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let data = try await node.get(ticket: ticket) { progress in
+    ///     Task { @MainActor in
+    ///         bar.value = progress.verifiedFraction ?? 0
+    ///         label.text = progress.stage == .verifying ? "Verifying…" : "Downloading…"
+    ///     }
+    /// }
+    /// ```
+    ///
     /// - Parameters:
     ///   - ticket: The ticket string obtained from another node's `put` call.
     ///   - onProgress: Called with progress updates during the download.
@@ -33,9 +53,20 @@ extension IrohNode {
                         let ctx = Unmanaged<ProgressContext>
                             .fromOpaque(userdata!)
                             .takeUnretainedValue()
+                        let stage: DownloadStage
+                        switch progress.stage {
+                        case DownloadVerifying:
+                            stage = .verifying
+                        case DownloadVerified:
+                            stage = .verified
+                        default:
+                            stage = .downloading
+                        }
                         let swiftProgress = DownloadProgress(
                             downloaded: progress.downloaded,
-                            total: progress.total
+                            total: progress.total,
+                            verified: progress.verified,
+                            stage: stage
                         )
                         ctx.onProgress(swiftProgress)
                     },
@@ -53,7 +84,7 @@ extension IrohNode {
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        ctx.continuation.resume(throwing: IrohError.getFailed(message))
+                        ctx.continuation.resume(throwing: IrohError.getFailed(message, operationId: currentOperationId()))
                     }
                 )
 
@@ -61,6 +92,83 @@ extension IrohNode {
             }
         }
     }
+
+    /// Download a blob, passing verified chunks to `sink` as they arrive.
+    ///
+    /// Content is streamed straight from the provider without being staged
+    /// in the store, so it can be written directly to an `OutputStream`,
+    /// socket, or decryptor. Chunks arrive in order. Only raw blob tickets
+    /// are supported.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let output = OutputStream(url: fileURL, append: false)!
+    /// output.open()
+    /// defer { output.close() }
+    ///
+    /// try await node.stream(ticket: ticket) { chunk in
+    ///     _ = chunk.withUnsafeBytes { output.write($0.bindMemory(to: UInt8.self).baseAddress!, maxLength: chunk.count) }
+    /// }
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - ticket: The ticket string obtained from another node's `put` call.
+    ///   - sink: Called with each verified chunk. Throw to abort the download.
+    /// - Returns: The total number of bytes passed to the sink.
+    /// - Throws: The error thrown by `sink`, `IrohError.nodeClosed` if the node
+    ///           is closed, or `IrohError.getFailed` if the download fails.
+    @discardableResult
+    public func stream(ticket: String, into sink: @escaping SinkHandler) async throws -> UInt64 {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+        return try await withCheckedThrowingContinuation { continuation in
+            ticket.withCString { ticketPtr in
+                let context = SinkContext(continuation: continuation, sink: sink)
+                let box = Unmanaged.passRetained(context).toOpaque()
+
+                let writer = IrohSinkWriter(
+                    userdata: box,
+                    write: { userdata, bytes in
+                        let ctx = Unmanaged<SinkContext>
+                            .fromOpaque(userdata!)
+                            .takeUnretainedValue()
+                        let chunk = Data(bytes: bytes.data, count: Int(bytes.len))
+                        do {
+                            try ctx.sink(chunk)
+                            return true
+                        } catch {
+                            ctx.sinkError = error
+                            return false
+                        }
+                    }
+                )
+
+                let callback = IrohSinkCallback(
+                    userdata: box,
+                    on_success: { userdata, totalBytes in
+                        let ctx = Unmanaged<SinkContext>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        ctx.continuation.resume(returning: totalBytes)
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let ctx = Unmanaged<SinkContext>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        if let sinkError = ctx.sinkError {
+                            ctx.continuation.resume(throwing: sinkError)
+                        } else {
+                            ctx.continuation.resume(throwing: IrohError.getFailed(message, operationId: currentOperationId()))
+                        }
+                    }
+                )
+
+                iroh_get_to_sink(handle.pointer, ticketPtr, writer, callback)
+            }
+        }
+    }
 }
 
 // MARK: - Internal Helpers
@@ -78,3 +186,19 @@ private final class ProgressContext: @unchecked Sendable {
         self.onProgress = onProgress
     }
 }
+
+/// Context for sink downloads, holding the continuation, the sink, and any
+/// error the sink threw.
+private final class SinkContext: @unchecked Sendable {
+    let continuation: CheckedContinuation<UInt64, Error>
+    let sink: SinkHandler
+    var sinkError: Error?
+
+    init(
+        continuation: CheckedContinuation<UInt64, Error>,
+        sink: @escaping SinkHandler
+    ) {
+        self.continuation = continuation
+        self.sink = sink
+    }
+}
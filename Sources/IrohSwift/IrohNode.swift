@@ -47,6 +47,18 @@ public actor IrohNode {
     let handle: NodeHandleWrapper
     private var isClosed = false
 
+    /// The node's public key, which peers use to reach it.
+    ///
+    /// Read once at creation, so it is available without `await` and after
+    /// the node is closed.
+    public nonisolated let nodeId: String
+
+    /// Whether the node was created with `docsEnabled`.
+    public nonisolated let isDocsEnabled: Bool
+
+    /// The directory holding the node's store and state.
+    public nonisolated let storageURL: URL
+
     /// Create a new Iroh node with the specified configuration.
     ///
     /// - Parameter config: Configuration options. Uses defaults if not specified.
@@ -60,13 +72,63 @@ public actor IrohNode {
             // Create the FFI config
             let storagePath = config.storagePath.path
 
-            // Helper to create node with optional relay URL
-            func createNode(pathPtr: UnsafePointer<CChar>, relayUrlPtr: UnsafePointer<CChar>?) {
+            // Helper to create node with optional relay URL, temp dir, telemetry endpoint,
+            // control socket, gateway URL, app identifier, announced tag prefix and test seed
+            func createNode(
+                pathPtr: UnsafePointer<CChar>,
+                relayUrlPtr: UnsafePointer<CChar>?,
+                tempDirPtr: UnsafePointer<CChar>?,
+                otlpEndpointPtr: UnsafePointer<CChar>?,
+                controlSocketPtr: UnsafePointer<CChar>?,
+                gatewayUrlPtr: UnsafePointer<CChar>?,
+                appIdPtr: UnsafePointer<CChar>?,
+                tagPrefixPtr: UnsafePointer<CChar>?,
+                testSeedPtr: UnsafePointer<CChar>?,
+                entropy: IrohEntropySource
+            ) {
                 let ffiConfig = IrohNodeConfig(
                     storage_path: pathPtr,
                     relay_enabled: config.relayEnabled,
                     custom_relay_url: relayUrlPtr,
-                    docs_enabled: config.docsEnabled
+                    docs_enabled: config.docsEnabled,
+                    temp_dir: tempDirPtr,
+                    ttl_prune_interval_ms: config.expiryPruneInterval?.milliseconds ?? 0,
+                    entropy: entropy,
+                    max_tls_tickets: UInt32(config.tlsTicketCacheSize ?? 0),
+                    idle_timeout_ms: config.idleTimeout?.milliseconds ?? 0,
+                    max_connections: UInt32(config.maxConnections ?? 0),
+                    discovery: makeDiscoveryProvider(config.discovery),
+                    disable_default_discovery: !config.defaultDiscoveryEnabled,
+                    storage_alert: makeStorageAlert(config.storageAlert),
+                    otlp_endpoint: otlpEndpointPtr,
+                    slow_operation_watchdog: makeSlowOperationWatchdog(config.slowOperationWatchdog),
+                    control_socket: controlSocketPtr,
+                    gateway_url: gatewayUrlPtr,
+                    blob_access: config.blobAccess.ffiMode,
+                    request_limits: IrohRequestLimits(
+                        requests_per_sec: UInt32(config.requestLimits?.requestsPerSecond ?? 0),
+                        max_concurrent: UInt32(config.requestLimits?.maxConcurrentTransfers ?? 0),
+                        bytes_per_sec: config.requestLimits?.bytesPerSecond ?? 0
+                    ),
+                    dedicated_callback_thread: config.dedicatedCallbackThread,
+                    app_id: appIdPtr,
+                    store_inlining: IrohStoreInlining(
+                        max_inline_data: config.storeInlining?.maxInlineDataSize ?? 0,
+                        max_inline_outboard: config.storeInlining?.maxInlineOutboardSize ?? 0
+                    ),
+                    content_announcer: makeContentAnnouncer(config.contentAnnouncer, tagPrefix: tagPrefixPtr),
+                    test_seed: testSeedPtr,
+                    worker_threads: UInt32(config.workerThreads ?? 0),
+                    max_blocking_threads: UInt32(config.maxBlockingThreads ?? 0),
+                    blob_store_recovery: config.blobStoreRecovery.ffiRecovery,
+                    docs_store_recovery: config.docsStoreRecovery.ffiRecovery,
+                    ephemeral: config.ephemeral,
+                    bandwidth_limits: IrohBandwidthLimits(
+                        wifi_upload_bytes_per_sec: config.bandwidthLimits?.wifi.uploadBytesPerSecond ?? 0,
+                        wifi_download_bytes_per_sec: config.bandwidthLimits?.wifi.downloadBytesPerSecond ?? 0,
+                        cellular_upload_bytes_per_sec: config.bandwidthLimits?.cellular.uploadBytesPerSecond ?? 0,
+                        cellular_download_bytes_per_sec: config.bandwidthLimits?.cellular.downloadBytesPerSecond ?? 0
+                    )
                 )
 
                 let box = Unmanaged.passRetained(
@@ -82,13 +144,34 @@ public actor IrohNode {
                         let wrapper = NodeHandleWrapper(pointer: handlePtr!)
                         box.continuation.resume(returning: wrapper)
                     },
+                    on_corrupt: { userdata, database, pathPtr, reasonPtr in
+                        let box = Unmanaged<ContinuationBox<NodeHandleWrapper>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let path = String(cString: pathPtr!)
+                        let reason = String(cString: reasonPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: pathPtr))
+                        iroh_string_free(UnsafeMutablePointer(mutating: reasonPtr))
+                        let store: StoreDatabase
+                        switch database {
+                        case StoreDatabaseDocs:
+                            store = .docs
+                        default:
+                            store = .blobs
+                        }
+                        box.continuation.resume(throwing: IrohError.storeCorrupted(
+                            database: store,
+                            path: path,
+                            reason: reason
+                        ))
+                    },
                     on_failure: { userdata, errorPtr in
                         let box = Unmanaged<ContinuationBox<NodeHandleWrapper>>
                             .fromOpaque(userdata!)
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        box.continuation.resume(throwing: IrohError.nodeCreationFailed(message))
+                        box.continuation.resume(throwing: IrohError.nodeCreationFailed(message, operationId: currentOperationId()))
                     }
                 )
 
@@ -96,16 +179,43 @@ public actor IrohNode {
             }
 
             storagePath.withCString { pathPtr in
-                if let relayUrl = config.customRelayUrl {
-                    relayUrl.withCString { relayUrlPtr in
-                        createNode(pathPtr: pathPtr, relayUrlPtr: relayUrlPtr)
+                withOptionalCString(config.customRelayUrl) { relayUrlPtr in
+                    withOptionalCString(config.tempDirectory?.path) { tempDirPtr in
+                        withOptionalCString(config.telemetryEndpoint?.absoluteString) { otlpEndpointPtr in
+                            withOptionalCString(config.controlSocket) { controlSocketPtr in
+                                withOptionalCString(config.gatewayUrl?.absoluteString) { gatewayUrlPtr in
+                                    withOptionalCString(config.appId) { appIdPtr in
+                                        withOptionalCString(config.contentAnnouncer?.tagPrefix) { tagPrefixPtr in
+                                            withOptionalCString(config.testSeed) { testSeedPtr in
+                                                withEntropySource(config.entropySource) { entropy in
+                                                    createNode(
+                                                        pathPtr: pathPtr,
+                                                        relayUrlPtr: relayUrlPtr,
+                                                        tempDirPtr: tempDirPtr,
+                                                        otlpEndpointPtr: otlpEndpointPtr,
+                                                        controlSocketPtr: controlSocketPtr,
+                                                        gatewayUrlPtr: gatewayUrlPtr,
+                                                        appIdPtr: appIdPtr,
+                                                        tagPrefixPtr: tagPrefixPtr,
+                                                        testSeedPtr: testSeedPtr,
+                                                        entropy: entropy
+                                                    )
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
                     }
-                } else {
-                    createNode(pathPtr: pathPtr, relayUrlPtr: nil)
                 }
             }
         }
         self.handle = wrapper
+        self.nodeId = takeFFIString(iroh_node_id(wrapper.pointer)) ?? ""
+        self.isDocsEnabled = iroh_node_docs_enabled(wrapper.pointer)
+        self.storageURL = takeFFIString(iroh_node_storage_path(wrapper.pointer))
+            .map { URL(fileURLWithPath: $0, isDirectory: true) } ?? config.storagePath
     }
 
     deinit {
@@ -121,35 +231,51 @@ public actor IrohNode {
     /// This is preferred over letting deinit handle cleanup when you need
     /// to await graceful shutdown completion.
     ///
+    /// Pass a deadline when time is limited, for example at app termination.
+    /// If shutdown has not finished by then, the remaining work is abandoned
+    /// so the app can exit; data already committed to the store is kept.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let graceful = try await node.close(deadline: .seconds(2))
+    /// if !graceful {
+    ///     logger.warning("node shutdown was cut short")
+    /// }
+    /// ```
+    ///
+    /// - Parameter deadline: How long to wait for a graceful shutdown.
+    ///                       If nil, waits as long as shutdown takes.
+    /// - Returns: `true` if the shutdown finished before the deadline.
     /// - Throws: `IrohError.closeFailed` if shutdown fails.
-    public func close() async throws {
-        guard !isClosed else { return }
+    @discardableResult
+    public func close(deadline: Duration? = nil) async throws -> Bool {
+        guard !isClosed else { return true }
         isClosed = true
 
-        try await withCheckedThrowingContinuation { (continuation: CheckedContinuation<Void, Error>) in
+        return try await withCheckedThrowingContinuation { continuation in
             let box = Unmanaged.passRetained(
-                VoidContinuationBox(continuation)
+                ContinuationBox<Bool>(continuation)
             ).toOpaque()
 
-            let callback = IrohCloseCallback(
+            let callback = IrohNodeCloseCallback(
                 userdata: box,
-                on_complete: { userdata in
-                    let box = Unmanaged<VoidContinuationBox>
+                on_complete: { userdata, graceful in
+                    let box = Unmanaged<ContinuationBox<Bool>>
                         .fromOpaque(userdata!)
                         .takeRetainedValue()
-                    box.continuation.resume()
+                    box.continuation.resume(returning: graceful)
                 },
                 on_failure: { userdata, errorPtr in
-                    let box = Unmanaged<VoidContinuationBox>
+                    let box = Unmanaged<ContinuationBox<Bool>>
                         .fromOpaque(userdata!)
                         .takeRetainedValue()
                     let message = String(cString: errorPtr!)
                     iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                    box.continuation.resume(throwing: IrohError.closeFailed(message))
+                    box.continuation.resume(throwing: IrohError.closeFailed(message, operationId: currentOperationId()))
                 }
             )
 
-            iroh_node_close(handle.pointer, callback)
+            iroh_node_close(handle.pointer, deadline?.milliseconds ?? 0, callback)
         }
     }
 
@@ -201,7 +327,7 @@ public actor IrohNode {
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        box.continuation.resume(throwing: IrohError.putFailed(message))
+                        box.continuation.resume(throwing: IrohError.putFailed(message, operationId: currentOperationId()))
                     }
                 )
 
@@ -235,7 +361,7 @@ public actor IrohNode {
                     ContinuationBox<String>(continuation)
                 ).toOpaque()
 
-                let callback = IrohCallback(
+                let callback = IrohPutWithOptionsCallback(
                     userdata: box,
                     on_success: { userdata, ticketPtr in
                         let box = Unmanaged<ContinuationBox<String>>
@@ -245,18 +371,19 @@ public actor IrohNode {
                         iroh_string_free(UnsafeMutablePointer(mutating: ticketPtr))
                         box.continuation.resume(returning: ticket)
                     },
+                    on_timeout: { userdata in
+                        let box = Unmanaged<ContinuationBox<String>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        box.continuation.resume(throwing: IrohError.timeout)
+                    },
                     on_failure: { userdata, errorPtr in
                         let box = Unmanaged<ContinuationBox<String>>
                             .fromOpaque(userdata!)
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        // Check if it's a timeout error
-                        if message.contains("timed out") {
-                            box.continuation.resume(throwing: IrohError.timeout)
-                        } else {
-                            box.continuation.resume(throwing: IrohError.putFailed(message))
-                        }
+                        box.continuation.resume(throwing: IrohError.putFailed(message, operationId: currentOperationId()))
                     }
                 )
 
@@ -268,6 +395,8 @@ public actor IrohNode {
     /// Download bytes from a ticket.
     ///
     /// This fetches the blob from the remote peer specified in the ticket.
+    /// A blob that is already complete on this device is returned without
+    /// contacting the peer, so it can be read offline.
     ///
     /// - Parameter ticket: The ticket string obtained from another node's `put` call.
     /// - Returns: The downloaded data.
@@ -299,7 +428,7 @@ public actor IrohNode {
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        box.continuation.resume(throwing: IrohError.getFailed(message))
+                        box.continuation.resume(throwing: IrohError.getFailed(message, operationId: currentOperationId()))
                     }
                 )
 
@@ -315,7 +444,8 @@ public actor IrohNode {
     ///   - options: Operation options including timeout.
     /// - Returns: The downloaded data.
     /// - Throws: `IrohError.nodeClosed` if the node is closed,
-    ///           `IrohError.timeout` if the operation times out,
+    ///           `IrohError.timeout` if the operation times out, after the
+    ///           transfer has been stopped,
     ///           `IrohError.getFailed` if the download fails.
     public func get(ticket: String, options: OperationOptions) async throws -> Data {
         try ensureNotClosed()
@@ -328,7 +458,7 @@ public actor IrohNode {
                     ContinuationBox<Data>(continuation)
                 ).toOpaque()
 
-                let callback = IrohGetCallback(
+                let callback = IrohGetWithOptionsCallback(
                     userdata: box,
                     on_success: { userdata, ownedBytes in
                         let box = Unmanaged<ContinuationBox<Data>>
@@ -338,18 +468,19 @@ public actor IrohNode {
                         iroh_bytes_free(ownedBytes)
                         box.continuation.resume(returning: data)
                     },
+                    on_timeout: { userdata in
+                        let box = Unmanaged<ContinuationBox<Data>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        box.continuation.resume(throwing: IrohError.timeout)
+                    },
                     on_failure: { userdata, errorPtr in
                         let box = Unmanaged<ContinuationBox<Data>>
                             .fromOpaque(userdata!)
                             .takeRetainedValue()
                         let message = String(cString: errorPtr!)
                         iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
-                        // Check if it's a timeout error
-                        if message.contains("timed out") {
-                            box.continuation.resume(throwing: IrohError.timeout)
-                        } else {
-                            box.continuation.resume(throwing: IrohError.getFailed(message))
-                        }
+                        box.continuation.resume(throwing: IrohError.getFailed(message, operationId: currentOperationId()))
                     }
                 )
 
@@ -357,24 +488,261 @@ public actor IrohNode {
             }
         }
     }
+
+    /// Download content that several providers offer, using the fastest path.
+    ///
+    /// Pass tickets for the same hash from different sources, such as a
+    /// server and peer devices.
+    ///
+    /// Example usage:
+    /// ```swift
+    /// let data = try await node.get(tickets: [serverTicket, peerTicket])
+    /// ```
+    ///
+    /// - Parameters:
+    ///   - tickets: Tickets that all refer to the same content.
+    ///   - strategy: How to use the providers (default: `.race`).
+    /// - Returns: The downloaded data.
+    /// - Throws: `IrohError.nodeClosed` if the node is closed,
+    ///           `IrohError.getFailed` if the tickets disagree or every provider fails.
+    public func get(tickets: [String], strategy: ProviderStrategy = .race) async throws -> Data {
+        try ensureNotClosed()
+        try Task.checkCancellation()
+
+        let ffiStrategy: IrohProviderStrategy = strategy == .race ? Race : Split
+
+        // Duplicate the strings so the pointers stay valid for the whole call
+        let ticketPtrs = tickets.map { UnsafePointer<CChar>(strdup($0)) }
+        defer { ticketPtrs.forEach { free(UnsafeMutablePointer(mutating: $0)) } }
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<Data>(continuation)
+            ).toOpaque()
+
+            let callback = IrohGetCallback(
+                userdata: box,
+                on_success: { userdata, ownedBytes in
+                    let box = Unmanaged<ContinuationBox<Data>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let data = Data(bytes: ownedBytes.data, count: Int(ownedBytes.len))
+                    iroh_bytes_free(ownedBytes)
+                    box.continuation.resume(returning: data)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ContinuationBox<Data>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohError.getFailed(message, operationId: currentOperationId()))
+                }
+            )
+
+            ticketPtrs.withUnsafeBufferPointer { buffer in
+                iroh_get_from_providers(
+                    handle.pointer, buffer.baseAddress, UInt(buffer.count), ffiStrategy, callback
+                )
+            }
+        }
+    }
 }
 
 // MARK: - Internal Helpers
 
-/// Box for passing Swift continuations through FFI callbacks.
-private final class ContinuationBox<T>: @unchecked Sendable {
-    let continuation: CheckedContinuation<T, Error>
+extension Duration {
+    /// Whole milliseconds, clamped at zero, for FFI parameters.
+    var milliseconds: UInt64 {
+        let components = self.components
+        let ms = components.seconds * 1000 + components.attoseconds / 1_000_000_000_000_000
+        return UInt64(max(ms, 0))
+    }
+}
 
-    init(_ continuation: CheckedContinuation<T, Error>) {
-        self.continuation = continuation
+/// Id of the Rust operation whose callback is running, or nil outside one.
+///
+/// Call it from a result callback to tag the error with the failed call.
+func currentOperationId() -> UInt64? {
+    let id = iroh_current_operation_id()
+    return id == 0 ? nil : id
+}
+
+/// Convert and free a string returned by a synchronous FFI getter.
+func takeFFIString(_ ptr: UnsafeMutablePointer<CChar>?) -> String? {
+    guard let ptr else { return nil }
+    defer { iroh_string_free(ptr) }
+    return String(cString: ptr)
+}
+
+/// Decode a hex hash for the FFI, or nil if `hex` is not a valid hash.
+func ffiHash(_ hex: String) -> IrohHash? {
+    var hash = IrohHash()
+    return iroh_hash_from_hex(hex, &hash) ? hash : nil
+}
+
+/// Encode a hash from the FFI as hex.
+func hexHash(_ hash: IrohHash) -> String {
+    takeFFIString(iroh_hash_to_hex(hash)) ?? ""
+}
+
+/// Call `body` with a C string for `value`, or null if `value` is nil.
+func withOptionalCString<R>(
+    _ value: String?,
+    _ body: (UnsafePointer<CChar>?) -> R
+) -> R {
+    guard let value else { return body(nil) }
+    return value.withCString { body($0) }
+}
+
+/// Call `body` with an FFI entropy source backed by `source`, or one that
+/// uses the system RNG if `source` is nil.
+///
+/// The source is only valid during `body`; key generation calls it synchronously.
+func withEntropySource<R>(
+    _ source: EntropySource?,
+    _ body: (IrohEntropySource) -> R
+) -> R {
+    guard let source else {
+        return body(IrohEntropySource(userdata: nil, fill: nil))
+    }
+    let box = EntropySourceBox(source)
+    return withExtendedLifetime(box) {
+        body(IrohEntropySource(
+            userdata: Unmanaged.passUnretained(box).toOpaque(),
+            fill: { userdata, buf, len in
+                let box = Unmanaged<EntropySourceBox>
+                    .fromOpaque(userdata!)
+                    .takeUnretainedValue()
+                return box.source(UnsafeMutableRawBufferPointer(start: buf, count: Int(len)))
+            }
+        ))
     }
 }
 
-/// Box for Void continuations (used by close callback).
-private final class VoidContinuationBox: @unchecked Sendable {
-    let continuation: CheckedContinuation<Void, Error>
+/// Build the FFI storage alert for `alert`, or an empty one if nil.
+///
+/// The alert is retained until Rust releases it.
+private func makeStorageAlert(_ alert: StorageAlert?) -> IrohStorageAlert {
+    guard let alert else {
+        return IrohStorageAlert(
+            userdata: nil,
+            threshold_bytes: 0,
+            check_interval_ms: 0,
+            on_threshold: nil,
+            release: nil
+        )
+    }
+
+    return IrohStorageAlert(
+        userdata: Unmanaged.passRetained(StorageAlertBox(alert)).toOpaque(),
+        threshold_bytes: alert.thresholdBytes,
+        check_interval_ms: alert.checkInterval.milliseconds,
+        on_threshold: { userdata, exceeded, sizeBytes in
+            // takeUnretainedValue - called on every crossing
+            let box = Unmanaged<StorageAlertBox>
+                .fromOpaque(userdata!)
+                .takeUnretainedValue()
+            box.alert.handler(exceeded, sizeBytes)
+        },
+        release: { userdata in
+            // release - consume on terminal
+            Unmanaged<StorageAlertBox>
+                .fromOpaque(userdata!)
+                .release()
+        }
+    )
+}
 
-    init(_ continuation: CheckedContinuation<Void, Error>) {
+/// Box for passing a storage alert through FFI callbacks.
+private final class StorageAlertBox: Sendable {
+    let alert: StorageAlert
+
+    init(_ alert: StorageAlert) {
+        self.alert = alert
+    }
+}
+
+/// Build the FFI watchdog for `watchdog`, or a disabled one if nil.
+///
+/// Slow operations are logged; no userdata is needed.
+private func makeSlowOperationWatchdog(_ watchdog: SlowOperationWatchdog?) -> IrohSlowOperationWatchdog {
+    IrohSlowOperationWatchdog(
+        userdata: nil,
+        threshold_ms: watchdog?.threshold.milliseconds ?? 0,
+        emit_events: watchdog?.emitsEvents ?? false,
+        on_slow: { _, operation in
+            let name = String(cString: operation.name!)
+            let stage = String(cString: operation.stage!)
+            IrohLogger.node.warning(
+                "\(name, privacy: .public): slow, operation=\(operation.operation_id), stage=\(stage, privacy: .public), elapsed=\(operation.elapsed_ms)ms"
+            )
+        },
+        release: nil
+    )
+}
+
+/// Build the FFI content announcer for `announcer`, or a disabled one if nil.
+///
+/// `tagPrefix` must stay valid until the node is created. The announcer is
+/// retained until Rust releases it.
+private func makeContentAnnouncer(
+    _ announcer: ContentAnnouncer?,
+    tagPrefix: UnsafePointer<CChar>?
+) -> IrohContentAnnouncer {
+    guard let announcer else {
+        return IrohContentAnnouncer(
+            userdata: nil,
+            interval_ms: 0,
+            tag_prefix: nil,
+            on_announce: nil,
+            release: nil
+        )
+    }
+
+    return IrohContentAnnouncer(
+        userdata: Unmanaged.passRetained(ContentAnnouncerBox(announcer)).toOpaque(),
+        interval_ms: announcer.interval.milliseconds,
+        tag_prefix: tagPrefix,
+        on_announce: { userdata, tagPtr, ticketPtr in
+            // takeUnretainedValue - called for every tag of every round
+            let box = Unmanaged<ContentAnnouncerBox>
+                .fromOpaque(userdata!)
+                .takeUnretainedValue()
+            box.announcer.handler?(String(cString: tagPtr!), String(cString: ticketPtr!))
+        },
+        release: { userdata in
+            // release - consume on terminal
+            Unmanaged<ContentAnnouncerBox>
+                .fromOpaque(userdata!)
+                .release()
+        }
+    )
+}
+
+/// Box for passing a content announcer through FFI callbacks.
+private final class ContentAnnouncerBox: Sendable {
+    let announcer: ContentAnnouncer
+
+    init(_ announcer: ContentAnnouncer) {
+        self.announcer = announcer
+    }
+}
+
+/// Box for passing an entropy source through FFI callbacks.
+private final class EntropySourceBox {
+    let source: EntropySource
+
+    init(_ source: @escaping EntropySource) {
+        self.source = source
+    }
+}
+
+/// Box for passing Swift continuations through FFI callbacks.
+private final class ContinuationBox<T>: @unchecked Sendable {
+    let continuation: CheckedContinuation<T, Error>
+
+    init(_ continuation: CheckedContinuation<T, Error>) {
         self.continuation = continuation
     }
 }
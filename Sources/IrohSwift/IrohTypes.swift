@@ -1,4 +1,16 @@
 import Foundation
+import IrohSwiftFFI
+
+/// What a download reporting progress is doing.
+public enum DownloadStage: Sendable, Equatable {
+    /// Bytes are arriving and being verified as they do.
+    case downloading
+    /// Every byte arrived; the store is confirming the whole blob against
+    /// its hash, which can take a moment for large blobs.
+    case verifying
+    /// The blob is verified and the download is about to return.
+    case verified
+}
 
 /// Progress information during a download operation.
 public struct DownloadProgress: Sendable {
@@ -6,12 +18,58 @@ public struct DownloadProgress: Sendable {
     public let downloaded: UInt64
     /// Total bytes expected (0 if unknown).
     public let total: UInt64
+    /// Bytes verified against the content hash so far.
+    public let verified: UInt64
+    /// What the download is doing.
+    public let stage: DownloadStage
+
+    public init(
+        downloaded: UInt64,
+        total: UInt64,
+        verified: UInt64 = 0,
+        stage: DownloadStage = .downloading
+    ) {
+        self.downloaded = downloaded
+        self.total = total
+        self.verified = verified
+        self.stage = stage
+    }
 
     /// Progress as a fraction (0.0 to 1.0), or nil if total is unknown.
     public var fraction: Double? {
         guard total > 0 else { return nil }
         return Double(downloaded) / Double(total)
     }
+
+    /// Verified bytes as a fraction (0.0 to 1.0), or nil if total is unknown.
+    /// Reaches 1.0 only once the whole blob is verified.
+    public var verifiedFraction: Double? {
+        guard total > 0 else { return nil }
+        return Double(verified) / Double(total)
+    }
+}
+
+/// Progress of importing a file into the blob store.
+public struct ImportProgress: Sendable, Equatable {
+    /// Bytes copied into the store so far.
+    public let copied: UInt64
+    /// Bytes hashed so far.
+    public let hashed: UInt64
+    /// Size of the file in bytes (0 until known).
+    public let total: UInt64
+
+    public init(copied: UInt64, hashed: UInt64, total: UInt64) {
+        self.copied = copied
+        self.hashed = hashed
+        self.total = total
+    }
+
+    /// Hashed bytes as a fraction (0.0 to 1.0), or nil if total is unknown.
+    /// Reaches 1.0 once the whole file is imported.
+    public var fraction: Double? {
+        guard total > 0 else { return nil }
+        return Double(hashed) / Double(total)
+    }
 }
 
 /// Information about an Iroh node.
@@ -22,6 +80,416 @@ public struct NodeInfo: Sendable {
     public let relayUrl: String?
     /// Whether the node is connected to the network.
     public let isConnected: Bool
+    /// The app identifier the node was created with, if any.
+    public let appId: String?
+    /// How well the network supports direct connections, or nil before the
+    /// first network report and on nodes without relays.
+    public let networkCondition: NetworkCondition?
+}
+
+/// How well the network supports direct connections, as measured by the
+/// node's periodic network reports.
+public enum NetworkCondition: Sendable, Equatable {
+    /// UDP works and hole punching should succeed.
+    case open
+    /// UDP works, but the NAT maps each destination to a different port, so
+    /// direct connections often fail and traffic goes through a relay.
+    case hardNat
+    /// UDP is blocked; all traffic goes through a relay and is slower.
+    case relayOnly
+    /// A captive portal intercepts traffic until the user signs in.
+    case captivePortal
+    /// Neither UDP nor any relay could be reached.
+    case offline
+
+    /// Create from FFI condition; nil if it is unknown.
+    init?(from ffiCondition: IrohNetworkCondition) {
+        switch ffiCondition {
+        case NetworkOpen:
+            self = .open
+        case NetworkHardNat:
+            self = .hardNat
+        case NetworkRelayOnly:
+            self = .relayOnly
+        case NetworkCaptivePortal:
+            self = .captivePortal
+        case NetworkOffline:
+            self = .offline
+        default:
+            return nil
+        }
+    }
+}
+
+/// Bytes exchanged with one peer since the node started.
+public struct PeerTraffic: Sendable {
+    /// The peer's node ID.
+    public let nodeId: String
+    /// Bytes sent to the peer on connections it opened.
+    public let bytesUploaded: UInt64
+    /// Bytes received from the peer on connections it opened. Content this
+    /// node downloads over connections it opens is not counted.
+    public let bytesDownloaded: UInt64
+}
+
+/// Who may fetch blobs from a node when a peer has no rule of its own.
+public enum BlobAccessMode: Sendable, Equatable {
+    /// Every peer that is not denied.
+    case open
+    /// Only peers that are allowed.
+    case allowList
+
+    var ffiMode: IrohBlobAccessMode {
+        switch self {
+        case .open:
+            return BlobAccessOpen
+        case .allowList:
+            return BlobAccessAllowList
+        }
+    }
+}
+
+/// Kind of network the device is on, which selects the node's bandwidth
+/// caps (see `IrohConfig.bandwidthLimits`).
+public enum NetworkKind: Sendable, Equatable {
+    /// Wi-Fi, Ethernet or another unmetered network.
+    case wifi
+    /// A cellular, usually metered, network.
+    case cellular
+
+    var ffiKind: IrohNetworkKind {
+        switch self {
+        case .wifi:
+            return NetworkKindWifi
+        case .cellular:
+            return NetworkKindCellular
+        }
+    }
+}
+
+/// One of a node's databases.
+public enum StoreDatabase: Sendable, Equatable, CustomStringConvertible {
+    /// The blob store's index of blobs and tags.
+    case blobs
+    /// The docs engine's replicas, authors and entries.
+    case docs
+
+    public var description: String {
+        switch self {
+        case .blobs:
+            return "blob store"
+        case .docs:
+            return "docs"
+        }
+    }
+}
+
+/// Whether one peer may fetch blobs from a node.
+public enum BlobAccessRule: Sendable, Equatable {
+    case allow
+    case deny
+}
+
+/// A node's blob access mode and peer rules (see `IrohNode.blobAccess()`).
+public struct BlobAccessList: Sendable, Equatable {
+    /// Who may fetch when a peer has no rule.
+    public let mode: BlobAccessMode
+    /// Node IDs that may fetch blobs, sorted.
+    public let allowed: [String]
+    /// Node IDs that may not fetch blobs, sorted.
+    public let denied: [String]
+}
+
+/// Something that happened on a node (see `IrohNode.events()`).
+public enum NodeEvent: Sendable, Equatable {
+    /// The home relay or the direct "ip:port" addresses changed.
+    case connectivityChanged(relayUrl: String?, directAddresses: [String])
+    /// A download started; `operationId` is the call that started it, if any.
+    case downloadStarted(hash: String, operationId: UInt64?)
+    /// A download ended; `error` is nil if it succeeded.
+    case downloadFinished(hash: String, operationId: UInt64?, error: String?)
+    /// A peer was refused a blob fetch by the node's access list.
+    case fetchRejected(peer: String)
+    /// A peer's blob request was refused by the node's request limits.
+    case fetchLimited(peer: String)
+    /// A pass removing expired document entries finished.
+    case expiredEntriesPruned(count: UInt64)
+    /// A peer joined a document's swarm.
+    case neighborUp(namespaceId: String, peer: String)
+    /// A peer left a document's swarm.
+    case neighborDown(namespaceId: String, peer: String)
+    /// A document sync with a peer ended; `error` is nil if it succeeded.
+    case syncFinished(namespaceId: String, peer: String, error: String?)
+    /// A document sync with a peer ended, changing the peer's status (see
+    /// `IrohDoc.peerSyncStatus()`).
+    case peerSyncChanged(namespaceId: String, status: PeerSyncStatus)
+    /// An outbox write was queued or settled (see `IrohDoc.enqueue(author:key:value:)`).
+    case outbox(id: UInt64, namespaceId: String, key: Data, status: OutboxStatus)
+    /// A durable peer confirmed a document's local writes up to
+    /// `confirmedThrough` (see `IrohDoc.setDurablePeers(_:)`).
+    case durableSync(namespaceId: String, peer: String, confirmedThrough: UInt64, backedUp: Bool)
+    /// An operation exceeded `IrohConfig.slowOperationWatchdog`'s threshold.
+    case slowOperation(operationId: UInt64, name: String, stage: String, elapsed: Duration)
+    /// `IrohConfig.contentAnnouncer` republished the node's discovery
+    /// records and announced `tags` tags.
+    case contentAnnounced(tags: UInt64)
+    /// A network report showed a different network condition, for example
+    /// UDP became blocked and transfers now go through a relay.
+    case networkConditionChanged(NetworkCondition)
+    /// A tag set with `IrohNode.tagBlob(hash:name:format:expiresAt:)`
+    /// expired and was removed.
+    case tagExpired(name: String, hash: String)
+    /// Garbage collection of a profile's content started, for `blobs`
+    /// blobs of `size` bytes no tag references anymore (see
+    /// `IrohNode.collectGarbage(profile:)` and `IrohNode.wipeProfile(_:)`).
+    case gcStarted(profile: String, blobs: UInt64, size: UInt64)
+    /// Garbage collection of a profile's content finished, deleting `blobs`
+    /// blobs of `size` bytes.
+    case gcFinished(profile: String, blobs: UInt64, size: UInt64)
+
+    /// Create from FFI event; strings are copied.
+    init(from ffiEvent: IrohNodeEvent) {
+        let string = { (ptr: UnsafePointer<CChar>?) in ptr.map { String(cString: $0) } }
+        let operationId = ffiEvent.operation_id == 0 ? nil : ffiEvent.operation_id
+        switch ffiEvent.event_type {
+        case NodeConnectivityChanged:
+            let addrs = UnsafeBufferPointer(start: ffiEvent.direct_addrs, count: Int(ffiEvent.direct_addrs_len))
+            self = .connectivityChanged(
+                relayUrl: string(ffiEvent.relay_url),
+                directAddresses: addrs.map { String(cString: $0!) }
+            )
+        case NodeDownloadStarted:
+            self = .downloadStarted(hash: hexHash(ffiEvent.hash), operationId: operationId)
+        case NodeDownloadFinished:
+            self = .downloadFinished(
+                hash: hexHash(ffiEvent.hash),
+                operationId: operationId,
+                error: string(ffiEvent.error)
+            )
+        case NodeFetchRejected:
+            self = .fetchRejected(peer: string(ffiEvent.peer_id) ?? "")
+        case NodeFetchLimited:
+            self = .fetchLimited(peer: string(ffiEvent.peer_id) ?? "")
+        case NodeExpiredPruned:
+            self = .expiredEntriesPruned(count: ffiEvent.removed)
+        case NodeNeighborUp:
+            self = .neighborUp(namespaceId: string(ffiEvent.namespace_id) ?? "", peer: string(ffiEvent.peer_id) ?? "")
+        case NodeNeighborDown:
+            self = .neighborDown(namespaceId: string(ffiEvent.namespace_id) ?? "", peer: string(ffiEvent.peer_id) ?? "")
+        case NodeOutboxQueued, NodeOutboxDelivered, NodeOutboxSuperseded, NodeOutboxFailed:
+            let status: OutboxStatus
+            switch ffiEvent.event_type {
+            case NodeOutboxQueued:
+                status = .queued
+            case NodeOutboxDelivered:
+                status = .delivered
+            case NodeOutboxSuperseded:
+                status = .superseded(by: string(ffiEvent.author_id) ?? "")
+            default:
+                status = .failed(string(ffiEvent.error) ?? "")
+            }
+            self = .outbox(
+                id: ffiEvent.item_id,
+                namespaceId: string(ffiEvent.namespace_id) ?? "",
+                key: Data(bytes: ffiEvent.key.data, count: Int(ffiEvent.key.len)),
+                status: status
+            )
+        case NodeDurableSync:
+            self = .durableSync(
+                namespaceId: string(ffiEvent.namespace_id) ?? "",
+                peer: string(ffiEvent.peer_id) ?? "",
+                confirmedThrough: ffiEvent.confirmed_through,
+                backedUp: ffiEvent.backed_up
+            )
+        case NodeSlowOperation:
+            self = .slowOperation(
+                operationId: ffiEvent.operation_id,
+                name: string(ffiEvent.operation_name) ?? "",
+                stage: string(ffiEvent.stage) ?? "",
+                elapsed: .milliseconds(ffiEvent.elapsed_ms)
+            )
+        case NodeContentAnnounced:
+            self = .contentAnnounced(tags: ffiEvent.announced_tags)
+        case NodeNetworkConditionChanged:
+            self = .networkConditionChanged(NetworkCondition(from: ffiEvent.network_condition) ?? .offline)
+        case NodePeerSyncChanged:
+            self = .peerSyncChanged(
+                namespaceId: string(ffiEvent.namespace_id) ?? "",
+                status: PeerSyncStatus(
+                    peer: string(ffiEvent.peer_id) ?? "",
+                    lastSuccess: ffiEvent.last_success,
+                    pendingEntries: ffiEvent.pending_entries,
+                    failureStreak: ffiEvent.failure_streak,
+                    lastError: string(ffiEvent.error)
+                )
+            )
+        case NodeTagExpired:
+            self = .tagExpired(name: string(ffiEvent.tag_name) ?? "", hash: hexHash(ffiEvent.hash))
+        case NodeGcStarted:
+            self = .gcStarted(profile: string(ffiEvent.profile_id) ?? "", blobs: ffiEvent.gc_blobs, size: ffiEvent.gc_size)
+        case NodeGcFinished:
+            self = .gcFinished(profile: string(ffiEvent.profile_id) ?? "", blobs: ffiEvent.gc_blobs, size: ffiEvent.gc_size)
+        default:
+            self = .syncFinished(
+                namespaceId: string(ffiEvent.namespace_id) ?? "",
+                peer: string(ffiEvent.peer_id) ?? "",
+                error: string(ffiEvent.error)
+            )
+        }
+    }
+}
+
+/// A node, document or subscription handle that has not been released.
+public struct LiveHandle: Sendable {
+    /// Kind of resource a handle refers to.
+    public enum Kind: Sendable {
+        case node
+        case doc
+        case subscription
+    }
+
+    /// What the handle refers to.
+    public let kind: Kind
+    /// The handle's address, for matching handles across calls.
+    public let address: UInt
+    /// When the handle was created.
+    public let createdAt: Date
+    /// Where the handle was created; nil in release builds of the Rust library.
+    public let backtrace: String?
+}
+
+/// Operation totals since the process started, across all nodes.
+public struct OperationCounters: Sendable, Equatable {
+    /// Successful puts.
+    public let puts: UInt64
+    /// Successful gets.
+    public let gets: UInt64
+    /// Successful document writes.
+    public let docSets: UInt64
+    /// Bytes passed in by puts.
+    public let bytesIn: UInt64
+    /// Bytes returned by gets.
+    public let bytesOut: UInt64
+    /// Failures by the Rust function that failed, like "iroh_get".
+    public let failures: [String: UInt64]
+}
+
+/// Outcome of a node health check.
+///
+/// Each error is nil if its check passed.
+public struct NodeHealth: Sendable {
+    /// Why the blob store did not accept a write.
+    public let storeError: String?
+    /// Why the node's runtime did not run a task.
+    public let runtimeError: String?
+    /// Why the endpoint is not bound.
+    public let endpointError: String?
+
+    /// Whether the blob store accepted a write.
+    public var isStoreWritable: Bool { storeError == nil }
+    /// Whether the node's runtime ran a task.
+    public var isRuntimeResponsive: Bool { runtimeError == nil }
+    /// Whether the endpoint is open and bound to a socket.
+    public var isEndpointBound: Bool { endpointError == nil }
+    /// Whether every check passed.
+    public var isHealthy: Bool { isStoreWritable && isRuntimeResponsive && isEndpointBound }
+}
+
+/// What a node is holding on to, for matching app memory use to it.
+public struct MemoryReport: Sendable {
+    /// Size on disk of the blob and document database files. This is not
+    /// memory use: it only bounds how much of the databases the store and
+    /// the OS page cache can hold in memory.
+    public let storeDiskBytes: UInt64
+    /// Tasks alive on the node's runtime.
+    public let runtimeTasks: UInt64
+    /// Events and messages buffered until every subscriber has them.
+    public let queuedEvents: UInt64
+    /// Callbacks waiting for the delivery thread.
+    public let queuedCallbacks: UInt64
+    /// Connections peers have open to this node.
+    public let openConnections: UInt64
+}
+
+/// How a peer was reached by a pre-flight dial.
+public enum Reachability: Sendable, Equatable {
+    /// Reached over a direct UDP path.
+    case direct(latency: Duration)
+    /// Reached only through a relay so far.
+    case relay(latency: Duration)
+    /// Not reached; holds the reason.
+    case unreachable(reason: String)
+
+    /// Whether the peer was reached at all.
+    public var isReachable: Bool {
+        if case .unreachable = self { return false }
+        return true
+    }
+}
+
+/// A message a peer sent with `IrohNode.sendMessage(_:to:)`.
+public struct DirectMessage: Sendable, Equatable {
+    /// The sender's node ID.
+    public let from: String
+    /// The message payload.
+    public let data: Data
+}
+
+/// How much of a blob a provider has, as reported by a probe.
+public enum BlobAvailability: Sendable, Equatable {
+    /// The provider has the whole blob.
+    case complete(size: UInt64)
+    /// The provider has part of the blob; `size` is nil if it has not verified it.
+    case partial(size: UInt64?)
+    /// The provider has none of the blob.
+    case missing
+    /// The provider could not be asked; holds the reason.
+    case unknown(reason: String)
+
+    /// Whether the provider can serve the whole blob.
+    public var isComplete: Bool {
+        if case .complete = self { return true }
+        return false
+    }
+}
+
+/// One provider's answer to `IrohNode.probeProviders(hash:nodeIds:timeout:)`.
+public struct ProviderProbe: Sendable, Equatable {
+    /// The provider's node ID.
+    public let nodeId: String
+    /// How much of the blob the provider has.
+    public let availability: BlobAvailability
+}
+
+/// Measured latency to one configured relay.
+public struct RelayLatency: Sendable {
+    /// The relay's URL.
+    public let url: String
+    /// Fastest probe to the relay, or nil if it has not been reached.
+    public let latency: Duration?
+    /// Whether the node is pinned to this relay with `IrohNode.setHomeRelay(_:)`.
+    public let isPinned: Bool
+}
+
+/// Latencies to the configured relays and the relay chosen as home.
+public struct RelayReport: Sendable {
+    /// The relay this node is reachable through, if any.
+    public let homeRelay: String?
+    /// Every configured relay, fastest first, unreached relays last.
+    public let relays: [RelayLatency]
+
+    /// The relay the node is pinned to, if any.
+    public var pinnedRelay: String? {
+        relays.first(where: \.isPinned)?.url
+    }
+
+    /// Whether the home relay is the fastest measured relay.
+    public var isHomeRelayNearest: Bool {
+        guard let homeRelay, let nearest = relays.first, nearest.latency != nil else {
+            return false
+        }
+        return nearest.url == homeRelay
+    }
 }
 
 /// Parsed ticket information.
@@ -62,6 +530,19 @@ public struct TicketInfo: Sendable {
     /// Whether this ticket points to a recursive collection.
     /// A recursive ticket can contain multiple related blobs.
     public let isRecursive: Bool
+
+    /// Format of the blob the ticket points to.
+    /// Nil if the ticket is invalid.
+    public let format: BlobFormat?
+
+    /// Relay servers the source node can be reached through,
+    /// like "https://euc1-1.relay.iroh.network./".
+    /// Empty if the ticket is invalid or has no relay.
+    public let relayUrls: [String]
+
+    /// Direct `ip:port` addresses of the source node.
+    /// Empty if the ticket is invalid or has no direct addresses.
+    public let directAddresses: [String]
 }
 
 /// Format of blob data.
@@ -75,3 +556,275 @@ public enum BlobFormat: UInt8, Sendable {
     /// Hash sequence (collection of blobs).
     case hashSeq = 1
 }
+
+/// How to download content that several providers offer.
+public enum ProviderStrategy: Sendable {
+    /// Use whichever provider connects first, falling back to the others.
+    case race
+    /// Split collection downloads across all providers in parallel.
+    /// Single blobs try providers in order.
+    case split
+}
+
+/// Sort order for blob listings.
+public enum BlobSort: Sendable {
+    /// By hash, which is stable but otherwise arbitrary.
+    case hash
+    /// Smallest blobs first.
+    case sizeAscending
+    /// Largest blobs first.
+    case sizeDescending
+    /// Most recently added first. Blobs with an unknown add time come last.
+    case newestFirst
+    /// Least recently added first. Blobs with an unknown add time come last.
+    case oldestFirst
+}
+
+/// A blob in the local store.
+public struct StoredBlob: Sendable {
+    /// The blob hash (hex string).
+    public let hash: String
+    /// Stored size in bytes.
+    public let size: UInt64
+    /// Whether the blob is fully stored.
+    public let isComplete: Bool
+    /// When the blob was put on this node, if known.
+    ///
+    /// Content gets an add time when it is put, downloaded, fetched from a
+    /// gateway or synced into a document; for collections only the root
+    /// has one. Parts of collections and content stored before add times
+    /// were recorded have none.
+    public let addedAt: Date?
+}
+
+/// One page of a blob listing.
+public struct BlobPage: Sendable {
+    /// Blobs on this page, in the requested order.
+    public let blobs: [StoredBlob]
+    /// Number of blobs in the store, across all pages.
+    public let totalCount: UInt64
+    /// Cursor for listing more pages of the same snapshot.
+    public let cursor: UInt64
+}
+
+/// A tag and the stored size of the content it keeps alive.
+public struct TagSummary: Sendable {
+    /// Tag name (e.g., "chat/attachments").
+    public let name: String
+    /// The tagged hash (hex string).
+    public let hash: String
+    /// Format of the tagged content.
+    public let format: BlobFormat
+    /// Stored size of all blobs reachable from the tag in bytes.
+    public let size: UInt64
+}
+
+/// Tags matching a prefix, with their combined size.
+public struct TagListing: Sendable {
+    /// Matching tags in name order.
+    public let tags: [TagSummary]
+    /// Stored size of all content kept alive by the matching tags.
+    ///
+    /// Blobs shared between several matching tags are counted once, so this
+    /// can be less than the sum of the individual tag sizes.
+    public let totalSize: UInt64
+}
+
+/// How much of a tag's or collection's content is shared with other references.
+///
+/// Answers "how much space will deleting this actually free?".
+public struct ReferenceReport: Sendable {
+    /// Number of distinct blobs reachable from the tag.
+    public let totalBlobs: UInt64
+    /// Number of those blobs also kept by other tags, temporary tags or documents.
+    public let sharedBlobs: UInt64
+    /// Total stored size of the tag's blobs in bytes.
+    public let totalSize: UInt64
+    /// Bytes that would be freed if the tag were deleted.
+    public let uniqueSize: UInt64
+}
+
+/// Content of a profile and its size.
+public struct ProfileStats: Sendable {
+    /// Number of tags in the profile.
+    public let tags: UInt64
+    /// Number of distinct blobs reachable from the profile's tags.
+    public let blobs: UInt64
+    /// Total stored size of those blobs in bytes.
+    public let totalSize: UInt64
+    /// Bytes that wiping the profile would free.
+    public let uniqueSize: UInt64
+}
+
+/// Content deleted from the blob store.
+public struct ReclaimedContent: Sendable {
+    /// Number of blobs deleted.
+    public let blobs: UInt64
+    /// Stored size of the deleted blobs in bytes.
+    public let size: UInt64
+}
+
+/// A blob referenced more than once by tags or collections.
+public struct DuplicateBlob: Sendable {
+    /// The blob hash (hex string).
+    public let hash: String
+    /// Stored size in bytes.
+    public let size: UInt64
+    /// Number of references, counting repeats inside one collection.
+    public let references: UInt64
+    /// Names of the tags the blob is reachable from, sorted.
+    public let tags: [String]
+
+    /// Bytes that storing each reference separately would take on top.
+    public var savedSize: UInt64 { size * (references - 1) }
+}
+
+/// How much identical content the store keeps only once.
+///
+/// Answers "why does storage grow slower than what users save?". Content
+/// counts as identical only when its hash matches.
+public struct DedupReport: Sendable {
+    /// References from tags to blobs, counting repeats inside collections.
+    public let references: UInt64
+    /// Distinct blobs reachable from any tag.
+    public let uniqueBlobs: UInt64
+    /// Distinct blobs referenced more than once.
+    public let duplicateBlobs: UInt64
+    /// Size of all references, as if each were stored separately.
+    public let referencedSize: UInt64
+    /// Size of the distinct blobs, as stored.
+    public let storedSize: UInt64
+    /// Blobs saving the most space, largest saving first.
+    public let duplicates: [DuplicateBlob]
+
+    /// Bytes saved by storing identical content once.
+    public var savedSize: UInt64 { referencedSize - storedSize }
+}
+
+/// Descriptive metadata stored alongside a blob.
+///
+/// Attach it with `put(_:metadata:)` so receivers know what they downloaded,
+/// and set `compression` to shrink compressible content such as JSON or logs
+/// on disk and in transit.
+public struct BlobMetadata: Sendable, Equatable {
+    /// Original filename.
+    public var filename: String?
+    /// MIME type of the content (e.g., "image/jpeg").
+    public var contentType: String?
+    /// When the content was created. Defaults to the put time if nil.
+    public var createdAt: Date?
+    /// How the content is stored. Downloads are decompressed automatically.
+    public var compression: BlobCompression
+
+    public init(
+        filename: String? = nil,
+        contentType: String? = nil,
+        createdAt: Date? = nil,
+        compression: BlobCompression = .uncompressed
+    ) {
+        self.filename = filename
+        self.contentType = contentType
+        self.createdAt = createdAt
+        self.compression = compression
+    }
+}
+
+/// Compression of content stored with a metadata record.
+public enum BlobCompression: Sendable {
+    /// Stored as given.
+    case uncompressed
+    /// Compressed with zstd. Content that would not get smaller is stored
+    /// as given, and its metadata says so.
+    case zstd
+}
+
+/// Downloaded content together with its metadata record.
+public struct BlobWithMetadata: Sendable {
+    /// The downloaded bytes.
+    public let data: Data
+    /// The metadata record, or nil if the ticket has none.
+    public let metadata: BlobMetadata?
+}
+
+/// Outcome of uploading or downloading a chunked file.
+public struct ChunkStats: Sendable, Equatable {
+    /// File size in bytes.
+    public let size: UInt64
+    /// Number of chunks in the file.
+    public let chunks: UInt64
+    /// Chunks that were not stored on this node before: stored by an
+    /// upload, or downloaded by a fetch.
+    public let newChunks: UInt64
+
+    init(from stats: IrohChunkStats) {
+        self.size = stats.size
+        self.chunks = stats.chunks
+        self.newChunks = stats.new_chunks
+    }
+}
+
+/// A ticket for a chunked file and the chunks its upload stored.
+public struct ChunkedUpload: Sendable {
+    /// Ticket for the file, to fetch with `getChunkedFile(ticket:to:)`.
+    public let ticket: String
+    public let stats: ChunkStats
+}
+
+/// Alternate text encoding for blob and doc tickets.
+///
+/// Standard tickets are lowercase base32, which forces QR codes into the
+/// dense byte mode. The alternate forms are easier to scan or read aloud and
+/// can be converted back with `decodeTicket(_:)`.
+public enum TicketEncoding: Sendable {
+    /// The standard lowercase ticket.
+    case standard
+    /// Uppercase ticket that fits the QR alphanumeric mode.
+    case qrAlphanumeric
+    /// z-base-32 payload that avoids easily confused characters.
+    case zBase32
+}
+
+/// A ticket packed into a bundle with `encodeTicketBundle(_:)`.
+public struct BundledTicket: Sendable, Equatable {
+    /// Kind of a bundled ticket.
+    public enum Kind: Sendable, Equatable {
+        /// A blob ticket, for `get(ticket:)`.
+        case blob
+        /// A doc ticket, for `joinDoc(ticket:)`.
+        case doc
+    }
+
+    /// What the ticket is for, like "avatar"; empty if unlabeled.
+    public let label: String
+    /// Whether this is a blob or a doc ticket.
+    public let kind: Kind
+    /// The ticket in its standard form.
+    public let ticket: String
+}
+
+/// Summary of pending sync and download work.
+///
+/// Read from a record kept next to the store, without dialing any peers.
+/// Useful in a silent push handler to decide whether a full sync is worth
+/// starting.
+public struct PendingWork: Sendable {
+    /// Whether any pending work was found.
+    public let hasPendingWork: Bool
+    /// Number of documents with local writes no peer has synced yet.
+    public let unsyncedDocs: UInt64
+    /// Number of downloads that have not finished.
+    public let incompleteDownloads: UInt64
+}
+
+/// Outcome of a time-bounded background sync step.
+public struct SyncStepReport: Sendable {
+    /// Whether all work finished before the budget ran out and no pending
+    /// work is left.
+    public let completed: Bool
+    /// Number of documents whose sync with known peers finished.
+    public let docsSynced: UInt64
+    /// Number of entries received from peers.
+    public let entriesReceived: UInt64
+    /// Number of missing content blobs that were downloaded.
+    public let blobsDownloaded: UInt64
+}
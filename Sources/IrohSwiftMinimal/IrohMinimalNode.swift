@@ -0,0 +1,244 @@
+import Foundation
+import IrohSwiftFFIMinimal
+
+/// Errors thrown by `IrohMinimalNode`.
+public enum IrohMinimalError: Error, Sendable, Equatable {
+    /// The node could not be created.
+    case nodeCreationFailed(String)
+    /// Adding data to the blob store failed.
+    case putFailed(String)
+    /// Downloading data from a ticket failed.
+    case getFailed(String)
+    /// The node has been closed.
+    case nodeClosed
+    /// Closing the node failed.
+    case closeFailed(String)
+}
+
+/// Blob-only Iroh node for targets such as share extensions, backed by
+/// `IrohSwiftFFIMinimal`, the library built without documents, SQLite
+/// export and download progress.
+///
+/// Only put and get are available; use `IrohSwift` for everything else.
+/// Don't link both products into one target, since the two libraries
+/// export the same C symbols.
+///
+/// ```swift
+/// let node = try await IrohMinimalNode(storagePath: containerURL)
+/// let ticket = try await node.put(data)
+/// try await node.close()
+/// ```
+public actor IrohMinimalNode {
+    private let handle: MinimalHandleWrapper
+    private var isClosed = false
+
+    /// Create a node storing blobs under `storagePath`.
+    ///
+    /// - Parameters:
+    ///   - storagePath: Directory holding the node's store and state.
+    ///   - relayEnabled: Whether to connect through relays when peers can't
+    ///     be reached directly.
+    /// - Throws: `IrohMinimalError.nodeCreationFailed` if the node cannot be
+    ///           created.
+    public init(storagePath: URL, relayEnabled: Bool = true) async throws {
+        // The JSON config is used because the struct's layout follows the
+        // full library's releases
+        let config: [String: Any] = [
+            "version": 1,
+            "storage_path": storagePath.path,
+            "relay_enabled": relayEnabled,
+        ]
+        let json = String(
+            decoding: try JSONSerialization.data(withJSONObject: config),
+            as: UTF8.self
+        )
+
+        handle = try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<MinimalHandleWrapper>(continuation)
+            ).toOpaque()
+
+            let callback = IrohNodeCreateCallback(
+                userdata: box,
+                on_success: { userdata, handlePtr in
+                    let box = Unmanaged<ContinuationBox<MinimalHandleWrapper>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: MinimalHandleWrapper(pointer: handlePtr!))
+                },
+                on_corrupt: { userdata, _, pathPtr, reasonPtr in
+                    let box = Unmanaged<ContinuationBox<MinimalHandleWrapper>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let path = String(cString: pathPtr!)
+                    let reason = String(cString: reasonPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: pathPtr))
+                    iroh_string_free(UnsafeMutablePointer(mutating: reasonPtr))
+                    box.continuation.resume(throwing: IrohMinimalError.nodeCreationFailed(
+                        "\(path) is corrupt: \(reason)"
+                    ))
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ContinuationBox<MinimalHandleWrapper>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohMinimalError.nodeCreationFailed(message))
+                }
+            )
+
+            json.withCString { jsonPtr in
+                iroh_node_create_from_json(jsonPtr, callback)
+            }
+        }
+    }
+
+    deinit {
+        // Only destroy if not already closed
+        if !isClosed {
+            iroh_node_destroy(handle.pointer)
+        }
+    }
+
+    /// Close the node and release its resources.
+    ///
+    /// - Returns: False if the deadline passed before shutdown finished.
+    /// - Throws: `IrohMinimalError.closeFailed` if shutdown fails.
+    @discardableResult
+    public func close(deadline: Duration? = nil) async throws -> Bool {
+        guard !isClosed else { return true }
+        isClosed = true
+
+        return try await withCheckedThrowingContinuation { continuation in
+            let box = Unmanaged.passRetained(
+                ContinuationBox<Bool>(continuation)
+            ).toOpaque()
+
+            let callback = IrohNodeCloseCallback(
+                userdata: box,
+                on_complete: { userdata, graceful in
+                    let box = Unmanaged<ContinuationBox<Bool>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    box.continuation.resume(returning: graceful)
+                },
+                on_failure: { userdata, errorPtr in
+                    let box = Unmanaged<ContinuationBox<Bool>>
+                        .fromOpaque(userdata!)
+                        .takeRetainedValue()
+                    let message = String(cString: errorPtr!)
+                    iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                    box.continuation.resume(throwing: IrohMinimalError.closeFailed(message))
+                }
+            )
+
+            iroh_node_close(handle.pointer, deadline?.milliseconds ?? 0, callback)
+        }
+    }
+
+    /// Add bytes to the blob store and return a shareable ticket.
+    ///
+    /// - Throws: `IrohMinimalError.nodeClosed` if the node is closed,
+    ///           `IrohMinimalError.putFailed` if the operation fails.
+    public func put(_ data: Data) async throws -> String {
+        guard !isClosed else { throw IrohMinimalError.nodeClosed }
+        try Task.checkCancellation()
+        return try await withCheckedThrowingContinuation { continuation in
+            data.withUnsafeBytes { buffer in
+                let bytes = IrohBytes(
+                    data: buffer.baseAddress?.assumingMemoryBound(to: UInt8.self),
+                    len: UInt(buffer.count)
+                )
+
+                let box = Unmanaged.passRetained(
+                    ContinuationBox<String>(continuation)
+                ).toOpaque()
+
+                let callback = IrohCallback(
+                    userdata: box,
+                    on_success: { userdata, ticketPtr in
+                        let box = Unmanaged<ContinuationBox<String>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let ticket = String(cString: ticketPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: ticketPtr))
+                        box.continuation.resume(returning: ticket)
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let box = Unmanaged<ContinuationBox<String>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        box.continuation.resume(throwing: IrohMinimalError.putFailed(message))
+                    }
+                )
+
+                iroh_put(handle.pointer, bytes, callback)
+            }
+        }
+    }
+
+    /// Download the bytes a ticket refers to.
+    ///
+    /// - Throws: `IrohMinimalError.nodeClosed` if the node is closed,
+    ///           `IrohMinimalError.getFailed` if the download fails.
+    public func get(ticket: String) async throws -> Data {
+        guard !isClosed else { throw IrohMinimalError.nodeClosed }
+        try Task.checkCancellation()
+        return try await withCheckedThrowingContinuation { continuation in
+            ticket.withCString { ticketPtr in
+                let box = Unmanaged.passRetained(
+                    ContinuationBox<Data>(continuation)
+                ).toOpaque()
+
+                let callback = IrohGetCallback(
+                    userdata: box,
+                    on_success: { userdata, ownedBytes in
+                        let box = Unmanaged<ContinuationBox<Data>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let data = Data(bytes: ownedBytes.data, count: Int(ownedBytes.len))
+                        iroh_bytes_free(ownedBytes)
+                        box.continuation.resume(returning: data)
+                    },
+                    on_failure: { userdata, errorPtr in
+                        let box = Unmanaged<ContinuationBox<Data>>
+                            .fromOpaque(userdata!)
+                            .takeRetainedValue()
+                        let message = String(cString: errorPtr!)
+                        iroh_string_free(UnsafeMutablePointer(mutating: errorPtr))
+                        box.continuation.resume(throwing: IrohMinimalError.getFailed(message))
+                    }
+                )
+
+                iroh_get(handle.pointer, ticketPtr, callback)
+            }
+        }
+    }
+}
+
+private extension Duration {
+    /// Whole milliseconds, clamped at zero, for FFI parameters.
+    var milliseconds: UInt64 {
+        let components = self.components
+        let ms = components.seconds * 1000 + components.attoseconds / 1_000_000_000_000_000
+        return UInt64(max(ms, 0))
+    }
+}
+
+/// Box for passing Swift continuations through FFI callbacks.
+private final class ContinuationBox<T>: @unchecked Sendable {
+    let continuation: CheckedContinuation<T, Error>
+
+    init(_ continuation: CheckedContinuation<T, Error>) {
+        self.continuation = continuation
+    }
+}
+
+/// Sendable wrapper for the node handle pointer, only used through actor
+/// isolation.
+private struct MinimalHandleWrapper: @unchecked Sendable {
+    let pointer: UnsafeMutablePointer<IrohNodeHandle>
+}
@@ -71,6 +71,236 @@ struct ErrorPathTests {
         }
     }
 
+    @Test("Non-positive idle timeout throws invalidConfiguration")
+    func testNonPositiveIdleTimeout() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.idleTimeout = .zero
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("Idle timeout"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Zero connection limit throws invalidConfiguration")
+    func testZeroMaxConnections() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.maxConnections = 0
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("Maximum connections"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Zero worker threads throws invalidConfiguration")
+    func testZeroWorkerThreads() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.workerThreads = 0
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("Worker threads"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Zero storage alert threshold throws invalidConfiguration")
+    func testZeroStorageAlertThreshold() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.storageAlert = StorageAlert(thresholdBytes: 0) { _, _ in }
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("Storage alert threshold"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Non-HTTP telemetry endpoint throws invalidConfiguration")
+    func testInvalidTelemetryEndpoint() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.telemetryEndpoint = URL(string: "grpc://otel.example.com:4317")
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("telemetry endpoint"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Empty control socket throws invalidConfiguration")
+    func testEmptyControlSocket() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.controlSocket = ""
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("Control socket"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Plain HTTP gateway throws invalidConfiguration")
+    func testInsecureGatewayUrl() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.gatewayUrl = URL(string: "http://gateway.example.com/blobs")
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("gateway URL"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Zero request rate limit throws invalidConfiguration")
+    func testZeroRequestRateLimit() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.requestLimits = RequestLimits(requestsPerSecond: 0)
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("Request rate limit"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Zero bandwidth cap throws invalidConfiguration")
+    func testZeroBandwidthCap() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.bandwidthLimits = BandwidthLimits(cellular: BandwidthCaps(uploadBytesPerSecond: 0))
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("Bandwidth caps"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
+    @Test("Sub-millisecond watchdog threshold throws invalidConfiguration")
+    func testZeroWatchdogThreshold() async throws {
+        // Use a temp directory to ensure storage validation passes
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        defer { try? FileManager.default.removeItem(at: tempDir) }
+
+        var config = IrohConfig(storagePath: tempDir)
+        config.slowOperationWatchdog = SlowOperationWatchdog(threshold: .microseconds(500))
+
+        do {
+            try config.validate()
+            #expect(Bool(false), "Should have thrown invalidConfiguration")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidConfiguration(let msg):
+                #expect(msg.contains("Slow operation threshold"))
+            default:
+                #expect(Bool(false), "Expected invalidConfiguration, got \(error)")
+            }
+        }
+    }
+
     @Test("Valid config with custom relay passes validation")
     func testValidConfigWithCustomRelay() async throws {
         // Use a temp directory for testing
@@ -93,6 +323,9 @@ struct ErrorPathTests {
         #expect(!info.isValid)
         #expect(info.hash == nil)
         #expect(info.nodeId == nil)
+        #expect(info.format == nil)
+        #expect(info.relayUrls.isEmpty)
+        #expect(info.directAddresses.isEmpty)
     }
 
     @Test("Empty ticket returns isValid=false")
@@ -108,6 +341,21 @@ struct ErrorPathTests {
         #expect(!info.isValid)
     }
 
+    @Test("Decoding an invalid ticket throws")
+    func testDecodeInvalidTicket() async throws {
+        do {
+            _ = try await decodeTicket("not-a-valid-ticket")
+            #expect(Bool(false), "Should have thrown invalidTicket")
+        } catch let error as IrohError {
+            switch error {
+            case .invalidTicket:
+                break
+            default:
+                #expect(Bool(false), "Expected invalidTicket, got \(error)")
+            }
+        }
+    }
+
     // MARK: - Node Close Tests
 
     @Test("Double close is safe")
@@ -180,6 +428,38 @@ struct ErrorPathTests {
         try? FileManager.default.removeItem(at: tempDir)
     }
 
+    // MARK: - Store Corruption Tests
+
+    @Test("Corrupt blob store throws storeCorrupted unless recovered")
+    func testCorruptBlobStore() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString)
+        try FileManager.default.createDirectory(at: tempDir, withIntermediateDirectories: true)
+        try Data(repeating: 0x5a, count: 8192)
+            .write(to: tempDir.appendingPathComponent("blobs.db"))
+
+        var config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        do {
+            _ = try await IrohNode(config: config)
+            #expect(Bool(false), "Should have thrown storeCorrupted")
+        } catch let error as IrohError {
+            switch error {
+            case .storeCorrupted(let database, let path, _):
+                #expect(database == .blobs)
+                #expect(path.hasSuffix("blobs.db"))
+            default:
+                #expect(Bool(false), "Expected storeCorrupted, got \(error)")
+            }
+        }
+
+        config.blobStoreRecovery = .quarantine
+        let node = try await IrohNode(config: config)
+        try await node.close()
+
+        // Cleanup
+        try? FileManager.default.removeItem(at: tempDir)
+    }
+
     // MARK: - Error Descriptions
 
     @Test("All error types have descriptions")
@@ -206,6 +486,13 @@ struct ErrorPathTests {
         }
     }
 
+    @Test("Operation id is carried by the error")
+    func testErrorOperationId() {
+        #expect(IrohError.getFailed("connection lost", operationId: 42).operationId == 42)
+        #expect(IrohError.getFailed("connection lost (operation 42)").operationId == nil)
+        #expect(IrohError.timeout.operationId == nil)
+    }
+
     // MARK: - OperationOptions Tests
 
     @Test("OperationOptions timeout conversion")
@@ -11,6 +11,36 @@ final class IrohAuthorTests: XCTestCase {
         XCTAssertEqual(author.id.count, 64, "Author ID should be 64 hex characters")
     }
 
+    /// Test that caller-provided entropy becomes the secret key.
+    func testAuthorCreateWithEntropy() async throws {
+        let author = try await IrohAuthor.create { buffer in
+            buffer.initializeMemory(as: UInt8.self, repeating: 0x2a)
+            return true
+        }
+        XCTAssertEqual(author.secret, Data(repeating: 0x2a, count: 32))
+
+        do {
+            _ = try await IrohAuthor.create { _ in false }
+            XCTFail("Should have thrown authorCreationFailed")
+        } catch IrohError.authorCreationFailed {
+            // Expected
+        }
+    }
+
+    /// Test signing and verifying payloads with author keys.
+    func testAuthorSignAndVerify() async throws {
+        let author = try await IrohAuthor.create()
+        let other = try await IrohAuthor.create()
+        let payload = Data("join my document".utf8)
+
+        let signature = author.sign(payload)
+
+        XCTAssertEqual(signature.count, 64, "Signature should be 64 bytes")
+        XCTAssertTrue(IrohAuthor.verify(signature, of: payload, publicKey: author.publicKey))
+        XCTAssertFalse(IrohAuthor.verify(signature, of: Data("tampered".utf8), publicKey: author.publicKey))
+        XCTAssertFalse(IrohAuthor.verify(signature, of: payload, publicKey: other.publicKey))
+    }
+
     /// Test importing an author from hex and verifying roundtrip.
     func testAuthorFromHex() async throws {
         // Create an author
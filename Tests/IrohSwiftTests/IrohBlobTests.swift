@@ -34,6 +34,37 @@ final class IrohBlobTests: XCTestCase {
         // If we get here without error, tagging succeeded
     }
 
+    /// Test that a ticket reports the addresses it was shared from.
+    func testTicketInfoReportsAddresses() async throws {
+        let ticket = try await node.put(Data("Addressed content".utf8))
+
+        let info = await validateTicket(ticket)
+        XCTAssertTrue(info.isValid)
+        XCTAssertEqual(info.format, .raw)
+        // The node runs without relays, so only direct addresses are known
+        XCTAssertTrue(info.relayUrls.isEmpty)
+        XCTAssertFalse(info.directAddresses.isEmpty)
+    }
+
+    /// Test that a time-limited tag is removed once it expires.
+    func testTagBlobExpires() async throws {
+        let ticket = try await node.put(Data("Test data for a brief share".utf8))
+        let ticketInfo = await validateTicket(ticket)
+        let events = try await node.events()
+
+        try await node.tagBlob(hash: ticketInfo.hash!, name: "shares/brief", expiresAt: Date().addingTimeInterval(0.3))
+
+        for try await event in events {
+            if case .tagExpired(let name, let hash) = event {
+                XCTAssertEqual(name, "shares/brief")
+                XCTAssertEqual(hash, ticketInfo.hash)
+                break
+            }
+        }
+        let listing = try await node.listTags(prefix: "shares/")
+        XCTAssertTrue(listing.tags.isEmpty)
+    }
+
     /// Test creating a ticket for an existing blob.
     func testCreateTicket() async throws {
         // Put some data
@@ -56,6 +87,304 @@ final class IrohBlobTests: XCTestCase {
         XCTAssertEqual(newTicketInfo.hash, ticketInfo.hash, "Hash should match original")
     }
 
+    /// Test that every ticket encoding decodes back to the original ticket.
+    func testTicketEncodingRoundtrip() async throws {
+        let data = Data("Test data for ticket encoding".utf8)
+        let ticket = try await node.put(data)
+
+        for encoding in [TicketEncoding.standard, .qrAlphanumeric, .zBase32] {
+            let encoded = try await encodeTicket(ticket, as: encoding)
+            let decoded = try await decodeTicket(encoded)
+            XCTAssertEqual(decoded, ticket, "Roundtrip failed for \(encoding)")
+        }
+
+        let qr = try await encodeTicket(ticket, as: .qrAlphanumeric)
+        XCTAssertEqual(qr, qr.uppercased())
+    }
+
+    /// Test that a locked ticket only unlocks with its passphrase.
+    func testTicketLockRoundtrip() async throws {
+        let data = Data("Test data for locked tickets".utf8)
+        let ticket = try await node.put(data)
+
+        let locked = try await lockTicket(ticket, passphrase: "correct horse")
+        XCTAssertFalse(locked.contains(ticket))
+        let unlocked = try await unlockTicket(locked, passphrase: "correct horse")
+        XCTAssertEqual(unlocked, ticket)
+
+        do {
+            _ = try await unlockTicket(locked, passphrase: "battery staple")
+            XCTFail("Should have thrown for a wrong passphrase")
+        } catch IrohError.invalidTicket {
+            // Expected
+        }
+    }
+
+    /// Test that a ticket bundle returns its tickets with their labels, in order.
+    func testTicketBundleRoundtrip() async throws {
+        let avatar = try await node.put(Data("avatar".utf8))
+        let banner = try await node.put(Data("banner".utf8))
+
+        let bundle = try await encodeTicketBundle([
+            (label: "avatar", ticket: avatar),
+            (label: "", ticket: try await encodeTicket(banner, as: .qrAlphanumeric)),
+        ])
+        let tickets = try await decodeTicketBundle(bundle.uppercased())
+        XCTAssertEqual(tickets, [
+            BundledTicket(label: "avatar", kind: .blob, ticket: avatar),
+            BundledTicket(label: "", kind: .blob, ticket: banner),
+        ])
+
+        do {
+            _ = try await decodeTicketBundle(avatar)
+            XCTFail("Should have thrown for a plain ticket")
+        } catch IrohError.invalidTicket {
+            // Expected
+        }
+    }
+
+    /// Test that metadata attached at put time is returned on get and inspection.
+    func testPutWithMetadata() async throws {
+        let data = Data("Test data with metadata".utf8)
+        let metadata = BlobMetadata(filename: "notes.txt", contentType: "text/plain")
+        let ticket = try await node.put(data, metadata: metadata)
+
+        let result = try await node.getWithMetadata(ticket: ticket)
+        XCTAssertEqual(result.data, data)
+        XCTAssertEqual(result.metadata?.filename, "notes.txt")
+        XCTAssertEqual(result.metadata?.contentType, "text/plain")
+        XCTAssertNotNil(result.metadata?.createdAt)
+
+        let inspected = try await node.metadata(forTicket: ticket)
+        XCTAssertEqual(inspected, result.metadata)
+
+        let plainTicket = try await node.put(data)
+        let plainMetadata = try await node.metadata(forTicket: plainTicket)
+        XCTAssertNil(plainMetadata)
+    }
+
+    /// Test that compressed content is returned decompressed.
+    func testPutWithCompression() async throws {
+        let data = Data(String(repeating: "{\"level\":\"info\",\"msg\":\"ok\"}\n", count: 100).utf8)
+        let ticket = try await node.put(data, metadata: BlobMetadata(compression: .zstd))
+
+        let result = try await node.getWithMetadata(ticket: ticket)
+        XCTAssertEqual(result.data, data)
+        XCTAssertEqual(result.metadata?.compression, .zstd)
+
+        // Content that would not shrink is stored as given
+        let tiny = try await node.put(Data("x".utf8), metadata: BlobMetadata(compression: .zstd))
+        let tinyMetadata = try await node.metadata(forTicket: tiny)
+        XCTAssertEqual(tinyMetadata?.compression, .uncompressed)
+    }
+
+    /// Test creating and releasing a temporary tag.
+    func testTempTagLifecycle() async throws {
+        let data = Data("Test data for temp tags".utf8)
+        let ticket = try await node.put(data)
+        let ticketInfo = await validateTicket(ticket)
+
+        let tempTag = try await node.createTempTag(hash: ticketInfo.hash!)
+        tempTag.release()
+        // Releasing twice is a no-op
+        tempTag.release()
+    }
+
+    /// Test that an exported temporary file is removed on release.
+    func testExportTemporaryFile() async throws {
+        let data = Data("Test data for temporary export".utf8)
+        let ticket = try await node.put(data)
+        let ticketInfo = await validateTicket(ticket)
+
+        let file = try await node.exportTemporaryFile(hash: ticketInfo.hash!, filename: "note.txt")
+        XCTAssertEqual(file.url.lastPathComponent, "note.txt")
+        XCTAssertEqual(try Data(contentsOf: file.url), data)
+
+        file.release()
+        XCTAssertFalse(FileManager.default.fileExists(atPath: file.url.path))
+    }
+
+    /// Test listing blobs sorted by size, one page at a time.
+    func testListBlobsSortedAndPaged() async throws {
+        _ = try await node.put(Data("a".utf8))
+        _ = try await node.put(Data("ccc".utf8))
+        _ = try await node.put(Data("bb".utf8))
+
+        let first = try await node.listBlobs(sort: .sizeDescending, offset: 0, limit: 2)
+        XCTAssertEqual(first.totalCount, 3)
+        XCTAssertEqual(first.blobs.map(\.size), [3, 2])
+        XCTAssertTrue(first.blobs.allSatisfy { $0.isComplete && $0.addedAt != nil })
+
+        _ = try await node.put(Data("dddd".utf8))
+        let second = try await node.listBlobs(cursor: first.cursor, offset: 2, limit: 2)
+        XCTAssertEqual(second.totalCount, 3)
+        XCTAssertEqual(second.blobs.map(\.size), [1])
+    }
+
+    /// Test listing tags under a prefix with their combined size.
+    func testListTagsByPrefix() async throws {
+        let data = Data("Test data for tag listing".utf8)
+        let ticket = try await node.put(data)
+        let ticketInfo = await validateTicket(ticket)
+
+        try await node.tagBlob(hash: ticketInfo.hash!, name: "chat/a")
+        try await node.tagBlob(hash: ticketInfo.hash!, name: "chat/b")
+        try await node.tagBlob(hash: ticketInfo.hash!, name: "profiles/a")
+
+        let listing = try await node.listTags(prefix: "chat/")
+        XCTAssertEqual(listing.tags.map(\.name), ["chat/a", "chat/b"])
+        XCTAssertEqual(listing.tags.first?.size, UInt64(data.count))
+        // Shared content is only counted once
+        XCTAssertEqual(listing.totalSize, UInt64(data.count))
+    }
+
+    /// Test that wiping a profile only reclaims content no other profile uses.
+    func testProfilesPartitionStore() async throws {
+        let shared = Data("Shared profile content".utf8)
+        let own = Data("Alice's own content".utf8)
+        _ = try await node.put(shared, profile: "alice")
+        _ = try await node.put(own, profile: "alice")
+        _ = try await node.put(shared, profile: "bob")
+
+        let stats = try await node.profileStats("alice")
+        XCTAssertEqual(stats.tags, 2)
+        XCTAssertEqual(stats.totalSize, UInt64(shared.count + own.count))
+        XCTAssertEqual(stats.uniqueSize, UInt64(own.count))
+
+        let events = try await node.events()
+        let reclaimed = try await node.wipeProfile("alice")
+        XCTAssertEqual(reclaimed.blobs, 1)
+        XCTAssertEqual(reclaimed.size, UInt64(own.count))
+        var gcEvents: [NodeEvent] = []
+        for try await event in events {
+            switch event {
+            case .gcStarted, .gcFinished:
+                gcEvents.append(event)
+            default:
+                continue
+            }
+            if gcEvents.count == 2 { break }
+        }
+        XCTAssertEqual(gcEvents, [
+            .gcStarted(profile: "alice", blobs: 1, size: UInt64(own.count)),
+            .gcFinished(profile: "alice", blobs: 1, size: UInt64(own.count)),
+        ])
+        let aliceTags = try await node.listTags(profile: "alice")
+        XCTAssertTrue(aliceTags.tags.isEmpty)
+        let bobTags = try await node.listTags(profile: "bob")
+        XCTAssertEqual(bobTags.totalSize, UInt64(shared.count))
+    }
+
+    /// Test copying a blob into another store directory.
+    func testCopyBlobsToStore() async throws {
+        let data = Data("Test data for store copy".utf8)
+        let ticket = try await node.put(data)
+        let ticketInfo = await validateTicket(ticket)
+
+        let destination = tempDir.appendingPathComponent("copy", isDirectory: true)
+        let copied = try await node.copyBlobs([ticketInfo.hash!], to: destination)
+        XCTAssertEqual(copied, UInt64(data.count))
+
+        let other = try await IrohNode(config: IrohConfig(storagePath: destination, relayEnabled: false))
+        let listing = try await other.listTags()
+        XCTAssertEqual(listing.tags.map(\.hash), [ticketInfo.hash!])
+        try await other.close()
+    }
+
+    /// Test putting a file from disk, which is copied into the store.
+    func testPutFile() async throws {
+        let data = Data(repeating: 0x2a, count: 512 * 1024)
+        let source = tempDir.appendingPathComponent("video.mov")
+        try data.write(to: source)
+
+        let ticket = try await node.putFile(at: source)
+        try FileManager.default.removeItem(at: source)
+
+        let fetched = try await node.get(ticket: ticket)
+        XCTAssertEqual(fetched, data)
+        // Same content as putting the bytes
+        let fileInfo = await validateTicket(ticket)
+        let bytesInfo = await validateTicket(try await node.put(data))
+        XCTAssertEqual(fileInfo.hash, bytesInfo.hash)
+    }
+
+    /// Test that uploading an edited chunked file only stores the changed chunks.
+    func testChunkedFileUpload() async throws {
+        var data = Data((0..<(1024 * 1024)).map { _ in UInt8.random(in: 0...255) })
+        let source = tempDir.appendingPathComponent("db.sqlite")
+        try data.write(to: source)
+
+        let first = try await node.putChunkedFile(at: source)
+        XCTAssertEqual(first.stats.size, UInt64(data.count))
+        XCTAssertEqual(first.stats.newChunks, first.stats.chunks)
+
+        data.replaceSubrange(512 * 1024..<(512 * 1024 + 16), with: Data(repeating: 0xff, count: 16))
+        try data.write(to: source)
+        let second = try await node.putChunkedFile(at: source)
+        XCTAssertNotEqual(second.ticket, first.ticket)
+        XCTAssertLessThanOrEqual(second.stats.newChunks, 2)
+
+        let destination = tempDir.appendingPathComponent("copy.sqlite")
+        let fetched = try await node.getChunkedFile(ticket: second.ticket, to: destination)
+        XCTAssertEqual(fetched.newChunks, 0)
+        XCTAssertEqual(try Data(contentsOf: destination), data)
+    }
+
+    /// Test that a blob pinned under two tags is reported as shared.
+    func testReferenceReportSharedBlob() async throws {
+        let data = Data("Test data for reference report".utf8)
+        let ticket = try await node.put(data)
+        let ticketInfo = await validateTicket(ticket)
+
+        try await node.tagBlob(hash: ticketInfo.hash!, name: "albums/a")
+        try await node.tagBlob(hash: ticketInfo.hash!, name: "albums/b")
+
+        let report = try await node.referenceReport(tag: "albums/a")
+        XCTAssertEqual(report.totalBlobs, 1)
+        XCTAssertEqual(report.sharedBlobs, 1)
+        XCTAssertEqual(report.totalSize, UInt64(data.count))
+        XCTAssertEqual(report.uniqueSize, 0)
+    }
+
+    /// Test that a collection's own blobs are reported as unique.
+    func testReferenceReportCollection() async throws {
+        let ticket = try await node.put(Data("Collection content".utf8), metadata: BlobMetadata())
+        let ticketInfo = await validateTicket(ticket)
+
+        let report = try await node.referenceReport(collection: ticketInfo.hash!)
+        XCTAssertGreaterThan(report.totalBlobs, 1)
+        XCTAssertEqual(report.sharedBlobs, 0)
+        XCTAssertEqual(report.uniqueSize, report.totalSize)
+    }
+
+    /// Test that content saved under several tags is reported as a duplicate.
+    func testDedupReport() async throws {
+        let data = Data("Test data for dedup report".utf8)
+        let ticket = try await node.put(data)
+        let ticketInfo = await validateTicket(ticket)
+        try await node.tagBlob(hash: ticketInfo.hash!, name: "chats/a")
+        try await node.tagBlob(hash: ticketInfo.hash!, name: "chats/b")
+
+        let report = try await node.dedupReport()
+        XCTAssertEqual(report.duplicateBlobs, 1)
+        XCTAssertEqual(report.duplicates.first?.hash, ticketInfo.hash)
+        XCTAssertTrue(report.duplicates.first?.tags.contains("chats/a") ?? false)
+        XCTAssertGreaterThanOrEqual(report.savedSize, UInt64(data.count) * 2)
+    }
+
+    /// Test that tickets for different content are rejected.
+    func testGetFromProvidersRejectsMismatchedTickets() async throws {
+        let first = try await node.put(Data("first".utf8))
+        let second = try await node.put(Data("second".utf8))
+
+        do {
+            _ = try await node.get(tickets: [first, second])
+            XCTFail("Should have thrown getFailed")
+        } catch IrohError.getFailed {
+            // Expected
+        }
+    }
+
     /// Test untagging (unpinning) a blob.
     func testUntagBlob() async throws {
         // Put some data
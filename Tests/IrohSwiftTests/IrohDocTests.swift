@@ -33,6 +33,26 @@ final class IrohDocTests: XCTestCase {
         XCTAssertEqual(namespaceId.count, 64, "Namespace ID should be 64 characters (hex)")
     }
 
+    /// Test that ticket inspection reports the granted capability.
+    func testInspectDocTicket() async throws {
+        let doc = try await node.createDoc()
+        let namespaceId = await doc.namespaceId
+
+        let readInfo = await inspectDocTicket(try await doc.shareTicket(mode: .read))
+        XCTAssertTrue(readInfo.isValid)
+        XCTAssertEqual(readInfo.namespaceId, namespaceId)
+        XCTAssertEqual(readInfo.capability, .read)
+        XCTAssertFalse(readInfo.grantsWrite)
+
+        let writeInfo = await inspectDocTicket(try await doc.shareTicket(mode: .write))
+        XCTAssertEqual(writeInfo.capability, .write)
+        XCTAssertTrue(writeInfo.grantsWrite)
+
+        let invalid = await inspectDocTicket("not-a-ticket")
+        XCTAssertFalse(invalid.isValid)
+        XCTAssertNil(invalid.capability)
+    }
+
     /// Test setting and getting a key-value pair.
     func testDocSetAndGet() async throws {
         let doc = try await node.createDoc()
@@ -40,15 +60,45 @@ final class IrohDocTests: XCTestCase {
         let key = "test-key"
         let value = "Hello, Docs!".data(using: .utf8)!
 
-        _ = try await doc.set(author: author, key: key, value: value)
+        let hash = try await doc.set(author: author, key: key, value: value)
 
         let entry = try await doc.get(key: key)
         XCTAssertNotNil(entry, "Entry should exist after set")
+        XCTAssertEqual(entry?.contentHash, hash, "Entry hash should match the written content")
 
         let content = try await entry!.content(from: doc)
         XCTAssertEqual(content, value, "Content should match what was set")
     }
 
+    /// Test setting an entry from a file reports progress and copies the content.
+    func testDocSetFile() async throws {
+        let doc = try await node.createDoc()
+        let value = Data(repeating: 7, count: 100_000)
+        let fileURL = tempDir.appendingPathComponent("photo.jpg")
+        try value.write(to: fileURL)
+
+        let collector = UpdateCollector<ImportProgress>()
+        let hash = try await doc.setFile(author: author, key: "photo", at: fileURL) { progress in
+            collector.append(progress)
+        }
+        XCTAssertEqual(collector.updates.last?.fraction, 1.0)
+
+        // The entry outlives the file
+        try FileManager.default.removeItem(at: fileURL)
+        let entry = try await doc.get(key: "photo")
+        XCTAssertEqual(entry?.contentHash, hash)
+        XCTAssertEqual(entry?.contentSize, 100_000)
+        let content = try await entry!.content(from: doc)
+        XCTAssertEqual(content, value)
+
+        do {
+            _ = try await doc.setFile(author: author, key: "missing", at: fileURL)
+            XCTFail("Importing a missing file should fail")
+        } catch IrohError.docSetFailed {
+            // Expected
+        }
+    }
+
     /// Test getting multiple entries with a prefix.
     func testDocGetMany() async throws {
         let doc = try await node.createDoc()
@@ -72,6 +122,149 @@ final class IrohDocTests: XCTestCase {
         XCTAssertTrue(keys.contains("prefix/b"))
     }
 
+    /// Test fetching several keys in one call.
+    func testDocGetBatch() async throws {
+        let doc = try await node.createDoc()
+
+        _ = try await doc.set(author: author, key: "batch/a", value: "value-a".data(using: .utf8)!)
+        _ = try await doc.set(author: author, key: "batch/b", value: "value-b".data(using: .utf8)!)
+
+        let entries = try await doc.get(keys: ["batch/b", "batch/missing", "batch/a"])
+
+        XCTAssertEqual(entries.count, 3, "Should return one result per key")
+        XCTAssertEqual(entries[0].map { String(data: $0.key, encoding: .utf8)! }, "batch/b")
+        XCTAssertNil(entries[1], "Missing key should be nil")
+        XCTAssertEqual(entries[2].map { String(data: $0.key, encoding: .utf8)! }, "batch/a")
+    }
+
+    /// Test that malformed signed entries are rejected.
+    func testVerifyDocEntryRejectsGarbage() async throws {
+        do {
+            _ = try await verifyDocEntry(Data("not an entry".utf8))
+            XCTFail("Should have thrown entryVerificationFailed")
+        } catch IrohError.entryVerificationFailed {
+            // Expected
+        }
+    }
+
+    /// Test that local writes show up in swarm statistics.
+    func testDocSwarmStats() async throws {
+        let doc = try await node.createDoc()
+
+        _ = try await doc.set(author: author, key: "presence", value: Data("online".utf8))
+
+        var stats = try await doc.swarmStats()
+        for _ in 0..<50 where stats.messagesSent == 0 {
+            try await Task.sleep(nanoseconds: 100_000_000)
+            stats = try await doc.swarmStats()
+        }
+
+        XCTAssertEqual(stats.messagesSent, 1, "Local write should be counted")
+        XCTAssertEqual(stats.neighborCount, 0, "No peers are connected")
+        XCTAssertNotNil(stats.lastActivity)
+    }
+
+    /// Test that a successful sync shows up in the peer's sync status.
+    func testPeerSyncStatus() async throws {
+        let owner = try await IrohNode(config: .testing(seed: "sync-status-owner", docsEnabled: true))
+        let reader = try await IrohNode(config: .testing(seed: "sync-status-reader", docsEnabled: true))
+        try await owner.importAuthor(author)
+        let doc = try await owner.createDoc()
+        _ = try await doc.set(author: author, key: "status", value: Data("synced".utf8))
+        let events = try await reader.events()
+        let joined = try await reader.joinDoc(ticket: try await doc.shareTicket(mode: .read))
+        let namespaceId = await joined.namespaceId
+
+        for try await event in events {
+            if case .peerSyncChanged(let synced, let status) = event, status.lastSuccess != nil {
+                XCTAssertEqual(synced, namespaceId)
+                XCTAssertEqual(status.peer, owner.nodeId)
+                break
+            }
+        }
+        let statuses = try await joined.peerSyncStatus()
+        let status = try XCTUnwrap(statuses.first { $0.peer == owner.nodeId })
+        XCTAssertEqual(status.failureStreak, 0)
+        XCTAssertEqual(status.pendingEntries, 0)
+        XCTAssertNil(status.lastError)
+        try await reader.close()
+        try await owner.close()
+    }
+
+    /// Test exporting a document's entries to a SQLite file.
+    func testDocExportSQLite() async throws {
+        let doc = try await node.createDoc()
+
+        _ = try await doc.set(author: author, key: "notes/a", value: Data("short".utf8))
+        _ = try await doc.set(author: author, key: "notes/b", value: Data("a longer value".utf8))
+
+        let url = tempDir.appendingPathComponent("export.sqlite")
+        let count = try await doc.exportSQLite(to: url, maxValueSize: 8)
+
+        XCTAssertEqual(count, 2)
+        XCTAssertTrue(FileManager.default.fileExists(atPath: url.path))
+    }
+
+    /// Test exporting the entries matching a query as NDJSON.
+    func testDocExportNDJSON() async throws {
+        let doc = try await node.createDoc()
+
+        _ = try await doc.set(author: author, key: "notes/a", value: Data("short".utf8))
+        _ = try await doc.set(author: author, key: "notes/b", value: Data("a longer value".utf8))
+        _ = try await doc.set(author: author, key: "other", value: Data("skipped".utf8))
+
+        let collector = ExportCollector()
+        let count = try await doc.exportNDJSON(
+            DocQuery(keyPrefix: Data("notes/".utf8)),
+            maxValueSize: 8
+        ) { chunk in
+            collector.append(chunk)
+        }
+
+        XCTAssertEqual(count, 2)
+        let lines = try collector.data
+            .split(separator: UInt8(ascii: "\n"))
+            .map { try JSONSerialization.jsonObject(with: Data($0)) as! [String: Any] }
+        XCTAssertEqual(lines.map { $0["key"] as? String }, ["notes/a", "notes/b"])
+        XCTAssertEqual(lines[0]["value"] as? String, "short")
+        XCTAssertEqual(lines[0]["author"] as? String, author.id)
+        XCTAssertNil(lines[1]["value"])
+    }
+
+    /// Test querying entries newer than a timestamp.
+    func testDocQueryNewerThan() async throws {
+        let doc = try await node.createDoc()
+
+        _ = try await doc.set(author: author, key: "feed/1", value: Data("1".utf8))
+        let first = try await doc.get(key: "feed/1")
+        _ = try await doc.set(author: author, key: "feed/2", value: Data("2".utf8))
+        _ = try await doc.set(author: author, key: "other/3", value: Data("3".utf8))
+
+        var keys: [String] = []
+        for try await entry in try await doc.query(DocQuery(prefix: "feed/", newerThan: first!.timestamp)) {
+            keys.append(entry.keyString!)
+        }
+        XCTAssertEqual(keys, ["feed/2"])
+    }
+
+    /// Test that a latest-only query yields one winning entry per key.
+    func testDocQueryLatestOnly() async throws {
+        let doc = try await node.createDoc()
+        let other = try await IrohAuthor.create()
+        try await node.importAuthor(other)
+
+        _ = try await doc.set(author: author, key: "list/a", value: Data("mine".utf8))
+        _ = try await doc.set(author: other, key: "list/a", value: Data("theirs".utf8))
+        _ = try await doc.set(author: author, key: "list/b", value: Data("b".utf8))
+
+        var entries: [DocEntry] = []
+        for try await entry in try await doc.query(.latest(prefix: "list/")) {
+            entries.append(entry)
+        }
+        XCTAssertEqual(entries.map(\.keyString), ["list/a", "list/b"])
+        XCTAssertEqual(entries.first?.contentSize, 6)
+    }
+
     /// Test deleting an entry.
     func testDocDelete() async throws {
         let doc = try await node.createDoc()
@@ -92,6 +285,338 @@ final class IrohDocTests: XCTestCase {
         XCTAssertNil(entry, "Entry should be nil after delete")
     }
 
+    /// Test writes that sign as the node's default author.
+    func testDocWritesAsDefaultAuthor() async throws {
+        // Only imported authors can become the default
+        let stranger = try await IrohAuthor.create()
+        do {
+            try await node.setDefaultAuthor(stranger)
+            XCTFail("Setting an unimported default author should fail")
+        } catch IrohError.defaultAuthorFailed {
+        }
+
+        try await node.setDefaultAuthor(author)
+        let defaultAuthorId = try await node.defaultAuthorId()
+        XCTAssertEqual(defaultAuthorId, author.id)
+
+        let doc = try await node.createDoc()
+        _ = try await doc.set(key: "greeting", value: Data("hello".utf8))
+        let entry = try await doc.get(key: "greeting")
+        XCTAssertEqual(entry?.authorId, author.id)
+
+        let count = try await doc.delete(key: "greeting")
+        XCTAssertEqual(count, 1)
+        let deleted = try await doc.get(key: "greeting")
+        XCTAssertNil(deleted)
+    }
+
+    /// Test deleting all keys in a range.
+    func testDocDeleteRange() async throws {
+        let doc = try await node.createDoc()
+
+        for key in ["events/2022/12", "events/2023/01", "events/2023/06", "events/2024/01"] {
+            _ = try await doc.set(author: author, key: key, value: Data(key.utf8))
+        }
+
+        let count = try await doc.delete(author: author, from: "events/2023/", to: "events/2024/")
+        XCTAssertEqual(count, 2)
+
+        let remaining = try await doc.getMany(prefix: "events/")
+        var keys: [String] = []
+        for try await entry in remaining {
+            keys.append(String(data: entry.key, encoding: .utf8)!)
+        }
+        XCTAssertEqual(keys.sorted(), ["events/2022/12", "events/2024/01"])
+    }
+
+    /// Test that compare-and-set rejects stale expectations.
+    func testDocCompareAndSet() async throws {
+        let doc = try await node.createDoc()
+
+        let first = try await doc.compareAndSet(
+            author: author, key: "counter", expectedHash: nil, value: Data("1".utf8)
+        )
+
+        do {
+            try await doc.compareAndSet(
+                author: author, key: "counter", expectedHash: nil, value: Data("2".utf8)
+            )
+            XCTFail("Should have thrown docConflict")
+        } catch IrohError.docConflict(let currentHash) {
+            XCTAssertEqual(currentHash, first)
+        }
+
+        try await doc.compareAndSet(
+            author: author, key: "counter", expectedHash: first, value: Data("2".utf8)
+        )
+        let entry = try await doc.get(key: "counter")
+        let content = try await entry!.content(from: doc)
+        XCTAssertEqual(content, Data("2".utf8))
+    }
+
+    /// Test that counter updates from different authors are summed.
+    func testDocCounterMergesAuthors() async throws {
+        let doc = try await node.createDoc()
+        let other = try await IrohAuthor.create()
+        try await node.importAuthor(other)
+
+        try await doc.increment(author: author, key: "likes", by: 2)
+        try await doc.increment(author: other, key: "likes")
+        let value = try await doc.decrement(author: author, key: "likes")
+        XCTAssertEqual(value, 2)
+
+        let read = try await doc.counter(key: "likes")
+        XCTAssertEqual(read, 2)
+    }
+
+    /// Test registering and cancelling a conflict resolver.
+    func testConflictResolverLifecycle() async throws {
+        let doc = try await node.createDoc()
+
+        let resolver = try await doc.setConflictResolver(author: author) { _, candidates in
+            candidates.max { $0.timestamp < $1.timestamp }?.value
+        }
+        // Local writes never invoke the resolver
+        _ = try await doc.set(author: author, key: "title", value: Data("draft".utf8))
+
+        resolver.cancel()
+        // Cancelling twice is a no-op
+        resolver.cancel()
+    }
+
+    /// Test that a write filter validates authors and can be cancelled.
+    func testWriteFilterLifecycle() async throws {
+        let doc = try await node.createDoc()
+
+        do {
+            _ = try await doc.setWriteFilter(author: author, allowedAuthors: ["not-an-author"])
+            XCTFail("Expected an invalid author ID to be rejected")
+        } catch IrohError.writeFilterFailed {
+            // Expected
+        }
+
+        let filter = try await doc.setWriteFilter(
+            author: author,
+            allowedAuthors: [author.id],
+            decide: { _ in .quarantine }
+        )
+        // Local writes are never filtered
+        _ = try await doc.set(author: author, key: "title", value: Data("draft".utf8))
+        let entry = try await doc.get(key: "title")
+        XCTAssertNotNil(entry)
+
+        filter.cancel()
+        // Cancelling twice is a no-op
+        filter.cancel()
+    }
+
+    /// Test that outbox writes are stored locally and stay queued without peers.
+    func testOutboxQueuesWithoutPeers() async throws {
+        let doc = try await node.createDoc()
+
+        let id = try await doc.enqueue(author: author, key: "draft", value: Data("hello".utf8))
+        let entry = try await doc.get(key: "draft")
+        XCTAssertNotNil(entry)
+
+        let items = try await node.outboxItems()
+        let item = try XCTUnwrap(items.first { $0.id == id })
+        XCTAssertEqual(item.namespaceId, doc.namespaceId)
+        XCTAssertEqual(item.authorId, author.id)
+        XCTAssertEqual(item.key, Data("draft".utf8))
+    }
+
+    /// Test that writes are not backed up until an unreachable durable peer confirms them.
+    func testDurablePeersTrackLocalWrites() async throws {
+        let doc = try await node.createDoc()
+        // Docs are disabled on the peer, so it can never confirm a sync
+        let peer = try await IrohNode(config: IrohConfig(storagePath: tempDir.appendingPathComponent("peer")))
+        let peerId = try await peer.info().nodeId
+
+        var status = try await doc.durableStatus()
+        XCTAssertTrue(status.peers.isEmpty)
+        XCTAssertFalse(status.isBackedUp)
+
+        try await doc.setDurablePeers([peerId])
+        _ = try await doc.set(author: author, key: "note", value: Data("hello".utf8))
+        status = try await doc.durableStatus()
+        XCTAssertEqual(status.peers.map(\.nodeId), [peerId])
+        XCTAssertGreaterThan(status.lastLocalWrite, 0)
+        XCTAssertFalse(status.isBackedUp)
+
+        try await doc.setDurablePeers([])
+        status = try await doc.durableStatus()
+        XCTAssertTrue(status.peers.isEmpty)
+
+        do {
+            try await doc.setDurablePeers(["not-a-node-id"])
+            XCTFail("Expected an invalid node ID to be rejected")
+        } catch IrohError.durablePeersFailed {
+        }
+        try await peer.close()
+    }
+
+    /// Test immediate durability per doc and per write, and flushing.
+    func testDocDurabilityAndFlush() async throws {
+        let doc = try await node.createDoc()
+
+        try await doc.setDurability(.immediate)
+        _ = try await doc.set(author: author, key: "settings", value: Data("dark".utf8))
+        _ = try await doc.set(author: author, key: "draft", value: Data("hi".utf8), durability: .batched)
+        try await doc.flush()
+        try await doc.setDurability(.batched)
+        _ = try await doc.set(author: author, key: "draft", value: Data("hello".utf8), durability: .immediate)
+
+        let entry = try await doc.get(key: "draft")
+        let content = try await entry!.content(from: doc)
+        XCTAssertEqual(content, Data("hello".utf8))
+        XCTAssertNotNil(try await doc.get(key: "settings"))
+    }
+
+    /// Test adding, listing and pruning backup manifest assets.
+    func testBackupManifest() async throws {
+        let doc = try await node.createDoc()
+
+        let hash = try await doc.addAsset(
+            author: author,
+            id: "IMG_1",
+            data: Data("photo".utf8),
+            metadata: BlobMetadata(filename: "IMG_1.HEIC", contentType: "image/heic")
+        )
+        let assets = try await doc.manifestAssets()
+        XCTAssertEqual(assets.map(\.assetId), ["IMG_1"])
+        XCTAssertEqual(assets.first?.hash, hash)
+        XCTAssertEqual(assets.first?.size, 5)
+        XCTAssertEqual(assets.first?.metadata.filename, "IMG_1.HEIC")
+
+        let missing = try await doc.missingAssets(among: ["IMG_1", "IMG_2"])
+        XCTAssertEqual(missing, ["IMG_2"])
+
+        let removed = try await doc.pruneAssets(author: author, ids: ["IMG_1"])
+        XCTAssertEqual(removed, 1)
+        let remaining = try await doc.manifestAssets()
+        XCTAssertTrue(remaining.isEmpty)
+    }
+
+    /// Test that entries written with a TTL are removed once expired.
+    func testDocEntryExpires() async throws {
+        let doc = try await node.createDoc()
+
+        _ = try await doc.set(author: author, key: "invites/a", value: Data("a".utf8), ttl: .zero)
+        _ = try await doc.set(author: author, key: "invites/b", value: Data("b".utf8), ttl: .seconds(3600))
+
+        let pruned = try await node.pruneExpiredEntries()
+        XCTAssertEqual(pruned, 1)
+
+        let expired = try await doc.get(key: "invites/a")
+        XCTAssertNil(expired)
+        let live = try await doc.get(key: "invites/b")
+        XCTAssertNotNil(live)
+    }
+
+    /// Test that resubscribing from a cursor replays missed entries.
+    func testSubscribeResumesFromCursor() async throws {
+        let doc = try await node.createDoc()
+
+        let namespaceId = await doc.namespaceId
+        var cursor: DocCursor?
+        let live = try await doc.subscribe(from: nil)
+        // Let the subscription start before writing
+        try await Task.sleep(for: .milliseconds(200))
+        _ = try await doc.set(author: author, key: "seen", value: Data("1".utf8))
+        for try await update in live {
+            if case .insertLocal = update.event {
+                XCTAssertEqual(update.namespaceId, namespaceId)
+                cursor = update.cursor
+                break
+            }
+        }
+        XCTAssertNotNil(cursor)
+
+        _ = try await doc.set(author: author, key: "missed", value: Data("2".utf8))
+
+        let resumed = try await doc.subscribe(from: cursor)
+        for try await update in resumed {
+            guard case .replayed(let entry) = update.event else {
+                XCTFail("Expected a replayed entry, got \(update.event)")
+                break
+            }
+            XCTAssertEqual(entry.keyString, "missed")
+            break
+        }
+    }
+
+    /// Test that a deletions-only subscription reports deletions but not writes.
+    func testSubscribeDeletions() async throws {
+        let doc = try await node.createDoc()
+        let deletions = try await doc.subscribeDeletions()
+        // Let the subscription start before writing
+        try await Task.sleep(for: .milliseconds(200))
+        _ = try await doc.set(author: author, key: "rows/1", value: Data("one".utf8))
+        _ = try await doc.set(author: author, key: "rows/2", value: Data("two".utf8))
+        _ = try await doc.delete(author: author, key: "rows/1")
+
+        for try await deletion in deletions {
+            XCTAssertEqual(deletion.keyString, "rows/1")
+            XCTAssertEqual(deletion.authorId, author.id)
+            XCTAssertNil(deletion.peerId)
+            break
+        }
+    }
+
+    /// Test that remote entries arrive with their content when fetching content.
+    func testSubscribeFetchesRemoteContent() async throws {
+        let owner = try await IrohNode(config: .testing(seed: "fetch-owner", docsEnabled: true))
+        let reader = try await IrohNode(config: .testing(seed: "fetch-reader", docsEnabled: true))
+        try await owner.importAuthor(author)
+        let doc = try await owner.createDoc()
+        let joined = try await reader.joinDoc(ticket: try await doc.shareTicket(mode: .read))
+
+        for try await event in try await joined.subscribe(fetchContent: true, maxContentSize: 1024) {
+            switch event {
+            case .neighborUp:
+                // Written once the reader is in the swarm, so it arrives live
+                _ = try await doc.set(author: author, key: "photo", value: Data(count: 4096))
+                _ = try await doc.set(author: author, key: "caption", value: Data("beach".utf8))
+            case .contentFetched(let from, let entry, let content):
+                XCTAssertEqual(from, owner.nodeId)
+                XCTAssertEqual(entry.keyString, "caption", "Larger values are not fetched")
+                XCTAssertEqual(content, Data("beach".utf8))
+            default:
+                continue
+            }
+            if case .contentFetched = event { break }
+        }
+        try await reader.close()
+        try await owner.close()
+    }
+
+    /// Test that a read-only handle rejects writes but still reads.
+    func testReadOnlyOpenRejectsWrites() async throws {
+        let doc = try await node.createDoc()
+        _ = try await doc.set(author: author, key: "greeting", value: Data("hi".utf8))
+
+        let readOnly = try await node.openDoc(namespaceId: doc.namespaceId, mode: .readOnly)
+        let isReadOnly = await readOnly.isReadOnly
+        XCTAssertTrue(isReadOnly)
+
+        let entry = try await readOnly.get(key: "greeting")
+        XCTAssertNotNil(entry)
+
+        do {
+            _ = try await readOnly.set(author: author, key: "greeting", value: Data("bye".utf8))
+            XCTFail("Should have thrown docReadOnly")
+        } catch IrohError.docReadOnly {
+            // Expected
+        }
+
+        do {
+            _ = try await readOnly.shareTicket(mode: .write)
+            XCTFail("Should have thrown docReadOnly")
+        } catch IrohError.docReadOnly {
+            // Expected
+        }
+    }
+
     /// Test getting a share ticket.
     func testDocShare() async throws {
         let doc = try await node.createDoc()
@@ -105,4 +630,39 @@ final class IrohDocTests: XCTestCase {
         // Write ticket should be different (includes capability)
         XCTAssertNotEqual(readTicket, writeTicket, "Read and write tickets should differ")
     }
+
+    /// Test restoring a document from its snapshot blob.
+    func testDocSnapshotRestore() async throws {
+        let doc = try await node.createDoc()
+        _ = try await doc.set(author: author, key: "note", value: Data("saved".utf8))
+
+        let snapshot = try await doc.snapshotTicket()
+        XCTAssertTrue(snapshot.hasPrefix("blob"), "Snapshot ticket should be a blob ticket")
+
+        let restored = try await node.restoreDoc(
+            ticket: try await doc.shareTicket(mode: .read),
+            snapshot: snapshot
+        )
+        XCTAssertEqual(restored.namespaceId, doc.namespaceId)
+        let entry = try await restored.get(key: "note")
+        XCTAssertNotNil(entry)
+    }
+}
+
+/// Thread-safe accumulator for exported NDJSON in tests.
+private final class ExportCollector: @unchecked Sendable {
+    private let lock = NSLock()
+    private var buffer = Data()
+
+    var data: Data {
+        lock.lock()
+        defer { lock.unlock() }
+        return buffer
+    }
+
+    func append(_ chunk: Data) {
+        lock.lock()
+        buffer.append(chunk)
+        lock.unlock()
+    }
 }
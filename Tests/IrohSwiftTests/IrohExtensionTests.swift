@@ -153,11 +153,31 @@ final class IrohExtensionTests: XCTestCase {
         let testData = Data(repeating: 0x42, count: 1024)
         let ticket = try await node.put(testData)
 
-        let retrieved = try await node.get(ticket: ticket) { _ in
-            // Progress callback - may or may not be called for local gets
+        let collector = UpdateCollector<DownloadProgress>()
+        let retrieved = try await node.get(ticket: ticket) { progress in
+            collector.append(progress)
         }
 
         XCTAssertEqual(retrieved, testData)
+        // Even local gets end with the verification stages
+        let stages = collector.updates.map(\.stage)
+        XCTAssertEqual(Array(stages.suffix(2)), [.verifying, .verified])
+        XCTAssertEqual(collector.updates.last?.verified, UInt64(testData.count))
+        XCTAssertEqual(collector.updates.last?.verifiedFraction, 1.0)
+    }
+
+    func testStreamToSink() async throws {
+        let node = try await createTestNode()
+        let testData = Data(repeating: 0x24, count: 40_000)
+        let ticket = try await node.put(testData)
+
+        let collector = ChunkCollector()
+        let written = try await node.stream(ticket: ticket) { chunk in
+            collector.append(chunk)
+        }
+
+        XCTAssertEqual(written, UInt64(testData.count))
+        XCTAssertEqual(collector.data, testData)
     }
 
     // MARK: - Cancellation Tests
@@ -181,3 +201,21 @@ final class IrohExtensionTests: XCTestCase {
         }
     }
 }
+
+/// Thread-safe accumulator for sink chunks in tests.
+private final class ChunkCollector: @unchecked Sendable {
+    private let lock = NSLock()
+    private var buffer = Data()
+
+    var data: Data {
+        lock.lock()
+        defer { lock.unlock() }
+        return buffer
+    }
+
+    func append(_ chunk: Data) {
+        lock.lock()
+        buffer.append(chunk)
+        lock.unlock()
+    }
+}
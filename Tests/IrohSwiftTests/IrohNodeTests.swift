@@ -19,6 +19,49 @@ final class IrohNodeTests: XCTestCase {
         _ = node
     }
 
+    /// Test that constant node properties are readable without await.
+    func testSynchronousProperties() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        let node = try await IrohNode(config: config)
+
+        XCTAssertFalse(node.isDocsEnabled)
+        XCTAssertEqual(node.storageURL.standardizedFileURL.path, tempDir.standardizedFileURL.path)
+        let info = try await node.info()
+        XCTAssertEqual(node.nodeId, info.nodeId)
+        // Without relays the network cannot be probed
+        XCTAssertNil(info.networkCondition)
+    }
+
+    /// Test that an ephemeral node removes its data when closed.
+    func testEphemeralNodeRemovesDataOnClose() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+        try FileManager.default.createDirectory(at: tempDir, withIntermediateDirectories: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(relayEnabled: false, tempDirectory: tempDir, ephemeral: true)
+        let node = try await IrohNode(config: config)
+        XCTAssertTrue(node.storageURL.standardizedFileURL.path.hasPrefix(tempDir.standardizedFileURL.path))
+
+        let ticket = try await node.put(Data("preview".utf8))
+        let data = try await node.get(ticket: ticket)
+        XCTAssertEqual(data, Data("preview".utf8))
+
+        try await node.close()
+        XCTAssertFalse(FileManager.default.fileExists(atPath: node.storageURL.path))
+        XCTAssertEqual(try FileManager.default.contentsOfDirectory(atPath: tempDir.path), [])
+    }
+
     /// Test putting data returns a valid ticket.
     func testPut() async throws {
         let tempDir = FileManager.default.temporaryDirectory
@@ -57,6 +100,638 @@ final class IrohNodeTests: XCTestCase {
         XCTAssertTrue(ticket.hasPrefix("blob"), "Ticket should start with 'blob'")
     }
 
+    /// Test that a fresh node reports no pending work.
+    func testPendingWorkOnFreshNode() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        let node = try await IrohNode(config: config)
+
+        _ = try await node.put(Data("Already complete".utf8))
+        let pending = try await node.pendingWork()
+
+        XCTAssertFalse(pending.hasPendingWork)
+        XCTAssertEqual(pending.incompleteDownloads, 0)
+    }
+
+    /// Test that pending work can be read from a store no node has used.
+    func testPendingWorkWithoutNode() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        let pending = try await IrohNode.pendingWork(storagePath: tempDir)
+
+        XCTAssertFalse(pending.hasPendingWork)
+        XCTAssertEqual(pending.unsyncedDocs, 0)
+    }
+
+    /// Test that closing connections to a peer that is not connected is a no-op.
+    func testCloseConnectionsToUnknownPeer() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        let node = try await IrohNode(config: config)
+        let peer = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir.appendingPathComponent("peer"),
+            relayEnabled: false
+        ))
+        let peerInfo = try await peer.info()
+
+        let closed = try await node.closeConnections(to: peerInfo.nodeId)
+        XCTAssertEqual(closed, 0)
+
+        do {
+            _ = try await node.closeConnections(to: "not-a-node-id")
+            XCTFail("Should have thrown connectionCloseFailed")
+        } catch IrohError.connectionCloseFailed {
+            // Expected
+        }
+    }
+
+    /// Test that blob access rules are stored and listed.
+    func testBlobAccessRules() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        var config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        config.blobAccess = .allowList
+        let node = try await IrohNode(config: config)
+        let peer = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir.appendingPathComponent("peer"),
+            relayEnabled: false
+        ))
+        let peerId = try await peer.info().nodeId
+
+        try await node.setBlobAccess(.allow, for: peerId)
+        var access = try await node.blobAccess()
+        XCTAssertEqual(access, BlobAccessList(mode: .allowList, allowed: [peerId], denied: []))
+
+        try await node.setBlobAccessMode(.open)
+        try await node.setBlobAccess(.deny, for: peerId)
+        access = try await node.blobAccess()
+        XCTAssertEqual(access, BlobAccessList(mode: .open, allowed: [], denied: [peerId]))
+
+        do {
+            try await node.setBlobAccess(.allow, for: "not-a-node-id")
+            XCTFail("Should have thrown blobAccessFailed")
+        } catch IrohError.blobAccessFailed {
+            // Expected
+        }
+    }
+
+    /// Test that serving blobs can be paused without affecting local reads.
+    func testProviderPause() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        let node = try await IrohNode(config: config)
+        let ticket = try await node.put(Data("still readable".utf8))
+
+        var enabled = await node.isProviderEnabled
+        XCTAssertTrue(enabled)
+
+        try await node.setProviderEnabled(false)
+        enabled = await node.isProviderEnabled
+        XCTAssertFalse(enabled)
+        let data = try await node.get(ticket: ticket)
+        XCTAssertEqual(data, Data("still readable".utf8))
+
+        try await node.setProviderEnabled(true)
+        enabled = await node.isProviderEnabled
+        XCTAssertTrue(enabled)
+    }
+
+    /// Test that a fresh node has exchanged no traffic with peers.
+    func testPeerTrafficOnFreshNode() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        let node = try await IrohNode(config: config)
+
+        let traffic = try await node.peerTraffic()
+        XCTAssertTrue(traffic.isEmpty)
+    }
+
+    /// Test that watching direct addresses yields the current set first.
+    func testDirectAddresses() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        let node = try await IrohNode(config: config)
+
+        var iterator = try await node.directAddresses().makeAsyncIterator()
+        let addresses = try await iterator.next()
+
+        XCTAssertFalse(addresses?.isEmpty ?? true, "Node should have a direct address")
+    }
+
+    /// Test that an app discovery provider is told the node's addresses.
+    func testDiscoveryProviderReceivesPublish() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let published = expectation(description: "addresses published")
+        published.assertForOverFulfill = false
+        let discovery = RecordingDiscovery { _ in published.fulfill() }
+        let config = IrohConfig(
+            storagePath: tempDir,
+            relayEnabled: false,
+            discovery: discovery,
+            defaultDiscoveryEnabled: false
+        )
+        let node = try await IrohNode(config: config)
+
+        await fulfillment(of: [published], timeout: 10)
+        let info = try await node.info()
+        XCTAssertEqual(discovery.lastPublished?.nodeId, info.nodeId)
+    }
+
+    /// Test that a node without relays reports no relay latencies.
+    func testRelayReportWithoutRelays() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(storagePath: tempDir, relayEnabled: false)
+        let node = try await IrohNode(config: config)
+
+        let report = try await node.relayReport()
+        XCTAssertNil(report.homeRelay)
+        XCTAssertTrue(report.relays.isEmpty)
+        XCTAssertFalse(report.isHomeRelayNearest)
+        XCTAssertNil(report.pinnedRelay)
+
+        // Only configured relays can be pinned; unpinning always works
+        do {
+            try await node.setHomeRelay("https://relay.example.com")
+            XCTFail("Should have thrown homeRelayFailed")
+        } catch IrohError.homeRelayFailed {
+            // Expected
+        }
+        try await node.setHomeRelay(nil)
+    }
+
+    /// Test that a provider on the same machine is reachable directly.
+    func testCanReachLocalProvider() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let provider = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir.appendingPathComponent("provider"),
+            relayEnabled: false
+        ))
+        let client = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir.appendingPathComponent("client"),
+            relayEnabled: false
+        ))
+
+        let ticket = try await provider.put(Data("reachable".utf8))
+        let reachability = try await client.canReach(ticket)
+        XCTAssertTrue(reachability.isReachable, "Expected reachable, got \(reachability)")
+
+        do {
+            _ = try await client.canReach("not-a-node")
+            XCTFail("Should have thrown reachabilityCheckFailed")
+        } catch IrohError.reachabilityCheckFailed {
+            // Expected
+        }
+    }
+
+    /// Test that a message reaches a subscribed peer and fails without one.
+    func testSendMessage() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let receiver = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir.appendingPathComponent("receiver"),
+            relayEnabled: false
+        ))
+        let sender = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir.appendingPathComponent("sender"),
+            relayEnabled: false
+        ))
+        // The ticket carries the receiver's address hints
+        let target = try await receiver.put(Data("address".utf8))
+
+        do {
+            try await sender.sendMessage(Data("sync".utf8), to: target)
+            XCTFail("Should have thrown messageFailed")
+        } catch IrohError.messageFailed {
+            // Expected: nobody is subscribed yet
+        }
+
+        var messages = try await receiver.messages().makeAsyncIterator()
+        try await sender.sendMessage(Data("sync".utf8), to: target)
+        let message = try await messages.next()
+        XCTAssertEqual(message, DirectMessage(from: try await sender.info().nodeId, data: Data("sync".utf8)))
+    }
+
+    /// Test that test nodes get the same IDs and tickets and reach each other.
+    func testTestNodesAreDeterministic() async throws {
+        let firstConfig = IrohConfig.testing(seed: "provider")
+        let providerConfig = IrohConfig.testing(seed: "provider")
+        let fetcherConfig = IrohConfig.testing(seed: "fetcher")
+
+        let first = try await IrohNode(config: firstConfig)
+        let ticket = try await first.put(Data("hello".utf8))
+        try await first.close()
+
+        let provider = try await IrohNode(config: providerConfig)
+        XCTAssertEqual(provider.nodeId, first.nodeId)
+        let secondTicket = try await provider.put(Data("hello".utf8))
+        XCTAssertEqual(secondTicket, ticket)
+
+        let fetcher = try await IrohNode(config: fetcherConfig)
+        let data = try await fetcher.get(ticket: ticket)
+        XCTAssertEqual(data, Data("hello".utf8))
+
+        let author = try await IrohAuthor.testing(seed: "alice")
+        let sameAuthor = try await IrohAuthor.testing(seed: "alice")
+        XCTAssertEqual(author, sameAuthor)
+    }
+
+    /// Test that a download running out of time throws a timeout.
+    func testGetTimeoutThrowsTimeout() async throws {
+        let providerConfig = IrohConfig.testing(seed: "slow-provider")
+        let fetcherConfig = IrohConfig.testing(seed: "impatient-fetcher")
+
+        let provider = try await IrohNode(config: providerConfig)
+        let ticket = try await provider.put(Data(repeating: 3, count: 32 * 1024 * 1024))
+
+        let fetcher = try await IrohNode(config: fetcherConfig)
+        do {
+            _ = try await fetcher.get(ticket: ticket, options: OperationOptions(timeout: .milliseconds(50)))
+            XCTFail("Expected the download to time out")
+        } catch IrohError.timeout {
+            // Expected
+        }
+    }
+
+    /// Test that injected put failures surface as put errors, then clear.
+    func testInjectedPutFailures() async throws {
+        let config = IrohConfig.testing(seed: "faulty")
+        let node = try await IrohNode(config: config)
+
+        do {
+            try await node.injectFaults(FaultInjection(failNextPuts: 1))
+        } catch IrohError.faultInjectionFailed {
+            try await node.close()
+            throw XCTSkip("Library built without the faults feature")
+        }
+
+        do {
+            _ = try await node.put(Data("hello".utf8))
+            XCTFail("Put should fail while a fault is injected")
+        } catch IrohError.putFailed(let message, let operationId) {
+            XCTAssertTrue(message.contains("Injected put failure"))
+            XCTAssertNotNil(operationId)
+        }
+        _ = try await node.put(Data("hello".utf8))
+
+        try await node.injectFaults(.none)
+        try await node.close()
+    }
+
+    /// Test probing a provider for a blob it has and one it lacks.
+    func testProbeProviders() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let provider = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir.appendingPathComponent("provider"),
+            relayEnabled: false
+        ))
+        let client = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir.appendingPathComponent("client"),
+            relayEnabled: false
+        ))
+
+        let ticket = try await provider.put(Data("available".utf8))
+        let hash = try XCTUnwrap(await validateTicket(ticket).hash)
+        // Dial with the ticket's address hints so the probe can find the provider
+        _ = try await client.canReach(ticket)
+        let providerId = try await provider.info().nodeId
+
+        let probes = try await client.probeProviders(hash: hash, nodeIds: [providerId])
+        XCTAssertEqual(probes, [ProviderProbe(nodeId: providerId, availability: .complete(size: 9))])
+
+        do {
+            _ = try await client.probeProviders(hash: "not-a-hash", nodeIds: [providerId])
+            XCTFail("Should have thrown providerProbeFailed")
+        } catch IrohError.providerProbeFailed {
+            // Expected
+        }
+    }
+
+    /// Test that node handles are listed while open and released on close.
+    func testLiveHandlesTracksNode() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let before = liveHandles().filter { $0.kind == .node }.count
+        let node = try await IrohNode(config: IrohConfig(storagePath: tempDir, relayEnabled: false))
+        XCTAssertEqual(liveHandles().filter { $0.kind == .node }.count, before + 1)
+
+        try await node.close()
+        XCTAssertEqual(liveHandles().filter { $0.kind == .node }.count, before)
+    }
+
+    /// Test that puts and gets are counted with their bytes.
+    func testOperationCountersCountPutsAndGets() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let node = try await IrohNode(config: IrohConfig(storagePath: tempDir, relayEnabled: false))
+        let before = operationCounters()
+
+        let ticket = try await node.put(Data("counted".utf8))
+        _ = try await node.get(ticket: ticket)
+        _ = try? await node.get(ticket: "not-a-ticket")
+
+        let after = operationCounters()
+        XCTAssertGreaterThan(after.puts, before.puts)
+        XCTAssertGreaterThan(after.gets, before.gets)
+        XCTAssertGreaterThanOrEqual(after.bytesIn, before.bytesIn + 7)
+        XCTAssertGreaterThanOrEqual(after.bytesOut, before.bytesOut + 7)
+        XCTAssertGreaterThan(after.failures["iroh_get", default: 0], before.failures["iroh_get", default: 0])
+        try await node.close()
+    }
+
+    /// Test that a freshly created node reports itself healthy.
+    func testHealthOfNewNode() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let node = try await IrohNode(config: IrohConfig(storagePath: tempDir, relayEnabled: false))
+        let health = try await node.health()
+        XCTAssertTrue(health.isHealthy, "Unexpected health report: \(health)")
+        XCTAssertNil(health.storeError)
+        XCTAssertNil(health.runtimeError)
+        XCTAssertNil(health.endpointError)
+    }
+
+    /// Test the memory report of an idle node.
+    func testMemoryReportOfIdleNode() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let node = try await IrohNode(config: IrohConfig(storagePath: tempDir, relayEnabled: false))
+        _ = try await node.put(Data("hello".utf8))
+        let report = try await node.memoryReport()
+        XCTAssertGreaterThan(report.storeDiskBytes, 0)
+        XCTAssertGreaterThan(report.runtimeTasks, 0)
+        XCTAssertEqual(report.queuedCallbacks, 0)
+        XCTAssertEqual(report.openConnections, 0)
+    }
+
+    /// Test that closing within a generous deadline is graceful.
+    func testCloseWithDeadline() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let node = try await IrohNode(config: IrohConfig(storagePath: tempDir, relayEnabled: false))
+        _ = try await node.put(Data("flushed".utf8))
+
+        let graceful = try await node.close(deadline: .seconds(10))
+        XCTAssertTrue(graceful)
+
+        // Closing again is a no-op
+        let again = try await node.close(deadline: .seconds(10))
+        XCTAssertTrue(again)
+    }
+
+    /// Test that the storage alert fires once the store grows past the threshold.
+    func testStorageAlertFiresWhenExceeded() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let exceeded = expectation(description: "storage threshold exceeded")
+        let alert = StorageAlert(thresholdBytes: 4 * 1024 * 1024, checkInterval: .milliseconds(100)) { isExceeded, sizeBytes in
+            if isExceeded && sizeBytes >= 4 * 1024 * 1024 {
+                exceeded.fulfill()
+            }
+        }
+        let node = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir,
+            relayEnabled: false,
+            storageAlert: alert
+        ))
+
+        _ = try await node.put(Data(repeating: 7, count: 8 * 1024 * 1024))
+        await fulfillment(of: [exceeded], timeout: 10)
+        try await node.close()
+    }
+
+    /// Test that the content announcer hands out tickets for tagged content.
+    func testContentAnnouncerAnnouncesTaggedContent() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let announced = expectation(description: "pinned content announced")
+        announced.assertForOverFulfill = false
+        let announcer = ContentAnnouncer(interval: .milliseconds(100), tagPrefix: "pinned/") { tag, ticket in
+            if tag == "pinned/a" && !ticket.isEmpty {
+                announced.fulfill()
+            }
+        }
+        let node = try await IrohNode(config: IrohConfig(
+            storagePath: tempDir,
+            relayEnabled: false,
+            contentAnnouncer: announcer
+        ))
+
+        let ticket = try await node.put(Data("long-lived".utf8))
+        let hash = try XCTUnwrap(await validateTicket(ticket).hash)
+        try await node.tagBlob(hash: hash, name: "pinned/a")
+        await fulfillment(of: [announced], timeout: 10)
+        try await node.close()
+    }
+
+    /// Test that downloads show up on the node event stream.
+    func testEventsReportDownloads() async throws {
+        let providerConfig = IrohConfig.testing(seed: "evented-provider")
+        let fetcherConfig = IrohConfig.testing(seed: "evented-fetcher")
+
+        // Content already on a node is served without a download, so
+        // fetch it from a peer
+        let provider = try await IrohNode(config: providerConfig)
+        let ticket = try await provider.put(Data("evented".utf8))
+
+        let node = try await IrohNode(config: fetcherConfig)
+        let events = try await node.events()
+        _ = try await node.get(ticket: ticket)
+
+        var received: [NodeEvent] = []
+        for try await event in events {
+            if case .connectivityChanged = event { continue }
+            received.append(event)
+            if case .downloadFinished = event { break }
+        }
+
+        guard case .downloadStarted(let hash, let operationId) = received.first else {
+            return XCTFail("Expected downloadStarted first, got \(received)")
+        }
+        XCTAssertNotNil(operationId)
+        XCTAssertEqual(received.last, .downloadFinished(hash: hash, operationId: operationId, error: nil))
+        try await node.close()
+    }
+
+    /// Test that cellular bandwidth caps apply once the node is on cellular.
+    func testCellularBandwidthCaps() async throws {
+        let providerConfig = IrohConfig.testing(seed: "capped-provider")
+        var fetcherConfig = IrohConfig.testing(seed: "capped-fetcher")
+        fetcherConfig.bandwidthLimits = BandwidthLimits(
+            cellular: BandwidthCaps(downloadBytesPerSecond: 200_000)
+        )
+
+        let provider = try await IrohNode(config: providerConfig)
+        let ticket = try await provider.put(Data(repeating: 9, count: 300_000))
+        let node = try await IrohNode(config: fetcherConfig)
+        try await node.setNetworkKind(.cellular)
+
+        let clock = ContinuousClock()
+        let start = clock.now
+        let data = try await node.get(ticket: ticket)
+        XCTAssertEqual(data.count, 300_000)
+        XCTAssertGreaterThanOrEqual(clock.now - start, .seconds(1))
+
+        try await node.close()
+        try await provider.close()
+    }
+
+    /// Test that events arrive through a dedicated callback thread.
+    func testDedicatedCallbackThreadDeliversEvents() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(
+            storagePath: tempDir,
+            relayEnabled: false,
+            dedicatedCallbackThread: true
+        )
+        let node = try await IrohNode(config: config)
+        let events = try await node.events()
+
+        let ticket = try await node.put(Data("delivered".utf8))
+        _ = try await node.get(ticket: ticket)
+
+        var received: [NodeEvent] = []
+        for try await event in events {
+            if case .connectivityChanged = event { continue }
+            received.append(event)
+            if case .downloadFinished = event { break }
+        }
+
+        guard case .downloadStarted = received.first else {
+            return XCTFail("Expected downloadStarted first, got \(received)")
+        }
+        try await node.close()
+    }
+
+    /// Test that the app identifier is reported by node info.
+    func testAppIdReportedInNodeInfo() async throws {
+        let tempDir = FileManager.default.temporaryDirectory
+            .appendingPathComponent(UUID().uuidString, isDirectory: true)
+
+        defer {
+            try? FileManager.default.removeItem(at: tempDir)
+        }
+
+        let config = IrohConfig(
+            storagePath: tempDir,
+            relayEnabled: false,
+            appId: "com.example.photos/2.1"
+        )
+        let node = try await IrohNode(config: config)
+        let info = try await node.info()
+        XCTAssertEqual(info.appId, "com.example.photos/2.1")
+        try await node.close()
+
+        var invalid = config
+        invalid.appId = ""
+        XCTAssertThrowsError(try invalid.validate())
+    }
+
     /// Test that IrohConfig uses Application Support by default.
     func testDefaultStoragePath() {
         let config = IrohConfig()
@@ -89,3 +764,31 @@ final class IrohNodeTests: XCTestCase {
         XCTAssertFalse(config.relayEnabled)
     }
 }
+
+/// Discovery provider that records published addresses and resolves nothing.
+private final class RecordingDiscovery: DiscoveryProvider, @unchecked Sendable {
+    private let lock = NSLock()
+    private var published: NodeAddress?
+    private let onPublish: @Sendable (NodeAddress) -> Void
+
+    init(onPublish: @escaping @Sendable (NodeAddress) -> Void) {
+        self.onPublish = onPublish
+    }
+
+    var lastPublished: NodeAddress? {
+        lock.lock()
+        defer { lock.unlock() }
+        return published
+    }
+
+    func resolve(nodeId: String) async throws -> NodeAddress? {
+        nil
+    }
+
+    func publish(_ address: NodeAddress) async {
+        lock.lock()
+        published = address
+        lock.unlock()
+        onPublish(address)
+    }
+}
@@ -0,0 +1,20 @@
+import Foundation
+
+/// Thread-safe accumulator for progress updates reported to callbacks in
+/// tests.
+final class UpdateCollector<Update>: @unchecked Sendable {
+    private let lock = NSLock()
+    private var buffer: [Update] = []
+
+    var updates: [Update] {
+        lock.lock()
+        defer { lock.unlock() }
+        return buffer
+    }
+
+    func append(_ update: Update) {
+        lock.lock()
+        buffer.append(update)
+        lock.unlock()
+    }
+}
@@ -0,0 +1,13 @@
+/* Cargo features the library was built with (the default set). */
+/* scripts/build-xcframework.sh writes this file for each build. */
+
+#ifndef IROH_SWIFT_FEATURES_H
+#define IROH_SWIFT_FEATURES_H
+
+#define IROH_FEATURE_DOCS
+#define IROH_FEATURE_DOWNLOADER_PROGRESS
+#define IROH_FEATURE_SQLITE
+#define IROH_FEATURE_COMPRESSION
+#define IROH_FEATURE_CHUNKING
+
+#endif /* IROH_SWIFT_FEATURES_H */
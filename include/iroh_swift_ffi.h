@@ -9,54 +9,61 @@
 #include <stdbool.h>
 #include <stdint.h>
 #include <stdlib.h>
+#include "iroh_swift_features.h"
 
 /**
- * Share mode for document tickets.
+ * Who may fetch blobs from a node when a peer has no rule of its own.
  */
-typedef enum IrohDocShareMode {
+typedef enum IrohBlobAccessMode {
     /**
-     * Read-only access.
+     * Every peer that is not denied.
      */
-    Read = 0,
+    BlobAccessOpen = 0,
     /**
-     * Read and write access.
+     * Only peers that are allowed.
      */
-    Write = 1,
-} IrohDocShareMode;
+    BlobAccessAllowList = 1,
+} IrohBlobAccessMode;
 
 /**
- * Document event types.
+ * Blob access rule for one peer.
  */
-typedef enum IrohDocEventType {
+typedef enum IrohBlobAccessRule {
     /**
-     * A local insertion.
+     * No rule; the access mode decides.
      */
-    InsertLocal = 0,
+    BlobAccessDefault = 0,
     /**
-     * Received a remote insert.
+     * The peer may fetch blobs.
      */
-    InsertRemote = 1,
+    BlobAccessAllow = 1,
     /**
-     * Content is now available locally.
+     * The peer may not fetch blobs.
      */
-    ContentReady = 2,
+    BlobAccessDeny = 2,
+} IrohBlobAccessRule;
+
+/**
+ * How much of a blob a provider has (see `iroh_blob_probe_providers`).
+ */
+typedef enum IrohBlobAvailability {
     /**
-     * All pending content is ready.
+     * The provider has the whole blob.
      */
-    PendingContentReady = 3,
+    BlobComplete = 0,
     /**
-     * A new neighbor joined the swarm.
+     * The provider has part of the blob.
      */
-    NeighborUp = 4,
+    BlobPartial = 1,
     /**
-     * A neighbor left the swarm.
+     * The provider has none of the blob.
      */
-    NeighborDown = 5,
+    BlobMissing = 2,
     /**
-     * Sync finished with a peer.
+     * The provider could not be asked; see `reason`.
      */
-    SyncFinished = 6,
-} IrohDocEventType;
+    BlobUnknown = 3,
+} IrohBlobAvailability;
 
 /**
  * Blob format for tickets and tags.
@@ -73,875 +80,5925 @@ typedef enum IrohBlobFormat {
 } IrohBlobFormat;
 
 /**
- * Configuration for creating a node.
+ * Sort order for blob listings.
  */
-typedef struct IrohNodeConfig {
+typedef enum IrohBlobSort {
     /**
-     * Path to the blob store directory (required).
+     * By hash (stable, otherwise arbitrary).
      */
-    const char *storage_path;
+    ByHash = 0,
     /**
-     * Whether to use relay servers (default: true).
+     * Smallest blobs first.
      */
-    bool relay_enabled;
+    SizeAscending = 1,
     /**
-     * Custom relay URL (null to use n0's public relays).
-     * Must be a valid URL like "https://relay.example.com".
+     * Largest blobs first.
      */
-    const char *custom_relay_url;
+    SizeDescending = 2,
     /**
-     * Whether to enable the Docs engine (default: false).
-     * When enabled, the node can create, join, and sync documents.
+     * Most recently added first.
      */
-    bool docs_enabled;
-} IrohNodeConfig;
-
-/**
- * Opaque handle to an Iroh node.
- *
- * The actual IrohNode is stored in a Box, and this handle holds
- * a raw pointer to that Box.
- */
-typedef struct IrohNodeHandle {
-    uint8_t _private[0];
-} IrohNodeHandle;
+    NewestFirst = 3,
+    /**
+     * Least recently added first.
+     */
+    OldestFirst = 4,
+} IrohBlobSort;
 
 /**
- * Callback for node creation.
+ * What a node does when its blob store database is corrupt. What recovery
+ * sets aside is moved to `quarantine/` in the storage directory.
  */
-typedef struct IrohNodeCreateCallback {
+typedef enum IrohBlobStoreRecovery {
     /**
-     * Opaque pointer passed back to Swift.
+     * Fail node creation with `on_corrupt`.
      */
-    void *userdata;
+    BlobStoreRecoveryFail = 0,
     /**
-     * Called on success with the node handle.
+     * Rebuild the database from the blob files. Complete files matching
+     * their hash are added back under automatic tags; tag names and blobs
+     * small enough to be kept inside the database are lost.
      */
-    void (*on_success)(void *userdata, struct IrohNodeHandle *handle);
+    BlobStoreRecoveryReindex = 1,
     /**
-     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     * Set the database and blob files aside and start with an empty store.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohNodeCreateCallback;
-
-/**
- * Borrowed bytes from Swift (read-only view into Swift memory).
- */
-typedef struct IrohBytes {
-    const uint8_t *data;
-    uintptr_t len;
-} IrohBytes;
+    BlobStoreRecoveryQuarantine = 2,
+} IrohBlobStoreRecovery;
 
 /**
- * Callback for operations that return a string on success.
- *
- * NOTE: Callbacks run on a Tokio worker thread. Swift must safely
- * resume continuations from that context.
+ * Kind of a ticket in a ticket bundle.
  */
-typedef struct IrohCallback {
-    /**
-     * Opaque pointer passed back to Swift.
-     */
-    void *userdata;
+typedef enum IrohBundledTicketKind {
     /**
-     * Called on success with a C string (caller must free with `iroh_string_free`).
+     * A blob ticket.
      */
-    void (*on_success)(void *userdata, const char *result);
+    BundledBlob = 0,
     /**
-     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     * A doc ticket.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohCallback;
+    BundledDoc = 1,
+} IrohBundledTicketKind;
 
 /**
- * Owned bytes returned to Swift (must be freed with `iroh_bytes_free`).
+ * Compression of blob content stored with a metadata record.
  */
-typedef struct IrohOwnedBytes {
-    uint8_t *data;
-    uintptr_t len;
-    uintptr_t capacity;
-} IrohOwnedBytes;
+typedef enum IrohCompression {
+    /**
+     * Stored as given.
+     */
+    Uncompressed = 0,
+    /**
+     * Compressed with zstd, if that makes the content smaller.
+     */
+    Zstd = 1,
+} IrohCompression;
 
+#if defined(IROH_FEATURE_DOCS)
 /**
- * Callback for operations that return bytes on success.
+ * Document event types.
  */
-typedef struct IrohGetCallback {
+typedef enum IrohDocEventType {
     /**
-     * Opaque pointer passed back to Swift.
+     * A local insertion.
      */
-    void *userdata;
+    InsertLocal = 0,
     /**
-     * Called on success with owned bytes (caller must free with `iroh_bytes_free`).
+     * Received a remote insert.
      */
-    void (*on_success)(void *userdata, struct IrohOwnedBytes bytes);
+    InsertRemote = 1,
     /**
-     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     * Content is now available locally.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohGetCallback;
+    ContentReady = 2,
+    /**
+     * All pending content is ready.
+     */
+    PendingContentReady = 3,
+    /**
+     * A new neighbor joined the swarm.
+     */
+    NeighborUp = 4,
+    /**
+     * A neighbor left the swarm.
+     */
+    NeighborDown = 5,
+    /**
+     * Sync finished with a peer.
+     */
+    SyncFinished = 6,
+    /**
+     * An entry written before the subscription, replayed from a cursor.
+     */
+    Replayed = 7,
+    /**
+     * The content of a remote insert is available locally, delivered with
+     * the entry when the subscription fetches content.
+     */
+    ContentFetched = 8,
+    /**
+     * A key, or every key under a prefix, was deleted. Only delivered by
+     * deletions-only subscriptions; the entry is the tombstone, with the
+     * deleted key and the deleting author.
+     */
+    EntryDeleted = 9,
+} IrohDocEventType;
+#endif
 
+#if defined(IROH_FEATURE_DOCS)
 /**
- * Progress information for a download operation.
+ * Access mode for opening or joining a document.
  */
-typedef struct IrohDownloadProgress {
+typedef enum IrohDocOpenMode {
     /**
-     * Bytes downloaded so far.
+     * Writes are allowed if the document capability permits them.
      */
-    uint64_t downloaded;
+    ReadWrite = 0,
     /**
-     * Total bytes expected (0 if unknown).
+     * Writes are rejected on this handle even if the capability is writable.
      */
-    uint64_t total;
-} IrohDownloadProgress;
+    ReadOnly = 1,
+} IrohDocOpenMode;
+#endif
 
+#if defined(IROH_FEATURE_DOCS)
 /**
- * Callback for get operations with progress reporting.
+ * Share mode for document tickets.
  */
-typedef struct IrohGetProgressCallback {
+typedef enum IrohDocShareMode {
     /**
-     * Opaque pointer passed back to Swift.
+     * Read-only access.
      */
-    void *userdata;
+    Read = 0,
     /**
-     * Called with progress updates during download.
+     * Read and write access.
      */
-    void (*on_progress)(void *userdata, struct IrohDownloadProgress progress);
+    Write = 1,
+} IrohDocShareMode;
+#endif
+
+/**
+ * What a node does when its docs database is corrupt.
+ */
+typedef enum IrohDocsStoreRecovery {
     /**
-     * Called on success with owned bytes (caller must free with `iroh_bytes_free`).
+     * Fail node creation with `on_corrupt`.
      */
-    void (*on_success)(void *userdata, struct IrohOwnedBytes bytes);
+    DocsStoreRecoveryFail = 0,
     /**
-     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     * Move the database to `quarantine/` in the storage directory and
+     * start without documents or authors; documents must be joined again.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohGetProgressCallback;
+    DocsStoreRecoveryDiscard = 1,
+} IrohDocsStoreRecovery;
 
+#if defined(IROH_FEATURE_DOWNLOADER_PROGRESS)
 /**
- * Information about an Iroh node.
+ * Stage of a download reporting progress.
  */
-typedef struct IrohNodeInfo {
+typedef enum IrohDownloadStage {
     /**
-     * Node ID as a string (caller must free with `iroh_string_free`).
+     * Bytes are arriving and being verified as they do.
      */
-    const char *node_id;
+    DownloadReceiving = 0,
     /**
-     * Relay URL if connected (caller must free with `iroh_string_free`).
-     * Null if not connected to a relay.
+     * Every byte arrived; the store is confirming the whole blob.
      */
-    const char *relay_url;
+    DownloadVerifying = 1,
     /**
-     * Whether the node is connected to the network.
+     * The blob is verified; `on_success` follows.
      */
-    bool is_connected;
-} IrohNodeInfo;
+    DownloadVerified = 2,
+} IrohDownloadStage;
+#endif
 
+#if defined(IROH_FEATURE_DOCS)
 /**
- * Callback for node info retrieval.
+ * How a document write is stored before it is reported done.
  */
-typedef struct IrohNodeInfoCallback {
+typedef enum IrohDurability {
     /**
-     * Opaque pointer passed back to Swift.
+     * Committed together with other writes within half a second, so a
+     * crash can lose the latest writes.
      */
-    void *userdata;
+    DurabilityBatched = 0,
     /**
-     * Called on success with node info.
+     * Committed and synced to disk before the write is reported done.
      */
-    void (*on_success)(void *userdata, struct IrohNodeInfo info);
+    DurabilityImmediate = 1,
+} IrohDurability;
+#endif
+
+/**
+ * Kind of resource a live handle refers to.
+ */
+typedef enum IrohHandleKind {
     /**
-     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     * A node handle from `iroh_node_create`.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohNodeInfoCallback;
+    NodeHandle = 0,
+    /**
+     * A document handle from `iroh_doc_create`, `iroh_doc_join` or `iroh_doc_open`.
+     */
+    DocHandle = 1,
+    /**
+     * A subscription handle, released by `iroh_subscription_cancel`.
+     */
+    SubscriptionHandle = 2,
+} IrohHandleKind;
 
 /**
- * Parsed ticket information.
+ * How well the network supports direct connections.
  */
-typedef struct IrohTicketInfo {
+typedef enum IrohNetworkCondition {
     /**
-     * Whether the ticket is valid.
+     * No network report has completed yet, or the node has no relays.
      */
-    bool is_valid;
+    NetworkUnknown = 0,
     /**
-     * The blob hash as a string (caller must free with `iroh_string_free`).
-     * Null if invalid.
+     * UDP works and hole punching should succeed.
      */
-    const char *hash;
+    NetworkOpen = 1,
     /**
-     * The node ID from the ticket (caller must free with `iroh_string_free`).
-     * Null if invalid.
+     * UDP works, but the NAT often defeats direct connections.
      */
-    const char *node_id;
+    NetworkHardNat = 2,
     /**
-     * Whether this is a recursive (collection) ticket.
+     * UDP is blocked; all traffic goes through a relay.
      */
-    bool is_recursive;
-} IrohTicketInfo;
+    NetworkRelayOnly = 3,
+    /**
+     * A captive portal intercepts traffic until the user signs in.
+     */
+    NetworkCaptivePortal = 4,
+    /**
+     * Neither UDP nor any relay could be reached.
+     */
+    NetworkOffline = 5,
+} IrohNetworkCondition;
 
 /**
- * Callback for ticket validation.
+ * Kind of network a device is on, which selects its bandwidth caps.
  */
-typedef struct IrohTicketValidateCallback {
+typedef enum IrohNetworkKind {
     /**
-     * Opaque pointer passed back to Swift.
+     * Wi-Fi, Ethernet or another unmetered network.
      */
-    void *userdata;
+    NetworkKindWifi = 0,
     /**
-     * Called with validation result. Always called (never fails).
+     * A cellular, usually metered, network.
      */
-    void (*on_complete)(void *userdata, struct IrohTicketInfo info);
-} IrohTicketValidateCallback;
+    NetworkKindCellular = 1,
+} IrohNetworkKind;
 
 /**
- * Callback for node close operation.
+ * Kind of node event (see `iroh_node_subscribe_events`).
  */
-typedef struct IrohCloseCallback {
+typedef enum IrohNodeEventType {
     /**
-     * Opaque pointer passed back to Swift.
+     * The home relay or the direct addresses changed.
      */
-    void *userdata;
+    NodeConnectivityChanged = 0,
     /**
-     * Called when close completes successfully.
+     * A download started.
      */
-    void (*on_complete)(void *userdata);
+    NodeDownloadStarted = 1,
     /**
-     * Called if close fails with an error message.
+     * A download ended; `error` is null on success.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohCloseCallback;
-
-/**
- * Options for put/get operations.
- */
-typedef struct IrohOperationOptions {
+    NodeDownloadFinished = 2,
     /**
-     * Timeout in milliseconds (0 = no timeout).
+     * A pass removing expired document entries finished.
      */
-    uint64_t timeout_ms;
-} IrohOperationOptions;
-
-/**
- * Author secret key (32 bytes).
- *
- * This is the private key material used for signing document entries.
- * Must be kept secure (e.g., in iOS Keychain).
- */
-typedef struct IrohAuthorSecret {
-    uint8_t bytes[32];
-} IrohAuthorSecret;
-
-/**
- * Author public ID (32 bytes).
- *
- * This is the public identifier derived from the secret key.
- * Safe to share and store openly.
- */
-typedef struct IrohAuthorId {
-    uint8_t bytes[32];
-} IrohAuthorId;
-
-/**
- * Callback for author creation.
- */
-typedef struct IrohAuthorCreateCallback {
+    NodeExpiredPruned = 3,
     /**
-     * Opaque pointer passed back to Swift.
+     * A peer joined a document's swarm.
      */
-    void *userdata;
+    NodeNeighborUp = 4,
     /**
-     * Called on success with the author secret and ID.
+     * A peer left a document's swarm.
      */
-    void (*on_success)(void *userdata, struct IrohAuthorSecret secret, struct IrohAuthorId id);
+    NodeNeighborDown = 5,
     /**
-     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     * A document sync with a peer ended; `error` is null on success.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohAuthorCreateCallback;
+    NodeSyncFinished = 6,
+    /**
+     * An operation exceeded the watchdog's threshold.
+     */
+    NodeSlowOperation = 7,
+    /**
+     * A peer was refused a blob fetch by the access list.
+     */
+    NodeFetchRejected = 8,
+    /**
+     * A peer's blob request was refused by the request limits.
+     */
+    NodeFetchLimited = 9,
+    /**
+     * A document write was added to the outbox.
+     */
+    NodeOutboxQueued = 10,
+    /**
+     * A peer synced an outbox write.
+     */
+    NodeOutboxDelivered = 11,
+    /**
+     * A peer synced an outbox write, but a newer write by `author_id` won.
+     */
+    NodeOutboxSuperseded = 12,
+    /**
+     * An outbox write can never be delivered; see `error`.
+     */
+    NodeOutboxFailed = 13,
+    /**
+     * Durable peer `peer_id` confirmed a document's local writes; see
+     * `confirmed_through` and `backed_up`.
+     */
+    NodeDurableSync = 14,
+    /**
+     * The content announcer republished this node's discovery records;
+     * see `announced_tags`.
+     */
+    NodeContentAnnounced = 15,
+    /**
+     * A network report showed a different network condition; see
+     * `network_condition`.
+     */
+    NodeNetworkConditionChanged = 16,
+    /**
+     * A time-limited tag expired and was removed; see `tag_name` and `hash`.
+     */
+    NodeTagExpired = 17,
+    /**
+     * A document sync with peer `peer_id` ended; see `last_success`,
+     * `pending_entries`, `failure_streak` and `error`.
+     */
+    NodePeerSyncChanged = 18,
+    /**
+     * Garbage collection of profile `profile_id`'s content started; see
+     * `gc_blobs` and `gc_size`.
+     */
+    NodeGcStarted = 19,
+    /**
+     * Garbage collection of profile `profile_id`'s content finished; see
+     * `gc_blobs` and `gc_size`.
+     */
+    NodeGcFinished = 20,
+} IrohNodeEventType;
 
 /**
- * Opaque handle to an Iroh document.
- *
- * Documents are syncing key-value stores shared between peers.
- * The handle wraps a Doc from iroh-docs.
+ * Strategy for downloading content available from several providers.
  */
-typedef struct IrohDocHandle {
-    uint8_t _private[0];
-} IrohDocHandle;
+typedef enum IrohProviderStrategy {
+    /**
+     * Use whichever provider connects first, falling back to the others.
+     */
+    Race = 0,
+    /**
+     * Split collection downloads across all providers in parallel.
+     */
+    Split = 1,
+} IrohProviderStrategy;
 
 /**
- * Callback for document creation/join operations.
+ * How a peer was reached by `iroh_can_reach`.
  */
-typedef struct IrohDocCreateCallback {
+typedef enum IrohReachability {
     /**
-     * Opaque pointer passed back to Swift.
+     * Reached over a direct UDP path.
      */
-    void *userdata;
+    Direct = 0,
     /**
-     * Called on success with the document handle and namespace ID.
+     * Reached only through a relay so far.
      */
-    void (*on_success)(void *userdata, struct IrohDocHandle *handle, const char *namespace_id);
+    Relay = 1,
     /**
-     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     * Not reached.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohDocCreateCallback;
+    Unreachable = 2,
+} IrohReachability;
 
 /**
- * Callback for document set operations.
+ * One of a node's databases.
  */
-typedef struct IrohDocSetCallback {
-    /**
-     * Opaque pointer passed back to Swift.
-     */
-    void *userdata;
+typedef enum IrohStoreDatabase {
     /**
-     * Called on success with the content hash (caller must free with `iroh_string_free`).
+     * The blob store's index of blobs and tags.
      */
-    void (*on_success)(void *userdata, const char *hash);
+    StoreDatabaseBlobs = 0,
     /**
-     * Called on failure with an error message.
+     * The docs engine's replicas, authors and entries.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohDocSetCallback;
+    StoreDatabaseDocs = 1,
+} IrohStoreDatabase;
 
 /**
- * A document entry (key-value pair with metadata).
+ * Alternate encoding for ticket strings.
  */
-typedef struct IrohDocEntry {
+typedef enum IrohTicketEncoding {
     /**
-     * Author ID who wrote this entry (32 bytes).
+     * Standard lowercase ticket.
      */
-    struct IrohAuthorId author_id;
+    Standard = 0,
     /**
-     * Key bytes (owned, must be freed).
+     * Uppercase ticket suited to the QR alphanumeric mode.
      */
-    struct IrohOwnedBytes key;
+    QrAlphanumeric = 1,
     /**
-     * Content hash as hex string (must be freed with `iroh_string_free`).
+     * z-base-32 payload, easier to read aloud.
      */
-    char *content_hash;
+    ZBase32 = 2,
+} IrohTicketEncoding;
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * What a document write filter does with a remote entry.
+ */
+typedef enum IrohWriteVerdict {
     /**
-     * Size of the content in bytes.
+     * Keep the entry.
      */
-    uint64_t content_size;
+    WriteAccept = 0,
     /**
-     * Timestamp when entry was created (microseconds since epoch).
+     * Roll the entry back.
      */
-    uint64_t timestamp;
-} IrohDocEntry;
+    WriteReject = 1,
+    /**
+     * Roll the entry back and report it with its content.
+     */
+    WriteQuarantine = 2,
+} IrohWriteVerdict;
+#endif
 
 /**
- * Callback for document get operations.
+ * Caller-provided entropy for key generation.
+ *
+ * When `fill` is null, keys come from the system RNG.
  */
-typedef struct IrohDocGetCallback {
+typedef struct IrohEntropySource {
     /**
      * Opaque pointer passed back to Swift.
      */
     void *userdata;
     /**
-     * Called on success with the entry (may be null if not found).
-     * Caller must free entry with `iroh_doc_entry_free` if not null.
+     * Fill `len` bytes at `buf` and return true, or return false to fail
+     * key generation. Called synchronously on the calling thread.
      */
-    void (*on_success)(void *userdata, const struct IrohDocEntry *entry);
+    bool (*fill)(void *userdata, uint8_t *buf, uintptr_t len);
+} IrohEntropySource;
+
+/**
+ * Opaque handle to a pending discovery lookup.
+ *
+ * Answer it with `iroh_discovery_request_complete`.
+ */
+typedef struct IrohDiscoveryRequest {
+    uint8_t _private[0];
+} IrohDiscoveryRequest;
+
+/**
+ * This node's addresses, as passed to `IrohDiscoveryProvider::publish`.
+ */
+typedef struct IrohNodeAddr {
     /**
-     * Called on failure with an error message.
+     * Node ID.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohDocGetCallback;
+    const char *node_id;
+    /**
+     * Home relay URL, or null if none.
+     */
+    const char *relay_url;
+    /**
+     * Direct addresses as "ip:port" strings.
+     */
+    const char *const *direct_addrs;
+    /**
+     * Number of direct addresses.
+     */
+    uintptr_t direct_addrs_len;
+} IrohNodeAddr;
 
 /**
- * Streaming callback for get_many (prefix queries).
- * Called multiple times - once per entry, then on_complete.
+ * App-provided discovery, used next to or instead of n0's DNS/pkarr discovery.
+ *
+ * Leave `resolve` and `publish` null to use only the default discovery.
  */
-typedef struct IrohDocGetManyCallback {
+typedef struct IrohDiscoveryProvider {
     /**
      * Opaque pointer passed back to Swift.
      */
     void *userdata;
     /**
-     * Called for each entry found. Entry must be freed with `iroh_doc_entry_free`.
+     * Look up a node's addresses. Must answer `request` exactly once with
+     * `iroh_discovery_request_complete`, from any thread.
      */
-    void (*on_entry)(void *userdata, const struct IrohDocEntry *entry);
+    void (*resolve)(void *userdata, const char *node_id, struct IrohDiscoveryRequest *request);
     /**
-     * Called when iteration completes successfully.
+     * Publish this node's addresses whenever they change. The strings are
+     * only valid for the duration of the call.
      */
-    void (*on_complete)(void *userdata);
+    void (*publish)(void *userdata, struct IrohNodeAddr addr);
     /**
-     * Called on error. No more callbacks after this.
+     * Called once when the node no longer uses the provider, including
+     * when node creation fails.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohDocGetManyCallback;
+    void (*release)(void *userdata);
+} IrohDiscoveryProvider;
 
 /**
- * Callback for document delete operations.
+ * Alert when the store's size on disk crosses a threshold.
+ *
+ * Leave `on_threshold` null to disable alerts.
  */
-typedef struct IrohDocDelCallback {
+typedef struct IrohStorageAlert {
     /**
      * Opaque pointer passed back to Swift.
      */
     void *userdata;
     /**
-     * Called on success with count of deleted entries.
+     * Size in bytes at which the store counts as full.
      */
-    void (*on_success)(void *userdata, uint64_t deleted_count);
+    uint64_t threshold_bytes;
     /**
-     * Called on failure with an error message.
+     * Milliseconds between measurements (0 = every 60 seconds).
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohDocDelCallback;
+    uint64_t check_interval_ms;
+    /**
+     * Called on a background thread with `exceeded` true when the store
+     * reaches `threshold_bytes`, and false when it drops below again.
+     */
+    void (*on_threshold)(void *userdata, bool exceeded, uint64_t size_bytes);
+    /**
+     * Called once when the node no longer uses the alert, including when
+     * node creation fails.
+     */
+    void (*release)(void *userdata);
+} IrohStorageAlert;
 
 /**
- * Opaque handle to a document subscription.
+ * An operation that has run longer than the watchdog's threshold.
  *
- * Used to cancel an active subscription.
+ * Strings are only valid for the duration of the callback.
  */
-typedef struct IrohSubscriptionHandle {
-    uint8_t _private[0];
-} IrohSubscriptionHandle;
+typedef struct IrohSlowOperation {
+    /**
+     * The operation's id (see `iroh_last_operation_id`).
+     */
+    uint64_t operation_id;
+    /**
+     * Name of the FFI function that started it, like "iroh_get".
+     */
+    const char *name;
+    /**
+     * What it was doing, like "download".
+     */
+    const char *stage;
+    /**
+     * Milliseconds it had been running.
+     */
+    uint64_t elapsed_ms;
+} IrohSlowOperation;
 
 /**
- * A document event from subscription.
+ * Report operations that run longer than a threshold.
+ *
+ * Set `threshold_ms` to 0 to disable the watchdog.
  */
-typedef struct IrohDocEvent {
+typedef struct IrohSlowOperationWatchdog {
     /**
-     * The type of event.
+     * Opaque pointer passed back to Swift.
      */
-    enum IrohDocEventType event_type;
+    void *userdata;
     /**
-     * The entry for insert events (null for other events).
-     * Must be freed with `iroh_doc_entry_free` if not null.
+     * Milliseconds after which an operation counts as slow.
      */
-    const struct IrohDocEntry *entry;
+    uint64_t threshold_ms;
     /**
-     * The peer ID for remote events (null for local events).
-     * Must be freed with `iroh_string_free` if not null.
+     * Whether slow operations are also published as node events.
      */
-    const char *peer_id;
+    bool emit_events;
     /**
-     * The content hash for ContentReady events (null for other events).
-     * Must be freed with `iroh_string_free` if not null.
+     * Called on a background thread once for each slow operation (null for none).
      */
-    const char *content_hash;
-} IrohDocEvent;
+    void (*on_slow)(void *userdata, struct IrohSlowOperation operation);
+    /**
+     * Called once when the node no longer uses the watchdog, including
+     * when node creation fails.
+     */
+    void (*release)(void *userdata);
+} IrohSlowOperationWatchdog;
 
 /**
- * Streaming callback for document subscriptions.
- * Called multiple times - once per event, then on_complete when stream ends.
+ * Per-peer limits on blob requests served by the node.
+ *
+ * Each field is 0 for no limit.
  */
-typedef struct IrohDocSubscribeCallback {
+typedef struct IrohRequestLimits {
+    /**
+     * Requests a peer may start per second, with bursts of up to one
+     * second's worth.
+     */
+    uint32_t requests_per_sec;
+    /**
+     * Transfers a peer may have in progress at once.
+     */
+    uint32_t max_concurrent;
+    /**
+     * Bytes per second sent to a peer across all its transfers.
+     */
+    uint64_t bytes_per_sec;
+} IrohRequestLimits;
+
+/**
+ * Size limits for keeping blobs inside the store's database instead of
+ * files of their own.
+ *
+ * Each field is 0 for the default of 16 KiB. Only content stored after the
+ * node starts is affected.
+ */
+typedef struct IrohStoreInlining {
+    /**
+     * Largest blob kept in the database. Raise it when storing many tiny
+     * values to create fewer files; lower it, down to 1, to keep media in
+     * files it can be streamed from.
+     */
+    uint64_t max_inline_data;
+    /**
+     * Largest outboard (verification data, 64 bytes per 16 KiB of blob)
+     * kept in the database.
+     */
+    uint64_t max_inline_outboard;
+} IrohStoreInlining;
+
+/**
+ * Periodically republish the node's discovery records and announce
+ * selected content.
+ *
+ * Set `interval_ms` to 0 to disable announcements.
+ */
+typedef struct IrohContentAnnouncer {
     /**
      * Opaque pointer passed back to Swift.
      */
     void *userdata;
     /**
-     * Called for each event. Event must be freed with `iroh_doc_event_free`.
+     * Milliseconds between rounds. Rounds also run when the node's
+     * addresses change.
      */
-    void (*on_event)(void *userdata, struct IrohDocEvent event);
+    uint64_t interval_ms;
     /**
-     * Called when subscription ends normally.
+     * Prefix of the tags whose content is announced (null for every tag).
      */
-    void (*on_complete)(void *userdata);
+    const char *tag_prefix;
     /**
-     * Called on error. No more callbacks after this.
+     * Called on a background thread for each announced tag with a ticket
+     * for its content at the node's current addresses (null for none). The
+     * strings are only valid for the duration of the call.
      */
-    void (*on_failure)(void *userdata, const char *error);
-} IrohDocSubscribeCallback;
+    void (*on_announce)(void *userdata, const char *tag, const char *ticket);
+    /**
+     * Called once when the node no longer uses the announcer, including
+     * when node creation fails.
+     */
+    void (*release)(void *userdata);
+} IrohContentAnnouncer;
 
 /**
- * Create a new Iroh node asynchronously.
+ * Node-wide transfer bandwidth caps on Wi-Fi and cellular, applied to
+ * blobs served to peers and to downloads the node starts. Document
+ * content the docs engine downloads while syncing is not download capped.
  *
- * # Safety
- * - `config.storage_path` must be a valid null-terminated UTF-8 string
- * - `config.custom_relay_url` must be null or a valid null-terminated UTF-8 string
- * - `callback` must have valid function pointers
+ * Each field is 0 for no cap.
  */
-void iroh_node_create(struct IrohNodeConfig config, struct IrohNodeCreateCallback callback);
+typedef struct IrohBandwidthLimits {
+    /**
+     * Bytes per second sent to peers on Wi-Fi.
+     */
+    uint64_t wifi_upload_bytes_per_sec;
+    /**
+     * Bytes per second received on Wi-Fi.
+     */
+    uint64_t wifi_download_bytes_per_sec;
+    /**
+     * Bytes per second sent to peers on cellular.
+     */
+    uint64_t cellular_upload_bytes_per_sec;
+    /**
+     * Bytes per second received on cellular.
+     */
+    uint64_t cellular_download_bytes_per_sec;
+} IrohBandwidthLimits;
+
+/**
+ * Configuration for creating a node.
+ *
+ * Fields are added as options are, so the struct must come from the header
+ * shipped with this build of the library. Callers that load the library
+ * separately from its header should use `iroh_node_create_from_json`.
+ */
+typedef struct IrohNodeConfig {
+    /**
+     * Path to the blob store directory (required unless `ephemeral`).
+     */
+    const char *storage_path;
+    /**
+     * Whether to use relay servers (default: true).
+     */
+    bool relay_enabled;
+    /**
+     * Custom relay URL (null to use n0's public relays).
+     * Must be a valid URL like "https://relay.example.com".
+     */
+    const char *custom_relay_url;
+    /**
+     * Whether to enable the Docs engine (default: false).
+     * When enabled, the node can create, join, and sync documents.
+     */
+    bool docs_enabled;
+    /**
+     * Directory for crate-managed temporary files (null to use the system temp dir).
+     */
+    const char *temp_dir;
+    /**
+     * Interval in milliseconds between background passes that remove
+     * expired document entries (0 = only prune on demand).
+     */
+    uint64_t ttl_prune_interval_ms;
+    /**
+     * Entropy for the node's secret key (null `fill` to use the system RNG).
+     */
+    struct IrohEntropySource entropy;
+    /**
+     * TLS session tickets to cache for resuming sessions (0 = default of 256).
+     */
+    uint32_t max_tls_tickets;
+    /**
+     * Milliseconds a silent connection survives before it is dropped
+     * (0 = default of 30 seconds).
+     */
+    uint64_t idle_timeout_ms;
+    /**
+     * Maximum number of connections served to peers at once (0 = unlimited).
+     * When full, the connection idle the longest is closed to admit a new one.
+     */
+    uint32_t max_connections;
+    /**
+     * App-provided discovery (null callbacks for none).
+     */
+    struct IrohDiscoveryProvider discovery;
+    /**
+     * Whether to skip n0's DNS/pkarr discovery (default: false).
+     */
+    bool disable_default_discovery;
+    /**
+     * Storage threshold alert (null `on_threshold` for none).
+     */
+    struct IrohStorageAlert storage_alert;
+    /**
+     * OTLP/HTTP collector to export operation spans and metrics to, like
+     * "https://otel.example.com:4318" (null for none). Export is shared by
+     * all nodes in the process and requires the `otel` feature.
+     */
+    const char *otlp_endpoint;
+    /**
+     * Slow operation watchdog (0 `threshold_ms` for none).
+     */
+    struct IrohSlowOperationWatchdog slow_operation_watchdog;
+    /**
+     * Debug control server address: a loopback "ip:port" or a Unix socket
+     * path (null for none). Serves node info, blobs, docs and transfers
+     * over line-delimited JSON-RPC and requires the `control` feature.
+     */
+    const char *control_socket;
+    /**
+     * HTTPS gateway URL serving content by hash (null for none). `iroh_get`
+     * fetches raw blobs from it, verified, when peers cannot be reached.
+     */
+    const char *gateway_url;
+    /**
+     * Who may fetch blobs before any peer rules are set (default: open).
+     */
+    enum IrohBlobAccessMode blob_access;
+    /**
+     * Per-peer limits on blob requests (all zero for none). Refused
+     * requests are reported as `NodeFetchLimited` events.
+     */
+    struct IrohRequestLimits request_limits;
+    /**
+     * Whether to run background callbacks on one dedicated thread
+     * (default: false). Subscription events, conflict resolution, write
+     * filters, discovery, storage alerts and slow operation reports then
+     * run one at a time, in the order they were made, instead of on
+     * whichever worker thread produced them. Callbacks should return
+     * quickly and must not wait for other node operations, since those may
+     * need the same thread. Operation callbacks are unaffected: they always
+     * run on the calling thread before the operation returns.
+     */
+    bool dedicated_callback_thread;
+    /**
+     * Identifier of the app running the node, like "com.example.photos/2.1"
+     * (null for none). Attached to exported telemetry, reported by node
+     * info and the control server, and published with the node's discovery
+     * records so peers can tell which app a node belongs to. At most 245
+     * bytes, without control characters.
+     */
+    const char *app_id;
+    /**
+     * Which blobs the store keeps in its database (all zero for defaults).
+     */
+    struct IrohStoreInlining store_inlining;
+    /**
+     * Periodic re-announcement of content (0 `interval_ms` for none).
+     */
+    struct IrohContentAnnouncer content_announcer;
+    /**
+     * Seed of a deterministic test node (null for a normal node). The
+     * node's key derives from the seed; it binds to loopback only, uses
+     * neither relays nor network discovery, and is found by the other test
+     * nodes of the process. Its tickets leave out addresses, so they only
+     * depend on the seed and the content. Blobs and documents are kept in
+     * memory. `entropy` must be unset.
+     */
+    const char *test_seed;
+    /**
+     * Worker threads of the node's runtime (0 = default: 2 on iOS and
+     * Android, one per core elsewhere).
+     */
+    uint32_t worker_threads;
+    /**
+     * Blocking threads the node's runtime may start for file I/O (0 =
+     * default: 16 on iOS and Android, 512 elsewhere).
+     */
+    uint32_t max_blocking_threads;
+    /**
+     * What to do when the blob store database is found corrupt at startup
+     * (default: fail with `on_corrupt`).
+     */
+    enum IrohBlobStoreRecovery blob_store_recovery;
+    /**
+     * What to do when the docs database is found corrupt at startup
+     * (default: fail with `on_corrupt`).
+     */
+    enum IrohDocsStoreRecovery docs_store_recovery;
+    /**
+     * Keep all data in a fresh directory under `temp_dir`, removed when
+     * the node is closed or destroyed (default: false). `storage_path` is
+     * not used and may be null.
+     */
+    bool ephemeral;
+    /**
+     * Transfer bandwidth caps on Wi-Fi and cellular (all zero for none).
+     * The node starts on Wi-Fi; tell it about network changes with
+     * `iroh_node_set_network_kind`.
+     */
+    struct IrohBandwidthLimits bandwidth_limits;
+} IrohNodeConfig;
+
+/**
+ * Opaque handle to an Iroh node.
+ *
+ * The actual IrohNode is stored in a Box, and this handle holds
+ * a raw pointer to that Box.
+ */
+typedef struct IrohNodeHandle {
+    uint8_t _private[0];
+} IrohNodeHandle;
+
+/**
+ * Callback for node creation.
+ */
+typedef struct IrohNodeCreateCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the node handle.
+     */
+    void (*on_success)(void *userdata, struct IrohNodeHandle *handle);
+    /**
+     * Called when a database is corrupt and its recovery mode is to fail,
+     * with the database's path and what is wrong with it (caller must free
+     * both with `iroh_string_free`).
+     */
+    void (*on_corrupt)(void *userdata,
+                       enum IrohStoreDatabase database,
+                       const char *path,
+                       const char *reason);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohNodeCreateCallback;
+
+/**
+ * Borrowed bytes from Swift (read-only view into Swift memory).
+ */
+typedef struct IrohBytes {
+    const uint8_t *data;
+    uintptr_t len;
+} IrohBytes;
+
+/**
+ * Callback for operations that return a string on success.
+ *
+ * NOTE: Callbacks run on a Tokio worker thread. Swift must safely
+ * resume continuations from that context.
+ */
+typedef struct IrohCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with a C string (caller must free with `iroh_string_free`).
+     */
+    void (*on_success)(void *userdata, const char *result);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohCallback;
+
+/**
+ * Progress of a file import.
+ */
+typedef struct IrohImportProgress {
+    /**
+     * Bytes copied into the store so far.
+     */
+    uint64_t copied;
+    /**
+     * Bytes hashed so far.
+     */
+    uint64_t hashed;
+    /**
+     * Size of the file in bytes (0 until known).
+     */
+    uint64_t total;
+} IrohImportProgress;
+
+/**
+ * Callback for putting a file.
+ */
+typedef struct IrohPutFileCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with progress updates while the file is imported.
+     */
+    void (*on_progress)(void *userdata, struct IrohImportProgress progress);
+    /**
+     * Called on success with the ticket (caller must free with `iroh_string_free`).
+     */
+    void (*on_success)(void *userdata, const char *ticket);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohPutFileCallback;
+
+/**
+ * Owned bytes returned to Swift (must be freed with `iroh_bytes_free`).
+ */
+typedef struct IrohOwnedBytes {
+    uint8_t *data;
+    uintptr_t len;
+    uintptr_t capacity;
+} IrohOwnedBytes;
+
+/**
+ * Callback for operations that return bytes on success.
+ */
+typedef struct IrohGetCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with owned bytes (caller must free with `iroh_bytes_free`).
+     */
+    void (*on_success)(void *userdata, struct IrohOwnedBytes bytes);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohGetCallback;
+
+/**
+ * Content hash (32 bytes).
+ *
+ * The binary form of the 64-character hex hashes used elsewhere; convert
+ * with `iroh_hash_to_hex` and `iroh_hash_from_hex`.
+ */
+typedef struct IrohHash {
+    uint8_t bytes[32];
+} IrohHash;
+
+/**
+ * Callback for operations that produce a hash.
+ */
+typedef struct IrohHashCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the hash.
+     */
+    void (*on_success)(void *userdata, struct IrohHash hash);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohHashCallback;
+
+/**
+ * A handle that has not been destroyed, closed or cancelled yet.
+ */
+typedef struct IrohLiveHandle {
+    /**
+     * What the handle refers to.
+     */
+    enum IrohHandleKind kind;
+    /**
+     * The handle's address.
+     */
+    uintptr_t address;
+    /**
+     * Creation time in microseconds since epoch.
+     */
+    uint64_t created_at;
+    /**
+     * Backtrace of the call that created the handle, or null in release builds
+     * (only valid for the duration of the callback).
+     */
+    const char *backtrace;
+} IrohLiveHandle;
+
+/**
+ * Callback for listing live handles.
+ */
+typedef struct IrohLiveHandlesCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each live handle, oldest first.
+     */
+    void (*on_handle)(void *userdata, struct IrohLiveHandle handle);
+    /**
+     * Called after the last handle.
+     */
+    void (*on_complete)(void *userdata);
+} IrohLiveHandlesCallback;
+
+/**
+ * Operation totals since the process started (see `iroh_operation_counters`).
+ */
+typedef struct IrohOperationCounters {
+    /**
+     * Successful puts.
+     */
+    uint64_t puts;
+    /**
+     * Successful gets.
+     */
+    uint64_t gets;
+    /**
+     * Successful document writes.
+     */
+    uint64_t doc_sets;
+    /**
+     * Bytes passed in by puts.
+     */
+    uint64_t bytes_in;
+    /**
+     * Bytes returned by gets.
+     */
+    uint64_t bytes_out;
+} IrohOperationCounters;
+
+/**
+ * Callback for reading operation counters.
+ */
+typedef struct IrohOperationCountersCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with the number of failures of each FFI function that has
+     * failed, like "iroh_get". `operation` is only valid during the call.
+     */
+    void (*on_failure_count)(void *userdata, const char *operation, uint64_t count);
+    /**
+     * Called last with the totals.
+     */
+    void (*on_complete)(void *userdata, struct IrohOperationCounters counters);
+} IrohOperationCountersCallback;
+
+/**
+ * Callback receiving the library's log messages.
+ */
+typedef struct IrohLogCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with each message, on the thread that logged it. For a panic
+     * this is the panicking thread, before it unwinds. `message` is only
+     * valid during the call.
+     */
+    void (*on_log)(void *userdata, const char *message);
+    /**
+     * Called once the callback is replaced and no longer running, if set.
+     */
+    void (*release)(void *userdata);
+} IrohLogCallback;
+
+#if defined(IROH_FEATURE_DOWNLOADER_PROGRESS)
+/**
+ * Progress information for a download operation.
+ */
+typedef struct IrohDownloadProgress {
+    /**
+     * Bytes received so far.
+     */
+    uint64_t downloaded;
+    /**
+     * Total bytes expected (0 if unknown).
+     */
+    uint64_t total;
+    /**
+     * Bytes verified against the hash and stored so far.
+     */
+    uint64_t verified;
+    /**
+     * What the download is doing.
+     */
+    enum IrohDownloadStage stage;
+} IrohDownloadProgress;
+#endif
+
+#if defined(IROH_FEATURE_DOWNLOADER_PROGRESS)
+/**
+ * Callback for get operations with progress reporting.
+ */
+typedef struct IrohGetProgressCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with progress updates during download.
+     */
+    void (*on_progress)(void *userdata, struct IrohDownloadProgress progress);
+    /**
+     * Called on success with owned bytes (caller must free with `iroh_bytes_free`).
+     */
+    void (*on_success)(void *userdata, struct IrohOwnedBytes bytes);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohGetProgressCallback;
+#endif
+
+#if (defined(IROH_FEATURE_DOWNLOADER_PROGRESS) || defined(IROH_FEATURE_DOCS))
+/**
+ * Writer that receives verified chunks during a sink download, or chunks
+ * of a streamed export.
+ */
+typedef struct IrohSinkWriter {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with each chunk, in order. The bytes are only valid for the
+     * duration of the call. Return false to abort the download or export.
+     */
+    bool (*write)(void *userdata, struct IrohBytes bytes);
+} IrohSinkWriter;
+#endif
+
+#if defined(IROH_FEATURE_DOWNLOADER_PROGRESS)
+/**
+ * Callback for sink download completion.
+ */
+typedef struct IrohSinkCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the total number of bytes written.
+     */
+    void (*on_success)(void *userdata, uint64_t total_bytes);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohSinkCallback;
+#endif
+
+/**
+ * Information about an Iroh node.
+ */
+typedef struct IrohNodeInfo {
+    /**
+     * Node ID as a string (caller must free with `iroh_string_free`).
+     */
+    const char *node_id;
+    /**
+     * Relay URL if connected (caller must free with `iroh_string_free`).
+     * Null if not connected to a relay.
+     */
+    const char *relay_url;
+    /**
+     * Whether the node is connected to the network.
+     */
+    bool is_connected;
+    /**
+     * App identifier the node was created with (caller must free with
+     * `iroh_string_free`). Null if none was configured.
+     */
+    const char *app_id;
+    /**
+     * How well the network supports direct connections.
+     */
+    enum IrohNetworkCondition network_condition;
+} IrohNodeInfo;
+
+/**
+ * Callback for node info retrieval.
+ */
+typedef struct IrohNodeInfoCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with node info.
+     */
+    void (*on_success)(void *userdata, struct IrohNodeInfo info);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohNodeInfoCallback;
+
+/**
+ * Callback for closing a peer's connections.
+ */
+typedef struct IrohConnectionCloseCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the number of connections that were closed.
+     */
+    void (*on_success)(void *userdata, uintptr_t closed);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohConnectionCloseCallback;
+
+/**
+ * Result of a pre-flight dial.
+ */
+typedef struct IrohReachResult {
+    /**
+     * How the peer was reached.
+     */
+    enum IrohReachability reachability;
+    /**
+     * Round-trip time in microseconds (0 if unreachable).
+     */
+    uint64_t latency_us;
+    /**
+     * Why the dial failed, or null if reached (only valid for the duration of the callback).
+     */
+    const char *reason;
+} IrohReachResult;
+
+/**
+ * Callback for `iroh_can_reach`.
+ */
+typedef struct IrohCanReachCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with the result, including when the peer is unreachable.
+     */
+    void (*on_success)(void *userdata, struct IrohReachResult result);
+    /**
+     * Called if the target cannot be parsed (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohCanReachCallback;
+
+/**
+ * Callback for node close operation.
+ */
+typedef struct IrohCloseCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called when close completes successfully.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called if close fails with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohCloseCallback;
+
+/**
+ * One provider's answer to a probe.
+ *
+ * Strings are only valid for the duration of the callback.
+ */
+typedef struct IrohProviderProbe {
+    /**
+     * Provider node ID.
+     */
+    const char *node_id;
+    /**
+     * How much of the blob the provider has.
+     */
+    enum IrohBlobAvailability availability;
+    /**
+     * Blob size in bytes, or 0 if the provider has not verified it.
+     */
+    uint64_t size;
+    /**
+     * Why the provider could not be asked, or null.
+     */
+    const char *reason;
+} IrohProviderProbe;
+
+/**
+ * Streaming callback for `iroh_blob_probe_providers`.
+ * Called once per provider in the order given, then on_complete.
+ */
+typedef struct IrohProviderProbeCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each provider.
+     */
+    void (*on_provider)(void *userdata, struct IrohProviderProbe probe);
+    /**
+     * Called after the last provider.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called if the arguments are invalid. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohProviderProbeCallback;
+
+/**
+ * Bytes exchanged with one peer (see `iroh_peer_traffic_list`).
+ */
+typedef struct IrohPeerTraffic {
+    /**
+     * Peer node ID (only valid for the duration of the callback).
+     */
+    const char *node_id;
+    /**
+     * Bytes sent to the peer on connections it opened.
+     */
+    uint64_t bytes_uploaded;
+    /**
+     * Bytes received from the peer on connections it opened. Content this
+     * node downloads over connections it opens is not counted.
+     */
+    uint64_t bytes_downloaded;
+} IrohPeerTraffic;
+
+/**
+ * Streaming callback for per-peer traffic.
+ * Called once per peer, then on_complete.
+ */
+typedef struct IrohPeerTrafficCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each peer, largest upload first.
+     */
+    void (*on_entry)(void *userdata, struct IrohPeerTraffic entry);
+    /**
+     * Called after the last peer.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohPeerTrafficCallback;
+
+/**
+ * A peer's blob access rule (see `iroh_blob_access_list`).
+ */
+typedef struct IrohBlobAccessEntry {
+    /**
+     * Peer node ID (only valid for the duration of the callback).
+     */
+    const char *node_id;
+    /**
+     * Allow or deny.
+     */
+    enum IrohBlobAccessRule rule;
+} IrohBlobAccessEntry;
+
+/**
+ * Streaming callback for blob access rules.
+ * Called once per peer rule, then on_complete with the mode.
+ */
+typedef struct IrohBlobAccessCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each peer rule, sorted by node ID.
+     */
+    void (*on_entry)(void *userdata, struct IrohBlobAccessEntry entry);
+    /**
+     * Called after the last rule with the access mode.
+     */
+    void (*on_complete)(void *userdata, enum IrohBlobAccessMode mode);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohBlobAccessCallback;
+
+/**
+ * Latency to one configured relay (see `iroh_node_relay_report`).
+ */
+typedef struct IrohRelayLatency {
+    /**
+     * Relay URL (only valid for the duration of the callback).
+     */
+    const char *url;
+    /**
+     * Whether the relay has been reached; `latency_us` is 0 otherwise.
+     */
+    bool is_measured;
+    /**
+     * Fastest probe to the relay in microseconds.
+     */
+    uint64_t latency_us;
+    /**
+     * Whether the node is pinned to this relay (see `iroh_node_set_home_relay`).
+     */
+    bool is_pinned;
+} IrohRelayLatency;
+
+/**
+ * Streaming callback for the relay latency report.
+ * Called once per configured relay, then on_complete.
+ */
+typedef struct IrohRelayReportCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each relay, fastest first, unreached relays last.
+     */
+    void (*on_relay)(void *userdata, struct IrohRelayLatency relay);
+    /**
+     * Called after the last relay with the home relay URL, or null if none
+     * (only valid for the duration of the callback).
+     */
+    void (*on_complete)(void *userdata, const char *home_relay);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohRelayReportCallback;
+
+/**
+ * Faults to inject into a node for testing.
+ */
+typedef struct IrohFaults {
+    /**
+     * Number of upcoming puts that fail (0 for none).
+     */
+    uint64_t fail_puts;
+    /**
+     * Milliseconds each download waits before it starts (0 for none).
+     */
+    uint64_t download_delay_ms;
+    /**
+     * Percentage of document syncs started by peers that are dropped
+     * (0 to 100).
+     */
+    uint8_t sync_drop_percent;
+} IrohFaults;
+
+/**
+ * Outcome of a node health check (see `iroh_node_health`).
+ *
+ * Error strings are null when the check passed and are only valid for the
+ * duration of the callback.
+ */
+typedef struct IrohNodeHealth {
+    /**
+     * Whether every check passed.
+     */
+    bool is_healthy;
+    /**
+     * Whether the blob store accepted a write.
+     */
+    bool store_writable;
+    /**
+     * Why the store check failed.
+     */
+    const char *store_error;
+    /**
+     * Whether the runtime ran a task.
+     */
+    bool runtime_responsive;
+    /**
+     * Why the runtime check failed.
+     */
+    const char *runtime_error;
+    /**
+     * Whether the endpoint is open and bound to a socket.
+     */
+    bool endpoint_bound;
+    /**
+     * Why the endpoint check failed.
+     */
+    const char *endpoint_error;
+} IrohNodeHealth;
+
+/**
+ * Callback for a node health check.
+ */
+typedef struct IrohNodeHealthCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with the health report, healthy or not.
+     */
+    void (*on_success)(void *userdata, struct IrohNodeHealth health);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohNodeHealthCallback;
+
+/**
+ * Opaque handle to a document subscription.
+ *
+ * Used to cancel an active subscription.
+ */
+typedef struct IrohSubscriptionHandle {
+    uint8_t _private[0];
+} IrohSubscriptionHandle;
+
+/**
+ * Callback for watching the node's direct addresses.
+ */
+typedef struct IrohDirectAddrsCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with the current addresses, then again whenever they change.
+     * `addrs` holds `len` "ip:port" strings, only valid for the duration of the call.
+     */
+    void (*on_addrs)(void *userdata, const char *const *addrs, uintptr_t len);
+    /**
+     * Called when watching stops, after cancellation or node shutdown.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDirectAddrsCallback;
+
+/**
+ * A node event.
+ *
+ * Fields that do not apply to the event type are null or zero. Strings
+ * are only valid for the duration of the callback.
+ */
+typedef struct IrohNodeEvent {
+    /**
+     * The type of event.
+     */
+    enum IrohNodeEventType event_type;
+    /**
+     * Home relay URL for connectivity events, or null if none.
+     */
+    const char *relay_url;
+    /**
+     * Direct "ip:port" addresses for connectivity events.
+     */
+    const char *const *direct_addrs;
+    /**
+     * Number of entries in `direct_addrs`.
+     */
+    uintptr_t direct_addrs_len;
+    /**
+     * Content hash for download and tag expiry events.
+     */
+    struct IrohHash hash;
+    /**
+     * Namespace ID for neighbor, sync, peer sync, outbox and durable sync events.
+     */
+    const char *namespace_id;
+    /**
+     * Peer node ID for neighbor, sync, peer sync, durable sync and rejected
+     * fetch events.
+     */
+    const char *peer_id;
+    /**
+     * Why a download, sync or outbox write failed, or null if it succeeded.
+     * For peer sync events, why the last sync failed.
+     */
+    const char *error;
+    /**
+     * Entries removed, for expiry events.
+     */
+    uint64_t removed;
+    /**
+     * Operation that started a download or was slow, or 0 if a download
+     * was not started by an FFI call.
+     */
+    uint64_t operation_id;
+    /**
+     * Name of the FFI function that started a slow operation.
+     */
+    const char *operation_name;
+    /**
+     * Stage a slow operation was in.
+     */
+    const char *stage;
+    /**
+     * Milliseconds a slow operation had been running.
+     */
+    uint64_t elapsed_ms;
+    /**
+     * Item ID for outbox events.
+     */
+    uint64_t item_id;
+    /**
+     * Entry key for outbox events.
+     */
+    struct IrohBytes key;
+    /**
+     * Author of the winning entry for superseded outbox writes.
+     */
+    const char *author_id;
+    /**
+     * For durable sync events, the time before which every durable peer
+     * confirmed every local write (microseconds since epoch, 0 if one
+     * never synced).
+     */
+    uint64_t confirmed_through;
+    /**
+     * For durable sync events, whether every durable peer has every local write.
+     */
+    bool backed_up;
+    /**
+     * Tags announced, for content announcement events.
+     */
+    uint64_t announced_tags;
+    /**
+     * New condition, for network condition events.
+     */
+    enum IrohNetworkCondition network_condition;
+    /**
+     * Name of the removed tag, for tag expiry events.
+     */
+    const char *tag_name;
+    /**
+     * For peer sync events, the time the last successful sync finished
+     * (microseconds since epoch, 0 if none).
+     */
+    uint64_t last_success;
+    /**
+     * For peer sync events, local inserts since the last successful sync.
+     */
+    uint64_t pending_entries;
+    /**
+     * For peer sync events, syncs that failed since the last successful one.
+     */
+    uint64_t failure_streak;
+    /**
+     * Profile whose content is collected, for GC events.
+     */
+    const char *profile_id;
+    /**
+     * For GC started events, blobs no tag references anymore; for GC
+     * finished events, blobs deleted.
+     */
+    uint64_t gc_blobs;
+    /**
+     * Stored size of the blobs in `gc_blobs`.
+     */
+    uint64_t gc_size;
+} IrohNodeEvent;
+
+/**
+ * Streaming callback for node events.
+ */
+typedef struct IrohNodeEventCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each event.
+     */
+    void (*on_event)(void *userdata, struct IrohNodeEvent event);
+    /**
+     * Called when the subscription ends, after cancellation or node shutdown.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohNodeEventCallback;
+
+/**
+ * Streaming callback for messages from peers.
+ */
+typedef struct IrohMessageCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each message. `from` is the sender's node ID; both
+     * `from` and `payload` are only valid for the duration of the call.
+     */
+    void (*on_message)(void *userdata, const char *from, struct IrohBytes payload);
+    /**
+     * Called when the subscription ends, after cancellation or node shutdown.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohMessageCallback;
+
+/**
+ * Parsed ticket information.
+ */
+typedef struct IrohTicketInfo {
+    /**
+     * Whether the ticket is valid.
+     */
+    bool is_valid;
+    /**
+     * The blob hash as a string (caller must free with `iroh_string_free`).
+     * Null if invalid.
+     */
+    const char *hash;
+    /**
+     * The node ID from the ticket (caller must free with `iroh_string_free`).
+     * Null if invalid.
+     */
+    const char *node_id;
+    /**
+     * Whether this is a recursive (collection) ticket.
+     */
+    bool is_recursive;
+    /**
+     * Format of the blob the ticket points to.
+     */
+    enum IrohBlobFormat format;
+    /**
+     * Relay URLs the provider can be reached through. Borrowed, valid only
+     * during `on_complete`.
+     */
+    const char *const *relay_urls;
+    /**
+     * Number of relay URLs.
+     */
+    uintptr_t relay_urls_len;
+    /**
+     * Direct `ip:port` addresses of the provider. Borrowed, valid only
+     * during `on_complete`.
+     */
+    const char *const *direct_addrs;
+    /**
+     * Number of direct addresses.
+     */
+    uintptr_t direct_addrs_len;
+} IrohTicketInfo;
+
+/**
+ * Callback for ticket validation.
+ */
+typedef struct IrohTicketValidateCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with validation result. Always called (never fails).
+     */
+    void (*on_complete)(void *userdata, struct IrohTicketInfo info);
+} IrohTicketValidateCallback;
+
+/**
+ * A ticket unpacked from a ticket bundle.
+ *
+ * Strings are only valid for the duration of the callback.
+ */
+typedef struct IrohBundledTicket {
+    /**
+     * What the ticket is for; empty if unlabeled.
+     */
+    const char *label;
+    /**
+     * Whether this is a blob or a doc ticket.
+     */
+    enum IrohBundledTicketKind kind;
+    /**
+     * The ticket in its standard form.
+     */
+    const char *ticket;
+} IrohBundledTicket;
+
+/**
+ * Callback for unpacking a ticket bundle.
+ */
+typedef struct IrohTicketBundleCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each ticket, in bundle order.
+     */
+    void (*on_entry)(void *userdata, struct IrohBundledTicket ticket);
+    /**
+     * Called after the last ticket.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error, before any ticket. Caller must free error with `iroh_string_free`.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohTicketBundleCallback;
+
+/**
+ * Callback for closing a node.
+ */
+typedef struct IrohNodeCloseCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called when close completes; `graceful` is false if the deadline
+     * passed and the remaining shutdown work was abandoned.
+     */
+    void (*on_complete)(void *userdata, bool graceful);
+    /**
+     * Called if close fails with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohNodeCloseCallback;
+
+/**
+ * Options for put/get operations.
+ */
+typedef struct IrohOperationOptions {
+    /**
+     * Timeout in milliseconds (0 = no timeout).
+     */
+    uint64_t timeout_ms;
+} IrohOperationOptions;
+
+/**
+ * Callback for puts with options, which report timeouts separately.
+ */
+typedef struct IrohPutWithOptionsCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with a C string (caller must free with `iroh_string_free`).
+     */
+    void (*on_success)(void *userdata, const char *result);
+    /**
+     * Called when the timeout runs out, after the import has been stopped.
+     */
+    void (*on_timeout)(void *userdata);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohPutWithOptionsCallback;
+
+/**
+ * Callback for downloads with options, which report timeouts separately.
+ */
+typedef struct IrohGetWithOptionsCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with owned bytes (caller must free with `iroh_bytes_free`).
+     */
+    void (*on_success)(void *userdata, struct IrohOwnedBytes bytes);
+    /**
+     * Called when the timeout runs out, after the transfer has been stopped.
+     */
+    void (*on_timeout)(void *userdata);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohGetWithOptionsCallback;
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Author secret key (32 bytes).
+ *
+ * This is the private key material used for signing document entries.
+ * Must be kept secure (e.g., in iOS Keychain).
+ */
+typedef struct IrohAuthorSecret {
+    uint8_t bytes[32];
+} IrohAuthorSecret;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Author public ID (32 bytes).
+ *
+ * This is the public identifier derived from the secret key.
+ * Safe to share and store openly.
+ */
+typedef struct IrohAuthorId {
+    uint8_t bytes[32];
+} IrohAuthorId;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for author creation.
+ */
+typedef struct IrohAuthorCreateCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the author secret and ID.
+     */
+    void (*on_success)(void *userdata, struct IrohAuthorSecret secret, struct IrohAuthorId id);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohAuthorCreateCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Signature made with an author key (64 bytes).
+ */
+typedef struct IrohAuthorSignature {
+    uint8_t bytes[64];
+} IrohAuthorSignature;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Opaque handle to an Iroh document.
+ *
+ * Documents are syncing key-value stores shared between peers.
+ * The handle wraps a Doc from iroh-docs.
+ */
+typedef struct IrohDocHandle {
+    uint8_t _private[0];
+} IrohDocHandle;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for document creation/join operations.
+ */
+typedef struct IrohDocCreateCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the document handle and namespace ID.
+     */
+    void (*on_success)(void *userdata, struct IrohDocHandle *handle, const char *namespace_id);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocCreateCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Parsed document ticket information.
+ */
+typedef struct IrohDocTicketInfo {
+    /**
+     * Whether the ticket is a valid document ticket.
+     */
+    bool is_valid;
+    /**
+     * The document's namespace ID (caller must free with `iroh_string_free`).
+     * Null if invalid.
+     */
+    const char *namespace_id;
+    /**
+     * Access the ticket grants. `Write` lets the holder change the document.
+     */
+    enum IrohDocShareMode capability;
+    /**
+     * Number of peers the ticket names to sync with.
+     */
+    uintptr_t peers_len;
+} IrohDocTicketInfo;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for document ticket inspection.
+ */
+typedef struct IrohDocTicketInspectCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with the inspection result. Always called (never fails).
+     */
+    void (*on_complete)(void *userdata, struct IrohDocTicketInfo info);
+} IrohDocTicketInspectCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for document set operations.
+ */
+typedef struct IrohDocSetCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the content hash (caller must free with `iroh_string_free`).
+     */
+    void (*on_success)(void *userdata, const char *hash);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocSetCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for setting a document entry from a file.
+ */
+typedef struct IrohDocSetFileCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with progress updates while the file is imported.
+     */
+    void (*on_progress)(void *userdata, struct IrohImportProgress progress);
+    /**
+     * Called on success with the content hash.
+     */
+    void (*on_success)(void *userdata, struct IrohHash hash);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocSetFileCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for compare-and-set document writes.
+ */
+typedef struct IrohDocSetCasCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the new content hash (caller must free with `iroh_string_free`).
+     */
+    void (*on_success)(void *userdata, const char *hash);
+    /**
+     * Called when the current entry does not match the expectation, with its
+     * content hash, or null if the key is absent (caller must free with `iroh_string_free`).
+     */
+    void (*on_conflict)(void *userdata, const char *current_hash);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocSetCasCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for compare-and-set document writes reporting binary hashes.
+ */
+typedef struct IrohDocSetCasHashCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the new content hash.
+     */
+    void (*on_success)(void *userdata, struct IrohHash hash);
+    /**
+     * Called when the current entry does not match the expectation, with its
+     * content hash, or null if the key is absent. Only valid during the call.
+     */
+    void (*on_conflict)(void *userdata, const struct IrohHash *current_hash);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocSetCasHashCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for mergeable counter operations.
+ */
+typedef struct IrohDocCounterCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the counter's value.
+     */
+    void (*on_success)(void *userdata, int64_t value);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocCounterCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for document writes added to the outbox.
+ */
+typedef struct IrohDocOutboxCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the outbox item ID.
+     */
+    void (*on_success)(void *userdata, uint64_t item_id);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocOutboxCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * A document write waiting for a peer (see `iroh_outbox_list`).
+ *
+ * Strings and bytes are only valid for the duration of the callback.
+ */
+typedef struct IrohOutboxItem {
+    /**
+     * Outbox item ID.
+     */
+    uint64_t item_id;
+    /**
+     * Namespace ID of the document.
+     */
+    const char *namespace_id;
+    /**
+     * Author ID of the entry.
+     */
+    const char *author_id;
+    /**
+     * Entry key.
+     */
+    struct IrohBytes key;
+    /**
+     * Content hash of the entry.
+     */
+    struct IrohHash content_hash;
+    /**
+     * Entry timestamp in microseconds since epoch.
+     */
+    uint64_t timestamp;
+} IrohOutboxItem;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Streaming callback for outbox items.
+ * Called once per item, then on_complete.
+ */
+typedef struct IrohOutboxCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each pending item, oldest first.
+     */
+    void (*on_entry)(void *userdata, struct IrohOutboxItem item);
+    /**
+     * Called after the last item.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohOutboxCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * A durable peer of a document (see `iroh_doc_durable_status`).
+ *
+ * Strings are only valid for the duration of the callback.
+ */
+typedef struct IrohDurablePeer {
+    /**
+     * Peer node ID.
+     */
+    const char *node_id;
+    /**
+     * Time before which the peer confirmed every local write (microseconds
+     * since epoch, 0 if it never synced).
+     */
+    uint64_t confirmed_through;
+} IrohDurablePeer;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * How far a document's local writes are backed up on its durable peers.
+ */
+typedef struct IrohDurableStatus {
+    /**
+     * Time before which every durable peer confirmed every local write
+     * (microseconds since epoch, 0 if one never synced or there are none).
+     */
+    uint64_t confirmed_through;
+    /**
+     * Time of the latest local write (microseconds since epoch, 0 if none).
+     */
+    uint64_t last_local_write;
+    /**
+     * Whether there are durable peers and all of them have every local write.
+     */
+    bool backed_up;
+} IrohDurableStatus;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Streaming callback for durable peer status.
+ * Called once per durable peer, then on_complete with the overall status.
+ */
+typedef struct IrohDurableStatusCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each durable peer, sorted by node ID.
+     */
+    void (*on_peer)(void *userdata, struct IrohDurablePeer peer);
+    /**
+     * Called after the last peer.
+     */
+    void (*on_complete)(void *userdata, struct IrohDurableStatus status);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDurableStatusCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * An asset in a backup manifest (see `iroh_manifest_list`).
+ *
+ * Strings are only valid for the duration of the callback.
+ */
+typedef struct IrohManifestAsset {
+    /**
+     * App-defined asset ID.
+     */
+    const char *asset_id;
+    /**
+     * Content hash of the asset's blob.
+     */
+    struct IrohHash hash;
+    /**
+     * Content size in bytes.
+     */
+    uint64_t size;
+    /**
+     * Original filename (may be null).
+     */
+    const char *filename;
+    /**
+     * MIME type of the content (may be null).
+     */
+    const char *content_type;
+    /**
+     * Creation date in seconds since the Unix epoch.
+     */
+    uint64_t created_at;
+} IrohManifestAsset;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Streaming callback for manifest assets.
+ * Called once per asset, then on_complete.
+ */
+typedef struct IrohManifestCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each asset, sorted by asset ID.
+     */
+    void (*on_entry)(void *userdata, struct IrohManifestAsset asset);
+    /**
+     * Called after the last asset.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohManifestCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for assets missing from a backup manifest.
+ */
+typedef struct IrohManifestMissingCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the missing asset IDs, sorted (only valid
+     * for the duration of the callback).
+     */
+    void (*on_success)(void *userdata, const char *const *asset_ids, uintptr_t len);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohManifestMissingCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for document delete operations.
+ */
+typedef struct IrohDocDelCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with count of deleted entries.
+     */
+    void (*on_success)(void *userdata, uint64_t deleted_count);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocDelCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * A document entry (key-value pair with metadata).
+ */
+typedef struct IrohDocEntry {
+    /**
+     * Author ID who wrote this entry (32 bytes).
+     */
+    struct IrohAuthorId author_id;
+    /**
+     * Key bytes (owned, must be freed).
+     */
+    struct IrohOwnedBytes key;
+    /**
+     * Content hash as hex string (must be freed with `iroh_string_free`).
+     */
+    char *content_hash;
+    /**
+     * Size of the content in bytes.
+     */
+    uint64_t content_size;
+    /**
+     * Timestamp when entry was created (microseconds since epoch).
+     */
+    uint64_t timestamp;
+    /**
+     * Content hash in binary form, the same hash as `content_hash`.
+     */
+    struct IrohHash hash;
+} IrohDocEntry;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for document get operations.
+ */
+typedef struct IrohDocGetCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the entry (may be null if not found).
+     * Caller must free entry with `iroh_doc_entry_free` if not null.
+     */
+    void (*on_success)(void *userdata, const struct IrohDocEntry *entry);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocGetCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Streaming callback for batched document gets.
+ * Called once per requested key, then on_complete.
+ */
+typedef struct IrohDocGetBatchCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each key in request order with its index and entry (null if not found).
+     * Caller must free entry with `iroh_doc_entry_free` if not null.
+     */
+    void (*on_entry)(void *userdata, uintptr_t index, const struct IrohDocEntry *entry);
+    /**
+     * Called when every key has been reported.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocGetBatchCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Streaming callback for get_many (prefix queries).
+ * Called multiple times - once per entry, then on_complete.
+ */
+typedef struct IrohDocGetManyCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each entry found. Entry must be freed with `iroh_doc_entry_free`.
+     */
+    void (*on_entry)(void *userdata, const struct IrohDocEntry *entry);
+    /**
+     * Called when iteration completes successfully.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocGetManyCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Filter for document entry queries.
+ */
+typedef struct IrohDocQuery {
+    /**
+     * Only keys starting with these bytes (empty for all keys).
+     */
+    struct IrohBytes key_prefix;
+    /**
+     * Only entries written at or after this time (microseconds since epoch, 0 for no bound).
+     */
+    uint64_t timestamp_start;
+    /**
+     * Only entries written before this time (microseconds since epoch, 0 for no bound).
+     */
+    uint64_t timestamp_end;
+    /**
+     * Only the winning entry per key: the latest across authors, ties going
+     * to the lowest author ID. Keys whose winning entry is a deletion are
+     * left out, and the timestamp range applies to the winning entry.
+     */
+    bool latest_only;
+} IrohDocQuery;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for document exports.
+ */
+typedef struct IrohDocExportCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the number of entries exported.
+     */
+    void (*on_success)(void *userdata, uint64_t entries_exported);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocExportCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Swarm statistics for a document's gossip topic.
+ */
+typedef struct IrohDocSwarmStats {
+    /**
+     * Peers currently connected on the document's topic.
+     */
+    uint64_t neighbors;
+    /**
+     * Local inserts announced to the swarm.
+     */
+    uint64_t messages_sent;
+    /**
+     * Inserts received from peers.
+     */
+    uint64_t messages_received;
+    /**
+     * Time of the last swarm event in microseconds since epoch (0 if none).
+     */
+    uint64_t last_activity;
+} IrohDocSwarmStats;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Callback for document swarm statistics.
+ */
+typedef struct IrohDocSwarmStatsCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the statistics.
+     */
+    void (*on_success)(void *userdata, struct IrohDocSwarmStats stats);
+    /**
+     * Called on failure with an error message.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocSwarmStatsCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * How syncs of a document with one peer went (see `iroh_doc_peer_sync_stats`).
+ *
+ * Strings are only valid for the duration of the callback.
+ */
+typedef struct IrohPeerSyncStats {
+    /**
+     * Peer node ID.
+     */
+    const char *peer_id;
+    /**
+     * Time the last successful sync finished in microseconds since epoch
+     * (0 if none).
+     */
+    uint64_t last_success;
+    /**
+     * Local inserts since the last successful sync, which the peer may not
+     * have yet.
+     */
+    uint64_t pending_entries;
+    /**
+     * Syncs that failed since the last successful one.
+     */
+    uint64_t failure_streak;
+    /**
+     * Why the last sync failed, or null if it succeeded.
+     */
+    const char *last_error;
+} IrohPeerSyncStats;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Streaming callback for peer sync statistics.
+ * Called once per peer, then on_complete.
+ */
+typedef struct IrohPeerSyncStatsCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each peer, sorted by node ID.
+     */
+    void (*on_entry)(void *userdata, struct IrohPeerSyncStats stats);
+    /**
+     * Called after the last peer.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohPeerSyncStatsCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * A document event from subscription.
+ */
+typedef struct IrohDocEvent {
+    /**
+     * The type of event.
+     */
+    enum IrohDocEventType event_type;
+    /**
+     * The entry for insert events (null for other events).
+     * Must be freed with `iroh_doc_entry_free` if not null.
+     */
+    const struct IrohDocEntry *entry;
+    /**
+     * The peer ID for remote events (null for local events).
+     * Must be freed with `iroh_string_free` if not null.
+     */
+    const char *peer_id;
+    /**
+     * The content hash for ContentReady and ContentFetched events (null for
+     * other events). Must be freed with `iroh_string_free` if not null.
+     */
+    const char *content_hash;
+    /**
+     * Opaque cursor covering this event, for `iroh_doc_subscribe_from`.
+     * Must be freed with `iroh_string_free` if not null.
+     */
+    const char *cursor;
+    /**
+     * Namespace ID of the document the event belongs to.
+     * Must be freed with `iroh_string_free` if not null.
+     */
+    const char *namespace_id;
+    /**
+     * The entry's content for ContentFetched events (null data for other
+     * events). Freed with the event.
+     */
+    struct IrohOwnedBytes content;
+} IrohDocEvent;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Streaming callback for document subscriptions.
+ * Called multiple times - once per event, then on_complete when stream ends.
+ */
+typedef struct IrohDocSubscribeCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each event. Event must be freed with `iroh_doc_event_free`.
+     */
+    void (*on_event)(void *userdata, struct IrohDocEvent event);
+    /**
+     * Called when subscription ends normally.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocSubscribeCallback;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Options for document subscriptions.
+ */
+typedef struct IrohDocSubscribeOptions {
+    /**
+     * Cursor to replay entries written since, as in
+     * `iroh_doc_subscribe_from` (null to start from the current state).
+     */
+    const char *cursor;
+    /**
+     * Deliver a ContentFetched event with the content of each remote
+     * insert matching the document's download policy once it is local.
+     */
+    bool fetch_content;
+    /**
+     * Largest content delivered in ContentFetched events; larger entries
+     * only get InsertRemote and ContentReady (0 = no limit).
+     */
+    uint64_t max_content_size;
+    /**
+     * Deliver only EntryDeleted events, for deletions written locally,
+     * received from peers, or replayed from the cursor. `fetch_content`
+     * is ignored.
+     */
+    bool deletions_only;
+} IrohDocSubscribeOptions;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * One author's latest value for a conflicting document key.
+ * Strings and bytes are only valid for the duration of the resolve call.
+ */
+typedef struct IrohConflictCandidate {
+    /**
+     * Author ID (hex string).
+     */
+    const char *author;
+    /**
+     * Content hash (hex string).
+     */
+    const char *content_hash;
+    /**
+     * Entry content.
+     */
+    struct IrohBytes content;
+    /**
+     * Entry timestamp (microseconds since epoch).
+     */
+    uint64_t timestamp;
+} IrohConflictCandidate;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Opaque slot receiving the value chosen by a conflict resolver.
+ */
+typedef struct IrohConflictResolution {
+    uint8_t _private[0];
+} IrohConflictResolution;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Resolver for keys whose authors hold diverging values.
+ * Runs until cancelled with `iroh_subscription_cancel`, then on_complete.
+ */
+typedef struct IrohDocConflictResolver {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called with the key and every author's latest entry for it. Call
+     * `iroh_conflict_resolution_set` before returning to write a value;
+     * otherwise the entries are left as they are.
+     */
+    void (*resolve)(void *userdata,
+                    struct IrohBytes key,
+                    const struct IrohConflictCandidate *candidates,
+                    uintptr_t candidates_len,
+                    struct IrohConflictResolution *resolution);
+    /**
+     * Called when the resolver stops normally.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocConflictResolver;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * A remote document entry seen by a write filter.
+ * Strings and bytes are only valid for the duration of the callback.
+ */
+typedef struct IrohFilteredEntry {
+    /**
+     * Entry key.
+     */
+    struct IrohBytes key;
+    /**
+     * Author ID (hex string).
+     */
+    const char *author;
+    /**
+     * Content hash (hex string).
+     */
+    const char *content_hash;
+    /**
+     * Content length in bytes.
+     */
+    uint64_t content_len;
+    /**
+     * Entry timestamp (microseconds since epoch).
+     */
+    uint64_t timestamp;
+} IrohFilteredEntry;
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Policy for remote writes to a document.
+ * Runs until cancelled with `iroh_subscription_cancel`, then on_complete.
+ */
+typedef struct IrohDocWriteFilter {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Author IDs (hex strings) whose entries are always accepted.
+     */
+    const char *const *allowed_authors;
+    /**
+     * Number of allowed authors.
+     */
+    uintptr_t allowed_authors_len;
+    /**
+     * Decides entries from other authors; if null they are rejected.
+     */
+    enum IrohWriteVerdict (*decide)(void *userdata, struct IrohFilteredEntry entry);
+    /**
+     * Called after an entry was rejected or quarantined. `content` is
+     * empty for rejected entries. May be null.
+     */
+    void (*on_filtered)(void *userdata,
+                        struct IrohFilteredEntry entry,
+                        enum IrohWriteVerdict verdict,
+                        struct IrohBytes content);
+    /**
+     * Called when the filter stops normally.
+     */
+    void (*on_complete)(void *userdata);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDocWriteFilter;
+#endif
+
+/**
+ * Opaque handle to a temporary tag.
+ *
+ * Protects content from garbage collection until dropped with
+ * `iroh_blob_temp_tag_drop`. Temporary tags are not persisted.
+ */
+typedef struct IrohTempTagHandle {
+    uint8_t _private[0];
+} IrohTempTagHandle;
+
+/**
+ * Callback for temporary tag creation.
+ */
+typedef struct IrohTempTagCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the temp tag handle (drop with `iroh_blob_temp_tag_drop`).
+     */
+    void (*on_success)(void *userdata, struct IrohTempTagHandle *tag);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohTempTagCallback;
+
+/**
+ * Deduplication report for a tag.
+ */
+typedef struct IrohReferenceReport {
+    /**
+     * Number of distinct blobs reachable from the tag.
+     */
+    uint64_t total_blobs;
+    /**
+     * Number of those blobs also kept by other tags, temporary tags or documents.
+     */
+    uint64_t shared_blobs;
+    /**
+     * Total stored size of the tag's blobs in bytes.
+     */
+    uint64_t total_size;
+    /**
+     * Bytes that would be freed if the tag were deleted.
+     */
+    uint64_t unique_size;
+} IrohReferenceReport;
+
+/**
+ * Callback for reference report operations.
+ */
+typedef struct IrohReferenceReportCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the report.
+     */
+    void (*on_success)(void *userdata, struct IrohReferenceReport report);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohReferenceReportCallback;
+
+/**
+ * A blob referenced more than once.
+ * All pointers are only valid for the duration of the callback.
+ */
+typedef struct IrohDuplicateBlob {
+    /**
+     * Hash of the blob (hex string).
+     */
+    const char *hash;
+    /**
+     * Stored size in bytes.
+     */
+    uint64_t size;
+    /**
+     * Number of references, counting repeats inside one collection.
+     */
+    uint64_t references;
+    /**
+     * Names of the tags the blob is reachable from, sorted.
+     */
+    const char *const *tags;
+    /**
+     * Number of tag names.
+     */
+    uintptr_t tags_len;
+} IrohDuplicateBlob;
+
+/**
+ * Store-wide deduplication totals.
+ */
+typedef struct IrohDedupSummary {
+    /**
+     * References from tags to blobs, counting repeats inside collections.
+     */
+    uint64_t references;
+    /**
+     * Distinct blobs reachable from any tag.
+     */
+    uint64_t unique_blobs;
+    /**
+     * Distinct blobs referenced more than once.
+     */
+    uint64_t duplicate_blobs;
+    /**
+     * Size of all references, as if each were stored separately.
+     */
+    uint64_t referenced_size;
+    /**
+     * Size of the distinct blobs, as stored.
+     */
+    uint64_t stored_size;
+} IrohDedupSummary;
+
+/**
+ * Streaming callback for deduplication reports.
+ * Called once per listed duplicate, then on_complete.
+ */
+typedef struct IrohDedupReportCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each duplicate, largest saving first.
+     */
+    void (*on_duplicate)(void *userdata, struct IrohDuplicateBlob duplicate);
+    /**
+     * Called after the last duplicate with the store-wide totals.
+     */
+    void (*on_complete)(void *userdata, struct IrohDedupSummary summary);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohDedupReportCallback;
+
+/**
+ * A tag in a prefix listing.
+ */
+typedef struct IrohTagListEntry {
+    /**
+     * Tag name (only valid for the duration of the callback).
+     */
+    const char *name;
+    /**
+     * Tagged hash as hex string (only valid for the duration of the callback).
+     */
+    const char *hash;
+    /**
+     * Format of the tagged content.
+     */
+    enum IrohBlobFormat format;
+    /**
+     * Stored size of all blobs reachable from the tag in bytes.
+     */
+    uint64_t size;
+} IrohTagListEntry;
+
+/**
+ * Streaming callback for tag listings.
+ * Called once per matching tag, then on_complete.
+ */
+typedef struct IrohTagListCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each matching tag, in name order.
+     */
+    void (*on_entry)(void *userdata, struct IrohTagListEntry entry);
+    /**
+     * Called after the last tag with the combined size, counting shared blobs once.
+     */
+    void (*on_complete)(void *userdata, uint64_t total_size);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohTagListCallback;
+
+/**
+ * A blob in the local store.
+ */
+typedef struct IrohBlobListEntry {
+    /**
+     * Hash as hex string (only valid for the duration of the callback).
+     */
+    const char *hash;
+    /**
+     * Stored size in bytes.
+     */
+    uint64_t size;
+    /**
+     * Whether the blob is fully stored.
+     */
+    bool is_complete;
+    /**
+     * When the blob was added (microseconds since epoch), or 0 if unknown.
+     */
+    uint64_t added_at;
+} IrohBlobListEntry;
+
+/**
+ * Streaming callback for blob listings.
+ * Called once per entry on the page, then on_complete.
+ */
+typedef struct IrohBlobListCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called for each entry on the page, in order.
+     */
+    void (*on_entry)(void *userdata, struct IrohBlobListEntry entry);
+    /**
+     * Called after the last entry with the number of blobs across all pages
+     * and the cursor for fetching more pages of the listing.
+     */
+    void (*on_complete)(void *userdata, uint64_t total, uint64_t cursor);
+    /**
+     * Called on error. No more callbacks after this.
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohBlobListCallback;
+
+/**
+ * Content of a profile and its size.
+ */
+typedef struct IrohProfileStats {
+    /**
+     * Number of tags of the profile.
+     */
+    uint64_t tags;
+    /**
+     * Number of distinct blobs reachable from the profile's tags.
+     */
+    uint64_t blobs;
+    /**
+     * Total stored size of those blobs in bytes.
+     */
+    uint64_t total_size;
+    /**
+     * Bytes no tag outside the profile references, freed by a wipe.
+     */
+    uint64_t unique_size;
+} IrohProfileStats;
+
+/**
+ * Callback for profile stats operations.
+ */
+typedef struct IrohProfileStatsCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the stats.
+     */
+    void (*on_success)(void *userdata, struct IrohProfileStats stats);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohProfileStatsCallback;
+
+/**
+ * Content deleted from the store.
+ */
+typedef struct IrohReclaimed {
+    /**
+     * Number of blobs deleted.
+     */
+    uint64_t blobs;
+    /**
+     * Stored size of the deleted blobs in bytes.
+     */
+    uint64_t size;
+} IrohReclaimed;
+
+/**
+ * Callback for operations that reclaim content.
+ */
+typedef struct IrohReclaimCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with what was deleted.
+     */
+    void (*on_success)(void *userdata, struct IrohReclaimed reclaimed);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohReclaimCallback;
+
+/**
+ * Opaque handle to a crate-managed temporary file.
+ *
+ * The file is deleted when the handle is released with
+ * `iroh_temp_file_release`.
+ */
+typedef struct IrohTempFileHandle {
+    uint8_t _private[0];
+} IrohTempFileHandle;
+
+/**
+ * Callback for temporary file exports.
+ */
+typedef struct IrohTempFileCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the file handle (release with `iroh_temp_file_release`)
+     * and its path (caller must free with `iroh_string_free`).
+     */
+    void (*on_success)(void *userdata, struct IrohTempFileHandle *file, const char *path);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohTempFileCallback;
+
+/**
+ * Callback for copying blobs between stores.
+ */
+typedef struct IrohStoreCopyCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the number of bytes copied.
+     */
+    void (*on_success)(void *userdata, uint64_t bytes_copied);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohStoreCopyCallback;
+
+#if defined(IROH_FEATURE_CHUNKING)
+/**
+ * Outcome of importing or downloading a chunked file.
+ */
+typedef struct IrohChunkStats {
+    /**
+     * File size in bytes.
+     */
+    uint64_t size;
+    /**
+     * Number of chunks in the file.
+     */
+    uint64_t chunks;
+    /**
+     * Chunks that were not stored locally before the call.
+     */
+    uint64_t new_chunks;
+} IrohChunkStats;
+#endif
+
+#if defined(IROH_FEATURE_CHUNKING)
+/**
+ * Callback for chunked file imports.
+ */
+typedef struct IrohChunkedPutCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the ticket (caller must free with `iroh_string_free`)
+     * and the import stats.
+     */
+    void (*on_success)(void *userdata, const char *ticket, struct IrohChunkStats stats);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohChunkedPutCallback;
+#endif
+
+#if defined(IROH_FEATURE_CHUNKING)
+/**
+ * Callback for chunked file downloads.
+ */
+typedef struct IrohChunkedGetCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success once the file is written.
+     */
+    void (*on_success)(void *userdata, struct IrohChunkStats stats);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohChunkedGetCallback;
+#endif
+
+/**
+ * Metadata record attached to a blob.
+ *
+ * String fields are null when absent. When returned by Iroh, the record
+ * must be freed with `iroh_blob_metadata_free`.
+ */
+typedef struct IrohBlobMetadata {
+    /**
+     * Original filename (may be null).
+     */
+    char *filename;
+    /**
+     * MIME type of the content (may be null).
+     */
+    char *content_type;
+    /**
+     * Creation date in seconds since the Unix epoch.
+     */
+    uint64_t created_at;
+    /**
+     * Encoding of the stored content.
+     */
+    enum IrohCompression compression;
+} IrohBlobMetadata;
+
+/**
+ * Callback for downloads that return bytes and an optional metadata record.
+ */
+typedef struct IrohGetWithMetadataCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with owned bytes (caller must free with `iroh_bytes_free`)
+     * and the metadata record (may be null; free with `iroh_blob_metadata_free`).
+     */
+    void (*on_success)(void *userdata,
+                       struct IrohOwnedBytes bytes,
+                       struct IrohBlobMetadata *metadata);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohGetWithMetadataCallback;
+
+/**
+ * Callback for ticket metadata lookups.
+ */
+typedef struct IrohBlobMetadataCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the metadata record (may be null if the ticket has none).
+     * Caller must free the record with `iroh_blob_metadata_free` if not null.
+     */
+    void (*on_success)(void *userdata, struct IrohBlobMetadata *metadata);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohBlobMetadataCallback;
+
+/**
+ * Summary of pending work (see `iroh_pending_work`).
+ */
+typedef struct IrohPendingWork {
+    /**
+     * Whether any pending work was found.
+     */
+    bool has_pending_work;
+    /**
+     * Number of documents with local writes no peer has synced yet.
+     */
+    uint64_t unsynced_docs;
+    /**
+     * Number of downloads that have not finished.
+     */
+    uint64_t incomplete_downloads;
+} IrohPendingWork;
+
+/**
+ * Callback for pending work queries.
+ */
+typedef struct IrohPendingWorkCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the pending work summary.
+     */
+    void (*on_success)(void *userdata, struct IrohPendingWork work);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohPendingWorkCallback;
+
+/**
+ * What a node is holding on to (see `iroh_node_memory_report`).
+ */
+typedef struct IrohMemoryReport {
+    /**
+     * Size on disk of the blob and document database files. This is not
+     * memory use: it only bounds how much of the databases the store and
+     * the OS page cache can hold in memory.
+     */
+    uint64_t store_disk_bytes;
+    /**
+     * Tasks alive on the node's runtime.
+     */
+    uint64_t runtime_tasks;
+    /**
+     * Events and messages buffered until every subscriber has them.
+     */
+    uint64_t queued_events;
+    /**
+     * Callbacks waiting for the delivery thread.
+     */
+    uint64_t queued_callbacks;
+    /**
+     * Connections peers have open to this node.
+     */
+    uint64_t open_connections;
+} IrohMemoryReport;
+
+/**
+ * Callback for memory reports.
+ */
+typedef struct IrohMemoryReportCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the report.
+     */
+    void (*on_success)(void *userdata, struct IrohMemoryReport report);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohMemoryReportCallback;
+
+/**
+ * Outcome of a bounded sync step (see `iroh_node_sync_step`).
+ */
+typedef struct IrohSyncStepReport {
+    /**
+     * Whether all work finished before the budget ran out and no pending
+     * work is left.
+     */
+    bool completed;
+    /**
+     * Number of documents whose sync with known peers finished.
+     */
+    uint64_t docs_synced;
+    /**
+     * Number of entries received from peers.
+     */
+    uint64_t entries_received;
+    /**
+     * Number of missing content blobs that were downloaded.
+     */
+    uint64_t blobs_downloaded;
+} IrohSyncStepReport;
+
+/**
+ * Callback for bounded sync steps.
+ */
+typedef struct IrohSyncStepCallback {
+    /**
+     * Opaque pointer passed back to Swift.
+     */
+    void *userdata;
+    /**
+     * Called on success with the step report.
+     */
+    void (*on_success)(void *userdata, struct IrohSyncStepReport report);
+    /**
+     * Called on failure with an error message (caller must free with `iroh_string_free`).
+     */
+    void (*on_failure)(void *userdata, const char *error);
+} IrohSyncStepCallback;
+
+/**
+ * Create a new Iroh node asynchronously.
+ *
+ * # Safety
+ * - `config` must be laid out as in the header shipped with this library
+ * - `config.storage_path` must be a valid null-terminated UTF-8 string
+ * - `config.custom_relay_url` must be null or a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_create(struct IrohNodeConfig config, struct IrohNodeCreateCallback callback);
+
+/**
+ * Create a new Iroh node from a versioned JSON configuration.
+ *
+ * Unlike `IrohNodeConfig`, whose layout changes whenever an option is
+ * added, the JSON config keeps working across releases. It must set
+ * `"version": 1` and `storage_path`, unless `ephemeral` is true; other
+ * options use the `IrohNodeConfig` field names and defaults, for example:
+ *
+ * `{"version": 1, "storage_path": "/path/to/store", "docs_enabled": true}`
+ *
+ * Unknown options fail node creation instead of being ignored. Options
+ * that take callbacks (entropy, discovery, storage alerts, the slow
+ * operation and content announcement callbacks) are only available
+ * through `iroh_node_create`.
+ *
+ * # Safety
+ * - `config_json` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_create_from_json(const char *configJson, struct IrohNodeCreateCallback callback);
+
+/**
+ * Destroy an Iroh node and free its resources.
+ *
+ * This performs a graceful shutdown, ensuring pending writes are flushed.
+ * Subscriptions are cancelled and deliver their `on_complete` first. Calls
+ * still running on other threads finish against the shut down node, which
+ * is freed when the last of them returns; calls made afterwards fail.
+ *
+ * # Safety
+ * - `handle` must be a valid pointer returned by `iroh_node_create`
+ * - `handle` must not be used after this call
+ */
+void iroh_node_destroy(struct IrohNodeHandle *handle);
+
+/**
+ * Add bytes to the blob store and get a shareable ticket.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `bytes.data` must point to valid memory for `bytes.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_put(const struct IrohNodeHandle *handle,
+              struct IrohBytes bytes,
+              struct IrohCallback callback);
+
+/**
+ * Add the file at `path` to the blob store and get a shareable ticket.
+ *
+ * Like `iroh_put`, but the file is streamed from disk and hashed without
+ * being read into memory, so large media can be shared. The content is
+ * copied into the store, so the file may change or be deleted afterwards.
+ * `on_progress` is called while the file is copied and hashed.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `path` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_put_file(const struct IrohNodeHandle *handle,
+                   const char *path,
+                   struct IrohPutFileCallback callback);
+
+/**
+ * Download bytes from a ticket.
+ *
+ * A blob already complete in the local store is returned without
+ * contacting the ticket's provider.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_get(const struct IrohNodeHandle *handle,
+              const char *ticket,
+              struct IrohGetCallback callback);
+
+/**
+ * Free a string returned by Iroh functions.
+ *
+ * # Safety
+ * - `s` must be a pointer returned by an Iroh function, or null
+ * - `s` must not be used after this call
+ */
+void iroh_string_free(char *s);
+
+/**
+ * Free bytes returned by `iroh_get`.
+ *
+ * # Safety
+ * - `bytes` must have been returned by `iroh_get`
+ * - The bytes must not be used after this call
+ */
+void iroh_bytes_free(struct IrohOwnedBytes bytes);
+
+/**
+ * Return bytes to the buffer pool for reuse by later results.
+ *
+ * Use this instead of `iroh_bytes_free` for small results read in bulk,
+ * such as document content, to avoid an allocation per result. Buffers the
+ * pool cannot keep are freed.
+ *
+ * # Safety
+ * - `bytes` must have been returned by an Iroh function
+ * - The bytes must not be used after this call
+ */
+void iroh_bytes_recycle(struct IrohOwnedBytes bytes);
+
+/**
+ * Encode a hash as a 64-character hex string.
+ *
+ * # Safety
+ * - The returned string must be freed with `iroh_string_free`
+ */
+char *iroh_hash_to_hex(struct IrohHash hash);
+
+/**
+ * Decode a hex (or base32) hash into `out`.
+ *
+ * Returns false, leaving `out` untouched, if `hex` is not a valid hash.
+ *
+ * # Safety
+ * - `hex` must be a valid null-terminated string
+ * - `out` must be valid for writes
+ */
+bool iroh_hash_from_hex(const char *hex, struct IrohHash *out);
+
+/**
+ * List node, document and subscription handles that are still live.
+ *
+ * Use this while debugging to find handles leaked across the boundary.
+ * Debug builds include the backtrace of the call that created each handle.
+ *
+ * # Safety
+ * - `callback` must have valid function pointers
+ */
+void iroh_debug_handles(struct IrohLiveHandlesCallback callback);
+
+/**
+ * Id of the operation most recently started on the calling thread, or 0.
+ *
+ * Every call that reports back through a callback starts an operation.
+ * Read this right after such a call, on the same thread, to learn its id.
+ * Download events it causes carry the id in `operation_id`.
+ */
+uint64_t iroh_last_operation_id(void);
+
+/**
+ * Id of the operation running on the calling thread, or 0 if none.
+ *
+ * A call's success and failure callbacks run inside its operation, so
+ * reading this from either gives the id of the call reporting its result.
+ * Callbacks of subscriptions that run later, on the node's threads, get 0.
+ */
+uint64_t iroh_current_operation_id(void);
+
+/**
+ * Read operation counters for all nodes in the process.
+ *
+ * The callbacks are called before this returns. Failures are broken down
+ * by the FFI function that failed.
+ *
+ * # Safety
+ * - `callback` must have valid function pointers
+ */
+void iroh_operation_counters(struct IrohOperationCountersCallback callback);
+
+/**
+ * Register the process-wide log callback, replacing any earlier one.
+ *
+ * From the first call on, every panic in the library, including in tasks
+ * on a node's runtime, is passed to the callback with its location and
+ * backtrace before the panic is contained or the process aborts.
+ *
+ * # Safety
+ * - `callback` must have valid function pointers
+ */
+void iroh_log_init(struct IrohLogCallback callback);
+
+#if defined(IROH_FEATURE_DOWNLOADER_PROGRESS)
+/**
+ * Download bytes from a ticket with progress reporting.
+ *
+ * Progress reports received and verified bytes. After the last byte
+ * arrives, a `DownloadVerifying` update is sent while the store confirms
+ * the blob, then a `DownloadVerified` update right before `on_success`.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_get_with_progress(const struct IrohNodeHandle *handle,
+                            const char *ticket,
+                            struct IrohGetProgressCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOWNLOADER_PROGRESS)
+/**
+ * Download a blob, pushing verified chunks to `writer` as they arrive.
+ *
+ * Content is streamed straight from the provider without being staged in
+ * the store, so it can be fed directly into output streams, sockets or
+ * decryptors. Only raw blob tickets are supported. `writer.write` is called
+ * on the calling thread before `callback` fires.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `writer` and `callback` must have valid function pointers
+ */
+void iroh_get_to_sink(const struct IrohNodeHandle *handle,
+                      const char *ticket,
+                      struct IrohSinkWriter writer,
+                      struct IrohSinkCallback callback);
+#endif
+
+/**
+ * Download content available from several providers of the same hash.
+ *
+ * All tickets must refer to the same content. See `IrohProviderStrategy`
+ * for how the providers are used.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `tickets` must point to `tickets_len` valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_get_from_providers(const struct IrohNodeHandle *handle,
+                             const char *const *tickets,
+                             uintptr_t ticketsLen,
+                             enum IrohProviderStrategy strategy,
+                             struct IrohGetCallback callback);
+
+/**
+ * Get the node ID as a string, without a callback.
+ *
+ * Returns null if `handle` is null or the node is closed.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle or null
+ * - The returned string must be freed with `iroh_string_free`
+ */
+char *iroh_node_id(const struct IrohNodeHandle *handle);
+
+/**
+ * Check whether the node was created with docs enabled, without a callback.
+ *
+ * Returns false if `handle` is null or the node is closed.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle or null
+ */
+bool iroh_node_docs_enabled(const struct IrohNodeHandle *handle);
+
+/**
+ * Get the node's storage directory, without a callback.
+ *
+ * Returns null if `handle` is null, the node is closed, or the path is not
+ * valid UTF-8.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle or null
+ * - The returned string must be freed with `iroh_string_free`
+ */
+char *iroh_node_storage_path(const struct IrohNodeHandle *handle);
+
+/**
+ * Get information about the node.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_info(const struct IrohNodeHandle *handle, struct IrohNodeInfoCallback callback);
+
+/**
+ * Close all connections a peer has open to this node.
+ *
+ * Blob transfers and document syncs served to the peer stop immediately.
+ * The peer is not blocked and may connect again.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `node_id` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_connection_close(const struct IrohNodeHandle *handle,
+                           const char *nodeId,
+                           struct IrohConnectionCloseCallback callback);
+
+/**
+ * Dial a peer without transferring content and report how it was reached.
+ *
+ * `node_addr` is a node ID or a blob ticket, whose address hints are used.
+ * Use this to warn before a large transfer that is bound to fail.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `node_addr` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_can_reach(const struct IrohNodeHandle *handle,
+                    const char *nodeAddr,
+                    uint64_t timeoutMs,
+                    struct IrohCanReachCallback callback);
+
+/**
+ * Send a message to a peer running this library.
+ *
+ * `node_addr` is a node ID or a blob ticket, whose address hints are used.
+ * Messages are meant for small control-plane signals, such as telling a
+ * known device to sync a document, and carry at most 64 KiB. `on_complete`
+ * is called once a subscriber on the peer received the message (see
+ * `iroh_node_subscribe_messages`); the call fails if the peer cannot be
+ * reached within 10 seconds or is not subscribed. Messages are not queued
+ * for offline peers.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `node_addr` must be a valid null-terminated UTF-8 string
+ * - `bytes.data` must point to valid memory for `bytes.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_message_send(const struct IrohNodeHandle *handle,
+                       const char *nodeAddr,
+                       struct IrohBytes bytes,
+                       struct IrohCloseCallback callback);
+
+/**
+ * Ask providers how much of a blob they have, without downloading it.
+ *
+ * Providers are probed concurrently and found by node ID through
+ * discovery. A provider that cannot be reached, refuses the request or
+ * does not answer within `timeout_ms` (0 = 10 seconds) is reported as
+ * `BlobUnknown`; the call only fails on invalid arguments.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `node_ids` must point to `node_ids_len` valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_blob_probe_providers(const struct IrohNodeHandle *handle,
+                               struct IrohHash hash,
+                               const char *const *nodeIds,
+                               uintptr_t nodeIdsLen,
+                               uint64_t timeoutMs,
+                               struct IrohProviderProbeCallback callback);
+
+/**
+ * List bytes exchanged with each peer since the node started.
+ *
+ * Counts connections that peers opened to this node, such as blob fetches
+ * and document syncs they initiated. Connections this node opens, such as
+ * its own downloads and syncs, are not counted.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_peer_traffic_list(const struct IrohNodeHandle *handle,
+                            struct IrohPeerTrafficCallback callback);
+
+/**
+ * Change who may fetch blobs from this node when a peer has no rule.
+ *
+ * Applies to new connections; close a peer's connections with
+ * `iroh_connection_close` to stop transfers already in progress.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_blob_access_set_mode(const struct IrohNodeHandle *handle,
+                               enum IrohBlobAccessMode mode,
+                               struct IrohCloseCallback callback);
+
+/**
+ * Allow or deny a peer fetching blobs from this node, or clear its rule.
+ *
+ * Denying a peer also closes its open connections. Rejected fetch attempts
+ * are reported as `NodeFetchRejected` events.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `node_id` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_blob_access_set(const struct IrohNodeHandle *handle,
+                          const char *nodeId,
+                          enum IrohBlobAccessRule rule,
+                          struct IrohCloseCallback callback);
+
+/**
+ * List the blob access mode and every peer rule.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_blob_access_list(const struct IrohNodeHandle *handle,
+                           struct IrohBlobAccessCallback callback);
+
+/**
+ * Pause or resume serving blobs to peers.
+ *
+ * While paused, blob connections from peers are closed, including
+ * transfers in progress, and new ones are turned away. Local reads and
+ * downloads from other nodes keep working. A new node serves blobs.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_set_provider_enabled(const struct IrohNodeHandle *handle,
+                                    bool enabled,
+                                    struct IrohCloseCallback callback);
+
+/**
+ * Check whether blobs are served to peers, without a callback.
+ *
+ * Returns false if `handle` is null or the node is closed.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle or null
+ */
+bool iroh_node_provider_enabled(const struct IrohNodeHandle *handle);
+
+/**
+ * Report the latency to each configured relay and the home relay.
+ *
+ * Latencies come from the endpoint's most recent network report; relays
+ * are unmeasured until the first report completes.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_relay_report(const struct IrohNodeHandle *handle,
+                            struct IrohRelayReportCallback callback);
+
+/**
+ * Pin the node to one of its configured relays, or unpin it.
+ *
+ * While pinned, the node uses `relay_url` as its home relay whatever the
+ * latency to the others, for example to keep relayed traffic in a region.
+ * The node moves after its next network report, which the change starts
+ * right away. Pass null to let the node choose the fastest relay again.
+ * Fails if `relay_url` is not one of the node's relays. The pin lasts
+ * until the node is closed.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `relay_url` must be null or a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_set_home_relay(const struct IrohNodeHandle *handle,
+                              const char *relayUrl,
+                              struct IrohCloseCallback callback);
+
+/**
+ * Tell a node which kind of network the device is on.
+ *
+ * The bandwidth caps configured for that network apply to transfers from
+ * then on, including those in progress. Nodes start on Wi-Fi; call this
+ * whenever the device's network path changes.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_set_network_kind(const struct IrohNodeHandle *handle,
+                                enum IrohNetworkKind kind,
+                                struct IrohCloseCallback callback);
+
+/**
+ * Replace the faults injected into a node, for testing error handling.
+ *
+ * Puts fail while `fail_puts` lasts, downloads start `download_delay_ms`
+ * late, and peers starting a document sync are dropped at
+ * `sync_drop_percent`. Pass all zeros to stop injecting faults. Fails
+ * unless the library was built with the `faults` feature.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_inject_faults(const struct IrohNodeHandle *handle,
+                             struct IrohFaults faults,
+                             struct IrohCloseCallback callback);
+
+/**
+ * Check that the node's store, runtime and endpoint still work.
+ *
+ * Each check gives up after a few seconds, so a wedged node produces an
+ * unhealthy report instead of blocking forever. Apps can recreate the
+ * node when the report is unhealthy.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_health(const struct IrohNodeHandle *handle, struct IrohNodeHealthCallback callback);
+
+/**
+ * Watch the direct addresses peers can use to reach this node.
+ *
+ * The current addresses are delivered first, then the full set again
+ * whenever it changes, for example after a network change or a new STUN
+ * observation. Returns a handle to stop watching with
+ * `iroh_subscription_cancel`.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers that remain valid until
+ *   on_complete or on_failure is called
+ */
+struct IrohSubscriptionHandle *iroh_node_watch_direct_addrs(const struct IrohNodeHandle *handle,
+                                                            struct IrohDirectAddrsCallback callback);
+
+/**
+ * Subscribe to every node event through one stream.
+ *
+ * Delivers connectivity changes, downloads starting and finishing,
+ * expired entry prune passes, profile garbage collection runs, and
+ * neighbor and sync events of documents opened on this node. Only events
+ * after subscribing are delivered; a subscriber that falls far behind
+ * skips the events it missed. Returns a handle to stop with
+ * `iroh_subscription_cancel`.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers that remain valid until
+ *   on_complete or on_failure is called
+ */
+struct IrohSubscriptionHandle *iroh_node_subscribe_events(const struct IrohNodeHandle *handle,
+                                                          struct IrohNodeEventCallback callback);
+
+/**
+ * Subscribe to messages peers send to this node with `iroh_message_send`.
+ *
+ * Only messages received after subscribing are delivered; while nobody is
+ * subscribed, senders are told the message was not delivered. A
+ * subscriber that falls far behind skips the messages it missed. Returns a
+ * handle to stop with `iroh_subscription_cancel`.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers that remain valid until
+ *   on_complete or on_failure is called
+ */
+struct IrohSubscriptionHandle *iroh_node_subscribe_messages(const struct IrohNodeHandle *handle,
+                                                            struct IrohMessageCallback callback);
+
+/**
+ * Answer a discovery lookup started by `IrohDiscoveryProvider::resolve`.
+ *
+ * Pass a null `relay_url` and no addresses if the node is unknown. Returns
+ * false if an address cannot be parsed, in which case the lookup yields
+ * nothing. The request is consumed either way.
+ *
+ * # Safety
+ * - `request` must come from a resolve call and not have been completed yet
+ * - `relay_url` must be null or a valid null-terminated UTF-8 string
+ * - `addrs` must point to `len` valid null-terminated UTF-8 strings
+ */
+bool iroh_discovery_request_complete(struct IrohDiscoveryRequest *request,
+                                     const char *relayUrl,
+                                     const char *const *addrs,
+                                     uintptr_t len);
+
+/**
+ * Validate and parse a ticket string.
+ *
+ * This function always succeeds - check `info.is_valid` for the result.
+ * Valid tickets also report the provider's relay URLs and direct
+ * addresses, so the app can show how content was shared without
+ * downloading it.
+ *
+ * # Safety
+ * - `ticket` must be a valid null-terminated UTF-8 string (or null)
+ * - `callback` must have valid function pointers
+ */
+void iroh_validate_ticket(const char *ticket, struct IrohTicketValidateCallback callback);
+
+/**
+ * Re-encode a blob or doc ticket for QR codes or reading aloud.
+ *
+ * On success the encoded string is passed to `on_success`
+ * (caller must free with `iroh_string_free`).
+ *
+ * # Safety
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_ticket_encode(const char *ticket,
+                        enum IrohTicketEncoding encoding,
+                        struct IrohCallback callback);
+
+/**
+ * Decode a ticket in any supported encoding back to the standard form.
+ *
+ * On success the standard ticket is passed to `on_success`
+ * (caller must free with `iroh_string_free`).
+ *
+ * # Safety
+ * - `encoded` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_ticket_decode(const char *encoded, struct IrohCallback callback);
+
+/**
+ * Encrypt a blob or doc ticket with a passphrase.
+ *
+ * The passphrase is stretched with PBKDF2 before encrypting, which takes a
+ * noticeable fraction of a second, so call this off the main thread. On
+ * success the locked ticket is passed to `on_success` (caller must free
+ * with `iroh_string_free`).
+ *
+ * # Safety
+ * - `ticket` and `passphrase` must be valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_ticket_lock(const char *ticket, const char *passphrase, struct IrohCallback callback);
+
+/**
+ * Decrypt a ticket locked with `iroh_ticket_lock`.
+ *
+ * Like locking, this stretches the passphrase and should be called off
+ * the main thread. On success the standard ticket is passed to
+ * `on_success` (caller must free with `iroh_string_free`); a wrong
+ * passphrase is reported through `on_failure`.
+ *
+ * # Safety
+ * - `locked` and `passphrase` must be valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_ticket_unlock(const char *locked, const char *passphrase, struct IrohCallback callback);
+
+/**
+ * Pack several blob and doc tickets into one shareable bundle string.
+ *
+ * `labels` and `tickets` both have `len` entries; `labels` may be null, or
+ * contain nulls, for unlabeled tickets. Labels are at most 255 bytes and a
+ * bundle holds 1 to 255 tickets. On success the bundle is passed to
+ * `on_success` (caller must free with `iroh_string_free`).
+ *
+ * # Safety
+ * - `tickets` must point to `len` valid null-terminated UTF-8 strings
+ * - `labels` must be null or point to `len` null or valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_ticket_bundle_encode(const char *const *labels,
+                               const char *const *tickets,
+                               uintptr_t len,
+                               struct IrohCallback callback);
+
+/**
+ * Unpack the tickets of a bundle made with `iroh_ticket_bundle_encode`.
+ *
+ * Each ticket is passed to `on_entry` in bundle order, then `on_complete`
+ * is called. A damaged bundle is reported through `on_failure` without
+ * any entries.
+ *
+ * # Safety
+ * - `bundle` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_ticket_bundle_decode(const char *bundle, struct IrohTicketBundleCallback callback);
+
+/**
+ * Explicitly close a node and free its resources asynchronously.
+ *
+ * This is preferred over `iroh_node_destroy` when you need to await
+ * graceful shutdown completion. Subscriptions are cancelled and deliver
+ * their `on_complete`, and calls still running on other threads are waited
+ * for, before the node is freed; calls made afterwards fail. If this has
+ * not finished after `deadline_ms`, the remaining work is abandoned, the
+ * node is freed when the last running call returns, and `on_complete`
+ * reports that the shutdown was not graceful. A `deadline_ms` of 0 waits
+ * as long as shutdown takes.
+ *
+ * # Safety
+ * - `handle` must be a valid pointer returned by `iroh_node_create`
+ * - `handle` must not be used after this call
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_close(struct IrohNodeHandle *handle,
+                     uint64_t deadlineMs,
+                     struct IrohNodeCloseCallback callback);
+
+/**
+ * Add bytes to the blob store with options (e.g., timeout).
+ *
+ * If the timeout runs out, the import is stopped and `on_timeout` is
+ * called instead of `on_failure`.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `bytes.data` must point to valid memory for `bytes.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_put_with_options(const struct IrohNodeHandle *handle,
+                           struct IrohBytes bytes,
+                           struct IrohOperationOptions options,
+                           struct IrohPutWithOptionsCallback callback);
+
+/**
+ * Download bytes from a ticket with options (e.g., timeout).
+ *
+ * If the timeout runs out, the transfer is stopped and `on_timeout` is
+ * called instead of `on_failure`.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_get_with_options(const struct IrohNodeHandle *handle,
+                           const char *ticket,
+                           struct IrohOperationOptions options,
+                           struct IrohGetWithOptionsCallback callback);
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Create a new random author keypair.
+ *
+ * The secret key should be stored securely (e.g., in iOS Keychain).
+ * The ID is derived from the secret and can be stored openly.
+ *
+ * # Safety
+ * - `callback` must have valid function pointers
+ */
+void iroh_author_create(struct IrohAuthorCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Create a new author keypair from caller-provided entropy.
+ *
+ * The 32 bytes produced by `entropy` become the author's secret key, so
+ * keys can come from `SecRandomCopyBytes` or a seed held in an HSM.
+ * With a null `fill` this behaves like `iroh_author_create`.
+ *
+ * # Safety
+ * - `entropy.fill` must be null or write `len` bytes to `buf`
+ * - `callback` must have valid function pointers
+ */
+void iroh_author_create_with_entropy(struct IrohEntropySource entropy,
+                                     struct IrohAuthorCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Create the deterministic test author with `seed`.
+ *
+ * The same seed always gives the same author, so test suites get
+ * reproducible author IDs. Never use this for real authors.
+ *
+ * # Safety
+ * - `seed` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_author_create_from_seed(const char *seed, struct IrohAuthorCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get the author ID from a secret key.
+ *
+ * This is a pure computation - no node required.
+ * Useful for deriving the ID after loading secret from Keychain.
+ *
+ * # Safety
+ * - `secret` must contain valid author secret bytes
+ */
+struct IrohAuthorId iroh_author_id_from_secret(struct IrohAuthorSecret secret);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Sign an arbitrary payload with an author key.
+ *
+ * This is a pure computation - no node required. The signature is bound
+ * to this API and cannot be mistaken for a document entry signature.
+ *
+ * # Safety
+ * - `secret` must contain valid author secret bytes
+ * - `payload.data` must point to valid memory for `payload.len` bytes
+ */
+struct IrohAuthorSignature iroh_author_sign(struct IrohAuthorSecret secret,
+                                            struct IrohBytes payload);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Verify a payload signature made with `iroh_author_sign`.
+ *
+ * Returns true if `signature` was made by `author_id` over `payload`.
+ *
+ * # Safety
+ * - `payload.data` must point to valid memory for `payload.len` bytes
+ */
+bool iroh_author_verify(struct IrohAuthorId authorId,
+                        struct IrohBytes payload,
+                        struct IrohAuthorSignature signature);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Import an author from a hex-encoded secret key.
+ *
+ * Useful for debugging or cross-device sync.
+ *
+ * # Safety
+ * - `secret_hex` must be a valid null-terminated UTF-8 string containing 64 hex chars
+ * - `callback` must have valid function pointers
+ */
+void iroh_author_from_hex(const char *secretHex, struct IrohAuthorCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Export an author secret as a hex string.
+ *
+ * Useful for debugging or backup.
+ *
+ * # Safety
+ * - The returned string must be freed with `iroh_string_free`
+ */
+char *iroh_author_secret_to_hex(struct IrohAuthorSecret secret);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Export an author ID as a hex string.
+ *
+ * # Safety
+ * - The returned string must be freed with `iroh_string_free`
+ */
+char *iroh_author_id_to_hex(struct IrohAuthorId id);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Import an author into the docs engine.
+ *
+ * This must be called before using an author to sign document entries.
+ * The author is registered with the docs engine so it can sign entries.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle with docs enabled
+ * - `callback` must have valid function pointers
+ */
+void iroh_author_import(const struct IrohNodeHandle *handle,
+                        struct IrohAuthorSecret authorSecret,
+                        struct IrohCloseCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Make an imported author the node's default author.
+ *
+ * Document writes that name no author, such as
+ * `iroh_doc_set_with_default_author`, are signed by the default author, so
+ * its secret only crosses the FFI once, in `iroh_author_import`. The choice
+ * is kept in the storage directory across restarts. Fails if the author
+ * has not been imported.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle with docs enabled
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_set_default_author(const struct IrohNodeHandle *handle,
+                                  struct IrohAuthorId authorId,
+                                  struct IrohCloseCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get the node's default author as a hex author ID.
+ *
+ * This is the author set with `iroh_node_set_default_author`, or else an
+ * author the docs engine created for the node.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle with docs enabled
+ * - `callback` must have valid function pointers
+ */
+void iroh_node_default_author(const struct IrohNodeHandle *handle, struct IrohCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Create a new document.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle with docs enabled
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_create(const struct IrohNodeHandle *handle, struct IrohDocCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Inspect a document ticket without joining the document.
+ *
+ * This function always succeeds - check `info.is_valid` for the result.
+ * Use `info.capability` to warn before accepting write access to someone
+ * else's document.
+ *
+ * # Safety
+ * - `ticket` must be a valid null-terminated UTF-8 string (or null)
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_ticket_inspect(const char *ticket, struct IrohDocTicketInspectCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Join an existing document via ticket.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle with docs enabled
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_join(const struct IrohNodeHandle *handle,
+                   const char *ticket,
+                   struct IrohDocCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Join an existing document via ticket with the given access mode.
+ *
+ * A `ReadOnly` handle rejects every write, even when the ticket grants
+ * write access, so it can be handed to untrusted code.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle with docs enabled
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_join_with_mode(const struct IrohNodeHandle *handle,
+                             const char *ticket,
+                             enum IrohDocOpenMode mode,
+                             struct IrohDocCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Open a document already stored on this node.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle with docs enabled
+ * - `namespace_id` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_open(const struct IrohNodeHandle *handle,
+                   const char *namespaceId,
+                   enum IrohDocOpenMode mode,
+                   struct IrohDocCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key-value pair in a document.
+ *
+ * The write is as durable as the document's durability, set with
+ * `iroh_doc_set_durability`.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `value.data` must point to valid memory for `value.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set(const struct IrohDocHandle *docHandle,
+                  struct IrohAuthorSecret authorSecret,
+                  struct IrohBytes key,
+                  struct IrohBytes value,
+                  struct IrohDocSetCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key-value pair in a document as the node's default author.
+ *
+ * Like `iroh_doc_set`, without passing the author secret; see
+ * `iroh_node_set_default_author`.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `value.data` must point to valid memory for `value.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_with_default_author(const struct IrohDocHandle *docHandle,
+                                      struct IrohBytes key,
+                                      struct IrohBytes value,
+                                      struct IrohDocSetCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key-value pair in a document with a durability of its own.
+ *
+ * With `DurabilityImmediate`, `on_success` is only called once the write,
+ * and every write batched before it, is synced to disk.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `value.data` must point to valid memory for `value.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_with_durability(const struct IrohDocHandle *docHandle,
+                                  struct IrohAuthorSecret authorSecret,
+                                  struct IrohBytes key,
+                                  struct IrohBytes value,
+                                  enum IrohDurability durability,
+                                  struct IrohDocSetCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key-value pair in a document, reporting the content hash as an
+ * `IrohHash`.
+ *
+ * Same as `iroh_doc_set`, without encoding the hash as hex.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `value.data` must point to valid memory for `value.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_with_hash_callback(const struct IrohDocHandle *docHandle,
+                                     struct IrohAuthorSecret authorSecret,
+                                     struct IrohBytes key,
+                                     struct IrohBytes value,
+                                     struct IrohHashCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key in a document to the contents of a file.
+ *
+ * The file is imported into the blob store and the entry set to its hash
+ * in one operation, without reading the file into memory, so large media
+ * can be stored from a path. The content is copied, so the file may
+ * change or be deleted afterwards. `on_progress` is called while the file
+ * is copied and hashed. The write is as durable as the document's
+ * durability.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `path` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_file(const struct IrohDocHandle *docHandle,
+                       struct IrohAuthorSecret authorSecret,
+                       struct IrohBytes key,
+                       const char *path,
+                       struct IrohDocSetFileCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set the durability of a document's writes that do not choose their
+ * own, for as long as the node runs.
+ *
+ * Applies to sets, deletions, compare-and-set, counter and TTL writes
+ * through any handle of the document. Use `DurabilityImmediate` for
+ * records that must survive a crash, and `DurabilityBatched`, the
+ * default, for bulk imports.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_durability(const struct IrohDocHandle *docHandle,
+                             enum IrohDurability durability,
+                             struct IrohCloseCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Commit every batched document write on the node and sync it to disk.
+ *
+ * Call after a bulk import with batched durability to make it crash-safe
+ * at once. The docs store is shared by all documents, so writes to other
+ * documents are flushed too.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_flush(const struct IrohDocHandle *docHandle, struct IrohCloseCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key only if its latest entry has the expected content hash.
+ *
+ * Pass a null `expected_hash` to require that the key is absent or
+ * deleted. On mismatch nothing is written and `on_conflict` receives the
+ * current hash, so the caller can re-read and retry.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `expected_hash` must be a valid null-terminated hex hash string or null
+ * - `value.data` must point to valid memory for `value.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_cas(const struct IrohDocHandle *docHandle,
+                      struct IrohAuthorSecret authorSecret,
+                      struct IrohBytes key,
+                      const char *expectedHash,
+                      struct IrohBytes value,
+                      struct IrohDocSetCasCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key only if its latest entry has the expected content hash, with
+ * hashes passed as `IrohHash`.
+ *
+ * Same as `iroh_doc_set_cas`, without encoding hashes as hex. Pass a null
+ * `expected_hash` to require that the key is absent or deleted.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `expected_hash` must point to a valid hash or be null
+ * - `value.data` must point to valid memory for `value.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_cas_by_hash(const struct IrohDocHandle *docHandle,
+                              struct IrohAuthorSecret authorSecret,
+                              struct IrohBytes key,
+                              const struct IrohHash *expectedHash,
+                              struct IrohBytes value,
+                              struct IrohDocSetCasHashCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Add `delta` to a mergeable counter and report its new value.
+ *
+ * Each author stores its own running total under `key`, and the counter's
+ * value is the sum across authors, so concurrent increments from different
+ * devices are not lost. Use a negative `delta` to decrement.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_counter_add(const struct IrohDocHandle *docHandle,
+                          struct IrohAuthorSecret authorSecret,
+                          struct IrohBytes key,
+                          int64_t delta,
+                          struct IrohDocCounterCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Read the value of a mergeable counter (0 if it has never been written).
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_counter_get(const struct IrohDocHandle *docHandle,
+                          struct IrohBytes key,
+                          struct IrohDocCounterCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key-value pair and track it in the outbox until a peer has it.
+ *
+ * The entry is stored locally before `on_success` runs, like with
+ * `iroh_doc_set`. The node then keeps syncing the document with its known
+ * peers, including after restarts, and reports the item's progress as
+ * `NodeOutbox*` events (see `iroh_node_subscribe_events`).
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `value.data` must point to valid memory for `value.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_outbox_set(const struct IrohDocHandle *docHandle,
+                         struct IrohAuthorSecret authorSecret,
+                         struct IrohBytes key,
+                         struct IrohBytes value,
+                         struct IrohDocOutboxCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * List the document writes still waiting for a peer.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_outbox_list(const struct IrohNodeHandle *handle, struct IrohOutboxCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Replace the durable peers of a document; none stops tracking it.
+ *
+ * Local writes count as backed up once a sync with every durable peer,
+ * started after the write, succeeded. The node syncs with the durable
+ * peers after local writes, retrying with backoff, and reports
+ * confirmations as `NodeDurableSync` events. Designations are persisted
+ * and resume after a restart.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `node_ids` must point to `node_ids_len` valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_durable_peers(const struct IrohDocHandle *docHandle,
+                                const char *const *nodeIds,
+                                uintptr_t nodeIdsLen,
+                                struct IrohCloseCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Report how far a document's local writes are backed up on its durable peers.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_durable_status(const struct IrohDocHandle *docHandle,
+                             struct IrohDurableStatusCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Store bytes as an asset of the backup manifest kept in a document.
+ *
+ * The blob is pinned with a tag and indexed under `asset_id` with its
+ * hash, size and metadata, replacing any previous version of the asset.
+ * `on_success` receives the content hash.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `asset_id` must be a valid null-terminated UTF-8 string
+ * - `data.data` must point to valid memory for `data.len` bytes
+ * - `filename` and `content_type` must be null or valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_manifest_add(const struct IrohDocHandle *docHandle,
+                       struct IrohAuthorSecret authorSecret,
+                       const char *assetId,
+                       struct IrohBytes data,
+                       const char *filename,
+                       const char *contentType,
+                       uint64_t createdAt,
+                       struct IrohHashCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * List the assets of the backup manifest kept in a document.
+ *
+ * Entries whose value has not been synced yet are skipped.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_manifest_list(const struct IrohDocHandle *docHandle,
+                        struct IrohManifestCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Find assets that are not fully backed up on this node.
+ *
+ * Reports the IDs in `asset_ids` that the manifest does not list, and
+ * listed assets whose content is not stored on this node. Listed assets
+ * whose content is stored but not yet pinned, such as blobs downloaded
+ * for a restore, are pinned.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `asset_ids` must point to `asset_ids_len` valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_manifest_missing(const struct IrohDocHandle *docHandle,
+                           const char *const *assetIds,
+                           uintptr_t assetIdsLen,
+                           struct IrohManifestMissingCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Remove deleted assets from the backup manifest and unpin their blobs.
+ *
+ * Each index entry is removed before its pin, and pins left behind by
+ * interrupted calls are removed as well. `on_success` receives the number
+ * of assets that were listed and are now removed.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `asset_ids` must point to `asset_ids_len` valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_manifest_prune(const struct IrohDocHandle *docHandle,
+                         struct IrohAuthorSecret authorSecret,
+                         const char *const *assetIds,
+                         uintptr_t assetIdsLen,
+                         struct IrohDocDelCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Set a key-value pair that is removed once `ttl_ms` has elapsed.
+ *
+ * The crate keeps an expiry record next to the entry and tombstones the
+ * key after it expires (see `IrohNodeConfig::ttl_prune_interval_ms`).
+ * Writing the key again without a TTL cancels the expiry. The key must
+ * not be empty.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `value.data` must point to valid memory for `value.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_set_with_ttl(const struct IrohDocHandle *docHandle,
+                           struct IrohAuthorSecret authorSecret,
+                           struct IrohBytes key,
+                           struct IrohBytes value,
+                           uint64_t ttlMs,
+                           struct IrohDocSetCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Remove expired document entries now instead of waiting for the
+ * background pass.
+ *
+ * Calls `on_success` with the number of keys removed.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_docs_prune_expired(const struct IrohNodeHandle *handle,
+                             struct IrohDocDelCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get the latest entry for a key.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_get(const struct IrohDocHandle *docHandle,
+                  struct IrohBytes key,
+                  struct IrohDocGetCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get the latest entries for several keys in one call.
+ *
+ * Entries are reported in the order of `keys`; a key with no entry is
+ * reported with a null entry. Nothing is reported if the lookup fails.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `keys` must point to `keys_len` `IrohBytes`, each valid for its `len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_get_batch(const struct IrohDocHandle *docHandle,
+                        const struct IrohBytes *keys,
+                        uintptr_t keysLen,
+                        struct IrohDocGetBatchCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get entries by key prefix.
+ *
+ * This streams entries back via the callback - on_entry is called for each
+ * entry, then on_complete when done.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `prefix.data` must point to valid memory for `prefix.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_get_many(const struct IrohDocHandle *docHandle,
+                       struct IrohBytes prefix,
+                       struct IrohDocGetManyCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get entries matching a query.
+ *
+ * Streams the latest entry per author and key, or only each key's winning
+ * entry with `latest_only`, in key order - on_entry is called for each
+ * matching entry, then on_complete when done.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `query.key_prefix.data` must point to valid memory for `query.key_prefix.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_query(const struct IrohDocHandle *docHandle,
+                    struct IrohDocQuery query,
+                    struct IrohDocGetManyCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Stream entries matching a query to `writer` as newline-delimited JSON.
+ *
+ * Each line is a JSON object for one entry, in the order of
+ * `iroh_doc_query`: `key`, `author`, `content_hash`, `content_len`,
+ * `timestamp` (microseconds since epoch) and `value`, which holds the
+ * content of entries up to `max_value_size` bytes that is stored locally
+ * and is left out otherwise. Keys and values that are not valid UTF-8 are
+ * hex encoded in `key_hex` and `value_hex` instead. `writer.write` gets
+ * chunks of whole lines on the calling thread before `callback` fires, and
+ * returns false to stop the export.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `query.key_prefix.data` must point to valid memory for `query.key_prefix.len` bytes
+ * - `writer` and `callback` must have valid function pointers
+ */
+void iroh_doc_export_query(const struct IrohDocHandle *docHandle,
+                           struct IrohDocQuery query,
+                           uint64_t maxValueSize,
+                           struct IrohSinkWriter writer,
+                           struct IrohDocExportCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Delete an entry (creates a tombstone).
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_del(const struct IrohDocHandle *docHandle,
+                  struct IrohAuthorSecret authorSecret,
+                  struct IrohBytes key,
+                  struct IrohDocDelCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Delete an entry as the node's default author.
+ *
+ * Like `iroh_doc_del`, without passing the author secret; see
+ * `iroh_node_set_default_author`.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `key.data` must point to valid memory for `key.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_del_with_default_author(const struct IrohDocHandle *docHandle,
+                                      struct IrohBytes key,
+                                      struct IrohDocDelCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Delete all of an author's entries with keys in a lexicographic range.
+ *
+ * The range includes `start_key` and excludes `end_key`; an empty
+ * `end_key` means no upper bound. Use this to prune time-bucketed keys
+ * such as `events/2023/...`. The author's keys outside the range are
+ * kept, even when they extend a deleted key; if a key in range can't be
+ * deleted without also deleting an older key outside it, nothing is
+ * deleted and the call fails.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `start_key.data` must point to valid memory for `start_key.len` bytes
+ * - `end_key.data` must point to valid memory for `end_key.len` bytes
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_del_range(const struct IrohDocHandle *docHandle,
+                        struct IrohAuthorSecret authorSecret,
+                        struct IrohBytes startKey,
+                        struct IrohBytes endKey,
+                        struct IrohDocDelCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_SQLITE)
+/**
+ * Write the latest entries of a document to a new SQLite file.
+ *
+ * The file has a `document` table with the namespace and export time, and
+ * an `entries` table with one row per author and key: `key`, `author`,
+ * `content_hash`, `content_len`, `timestamp` (microseconds since epoch) and
+ * `value`. `value` holds the content of entries up to `max_value_size`
+ * bytes that is stored locally, and is NULL otherwise; pass 0 to export
+ * metadata only. An existing file at `path` is replaced once the export
+ * is complete.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `path` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_export_sqlite(const struct IrohDocHandle *docHandle,
+                            const char *path,
+                            uint64_t maxValueSize,
+                            struct IrohDocExportCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Read content bytes by hash.
+ *
+ * This fetches the actual content data for an entry (entries only contain the hash).
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `content_hash` must be a valid null-terminated UTF-8 hex string
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_read_content(const struct IrohNodeHandle *handle,
+                           const char *contentHash,
+                           struct IrohGetCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Read content bytes by binary hash.
+ *
+ * Same as `iroh_doc_read_content`, without encoding the hash as hex, for
+ * reading the content of many entries.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_read_content_by_hash(const struct IrohNodeHandle *handle,
+                                   struct IrohHash contentHash,
+                                   struct IrohGetCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get a share ticket for a document.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_share(const struct IrohDocHandle *docHandle,
+                    enum IrohDocShareMode mode,
+                    struct IrohCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Store a snapshot of a document as a blob and return its blob ticket.
+ *
+ * The blob holds every signed entry and the content stored for it, so a
+ * new device can bootstrap the document with `iroh_doc_restore_from_blob`
+ * in a single fetch. Anyone with the ticket can read the document.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_snapshot_to_blob(const struct IrohDocHandle *docHandle, struct IrohCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Join a document, bootstrapping it from a snapshot blob.
+ *
+ * Fetches the snapshot from `snapshot_ticket` (from
+ * `iroh_doc_snapshot_to_blob`), inserts its entries, whose signatures are
+ * checked as in a sync, and then starts live sync with the peers in
+ * `doc_ticket` to catch up on later writes. The doc ticket grants access;
+ * the snapshot must be of the same document.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle with docs enabled
+ * - `doc_ticket` and `snapshot_ticket` must be valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_restore_from_blob(const struct IrohNodeHandle *handle,
+                                const char *docTicket,
+                                const char *snapshotTicket,
+                                struct IrohDocCreateCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get swarm statistics for a document's gossip topic.
+ *
+ * Counting starts when the document is first created, joined or opened on
+ * this node and continues until the node shuts down.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_swarm_stats(const struct IrohDocHandle *docHandle,
+                          struct IrohDocSwarmStatsCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get how syncs of a document with each peer went, for sync health
+ * displays.
+ *
+ * Peers are listed once they joined the document's swarm or synced,
+ * counting from when the document was first created, joined or opened on
+ * this node. `NodePeerSyncChanged` events report each change.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_peer_sync_stats(const struct IrohDocHandle *docHandle,
+                              struct IrohPeerSyncStatsCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Get a document's namespace ID as a string, without a callback.
+ *
+ * Returns null if `doc_handle` is null.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle or null
+ * - The returned string must be freed with `iroh_string_free`
+ */
+char *iroh_doc_namespace_id(const struct IrohDocHandle *docHandle);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Close a document and free its resources.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle returned by `iroh_doc_create` or `iroh_doc_join`
+ * - `doc_handle` must not be used after this call
+ */
+void iroh_doc_close(struct IrohDocHandle *docHandle);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Free a document entry.
+ *
+ * # Safety
+ * - `entry` must be a valid entry pointer returned by document operations
+ * - `entry` must not be used after this call
+ */
+void iroh_doc_entry_free(struct IrohDocEntry *entry);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Verify a signed entry received outside of sync.
+ *
+ * `entry` holds a signed entry in the iroh-docs wire encoding. Its author
+ * and namespace signatures are checked, and if `namespace_id` is not null
+ * the entry must also belong to that document. On success the entry is
+ * passed to `on_success` (caller must free with `iroh_doc_entry_free`).
+ *
+ * # Safety
+ * - `entry.data` must point to valid memory for `entry.len` bytes
+ * - `namespace_id` must be a valid null-terminated UTF-8 string or null
+ * - `callback` must have valid function pointers
+ */
+void iroh_doc_entry_verify(struct IrohBytes entry,
+                           const char *namespaceId,
+                           struct IrohDocGetCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Subscribe to document events.
+ *
+ * Returns a subscription handle that can be used to cancel the subscription.
+ * Events are delivered via the callback until the subscription is cancelled
+ * or the stream ends.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `callback` must have valid function pointers that remain valid for the
+ *   duration of the subscription
+ */
+struct IrohSubscriptionHandle *iroh_doc_subscribe(const struct IrohDocHandle *docHandle,
+                                                  struct IrohDocSubscribeCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Subscribe to document events, replaying entries written since `cursor`.
+ *
+ * Every event carries a cursor; pass the last one seen to resume after a
+ * restart. Entries written since then are delivered first as `Replayed`
+ * events, oldest first, followed by live events. Only the latest entry per
+ * author and key is replayed, and deletions are replayed as empty entries.
+ * A null `cursor` starts from the document's current state.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `cursor` must be null or a valid C string returned in an event
+ * - `callback` must have valid function pointers that remain valid for the
+ *   duration of the subscription
+ */
+struct IrohSubscriptionHandle *iroh_doc_subscribe_from(const struct IrohDocHandle *docHandle,
+                                                       const char *cursor,
+                                                       struct IrohDocSubscribeCallback callback);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Subscribe to document events with options.
+ *
+ * With `fetch_content`, each remote insert matching the document's
+ * download policy is followed by a ContentFetched event carrying the entry
+ * and its content as soon as the content is local: right after the
+ * InsertRemote if it already was, or else once the download finishes,
+ * next to the ContentReady. Content that fails to download is not
+ * reported. The download policy is read when the subscription starts.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `options.cursor` must be null or a valid C string returned in an event
+ * - `callback` must have valid function pointers that remain valid for the
+ *   duration of the subscription
+ */
+struct IrohSubscriptionHandle *iroh_doc_subscribe_with_options(const struct IrohDocHandle *docHandle,
+                                                               struct IrohDocSubscribeOptions options,
+                                                               struct IrohDocSubscribeCallback callback);
+#endif
+
+/**
+ * Cancel an active subscription.
+ *
+ * After calling this, no more events will be delivered and on_complete will be called.
+ *
+ * # Safety
+ * - `handle` must be a valid subscription handle returned by `iroh_doc_subscribe`
+ * - `handle` must not be used after this call
+ */
+void iroh_subscription_cancel(struct IrohSubscriptionHandle *handle);
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Register a resolver for keys whose authors hold diverging values.
+ *
+ * Whenever a remote insert leaves several authors with different latest
+ * values for a key, `resolver.resolve` is called on a background thread
+ * with every candidate; no lock is held while it runs. A value set on the
+ * resolution is written by `author_secret` while compare-and-set writes
+ * are held off, and only if the candidates are still the key's latest
+ * entries. Local writes never invoke the resolver.
+ *
+ * Returns a handle to stop the resolver with `iroh_subscription_cancel`.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `resolver` must have valid function pointers that remain valid until
+ *   on_complete or on_failure is called
+ */
+struct IrohSubscriptionHandle *iroh_doc_set_conflict_resolver(const struct IrohDocHandle *docHandle,
+                                                              struct IrohAuthorSecret authorSecret,
+                                                              struct IrohDocConflictResolver resolver);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Filter remote writes to a document by author.
+ *
+ * Entries synced from peers are accepted if their author is
+ * `author_secret` or in `filter.allowed_authors`; otherwise `filter.decide`
+ * is called on a background thread, and without it they are rejected.
+ * Rejected and quarantined entries are rolled back by `author_secret`,
+ * restoring the newest allowed value or deleting the key, then reported
+ * to `filter.on_filtered`. Sync cannot be stopped before entries are
+ * stored, so they stay in the replica and are briefly visible.
+ *
+ * Returns a handle to stop the filter with `iroh_subscription_cancel`.
+ *
+ * # Safety
+ * - `doc_handle` must be a valid document handle
+ * - `filter.allowed_authors` must point to `filter.allowed_authors_len`
+ *   valid null-terminated UTF-8 strings
+ * - `filter` must have valid function pointers that remain valid until
+ *   on_complete or on_failure is called
+ */
+struct IrohSubscriptionHandle *iroh_doc_set_write_filter(const struct IrohDocHandle *docHandle,
+                                                         struct IrohAuthorSecret authorSecret,
+                                                         struct IrohDocWriteFilter filter);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Choose the value a conflict resolver writes for the key.
+ *
+ * Calling this again replaces the previous value.
+ *
+ * # Safety
+ * - `resolution` must be the pointer passed to the current resolve call
+ * - `value.data` must point to valid memory for `value.len` bytes
+ */
+void iroh_conflict_resolution_set(struct IrohConflictResolution *resolution,
+                                  struct IrohBytes value);
+#endif
+
+#if defined(IROH_FEATURE_DOCS)
+/**
+ * Free a document event.
+ *
+ * # Safety
+ * - `event` fields that are non-null must be valid pointers
+ */
+void iroh_doc_event_free(struct IrohDocEvent event);
+#endif
+
+/**
+ * Tag (pin) a blob to prevent garbage collection.
+ *
+ * Tagged blobs are protected from GC until the tag is removed.
+ * Use this after downloading content you want to keep.
+ *
+ * # Safety
+ * - `handle` must be a valid node handle
+ * - `tag_name` must be a valid null-terminated UTF-8 string
+ * - `hash_str` must be a valid null-terminated hex hash string
+ * - `callback` must have valid function pointers
+ */
+void iroh_blob_tag_set(const struct IrohNodeHandle *handle,
+                       const char *tagName,
+                       const char *hashStr,
+                       enum IrohBlobFormat format,
+                       struct IrohCloseCallback callback);
 
 /**
- * Destroy an Iroh node and free its resources.
+ * Tag (pin) a blob given by binary hash to prevent garbage collection.
  *
- * This performs a graceful shutdown, ensuring pending writes are flushed.
+ * Same as `iroh_blob_tag_set`, without encoding the hash as hex.
  *
  * # Safety
- * - `handle` must be a valid pointer returned by `iroh_node_create`
- * - `handle` must not be used after this call
+ * - `handle` must be a valid node handle
+ * - `tag_name` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
  */
-void iroh_node_destroy(struct IrohNodeHandle *handle);
+void iroh_blob_tag_set_by_hash(const struct IrohNodeHandle *handle,
+                               const char *tagName,
+                               struct IrohHash hash,
+                               enum IrohBlobFormat format,
+                               struct IrohCloseCallback callback);
 
 /**
- * Add bytes to the blob store and get a shareable ticket.
+ * Tag (pin) a blob until an expiry time, for time-limited shares.
+ *
+ * At `expires_at` (microseconds since epoch) the tag is removed, its
+ * content becomes eligible for garbage collection, and a
+ * `NodeTagExpired` event is emitted. Expiry times survive restarts.
+ * Setting or deleting the tag with `iroh_blob_tag_set` or
+ * `iroh_blob_tag_delete` cancels the expiry.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `bytes.data` must point to valid memory for `bytes.len` bytes
+ * - `tag_name` must be a valid null-terminated UTF-8 string
+ * - `hash_str` must be a valid null-terminated hex hash string
  * - `callback` must have valid function pointers
  */
-void iroh_put(const struct IrohNodeHandle *handle,
-              struct IrohBytes bytes,
-              struct IrohCallback callback);
+void iroh_blob_tag_set_until(const struct IrohNodeHandle *handle,
+                             const char *tagName,
+                             const char *hashStr,
+                             enum IrohBlobFormat format,
+                             uint64_t expiresAt,
+                             struct IrohCloseCallback callback);
 
 /**
- * Download bytes from a ticket.
+ * Create a shareable ticket for an existing local blob.
+ *
+ * The ticket points to this node as the provider.
+ * Use this to "mint" a bootstrap ticket after downloading content.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `hash_str` must be a valid null-terminated hex hash string
  * - `callback` must have valid function pointers
  */
-void iroh_get(const struct IrohNodeHandle *handle,
-              const char *ticket,
-              struct IrohGetCallback callback);
+void iroh_blob_ticket_create(const struct IrohNodeHandle *handle,
+                             const char *hashStr,
+                             enum IrohBlobFormat format,
+                             struct IrohCallback callback);
 
 /**
- * Free a string returned by Iroh functions.
+ * Create a shareable ticket for an existing local blob given by binary hash.
  *
- * # Safety
- * - `s` must be a pointer returned by an Iroh function, or null
- * - `s` must not be used after this call
- */
-void iroh_string_free(char *s);
-
-/**
- * Free bytes returned by `iroh_get`.
+ * Same as `iroh_blob_ticket_create`, without encoding the hash as hex.
  *
  * # Safety
- * - `bytes` must have been returned by `iroh_get`
- * - The bytes must not be used after this call
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
  */
-void iroh_bytes_free(struct IrohOwnedBytes bytes);
+void iroh_blob_ticket_create_by_hash(const struct IrohNodeHandle *handle,
+                                     struct IrohHash hash,
+                                     enum IrohBlobFormat format,
+                                     struct IrohCallback callback);
 
 /**
- * Download bytes from a ticket with progress reporting.
+ * Remove a tag (unpin) from a blob, allowing garbage collection.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `tag_name` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_get_with_progress(const struct IrohNodeHandle *handle,
-                            const char *ticket,
-                            struct IrohGetProgressCallback callback);
+void iroh_blob_tag_delete(const struct IrohNodeHandle *handle,
+                          const char *tagName,
+                          struct IrohCloseCallback callback);
 
 /**
- * Get information about the node.
+ * Create a temporary tag that protects a blob from garbage collection.
+ *
+ * Use this to protect content during a multi-step operation, such as
+ * building a collection, until a permanent tag is set. The protection
+ * lasts until the tag is dropped or the node shuts down.
  *
  * # Safety
  * - `handle` must be a valid node handle
+ * - `hash_str` must be a valid null-terminated hex hash string
  * - `callback` must have valid function pointers
  */
-void iroh_node_info(const struct IrohNodeHandle *handle, struct IrohNodeInfoCallback callback);
+void iroh_blob_temp_tag_create(const struct IrohNodeHandle *handle,
+                               const char *hashStr,
+                               enum IrohBlobFormat format,
+                               struct IrohTempTagCallback callback);
 
 /**
- * Validate and parse a ticket string.
+ * Drop a temporary tag, allowing its content to be garbage collected.
  *
- * This function always succeeds - check `info.is_valid` for the result.
+ * Content that has since been given a permanent tag stays protected.
  *
  * # Safety
- * - `ticket` must be a valid null-terminated UTF-8 string (or null)
- * - `callback` must have valid function pointers
+ * - `tag` must be a handle returned by `iroh_blob_temp_tag_create`, or null
+ * - `tag` must not be used after this call
  */
-void iroh_validate_ticket(const char *ticket, struct IrohTicketValidateCallback callback);
+void iroh_blob_temp_tag_drop(struct IrohTempTagHandle *tag);
 
 /**
- * Explicitly close a node and free its resources asynchronously.
+ * Report how much of a tag's content is shared with other references.
  *
- * This is preferred over `iroh_node_destroy` when you need to await
- * graceful shutdown completion.
+ * `unique_size` is the space that garbage collection would reclaim after
+ * deleting the tag. Other tags, temporary tags and document entries all
+ * keep content.
  *
  * # Safety
- * - `handle` must be a valid pointer returned by `iroh_node_create`
- * - `handle` must not be used after this call
+ * - `handle` must be a valid node handle
+ * - `tag_name` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_node_close(struct IrohNodeHandle *handle, struct IrohCloseCallback callback);
+void iroh_blob_reference_report(const struct IrohNodeHandle *handle,
+                                const char *tagName,
+                                struct IrohReferenceReportCallback callback);
 
 /**
- * Add bytes to the blob store with options (e.g., timeout).
+ * Report how much of a collection's content is shared with other references.
+ *
+ * Counts the collection and its children. Tags naming the collection
+ * itself are treated as deleted with it.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `bytes.data` must point to valid memory for `bytes.len` bytes
+ * - `hash_str` must be a valid null-terminated hex hash string
  * - `callback` must have valid function pointers
  */
-void iroh_put_with_options(const struct IrohNodeHandle *handle,
-                           struct IrohBytes bytes,
-                           struct IrohOperationOptions options,
-                           struct IrohCallback callback);
+void iroh_blob_collection_reference_report(const struct IrohNodeHandle *handle,
+                                           const char *hashStr,
+                                           struct IrohReferenceReportCallback callback);
 
 /**
- * Download bytes from a ticket with options (e.g., timeout).
+ * Report identical content referenced by several tags or collections.
+ *
+ * Lists at most `limit` duplicates, those saving the most space first,
+ * followed by store-wide totals. Only exact hash matches are counted.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `ticket` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_get_with_options(const struct IrohNodeHandle *handle,
-                           const char *ticket,
-                           struct IrohOperationOptions options,
-                           struct IrohGetCallback callback);
+void iroh_blob_dedup_report(const struct IrohNodeHandle *handle,
+                            uintptr_t limit,
+                            struct IrohDedupReportCallback callback);
 
 /**
- * Create a new random author keypair.
+ * List tags whose names start with `prefix`, with their stored sizes.
  *
- * The secret key should be stored securely (e.g., in iOS Keychain).
- * The ID is derived from the secret and can be stored openly.
+ * An empty prefix lists all tags.
  *
  * # Safety
+ * - `handle` must be a valid node handle
+ * - `prefix` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_author_create(struct IrohAuthorCreateCallback callback);
+void iroh_blob_tag_list(const struct IrohNodeHandle *handle,
+                        const char *prefix,
+                        struct IrohTagListCallback callback);
 
 /**
- * Get the author ID from a secret key.
+ * List blobs in the local store, sorted and paged.
  *
- * This is a pure computation - no node required.
- * Useful for deriving the ID after loading secret from Keychain.
+ * A `cursor` of 0 starts a new listing: the store is read into a sorted
+ * snapshot and `on_complete` reports the cursor for it. Passing that
+ * cursor cuts later pages from the same snapshot, in its order, so each
+ * caller pages through its own unchanged listing; start a new listing to
+ * see changes. Only the latest 16 listings are kept; older cursors fail.
+ * Add times are recorded for the blobs that puts return tickets for and
+ * content that arrives by download, gateway or document sync; other
+ * blobs report 0 and sort last for the time-based orders.
  *
  * # Safety
- * - `secret` must contain valid author secret bytes
+ * - `handle` must be a valid node handle
+ * - `callback` must have valid function pointers
  */
-struct IrohAuthorId iroh_author_id_from_secret(struct IrohAuthorSecret secret);
+void iroh_blob_list(const struct IrohNodeHandle *handle,
+                    enum IrohBlobSort sort,
+                    uint64_t cursor,
+                    uint64_t offset,
+                    uint64_t limit,
+                    struct IrohBlobListCallback callback);
 
 /**
- * Import an author from a hex-encoded secret key.
+ * Add bytes to the blob store for a profile and get a shareable ticket.
  *
- * Useful for debugging or cross-device sync.
+ * Profiles partition the store between user accounts on one device. The
+ * content is tagged `auto/<hash>` in the profile rather than getting an
+ * automatic tag, so only the profile keeps it. Profile IDs consist of
+ * ASCII letters, digits, `-` and `_`, at most 64 bytes.
  *
  * # Safety
- * - `secret_hex` must be a valid null-terminated UTF-8 string containing 64 hex chars
+ * - `handle` must be a valid node handle
+ * - `profile` must be a valid null-terminated UTF-8 string
+ * - `bytes.data` must point to valid memory for `bytes.len` bytes
  * - `callback` must have valid function pointers
  */
-void iroh_author_from_hex(const char *secretHex, struct IrohAuthorCreateCallback callback);
+void iroh_profile_put(const struct IrohNodeHandle *handle,
+                      const char *profile,
+                      struct IrohBytes bytes,
+                      struct IrohCallback callback);
 
 /**
- * Export an author secret as a hex string.
+ * Tag a blob in a profile, replacing any profile tag of the same name.
  *
- * Useful for debugging or backup.
+ * The tag is named `profiles/<profile>/<tag_name>` in the store.
  *
  * # Safety
- * - The returned string must be freed with `iroh_string_free`
+ * - `handle` must be a valid node handle
+ * - `profile` and `tag_name` must be valid null-terminated UTF-8 strings
+ * - `hash_str` must be a valid null-terminated hex hash string
+ * - `callback` must have valid function pointers
  */
-char *iroh_author_secret_to_hex(struct IrohAuthorSecret secret);
+void iroh_profile_tag_set(const struct IrohNodeHandle *handle,
+                          const char *profile,
+                          const char *tagName,
+                          const char *hashStr,
+                          enum IrohBlobFormat format,
+                          struct IrohCloseCallback callback);
 
 /**
- * Export an author ID as a hex string.
+ * Remove a tag from a profile.
+ *
+ * Content the profile no longer tags stays stored until
+ * `iroh_profile_gc` reclaims it.
  *
  * # Safety
- * - The returned string must be freed with `iroh_string_free`
+ * - `handle` must be a valid node handle
+ * - `profile` and `tag_name` must be valid null-terminated UTF-8 strings
+ * - `callback` must have valid function pointers
  */
-char *iroh_author_id_to_hex(struct IrohAuthorId id);
+void iroh_profile_tag_delete(const struct IrohNodeHandle *handle,
+                             const char *profile,
+                             const char *tagName,
+                             struct IrohCloseCallback callback);
 
 /**
- * Import an author into the docs engine.
+ * List a profile's tags with their stored sizes.
  *
- * This must be called before using an author to sign document entries.
- * The author is registered with the docs engine so it can sign entries.
+ * Names are given without the `profiles/<profile>/` prefix.
  *
  * # Safety
- * - `handle` must be a valid node handle with docs enabled
+ * - `handle` must be a valid node handle
+ * - `profile` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_author_import(const struct IrohNodeHandle *handle,
-                        struct IrohAuthorSecret authorSecret,
-                        struct IrohCloseCallback callback);
+void iroh_profile_tag_list(const struct IrohNodeHandle *handle,
+                           const char *profile,
+                           struct IrohTagListCallback callback);
 
 /**
- * Create a new document.
+ * Count a profile's content and how much of it no other tag shares.
  *
  * # Safety
- * - `handle` must be a valid node handle with docs enabled
+ * - `handle` must be a valid node handle
+ * - `profile` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_doc_create(const struct IrohNodeHandle *handle, struct IrohDocCreateCallback callback);
+void iroh_profile_stats(const struct IrohNodeHandle *handle,
+                        const char *profile,
+                        struct IrohProfileStatsCallback callback);
 
 /**
- * Join an existing document via ticket.
+ * Delete content added to a profile that no tag references anymore.
+ *
+ * Content still used by other profiles, other tags, documents or
+ * temporary tags is kept. Returns after the store's next garbage
+ * collection run, within about a second.
  *
  * # Safety
- * - `handle` must be a valid node handle with docs enabled
- * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `handle` must be a valid node handle
+ * - `profile` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_doc_join(const struct IrohNodeHandle *handle,
-                   const char *ticket,
-                   struct IrohDocCreateCallback callback);
+void iroh_profile_gc(const struct IrohNodeHandle *handle,
+                     const char *profile,
+                     struct IrohReclaimCallback callback);
 
 /**
- * Set a key-value pair in a document.
+ * Delete all of a profile's tags and the content only they referenced.
+ *
+ * Use this when a user account is removed from the device. Like
+ * `iroh_profile_gc`, content still used elsewhere is kept.
  *
  * # Safety
- * - `doc_handle` must be a valid document handle
- * - `key.data` must point to valid memory for `key.len` bytes
- * - `value.data` must point to valid memory for `value.len` bytes
+ * - `handle` must be a valid node handle
+ * - `profile` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_doc_set(const struct IrohDocHandle *docHandle,
-                  struct IrohAuthorSecret authorSecret,
-                  struct IrohBytes key,
-                  struct IrohBytes value,
-                  struct IrohDocSetCallback callback);
+void iroh_profile_wipe(const struct IrohNodeHandle *handle,
+                       const char *profile,
+                       struct IrohReclaimCallback callback);
 
 /**
- * Get the latest entry for a key.
+ * Export a local blob to a crate-managed temporary file.
+ *
+ * The file is created under the node's temp dir (see `IrohNodeConfig`)
+ * and deleted when the returned handle is released. Use this to hand
+ * content to APIs that need a file URL, such as QuickLook.
  *
  * # Safety
- * - `doc_handle` must be a valid document handle
- * - `key.data` must point to valid memory for `key.len` bytes
+ * - `handle` must be a valid node handle
+ * - `hash_str` must be a valid null-terminated hex hash string
+ * - `filename` must be a valid null-terminated UTF-8 string or null (uses the hash)
  * - `callback` must have valid function pointers
  */
-void iroh_doc_get(const struct IrohDocHandle *docHandle,
-                  struct IrohBytes key,
-                  struct IrohDocGetCallback callback);
+void iroh_blob_export_temp(const struct IrohNodeHandle *handle,
+                           const char *hashStr,
+                           const char *filename,
+                           struct IrohTempFileCallback callback);
 
 /**
- * Get entries by key prefix.
- *
- * This streams entries back via the callback - on_entry is called for each
- * entry, then on_complete when done.
+ * Release a temporary file, deleting it from disk.
  *
  * # Safety
- * - `doc_handle` must be a valid document handle
- * - `prefix.data` must point to valid memory for `prefix.len` bytes
- * - `callback` must have valid function pointers
+ * - `file` must be a handle returned by `iroh_blob_export_temp`, or null
+ * - `file` must not be used after this call
  */
-void iroh_doc_get_many(const struct IrohDocHandle *docHandle,
-                       struct IrohBytes prefix,
-                       struct IrohDocGetManyCallback callback);
+void iroh_temp_file_release(struct IrohTempFileHandle *file);
 
 /**
- * Delete an entry (creates a tombstone).
+ * Copy local blobs into another store directory.
+ *
+ * The destination is opened for the duration of the call, so it must not
+ * be in use by another node. Every chunk is verified against its hash on
+ * import, and each copied blob is tagged in the destination so it
+ * survives garbage collection there.
  *
  * # Safety
- * - `doc_handle` must be a valid document handle
- * - `key.data` must point to valid memory for `key.len` bytes
+ * - `src_handle` must be a valid node handle
+ * - `dst_path` must be a valid null-terminated UTF-8 string
+ * - `hashes` must point to `hashes_len` valid null-terminated hex hash strings
  * - `callback` must have valid function pointers
  */
-void iroh_doc_del(const struct IrohDocHandle *docHandle,
-                  struct IrohAuthorSecret authorSecret,
-                  struct IrohBytes key,
-                  struct IrohDocDelCallback callback);
+void iroh_store_copy(const struct IrohNodeHandle *srcHandle,
+                     const char *dstPath,
+                     const char *const *hashes,
+                     uintptr_t hashesLen,
+                     struct IrohStoreCopyCallback callback);
 
+#if defined(IROH_FEATURE_CHUNKING)
 /**
- * Read content bytes by hash.
+ * Import a file split into content-defined chunks and get a shareable ticket.
  *
- * This fetches the actual content data for an entry (entries only contain the hash).
+ * The ticket has the `HashSeq` format. Chunks already stored from an
+ * earlier version of the file are reused, so peers holding that version
+ * only download the changed chunks with `iroh_get_chunked_file`.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `content_hash` must be a valid null-terminated UTF-8 hex string
+ * - `path` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_doc_read_content(const struct IrohNodeHandle *handle,
-                           const char *contentHash,
-                           struct IrohGetCallback callback);
+void iroh_put_chunked_file(const struct IrohNodeHandle *handle,
+                           const char *path,
+                           struct IrohChunkedPutCallback callback);
+#endif
 
+#if defined(IROH_FEATURE_CHUNKING)
 /**
- * Get a share ticket for a document.
+ * Download a chunked file ticket and write the file to `dst_path`.
+ *
+ * Only chunks missing from the local store are downloaded. The file is
+ * written next to `dst_path` and moved into place once complete.
  *
  * # Safety
- * - `doc_handle` must be a valid document handle
+ * - `handle` must be a valid node handle
+ * - `ticket` and `dst_path` must be valid null-terminated UTF-8 strings
  * - `callback` must have valid function pointers
  */
-void iroh_doc_share(const struct IrohDocHandle *docHandle,
-                    enum IrohDocShareMode mode,
-                    struct IrohCallback callback);
+void iroh_get_chunked_file(const struct IrohNodeHandle *handle,
+                           const char *ticket,
+                           const char *dstPath,
+                           struct IrohChunkedGetCallback callback);
+#endif
 
 /**
- * Close a document and free its resources.
+ * Add bytes with a metadata record and get a shareable ticket.
+ *
+ * The content and metadata are wrapped in a collection, so the returned
+ * ticket has the `HashSeq` format. Pass null for absent string fields and
+ * 0 for `created_at` to use the current time. With `Zstd` compression the
+ * content is stored compressed if that makes it smaller, and
+ * `iroh_get_with_metadata` returns it decompressed.
  *
  * # Safety
- * - `doc_handle` must be a valid document handle returned by `iroh_doc_create` or `iroh_doc_join`
- * - `doc_handle` must not be used after this call
+ * - `handle` must be a valid node handle
+ * - `bytes.data` must point to valid memory for `bytes.len` bytes
+ * - `filename` and `content_type` must be valid null-terminated UTF-8 strings or null
+ * - `callback` must have valid function pointers
  */
-void iroh_doc_close(struct IrohDocHandle *docHandle);
+void iroh_put_with_metadata(const struct IrohNodeHandle *handle,
+                            struct IrohBytes bytes,
+                            const char *filename,
+                            const char *contentType,
+                            uint64_t createdAt,
+                            enum IrohCompression compression,
+                            struct IrohCallback callback);
 
 /**
- * Free a document entry.
+ * Download bytes from a ticket along with its metadata record.
+ *
+ * Raw tickets succeed with a null metadata pointer.
  *
  * # Safety
- * - `entry` must be a valid entry pointer returned by document operations
- * - `entry` must not be used after this call
+ * - `handle` must be a valid node handle
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
  */
-void iroh_doc_entry_free(struct IrohDocEntry *entry);
+void iroh_get_with_metadata(const struct IrohNodeHandle *handle,
+                            const char *ticket,
+                            struct IrohGetWithMetadataCallback callback);
 
 /**
- * Subscribe to document events.
+ * Fetch the metadata record for a ticket without downloading its content.
  *
- * Returns a subscription handle that can be used to cancel the subscription.
- * Events are delivered via the callback until the subscription is cancelled
- * or the stream ends.
+ * Succeeds with a null pointer for raw tickets and collections that have
+ * no metadata record.
  *
  * # Safety
- * - `doc_handle` must be a valid document handle
- * - `callback` must have valid function pointers that remain valid for the
- *   duration of the subscription
+ * - `handle` must be a valid node handle
+ * - `ticket` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
  */
-struct IrohSubscriptionHandle *iroh_doc_subscribe(const struct IrohDocHandle *docHandle,
-                                                  struct IrohDocSubscribeCallback callback);
+void iroh_ticket_metadata(const struct IrohNodeHandle *handle,
+                          const char *ticket,
+                          struct IrohBlobMetadataCallback callback);
 
 /**
- * Cancel an active subscription.
- *
- * After calling this, no more events will be delivered and on_complete will be called.
+ * Free a metadata record returned by Iroh functions.
  *
  * # Safety
- * - `handle` must be a valid subscription handle returned by `iroh_doc_subscribe`
- * - `handle` must not be used after this call
+ * - `metadata` must be a pointer returned by a metadata operation, or null
+ * - `metadata` must not be used after this call
  */
-void iroh_subscription_cancel(struct IrohSubscriptionHandle *handle);
+void iroh_blob_metadata_free(struct IrohBlobMetadata *metadata);
 
 /**
- * Free a document event.
+ * Report the pending work recorded in the store at `storage_path`.
+ *
+ * Reads the record a node keeps next to its store of document writes no
+ * peer has synced and downloads that have not finished. No store is opened
+ * and no endpoint is bound, so a silent push handler can call this before
+ * deciding whether to start a node at all. The record is updated a moment
+ * after changes, so a node that was killed may have lost its latest ones.
  *
  * # Safety
- * - `event` fields that are non-null must be valid pointers
+ * - `storage_path` must be a valid null-terminated UTF-8 string
+ * - `callback` must have valid function pointers
  */
-void iroh_doc_event_free(struct IrohDocEvent event);
+void iroh_pending_work(const char *storagePath, struct IrohPendingWorkCallback callback);
 
 /**
- * Tag (pin) a blob to prevent garbage collection.
+ * Report the pending work of a running node.
  *
- * Tagged blobs are protected from GC until the tag is removed.
- * Use this after downloading content you want to keep.
+ * Reads counters the node keeps up to date, so it never scans the stores
+ * or dials peers. Use `iroh_pending_work` when no node is running.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `tag_name` must be a valid null-terminated UTF-8 string
- * - `hash_str` must be a valid null-terminated hex hash string
  * - `callback` must have valid function pointers
  */
-void iroh_blob_tag_set(const struct IrohNodeHandle *handle,
-                       const char *tagName,
-                       const char *hashStr,
-                       enum IrohBlobFormat format,
-                       struct IrohCloseCallback callback);
+void iroh_node_pending_work(const struct IrohNodeHandle *handle,
+                            struct IrohPendingWorkCallback callback);
 
 /**
- * Create a shareable ticket for an existing local blob.
+ * Report what the node is holding on to: the size of the store's
+ * databases, live runtime tasks, buffered events and callbacks, and open
+ * peer connections.
  *
- * The ticket points to this node as the provider.
- * Use this to "mint" a bootstrap ticket after downloading content.
+ * Only reads counters and file sizes, so it can be called whenever the
+ * app sees a memory spike to tell whether the P2P layer is behind it.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `hash_str` must be a valid null-terminated hex hash string
  * - `callback` must have valid function pointers
  */
-void iroh_blob_ticket_create(const struct IrohNodeHandle *handle,
-                             const char *hashStr,
-                             enum IrohBlobFormat format,
-                             struct IrohCallback callback);
+void iroh_node_memory_report(const struct IrohNodeHandle *handle,
+                             struct IrohMemoryReportCallback callback);
 
 /**
- * Remove a tag (unpin) from a blob, allowing garbage collection.
+ * Run sync and download work for at most `budget_ms` milliseconds.
+ *
+ * Designed for short background windows (e.g., `BGAppRefreshTask`):
+ * unfinished downloads are resumed, docs are synced with their known peers
+ * and missing content is fetched until the budget runs out, after which any
+ * sync started by this call is stopped. The docs store and the pending work
+ * record are flushed before the callback runs; `completed` reports whether
+ * everything finished in time and no pending work is left.
  *
  * # Safety
  * - `handle` must be a valid node handle
- * - `tag_name` must be a valid null-terminated UTF-8 string
  * - `callback` must have valid function pointers
  */
-void iroh_blob_tag_delete(const struct IrohNodeHandle *handle,
-                          const char *tagName,
-                          struct IrohCloseCallback callback);
+void iroh_node_sync_step(const struct IrohNodeHandle *handle,
+                         uint64_t budgetMs,
+                         struct IrohSyncStepCallback callback);
 
 #endif  /* IROH_SWIFT_H */
@@ -0,0 +1,175 @@
+//! Which peers may fetch blobs from this node.
+//!
+//! By default anyone who knows a hash can download it from us. The access
+//! list wraps the blobs protocol handler and turns away connections from
+//! peers that are denied, or, in allow-list mode, not explicitly allowed.
+//! Rejected attempts are published as [`NodeEvent::FetchRejected`].
+//!
+//! Serving can also be paused for everyone, for example on low battery,
+//! which closes blob connections in progress and turns away new ones until
+//! it resumes. Downloads from other nodes and local reads are unaffected.
+//!
+//! Rules only apply to the blobs protocol and are not persisted; apps
+//! restore them after creating the node.
+
+use crate::events::{EventBus, NodeEvent};
+use iroh::EndpointId;
+use iroh::endpoint::{Connection, VarInt};
+use iroh::protocol::{AcceptError, ProtocolHandler};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::sync::watch;
+
+/// QUIC application error code sent to peers that may not fetch blobs.
+const CLOSED_NOT_ALLOWED: u32 = 3;
+
+/// QUIC application error code sent to peers while serving is paused.
+const CLOSED_PAUSED: u32 = 4;
+
+/// Who may fetch blobs when a peer has no rule of its own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccessMode {
+    /// Every peer that is not denied.
+    #[default]
+    Open,
+    /// Only peers that are allowed.
+    AllowList,
+}
+
+/// Rule for a single peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessRule {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Default)]
+struct Rules {
+    mode: AccessMode,
+    peers: HashMap<EndpointId, AccessRule>,
+}
+
+/// Shared, mutable access rules for the blobs protocol.
+#[derive(Debug, Clone)]
+pub struct AccessList {
+    rules: Arc<RwLock<Rules>>,
+    /// Whether blobs are served at all.
+    serving: Arc<watch::Sender<bool>>,
+}
+
+impl AccessList {
+    /// Start serving with `mode` and no peer rules.
+    pub fn new(mode: AccessMode) -> Self {
+        Self {
+            rules: Arc::new(RwLock::new(Rules {
+                mode,
+                ..Default::default()
+            })),
+            serving: Arc::new(watch::Sender::new(true)),
+        }
+    }
+
+    /// Whether blobs are served to peers.
+    pub fn serving(&self) -> bool {
+        *self.serving.borrow()
+    }
+
+    /// Resume or pause serving blobs; pausing closes connections in progress.
+    pub fn set_serving(&self, serving: bool) {
+        self.serving.send_replace(serving);
+    }
+
+    /// Current mode.
+    pub fn mode(&self) -> AccessMode {
+        self.rules.read().unwrap().mode
+    }
+
+    /// Change who may fetch when a peer has no rule.
+    pub fn set_mode(&self, mode: AccessMode) {
+        self.rules.write().unwrap().mode = mode;
+    }
+
+    /// Set or, with None, remove the rule for `peer`.
+    pub fn set(&self, peer: EndpointId, rule: Option<AccessRule>) {
+        let mut rules = self.rules.write().unwrap();
+        match rule {
+            Some(rule) => rules.peers.insert(peer, rule),
+            None => rules.peers.remove(&peer),
+        };
+    }
+
+    /// Every peer rule, sorted by peer.
+    pub fn rules(&self) -> Vec<(EndpointId, AccessRule)> {
+        let mut rules: Vec<_> = self
+            .rules
+            .read()
+            .unwrap()
+            .peers
+            .iter()
+            .map(|(peer, rule)| (*peer, *rule))
+            .collect();
+        rules.sort_by_key(|(peer, _)| *peer);
+        rules
+    }
+
+    /// Whether `peer` may fetch blobs.
+    pub fn permits(&self, peer: EndpointId) -> bool {
+        let rules = self.rules.read().unwrap();
+        match rules.peers.get(&peer) {
+            Some(rule) => *rule == AccessRule::Allow,
+            None => rules.mode == AccessMode::Open,
+        }
+    }
+
+    /// Wrap `handler` so it only serves permitted peers.
+    pub fn guard<P: ProtocolHandler>(&self, handler: P, events: EventBus) -> Guarded<P> {
+        Guarded {
+            inner: handler,
+            access: self.clone(),
+            events,
+        }
+    }
+}
+
+/// Protocol handler that turns away peers the access list does not permit.
+#[derive(Debug)]
+pub struct Guarded<P> {
+    inner: P,
+    access: AccessList,
+    events: EventBus,
+}
+
+impl<P: ProtocolHandler> ProtocolHandler for Guarded<P> {
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        let peer = connection.remote_id();
+        let mut serving = self.access.serving.subscribe();
+        if !*serving.borrow_and_update() {
+            connection.close(VarInt::from_u32(CLOSED_PAUSED), b"paused");
+            return Err(paused());
+        }
+        if !self.access.permits(peer) {
+            self.events.emit(NodeEvent::FetchRejected { peer });
+            connection.close(VarInt::from_u32(CLOSED_NOT_ALLOWED), b"not allowed");
+            return Err(AcceptError::from_err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("peer {} may not fetch blobs", peer.fmt_short()),
+            )));
+        }
+        let closer = connection.clone();
+        tokio::select! {
+            result = self.inner.accept(connection) => result,
+            _ = serving.wait_for(|serving| !serving) => {
+                closer.close(VarInt::from_u32(CLOSED_PAUSED), b"paused");
+                Err(paused())
+            }
+        }
+    }
+
+    async fn shutdown(&self) {
+        self.inner.shutdown().await
+    }
+}
+
+fn paused() -> AcceptError {
+    AcceptError::from_err(std::io::Error::other("serving blobs is paused"))
+}
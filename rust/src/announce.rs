@@ -0,0 +1,100 @@
+//! Periodic re-announcement of selected content to discovery.
+//!
+//! Discovery records expire and go stale when the node's addresses change,
+//! and tickets handed out earlier keep pointing at old addresses, so content
+//! shared long ago slowly stops being found. The announcer republishes this
+//! node's discovery records on an interval and whenever connectivity
+//! changes, and hands the app a fresh ticket for every tag under a prefix,
+//! for example to store next to its own discovery records. Each round is
+//! published as [`NodeEvent::ContentAnnounced`].
+
+use crate::events::{EventBus, NodeEvent};
+use anyhow::Result;
+use futures_lite::StreamExt;
+use iroh::Endpoint;
+use iroh::discovery::{Discovery, UserData};
+use iroh::endpoint_info::EndpointData;
+use iroh_blobs::api::Store;
+use iroh_blobs::ticket::BlobTicket;
+use std::time::Duration;
+
+/// Called with a tag's name and a ticket for its content at the current addresses.
+pub type OnAnnounce = dyn Fn(&str, &BlobTicket) + Send + Sync;
+
+/// Which content to announce and how often.
+pub struct ContentAnnouncer {
+    /// Time between rounds.
+    pub interval: Duration,
+    /// Prefix of the names of the tags whose content is announced; empty
+    /// for every tag.
+    pub tag_prefix: Vec<u8>,
+    /// Told about each announced tag, if set.
+    pub on_announce: Option<Box<OnAnnounce>>,
+}
+
+impl std::fmt::Debug for ContentAnnouncer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContentAnnouncer")
+            .field("interval", &self.interval)
+            .field("tag_prefix", &String::from_utf8_lossy(&self.tag_prefix))
+            .finish()
+    }
+}
+
+impl ContentAnnouncer {
+    /// Announce every interval and after connectivity changes until the
+    /// runtime stops.
+    ///
+    /// The first round runs right away. `user_data` is published with the
+    /// node's discovery records, like the endpoint does itself.
+    pub async fn run(
+        self,
+        endpoint: Endpoint,
+        store: Store,
+        user_data: Option<UserData>,
+        events: EventBus,
+    ) {
+        let mut node_events = events.subscribe();
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                event = node_events.next() => match event {
+                    // The node is shutting down
+                    None => return,
+                    Some(NodeEvent::Connectivity { .. }) => ticker.reset(),
+                    Some(_) => continue,
+                },
+            }
+            // Failures are retried on the next round
+            if let Ok(tags) = self.announce(&endpoint, &store, user_data.clone()).await {
+                events.emit(NodeEvent::ContentAnnounced { tags });
+            }
+        }
+    }
+
+    /// Run one round, returning the number of tags announced.
+    async fn announce(
+        &self,
+        endpoint: &Endpoint,
+        store: &Store,
+        user_data: Option<UserData>,
+    ) -> Result<u64> {
+        let addr = endpoint.addr();
+        let data = EndpointData::new(addr.addrs.clone()).with_user_data(user_data);
+        endpoint.discovery().publish(&data);
+
+        let mut tags = store.tags().list_prefix(&self.tag_prefix).await?;
+        let mut announced = 0;
+        while let Some(tag) = tags.next().await {
+            let tag = tag?;
+            if let Some(on_announce) = &self.on_announce {
+                let ticket = BlobTicket::new(addr.clone(), tag.hash, tag.format);
+                on_announce(&String::from_utf8_lossy(tag.name.as_ref()), &ticket);
+            }
+            announced += 1;
+        }
+        Ok(announced)
+    }
+}
@@ -0,0 +1,232 @@
+//! Node-wide caps on transfer bandwidth.
+//!
+//! Syncing over a metered connection should not eat the user's data plan,
+//! so upload and download caps can be set separately for Wi-Fi and
+//! cellular. The app tells the node which kind of network it is on, and
+//! the caps of that network apply from then on.
+//!
+//! Uploads are paced through the blobs protocol's throttle events, like
+//! per-peer bandwidth limits, so they cover everything served to peers,
+//! document content included. Downloads the node starts itself are paced
+//! by reading the downloader's progress no faster than the cap allows; the
+//! downloader waits for its progress to be read, so it stops reading from
+//! the connection and QUIC flow control holds the provider back. Fetches
+//! over a connection the node already holds are paced the same way.
+//!
+//! The docs engine drives its own downloader and reports no progress, so
+//! document content it fetches in the background while syncing is not held
+//! to the download cap, only to the sending peer's upload cap.
+
+use futures_lite::StreamExt;
+use iroh_blobs::api::downloader::{DownloadProgress, DownloadProgressItem};
+use iroh_blobs::api::remote::{GetProgress, GetProgressItem};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Kind of network the device is on, as reported by the app.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetworkKind {
+    /// Wi-Fi, Ethernet or another unmetered network.
+    #[default]
+    Wifi,
+    /// A cellular, usually metered, network.
+    Cellular,
+}
+
+/// Bandwidth caps on one kind of network. None means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BandwidthCaps {
+    /// Bytes per second sent to peers across all transfers.
+    pub upload_bytes_per_sec: Option<u64>,
+    /// Bytes per second received by downloads the node starts, across all
+    /// of them. Document content synced in the background is not capped.
+    pub download_bytes_per_sec: Option<u64>,
+}
+
+/// Bandwidth caps for each kind of network.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BandwidthLimits {
+    pub wifi: BandwidthCaps,
+    pub cellular: BandwidthCaps,
+}
+
+impl BandwidthLimits {
+    /// Whether no cap is set on any network.
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether uploads are capped on any network.
+    pub fn caps_uploads(&self) -> bool {
+        self.wifi.upload_bytes_per_sec.is_some() || self.cellular.upload_bytes_per_sec.is_some()
+    }
+
+    fn caps(&self, network: NetworkKind) -> BandwidthCaps {
+        match network {
+            NetworkKind::Wifi => self.wifi,
+            NetworkKind::Cellular => self.cellular,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    limits: BandwidthLimits,
+    network: NetworkKind,
+    /// When the next uploaded bytes may be sent.
+    next_upload: Instant,
+    /// When the next downloaded bytes may be read.
+    next_download: Instant,
+}
+
+/// Paces the node's transfers to the caps of the current network.
+#[derive(Debug, Clone)]
+pub struct Bandwidth {
+    state: Arc<Mutex<State>>,
+}
+
+impl Bandwidth {
+    pub fn new(limits: BandwidthLimits) -> Self {
+        let now = Instant::now();
+        Self {
+            state: Arc::new(Mutex::new(State {
+                limits,
+                network: NetworkKind::default(),
+                next_upload: now,
+                next_download: now,
+            })),
+        }
+    }
+
+    /// The network the node is on.
+    pub fn network(&self) -> NetworkKind {
+        self.state.lock().unwrap().network
+    }
+
+    /// Apply the caps of `network` from now on.
+    ///
+    /// Pacing owed under the previous network's caps is forgiven.
+    pub fn set_network(&self, network: NetworkKind) {
+        let mut state = self.state.lock().unwrap();
+        if state.network != network {
+            let now = Instant::now();
+            state.network = network;
+            state.next_upload = now;
+            state.next_download = now;
+        }
+    }
+
+    /// When `size` bytes may be sent to a peer.
+    pub fn schedule_upload(&self, size: u64, now: Instant) -> Instant {
+        let mut state = self.state.lock().unwrap();
+        let rate = state.limits.caps(state.network).upload_bytes_per_sec;
+        schedule(&mut state.next_upload, rate, size, now)
+    }
+
+    /// When the download that received `size` bytes may read more.
+    fn schedule_download(&self, size: u64, now: Instant) -> Instant {
+        let mut state = self.state.lock().unwrap();
+        let rate = state.limits.caps(state.network).download_bytes_per_sec;
+        schedule(&mut state.next_download, rate, size, now)
+    }
+
+    /// Wait until a download that received `size` bytes may read more.
+    pub async fn pace_download(&self, size: u64) {
+        let now = Instant::now();
+        let start = self.schedule_download(size, now);
+        if start > now {
+            tokio::time::sleep_until(start).await;
+        }
+    }
+
+    /// Run `download` to completion, paced to the download cap.
+    pub async fn paced(&self, download: DownloadProgress) -> anyhow::Result<()> {
+        let mut stream = download.stream().await?;
+        let mut received = 0;
+        while let Some(item) = stream.next().await {
+            match item {
+                DownloadProgressItem::Progress(bytes) => {
+                    // Progress restarts when a provider fails
+                    self.pace_download(bytes.saturating_sub(received)).await;
+                    received = bytes;
+                }
+                DownloadProgressItem::Error(e) => return Err(e),
+                DownloadProgressItem::DownloadError => anyhow::bail!("Download error"),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Run a fetch over an open connection to completion, paced to the
+    /// download cap.
+    pub async fn paced_get(&self, get: GetProgress) -> anyhow::Result<()> {
+        let mut stream = std::pin::pin!(get.stream());
+        let mut received = 0;
+        while let Some(item) = stream.next().await {
+            match item {
+                GetProgressItem::Progress(bytes) => {
+                    self.pace_download(bytes.saturating_sub(received)).await;
+                    received = bytes;
+                }
+                GetProgressItem::Done(_) => return Ok(()),
+                GetProgressItem::Error(e) => return Err(e.into()),
+            }
+        }
+        anyhow::bail!("Fetch ended before completing")
+    }
+}
+
+/// Book `size` bytes at `rate` after `next`, returning when they may go.
+fn schedule(next: &mut Instant, rate: Option<u64>, size: u64, now: Instant) -> Instant {
+    let Some(rate) = rate.filter(|rate| *rate > 0) else {
+        return now;
+    };
+    let start = (*next).max(now);
+    *next = start + Duration::from_secs_f64(size as f64 / rate as f64);
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caps_follow_network() {
+        let bandwidth = Bandwidth::new(BandwidthLimits {
+            wifi: BandwidthCaps {
+                upload_bytes_per_sec: None,
+                download_bytes_per_sec: Some(1000),
+            },
+            cellular: BandwidthCaps {
+                upload_bytes_per_sec: Some(100),
+                download_bytes_per_sec: Some(100),
+            },
+        });
+        let now = Instant::now();
+
+        // Uploads are not capped on Wi-Fi
+        assert_eq!(bandwidth.schedule_upload(1000, now), now);
+        assert_eq!(bandwidth.schedule_upload(1000, now), now);
+        assert_eq!(bandwidth.schedule_download(500, now), now);
+        assert_eq!(
+            bandwidth.schedule_download(500, now),
+            now + Duration::from_millis(500)
+        );
+
+        bandwidth.set_network(NetworkKind::Cellular);
+        let now = Instant::now();
+        // Pacing owed on Wi-Fi is forgiven
+        assert_eq!(bandwidth.schedule_download(50, now), now);
+        assert_eq!(
+            bandwidth.schedule_download(50, now),
+            now + Duration::from_millis(500)
+        );
+        assert_eq!(bandwidth.schedule_upload(100, now), now);
+        assert_eq!(
+            bandwidth.schedule_upload(100, now),
+            now + Duration::from_secs(1)
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Companion CLI for testing the Swift app against a second device.
+//!
+//! Uses the same node and FsStore format as the app, so QA can generate
+//! tickets and act as the other side of a transfer or document on a single
+//! machine. Commands that share something keep serving it until interrupted.
+
+use anyhow::{Context, Result, bail};
+use futures_lite::StreamExt;
+use iroh_docs::DocTicket;
+use iroh_docs::api::Doc;
+use iroh_docs::api::protocol::{AddrInfoOptions, ShareMode};
+use iroh_docs::engine::LiveEvent;
+use iroh_docs::store::Query;
+use iroh_swift::{IrohNode, NodeOptions, Reachability};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long to wait for a blob provider to answer.
+const DIAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+const USAGE: &str = "\
+Usage: iroh-swift-cli [--store DIR] [--no-relay] <command>
+
+Commands:
+  put <FILE>                      Store a file, print its ticket and serve it
+  get <TICKET> [OUT]              Download a blob to OUT or stdout
+  doc-create                      Create a document, print a write ticket and serve it
+  doc-join <TICKET>               Join a document and print entries as they sync
+  doc-set <TICKET> <KEY> <VALUE>  Join a document, write an entry and serve it
+
+Options:
+  --store DIR   Store directory (default: ./iroh-cli-store)
+  --no-relay    Connect directly only, without relay servers";
+
+fn main() {
+    if let Err(e) = run(std::env::args().skip(1).collect()) {
+        eprintln!("error: {e:#}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<()> {
+    let mut store = PathBuf::from("iroh-cli-store");
+    let mut relay_enabled = true;
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--store" => store = args.next().context("--store needs a directory")?.into(),
+            "--no-relay" => relay_enabled = false,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => rest.push(arg),
+        }
+    }
+    let rest: Vec<&str> = rest.iter().map(String::as_str).collect();
+    let Some((&command, params)) = rest.split_first() else {
+        bail!("missing command\n\n{USAGE}");
+    };
+
+    let node = IrohNode::with_options(
+        store,
+        NodeOptions {
+            relay_enabled,
+            docs_enabled: command.starts_with("doc-"),
+            ..Default::default()
+        },
+    )?;
+    eprintln!("node {}", node.endpoint().id());
+
+    match (command, params) {
+        ("put", [file]) => {
+            let data = std::fs::read(file).with_context(|| format!("Failed to read {file}"))?;
+            println!("{}", node.put(&data)?);
+            serve()
+        }
+        ("get", [ticket]) => {
+            dial(&node, ticket)?;
+            let data = node.get(ticket)?;
+            std::io::stdout().write_all(&data)?;
+            node.shutdown()
+        }
+        ("get", [ticket, out]) => {
+            dial(&node, ticket)?;
+            let data = node.get(ticket)?;
+            std::fs::write(out, &data).with_context(|| format!("Failed to write {out}"))?;
+            eprintln!("wrote {} bytes to {out}", data.len());
+            node.shutdown()
+        }
+        ("doc-create", []) => {
+            let docs = node.docs().context("Docs not enabled")?;
+            let ticket = node.runtime().block_on(async {
+                let doc = docs.api().create().await?;
+                doc.share(ShareMode::Write, AddrInfoOptions::RelayAndAddresses)
+                    .await
+            })?;
+            println!("{ticket}");
+            serve()
+        }
+        ("doc-join", [ticket]) => {
+            let doc = join(&node, ticket)?;
+            watch(&node, &doc)
+        }
+        ("doc-set", [ticket, key, value]) => {
+            let doc = join(&node, ticket)?;
+            let docs = node.docs().context("Docs not enabled")?;
+            node.runtime().block_on(async {
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, key.to_string(), value.to_string())
+                    .await
+            })?;
+            eprintln!("set {key}");
+            serve()
+        }
+        _ => bail!("invalid command or arguments\n\n{USAGE}"),
+    }
+}
+
+/// Connect to the provider of a blob ticket.
+///
+/// Dialing first uses the addresses in the ticket, so downloads work
+/// without discovery, e.g. between two local processes with `--no-relay`.
+fn dial(node: &IrohNode, ticket: &str) -> Result<()> {
+    match node.can_reach(ticket, Some(DIAL_TIMEOUT))? {
+        Reachability::Unreachable { reason } => bail!("Provider unreachable: {reason}"),
+        Reachability::Direct { latency } | Reachability::Relay { latency } => {
+            eprintln!("provider reached in {} ms", latency.as_millis());
+            Ok(())
+        }
+    }
+}
+
+/// Join the document shared by `ticket`.
+fn join(node: &IrohNode, ticket: &str) -> Result<Doc> {
+    let ticket: DocTicket = ticket.parse().context("Invalid doc ticket")?;
+    let docs = node.docs().context("Docs not enabled")?;
+    node.runtime().block_on(docs.api().import(ticket))
+}
+
+/// Print the document's entries, then new ones as they arrive.
+fn watch(node: &IrohNode, doc: &Doc) -> Result<()> {
+    node.runtime().block_on(async {
+        let mut events = doc.subscribe().await?;
+        let entries = doc.get_many(Query::all()).await?;
+        let mut entries = std::pin::pin!(entries);
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            print_entry(node, entry.key(), entry.content_hash()).await;
+        }
+
+        // Remote entries are printed once their content has arrived
+        let mut pending = HashMap::new();
+        while let Some(event) = events.next().await {
+            match event? {
+                LiveEvent::InsertRemote { entry, .. } => {
+                    pending.insert(entry.content_hash(), entry.key().to_vec());
+                    if node.store().blobs().has(entry.content_hash()).await? {
+                        pending.remove(&entry.content_hash());
+                        print_entry(node, entry.key(), entry.content_hash()).await;
+                    }
+                }
+                LiveEvent::ContentReady { hash } => {
+                    if let Some(key) = pending.remove(&hash) {
+                        print_entry(node, &key, hash).await;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Print `key=value`, or the content hash if the value is not local yet.
+async fn print_entry(node: &IrohNode, key: &[u8], hash: iroh_blobs::Hash) {
+    let key = String::from_utf8_lossy(key);
+    match node.store().get_bytes(hash).await {
+        Ok(value) => println!("{key}={}", String::from_utf8_lossy(&value)),
+        Err(_) => println!("{key}=<{hash}>"),
+    }
+}
+
+/// Keep the node serving until the process is interrupted.
+fn serve() -> Result<()> {
+    eprintln!("serving, press Ctrl-C to stop");
+    loop {
+        std::thread::park();
+    }
+}
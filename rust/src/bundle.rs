@@ -0,0 +1,206 @@
+//! Ticket bundles packing several tickets into one string.
+//!
+//! Sharing something often takes more than one ticket, like a profile
+//! document and its avatar blob, and users forward one string but lose the
+//! other. A bundle carries any number of blob and doc tickets, each with an
+//! optional label saying what it is for, in a single string.
+//!
+//! A bundle is `bundle` followed by lowercase base32 of a version byte (1),
+//! the number of tickets and then, for each ticket:
+//!
+//! | bytes | field |
+//! |-------|-------|
+//! | 1 | kind (0 = blob, 1 = doc) |
+//! | 1 | label length |
+//! | .. | UTF-8 label |
+//! | 2 | ticket length, big endian |
+//! | .. | ticket payload, the base32-decoded part after the kind prefix |
+
+use crate::ticket;
+use anyhow::{Context, Result, bail, ensure};
+use data_encoding::BASE32_NOPAD;
+
+/// Prefix that marks a ticket bundle.
+const BUNDLE_PREFIX: &str = "bundle";
+
+/// Format version written by [`encode`].
+const VERSION: u8 = 1;
+
+/// Kind of a ticket in a bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketKind {
+    Blob,
+    Doc,
+}
+
+impl TicketKind {
+    fn prefix(self) -> &'static str {
+        match self {
+            TicketKind::Blob => "blob",
+            TicketKind::Doc => "doc",
+        }
+    }
+}
+
+/// A ticket in a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundledTicket {
+    /// What the ticket is for, like "avatar"; empty if unlabeled.
+    pub label: String,
+    pub kind: TicketKind,
+    /// The ticket in its standard form.
+    pub ticket: String,
+}
+
+/// Pack `(label, ticket)` pairs into a bundle, keeping their order.
+///
+/// Tickets may be in any supported encoding. Labels are at most 255 bytes
+/// and a bundle holds at most 255 tickets.
+pub fn encode<'a>(tickets: impl IntoIterator<Item = (&'a str, &'a str)>) -> Result<String> {
+    let mut data = vec![VERSION, 0];
+    let mut count: u8 = 0;
+    for (label, ticket) in tickets {
+        count = count
+            .checked_add(1)
+            .context("a bundle holds at most 255 tickets")?;
+        let ticket = ticket::decode(ticket)?;
+        let (kind, payload) = if let Some(payload) = ticket.strip_prefix("blob") {
+            (TicketKind::Blob, payload)
+        } else if let Some(payload) = ticket.strip_prefix("doc") {
+            (TicketKind::Doc, payload)
+        } else {
+            bail!("not a blob or doc ticket");
+        };
+        let payload = BASE32_NOPAD
+            .decode(payload.to_ascii_uppercase().as_bytes())
+            .context("invalid ticket")?;
+        let label_len = u8::try_from(label.len()).context("label is longer than 255 bytes")?;
+        let payload_len = u16::try_from(payload.len()).context("ticket is too long")?;
+
+        data.push(kind as u8);
+        data.push(label_len);
+        data.extend_from_slice(label.as_bytes());
+        data.extend_from_slice(&payload_len.to_be_bytes());
+        data.extend_from_slice(&payload);
+    }
+    ensure!(count > 0, "a bundle needs at least one ticket");
+    data[1] = count;
+    Ok(format!(
+        "{BUNDLE_PREFIX}{}",
+        BASE32_NOPAD.encode(&data).to_ascii_lowercase()
+    ))
+}
+
+/// Unpack the tickets of a bundle produced by [`encode`], in order.
+pub fn decode(bundle: &str) -> Result<Vec<BundledTicket>> {
+    let payload = bundle
+        .trim()
+        .to_ascii_lowercase()
+        .strip_prefix(BUNDLE_PREFIX)
+        .context("not a ticket bundle")?
+        .to_ascii_uppercase();
+    let data = BASE32_NOPAD
+        .decode(payload.as_bytes())
+        .context("invalid ticket bundle")?;
+    let mut reader = Reader(&data);
+    let version = reader.take(1)?[0];
+    ensure!(
+        version == VERSION,
+        "unsupported ticket bundle version {version}"
+    );
+
+    let count = reader.take(1)?[0];
+    let mut tickets = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let kind = match reader.take(1)?[0] {
+            0 => TicketKind::Blob,
+            1 => TicketKind::Doc,
+            other => bail!("unknown ticket kind {other} in bundle"),
+        };
+        let label_len = reader.take(1)?[0] as usize;
+        let label = std::str::from_utf8(reader.take(label_len)?)
+            .context("bundle label is not text")?
+            .to_string();
+        let payload_len = u16::from_be_bytes(reader.take(2)?.try_into()?) as usize;
+        let payload = BASE32_NOPAD
+            .encode(reader.take(payload_len)?)
+            .to_ascii_lowercase();
+        let ticket = ticket::decode(&format!("{}{payload}", kind.prefix()))?;
+        tickets.push(BundledTicket {
+            label,
+            kind,
+            ticket,
+        });
+    }
+    ensure!(reader.0.is_empty(), "ticket bundle has trailing data");
+    Ok(tickets)
+}
+
+/// Reads fields off the front of a bundle.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        ensure!(self.0.len() >= len, "ticket bundle is truncated");
+        let (field, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::{EndpointAddr, SecretKey};
+    use iroh_blobs::ticket::BlobTicket;
+    use iroh_blobs::{BlobFormat, Hash};
+
+    fn sample_ticket(content: &[u8]) -> String {
+        let key = SecretKey::from_bytes(&[7u8; 32]);
+        let addr = EndpointAddr::new(key.public());
+        BlobTicket::new(addr, Hash::new(content), BlobFormat::Raw).to_string()
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let avatar = sample_ticket(b"avatar");
+        let banner = sample_ticket(b"banner");
+        let bundle = encode([("avatar", avatar.as_str()), ("", banner.as_str())]).unwrap();
+        assert!(bundle.starts_with(BUNDLE_PREFIX));
+
+        let expected = vec![
+            BundledTicket {
+                label: "avatar".to_string(),
+                kind: TicketKind::Blob,
+                ticket: avatar.clone(),
+            },
+            BundledTicket {
+                label: String::new(),
+                kind: TicketKind::Blob,
+                ticket: banner,
+            },
+        ];
+        assert_eq!(decode(&bundle).unwrap(), expected);
+        // Survives being uppercased, e.g. for QR codes
+        assert_eq!(decode(&bundle.to_ascii_uppercase()).unwrap(), expected);
+        // Tickets in other encodings are stored in their standard form
+        let qr = ticket::encode(&avatar, ticket::TicketEncoding::QrAlphanumeric).unwrap();
+        assert_eq!(
+            decode(&encode([("a", qr.as_str())]).unwrap()).unwrap()[0].ticket,
+            avatar
+        );
+    }
+
+    #[test]
+    fn test_bundle_rejects_bad_input() {
+        let ticket = sample_ticket(b"x");
+        assert!(encode([]).is_err());
+        assert!(encode([("a", "not-a-ticket")]).is_err());
+        let long_label = "x".repeat(256);
+        assert!(encode([(long_label.as_str(), ticket.as_str())]).is_err());
+
+        let bundle = encode([("a", ticket.as_str())]).unwrap();
+        assert!(decode(&bundle[..bundle.len() - 8]).is_err());
+        assert!(decode(&ticket).is_err());
+    }
+}
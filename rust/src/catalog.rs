@@ -0,0 +1,134 @@
+//! When blobs were added, for listings sorted by age.
+//!
+//! The first time content is put on this node or arrives by download,
+//! gateway fetch or document sync, the time is recorded for the blob a
+//! ticket or entry refers to, in a file next to the store, so it survives
+//! restarts. Other blobs have no add time: the parts of a collection and
+//! content stored before add times were recorded. Listings
+//! report their time as unknown and sort them after every blob that has
+//! one. Records of blobs that left the store are dropped when the store is
+//! listed.
+
+use crate::clock::now_micros;
+use anyhow::{Context, Result};
+use iroh_blobs::Hash;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Add times of the blobs put on or fetched by this node.
+#[derive(Debug)]
+pub struct AddedTimes {
+    path: PathBuf,
+    /// Microseconds since epoch, by hash.
+    times: Mutex<HashMap<Hash, u64>>,
+}
+
+impl AddedTimes {
+    /// Load the add times stored at `path`, or start with none.
+    ///
+    /// Unreadable records are skipped rather than keeping the node from
+    /// starting. A blob recorded more than once keeps its earliest time.
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let mut times = HashMap::new();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                for (hash, time) in contents.lines().filter_map(parse_record) {
+                    let earliest = times.entry(hash).or_insert(time);
+                    *earliest = (*earliest).min(time);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context("Failed to read blob add times"),
+        }
+        Ok(Self {
+            path,
+            times: Mutex::new(times),
+        })
+    }
+
+    /// When `hash` was added (microseconds since epoch), if known.
+    pub fn get(&self, hash: &Hash) -> Option<u64> {
+        self.times.lock().unwrap().get(hash).copied()
+    }
+
+    /// Record that `hash` was added now, unless it already has an add time.
+    pub fn record(&self, hash: Hash) -> Result<()> {
+        let mut times = self.times.lock().unwrap();
+        if times.contains_key(&hash) {
+            return Ok(());
+        }
+        let time = now_micros();
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .context("Failed to open blob add times")?;
+        file.write_all(format_record(hash, time).as_bytes())
+            .context("Failed to record blob add time")?;
+        times.insert(hash, time);
+        Ok(())
+    }
+
+    /// Drop the records of blobs not in `present` that were added before
+    /// `since`, so blobs put while the store was listed keep theirs.
+    pub fn retain(&self, present: &HashSet<Hash>, since: u64) -> Result<()> {
+        let mut times = self.times.lock().unwrap();
+        let before = times.len();
+        times.retain(|hash, time| present.contains(hash) || *time >= since);
+        if times.len() == before {
+            return Ok(());
+        }
+        let contents: String = times
+            .iter()
+            .map(|(hash, time)| format_record(*hash, *time))
+            .collect();
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, contents).context("Failed to write blob add times")?;
+        std::fs::rename(&tmp, &self.path).context("Failed to write blob add times")
+    }
+}
+
+fn format_record(hash: Hash, time: u64) -> String {
+    format!("{time} {hash}\n")
+}
+
+fn parse_record(line: &str) -> Option<(Hash, u64)> {
+    let (time, hash) = line.split_once(' ')?;
+    Some((hash.parse().ok()?, time.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_added_times_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blob-added");
+        let (kept, gone) = (Hash::new(b"kept"), Hash::new(b"gone"));
+
+        let times = AddedTimes::load(path.clone()).unwrap();
+        times.record(kept).unwrap();
+        let first = times.get(&kept).unwrap();
+        times.record(kept).unwrap();
+        assert_eq!(times.get(&kept), Some(first));
+        times.record(gone).unwrap();
+
+        // Records of blobs added since the listing started are kept
+        times.retain(&HashSet::from([kept]), 0).unwrap();
+        assert!(times.get(&gone).is_some());
+        times.retain(&HashSet::from([kept]), u64::MAX).unwrap();
+        assert_eq!(times.get(&gone), None);
+        std::fs::write(
+            &path,
+            format!("{}garbage\n", std::fs::read_to_string(&path).unwrap()),
+        )
+        .unwrap();
+
+        let times = AddedTimes::load(path).unwrap();
+        assert_eq!(times.get(&kept), Some(first));
+        assert_eq!(times.get(&gone), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! Content-defined chunking for large files that change in place.
+//!
+//! A file imported whole is a single blob, so after a small edit every peer
+//! downloads all of it again. Chunked files are split at boundaries chosen
+//! by the content itself (FastCDC), each chunk is stored as its own blob,
+//! and the file is the hash sequence of its chunks. An edit only changes
+//! the chunks around it, so re-importing stores few new chunks and peers
+//! that have the previous version only fetch those.
+
+use anyhow::{Context, Result};
+use fastcdc::v2020::StreamCDC;
+use iroh_blobs::api::Store;
+use iroh_blobs::hashseq::HashSeq;
+use iroh_blobs::{Hash, HashAndFormat};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Smallest chunk, except for the last one.
+pub(crate) const MIN_CHUNK_SIZE: u32 = 16 * 1024;
+
+/// Chunk size the boundaries are tuned for.
+pub(crate) const AVG_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// Largest chunk.
+pub(crate) const MAX_CHUNK_SIZE: u32 = 256 * 1024;
+
+/// Outcome of importing or exporting a chunked file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkStats {
+    /// File size in bytes.
+    pub size: u64,
+    /// Number of chunks in the file.
+    pub chunks: u64,
+    /// Chunks that were not in the store yet: stored by an import, or
+    /// downloaded by a fetch.
+    pub new_chunks: u64,
+}
+
+/// Split the file at `path` into chunks, store them and return the hash of
+/// the chunk sequence.
+///
+/// The sequence gets an automatic tag, which keeps the chunks alive.
+pub async fn import(store: &Store, path: &Path) -> Result<(Hash, ChunkStats)> {
+    let file = File::open(path).context("Failed to open file")?;
+    let mut stats = ChunkStats::default();
+    // Temp tags keep the chunks alive until the sequence is tagged
+    let mut chunks = Vec::new();
+    for chunk in StreamCDC::new(file, MIN_CHUNK_SIZE, AVG_CHUNK_SIZE, MAX_CHUNK_SIZE) {
+        let chunk = chunk.context("Failed to read file")?;
+        if !store.blobs().has(Hash::new(&chunk.data)).await? {
+            stats.new_chunks += 1;
+        }
+        stats.size += chunk.length as u64;
+        stats.chunks += 1;
+        let tag = store
+            .add_bytes(chunk.data)
+            .temp_tag()
+            .await
+            .context("Failed to add chunk to store")?;
+        chunks.push(tag);
+    }
+
+    let seq: HashSeq = chunks.iter().map(|tag| tag.hash()).collect();
+    let root = store
+        .add_bytes(seq.into_inner())
+        .temp_tag()
+        .await
+        .context("Failed to add chunk list to store")?;
+    store
+        .tags()
+        .create(HashAndFormat::hash_seq(root.hash()))
+        .await
+        .context("Failed to tag chunked file")?;
+    Ok((root.hash(), stats))
+}
+
+/// Chunks of the sequence `root` that are not complete in the store.
+///
+/// The sequence itself must be stored.
+pub async fn missing(store: &Store, root: Hash) -> Result<u64> {
+    let seq = chunk_list(store, root).await?;
+    let mut missing = 0;
+    for hash in seq.iter() {
+        if !store.blobs().has(hash).await? {
+            missing += 1;
+        }
+    }
+    Ok(missing)
+}
+
+/// Write the chunks of the sequence `root` to `dst`, replacing it.
+///
+/// All chunks must be stored. Returns the file size and chunk count.
+pub async fn export(store: &Store, root: Hash, dst: &Path) -> Result<ChunkStats> {
+    let seq = chunk_list(store, root).await?;
+    // Write next to the destination so a failed export leaves it untouched
+    let tmp = dst.with_extension("part");
+    let mut out = BufWriter::new(File::create(&tmp).context("Failed to create file")?);
+    let mut stats = ChunkStats::default();
+    for hash in seq.iter() {
+        let bytes = store
+            .get_bytes(hash)
+            .await
+            .context("Failed to read chunk from store")?;
+        out.write_all(&bytes).context("Failed to write file")?;
+        stats.size += bytes.len() as u64;
+        stats.chunks += 1;
+    }
+    out.into_inner()
+        .map_err(|e| e.into_error())
+        .and_then(|file| file.sync_all())
+        .context("Failed to write file")?;
+    std::fs::rename(&tmp, dst).context("Failed to write file")?;
+    Ok(stats)
+}
+
+async fn chunk_list(store: &Store, root: Hash) -> Result<HashSeq> {
+    let bytes = store
+        .get_bytes(root)
+        .await
+        .context("Failed to read chunk list")?;
+    HashSeq::try_from(bytes).context("Invalid chunk list")
+}
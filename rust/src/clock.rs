@@ -0,0 +1,15 @@
+//! Wall-clock time in microseconds since epoch, the unit of document entry
+//! timestamps and of the times this crate keeps in its state files.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current time in microseconds since epoch.
+pub(crate) fn now_micros() -> u64 {
+    micros(SystemTime::now())
+}
+
+/// `time` in microseconds since epoch, or 0 if it is before the epoch.
+pub(crate) fn micros(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_micros() as u64)
+}
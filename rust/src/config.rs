@@ -0,0 +1,392 @@
+//! Versioned JSON node configuration.
+//!
+//! `IrohNodeConfig` is a C struct that still gains fields as options are
+//! added, so callers built against an older header pass garbage. C callers
+//! that load the library separately from its header can use a JSON config
+//! instead, which names its options and rejects ones this library does not
+//! know rather than ignoring them. Option names match the `IrohNodeConfig`
+//! fields; options that take callbacks, such as discovery or storage
+//! alerts, are only available through the struct, which is why the Swift
+//! package, shipping its header with the library, keeps using it.
+
+use crate::access::AccessMode;
+use crate::announce::ContentAnnouncer;
+use crate::bandwidth::{BandwidthCaps, BandwidthLimits};
+use crate::limits::RequestLimits;
+use crate::node::NodeOptions;
+use crate::recovery::{BlobStoreRecovery, DocsStoreRecovery};
+use crate::storage::StoreInlining;
+use crate::watchdog::Watchdog;
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Config version this library reads.
+pub(crate) const CONFIG_VERSION: u64 = 1;
+
+/// A node configuration read from JSON.
+pub struct NodeConfig {
+    /// Directory for the blob store.
+    pub storage_path: PathBuf,
+    /// Options for creating the node.
+    pub options: NodeOptions,
+    /// OTLP/HTTP collector to export telemetry to, if any.
+    pub otlp_endpoint: Option<String>,
+    /// Whether to run background callbacks on one dedicated thread.
+    pub dedicated_callback_thread: bool,
+}
+
+/// Parse a JSON node configuration.
+///
+/// `version` and, unless `ephemeral` is set, `storage_path` are required;
+/// every other option falls back to the same default as in `IrohNodeConfig`.
+pub fn parse(json: &str) -> Result<NodeConfig> {
+    let value: Value = serde_json::from_str(json).context("Invalid config JSON")?;
+    let mut fields = into_object(value, "config")?;
+
+    match take(&mut fields, "version", Value::as_u64, "a number")? {
+        Some(CONFIG_VERSION) => {}
+        Some(version) => bail!(
+            "Unsupported config version {version} (this library reads version {CONFIG_VERSION})"
+        ),
+        None => bail!("Config is missing `version`"),
+    }
+    let mut options = NodeOptions::default();
+    if let Some(ephemeral) = take_bool(&mut fields, "ephemeral")? {
+        options.ephemeral = ephemeral;
+    }
+    let storage_path = match take_string(&mut fields, "storage_path")? {
+        Some(path) => PathBuf::from(path),
+        // Ephemeral nodes store their data under the temp dir
+        None if options.ephemeral => PathBuf::new(),
+        None => bail!("Config is missing `storage_path`"),
+    };
+    if let Some(enabled) = take_bool(&mut fields, "relay_enabled")? {
+        options.relay_enabled = enabled;
+    }
+    options.custom_relay_url = take_string(&mut fields, "custom_relay_url")?;
+    if let Some(enabled) = take_bool(&mut fields, "docs_enabled")? {
+        options.docs_enabled = enabled;
+    }
+    options.temp_dir = take_string(&mut fields, "temp_dir")?.map(PathBuf::from);
+    if let Some(ms) = take_u64(&mut fields, "ttl_prune_interval_ms")? {
+        options.ttl_prune_interval = (ms > 0).then(|| Duration::from_millis(ms));
+    }
+    options.max_tls_tickets = take_u64(&mut fields, "max_tls_tickets")?
+        .filter(|&n| n > 0)
+        .map(|n| n as usize);
+    options.idle_timeout = take_u64(&mut fields, "idle_timeout_ms")?
+        .filter(|&ms| ms > 0)
+        .map(Duration::from_millis);
+    options.max_connections = take_u64(&mut fields, "max_connections")?
+        .filter(|&n| n > 0)
+        .map(|n| n as usize);
+    if let Some(disabled) = take_bool(&mut fields, "disable_default_discovery")? {
+        options.default_discovery = !disabled;
+    }
+    let otlp_endpoint = take_string(&mut fields, "otlp_endpoint")?;
+    if let Some(watchdog) = fields.remove("slow_operation_watchdog") {
+        options.watchdog = parse_watchdog(watchdog)?;
+    }
+    options.control_socket = take_string(&mut fields, "control_socket")?;
+    options.gateway_url = take_string(&mut fields, "gateway_url")?;
+    options.blob_access = match take_string(&mut fields, "blob_access")?.as_deref() {
+        None | Some("open") => AccessMode::Open,
+        Some("allow_list") => AccessMode::AllowList,
+        Some(other) => bail!("`blob_access` must be \"open\" or \"allow_list\", not {other:?}"),
+    };
+    if let Some(limits) = fields.remove("request_limits") {
+        options.request_limits = parse_request_limits(limits)?;
+    }
+    if let Some(limits) = fields.remove("bandwidth_limits") {
+        options.bandwidth_limits = parse_bandwidth_limits(limits)?;
+    }
+    options.app_id = take_string(&mut fields, "app_id")?;
+    if let Some(inlining) = fields.remove("store_inlining") {
+        options.store_inlining = parse_store_inlining(inlining)?;
+    }
+    if let Some(announcer) = fields.remove("content_announcer") {
+        options.content_announcer = parse_content_announcer(announcer)?;
+    }
+    options.test_seed = take_string(&mut fields, "test_seed")?;
+    options.worker_threads = take_u64(&mut fields, "worker_threads")?
+        .filter(|&n| n > 0)
+        .map(|n| n as usize);
+    options.max_blocking_threads = take_u64(&mut fields, "max_blocking_threads")?
+        .filter(|&n| n > 0)
+        .map(|n| n as usize);
+    options.blob_store_recovery = match take_string(&mut fields, "blob_store_recovery")?.as_deref()
+    {
+        None | Some("fail") => BlobStoreRecovery::Fail,
+        Some("reindex") => BlobStoreRecovery::Reindex,
+        Some("quarantine") => BlobStoreRecovery::Quarantine,
+        Some(other) => bail!(
+            "`blob_store_recovery` must be \"fail\", \"reindex\" or \"quarantine\", not {other:?}"
+        ),
+    };
+    options.docs_store_recovery = match take_string(&mut fields, "docs_store_recovery")?.as_deref()
+    {
+        None | Some("fail") => DocsStoreRecovery::Fail,
+        Some("discard") => DocsStoreRecovery::Discard,
+        Some(other) => {
+            bail!("`docs_store_recovery` must be \"fail\" or \"discard\", not {other:?}")
+        }
+    };
+    let dedicated_callback_thread =
+        take_bool(&mut fields, "dedicated_callback_thread")?.unwrap_or(false);
+    reject_unknown(&fields, "")?;
+
+    Ok(NodeConfig {
+        storage_path,
+        options,
+        otlp_endpoint,
+        dedicated_callback_thread,
+    })
+}
+
+/// Slow operation watchdog without a callback; slow operations are logged
+/// and, with `emit_events`, published as node events.
+fn parse_watchdog(value: Value) -> Result<Option<Watchdog>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let mut fields = into_object(value, "slow_operation_watchdog")?;
+    let threshold_ms = take_u64(&mut fields, "threshold_ms")?.unwrap_or(0);
+    let emit_events = take_bool(&mut fields, "emit_events")?.unwrap_or(false);
+    reject_unknown(&fields, "slow_operation_watchdog.")?;
+    Ok((threshold_ms > 0).then(|| Watchdog {
+        threshold: Duration::from_millis(threshold_ms),
+        emit_events,
+        on_slow: None,
+    }))
+}
+
+/// Content announcer without a callback; rounds are published as node events.
+fn parse_content_announcer(value: Value) -> Result<Option<ContentAnnouncer>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let mut fields = into_object(value, "content_announcer")?;
+    let interval_ms = take_u64(&mut fields, "interval_ms")?.unwrap_or(0);
+    let tag_prefix = take_string(&mut fields, "tag_prefix")?.unwrap_or_default();
+    reject_unknown(&fields, "content_announcer.")?;
+    Ok((interval_ms > 0).then(|| ContentAnnouncer {
+        interval: Duration::from_millis(interval_ms),
+        tag_prefix: tag_prefix.into_bytes(),
+        on_announce: None,
+    }))
+}
+
+fn parse_request_limits(value: Value) -> Result<RequestLimits> {
+    if value.is_null() {
+        return Ok(RequestLimits::default());
+    }
+    let mut fields = into_object(value, "request_limits")?;
+    let limit = |fields: &mut Map<String, Value>, key: &str| -> Result<Option<u64>> {
+        Ok(take_u64(fields, key)?.filter(|&n| n > 0))
+    };
+    let limits = RequestLimits {
+        requests_per_sec: limit(&mut fields, "requests_per_sec")?
+            .map(u32::try_from)
+            .transpose()
+            .context("`request_limits.requests_per_sec` is too large")?,
+        max_concurrent: limit(&mut fields, "max_concurrent")?
+            .map(u32::try_from)
+            .transpose()
+            .context("`request_limits.max_concurrent` is too large")?,
+        bytes_per_sec: limit(&mut fields, "bytes_per_sec")?,
+    };
+    reject_unknown(&fields, "request_limits.")?;
+    Ok(limits)
+}
+
+fn parse_bandwidth_limits(value: Value) -> Result<BandwidthLimits> {
+    if value.is_null() {
+        return Ok(BandwidthLimits::default());
+    }
+    let mut fields = into_object(value, "bandwidth_limits")?;
+    let cap = |fields: &mut Map<String, Value>, key: &str| -> Result<Option<u64>> {
+        Ok(take_u64(fields, key)?.filter(|&n| n > 0))
+    };
+    let limits = BandwidthLimits {
+        wifi: BandwidthCaps {
+            upload_bytes_per_sec: cap(&mut fields, "wifi_upload_bytes_per_sec")?,
+            download_bytes_per_sec: cap(&mut fields, "wifi_download_bytes_per_sec")?,
+        },
+        cellular: BandwidthCaps {
+            upload_bytes_per_sec: cap(&mut fields, "cellular_upload_bytes_per_sec")?,
+            download_bytes_per_sec: cap(&mut fields, "cellular_download_bytes_per_sec")?,
+        },
+    };
+    reject_unknown(&fields, "bandwidth_limits.")?;
+    Ok(limits)
+}
+
+fn parse_store_inlining(value: Value) -> Result<StoreInlining> {
+    if value.is_null() {
+        return Ok(StoreInlining::default());
+    }
+    let mut fields = into_object(value, "store_inlining")?;
+    let inlining = StoreInlining {
+        max_data: take_u64(&mut fields, "max_inline_data")?.filter(|&n| n > 0),
+        max_outboard: take_u64(&mut fields, "max_inline_outboard")?.filter(|&n| n > 0),
+    };
+    reject_unknown(&fields, "store_inlining.")?;
+    Ok(inlining)
+}
+
+fn into_object(value: Value, name: &str) -> Result<Map<String, Value>> {
+    match value {
+        Value::Object(fields) => Ok(fields),
+        _ => bail!("`{name}` must be a JSON object"),
+    }
+}
+
+/// Remove `key` and convert it, treating null like a missing option.
+fn take<T>(
+    fields: &mut Map<String, Value>,
+    key: &str,
+    convert: impl FnOnce(&Value) -> Option<T>,
+    expected: &str,
+) -> Result<Option<T>> {
+    match fields.remove(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => convert(&value)
+            .map(Some)
+            .with_context(|| format!("`{key}` must be {expected}")),
+    }
+}
+
+fn take_bool(fields: &mut Map<String, Value>, key: &str) -> Result<Option<bool>> {
+    take(fields, key, Value::as_bool, "true or false")
+}
+
+fn take_u64(fields: &mut Map<String, Value>, key: &str) -> Result<Option<u64>> {
+    take(fields, key, Value::as_u64, "a non-negative integer")
+}
+
+fn take_string(fields: &mut Map<String, Value>, key: &str) -> Result<Option<String>> {
+    take(fields, key, |v| v.as_str().map(str::to_string), "a string")
+}
+
+/// Fail on options left over after all known ones were taken.
+fn reject_unknown(fields: &Map<String, Value>, prefix: &str) -> Result<()> {
+    match fields.keys().next() {
+        Some(key) => bail!("Unknown config option `{prefix}{key}`"),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_minimal_config_uses_defaults() {
+        let config = parse(r#"{"version": 1, "storage_path": "/tmp/iroh"}"#)
+            .ok()
+            .unwrap();
+        assert_eq!(config.storage_path, PathBuf::from("/tmp/iroh"));
+        assert!(config.options.relay_enabled);
+        assert!(!config.options.docs_enabled);
+        assert!(config.options.default_discovery);
+        assert_eq!(
+            config.options.ttl_prune_interval,
+            NodeOptions::default().ttl_prune_interval
+        );
+        assert!(config.otlp_endpoint.is_none());
+        assert!(!config.dedicated_callback_thread);
+        assert!(!config.options.ephemeral);
+
+        let config = parse(r#"{"version": 1, "ephemeral": true}"#).ok().unwrap();
+        assert!(config.options.ephemeral);
+    }
+
+    #[test]
+    fn test_parse_full_config() {
+        let config = parse(
+            r#"{
+                "version": 1,
+                "storage_path": "/tmp/iroh",
+                "relay_enabled": false,
+                "docs_enabled": true,
+                "ttl_prune_interval_ms": 0,
+                "idle_timeout_ms": 5000,
+                "max_connections": 8,
+                "disable_default_discovery": true,
+                "slow_operation_watchdog": {"threshold_ms": 250, "emit_events": true},
+                "gateway_url": "https://gateway.example.com/blobs",
+                "blob_access": "allow_list",
+                "request_limits": {"requests_per_sec": 10, "bytes_per_sec": 0},
+                "bandwidth_limits": {"cellular_upload_bytes_per_sec": 65536},
+                "dedicated_callback_thread": true,
+                "app_id": "com.example.photos",
+                "store_inlining": {"max_inline_data": 65536},
+                "content_announcer": {"interval_ms": 60000, "tag_prefix": "pinned/"},
+                "test_seed": "alice",
+                "worker_threads": 2,
+                "max_blocking_threads": 8,
+                "blob_store_recovery": "reindex",
+                "docs_store_recovery": "discard"
+            }"#,
+        )
+        .ok()
+        .unwrap();
+        let options = &config.options;
+        assert!(!options.relay_enabled);
+        assert!(options.docs_enabled);
+        assert_eq!(options.ttl_prune_interval, None);
+        assert_eq!(options.idle_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(options.max_connections, Some(8));
+        assert!(!options.default_discovery);
+        let watchdog = options.watchdog.as_ref().unwrap();
+        assert_eq!(watchdog.threshold, Duration::from_millis(250));
+        assert!(watchdog.emit_events);
+        assert_eq!(
+            options.gateway_url.as_deref(),
+            Some("https://gateway.example.com/blobs")
+        );
+        assert_eq!(options.blob_access, AccessMode::AllowList);
+        assert_eq!(options.request_limits.requests_per_sec, Some(10));
+        assert_eq!(options.request_limits.bytes_per_sec, None);
+        assert_eq!(
+            options.bandwidth_limits.cellular.upload_bytes_per_sec,
+            Some(65536)
+        );
+        assert_eq!(options.bandwidth_limits.wifi, BandwidthCaps::default());
+        assert!(config.dedicated_callback_thread);
+        assert_eq!(options.app_id.as_deref(), Some("com.example.photos"));
+        assert_eq!(options.store_inlining.max_data, Some(65536));
+        assert_eq!(options.store_inlining.max_outboard, None);
+        let announcer = options.content_announcer.as_ref().unwrap();
+        assert_eq!(announcer.interval, Duration::from_secs(60));
+        assert_eq!(announcer.tag_prefix, b"pinned/");
+        assert_eq!(options.test_seed.as_deref(), Some("alice"));
+        assert_eq!(options.worker_threads, Some(2));
+        assert_eq!(options.max_blocking_threads, Some(8));
+        assert_eq!(options.blob_store_recovery, BlobStoreRecovery::Reindex);
+        assert_eq!(options.docs_store_recovery, DocsStoreRecovery::Discard);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_configs() {
+        let error = |json: &str| parse(json).err().unwrap().to_string();
+        assert!(error(r#"{"storage_path": "/tmp"}"#).contains("missing `version`"));
+        assert!(error(r#"{"version": 2, "storage_path": "/tmp"}"#).contains("version 2"));
+        assert!(error(r#"{"version": 1}"#).contains("storage_path"));
+        assert!(
+            error(r#"{"version": 1, "storage_path": "/tmp", "relay": false}"#)
+                .contains("Unknown config option `relay`")
+        );
+        assert!(
+            error(r#"{"version": 1, "storage_path": "/tmp", "docs_enabled": "yes"}"#)
+                .contains("`docs_enabled` must be true or false")
+        );
+        assert!(
+            error(r#"{"version": 1, "storage_path": "/tmp", "request_limits": {"rate": 1}}"#)
+                .contains("`request_limits.rate`")
+        );
+        assert!(error("[]").contains("must be a JSON object"));
+    }
+}
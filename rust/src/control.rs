@@ -0,0 +1,340 @@
+//! Debug control server.
+//!
+//! An opt-in JSON-RPC 2.0 server on a Unix socket or a loopback TCP port,
+//! so developers can inspect a running app's node from the command line:
+//!
+//! ```sh
+//! echo '{"jsonrpc":"2.0","id":1,"method":"node.info"}' | nc -U /tmp/iroh.sock
+//! ```
+//!
+//! Requests and responses are one JSON object per line. Methods:
+//! - `node.info`: node id, relay and direct addresses
+//! - `blobs.list`: every blob in the store with its size and completeness
+//! - `docs.list`: every document with its capability
+//! - `transfers.list`: downloads in progress
+//!
+//! The server only reads node state and never accepts remote connections.
+
+use crate::events::{EventBus, NodeEvent};
+use anyhow::{Context, Result, bail};
+use futures_lite::StreamExt;
+use iroh::Endpoint;
+use iroh_blobs::Hash;
+use iroh_blobs::api::Store;
+use iroh_blobs::api::proto::BlobStatus;
+#[cfg(feature = "docs")]
+use iroh_docs::{CapabilityKind, protocol::Docs};
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Where the control server listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlAddr {
+    /// A loopback TCP address, like "127.0.0.1:7777".
+    Tcp(SocketAddr),
+    /// A Unix socket path.
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+impl ControlAddr {
+    /// Parse a loopback `ip:port` or, on Unix, a socket path.
+    pub fn parse(addr: &str) -> Result<Self> {
+        if let Ok(socket_addr) = addr.parse::<SocketAddr>() {
+            if !socket_addr.ip().is_loopback() {
+                bail!("control server must listen on a loopback address, not {socket_addr}");
+            }
+            return Ok(Self::Tcp(socket_addr));
+        }
+        #[cfg(unix)]
+        if !addr.is_empty() {
+            return Ok(Self::Unix(addr.into()));
+        }
+        bail!("invalid control server address: {addr:?}")
+    }
+}
+
+/// A download in progress.
+#[derive(Debug)]
+struct Transfer {
+    hash: Hash,
+    operation: Option<u64>,
+    started: Instant,
+}
+
+/// Node state the server reports on.
+#[derive(Debug, Clone)]
+pub struct ControlServer {
+    endpoint: Endpoint,
+    store: Store,
+    #[cfg(feature = "docs")]
+    docs: Option<Docs>,
+    app_id: Option<String>,
+    transfers: Arc<Mutex<Vec<Transfer>>>,
+}
+
+impl ControlServer {
+    /// Report on the given node parts.
+    pub fn new(
+        endpoint: Endpoint,
+        store: Store,
+        #[cfg(feature = "docs")] docs: Option<Docs>,
+        app_id: Option<String>,
+    ) -> Self {
+        Self {
+            endpoint,
+            store,
+            #[cfg(feature = "docs")]
+            docs,
+            app_id,
+            transfers: Default::default(),
+        }
+    }
+
+    /// Listen on `addr` and serve requests until the runtime stops.
+    ///
+    /// Binding happens before this returns, so address errors surface at
+    /// node creation. Downloads are tracked from `events` from now on.
+    pub async fn spawn(self, addr: &ControlAddr, events: &EventBus) -> Result<()> {
+        let transfers = self.transfers.clone();
+        let mut stream = events.subscribe();
+        tokio::spawn(async move {
+            while let Some(event) = stream.next().await {
+                let mut transfers = transfers.lock().unwrap();
+                match event {
+                    NodeEvent::DownloadStarted { hash, operation } => transfers.push(Transfer {
+                        hash,
+                        operation,
+                        started: Instant::now(),
+                    }),
+                    NodeEvent::DownloadFinished {
+                        hash, operation, ..
+                    } => {
+                        if let Some(i) = transfers
+                            .iter()
+                            .position(|t| t.hash == hash && t.operation == operation)
+                        {
+                            transfers.remove(i);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        match addr {
+            ControlAddr::Tcp(socket_addr) => {
+                let listener = TcpListener::bind(socket_addr)
+                    .await
+                    .context("Failed to bind control server")?;
+                tokio::spawn(async move {
+                    while let Ok((stream, _)) = listener.accept().await {
+                        tokio::spawn(self.clone().serve(stream));
+                    }
+                });
+            }
+            #[cfg(unix)]
+            ControlAddr::Unix(path) => {
+                use std::os::unix::fs::FileTypeExt;
+                // A socket left behind by an earlier run would block the bind
+                if let Ok(metadata) = std::fs::symlink_metadata(path)
+                    && metadata.file_type().is_socket()
+                {
+                    let _ = std::fs::remove_file(path);
+                }
+                let listener = tokio::net::UnixListener::bind(path)
+                    .context("Failed to bind control server")?;
+                tokio::spawn(async move {
+                    while let Ok((stream, _)) = listener.accept().await {
+                        tokio::spawn(self.clone().serve(stream));
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Answer each request line on `stream` until it closes.
+    async fn serve(self, stream: impl AsyncRead + AsyncWrite) {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut response = self.handle(&line).await.to_string();
+            response.push('\n');
+            if writer.write_all(response.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Response to one JSON-RPC request.
+    async fn handle(&self, request: &str) -> Value {
+        let request: Value = match serde_json::from_str(request) {
+            Ok(request) => request,
+            Err(e) => return error(Value::Null, PARSE_ERROR, &e.to_string()),
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            return error(id, INVALID_REQUEST, "missing method");
+        };
+        let result = match method {
+            "node.info" => Ok(self.node_info()),
+            "blobs.list" => self.blobs().await,
+            "docs.list" => self.docs().await,
+            "transfers.list" => Ok(self.transfers()),
+            _ => return error(id, METHOD_NOT_FOUND, &format!("unknown method {method}")),
+        };
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => error(id, INTERNAL_ERROR, &format!("{e:#}")),
+        }
+    }
+
+    fn node_info(&self) -> Value {
+        let addr = self.endpoint.addr();
+        json!({
+            "node_id": self.endpoint.id().to_string(),
+            "relay_url": addr.relay_urls().next().map(|url| url.to_string()),
+            "direct_addrs": addr.ip_addrs().map(|a| a.to_string()).collect::<Vec<_>>(),
+            "app_id": self.app_id,
+        })
+    }
+
+    async fn blobs(&self) -> Result<Value> {
+        let hashes = self
+            .store
+            .blobs()
+            .list()
+            .hashes()
+            .await
+            .context("Failed to list blobs")?;
+        let mut blobs = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let (size, complete) = match self.store.blobs().status(hash).await? {
+                BlobStatus::Complete { size } => (Some(size), true),
+                BlobStatus::Partial { size } => (size, false),
+                BlobStatus::NotFound => continue,
+            };
+            blobs.push(json!({ "hash": hash.to_string(), "size": size, "complete": complete }));
+        }
+        Ok(Value::Array(blobs))
+    }
+
+    #[cfg(feature = "docs")]
+    async fn docs(&self) -> Result<Value> {
+        let Some(docs) = &self.docs else {
+            return Ok(json!([]));
+        };
+        let mut list = Vec::new();
+        let mut namespaces = docs.api().list().await.context("Failed to list docs")?;
+        while let Some(item) = namespaces.next().await {
+            let (namespace, capability) = item?;
+            let capability = match capability {
+                CapabilityKind::Write => "write",
+                CapabilityKind::Read => "read",
+            };
+            list.push(json!({ "namespace": namespace.to_string(), "capability": capability }));
+        }
+        Ok(Value::Array(list))
+    }
+
+    #[cfg(not(feature = "docs"))]
+    async fn docs(&self) -> Result<Value> {
+        Ok(json!([]))
+    }
+
+    fn transfers(&self) -> Value {
+        let transfers = self.transfers.lock().unwrap();
+        transfers
+            .iter()
+            .map(|t| {
+                json!({
+                    "hash": t.hash.to_string(),
+                    "operation": t.operation,
+                    "elapsed_ms": t.started.elapsed().as_millis() as u64,
+                })
+            })
+            .collect()
+    }
+}
+
+/// JSON-RPC error response.
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::{IrohNode, NodeOptions};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_requires_loopback() {
+        assert!(ControlAddr::parse("127.0.0.1:7777").is_ok());
+        assert!(ControlAddr::parse("0.0.0.0:7777").is_err());
+        assert!(ControlAddr::parse("").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_serves_requests_on_unix_socket() {
+        let dir = tempdir().unwrap();
+        let socket = dir.path().join("control.sock");
+        let node = IrohNode::with_options(
+            dir.path().join("store"),
+            NodeOptions {
+                relay_enabled: false,
+                control_socket: Some(socket.to_str().unwrap().to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        node.put(b"inspected").unwrap();
+
+        let responses = node.runtime().block_on(async {
+            let stream = tokio::net::UnixStream::connect(&socket).await.unwrap();
+            let (reader, mut writer) = tokio::io::split(stream);
+            writer
+                .write_all(
+                    b"{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"node.info\"}\n\
+                      {\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"blobs.list\"}\n\
+                      {\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"nope\"}\n",
+                )
+                .await
+                .unwrap();
+            let mut lines = BufReader::new(reader).lines();
+            let mut responses = Vec::new();
+            for _ in 0..3 {
+                let line = lines.next_line().await.unwrap().unwrap();
+                responses.push(serde_json::from_str::<Value>(&line).unwrap());
+            }
+            responses
+        });
+
+        assert_eq!(
+            responses[0]["result"]["node_id"],
+            node.endpoint().id().to_string()
+        );
+        assert_eq!(
+            responses[1]["result"][0]["hash"],
+            Hash::new(b"inspected").to_string()
+        );
+        assert_eq!(responses[2]["error"]["code"], METHOD_NOT_FOUND);
+
+        node.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+//! Where background callbacks into the app run.
+//!
+//! Subscription events, conflict resolution, write filters and alerts are
+//! produced by tasks on the node's Tokio workers, so callbacks normally run
+//! on whichever worker produced them, and callbacks of different
+//! subscriptions can run at the same time. With a dedicated delivery thread
+//! every such callback runs on that one thread instead, one at a time and in
+//! the order they were made, which keeps each subscription's callbacks in
+//! order and never runs two callbacks concurrently.
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SendError, Sender};
+use std::thread::ThreadId;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// Name of the delivery thread, as seen in debuggers and crash reports.
+pub const DELIVERY_THREAD_NAME: &str = "iroh-callbacks";
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Runs callbacks inline or on a dedicated delivery thread.
+///
+/// Clones share the thread, which exits once the last clone is dropped.
+#[derive(Clone, Default)]
+pub struct Delivery {
+    thread: Option<Arc<DeliveryThread>>,
+}
+
+struct DeliveryThread {
+    jobs: Sender<Job>,
+    id: ThreadId,
+    /// Callbacks sent to the thread that have not started yet.
+    queued: AtomicUsize,
+}
+
+impl std::fmt::Debug for Delivery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Delivery")
+            .field("dedicated", &self.thread.is_some())
+            .finish()
+    }
+}
+
+impl Delivery {
+    /// Run callbacks on the thread that produces them.
+    pub fn inline() -> Self {
+        Self::default()
+    }
+
+    /// Run callbacks on a new dedicated delivery thread.
+    pub fn dedicated() -> Result<Self> {
+        let (jobs, rx) = mpsc::channel::<Job>();
+        let thread = std::thread::Builder::new()
+            .name(DELIVERY_THREAD_NAME.to_string())
+            .spawn(move || {
+                while let Ok(job) = rx.recv() {
+                    job();
+                }
+            })
+            .context("Failed to start callback delivery thread")?;
+        Ok(Self {
+            thread: Some(Arc::new(DeliveryThread {
+                jobs,
+                id: thread.thread().id(),
+                queued: AtomicUsize::new(0),
+            })),
+        })
+    }
+
+    /// Number of callbacks waiting for the delivery thread.
+    pub fn queued(&self) -> usize {
+        self.thread
+            .as_ref()
+            .map_or(0, |thread| thread.queued.load(Ordering::Relaxed))
+    }
+
+    /// Run `f` where callbacks are delivered and return its result.
+    ///
+    /// Blocks until `f` has run, so it may borrow from the caller, such as
+    /// strings handed to the app for the duration of the callback. On a
+    /// Tokio worker the worker's other tasks move to another thread while
+    /// this waits. Runs `f` inline when called on the delivery thread itself.
+    ///
+    /// # Safety
+    /// `f` may run on another thread, so everything it touches must be safe
+    /// to use from there for the duration of the call, like the callback
+    /// pointers and userdata the app registered.
+    pub unsafe fn call<R>(&self, f: impl FnOnce() -> R) -> R {
+        let Some(thread) = &self.thread else {
+            return f();
+        };
+        if std::thread::current().id() == thread.id {
+            return f();
+        }
+
+        let (tx, rx) = mpsc::sync_channel(1);
+        let queued = &thread.queued;
+        let job: Box<dyn FnOnce() + '_> = Box::new(move || {
+            queued.fetch_sub(1, Ordering::Relaxed);
+            let _ = tx.send(f());
+        });
+        // SAFETY: this call does not return before the job has run or been
+        // dropped, so nothing it borrows goes away while it runs, and the
+        // caller vouches for running it on another thread
+        let job: Job = unsafe { std::mem::transmute(job) };
+        thread.queued.fetch_add(1, Ordering::Relaxed);
+        if let Err(SendError(job)) = thread.jobs.send(job) {
+            // The delivery thread is gone; deliver here rather than not at all
+            job();
+        }
+
+        let wait = || rx.recv().expect("callback panicked on the delivery thread");
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(wait)
+            }
+            _ => wait(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_dedicated_delivery_runs_in_order_on_one_thread() {
+        let delivery = Delivery::dedicated().unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let tasks: Vec<_> = (0..4)
+            .map(|task| {
+                let delivery = delivery.clone();
+                let seen = seen.clone();
+                runtime.spawn(async move {
+                    for i in 0..25 {
+                        let label = format!("{task}-{i}");
+                        // Borrows `label`, which outlives the call
+                        let name = unsafe {
+                            delivery.call(|| {
+                                seen.lock().unwrap().push(label.clone());
+                                std::thread::current().name().map(str::to_string)
+                            })
+                        };
+                        assert_eq!(name.as_deref(), Some(DELIVERY_THREAD_NAME));
+                    }
+                })
+            })
+            .collect();
+        runtime.block_on(async {
+            for task in tasks {
+                task.await.unwrap();
+            }
+        });
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 100);
+        // Each producer's callbacks arrive in the order they were made
+        for task in 0..4 {
+            let order: Vec<usize> = seen
+                .iter()
+                .filter_map(|label| label.strip_prefix(&format!("{task}-")))
+                .map(|i| i.parse().unwrap())
+                .collect();
+            assert_eq!(order, (0..25).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_inline_delivery_runs_on_caller() {
+        let delivery = Delivery::inline();
+        let caller = std::thread::current().id();
+        assert_eq!(
+            unsafe { delivery.call(|| std::thread::current().id()) },
+            caller
+        );
+    }
+}
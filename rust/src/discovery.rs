@@ -0,0 +1,82 @@
+//! App-provided endpoint discovery.
+//!
+//! Apps can back discovery with their own service, for example a backend
+//! that stores the addresses of their users' nodes, instead of or next to
+//! the n0 DNS/pkarr discovery. The app is told whenever this node's
+//! addresses change and is asked for the addresses of nodes being dialed.
+
+use futures_lite::StreamExt;
+use futures_lite::stream::{self, Boxed};
+use iroh::discovery::{
+    Discovery, DiscoveryError, DiscoveryItem, IntoDiscovery, IntoDiscoveryError,
+};
+use iroh::endpoint_info::{EndpointData, EndpointInfo};
+use iroh::{Endpoint, EndpointAddr, EndpointId};
+use tokio::sync::oneshot;
+
+/// Provenance reported for addresses resolved by the app.
+const PROVENANCE: &str = "app";
+
+/// Looks up a node's addresses and sends them on the channel.
+///
+/// Dropping the sender without sending means the node is unknown.
+pub type Resolve = dyn Fn(EndpointId, oneshot::Sender<EndpointAddr>) + Send + Sync;
+
+/// Publishes this node's current addresses.
+pub type Publish = dyn Fn(&EndpointAddr) + Send + Sync;
+
+/// Discovery backed by app callbacks.
+pub struct AppDiscovery {
+    /// Resolver for nodes being dialed, if the app resolves.
+    pub resolve: Option<Box<Resolve>>,
+    /// Publisher for this node's addresses, if the app publishes.
+    pub publish: Option<Box<Publish>>,
+}
+
+impl std::fmt::Debug for AppDiscovery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AppDiscovery")
+            .field("resolve", &self.resolve.is_some())
+            .field("publish", &self.publish.is_some())
+            .finish()
+    }
+}
+
+impl IntoDiscovery for AppDiscovery {
+    fn into_discovery(self, endpoint: &Endpoint) -> Result<impl Discovery, IntoDiscoveryError> {
+        Ok(AppDiscoveryService {
+            id: endpoint.id(),
+            inner: self,
+        })
+    }
+}
+
+/// [`AppDiscovery`] bound to the endpoint it publishes for.
+#[derive(Debug)]
+struct AppDiscoveryService {
+    id: EndpointId,
+    inner: AppDiscovery,
+}
+
+impl Discovery for AppDiscoveryService {
+    fn publish(&self, data: &EndpointData) {
+        if let Some(publish) = &self.inner.publish {
+            publish(&EndpointAddr::from_parts(self.id, data.addrs().cloned()));
+        }
+    }
+
+    fn resolve(
+        &self,
+        endpoint_id: EndpointId,
+    ) -> Option<Boxed<Result<DiscoveryItem, DiscoveryError>>> {
+        let resolve = self.inner.resolve.as_ref()?;
+        let (tx, rx) = oneshot::channel();
+        resolve(endpoint_id, tx);
+        let item = stream::once_future(rx).filter_map(move |addr| {
+            let addr = addr.ok()?;
+            let info = EndpointInfo::from_parts(endpoint_id, EndpointData::new(addr.addrs));
+            Some(Ok(DiscoveryItem::new(info, PROVENANCE, None)))
+        });
+        Some(item.boxed())
+    }
+}
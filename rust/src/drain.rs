@@ -0,0 +1,224 @@
+//! Draining of in-flight calls before a node is freed.
+//!
+//! FFI calls block on the node from whatever thread the app called them
+//! on, so a node destroyed from another thread could be freed while those
+//! calls are still inside it. Every call on a node handle enters the node's
+//! gate for as long as it uses the node. Closing the gate turns away new
+//! calls, and the node is only freed once the calls already inside have
+//! left, by the closing thread or, past its deadline, by the last call out.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Gates of nodes that are open, by handle address.
+static GATES: LazyLock<Mutex<HashMap<usize, Arc<Gate>>>> = LazyLock::new(Default::default);
+
+thread_local! {
+    /// Gates entered by calls on this thread, once per call.
+    static HELD: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Tracks the calls inside one node.
+#[derive(Default)]
+pub struct Gate {
+    state: Mutex<GateState>,
+    idle: Condvar,
+}
+
+#[derive(Default)]
+struct GateState {
+    in_flight: usize,
+    free: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl Gate {
+    fn key(&self) -> usize {
+        self as *const Gate as usize
+    }
+
+    /// Wait until no other call is inside the node, for at most `deadline`.
+    ///
+    /// Calls on the current thread are not waited for, since they cannot
+    /// leave while it waits. Returns whether the node became idle in time.
+    pub fn drain(&self, deadline: Option<Duration>) -> bool {
+        let key = self.key();
+        let held_here = HELD.with(|held| held.borrow().iter().filter(|k| **k == key).count());
+        let until = deadline.map(|deadline| Instant::now() + deadline);
+        let mut state = self.state.lock().unwrap();
+        while state.in_flight > held_here {
+            state = match until {
+                None => self.idle.wait(state).unwrap(),
+                Some(until) => {
+                    let Some(left) = until.checked_duration_since(Instant::now()) else {
+                        return false;
+                    };
+                    self.idle.wait_timeout(state, left).unwrap().0
+                }
+            };
+        }
+        true
+    }
+
+    /// Run `free` now if no call is inside the node, or else when the last
+    /// call leaves.
+    pub fn free_when_idle(&self, free: impl FnOnce() + Send + 'static) {
+        let mut state = self.state.lock().unwrap();
+        if state.in_flight == 0 {
+            drop(state);
+            free();
+        } else {
+            state.free = Some(Box::new(free));
+        }
+    }
+
+    fn leave(&self) {
+        let key = self.key();
+        HELD.with(|held| {
+            let mut held = held.borrow_mut();
+            if let Some(i) = held.iter().rposition(|k| *k == key) {
+                held.swap_remove(i);
+            }
+        });
+        let free = {
+            let mut state = self.state.lock().unwrap();
+            state.in_flight -= 1;
+            self.idle.notify_all();
+            if state.in_flight == 0 {
+                state.free.take()
+            } else {
+                None
+            }
+        };
+        if let Some(free) = free {
+            free();
+        }
+    }
+}
+
+/// A call inside a node; the node is not freed while this is held.
+pub struct Entered<T: 'static> {
+    target: &'static T,
+    gate: Arc<Gate>,
+    /// Left on the thread that entered, which `HELD` relies on.
+    _thread: PhantomData<*const ()>,
+}
+
+impl<T> Deref for Entered<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.target
+    }
+}
+
+impl<T> Drop for Entered<T> {
+    fn drop(&mut self) {
+        self.gate.leave();
+    }
+}
+
+/// Open the gate of a node just handed out.
+///
+/// # Safety
+/// `node` must only be entered as a `T`, and must not be freed until its
+/// gate has been closed and has no calls inside.
+pub unsafe fn open<T>(node: *const T) {
+    GATES
+        .lock()
+        .unwrap()
+        .insert(node as usize, Arc::new(Gate::default()));
+}
+
+/// Enter the node behind `handle`, or None if it is not open.
+///
+/// Handles that were never opened, or have been closed, are turned away
+/// rather than dereferenced.
+pub fn enter<H, T>(handle: *const H) -> Option<Entered<T>> {
+    let gate = {
+        let gates = GATES.lock().unwrap();
+        let gate = gates.get(&(handle as usize))?.clone();
+        gate.state.lock().unwrap().in_flight += 1;
+        gate
+    };
+    HELD.with(|held| held.borrow_mut().push(gate.key()));
+    // Safety: open gates guard live values, as promised to `open`
+    Some(Entered {
+        target: unsafe { &*(handle as *const T) },
+        gate,
+        _thread: PhantomData,
+    })
+}
+
+/// Close the gate of the node behind `handle` so no new call enters it.
+///
+/// Returns None if the node was already closed.
+pub fn close<H>(handle: *const H) -> Option<Arc<Gate>> {
+    GATES.lock().unwrap().remove(&(handle as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn test_closed_gate_turns_calls_away() {
+        let value = Box::into_raw(Box::new(7u32));
+        unsafe { open(value) };
+
+        let entered = enter::<_, u32>(value).unwrap();
+        assert_eq!(*entered, 7);
+        drop(entered);
+
+        let gate = close(value).unwrap();
+        assert!(close(value).is_none());
+        assert!(enter::<_, u32>(value).is_none());
+        assert!(gate.drain(Some(Duration::ZERO)));
+        let address = value as usize;
+        gate.free_when_idle(move || drop(unsafe { Box::from_raw(address as *mut u32) }));
+    }
+
+    #[test]
+    fn test_last_call_out_frees() {
+        let value = Box::into_raw(Box::new(7u32));
+        unsafe { open(value) };
+        let entered = enter::<_, u32>(value).unwrap();
+
+        // Another thread closing gives up waiting for the call
+        let address = value as usize;
+        let freed = Arc::new(AtomicBool::new(false));
+        let flag = freed.clone();
+        std::thread::spawn(move || {
+            let gate = close(address as *const u32).unwrap();
+            assert!(!gate.drain(Some(Duration::from_millis(10))));
+            gate.free_when_idle(move || {
+                drop(unsafe { Box::from_raw(address as *mut u32) });
+                flag.store(true, Ordering::SeqCst);
+            });
+        })
+        .join()
+        .unwrap();
+
+        assert!(!freed.load(Ordering::SeqCst));
+        assert_eq!(*entered, 7);
+        drop(entered);
+        assert!(freed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_drain_skips_calls_on_closing_thread() {
+        let value = Box::into_raw(Box::new(7u32));
+        unsafe { open(value) };
+        let entered = enter::<_, u32>(value).unwrap();
+
+        let gate = close(value).unwrap();
+        assert!(gate.drain(None));
+        let address = value as usize;
+        gate.free_when_idle(move || drop(unsafe { Box::from_raw(address as *mut u32) }));
+        drop(entered);
+    }
+}
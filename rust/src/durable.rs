@@ -0,0 +1,381 @@
+//! Durable peers confirming that a document's local writes are backed up.
+//!
+//! Syncs are opaque: local writes spread to whichever peers are around, and
+//! an app cannot tell whether they reached a server it trusts to keep them.
+//! Apps designate durable peers per document. A peer confirms every local
+//! write made before a successful sync with it started, and the writes are
+//! backed up once every durable peer confirmed them. After local writes the
+//! node keeps asking the durable peers to sync, with backoff and again
+//! whenever connectivity changes, until they have.
+//!
+//! Designations and confirmations are recorded in a file next to the store,
+//! so they survive restarts. Progress is reported as
+//! [`NodeEvent::DurableSync`].
+
+use crate::clock::micros;
+use crate::events::{EventBus, NodeEvent};
+use anyhow::{Context, Result};
+use futures_lite::StreamExt;
+use iroh::{EndpointAddr, EndpointId};
+use iroh_docs::NamespaceId;
+use iroh_docs::api::Doc;
+use iroh_docs::engine::LiveEvent;
+use iroh_docs::protocol::Docs;
+use iroh_docs::store::Query;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// Delay after a local write before syncing, so bursts of writes share one sync.
+const WRITE_DELAY: Duration = Duration::from_secs(1);
+
+/// Delay before the first sync retry, doubled after every attempt.
+const RETRY_MIN: Duration = Duration::from_secs(5);
+
+/// Longest delay between sync retries.
+const RETRY_MAX: Duration = Duration::from_secs(300);
+
+/// How far a document's local writes are backed up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DurableStatus {
+    /// Each durable peer, sorted, with the time before which it confirmed
+    /// every local write (microseconds since epoch, 0 if it never synced).
+    pub peers: Vec<(EndpointId, u64)>,
+    /// Time before which every durable peer confirmed every local write
+    /// (microseconds since epoch, 0 if one never synced or there are none).
+    pub confirmed_through: u64,
+    /// Time of the latest local write (microseconds since epoch, 0 if none).
+    pub last_local_write: u64,
+}
+
+impl DurableStatus {
+    /// Whether there are durable peers and all of them have every local write.
+    pub fn is_backed_up(&self) -> bool {
+        !self.peers.is_empty() && self.last_local_write < self.confirmed_through
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Durable peers of each document and what they confirmed.
+    docs: BTreeMap<NamespaceId, BTreeMap<EndpointId, u64>>,
+    /// Documents with a watch task running, and how to stop it.
+    watching: HashMap<NamespaceId, CancellationToken>,
+}
+
+/// Durable peer designations and the tasks syncing with them.
+#[derive(Debug, Clone)]
+pub struct DurablePeers {
+    path: PathBuf,
+    events: EventBus,
+    state: Arc<Mutex<State>>,
+}
+
+impl DurablePeers {
+    /// Load the designations stored at `path`, or start with none.
+    ///
+    /// Unreadable records are skipped rather than keeping the node from
+    /// starting.
+    pub fn load(path: PathBuf, events: EventBus) -> Result<Self> {
+        let mut state = State::default();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                for (namespace, peer, confirmed) in contents.lines().filter_map(parse_record) {
+                    state
+                        .docs
+                        .entry(namespace)
+                        .or_default()
+                        .insert(peer, confirmed);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context("Failed to read durable peers"),
+        }
+        Ok(Self {
+            path,
+            events,
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Replace the durable peers of `namespace`; none stops tracking it.
+    ///
+    /// Peers that stay keep what they confirmed. Must be called from within
+    /// the node's runtime.
+    pub fn set(&self, docs: &Docs, namespace: NamespaceId, peers: &[EndpointId]) -> Result<()> {
+        {
+            let mut state = self.state.lock().unwrap();
+            let previous = state.docs.remove(&namespace);
+            if !peers.is_empty() {
+                let confirmed = |peer: &EndpointId| {
+                    previous
+                        .as_ref()
+                        .and_then(|p| p.get(peer).copied())
+                        .unwrap_or(0)
+                };
+                let peers = peers.iter().map(|p| (*p, confirmed(p))).collect();
+                state.docs.insert(namespace, peers);
+            }
+            if let Err(e) = self.persist(&state) {
+                match previous {
+                    Some(previous) => state.docs.insert(namespace, previous),
+                    None => state.docs.remove(&namespace),
+                };
+                return Err(e);
+            }
+            if peers.is_empty() {
+                if let Some(cancel) = state.watching.remove(&namespace) {
+                    cancel.cancel();
+                }
+                return Ok(());
+            }
+        }
+        self.watch(docs, namespace);
+        Ok(())
+    }
+
+    /// Durable peers of `namespace` and what they confirmed, sorted.
+    pub fn peers(&self, namespace: NamespaceId) -> Vec<(EndpointId, u64)> {
+        let state = self.state.lock().unwrap();
+        state
+            .docs
+            .get(&namespace)
+            .map(|peers| peers.iter().map(|(p, c)| (*p, *c)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Time before which every durable peer of `namespace` confirmed every
+    /// local write, or None if it has no durable peers.
+    fn confirmed_through(&self, namespace: NamespaceId) -> Option<u64> {
+        let state = self.state.lock().unwrap();
+        state.docs.get(&namespace)?.values().min().copied()
+    }
+
+    /// How far the local writes of `doc` are backed up.
+    pub async fn status(&self, docs: &Docs, doc: &Doc) -> Result<DurableStatus> {
+        let peers = self.peers(doc.id());
+        Ok(DurableStatus {
+            confirmed_through: peers.iter().map(|(_, c)| *c).min().unwrap_or(0),
+            peers,
+            last_local_write: last_local_write(docs, doc).await?,
+        })
+    }
+
+    /// Write the designations file, replacing it atomically.
+    fn persist(&self, state: &State) -> Result<()> {
+        let mut contents = String::new();
+        for (namespace, peers) in &state.docs {
+            for (peer, confirmed) in peers {
+                contents.push_str(&format!("{namespace} {peer} {confirmed}\n"));
+            }
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, contents).context("Failed to write durable peers")?;
+        std::fs::rename(&tmp, &self.path).context("Failed to write durable peers")
+    }
+
+    /// Start watching `namespace` unless that is already under way.
+    ///
+    /// Must be called from within the node's runtime.
+    fn watch(&self, docs: &Docs, namespace: NamespaceId) {
+        let cancel = {
+            let mut state = self.state.lock().unwrap();
+            if state.watching.contains_key(&namespace) {
+                return;
+            }
+            let cancel = CancellationToken::new();
+            state.watching.insert(namespace, cancel.clone());
+            cancel
+        };
+        let (durable, docs) = (self.clone(), docs.clone());
+        tokio::spawn(async move {
+            // After an error, watching resumes on the next designation or start
+            let _ = durable.run(&docs, namespace, &cancel).await;
+            if !cancel.is_cancelled() {
+                durable.state.lock().unwrap().watching.remove(&namespace);
+            }
+        });
+    }
+
+    /// Start watching every document with durable peers.
+    ///
+    /// Must be called from within the node's runtime.
+    pub fn resume(&self, docs: &Docs) {
+        let namespaces: Vec<NamespaceId> =
+            self.state.lock().unwrap().docs.keys().copied().collect();
+        for namespace in namespaces {
+            self.watch(docs, namespace);
+        }
+    }
+
+    /// Track syncs of `namespace` until it is cancelled or the node shuts down.
+    async fn run(
+        &self,
+        docs: &Docs,
+        namespace: NamespaceId,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let Some(doc) = docs.api().open(namespace).await? else {
+            return Ok(());
+        };
+        let result = self.run_doc(docs, &doc, cancel).await;
+        let _ = doc.close().await;
+        result
+    }
+
+    async fn run_doc(&self, docs: &Docs, doc: &Doc, cancel: &CancellationToken) -> Result<()> {
+        let namespace = doc.id();
+        let mut live = doc.subscribe().await?;
+        let mut node_events = self.events.subscribe();
+        let mut last_local = last_local_write(docs, doc).await?;
+        let mut backoff = RETRY_MIN;
+        let retry = tokio::time::sleep(Duration::ZERO);
+        tokio::pin!(retry);
+
+        loop {
+            let unconfirmed = self
+                .confirmed_through(namespace)
+                .is_some_and(|confirmed| last_local > 0 && last_local >= confirmed);
+            tokio::select! {
+                _ = cancel.cancelled() => return Ok(()),
+                _ = &mut retry, if unconfirmed => {
+                    // A failed attempt is retried after the backoff
+                    let _ = self.request_sync(doc).await;
+                    retry.as_mut().reset(Instant::now() + backoff);
+                    backoff = (backoff * 2).min(RETRY_MAX);
+                }
+                event = live.next() => match event.transpose()? {
+                    None => return Ok(()),
+                    Some(LiveEvent::InsertLocal { entry }) => {
+                        last_local = last_local.max(entry.timestamp());
+                        backoff = RETRY_MIN;
+                        retry.as_mut().reset(Instant::now() + WRITE_DELAY);
+                    }
+                    Some(LiveEvent::SyncFinished(sync)) if sync.result.is_ok() => {
+                        self.confirm(namespace, sync.peer, micros(sync.started), last_local);
+                    }
+                    Some(_) => {}
+                },
+                event = node_events.next() => match event {
+                    // The node is shutting down
+                    None => return Ok(()),
+                    Some(NodeEvent::Connectivity { .. }) => {
+                        backoff = RETRY_MIN;
+                        retry.as_mut().reset(Instant::now());
+                    }
+                    Some(_) => {}
+                },
+            }
+        }
+    }
+
+    /// Record that `peer` confirmed the local writes of `namespace` made
+    /// before `started`, if it is a durable peer.
+    fn confirm(&self, namespace: NamespaceId, peer: EndpointId, started: u64, last_local: u64) {
+        let confirmed_through = {
+            let mut state = self.state.lock().unwrap();
+            let Some(peers) = state.docs.get_mut(&namespace) else {
+                return;
+            };
+            match peers.get_mut(&peer) {
+                Some(confirmed) if *confirmed < started => *confirmed = started,
+                _ => return,
+            }
+            let confirmed_through = peers.values().min().copied().unwrap_or(0);
+            // If this fails the confirmation is repeated by the next sync
+            let _ = self.persist(&state);
+            confirmed_through
+        };
+        self.events.emit(NodeEvent::DurableSync {
+            namespace,
+            peer,
+            confirmed_through,
+            backed_up: last_local < confirmed_through,
+        });
+    }
+
+    /// Ask the durable peers of `doc` to sync.
+    async fn request_sync(&self, doc: &Doc) -> Result<()> {
+        let peers = self
+            .peers(doc.id())
+            .into_iter()
+            .map(|(peer, _)| EndpointAddr::new(peer))
+            .collect();
+        doc.start_sync(peers).await
+    }
+}
+
+/// Time of the latest entry written by any author on this node, deletions
+/// included (microseconds since epoch, 0 if none).
+async fn last_local_write(docs: &Docs, doc: &Doc) -> Result<u64> {
+    let authors = docs.api().author_list().await?;
+    let authors: Vec<_> = authors.try_collect().await?;
+    let mut latest = 0;
+    for author in authors {
+        let entries = doc
+            .get_many(Query::author(author).include_empty())
+            .await
+            .context("Failed to read entries")?;
+        let mut entries = std::pin::pin!(entries);
+        while let Some(entry) = entries.next().await {
+            latest = latest.max(entry?.timestamp());
+        }
+    }
+    Ok(latest)
+}
+
+fn parse_record(line: &str) -> Option<(NamespaceId, EndpointId, u64)> {
+    let mut fields = line.split(' ');
+    let namespace = NamespaceId::from_str(fields.next()?).ok()?;
+    let peer = EndpointId::from_str(fields.next()?).ok()?;
+    let confirmed = fields.next()?.parse().ok()?;
+    Some((namespace, peer, confirmed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::SecretKey;
+    use iroh_docs::NamespaceSecret;
+
+    #[test]
+    fn test_status_backed_up() {
+        let peer = SecretKey::from_bytes(&[5u8; 32]).public();
+        let mut status = DurableStatus {
+            peers: vec![(peer, 20)],
+            confirmed_through: 20,
+            last_local_write: 10,
+        };
+        assert!(status.is_backed_up());
+        status.last_local_write = 20;
+        assert!(!status.is_backed_up());
+        assert!(!DurableStatus::default().is_backed_up());
+    }
+
+    #[test]
+    fn test_designations_survive_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("durable");
+        let namespace = NamespaceSecret::new(&mut rand::rng()).id();
+        let peer = SecretKey::from_bytes(&[5u8; 32]).public();
+
+        let durable = DurablePeers::load(path.clone(), EventBus::default()).unwrap();
+        durable
+            .state
+            .lock()
+            .unwrap()
+            .docs
+            .insert(namespace, BTreeMap::from([(peer, 0)]));
+        durable.confirm(namespace, peer, 42, 10);
+        // Older syncs do not move the confirmation back
+        durable.confirm(namespace, peer, 7, 10);
+
+        let reloaded = DurablePeers::load(path, EventBus::default()).unwrap();
+        assert_eq!(reloaded.peers(namespace), vec![(peer, 42)]);
+        assert_eq!(reloaded.confirmed_through(namespace), Some(42));
+    }
+}
@@ -0,0 +1,60 @@
+//! Verification of signed document entries.
+//!
+//! Every document entry is signed twice: by its author and by the
+//! document's namespace key. Entries received outside of sync, such as ones
+//! exported by another peer and passed along by an app, can be checked here
+//! before they are trusted. The encoding is the postcard form of
+//! [`SignedEntry`] that iroh-docs uses on the wire.
+
+use anyhow::{Context, Result, bail};
+use iroh_docs::{NamespaceId, SignedEntry};
+
+/// Decode and verify a serialized signed entry.
+///
+/// Checks both signatures and, if `namespace` is given, that the entry
+/// belongs to that document.
+pub fn verify(bytes: &[u8], namespace: Option<NamespaceId>) -> Result<SignedEntry> {
+    let entry: SignedEntry = postcard::from_bytes(bytes).context("Malformed signed entry")?;
+    entry.verify(&()).context("Entry signature is invalid")?;
+    entry.validate_empty().context("Entry is invalid")?;
+
+    let entry_namespace = entry.entry().namespace();
+    if let Some(namespace) = namespace
+        && entry_namespace != namespace
+    {
+        bail!("Entry belongs to namespace {entry_namespace}, expected {namespace}");
+    }
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh_docs::{Author, NamespaceSecret, Record};
+
+    #[test]
+    fn test_verify_signed_entry() {
+        let namespace = NamespaceSecret::new(&mut rand::rng());
+        let author = Author::new(&mut rand::rng());
+        let record = Record::new_current(iroh_blobs::Hash::new(b"hello"), 5);
+        let entry = SignedEntry::from_parts(&namespace, &author, b"greeting", record);
+        let bytes = postcard::to_allocvec(&entry).unwrap();
+
+        let verified = verify(&bytes, Some(namespace.id())).unwrap();
+        assert_eq!(verified, entry);
+        assert_eq!(verified.author_bytes(), author.id());
+
+        let other = NamespaceSecret::new(&mut rand::rng());
+        assert!(verify(&bytes, Some(other.id())).is_err());
+
+        // Flipping a byte of the key breaks both signatures
+        let mut tampered = bytes.clone();
+        let key_at = tampered
+            .windows(8)
+            .position(|window| window == b"greeting")
+            .unwrap();
+        tampered[key_at] ^= 1;
+        assert!(verify(&tampered, None).is_err());
+        assert!(verify(b"garbage", None).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Node-wide event bus.
+//!
+//! Activity from several parts of the node (address changes, downloads,
+//! rejected fetches, expiry passes, expired tags, document swarms and
+//! syncs, outbox and durable peer progress, slow operations, and network
+//! conditions) is published on one broadcast channel, so apps can follow
+//! everything through a single subscription instead of wiring each source
+//! separately.
+
+use crate::network::NetworkCondition;
+#[cfg(feature = "docs")]
+use crate::outbox::OutboxStatus;
+#[cfg(feature = "docs")]
+use crate::swarm::PeerSyncStats;
+use crate::trace::{self, SlowOperation};
+use futures_lite::StreamExt;
+use futures_lite::stream::{self, Boxed};
+#[cfg(feature = "docs")]
+use iroh::PublicKey;
+use iroh::{Endpoint, EndpointId, RelayUrl, Watcher};
+use iroh_blobs::Hash;
+#[cfg(feature = "docs")]
+use iroh_docs::NamespaceId;
+#[cfg(feature = "docs")]
+use iroh_docs::engine::LiveEvent;
+use std::net::SocketAddr;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+
+/// Events buffered per subscriber before the oldest are dropped.
+const EVENT_BUFFER: usize = 256;
+
+/// Something that happened on the node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeEvent {
+    /// The home relay or the direct addresses changed.
+    Connectivity {
+        /// Relay this node is reachable through, if any.
+        relay_url: Option<RelayUrl>,
+        /// Direct addresses, sorted.
+        direct_addrs: Vec<SocketAddr>,
+    },
+    /// A download started.
+    DownloadStarted {
+        hash: Hash,
+        /// FFI operation that started the download, if any.
+        operation: Option<u64>,
+    },
+    /// A download ended.
+    DownloadFinished {
+        hash: Hash,
+        /// FFI operation that started the download, if any.
+        operation: Option<u64>,
+        /// Why the download failed, or None if it succeeded.
+        error: Option<String>,
+    },
+    /// A peer was refused a blob fetch by the access list.
+    FetchRejected { peer: EndpointId },
+    /// A peer's blob request was refused by the request limits.
+    FetchLimited { peer: EndpointId },
+    /// A pass removing expired document entries finished.
+    #[cfg(feature = "docs")]
+    ExpiredPruned {
+        /// Number of entries removed.
+        removed: u64,
+    },
+    /// A peer joined a document's swarm.
+    #[cfg(feature = "docs")]
+    NeighborUp {
+        namespace: NamespaceId,
+        peer: PublicKey,
+    },
+    /// A peer left a document's swarm.
+    #[cfg(feature = "docs")]
+    NeighborDown {
+        namespace: NamespaceId,
+        peer: PublicKey,
+    },
+    /// A document sync with a peer ended.
+    #[cfg(feature = "docs")]
+    SyncFinished {
+        namespace: NamespaceId,
+        peer: PublicKey,
+        /// Why the sync failed, or None if it succeeded.
+        error: Option<String>,
+    },
+    /// A document sync with a peer ended, changing the peer's sync statistics.
+    #[cfg(feature = "docs")]
+    PeerSync {
+        namespace: NamespaceId,
+        stats: PeerSyncStats,
+    },
+    /// An outbox item was queued or settled.
+    #[cfg(feature = "docs")]
+    Outbox {
+        /// Outbox item ID.
+        id: u64,
+        namespace: NamespaceId,
+        key: Vec<u8>,
+        status: OutboxStatus,
+    },
+    /// A durable peer confirmed a document's local writes.
+    #[cfg(feature = "docs")]
+    DurableSync {
+        namespace: NamespaceId,
+        peer: PublicKey,
+        /// Time before which every durable peer confirmed every local write
+        /// (microseconds since epoch, 0 if one never synced).
+        confirmed_through: u64,
+        /// Whether every durable peer has every local write.
+        backed_up: bool,
+    },
+    /// An operation exceeded the watchdog's threshold.
+    SlowOperation(SlowOperation),
+    /// A time-limited tag expired and was removed.
+    TagExpired {
+        /// Name of the removed tag.
+        name: String,
+        /// Hash the tag referenced.
+        hash: Hash,
+    },
+    /// The content announcer republished this node's discovery records.
+    ContentAnnounced {
+        /// Number of tags announced.
+        tags: u64,
+    },
+    /// A network report showed a different network condition.
+    NetworkCondition(NetworkCondition),
+    /// Garbage collection of a profile's content started.
+    GcStarted {
+        profile: String,
+        /// Blobs no tag references anymore, which the collection deletes
+        /// unless they are referenced again meanwhile.
+        blobs: u64,
+        /// Stored size of those blobs.
+        size: u64,
+    },
+    /// Garbage collection of a profile's content finished.
+    GcFinished {
+        profile: String,
+        /// Blobs deleted.
+        blobs: u64,
+        /// Stored size of the deleted blobs.
+        size: u64,
+    },
+}
+
+/// Broadcasts [`NodeEvent`]s to every subscriber.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<NodeEvent>,
+    /// Ends every subscription when the node shuts down.
+    closed: CancellationToken,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(EVENT_BUFFER).0,
+            closed: CancellationToken::new(),
+        }
+    }
+}
+
+impl EventBus {
+    /// Publish `event`; dropped if nobody is subscribed.
+    pub fn emit(&self, event: NodeEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Number of events some subscriber has yet to receive.
+    pub fn queued(&self) -> usize {
+        self.sender.len()
+    }
+
+    /// Events published from now on, until the bus is closed.
+    ///
+    /// Slow subscribers skip events they fell too far behind on.
+    pub fn subscribe(&self) -> Boxed<NodeEvent> {
+        let closed = self.closed.clone();
+        stream::unfold(self.sender.subscribe(), move |mut rx| {
+            let closed = closed.clone();
+            async move {
+                loop {
+                    tokio::select! {
+                        _ = closed.cancelled() => return None,
+                        next = rx.recv() => match next {
+                            Ok(event) => return Some((event, rx)),
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => return None,
+                        },
+                    }
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// End every subscription.
+    pub fn close(&self) {
+        self.closed.cancel();
+    }
+
+    /// Run `download` for `hash`, publishing when it starts and ends.
+    ///
+    /// A download dropped before completing is reported as failed. Both
+    /// events carry the operation running when the download started.
+    pub async fn download<T, E: std::fmt::Display>(
+        &self,
+        hash: Hash,
+        download: impl IntoFuture<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        let operation = trace::current();
+        trace::stage("download");
+        self.emit(NodeEvent::DownloadStarted { hash, operation });
+        let mut finished = DownloadFinished {
+            events: self,
+            hash,
+            operation,
+            error: Some("Download cancelled".to_string()),
+        };
+        let result = download.await;
+        finished.error = result.as_ref().err().map(|e| format!("{e:#}"));
+        result
+    }
+
+    /// Publish swarm events from a document's live event stream.
+    #[cfg(feature = "docs")]
+    pub fn observe(&self, namespace: NamespaceId, event: &LiveEvent) {
+        let event = match event {
+            LiveEvent::NeighborUp(peer) => NodeEvent::NeighborUp {
+                namespace,
+                peer: *peer,
+            },
+            LiveEvent::NeighborDown(peer) => NodeEvent::NeighborDown {
+                namespace,
+                peer: *peer,
+            },
+            LiveEvent::SyncFinished(sync) => NodeEvent::SyncFinished {
+                namespace,
+                peer: sync.peer,
+                error: sync.result.clone().err(),
+            },
+            _ => return,
+        };
+        self.emit(event);
+    }
+
+    /// Publish connectivity changes of `endpoint` until it closes.
+    pub async fn watch_connectivity(self, endpoint: Endpoint) {
+        let mut last = None;
+        let mut addrs = endpoint.watch_addr().stream();
+        while let Some(addr) = addrs.next().await {
+            let mut direct_addrs: Vec<SocketAddr> = addr.ip_addrs().copied().collect();
+            direct_addrs.sort();
+            let event = NodeEvent::Connectivity {
+                relay_url: addr.relay_urls().next().cloned(),
+                direct_addrs,
+            };
+            if last.as_ref() != Some(&event) {
+                last = Some(event.clone());
+                self.emit(event);
+            }
+        }
+    }
+}
+
+/// Publishes the end of a download, including when it is dropped early.
+struct DownloadFinished<'a> {
+    events: &'a EventBus,
+    hash: Hash,
+    operation: Option<u64>,
+    error: Option<String>,
+}
+
+impl Drop for DownloadFinished<'_> {
+    fn drop(&mut self) {
+        self.events.emit(NodeEvent::DownloadFinished {
+            hash: self.hash,
+            operation: self.operation,
+            error: self.error.take(),
+        });
+    }
+}
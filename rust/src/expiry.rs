@@ -0,0 +1,202 @@
+//! Expiring document entries.
+//!
+//! An entry written with a TTL gets a companion record under [`TTL_PREFIX`]
+//! holding its expiry time. The record is written by the same author right
+//! after the value, so a later write to the key without a TTL is newer than
+//! the record and cancels the expiry.
+
+use crate::filter::Tombstones;
+use anyhow::{Context, Result, bail};
+use futures_lite::StreamExt;
+use iroh_blobs::api::Store;
+use iroh_docs::api::Doc;
+use iroh_docs::protocol::Docs;
+use iroh_docs::store::Query;
+use iroh_docs::{AuthorId, CapabilityKind};
+use std::collections::{HashMap, HashSet};
+
+/// Key prefix for expiry records.
+pub const TTL_PREFIX: &[u8] = b".iroh-ttl/";
+
+/// Key of the expiry record for `key`.
+pub fn record_key(key: &[u8]) -> Vec<u8> {
+    [TTL_PREFIX, key].concat()
+}
+
+/// Encode an expiry time (microseconds since epoch) as a record value.
+pub fn encode_expiry(expires_at: u64) -> Vec<u8> {
+    expires_at.to_string().into_bytes()
+}
+
+/// Decode a record value produced by [`encode_expiry`].
+pub fn decode_expiry(bytes: &[u8]) -> Result<u64> {
+    let text = std::str::from_utf8(bytes)?;
+    match text.parse() {
+        Ok(expires_at) => Ok(expires_at),
+        Err(_) => bail!("malformed expiry record: {text}"),
+    }
+}
+
+/// Tombstone expired keys in every writable document.
+///
+/// Only records written by authors stored on this node are handled; peers
+/// prune the entries of their own authors. Returns the number of keys removed.
+pub async fn prune_expired(
+    docs: &Docs,
+    store: &Store,
+    tombstones: &Tombstones,
+    now: u64,
+) -> Result<u64> {
+    let local_authors = docs
+        .api()
+        .author_list()
+        .await
+        .context("Failed to list authors")?
+        .try_collect::<_, _, HashSet<_>>()
+        .await?;
+
+    let mut pruned = 0;
+    let mut namespaces = docs.api().list().await.context("Failed to list docs")?;
+    while let Some(item) = namespaces.next().await {
+        let (namespace_id, capability) = item?;
+        if !matches!(capability, CapabilityKind::Write) {
+            continue;
+        }
+        let Some(doc) = docs.api().open(namespace_id).await? else {
+            continue;
+        };
+        let result = prune_doc(&doc, store, tombstones, &local_authors, now).await;
+        doc.close().await?;
+        pruned += result?;
+    }
+    Ok(pruned)
+}
+
+/// Expired keys of one author.
+#[derive(Default)]
+struct AuthorExpired {
+    /// Keys to delete: expired values and their records.
+    keys: Vec<Vec<u8>>,
+    /// Which of `keys` are values, not records, with the timestamps of
+    /// their records.
+    values: HashMap<Vec<u8>, u64>,
+}
+
+/// Tombstone expired keys in one document.
+///
+/// Only the expired key and its record are deleted; the author's longer
+/// keys starting with them are left as they are. An expired key stays,
+/// with its record, while the author has longer keys starting with it that
+/// were there before its TTL was set and aren't deleted with it, such as
+/// keys without a TTL; it is deleted on a later prune once they are gone. Keys that would sweep an older key that
+/// is kept likewise stay, without holding up the others.
+async fn prune_doc(
+    doc: &Doc,
+    store: &Store,
+    tombstones: &Tombstones,
+    local_authors: &HashSet<AuthorId>,
+    now: u64,
+) -> Result<u64> {
+    // Collect first so deletions don't race the query stream
+    let records = doc
+        .get_many(Query::key_prefix(TTL_PREFIX))
+        .await?
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+
+    let mut expired: HashMap<AuthorId, AuthorExpired> = HashMap::new();
+    for record in records {
+        let author = record.author();
+        if !local_authors.contains(&author) {
+            continue;
+        }
+        // Content of records synced from other devices may not be local yet
+        let Ok(bytes) = store.get_bytes(record.content_hash()).await else {
+            continue;
+        };
+        let Ok(expires_at) = decode_expiry(&bytes) else {
+            continue;
+        };
+        if expires_at > now {
+            continue;
+        }
+
+        let author_expired = expired.entry(author).or_default();
+        let key = &record.key()[TTL_PREFIX.len()..];
+        if let Some(current) = doc.get_exact(author, key, false).await?
+            && current.timestamp() <= record.timestamp()
+        {
+            author_expired.keys.push(key.to_vec());
+            author_expired
+                .values
+                .insert(key.to_vec(), record.timestamp());
+        }
+        author_expired.keys.push(record.key().to_vec());
+    }
+
+    let mut pruned = 0;
+    for (author, AuthorExpired { mut keys, values }) in expired {
+        // The author's live longer keys under each expired value that are
+        // older than its record
+        let mut nested = HashMap::new();
+        for (key, &set_at) in &values {
+            let longer = doc
+                .get_many(Query::author(author).key_prefix(key))
+                .await?
+                .try_collect::<_, _, Vec<_>>()
+                .await?
+                .into_iter()
+                .filter(|e| e.key() != key.as_slice() && e.timestamp() < set_at)
+                .map(|e| e.key().to_vec())
+                .collect::<Vec<_>>();
+            nested.insert(key.clone(), longer);
+        }
+        // Keeping a key may in turn keep a shorter one
+        loop {
+            let deleted = keys.iter().cloned().collect::<HashSet<_>>();
+            let kept = values
+                .keys()
+                .filter(|key| deleted.contains(*key))
+                .filter(|key| nested[*key].iter().any(|k| !deleted.contains(k)))
+                .flat_map(|key| [key.clone(), record_key(key)])
+                .collect::<HashSet<_>>();
+            if kept.is_empty() {
+                break;
+            }
+            keys.retain(|key| !kept.contains(key));
+        }
+
+        let mut deletion = tombstones.plan(doc, author, keys.clone(), 0).await?;
+        // A key that can't be deleted keeps its record, so it expires on a
+        // later prune
+        let kept = deletion
+            .blocked
+            .iter()
+            .filter(|key| values.contains_key(*key))
+            .map(|key| record_key(key))
+            .collect::<HashSet<_>>();
+        if !kept.is_empty() {
+            keys.retain(|key| !kept.contains(key));
+            deletion = tombstones.plan(doc, author, keys, 0).await?;
+        }
+        tombstones.apply(doc, author, &deletion).await?;
+        pruned += deletion
+            .keys()
+            .filter(|key| values.contains_key(*key))
+            .count() as u64;
+    }
+    Ok(pruned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry_roundtrip() {
+        let bytes = encode_expiry(1_700_000_000_000_000);
+        assert_eq!(decode_expiry(&bytes).unwrap(), 1_700_000_000_000_000);
+        assert!(decode_expiry(b"soon").is_err());
+        assert_eq!(record_key(b"invites/1"), b".iroh-ttl/invites/1".to_vec());
+    }
+}
@@ -0,0 +1,149 @@
+//! Injected faults for exercising error paths in tests.
+//!
+//! Retry and error handling in the app is hard to test against a healthy
+//! local network. With the `faults` feature, a test can make a node fail
+//! its next puts, delay its downloads, or drop a share of the document
+//! syncs peers start with it, then check how the app copes.
+
+// Without documents there are no syncs to drop
+#![cfg_attr(not(feature = "docs"), allow(dead_code))]
+
+use anyhow::Result;
+use iroh::endpoint::{Connection, VarInt};
+use iroh::protocol::{AcceptError, ProtocolHandler};
+use rand::Rng;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// QUIC close code for a connection dropped by an injected fault.
+const CLOSED_BY_FAULT: u32 = 0x46;
+
+/// Faults to inject into a node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultConfig {
+    /// Number of upcoming puts that fail.
+    pub fail_puts: u64,
+    /// Time each download waits before it starts.
+    pub download_delay: Duration,
+    /// Percentage of document syncs started by peers that are dropped.
+    pub sync_drop_percent: u8,
+}
+
+/// Faults currently injected into a node.
+#[derive(Debug, Default)]
+pub struct Faults {
+    fail_puts: AtomicU64,
+    download_delay_ms: AtomicU64,
+    sync_drop_percent: AtomicU8,
+}
+
+impl Faults {
+    /// Replace the injected faults with `config`.
+    pub fn set(&self, config: FaultConfig) -> Result<()> {
+        anyhow::ensure!(
+            config.sync_drop_percent <= 100,
+            "Sync drop percentage must be at most 100"
+        );
+        self.fail_puts.store(config.fail_puts, Ordering::Relaxed);
+        self.download_delay_ms
+            .store(config.download_delay.as_millis() as u64, Ordering::Relaxed);
+        self.sync_drop_percent
+            .store(config.sync_drop_percent, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Fail if a put failure is pending, using it up.
+    pub fn check_put(&self) -> Result<()> {
+        let pending = self
+            .fail_puts
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+        anyhow::ensure!(pending.is_err(), "Injected put failure");
+        Ok(())
+    }
+
+    /// Wait for the injected download delay.
+    pub async fn delay_download(&self) {
+        let delay = self.download_delay_ms.load(Ordering::Relaxed);
+        if delay > 0 {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+    }
+
+    /// Whether to drop the sync a peer is starting.
+    fn drop_sync(&self) -> bool {
+        let percent = self.sync_drop_percent.load(Ordering::Relaxed);
+        percent > 0 && rand::rng().random_range(0..100) < percent
+    }
+
+    /// Wrap the document sync `handler` so it drops syncs as injected.
+    pub fn wrap_sync<P: ProtocolHandler>(self: &Arc<Self>, handler: P) -> FaultySync<P> {
+        FaultySync {
+            inner: handler,
+            faults: self.clone(),
+        }
+    }
+}
+
+/// Protocol handler that drops a share of incoming document syncs.
+#[derive(Debug)]
+pub struct FaultySync<P> {
+    inner: P,
+    faults: Arc<Faults>,
+}
+
+impl<P: ProtocolHandler> ProtocolHandler for FaultySync<P> {
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        if self.faults.drop_sync() {
+            connection.close(VarInt::from_u32(CLOSED_BY_FAULT), b"injected fault");
+            return Err(AcceptError::from_err(std::io::Error::other(
+                "Injected sync drop",
+            )));
+        }
+        self.inner.accept(connection).await
+    }
+
+    async fn shutdown(&self) {
+        self.inner.shutdown().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_failures_are_used_up() {
+        let faults = Faults::default();
+        assert!(faults.check_put().is_ok());
+
+        faults
+            .set(FaultConfig {
+                fail_puts: 2,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(faults.check_put().is_err());
+        assert!(faults.check_put().is_err());
+        assert!(faults.check_put().is_ok());
+    }
+
+    #[test]
+    fn test_sync_drop_percent() {
+        let faults = Faults::default();
+        assert!(!faults.drop_sync());
+
+        let drop_all = FaultConfig {
+            sync_drop_percent: 100,
+            ..Default::default()
+        };
+        faults.set(drop_all).unwrap();
+        assert!((0..100).all(|_| faults.drop_sync()));
+
+        let too_many = FaultConfig {
+            sync_drop_percent: 101,
+            ..Default::default()
+        };
+        assert!(faults.set(too_many).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Content of remote inserts delivered with document events.
+//!
+//! A remote insert only carries the hash of its value. The docs engine
+//! downloads the content in the background for entries matching the
+//! document's download policy and later reports the hash as ready, so every
+//! consumer that wants values has to pair the two events and read the store.
+//! A subscription that fetches content does this once and hands out each
+//! remote entry together with its value.
+
+use anyhow::Result;
+use iroh::PublicKey;
+use iroh_blobs::Hash;
+use iroh_blobs::api::Store;
+use iroh_docs::api::Doc;
+use iroh_docs::engine::LiveEvent;
+use iroh_docs::store::DownloadPolicy;
+use iroh_docs::{ContentStatus, Entry};
+use std::collections::HashMap;
+
+/// A remote entry whose content is available locally.
+pub struct Fetched {
+    /// Peer that sent the entry.
+    pub from: PublicKey,
+    /// The entry.
+    pub entry: Entry,
+    /// Content of the entry.
+    pub content: Vec<u8>,
+}
+
+/// Pairs remote inserts of one document with their content.
+pub struct ContentFetch {
+    store: Store,
+    /// Download policy of the document when the subscription started.
+    policy: DownloadPolicy,
+    /// Largest content delivered; larger entries are left to the app.
+    max_size: Option<u64>,
+    /// Remote entries waiting for their content, by hash.
+    pending: HashMap<Hash, Vec<(PublicKey, Entry)>>,
+}
+
+impl ContentFetch {
+    /// Start fetching content for remote inserts into `doc`.
+    pub async fn new(doc: &Doc, store: Store, max_size: Option<u64>) -> Result<Self> {
+        Ok(Self {
+            store,
+            policy: doc.get_download_policy().await?,
+            max_size,
+            pending: HashMap::new(),
+        })
+    }
+
+    /// Remote entries whose content `event` made available.
+    ///
+    /// Entries outside the download policy or above the size limit are
+    /// skipped, as are entries whose content could not be read; the engine
+    /// does not download them, or the app can still read them itself.
+    pub async fn observe(&mut self, event: &LiveEvent) -> Vec<Fetched> {
+        let waiting = match event {
+            LiveEvent::InsertRemote {
+                from,
+                entry,
+                content_status,
+            } => {
+                if !self.policy.matches(entry)
+                    || self.max_size.is_some_and(|max| entry.content_len() > max)
+                {
+                    return Vec::new();
+                }
+                if !matches!(content_status, ContentStatus::Complete) {
+                    self.pending
+                        .entry(entry.content_hash())
+                        .or_default()
+                        .push((*from, entry.clone()));
+                    return Vec::new();
+                }
+                vec![(*from, entry.clone())]
+            }
+            LiveEvent::ContentReady { hash } => self.pending.remove(hash).unwrap_or_default(),
+            _ => return Vec::new(),
+        };
+        let Some((_, first)) = waiting.first() else {
+            return Vec::new();
+        };
+        // Deletions are empty entries, whose content is never stored
+        let content = if first.content_len() == 0 {
+            Vec::new()
+        } else {
+            match self.store.get_bytes(first.content_hash()).await {
+                Ok(bytes) => bytes.to_vec(),
+                Err(_) => return Vec::new(),
+            }
+        };
+        waiting
+            .into_iter()
+            .map(|(from, entry)| Fetched {
+                from,
+                entry,
+                content: content.clone(),
+            })
+            .collect()
+    }
+}
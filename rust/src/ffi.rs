@@ -4,15 +4,77 @@
 //! All functions use callback-based async patterns to integrate with
 //! Swift's concurrency model.
 
-use crate::node::IrohNode;
+use crate::access::{AccessMode, AccessRule};
+use crate::announce::{ContentAnnouncer, OnAnnounce};
+use crate::bandwidth::{BandwidthCaps, BandwidthLimits, NetworkKind};
+use crate::bundle::{self, TicketKind};
+#[cfg(feature = "chunking")]
+use crate::chunking::ChunkStats;
+use crate::config;
+#[cfg(feature = "docs")]
+use crate::cursor::Cursor;
+use crate::delivery::Delivery;
+use crate::discovery::{AppDiscovery, Publish, Resolve};
+use crate::drain;
+#[cfg(feature = "docs")]
+use crate::entry;
+use crate::events::NodeEvent;
+#[cfg(feature = "docs")]
+use crate::fetch::{ContentFetch, Fetched};
+#[cfg(feature = "docs")]
+use crate::filter::{Decide, FilteredEntry, OnFiltered, Verdict, WritePolicy};
+use crate::handles::{self, HandleKind};
+use crate::limits::RequestLimits;
+use crate::logging;
+use crate::metadata::{BlobMetadata, Compression};
+use crate::network::NetworkCondition;
+use crate::node::{
+    Availability, BlobSort, DEFAULT_HEALTH_CHECK_TIMEOUT, IrohNode, NodeOptions, ProviderStrategy,
+    Reachability, TagListing, TempFile, TimedOut,
+};
+#[cfg(feature = "docs")]
+use crate::node::{CasOutcome, DocQuery, Durability};
+#[cfg(feature = "downloader-progress")]
+use crate::node::{DownloadProgress, DownloadStage};
+#[cfg(feature = "docs")]
+use crate::outbox::OutboxStatus;
+use crate::passphrase;
+use crate::pending::{self, PendingWork};
+use crate::pool;
+use crate::profiles::{ProfileStats, Reclaimed};
+use crate::recovery::{BlobStoreRecovery, DocsStoreRecovery, StoreCorrupt, StoreDatabase};
+#[cfg(feature = "docs")]
+use crate::resolver::ConflictCandidate;
+#[cfg(feature = "docs")]
+use crate::signing;
+use crate::stats;
+use crate::storage::{DEFAULT_STORAGE_CHECK_INTERVAL, StorageAlert, StoreInlining};
+use crate::subscriptions::CancelSwitch;
+#[cfg(feature = "docs")]
+use crate::testing;
+use crate::ticket::{self, TicketEncoding};
+use crate::trace::{self, SlowOperation};
+use crate::watchdog::{OnSlow, Watchdog};
+use iroh::{EndpointAddr, EndpointId, RelayUrl, SecretKey, TransportAddr};
+use iroh_blobs::api::TempTag;
 use iroh_blobs::ticket::BlobTicket;
 use iroh_blobs::{BlobFormat, Hash, HashAndFormat};
-use iroh_docs::Author;
-use iroh_docs::DocTicket;
-use iroh_docs::api::Doc;
-use iroh_docs::api::protocol::{AddrInfoOptions, ShareMode};
+#[cfg(feature = "docs")]
+use iroh_docs::{
+    Author, AuthorId, CapabilityKind, DocTicket, NamespaceId,
+    api::Doc,
+    api::protocol::{AddrInfoOptions, ShareMode},
+};
+#[cfg(feature = "docs")]
+use std::collections::HashSet;
 use std::ffi::{CStr, CString, c_char, c_void};
+use std::net::SocketAddr;
 use std::path::PathBuf;
+#[cfg(feature = "docs")]
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
 
 // ============================================================================
 // Types
@@ -33,10 +95,253 @@ pub struct IrohOwnedBytes {
     pub capacity: usize,
 }
 
+/// Content hash (32 bytes).
+///
+/// The binary form of the 64-character hex hashes used elsewhere; convert
+/// with `iroh_hash_to_hex` and `iroh_hash_from_hex`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct IrohHash {
+    pub bytes: [u8; 32],
+}
+
+impl From<Hash> for IrohHash {
+    fn from(hash: Hash) -> Self {
+        Self {
+            bytes: *hash.as_bytes(),
+        }
+    }
+}
+
+impl From<IrohHash> for Hash {
+    fn from(hash: IrohHash) -> Self {
+        Hash::from_bytes(hash.bytes)
+    }
+}
+
+/// Caller-provided entropy for key generation.
+///
+/// When `fill` is null, keys come from the system RNG.
+#[repr(C)]
+pub struct IrohEntropySource {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Fill `len` bytes at `buf` and return true, or return false to fail
+    /// key generation. Called synchronously on the calling thread.
+    pub fill: Option<extern "C" fn(userdata: *mut c_void, buf: *mut u8, len: usize) -> bool>,
+}
+
+/// This node's addresses, as passed to `IrohDiscoveryProvider::publish`.
+#[repr(C)]
+pub struct IrohNodeAddr {
+    /// Node ID.
+    pub node_id: *const c_char,
+    /// Home relay URL, or null if none.
+    pub relay_url: *const c_char,
+    /// Direct addresses as "ip:port" strings.
+    pub direct_addrs: *const *const c_char,
+    /// Number of direct addresses.
+    pub direct_addrs_len: usize,
+}
+
+/// Opaque handle to a pending discovery lookup.
+///
+/// Answer it with `iroh_discovery_request_complete`.
+#[repr(C)]
+pub struct IrohDiscoveryRequest {
+    _private: [u8; 0],
+}
+
+/// App-provided discovery, used next to or instead of n0's DNS/pkarr discovery.
+///
+/// Leave `resolve` and `publish` null to use only the default discovery.
+#[repr(C)]
+pub struct IrohDiscoveryProvider {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Look up a node's addresses. Must answer `request` exactly once with
+    /// `iroh_discovery_request_complete`, from any thread.
+    pub resolve: Option<
+        extern "C" fn(
+            userdata: *mut c_void,
+            node_id: *const c_char,
+            request: *mut IrohDiscoveryRequest,
+        ),
+    >,
+    /// Publish this node's addresses whenever they change. The strings are
+    /// only valid for the duration of the call.
+    pub publish: Option<extern "C" fn(userdata: *mut c_void, addr: IrohNodeAddr)>,
+    /// Called once when the node no longer uses the provider, including
+    /// when node creation fails.
+    pub release: Option<extern "C" fn(userdata: *mut c_void)>,
+}
+
+/// Alert when the store's size on disk crosses a threshold.
+///
+/// Leave `on_threshold` null to disable alerts.
+#[repr(C)]
+pub struct IrohStorageAlert {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Size in bytes at which the store counts as full.
+    pub threshold_bytes: u64,
+    /// Milliseconds between measurements (0 = every 60 seconds).
+    pub check_interval_ms: u64,
+    /// Called on a background thread with `exceeded` true when the store
+    /// reaches `threshold_bytes`, and false when it drops below again.
+    pub on_threshold: Option<extern "C" fn(userdata: *mut c_void, exceeded: bool, size_bytes: u64)>,
+    /// Called once when the node no longer uses the alert, including when
+    /// node creation fails.
+    pub release: Option<extern "C" fn(userdata: *mut c_void)>,
+}
+
+/// An operation that has run longer than the watchdog's threshold.
+///
+/// Strings are only valid for the duration of the callback.
+#[repr(C)]
+pub struct IrohSlowOperation {
+    /// The operation's id (see `iroh_last_operation_id`).
+    pub operation_id: u64,
+    /// Name of the FFI function that started it, like "iroh_get".
+    pub name: *const c_char,
+    /// What it was doing, like "download".
+    pub stage: *const c_char,
+    /// Milliseconds it had been running.
+    pub elapsed_ms: u64,
+}
+
+/// Report operations that run longer than a threshold.
+///
+/// Set `threshold_ms` to 0 to disable the watchdog.
+#[repr(C)]
+pub struct IrohSlowOperationWatchdog {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Milliseconds after which an operation counts as slow.
+    pub threshold_ms: u64,
+    /// Whether slow operations are also published as node events.
+    pub emit_events: bool,
+    /// Called on a background thread once for each slow operation (null for none).
+    pub on_slow: Option<extern "C" fn(userdata: *mut c_void, operation: IrohSlowOperation)>,
+    /// Called once when the node no longer uses the watchdog, including
+    /// when node creation fails.
+    pub release: Option<extern "C" fn(userdata: *mut c_void)>,
+}
+
+/// Periodically republish the node's discovery records and announce
+/// selected content.
+///
+/// Set `interval_ms` to 0 to disable announcements.
+#[repr(C)]
+pub struct IrohContentAnnouncer {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Milliseconds between rounds. Rounds also run when the node's
+    /// addresses change.
+    pub interval_ms: u64,
+    /// Prefix of the tags whose content is announced (null for every tag).
+    pub tag_prefix: *const c_char,
+    /// Called on a background thread for each announced tag with a ticket
+    /// for its content at the node's current addresses (null for none). The
+    /// strings are only valid for the duration of the call.
+    pub on_announce:
+        Option<extern "C" fn(userdata: *mut c_void, tag: *const c_char, ticket: *const c_char)>,
+    /// Called once when the node no longer uses the announcer, including
+    /// when node creation fails.
+    pub release: Option<extern "C" fn(userdata: *mut c_void)>,
+}
+
+/// Per-peer limits on blob requests served by the node.
+///
+/// Each field is 0 for no limit.
+#[repr(C)]
+pub struct IrohRequestLimits {
+    /// Requests a peer may start per second, with bursts of up to one
+    /// second's worth.
+    pub requests_per_sec: u32,
+    /// Transfers a peer may have in progress at once.
+    pub max_concurrent: u32,
+    /// Bytes per second sent to a peer across all its transfers.
+    pub bytes_per_sec: u64,
+}
+
+impl From<&IrohRequestLimits> for RequestLimits {
+    fn from(limits: &IrohRequestLimits) -> Self {
+        Self {
+            requests_per_sec: (limits.requests_per_sec > 0).then_some(limits.requests_per_sec),
+            max_concurrent: (limits.max_concurrent > 0).then_some(limits.max_concurrent),
+            bytes_per_sec: (limits.bytes_per_sec > 0).then_some(limits.bytes_per_sec),
+        }
+    }
+}
+
+/// Node-wide transfer bandwidth caps on Wi-Fi and cellular, applied to
+/// blobs served to peers and to downloads the node starts. Document
+/// content the docs engine downloads while syncing is not download capped.
+///
+/// Each field is 0 for no cap.
+#[repr(C)]
+pub struct IrohBandwidthLimits {
+    /// Bytes per second sent to peers on Wi-Fi.
+    pub wifi_upload_bytes_per_sec: u64,
+    /// Bytes per second received on Wi-Fi.
+    pub wifi_download_bytes_per_sec: u64,
+    /// Bytes per second sent to peers on cellular.
+    pub cellular_upload_bytes_per_sec: u64,
+    /// Bytes per second received on cellular.
+    pub cellular_download_bytes_per_sec: u64,
+}
+
+impl From<&IrohBandwidthLimits> for BandwidthLimits {
+    fn from(limits: &IrohBandwidthLimits) -> Self {
+        let cap = |rate: u64| (rate > 0).then_some(rate);
+        Self {
+            wifi: BandwidthCaps {
+                upload_bytes_per_sec: cap(limits.wifi_upload_bytes_per_sec),
+                download_bytes_per_sec: cap(limits.wifi_download_bytes_per_sec),
+            },
+            cellular: BandwidthCaps {
+                upload_bytes_per_sec: cap(limits.cellular_upload_bytes_per_sec),
+                download_bytes_per_sec: cap(limits.cellular_download_bytes_per_sec),
+            },
+        }
+    }
+}
+
+/// Size limits for keeping blobs inside the store's database instead of
+/// files of their own.
+///
+/// Each field is 0 for the default of 16 KiB. Only content stored after the
+/// node starts is affected.
+#[repr(C)]
+pub struct IrohStoreInlining {
+    /// Largest blob kept in the database. Raise it when storing many tiny
+    /// values to create fewer files; lower it, down to 1, to keep media in
+    /// files it can be streamed from.
+    pub max_inline_data: u64,
+    /// Largest outboard (verification data, 64 bytes per 16 KiB of blob)
+    /// kept in the database.
+    pub max_inline_outboard: u64,
+}
+
+impl From<&IrohStoreInlining> for StoreInlining {
+    fn from(inlining: &IrohStoreInlining) -> Self {
+        Self {
+            max_data: (inlining.max_inline_data > 0).then_some(inlining.max_inline_data),
+            max_outboard: (inlining.max_inline_outboard > 0)
+                .then_some(inlining.max_inline_outboard),
+        }
+    }
+}
+
 /// Configuration for creating a node.
+///
+/// Fields are added as options are, so the struct must come from the header
+/// shipped with this build of the library. Callers that load the library
+/// separately from its header should use `iroh_node_create_from_json`.
 #[repr(C)]
 pub struct IrohNodeConfig {
-    /// Path to the blob store directory (required).
+    /// Path to the blob store directory (required unless `ephemeral`).
     pub storage_path: *const c_char,
     /// Whether to use relay servers (default: true).
     pub relay_enabled: bool,
@@ -46,6 +351,91 @@ pub struct IrohNodeConfig {
     /// Whether to enable the Docs engine (default: false).
     /// When enabled, the node can create, join, and sync documents.
     pub docs_enabled: bool,
+    /// Directory for crate-managed temporary files (null to use the system temp dir).
+    pub temp_dir: *const c_char,
+    /// Interval in milliseconds between background passes that remove
+    /// expired document entries (0 = only prune on demand).
+    pub ttl_prune_interval_ms: u64,
+    /// Entropy for the node's secret key (null `fill` to use the system RNG).
+    pub entropy: IrohEntropySource,
+    /// TLS session tickets to cache for resuming sessions (0 = default of 256).
+    pub max_tls_tickets: u32,
+    /// Milliseconds a silent connection survives before it is dropped
+    /// (0 = default of 30 seconds).
+    pub idle_timeout_ms: u64,
+    /// Maximum number of connections served to peers at once (0 = unlimited).
+    /// When full, the connection idle the longest is closed to admit a new one.
+    pub max_connections: u32,
+    /// App-provided discovery (null callbacks for none).
+    pub discovery: IrohDiscoveryProvider,
+    /// Whether to skip n0's DNS/pkarr discovery (default: false).
+    pub disable_default_discovery: bool,
+    /// Storage threshold alert (null `on_threshold` for none).
+    pub storage_alert: IrohStorageAlert,
+    /// OTLP/HTTP collector to export operation spans and metrics to, like
+    /// "https://otel.example.com:4318" (null for none). Export is shared by
+    /// all nodes in the process and requires the `otel` feature.
+    pub otlp_endpoint: *const c_char,
+    /// Slow operation watchdog (0 `threshold_ms` for none).
+    pub slow_operation_watchdog: IrohSlowOperationWatchdog,
+    /// Debug control server address: a loopback "ip:port" or a Unix socket
+    /// path (null for none). Serves node info, blobs, docs and transfers
+    /// over line-delimited JSON-RPC and requires the `control` feature.
+    pub control_socket: *const c_char,
+    /// HTTPS gateway URL serving content by hash (null for none). `iroh_get`
+    /// fetches raw blobs from it, verified, when peers cannot be reached.
+    pub gateway_url: *const c_char,
+    /// Who may fetch blobs before any peer rules are set (default: open).
+    pub blob_access: IrohBlobAccessMode,
+    /// Per-peer limits on blob requests (all zero for none). Refused
+    /// requests are reported as `NodeFetchLimited` events.
+    pub request_limits: IrohRequestLimits,
+    /// Whether to run background callbacks on one dedicated thread
+    /// (default: false). Subscription events, conflict resolution, write
+    /// filters, discovery, storage alerts and slow operation reports then
+    /// run one at a time, in the order they were made, instead of on
+    /// whichever worker thread produced them. Callbacks should return
+    /// quickly and must not wait for other node operations, since those may
+    /// need the same thread. Operation callbacks are unaffected: they always
+    /// run on the calling thread before the operation returns.
+    pub dedicated_callback_thread: bool,
+    /// Identifier of the app running the node, like "com.example.photos/2.1"
+    /// (null for none). Attached to exported telemetry, reported by node
+    /// info and the control server, and published with the node's discovery
+    /// records so peers can tell which app a node belongs to. At most 245
+    /// bytes, without control characters.
+    pub app_id: *const c_char,
+    /// Which blobs the store keeps in its database (all zero for defaults).
+    pub store_inlining: IrohStoreInlining,
+    /// Periodic re-announcement of content (0 `interval_ms` for none).
+    pub content_announcer: IrohContentAnnouncer,
+    /// Seed of a deterministic test node (null for a normal node). The
+    /// node's key derives from the seed; it binds to loopback only, uses
+    /// neither relays nor network discovery, and is found by the other test
+    /// nodes of the process. Its tickets leave out addresses, so they only
+    /// depend on the seed and the content. Blobs and documents are kept in
+    /// memory. `entropy` must be unset.
+    pub test_seed: *const c_char,
+    /// Worker threads of the node's runtime (0 = default: 2 on iOS and
+    /// Android, one per core elsewhere).
+    pub worker_threads: u32,
+    /// Blocking threads the node's runtime may start for file I/O (0 =
+    /// default: 16 on iOS and Android, 512 elsewhere).
+    pub max_blocking_threads: u32,
+    /// What to do when the blob store database is found corrupt at startup
+    /// (default: fail with `on_corrupt`).
+    pub blob_store_recovery: IrohBlobStoreRecovery,
+    /// What to do when the docs database is found corrupt at startup
+    /// (default: fail with `on_corrupt`).
+    pub docs_store_recovery: IrohDocsStoreRecovery,
+    /// Keep all data in a fresh directory under `temp_dir`, removed when
+    /// the node is closed or destroyed (default: false). `storage_path` is
+    /// not used and may be null.
+    pub ephemeral: bool,
+    /// Transfer bandwidth caps on Wi-Fi and cellular (all zero for none).
+    /// The node starts on Wi-Fi; tell it about network changes with
+    /// `iroh_node_set_network_kind`.
+    pub bandwidth_limits: IrohBandwidthLimits,
 }
 
 /// Options for put/get operations.
@@ -64,6 +454,15 @@ pub struct IrohNodeHandle {
     _private: [u8; 0],
 }
 
+/// Error reported by calls on a node that has been closed or destroyed.
+const NODE_CLOSED_ERROR: &str = "node is closed";
+
+/// Enter the node behind `handle` for the rest of the call, or None if it
+/// is not an open node. The node is not freed while the result is held.
+fn enter_node<H>(handle: *const H) -> Option<drain::Entered<IrohNode>> {
+    drain::enter(handle)
+}
+
 // ============================================================================
 // Author Types
 // ============================================================================
@@ -72,6 +471,7 @@ pub struct IrohNodeHandle {
 ///
 /// This is the private key material used for signing document entries.
 /// Must be kept secure (e.g., in iOS Keychain).
+#[cfg(feature = "docs")]
 #[repr(C)]
 pub struct IrohAuthorSecret {
     pub bytes: [u8; 32],
@@ -81,11 +481,19 @@ pub struct IrohAuthorSecret {
 ///
 /// This is the public identifier derived from the secret key.
 /// Safe to share and store openly.
+#[cfg(feature = "docs")]
 #[repr(C)]
 pub struct IrohAuthorId {
     pub bytes: [u8; 32],
 }
 
+/// Signature made with an author key (64 bytes).
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohAuthorSignature {
+    pub bytes: [u8; 64],
+}
+
 // ============================================================================
 // Document Types
 // ============================================================================
@@ -94,23 +502,34 @@ pub struct IrohAuthorId {
 ///
 /// Documents are syncing key-value stores shared between peers.
 /// The handle wraps a Doc from iroh-docs.
+#[cfg(feature = "docs")]
 #[repr(C)]
 pub struct IrohDocHandle {
     _private: [u8; 0],
 }
 
 /// Internal document wrapper for FFI safety.
+#[cfg(feature = "docs")]
 struct DocWrapper {
     doc: Doc,
     node_handle: *const IrohNodeHandle,
+    /// Reject writes through this handle, whatever the capability.
+    read_only: bool,
 }
 
+/// Error reported by write operations on a read-only document handle.
+#[cfg(feature = "docs")]
+const DOC_READ_ONLY_ERROR: &str = "document handle is read-only";
+
 // Safety: DocWrapper is Send+Sync because Doc is Send+Sync and we
 // only use node_handle for reads through the node's runtime.
+#[cfg(feature = "docs")]
 unsafe impl Send for DocWrapper {}
+#[cfg(feature = "docs")]
 unsafe impl Sync for DocWrapper {}
 
 /// A document entry (key-value pair with metadata).
+#[cfg(feature = "docs")]
 #[repr(C)]
 pub struct IrohDocEntry {
     /// Author ID who wrote this entry (32 bytes).
@@ -123,9 +542,22 @@ pub struct IrohDocEntry {
     pub content_size: u64,
     /// Timestamp when entry was created (microseconds since epoch).
     pub timestamp: u64,
+    /// Content hash in binary form, the same hash as `content_hash`.
+    pub hash: IrohHash,
+}
+
+/// Access mode for opening or joining a document.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub enum IrohDocOpenMode {
+    /// Writes are allowed if the document capability permits them.
+    ReadWrite = 0,
+    /// Writes are rejected on this handle even if the capability is writable.
+    ReadOnly = 1,
 }
 
 /// Share mode for document tickets.
+#[cfg(feature = "docs")]
 #[repr(C)]
 pub enum IrohDocShareMode {
     /// Read-only access.
@@ -134,6 +566,31 @@ pub enum IrohDocShareMode {
     Write = 1,
 }
 
+/// Parsed document ticket information.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocTicketInfo {
+    /// Whether the ticket is a valid document ticket.
+    pub is_valid: bool,
+    /// The document's namespace ID (caller must free with `iroh_string_free`).
+    /// Null if invalid.
+    pub namespace_id: *const c_char,
+    /// Access the ticket grants. `Write` lets the holder change the document.
+    pub capability: IrohDocShareMode,
+    /// Number of peers the ticket names to sync with.
+    pub peers_len: usize,
+}
+
+/// Callback for document ticket inspection.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocTicketInspectCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with the inspection result. Always called (never fails).
+    pub on_complete: extern "C" fn(userdata: *mut c_void, info: IrohDocTicketInfo),
+}
+
 // ============================================================================
 // Blob Types
 // ============================================================================
@@ -147,6 +604,221 @@ pub enum IrohBlobFormat {
     HashSeq = 1,
 }
 
+impl From<BlobFormat> for IrohBlobFormat {
+    fn from(format: BlobFormat) -> Self {
+        match format {
+            BlobFormat::Raw => Self::Raw,
+            BlobFormat::HashSeq => Self::HashSeq,
+        }
+    }
+}
+
+/// Strategy for downloading content available from several providers.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohProviderStrategy {
+    /// Use whichever provider connects first, falling back to the others.
+    Race = 0,
+    /// Split collection downloads across all providers in parallel.
+    Split = 1,
+}
+
+impl From<IrohProviderStrategy> for ProviderStrategy {
+    fn from(strategy: IrohProviderStrategy) -> Self {
+        match strategy {
+            IrohProviderStrategy::Race => ProviderStrategy::Race,
+            IrohProviderStrategy::Split => ProviderStrategy::Split,
+        }
+    }
+}
+
+/// Sort order for blob listings.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohBlobSort {
+    /// By hash (stable, otherwise arbitrary).
+    ByHash = 0,
+    /// Smallest blobs first.
+    SizeAscending = 1,
+    /// Largest blobs first.
+    SizeDescending = 2,
+    /// Most recently added first.
+    NewestFirst = 3,
+    /// Least recently added first.
+    OldestFirst = 4,
+}
+
+impl From<IrohBlobSort> for BlobSort {
+    fn from(sort: IrohBlobSort) -> Self {
+        match sort {
+            IrohBlobSort::ByHash => BlobSort::Hash,
+            IrohBlobSort::SizeAscending => BlobSort::SizeAscending,
+            IrohBlobSort::SizeDescending => BlobSort::SizeDescending,
+            IrohBlobSort::NewestFirst => BlobSort::NewestFirst,
+            IrohBlobSort::OldestFirst => BlobSort::OldestFirst,
+        }
+    }
+}
+
+/// A blob in the local store.
+#[repr(C)]
+pub struct IrohBlobListEntry {
+    /// Hash as hex string (only valid for the duration of the callback).
+    pub hash: *const c_char,
+    /// Stored size in bytes.
+    pub size: u64,
+    /// Whether the blob is fully stored.
+    pub is_complete: bool,
+    /// When the blob was added (microseconds since epoch), or 0 if unknown.
+    pub added_at: u64,
+}
+
+/// A tag in a prefix listing.
+#[repr(C)]
+pub struct IrohTagListEntry {
+    /// Tag name (only valid for the duration of the callback).
+    pub name: *const c_char,
+    /// Tagged hash as hex string (only valid for the duration of the callback).
+    pub hash: *const c_char,
+    /// Format of the tagged content.
+    pub format: IrohBlobFormat,
+    /// Stored size of all blobs reachable from the tag in bytes.
+    pub size: u64,
+}
+
+/// Opaque handle to a temporary tag.
+///
+/// Protects content from garbage collection until dropped with
+/// `iroh_blob_temp_tag_drop`. Temporary tags are not persisted.
+#[repr(C)]
+pub struct IrohTempTagHandle {
+    _private: [u8; 0],
+}
+
+/// Opaque handle to a crate-managed temporary file.
+///
+/// The file is deleted when the handle is released with
+/// `iroh_temp_file_release`.
+#[repr(C)]
+pub struct IrohTempFileHandle {
+    _private: [u8; 0],
+}
+
+/// Metadata record attached to a blob.
+///
+/// String fields are null when absent. When returned by Iroh, the record
+/// must be freed with `iroh_blob_metadata_free`.
+#[repr(C)]
+pub struct IrohBlobMetadata {
+    /// Original filename (may be null).
+    pub filename: *mut c_char,
+    /// MIME type of the content (may be null).
+    pub content_type: *mut c_char,
+    /// Creation date in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// Encoding of the stored content.
+    pub compression: IrohCompression,
+}
+
+/// Compression of blob content stored with a metadata record.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohCompression {
+    /// Stored as given.
+    Uncompressed = 0,
+    /// Compressed with zstd, if that makes the content smaller.
+    Zstd = 1,
+}
+
+impl From<IrohCompression> for Compression {
+    fn from(compression: IrohCompression) -> Self {
+        match compression {
+            IrohCompression::Uncompressed => Compression::None,
+            IrohCompression::Zstd => Compression::Zstd,
+        }
+    }
+}
+
+impl From<Compression> for IrohCompression {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::None => IrohCompression::Uncompressed,
+            Compression::Zstd => IrohCompression::Zstd,
+        }
+    }
+}
+
+/// Outcome of importing or downloading a chunked file.
+#[cfg(feature = "chunking")]
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct IrohChunkStats {
+    /// File size in bytes.
+    pub size: u64,
+    /// Number of chunks in the file.
+    pub chunks: u64,
+    /// Chunks that were not stored locally before the call.
+    pub new_chunks: u64,
+}
+
+/// Alternate encoding for ticket strings.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohTicketEncoding {
+    /// Standard lowercase ticket.
+    Standard = 0,
+    /// Uppercase ticket suited to the QR alphanumeric mode.
+    QrAlphanumeric = 1,
+    /// z-base-32 payload, easier to read aloud.
+    ZBase32 = 2,
+}
+
+impl From<IrohTicketEncoding> for TicketEncoding {
+    fn from(encoding: IrohTicketEncoding) -> Self {
+        match encoding {
+            IrohTicketEncoding::Standard => TicketEncoding::Standard,
+            IrohTicketEncoding::QrAlphanumeric => TicketEncoding::QrAlphanumeric,
+            IrohTicketEncoding::ZBase32 => TicketEncoding::ZBase32,
+        }
+    }
+}
+
+/// Kind of a ticket in a ticket bundle.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohBundledTicketKind {
+    /// A blob ticket.
+    BundledBlob = 0,
+    /// A doc ticket.
+    BundledDoc = 1,
+}
+
+/// A ticket unpacked from a ticket bundle.
+///
+/// Strings are only valid for the duration of the callback.
+#[repr(C)]
+pub struct IrohBundledTicket {
+    /// What the ticket is for; empty if unlabeled.
+    pub label: *const c_char,
+    /// Whether this is a blob or a doc ticket.
+    pub kind: IrohBundledTicketKind,
+    /// The ticket in its standard form.
+    pub ticket: *const c_char,
+}
+
+/// Callback for unpacking a ticket bundle.
+#[repr(C)]
+pub struct IrohTicketBundleCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each ticket, in bundle order.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, ticket: IrohBundledTicket),
+    /// Called after the last ticket.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error, before any ticket. Caller must free error with `iroh_string_free`.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
 // ============================================================================
 // Subscription Types
 // ============================================================================
@@ -161,10 +833,21 @@ pub struct IrohSubscriptionHandle {
 
 /// Internal subscription wrapper for cancellation.
 struct SubscriptionWrapper {
-    cancel_tx: Option<tokio::sync::oneshot::Sender<()>>,
+    cancel: Arc<CancelSwitch>,
+}
+
+impl SubscriptionWrapper {
+    /// Box a subscription that stops when `cancel` fires and return its handle.
+    fn into_handle(cancel: Arc<CancelSwitch>) -> *mut IrohSubscriptionHandle {
+        let handle =
+            Box::into_raw(Box::new(SubscriptionWrapper { cancel })) as *mut IrohSubscriptionHandle;
+        handles::register(HandleKind::Subscription, handle);
+        handle
+    }
 }
 
 /// Document event types.
+#[cfg(feature = "docs")]
 #[repr(C)]
 pub enum IrohDocEventType {
     /// A local insertion.
@@ -181,9 +864,19 @@ pub enum IrohDocEventType {
     NeighborDown = 5,
     /// Sync finished with a peer.
     SyncFinished = 6,
+    /// An entry written before the subscription, replayed from a cursor.
+    Replayed = 7,
+    /// The content of a remote insert is available locally, delivered with
+    /// the entry when the subscription fetches content.
+    ContentFetched = 8,
+    /// A key, or every key under a prefix, was deleted. Only delivered by
+    /// deletions-only subscriptions; the entry is the tombstone, with the
+    /// deleted key and the deleting author.
+    EntryDeleted = 9,
 }
 
 /// A document event from subscription.
+#[cfg(feature = "docs")]
 #[repr(C)]
 pub struct IrohDocEvent {
     /// The type of event.
@@ -194,9 +887,37 @@ pub struct IrohDocEvent {
     /// The peer ID for remote events (null for local events).
     /// Must be freed with `iroh_string_free` if not null.
     pub peer_id: *const c_char,
-    /// The content hash for ContentReady events (null for other events).
-    /// Must be freed with `iroh_string_free` if not null.
+    /// The content hash for ContentReady and ContentFetched events (null for
+    /// other events). Must be freed with `iroh_string_free` if not null.
     pub content_hash: *const c_char,
+    /// Opaque cursor covering this event, for `iroh_doc_subscribe_from`.
+    /// Must be freed with `iroh_string_free` if not null.
+    pub cursor: *const c_char,
+    /// Namespace ID of the document the event belongs to.
+    /// Must be freed with `iroh_string_free` if not null.
+    pub namespace_id: *const c_char,
+    /// The entry's content for ContentFetched events (null data for other
+    /// events). Freed with the event.
+    pub content: IrohOwnedBytes,
+}
+
+/// Options for document subscriptions.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocSubscribeOptions {
+    /// Cursor to replay entries written since, as in
+    /// `iroh_doc_subscribe_from` (null to start from the current state).
+    pub cursor: *const c_char,
+    /// Deliver a ContentFetched event with the content of each remote
+    /// insert matching the document's download policy once it is local.
+    pub fetch_content: bool,
+    /// Largest content delivered in ContentFetched events; larger entries
+    /// only get InsertRemote and ContentReady (0 = no limit).
+    pub max_content_size: u64,
+    /// Deliver only EntryDeleted events, for deletions written locally,
+    /// received from peers, or replayed from the cursor. `fetch_content`
+    /// is ignored.
+    pub deletions_only: bool,
 }
 
 // ============================================================================
@@ -228,1238 +949,9194 @@ pub struct IrohGetCallback {
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for node creation.
+/// Callback for puts with options, which report timeouts separately.
 #[repr(C)]
-pub struct IrohNodeCreateCallback {
+pub struct IrohPutWithOptionsCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called on success with the node handle.
-    pub on_success: extern "C" fn(userdata: *mut c_void, handle: *mut IrohNodeHandle),
+    /// Called on success with a C string (caller must free with `iroh_string_free`).
+    pub on_success: extern "C" fn(userdata: *mut c_void, result: *const c_char),
+    /// Called when the timeout runs out, after the import has been stopped.
+    pub on_timeout: extern "C" fn(userdata: *mut c_void),
     /// Called on failure with an error message (caller must free with `iroh_string_free`).
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Progress information for a download operation.
-#[repr(C)]
-pub struct IrohDownloadProgress {
-    /// Bytes downloaded so far.
-    pub downloaded: u64,
-    /// Total bytes expected (0 if unknown).
-    pub total: u64,
-}
-
-/// Callback for get operations with progress reporting.
+/// Callback for downloads with options, which report timeouts separately.
 #[repr(C)]
-pub struct IrohGetProgressCallback {
+pub struct IrohGetWithOptionsCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called with progress updates during download.
-    pub on_progress: extern "C" fn(userdata: *mut c_void, progress: IrohDownloadProgress),
     /// Called on success with owned bytes (caller must free with `iroh_bytes_free`).
     pub on_success: extern "C" fn(userdata: *mut c_void, bytes: IrohOwnedBytes),
+    /// Called when the timeout runs out, after the transfer has been stopped.
+    pub on_timeout: extern "C" fn(userdata: *mut c_void),
     /// Called on failure with an error message (caller must free with `iroh_string_free`).
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Information about an Iroh node.
+/// Callback for downloads that return bytes and an optional metadata record.
 #[repr(C)]
-pub struct IrohNodeInfo {
-    /// Node ID as a string (caller must free with `iroh_string_free`).
-    pub node_id: *const c_char,
-    /// Relay URL if connected (caller must free with `iroh_string_free`).
-    /// Null if not connected to a relay.
-    pub relay_url: *const c_char,
-    /// Whether the node is connected to the network.
-    pub is_connected: bool,
+pub struct IrohGetWithMetadataCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with owned bytes (caller must free with `iroh_bytes_free`)
+    /// and the metadata record (may be null; free with `iroh_blob_metadata_free`).
+    pub on_success: extern "C" fn(
+        userdata: *mut c_void,
+        bytes: IrohOwnedBytes,
+        metadata: *mut IrohBlobMetadata,
+    ),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for node info retrieval.
+/// Callback for ticket metadata lookups.
 #[repr(C)]
-pub struct IrohNodeInfoCallback {
+pub struct IrohBlobMetadataCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called on success with node info.
-    pub on_success: extern "C" fn(userdata: *mut c_void, info: IrohNodeInfo),
+    /// Called on success with the metadata record (may be null if the ticket has none).
+    /// Caller must free the record with `iroh_blob_metadata_free` if not null.
+    pub on_success: extern "C" fn(userdata: *mut c_void, metadata: *mut IrohBlobMetadata),
     /// Called on failure with an error message (caller must free with `iroh_string_free`).
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Parsed ticket information.
+/// Callback for chunked file imports.
+#[cfg(feature = "chunking")]
 #[repr(C)]
-pub struct IrohTicketInfo {
-    /// Whether the ticket is valid.
-    pub is_valid: bool,
-    /// The blob hash as a string (caller must free with `iroh_string_free`).
-    /// Null if invalid.
-    pub hash: *const c_char,
-    /// The node ID from the ticket (caller must free with `iroh_string_free`).
-    /// Null if invalid.
-    pub node_id: *const c_char,
-    /// Whether this is a recursive (collection) ticket.
-    pub is_recursive: bool,
+pub struct IrohChunkedPutCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the ticket (caller must free with `iroh_string_free`)
+    /// and the import stats.
+    pub on_success:
+        extern "C" fn(userdata: *mut c_void, ticket: *const c_char, stats: IrohChunkStats),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for ticket validation.
+/// Callback for chunked file downloads.
+#[cfg(feature = "chunking")]
 #[repr(C)]
-pub struct IrohTicketValidateCallback {
+pub struct IrohChunkedGetCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called with validation result. Always called (never fails).
-    pub on_complete: extern "C" fn(userdata: *mut c_void, info: IrohTicketInfo),
+    /// Called on success once the file is written.
+    pub on_success: extern "C" fn(userdata: *mut c_void, stats: IrohChunkStats),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for node close operation.
+/// Callback for temporary tag creation.
 #[repr(C)]
-pub struct IrohCloseCallback {
+pub struct IrohTempTagCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called when close completes successfully.
-    pub on_complete: extern "C" fn(userdata: *mut c_void),
-    /// Called if close fails with an error message.
+    /// Called on success with the temp tag handle (drop with `iroh_blob_temp_tag_drop`).
+    pub on_success: extern "C" fn(userdata: *mut c_void, tag: *mut IrohTempTagHandle),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for author creation.
+/// Deduplication report for a tag.
 #[repr(C)]
-pub struct IrohAuthorCreateCallback {
+pub struct IrohReferenceReport {
+    /// Number of distinct blobs reachable from the tag.
+    pub total_blobs: u64,
+    /// Number of those blobs also kept by other tags, temporary tags or documents.
+    pub shared_blobs: u64,
+    /// Total stored size of the tag's blobs in bytes.
+    pub total_size: u64,
+    /// Bytes that would be freed if the tag were deleted.
+    pub unique_size: u64,
+}
+
+/// Callback for reference report operations.
+#[repr(C)]
+pub struct IrohReferenceReportCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called on success with the author secret and ID.
-    pub on_success:
-        extern "C" fn(userdata: *mut c_void, secret: IrohAuthorSecret, id: IrohAuthorId),
+    /// Called on success with the report.
+    pub on_success: extern "C" fn(userdata: *mut c_void, report: IrohReferenceReport),
     /// Called on failure with an error message (caller must free with `iroh_string_free`).
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for document creation/join operations.
+/// Content of a profile and its size.
 #[repr(C)]
-pub struct IrohDocCreateCallback {
+pub struct IrohProfileStats {
+    /// Number of tags of the profile.
+    pub tags: u64,
+    /// Number of distinct blobs reachable from the profile's tags.
+    pub blobs: u64,
+    /// Total stored size of those blobs in bytes.
+    pub total_size: u64,
+    /// Bytes no tag outside the profile references, freed by a wipe.
+    pub unique_size: u64,
+}
+
+/// Callback for profile stats operations.
+#[repr(C)]
+pub struct IrohProfileStatsCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called on success with the document handle and namespace ID.
-    pub on_success: extern "C" fn(
-        userdata: *mut c_void,
-        handle: *mut IrohDocHandle,
-        namespace_id: *const c_char,
-    ),
+    /// Called on success with the stats.
+    pub on_success: extern "C" fn(userdata: *mut c_void, stats: IrohProfileStats),
     /// Called on failure with an error message (caller must free with `iroh_string_free`).
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for document get operations.
+/// Content deleted from the store.
 #[repr(C)]
-pub struct IrohDocGetCallback {
+pub struct IrohReclaimed {
+    /// Number of blobs deleted.
+    pub blobs: u64,
+    /// Stored size of the deleted blobs in bytes.
+    pub size: u64,
+}
+
+/// Callback for operations that reclaim content.
+#[repr(C)]
+pub struct IrohReclaimCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called on success with the entry (may be null if not found).
-    /// Caller must free entry with `iroh_doc_entry_free` if not null.
-    pub on_success: extern "C" fn(userdata: *mut c_void, entry: *const IrohDocEntry),
-    /// Called on failure with an error message.
+    /// Called on success with what was deleted.
+    pub on_success: extern "C" fn(userdata: *mut c_void, reclaimed: IrohReclaimed),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for document set operations.
+/// Store-wide deduplication totals.
 #[repr(C)]
-pub struct IrohDocSetCallback {
+pub struct IrohDedupSummary {
+    /// References from tags to blobs, counting repeats inside collections.
+    pub references: u64,
+    /// Distinct blobs reachable from any tag.
+    pub unique_blobs: u64,
+    /// Distinct blobs referenced more than once.
+    pub duplicate_blobs: u64,
+    /// Size of all references, as if each were stored separately.
+    pub referenced_size: u64,
+    /// Size of the distinct blobs, as stored.
+    pub stored_size: u64,
+}
+
+/// A blob referenced more than once.
+/// All pointers are only valid for the duration of the callback.
+#[repr(C)]
+pub struct IrohDuplicateBlob {
+    /// Hash of the blob (hex string).
+    pub hash: *const c_char,
+    /// Stored size in bytes.
+    pub size: u64,
+    /// Number of references, counting repeats inside one collection.
+    pub references: u64,
+    /// Names of the tags the blob is reachable from, sorted.
+    pub tags: *const *const c_char,
+    /// Number of tag names.
+    pub tags_len: usize,
+}
+
+/// Streaming callback for deduplication reports.
+/// Called once per listed duplicate, then on_complete.
+#[repr(C)]
+pub struct IrohDedupReportCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called on success with the content hash (caller must free with `iroh_string_free`).
-    pub on_success: extern "C" fn(userdata: *mut c_void, hash: *const c_char),
-    /// Called on failure with an error message.
+    /// Called for each duplicate, largest saving first.
+    pub on_duplicate: extern "C" fn(userdata: *mut c_void, duplicate: IrohDuplicateBlob),
+    /// Called after the last duplicate with the store-wide totals.
+    pub on_complete: extern "C" fn(userdata: *mut c_void, summary: IrohDedupSummary),
+    /// Called on error. No more callbacks after this.
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Callback for document delete operations.
+/// Writer that receives verified chunks during a sink download, or chunks
+/// of a streamed export.
+#[cfg(any(feature = "downloader-progress", feature = "docs"))]
 #[repr(C)]
-pub struct IrohDocDelCallback {
+pub struct IrohSinkWriter {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called on success with count of deleted entries.
-    pub on_success: extern "C" fn(userdata: *mut c_void, deleted_count: u64),
-    /// Called on failure with an error message.
+    /// Called with each chunk, in order. The bytes are only valid for the
+    /// duration of the call. Return false to abort the download or export.
+    pub write: extern "C" fn(userdata: *mut c_void, bytes: IrohBytes) -> bool,
+}
+
+/// Callback for sink download completion.
+#[cfg(feature = "downloader-progress")]
+#[repr(C)]
+pub struct IrohSinkCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the total number of bytes written.
+    pub on_success: extern "C" fn(userdata: *mut c_void, total_bytes: u64),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Streaming callback for get_many (prefix queries).
-/// Called multiple times - once per entry, then on_complete.
+/// Streaming callback for blob listings.
+/// Called once per entry on the page, then on_complete.
 #[repr(C)]
-pub struct IrohDocGetManyCallback {
+pub struct IrohBlobListCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called for each entry found. Entry must be freed with `iroh_doc_entry_free`.
-    pub on_entry: extern "C" fn(userdata: *mut c_void, entry: *const IrohDocEntry),
-    /// Called when iteration completes successfully.
-    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called for each entry on the page, in order.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, entry: IrohBlobListEntry),
+    /// Called after the last entry with the number of blobs across all pages
+    /// and the cursor for fetching more pages of the listing.
+    pub on_complete: extern "C" fn(userdata: *mut c_void, total: u64, cursor: u64),
     /// Called on error. No more callbacks after this.
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-/// Streaming callback for document subscriptions.
-/// Called multiple times - once per event, then on_complete when stream ends.
+/// Streaming callback for tag listings.
+/// Called once per matching tag, then on_complete.
 #[repr(C)]
-pub struct IrohDocSubscribeCallback {
+pub struct IrohTagListCallback {
     /// Opaque pointer passed back to Swift.
     pub userdata: *mut c_void,
-    /// Called for each event. Event must be freed with `iroh_doc_event_free`.
-    pub on_event: extern "C" fn(userdata: *mut c_void, event: IrohDocEvent),
-    /// Called when subscription ends normally.
-    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called for each matching tag, in name order.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, entry: IrohTagListEntry),
+    /// Called after the last tag with the combined size, counting shared blobs once.
+    pub on_complete: extern "C" fn(userdata: *mut c_void, total_size: u64),
     /// Called on error. No more callbacks after this.
     pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
 }
 
-// ============================================================================
-// Node Lifecycle
-// ============================================================================
+/// Callback for copying blobs between stores.
+#[repr(C)]
+pub struct IrohStoreCopyCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the number of bytes copied.
+    pub on_success: extern "C" fn(userdata: *mut c_void, bytes_copied: u64),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
 
-/// Create a new Iroh node asynchronously.
-///
-/// # Safety
-/// - `config.storage_path` must be a valid null-terminated UTF-8 string
-/// - `config.custom_relay_url` must be null or a valid null-terminated UTF-8 string
-/// - `callback` must have valid function pointers
-#[unsafe(no_mangle)]
-pub extern "C" fn iroh_node_create(config: IrohNodeConfig, callback: IrohNodeCreateCallback) {
-    // Parse the storage path
-    let storage_path = if config.storage_path.is_null() {
-        let error = CString::new("storage_path cannot be null").unwrap();
-        (callback.on_failure)(callback.userdata, error.into_raw());
-        return;
-    } else {
-        let path_str = unsafe { CStr::from_ptr(config.storage_path) };
-        match path_str.to_str() {
-            Ok(s) => PathBuf::from(s),
-            Err(e) => {
-                let error = CString::new(format!("Invalid storage path: {}", e)).unwrap();
-                (callback.on_failure)(callback.userdata, error.into_raw());
-                return;
-            }
-        }
-    };
+/// Callback for temporary file exports.
+#[repr(C)]
+pub struct IrohTempFileCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the file handle (release with `iroh_temp_file_release`)
+    /// and its path (caller must free with `iroh_string_free`).
+    pub on_success:
+        extern "C" fn(userdata: *mut c_void, file: *mut IrohTempFileHandle, path: *const c_char),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
 
-    // Parse optional custom relay URL
-    let custom_relay_url = if config.custom_relay_url.is_null() {
-        None
-    } else {
-        let url_str = unsafe { CStr::from_ptr(config.custom_relay_url) };
-        match url_str.to_str() {
-            Ok(s) => Some(s.to_string()),
-            Err(e) => {
-                let error = CString::new(format!("Invalid custom relay URL: {}", e)).unwrap();
-                (callback.on_failure)(callback.userdata, error.into_raw());
-                return;
-            }
-        }
-    };
+/// Callback for node creation.
+#[repr(C)]
+pub struct IrohNodeCreateCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the node handle.
+    pub on_success: extern "C" fn(userdata: *mut c_void, handle: *mut IrohNodeHandle),
+    /// Called when a database is corrupt and its recovery mode is to fail,
+    /// with the database's path and what is wrong with it (caller must free
+    /// both with `iroh_string_free`).
+    pub on_corrupt: extern "C" fn(
+        userdata: *mut c_void,
+        database: IrohStoreDatabase,
+        path: *const c_char,
+        reason: *const c_char,
+    ),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
 
-    let relay_enabled = config.relay_enabled;
-    let docs_enabled = config.docs_enabled;
+/// One of a node's databases.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohStoreDatabase {
+    /// The blob store's index of blobs and tags.
+    StoreDatabaseBlobs = 0,
+    /// The docs engine's replicas, authors and entries.
+    StoreDatabaseDocs = 1,
+}
 
-    // Create the node synchronously
-    // Note: Swift should call this from a background thread/task
-    match IrohNode::new(storage_path, relay_enabled, custom_relay_url, docs_enabled) {
-        Ok(node) => {
-            // Box the node and convert to raw pointer
-            let boxed = Box::new(node);
-            let handle = Box::into_raw(boxed) as *mut IrohNodeHandle;
-            (callback.on_success)(callback.userdata, handle);
-        }
-        Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
+impl From<StoreDatabase> for IrohStoreDatabase {
+    fn from(database: StoreDatabase) -> Self {
+        match database {
+            StoreDatabase::Blobs => IrohStoreDatabase::StoreDatabaseBlobs,
+            StoreDatabase::Docs => IrohStoreDatabase::StoreDatabaseDocs,
         }
     }
 }
 
-/// Destroy an Iroh node and free its resources.
-///
-/// This performs a graceful shutdown, ensuring pending writes are flushed.
-///
-/// # Safety
-/// - `handle` must be a valid pointer returned by `iroh_node_create`
-/// - `handle` must not be used after this call
-#[unsafe(no_mangle)]
-pub extern "C" fn iroh_node_destroy(handle: *mut IrohNodeHandle) {
-    if handle.is_null() {
-        return;
-    }
+/// What a node does when its blob store database is corrupt. What recovery
+/// sets aside is moved to `quarantine/` in the storage directory.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohBlobStoreRecovery {
+    /// Fail node creation with `on_corrupt`.
+    BlobStoreRecoveryFail = 0,
+    /// Rebuild the database from the blob files. Complete files matching
+    /// their hash are added back under automatic tags; tag names and blobs
+    /// small enough to be kept inside the database are lost.
+    BlobStoreRecoveryReindex = 1,
+    /// Set the database and blob files aside and start with an empty store.
+    BlobStoreRecoveryQuarantine = 2,
+}
 
-    unsafe {
-        // Convert back to Box and drop it
-        let node = Box::from_raw(handle as *mut IrohNode);
-        // Attempt graceful shutdown, ignore errors
-        let _ = node.shutdown();
+impl From<IrohBlobStoreRecovery> for BlobStoreRecovery {
+    fn from(recovery: IrohBlobStoreRecovery) -> Self {
+        match recovery {
+            IrohBlobStoreRecovery::BlobStoreRecoveryFail => BlobStoreRecovery::Fail,
+            IrohBlobStoreRecovery::BlobStoreRecoveryReindex => BlobStoreRecovery::Reindex,
+            IrohBlobStoreRecovery::BlobStoreRecoveryQuarantine => BlobStoreRecovery::Quarantine,
+        }
     }
 }
 
-// ============================================================================
-// Core Operations
-// ============================================================================
+/// What a node does when its docs database is corrupt.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohDocsStoreRecovery {
+    /// Fail node creation with `on_corrupt`.
+    DocsStoreRecoveryFail = 0,
+    /// Move the database to `quarantine/` in the storage directory and
+    /// start without documents or authors; documents must be joined again.
+    DocsStoreRecoveryDiscard = 1,
+}
 
-/// Add bytes to the blob store and get a shareable ticket.
-///
-/// # Safety
-/// - `handle` must be a valid node handle
-/// - `bytes.data` must point to valid memory for `bytes.len` bytes
-/// - `callback` must have valid function pointers
-#[unsafe(no_mangle)]
-pub extern "C" fn iroh_put(
-    handle: *const IrohNodeHandle,
-    bytes: IrohBytes,
-    callback: IrohCallback,
-) {
-    if handle.is_null() {
-        let error = CString::new("handle cannot be null").unwrap();
-        (callback.on_failure)(callback.userdata, error.into_raw());
-        return;
+impl From<IrohDocsStoreRecovery> for DocsStoreRecovery {
+    fn from(recovery: IrohDocsStoreRecovery) -> Self {
+        match recovery {
+            IrohDocsStoreRecovery::DocsStoreRecoveryFail => DocsStoreRecovery::Fail,
+            IrohDocsStoreRecovery::DocsStoreRecoveryDiscard => DocsStoreRecovery::Discard,
+        }
     }
+}
 
-    // Copy the bytes to own them (Swift memory may not be stable)
-    let data = if bytes.data.is_null() || bytes.len == 0 {
-        Vec::new()
-    } else {
-        unsafe { std::slice::from_raw_parts(bytes.data, bytes.len).to_vec() }
-    };
-
-    // Get reference to node (we don't own it)
-    let node = unsafe { &*(handle as *const IrohNode) };
+/// Stage of a download reporting progress.
+#[cfg(feature = "downloader-progress")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohDownloadStage {
+    /// Bytes are arriving and being verified as they do.
+    DownloadReceiving = 0,
+    /// Every byte arrived; the store is confirming the whole blob.
+    DownloadVerifying = 1,
+    /// The blob is verified; `on_success` follows.
+    DownloadVerified = 2,
+}
 
-    // Perform the put operation
-    // Note: This blocks on the node's runtime, which is intentional
-    match node.put(&data) {
-        Ok(ticket) => {
-            let ticket_cstr = CString::new(ticket).unwrap();
-            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
-        }
-        Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
+#[cfg(feature = "downloader-progress")]
+impl From<DownloadStage> for IrohDownloadStage {
+    fn from(stage: DownloadStage) -> Self {
+        match stage {
+            DownloadStage::Downloading => IrohDownloadStage::DownloadReceiving,
+            DownloadStage::Verifying => IrohDownloadStage::DownloadVerifying,
+            DownloadStage::Verified => IrohDownloadStage::DownloadVerified,
         }
     }
 }
 
-/// Download bytes from a ticket.
-///
-/// # Safety
-/// - `handle` must be a valid node handle
-/// - `ticket` must be a valid null-terminated UTF-8 string
-/// - `callback` must have valid function pointers
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_get(
-    handle: *const IrohNodeHandle,
-    ticket: *const c_char,
-    callback: IrohGetCallback,
-) {
-    if handle.is_null() {
-        let error = CString::new("handle cannot be null").unwrap();
-        (callback.on_failure)(callback.userdata, error.into_raw());
-        return;
-    }
+/// Progress information for a download operation.
+#[cfg(feature = "downloader-progress")]
+#[repr(C)]
+pub struct IrohDownloadProgress {
+    /// Bytes received so far.
+    pub downloaded: u64,
+    /// Total bytes expected (0 if unknown).
+    pub total: u64,
+    /// Bytes verified against the hash and stored so far.
+    pub verified: u64,
+    /// What the download is doing.
+    pub stage: IrohDownloadStage,
+}
 
-    if ticket.is_null() {
-        let error = CString::new("ticket cannot be null").unwrap();
-        (callback.on_failure)(callback.userdata, error.into_raw());
-        return;
-    }
+/// Callback for get operations with progress reporting.
+#[cfg(feature = "downloader-progress")]
+#[repr(C)]
+pub struct IrohGetProgressCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with progress updates during download.
+    pub on_progress: extern "C" fn(userdata: *mut c_void, progress: IrohDownloadProgress),
+    /// Called on success with owned bytes (caller must free with `iroh_bytes_free`).
+    pub on_success: extern "C" fn(userdata: *mut c_void, bytes: IrohOwnedBytes),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
 
-    // Parse the ticket string
-    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
-        Ok(s) => s.to_string(),
-        Err(e) => {
-            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
+/// How well the network supports direct connections.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohNetworkCondition {
+    /// No network report has completed yet, or the node has no relays.
+    NetworkUnknown = 0,
+    /// UDP works and hole punching should succeed.
+    NetworkOpen = 1,
+    /// UDP works, but the NAT often defeats direct connections.
+    NetworkHardNat = 2,
+    /// UDP is blocked; all traffic goes through a relay.
+    NetworkRelayOnly = 3,
+    /// A captive portal intercepts traffic until the user signs in.
+    NetworkCaptivePortal = 4,
+    /// Neither UDP nor any relay could be reached.
+    NetworkOffline = 5,
+}
+
+impl From<Option<NetworkCondition>> for IrohNetworkCondition {
+    fn from(condition: Option<NetworkCondition>) -> Self {
+        match condition {
+            None => Self::NetworkUnknown,
+            Some(NetworkCondition::Open) => Self::NetworkOpen,
+            Some(NetworkCondition::HardNat) => Self::NetworkHardNat,
+            Some(NetworkCondition::RelayOnly) => Self::NetworkRelayOnly,
+            Some(NetworkCondition::CaptivePortal) => Self::NetworkCaptivePortal,
+            Some(NetworkCondition::Offline) => Self::NetworkOffline,
         }
-    };
+    }
+}
 
-    // Get reference to node
+/// Information about an Iroh node.
+#[repr(C)]
+pub struct IrohNodeInfo {
+    /// Node ID as a string (caller must free with `iroh_string_free`).
+    pub node_id: *const c_char,
+    /// Relay URL if connected (caller must free with `iroh_string_free`).
+    /// Null if not connected to a relay.
+    pub relay_url: *const c_char,
+    /// Whether the node is connected to the network.
+    pub is_connected: bool,
+    /// App identifier the node was created with (caller must free with
+    /// `iroh_string_free`). Null if none was configured.
+    pub app_id: *const c_char,
+    /// How well the network supports direct connections.
+    pub network_condition: IrohNetworkCondition,
+}
+
+/// Callback for node info retrieval.
+#[repr(C)]
+pub struct IrohNodeInfoCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with node info.
+    pub on_success: extern "C" fn(userdata: *mut c_void, info: IrohNodeInfo),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for closing a peer's connections.
+#[repr(C)]
+pub struct IrohConnectionCloseCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the number of connections that were closed.
+    pub on_success: extern "C" fn(userdata: *mut c_void, closed: usize),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// How a peer was reached by `iroh_can_reach`.
+#[repr(C)]
+pub enum IrohReachability {
+    /// Reached over a direct UDP path.
+    Direct = 0,
+    /// Reached only through a relay so far.
+    Relay = 1,
+    /// Not reached.
+    Unreachable = 2,
+}
+
+/// Result of a pre-flight dial.
+#[repr(C)]
+pub struct IrohReachResult {
+    /// How the peer was reached.
+    pub reachability: IrohReachability,
+    /// Round-trip time in microseconds (0 if unreachable).
+    pub latency_us: u64,
+    /// Why the dial failed, or null if reached (only valid for the duration of the callback).
+    pub reason: *const c_char,
+}
+
+/// Callback for `iroh_can_reach`.
+#[repr(C)]
+pub struct IrohCanReachCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with the result, including when the peer is unreachable.
+    pub on_success: extern "C" fn(userdata: *mut c_void, result: IrohReachResult),
+    /// Called if the target cannot be parsed (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// How much of a blob a provider has (see `iroh_blob_probe_providers`).
+#[repr(C)]
+pub enum IrohBlobAvailability {
+    /// The provider has the whole blob.
+    BlobComplete = 0,
+    /// The provider has part of the blob.
+    BlobPartial = 1,
+    /// The provider has none of the blob.
+    BlobMissing = 2,
+    /// The provider could not be asked; see `reason`.
+    BlobUnknown = 3,
+}
+
+/// One provider's answer to a probe.
+///
+/// Strings are only valid for the duration of the callback.
+#[repr(C)]
+pub struct IrohProviderProbe {
+    /// Provider node ID.
+    pub node_id: *const c_char,
+    /// How much of the blob the provider has.
+    pub availability: IrohBlobAvailability,
+    /// Blob size in bytes, or 0 if the provider has not verified it.
+    pub size: u64,
+    /// Why the provider could not be asked, or null.
+    pub reason: *const c_char,
+}
+
+/// Streaming callback for `iroh_blob_probe_providers`.
+/// Called once per provider in the order given, then on_complete.
+#[repr(C)]
+pub struct IrohProviderProbeCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each provider.
+    pub on_provider: extern "C" fn(userdata: *mut c_void, probe: IrohProviderProbe),
+    /// Called after the last provider.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called if the arguments are invalid. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Bytes exchanged with one peer (see `iroh_peer_traffic_list`).
+#[repr(C)]
+pub struct IrohPeerTraffic {
+    /// Peer node ID (only valid for the duration of the callback).
+    pub node_id: *const c_char,
+    /// Bytes sent to the peer on connections it opened.
+    pub bytes_uploaded: u64,
+    /// Bytes received from the peer on connections it opened. Content this
+    /// node downloads over connections it opens is not counted.
+    pub bytes_downloaded: u64,
+}
+
+/// Streaming callback for per-peer traffic.
+/// Called once per peer, then on_complete.
+#[repr(C)]
+pub struct IrohPeerTrafficCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each peer, largest upload first.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, entry: IrohPeerTraffic),
+    /// Called after the last peer.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Kind of network a device is on, which selects its bandwidth caps.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohNetworkKind {
+    /// Wi-Fi, Ethernet or another unmetered network.
+    NetworkKindWifi = 0,
+    /// A cellular, usually metered, network.
+    NetworkKindCellular = 1,
+}
+
+impl From<IrohNetworkKind> for NetworkKind {
+    fn from(kind: IrohNetworkKind) -> Self {
+        match kind {
+            IrohNetworkKind::NetworkKindWifi => NetworkKind::Wifi,
+            IrohNetworkKind::NetworkKindCellular => NetworkKind::Cellular,
+        }
+    }
+}
+
+/// Who may fetch blobs from a node when a peer has no rule of its own.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohBlobAccessMode {
+    /// Every peer that is not denied.
+    BlobAccessOpen = 0,
+    /// Only peers that are allowed.
+    BlobAccessAllowList = 1,
+}
+
+impl From<IrohBlobAccessMode> for AccessMode {
+    fn from(mode: IrohBlobAccessMode) -> Self {
+        match mode {
+            IrohBlobAccessMode::BlobAccessOpen => AccessMode::Open,
+            IrohBlobAccessMode::BlobAccessAllowList => AccessMode::AllowList,
+        }
+    }
+}
+
+impl From<AccessMode> for IrohBlobAccessMode {
+    fn from(mode: AccessMode) -> Self {
+        match mode {
+            AccessMode::Open => IrohBlobAccessMode::BlobAccessOpen,
+            AccessMode::AllowList => IrohBlobAccessMode::BlobAccessAllowList,
+        }
+    }
+}
+
+/// Blob access rule for one peer.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohBlobAccessRule {
+    /// No rule; the access mode decides.
+    BlobAccessDefault = 0,
+    /// The peer may fetch blobs.
+    BlobAccessAllow = 1,
+    /// The peer may not fetch blobs.
+    BlobAccessDeny = 2,
+}
+
+impl From<IrohBlobAccessRule> for Option<AccessRule> {
+    fn from(rule: IrohBlobAccessRule) -> Self {
+        match rule {
+            IrohBlobAccessRule::BlobAccessDefault => None,
+            IrohBlobAccessRule::BlobAccessAllow => Some(AccessRule::Allow),
+            IrohBlobAccessRule::BlobAccessDeny => Some(AccessRule::Deny),
+        }
+    }
+}
+
+/// A peer's blob access rule (see `iroh_blob_access_list`).
+#[repr(C)]
+pub struct IrohBlobAccessEntry {
+    /// Peer node ID (only valid for the duration of the callback).
+    pub node_id: *const c_char,
+    /// Allow or deny.
+    pub rule: IrohBlobAccessRule,
+}
+
+/// Streaming callback for blob access rules.
+/// Called once per peer rule, then on_complete with the mode.
+#[repr(C)]
+pub struct IrohBlobAccessCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each peer rule, sorted by node ID.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, entry: IrohBlobAccessEntry),
+    /// Called after the last rule with the access mode.
+    pub on_complete: extern "C" fn(userdata: *mut c_void, mode: IrohBlobAccessMode),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Kind of node event (see `iroh_node_subscribe_events`).
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohNodeEventType {
+    /// The home relay or the direct addresses changed.
+    NodeConnectivityChanged = 0,
+    /// A download started.
+    NodeDownloadStarted = 1,
+    /// A download ended; `error` is null on success.
+    NodeDownloadFinished = 2,
+    /// A pass removing expired document entries finished.
+    NodeExpiredPruned = 3,
+    /// A peer joined a document's swarm.
+    NodeNeighborUp = 4,
+    /// A peer left a document's swarm.
+    NodeNeighborDown = 5,
+    /// A document sync with a peer ended; `error` is null on success.
+    NodeSyncFinished = 6,
+    /// An operation exceeded the watchdog's threshold.
+    NodeSlowOperation = 7,
+    /// A peer was refused a blob fetch by the access list.
+    NodeFetchRejected = 8,
+    /// A peer's blob request was refused by the request limits.
+    NodeFetchLimited = 9,
+    /// A document write was added to the outbox.
+    NodeOutboxQueued = 10,
+    /// A peer synced an outbox write.
+    NodeOutboxDelivered = 11,
+    /// A peer synced an outbox write, but a newer write by `author_id` won.
+    NodeOutboxSuperseded = 12,
+    /// An outbox write can never be delivered; see `error`.
+    NodeOutboxFailed = 13,
+    /// Durable peer `peer_id` confirmed a document's local writes; see
+    /// `confirmed_through` and `backed_up`.
+    NodeDurableSync = 14,
+    /// The content announcer republished this node's discovery records;
+    /// see `announced_tags`.
+    NodeContentAnnounced = 15,
+    /// A network report showed a different network condition; see
+    /// `network_condition`.
+    NodeNetworkConditionChanged = 16,
+    /// A time-limited tag expired and was removed; see `tag_name` and `hash`.
+    NodeTagExpired = 17,
+    /// A document sync with peer `peer_id` ended; see `last_success`,
+    /// `pending_entries`, `failure_streak` and `error`.
+    NodePeerSyncChanged = 18,
+    /// Garbage collection of profile `profile_id`'s content started; see
+    /// `gc_blobs` and `gc_size`.
+    NodeGcStarted = 19,
+    /// Garbage collection of profile `profile_id`'s content finished; see
+    /// `gc_blobs` and `gc_size`.
+    NodeGcFinished = 20,
+}
+
+/// A node event.
+///
+/// Fields that do not apply to the event type are null or zero. Strings
+/// are only valid for the duration of the callback.
+#[repr(C)]
+pub struct IrohNodeEvent {
+    /// The type of event.
+    pub event_type: IrohNodeEventType,
+    /// Home relay URL for connectivity events, or null if none.
+    pub relay_url: *const c_char,
+    /// Direct "ip:port" addresses for connectivity events.
+    pub direct_addrs: *const *const c_char,
+    /// Number of entries in `direct_addrs`.
+    pub direct_addrs_len: usize,
+    /// Content hash for download and tag expiry events.
+    pub hash: IrohHash,
+    /// Namespace ID for neighbor, sync, peer sync, outbox and durable sync events.
+    pub namespace_id: *const c_char,
+    /// Peer node ID for neighbor, sync, peer sync, durable sync and rejected
+    /// fetch events.
+    pub peer_id: *const c_char,
+    /// Why a download, sync or outbox write failed, or null if it succeeded.
+    /// For peer sync events, why the last sync failed.
+    pub error: *const c_char,
+    /// Entries removed, for expiry events.
+    pub removed: u64,
+    /// Operation that started a download or was slow, or 0 if a download
+    /// was not started by an FFI call.
+    pub operation_id: u64,
+    /// Name of the FFI function that started a slow operation.
+    pub operation_name: *const c_char,
+    /// Stage a slow operation was in.
+    pub stage: *const c_char,
+    /// Milliseconds a slow operation had been running.
+    pub elapsed_ms: u64,
+    /// Item ID for outbox events.
+    pub item_id: u64,
+    /// Entry key for outbox events.
+    pub key: IrohBytes,
+    /// Author of the winning entry for superseded outbox writes.
+    pub author_id: *const c_char,
+    /// For durable sync events, the time before which every durable peer
+    /// confirmed every local write (microseconds since epoch, 0 if one
+    /// never synced).
+    pub confirmed_through: u64,
+    /// For durable sync events, whether every durable peer has every local write.
+    pub backed_up: bool,
+    /// Tags announced, for content announcement events.
+    pub announced_tags: u64,
+    /// New condition, for network condition events.
+    pub network_condition: IrohNetworkCondition,
+    /// Name of the removed tag, for tag expiry events.
+    pub tag_name: *const c_char,
+    /// For peer sync events, the time the last successful sync finished
+    /// (microseconds since epoch, 0 if none).
+    pub last_success: u64,
+    /// For peer sync events, local inserts since the last successful sync.
+    pub pending_entries: u64,
+    /// For peer sync events, syncs that failed since the last successful one.
+    pub failure_streak: u64,
+    /// Profile whose content is collected, for GC events.
+    pub profile_id: *const c_char,
+    /// For GC started events, blobs no tag references anymore; for GC
+    /// finished events, blobs deleted.
+    pub gc_blobs: u64,
+    /// Stored size of the blobs in `gc_blobs`.
+    pub gc_size: u64,
+}
+
+/// Streaming callback for node events.
+#[repr(C)]
+pub struct IrohNodeEventCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each event.
+    pub on_event: extern "C" fn(userdata: *mut c_void, event: IrohNodeEvent),
+    /// Called when the subscription ends, after cancellation or node shutdown.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Streaming callback for messages from peers.
+#[repr(C)]
+pub struct IrohMessageCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each message. `from` is the sender's node ID; both
+    /// `from` and `payload` are only valid for the duration of the call.
+    pub on_message: extern "C" fn(userdata: *mut c_void, from: *const c_char, payload: IrohBytes),
+    /// Called when the subscription ends, after cancellation or node shutdown.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Kind of resource a live handle refers to.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohHandleKind {
+    /// A node handle from `iroh_node_create`.
+    NodeHandle = 0,
+    /// A document handle from `iroh_doc_create`, `iroh_doc_join` or `iroh_doc_open`.
+    DocHandle = 1,
+    /// A subscription handle, released by `iroh_subscription_cancel`.
+    SubscriptionHandle = 2,
+}
+
+/// A handle that has not been destroyed, closed or cancelled yet.
+#[repr(C)]
+pub struct IrohLiveHandle {
+    /// What the handle refers to.
+    pub kind: IrohHandleKind,
+    /// The handle's address.
+    pub address: usize,
+    /// Creation time in microseconds since epoch.
+    pub created_at: u64,
+    /// Backtrace of the call that created the handle, or null in release builds
+    /// (only valid for the duration of the callback).
+    pub backtrace: *const c_char,
+}
+
+/// Callback for listing live handles.
+#[repr(C)]
+pub struct IrohLiveHandlesCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each live handle, oldest first.
+    pub on_handle: extern "C" fn(userdata: *mut c_void, handle: IrohLiveHandle),
+    /// Called after the last handle.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+}
+
+/// Operation totals since the process started (see `iroh_operation_counters`).
+#[repr(C)]
+pub struct IrohOperationCounters {
+    /// Successful puts.
+    pub puts: u64,
+    /// Successful gets.
+    pub gets: u64,
+    /// Successful document writes.
+    pub doc_sets: u64,
+    /// Bytes passed in by puts.
+    pub bytes_in: u64,
+    /// Bytes returned by gets.
+    pub bytes_out: u64,
+}
+
+/// Callback for reading operation counters.
+#[repr(C)]
+pub struct IrohOperationCountersCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with the number of failures of each FFI function that has
+    /// failed, like "iroh_get". `operation` is only valid during the call.
+    pub on_failure_count:
+        extern "C" fn(userdata: *mut c_void, operation: *const c_char, count: u64),
+    /// Called last with the totals.
+    pub on_complete: extern "C" fn(userdata: *mut c_void, counters: IrohOperationCounters),
+}
+
+/// Callback receiving the library's log messages.
+#[repr(C)]
+pub struct IrohLogCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with each message, on the thread that logged it. For a panic
+    /// this is the panicking thread, before it unwinds. `message` is only
+    /// valid during the call.
+    pub on_log: extern "C" fn(userdata: *mut c_void, message: *const c_char),
+    /// Called once the callback is replaced and no longer running, if set.
+    pub release: Option<extern "C" fn(userdata: *mut c_void)>,
+}
+
+/// Callback for watching the node's direct addresses.
+#[repr(C)]
+pub struct IrohDirectAddrsCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with the current addresses, then again whenever they change.
+    /// `addrs` holds `len` "ip:port" strings, only valid for the duration of the call.
+    pub on_addrs: extern "C" fn(userdata: *mut c_void, addrs: *const *const c_char, len: usize),
+    /// Called when watching stops, after cancellation or node shutdown.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Latency to one configured relay (see `iroh_node_relay_report`).
+#[repr(C)]
+pub struct IrohRelayLatency {
+    /// Relay URL (only valid for the duration of the callback).
+    pub url: *const c_char,
+    /// Whether the relay has been reached; `latency_us` is 0 otherwise.
+    pub is_measured: bool,
+    /// Fastest probe to the relay in microseconds.
+    pub latency_us: u64,
+    /// Whether the node is pinned to this relay (see `iroh_node_set_home_relay`).
+    pub is_pinned: bool,
+}
+
+/// Streaming callback for the relay latency report.
+/// Called once per configured relay, then on_complete.
+#[repr(C)]
+pub struct IrohRelayReportCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each relay, fastest first, unreached relays last.
+    pub on_relay: extern "C" fn(userdata: *mut c_void, relay: IrohRelayLatency),
+    /// Called after the last relay with the home relay URL, or null if none
+    /// (only valid for the duration of the callback).
+    pub on_complete: extern "C" fn(userdata: *mut c_void, home_relay: *const c_char),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Outcome of a node health check (see `iroh_node_health`).
+///
+/// Error strings are null when the check passed and are only valid for the
+/// duration of the callback.
+#[repr(C)]
+pub struct IrohNodeHealth {
+    /// Whether every check passed.
+    pub is_healthy: bool,
+    /// Whether the blob store accepted a write.
+    pub store_writable: bool,
+    /// Why the store check failed.
+    pub store_error: *const c_char,
+    /// Whether the runtime ran a task.
+    pub runtime_responsive: bool,
+    /// Why the runtime check failed.
+    pub runtime_error: *const c_char,
+    /// Whether the endpoint is open and bound to a socket.
+    pub endpoint_bound: bool,
+    /// Why the endpoint check failed.
+    pub endpoint_error: *const c_char,
+}
+
+/// Callback for a node health check.
+#[repr(C)]
+pub struct IrohNodeHealthCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with the health report, healthy or not.
+    pub on_success: extern "C" fn(userdata: *mut c_void, health: IrohNodeHealth),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Summary of pending work (see `iroh_pending_work`).
+#[repr(C)]
+pub struct IrohPendingWork {
+    /// Whether any pending work was found.
+    pub has_pending_work: bool,
+    /// Number of documents with local writes no peer has synced yet.
+    pub unsynced_docs: u64,
+    /// Number of downloads that have not finished.
+    pub incomplete_downloads: u64,
+}
+
+impl From<PendingWork> for IrohPendingWork {
+    fn from(work: PendingWork) -> Self {
+        Self {
+            has_pending_work: !work.is_empty(),
+            unsynced_docs: work.unsynced_docs,
+            incomplete_downloads: work.incomplete_downloads,
+        }
+    }
+}
+
+/// Callback for pending work queries.
+#[repr(C)]
+pub struct IrohPendingWorkCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the pending work summary.
+    pub on_success: extern "C" fn(userdata: *mut c_void, work: IrohPendingWork),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// What a node is holding on to (see `iroh_node_memory_report`).
+#[repr(C)]
+pub struct IrohMemoryReport {
+    /// Size on disk of the blob and document database files. This is not
+    /// memory use: it only bounds how much of the databases the store and
+    /// the OS page cache can hold in memory.
+    pub store_disk_bytes: u64,
+    /// Tasks alive on the node's runtime.
+    pub runtime_tasks: u64,
+    /// Events and messages buffered until every subscriber has them.
+    pub queued_events: u64,
+    /// Callbacks waiting for the delivery thread.
+    pub queued_callbacks: u64,
+    /// Connections peers have open to this node.
+    pub open_connections: u64,
+}
+
+/// Callback for memory reports.
+#[repr(C)]
+pub struct IrohMemoryReportCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the report.
+    pub on_success: extern "C" fn(userdata: *mut c_void, report: IrohMemoryReport),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Outcome of a bounded sync step (see `iroh_node_sync_step`).
+#[repr(C)]
+pub struct IrohSyncStepReport {
+    /// Whether all work finished before the budget ran out and no pending
+    /// work is left.
+    pub completed: bool,
+    /// Number of documents whose sync with known peers finished.
+    pub docs_synced: u64,
+    /// Number of entries received from peers.
+    pub entries_received: u64,
+    /// Number of missing content blobs that were downloaded.
+    pub blobs_downloaded: u64,
+}
+
+/// Callback for bounded sync steps.
+#[repr(C)]
+pub struct IrohSyncStepCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the step report.
+    pub on_success: extern "C" fn(userdata: *mut c_void, report: IrohSyncStepReport),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Parsed ticket information.
+#[repr(C)]
+pub struct IrohTicketInfo {
+    /// Whether the ticket is valid.
+    pub is_valid: bool,
+    /// The blob hash as a string (caller must free with `iroh_string_free`).
+    /// Null if invalid.
+    pub hash: *const c_char,
+    /// The node ID from the ticket (caller must free with `iroh_string_free`).
+    /// Null if invalid.
+    pub node_id: *const c_char,
+    /// Whether this is a recursive (collection) ticket.
+    pub is_recursive: bool,
+    /// Format of the blob the ticket points to.
+    pub format: IrohBlobFormat,
+    /// Relay URLs the provider can be reached through. Borrowed, valid only
+    /// during `on_complete`.
+    pub relay_urls: *const *const c_char,
+    /// Number of relay URLs.
+    pub relay_urls_len: usize,
+    /// Direct `ip:port` addresses of the provider. Borrowed, valid only
+    /// during `on_complete`.
+    pub direct_addrs: *const *const c_char,
+    /// Number of direct addresses.
+    pub direct_addrs_len: usize,
+}
+
+impl IrohTicketInfo {
+    /// Info reported for a ticket that cannot be parsed.
+    fn invalid() -> Self {
+        Self {
+            is_valid: false,
+            hash: std::ptr::null(),
+            node_id: std::ptr::null(),
+            is_recursive: false,
+            format: IrohBlobFormat::Raw,
+            relay_urls: std::ptr::null(),
+            relay_urls_len: 0,
+            direct_addrs: std::ptr::null(),
+            direct_addrs_len: 0,
+        }
+    }
+}
+
+/// Callback for ticket validation.
+#[repr(C)]
+pub struct IrohTicketValidateCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with validation result. Always called (never fails).
+    pub on_complete: extern "C" fn(userdata: *mut c_void, info: IrohTicketInfo),
+}
+
+/// Callback for node close operation.
+#[repr(C)]
+pub struct IrohCloseCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called when close completes successfully.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called if close fails with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Faults to inject into a node for testing.
+#[repr(C)]
+pub struct IrohFaults {
+    /// Number of upcoming puts that fail (0 for none).
+    pub fail_puts: u64,
+    /// Milliseconds each download waits before it starts (0 for none).
+    pub download_delay_ms: u64,
+    /// Percentage of document syncs started by peers that are dropped
+    /// (0 to 100).
+    pub sync_drop_percent: u8,
+}
+
+/// Callback for closing a node.
+#[repr(C)]
+pub struct IrohNodeCloseCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called when close completes; `graceful` is false if the deadline
+    /// passed and the remaining shutdown work was abandoned.
+    pub on_complete: extern "C" fn(userdata: *mut c_void, graceful: bool),
+    /// Called if close fails with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for author creation.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohAuthorCreateCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the author secret and ID.
+    pub on_success:
+        extern "C" fn(userdata: *mut c_void, secret: IrohAuthorSecret, id: IrohAuthorId),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for document creation/join operations.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocCreateCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the document handle and namespace ID.
+    pub on_success: extern "C" fn(
+        userdata: *mut c_void,
+        handle: *mut IrohDocHandle,
+        namespace_id: *const c_char,
+    ),
+    /// Called on failure with an error message (caller must free with `iroh_string_free`).
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for document get operations.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocGetCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the entry (may be null if not found).
+    /// Caller must free entry with `iroh_doc_entry_free` if not null.
+    pub on_success: extern "C" fn(userdata: *mut c_void, entry: *const IrohDocEntry),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Streaming callback for batched document gets.
+/// Called once per requested key, then on_complete.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocGetBatchCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each key in request order with its index and entry (null if not found).
+    /// Caller must free entry with `iroh_doc_entry_free` if not null.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, index: usize, entry: *const IrohDocEntry),
+    /// Called when every key has been reported.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for document set operations.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocSetCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the content hash (caller must free with `iroh_string_free`).
+    pub on_success: extern "C" fn(userdata: *mut c_void, hash: *const c_char),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for operations that produce a hash.
+#[repr(C)]
+pub struct IrohHashCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the hash.
+    pub on_success: extern "C" fn(userdata: *mut c_void, hash: IrohHash),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Progress of a file import.
+#[repr(C)]
+pub struct IrohImportProgress {
+    /// Bytes copied into the store so far.
+    pub copied: u64,
+    /// Bytes hashed so far.
+    pub hashed: u64,
+    /// Size of the file in bytes (0 until known).
+    pub total: u64,
+}
+
+impl From<crate::node::ImportProgress> for IrohImportProgress {
+    fn from(progress: crate::node::ImportProgress) -> Self {
+        Self {
+            copied: progress.copied,
+            hashed: progress.hashed,
+            total: progress.total,
+        }
+    }
+}
+
+/// Callback for putting a file.
+#[repr(C)]
+pub struct IrohPutFileCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with progress updates while the file is imported.
+    pub on_progress: extern "C" fn(userdata: *mut c_void, progress: IrohImportProgress),
+    /// Called on success with the ticket (caller must free with `iroh_string_free`).
+    pub on_success: extern "C" fn(userdata: *mut c_void, ticket: *const c_char),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for setting a document entry from a file.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocSetFileCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with progress updates while the file is imported.
+    pub on_progress: extern "C" fn(userdata: *mut c_void, progress: IrohImportProgress),
+    /// Called on success with the content hash.
+    pub on_success: extern "C" fn(userdata: *mut c_void, hash: IrohHash),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// How a document write is stored before it is reported done.
+#[cfg(feature = "docs")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohDurability {
+    /// Committed together with other writes within half a second, so a
+    /// crash can lose the latest writes.
+    DurabilityBatched = 0,
+    /// Committed and synced to disk before the write is reported done.
+    DurabilityImmediate = 1,
+}
+
+#[cfg(feature = "docs")]
+impl From<IrohDurability> for Durability {
+    fn from(durability: IrohDurability) -> Self {
+        match durability {
+            IrohDurability::DurabilityBatched => Durability::Batched,
+            IrohDurability::DurabilityImmediate => Durability::Immediate,
+        }
+    }
+}
+
+/// Callback for compare-and-set document writes.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocSetCasCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the new content hash (caller must free with `iroh_string_free`).
+    pub on_success: extern "C" fn(userdata: *mut c_void, hash: *const c_char),
+    /// Called when the current entry does not match the expectation, with its
+    /// content hash, or null if the key is absent (caller must free with `iroh_string_free`).
+    pub on_conflict: extern "C" fn(userdata: *mut c_void, current_hash: *const c_char),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for compare-and-set document writes reporting binary hashes.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocSetCasHashCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the new content hash.
+    pub on_success: extern "C" fn(userdata: *mut c_void, hash: IrohHash),
+    /// Called when the current entry does not match the expectation, with its
+    /// content hash, or null if the key is absent. Only valid during the call.
+    pub on_conflict: extern "C" fn(userdata: *mut c_void, current_hash: *const IrohHash),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for mergeable counter operations.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocCounterCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the counter's value.
+    pub on_success: extern "C" fn(userdata: *mut c_void, value: i64),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for document writes added to the outbox.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocOutboxCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the outbox item ID.
+    pub on_success: extern "C" fn(userdata: *mut c_void, item_id: u64),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// A document write waiting for a peer (see `iroh_outbox_list`).
+///
+/// Strings and bytes are only valid for the duration of the callback.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohOutboxItem {
+    /// Outbox item ID.
+    pub item_id: u64,
+    /// Namespace ID of the document.
+    pub namespace_id: *const c_char,
+    /// Author ID of the entry.
+    pub author_id: *const c_char,
+    /// Entry key.
+    pub key: IrohBytes,
+    /// Content hash of the entry.
+    pub content_hash: IrohHash,
+    /// Entry timestamp in microseconds since epoch.
+    pub timestamp: u64,
+}
+
+/// Streaming callback for outbox items.
+/// Called once per item, then on_complete.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohOutboxCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each pending item, oldest first.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, item: IrohOutboxItem),
+    /// Called after the last item.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// A durable peer of a document (see `iroh_doc_durable_status`).
+///
+/// Strings are only valid for the duration of the callback.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDurablePeer {
+    /// Peer node ID.
+    pub node_id: *const c_char,
+    /// Time before which the peer confirmed every local write (microseconds
+    /// since epoch, 0 if it never synced).
+    pub confirmed_through: u64,
+}
+
+/// How far a document's local writes are backed up on its durable peers.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDurableStatus {
+    /// Time before which every durable peer confirmed every local write
+    /// (microseconds since epoch, 0 if one never synced or there are none).
+    pub confirmed_through: u64,
+    /// Time of the latest local write (microseconds since epoch, 0 if none).
+    pub last_local_write: u64,
+    /// Whether there are durable peers and all of them have every local write.
+    pub backed_up: bool,
+}
+
+/// Streaming callback for durable peer status.
+/// Called once per durable peer, then on_complete with the overall status.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDurableStatusCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each durable peer, sorted by node ID.
+    pub on_peer: extern "C" fn(userdata: *mut c_void, peer: IrohDurablePeer),
+    /// Called after the last peer.
+    pub on_complete: extern "C" fn(userdata: *mut c_void, status: IrohDurableStatus),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// An asset in a backup manifest (see `iroh_manifest_list`).
+///
+/// Strings are only valid for the duration of the callback.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohManifestAsset {
+    /// App-defined asset ID.
+    pub asset_id: *const c_char,
+    /// Content hash of the asset's blob.
+    pub hash: IrohHash,
+    /// Content size in bytes.
+    pub size: u64,
+    /// Original filename (may be null).
+    pub filename: *const c_char,
+    /// MIME type of the content (may be null).
+    pub content_type: *const c_char,
+    /// Creation date in seconds since the Unix epoch.
+    pub created_at: u64,
+}
+
+/// Streaming callback for manifest assets.
+/// Called once per asset, then on_complete.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohManifestCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each asset, sorted by asset ID.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, asset: IrohManifestAsset),
+    /// Called after the last asset.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for assets missing from a backup manifest.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohManifestMissingCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the missing asset IDs, sorted (only valid
+    /// for the duration of the callback).
+    pub on_success:
+        extern "C" fn(userdata: *mut c_void, asset_ids: *const *const c_char, len: usize),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Swarm statistics for a document's gossip topic.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocSwarmStats {
+    /// Peers currently connected on the document's topic.
+    pub neighbors: u64,
+    /// Local inserts announced to the swarm.
+    pub messages_sent: u64,
+    /// Inserts received from peers.
+    pub messages_received: u64,
+    /// Time of the last swarm event in microseconds since epoch (0 if none).
+    pub last_activity: u64,
+}
+
+/// Callback for document swarm statistics.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocSwarmStatsCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the statistics.
+    pub on_success: extern "C" fn(userdata: *mut c_void, stats: IrohDocSwarmStats),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// How syncs of a document with one peer went (see `iroh_doc_peer_sync_stats`).
+///
+/// Strings are only valid for the duration of the callback.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohPeerSyncStats {
+    /// Peer node ID.
+    pub peer_id: *const c_char,
+    /// Time the last successful sync finished in microseconds since epoch
+    /// (0 if none).
+    pub last_success: u64,
+    /// Local inserts since the last successful sync, which the peer may not
+    /// have yet.
+    pub pending_entries: u64,
+    /// Syncs that failed since the last successful one.
+    pub failure_streak: u64,
+    /// Why the last sync failed, or null if it succeeded.
+    pub last_error: *const c_char,
+}
+
+/// Streaming callback for peer sync statistics.
+/// Called once per peer, then on_complete.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohPeerSyncStatsCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each peer, sorted by node ID.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, stats: IrohPeerSyncStats),
+    /// Called after the last peer.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for document delete operations.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocDelCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with count of deleted entries.
+    pub on_success: extern "C" fn(userdata: *mut c_void, deleted_count: u64),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Callback for document exports.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocExportCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called on success with the number of entries exported.
+    pub on_success: extern "C" fn(userdata: *mut c_void, entries_exported: u64),
+    /// Called on failure with an error message.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Filter for document entry queries.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocQuery {
+    /// Only keys starting with these bytes (empty for all keys).
+    pub key_prefix: IrohBytes,
+    /// Only entries written at or after this time (microseconds since epoch, 0 for no bound).
+    pub timestamp_start: u64,
+    /// Only entries written before this time (microseconds since epoch, 0 for no bound).
+    pub timestamp_end: u64,
+    /// Only the winning entry per key: the latest across authors, ties going
+    /// to the lowest author ID. Keys whose winning entry is a deletion are
+    /// left out, and the timestamp range applies to the winning entry.
+    pub latest_only: bool,
+}
+
+/// Streaming callback for get_many (prefix queries).
+/// Called multiple times - once per entry, then on_complete.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocGetManyCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each entry found. Entry must be freed with `iroh_doc_entry_free`.
+    pub on_entry: extern "C" fn(userdata: *mut c_void, entry: *const IrohDocEntry),
+    /// Called when iteration completes successfully.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// Streaming callback for document subscriptions.
+/// Called multiple times - once per event, then on_complete when stream ends.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocSubscribeCallback {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called for each event. Event must be freed with `iroh_doc_event_free`.
+    pub on_event: extern "C" fn(userdata: *mut c_void, event: IrohDocEvent),
+    /// Called when subscription ends normally.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// One author's latest value for a conflicting document key.
+/// Strings and bytes are only valid for the duration of the resolve call.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohConflictCandidate {
+    /// Author ID (hex string).
+    pub author: *const c_char,
+    /// Content hash (hex string).
+    pub content_hash: *const c_char,
+    /// Entry content.
+    pub content: IrohBytes,
+    /// Entry timestamp (microseconds since epoch).
+    pub timestamp: u64,
+}
+
+/// Opaque slot receiving the value chosen by a conflict resolver.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohConflictResolution {
+    _private: [u8; 0],
+}
+
+/// Resolver for keys whose authors hold diverging values.
+/// Runs until cancelled with `iroh_subscription_cancel`, then on_complete.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocConflictResolver {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Called with the key and every author's latest entry for it. Call
+    /// `iroh_conflict_resolution_set` before returning to write a value;
+    /// otherwise the entries are left as they are.
+    pub resolve: extern "C" fn(
+        userdata: *mut c_void,
+        key: IrohBytes,
+        candidates: *const IrohConflictCandidate,
+        candidates_len: usize,
+        resolution: *mut IrohConflictResolution,
+    ),
+    /// Called when the resolver stops normally.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+/// What a document write filter does with a remote entry.
+#[cfg(feature = "docs")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub enum IrohWriteVerdict {
+    /// Keep the entry.
+    WriteAccept = 0,
+    /// Roll the entry back.
+    WriteReject = 1,
+    /// Roll the entry back and report it with its content.
+    WriteQuarantine = 2,
+}
+
+#[cfg(feature = "docs")]
+impl From<IrohWriteVerdict> for Verdict {
+    fn from(verdict: IrohWriteVerdict) -> Self {
+        match verdict {
+            IrohWriteVerdict::WriteAccept => Verdict::Accept,
+            IrohWriteVerdict::WriteReject => Verdict::Reject,
+            IrohWriteVerdict::WriteQuarantine => Verdict::Quarantine,
+        }
+    }
+}
+
+#[cfg(feature = "docs")]
+impl From<Verdict> for IrohWriteVerdict {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Accept => IrohWriteVerdict::WriteAccept,
+            Verdict::Reject => IrohWriteVerdict::WriteReject,
+            Verdict::Quarantine => IrohWriteVerdict::WriteQuarantine,
+        }
+    }
+}
+
+/// A remote document entry seen by a write filter.
+/// Strings and bytes are only valid for the duration of the callback.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohFilteredEntry {
+    /// Entry key.
+    pub key: IrohBytes,
+    /// Author ID (hex string).
+    pub author: *const c_char,
+    /// Content hash (hex string).
+    pub content_hash: *const c_char,
+    /// Content length in bytes.
+    pub content_len: u64,
+    /// Entry timestamp (microseconds since epoch).
+    pub timestamp: u64,
+}
+
+/// Policy for remote writes to a document.
+/// Runs until cancelled with `iroh_subscription_cancel`, then on_complete.
+#[cfg(feature = "docs")]
+#[repr(C)]
+pub struct IrohDocWriteFilter {
+    /// Opaque pointer passed back to Swift.
+    pub userdata: *mut c_void,
+    /// Author IDs (hex strings) whose entries are always accepted.
+    pub allowed_authors: *const *const c_char,
+    /// Number of allowed authors.
+    pub allowed_authors_len: usize,
+    /// Decides entries from other authors; if null they are rejected.
+    pub decide:
+        Option<extern "C" fn(userdata: *mut c_void, entry: IrohFilteredEntry) -> IrohWriteVerdict>,
+    /// Called after an entry was rejected or quarantined. `content` is
+    /// empty for rejected entries. May be null.
+    pub on_filtered: Option<
+        extern "C" fn(
+            userdata: *mut c_void,
+            entry: IrohFilteredEntry,
+            verdict: IrohWriteVerdict,
+            content: IrohBytes,
+        ),
+    >,
+    /// Called when the filter stops normally.
+    pub on_complete: extern "C" fn(userdata: *mut c_void),
+    /// Called on error. No more callbacks after this.
+    pub on_failure: extern "C" fn(userdata: *mut c_void, error: *const c_char),
+}
+
+// ============================================================================
+// Node Lifecycle
+// ============================================================================
+
+/// Create a new Iroh node asynchronously.
+///
+/// # Safety
+/// - `config` must be laid out as in the header shipped with this library
+/// - `config.storage_path` must be a valid null-terminated UTF-8 string
+/// - `config.custom_relay_url` must be null or a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_node_create(config: IrohNodeConfig, callback: IrohNodeCreateCallback) {
+    let _operation = trace::begin("iroh_node_create");
+    // Parse the storage path
+    let storage_path = if config.storage_path.is_null() && config.ephemeral {
+        PathBuf::new()
+    } else if config.storage_path.is_null() {
+        let error = CString::new("storage_path cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    } else {
+        let path_str = unsafe { CStr::from_ptr(config.storage_path) };
+        match path_str.to_str() {
+            Ok(s) => PathBuf::from(s),
+            Err(e) => {
+                let error = CString::new(format!("Invalid storage path: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    };
+
+    // Parse optional custom relay URL
+    let custom_relay_url = if config.custom_relay_url.is_null() {
+        None
+    } else {
+        let url_str = unsafe { CStr::from_ptr(config.custom_relay_url) };
+        match url_str.to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(e) => {
+                let error = CString::new(format!("Invalid custom relay URL: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    };
+
+    // Parse optional temp directory
+    let temp_dir = if config.temp_dir.is_null() {
+        None
+    } else {
+        let dir_str = unsafe { CStr::from_ptr(config.temp_dir) };
+        match dir_str.to_str() {
+            Ok(s) => Some(PathBuf::from(s)),
+            Err(e) => {
+                let error = CString::new(format!("Invalid temp dir: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    };
+
+    // Parse optional app identifier
+    let app_id = if config.app_id.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(config.app_id) }.to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(e) => {
+                let error = CString::new(format!("Invalid app identifier: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    };
+
+    // Start exporting telemetry before the node does any work
+    if !config.otlp_endpoint.is_null() {
+        let endpoint = match unsafe { CStr::from_ptr(config.otlp_endpoint) }.to_str() {
+            Ok(s) => s,
+            Err(e) => {
+                let error = CString::new(format!("Invalid OTLP endpoint: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        };
+        if let Err(e) = start_telemetry(endpoint, app_id.as_deref()) {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    }
+
+    // Parse optional control server address
+    let control_socket = if config.control_socket.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(config.control_socket) }.to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(e) => {
+                let error = CString::new(format!("Invalid control socket: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    };
+
+    // Parse optional gateway URL
+    let gateway_url = if config.gateway_url.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(config.gateway_url) }.to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(e) => {
+                let error = CString::new(format!("Invalid gateway URL: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    };
+
+    // Parse optional test seed
+    let test_seed = if config.test_seed.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(config.test_seed) }.to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(e) => {
+                let error = CString::new(format!("Invalid test seed: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    };
+
+    let secret_key = match entropy_seed(&config.entropy) {
+        Ok(seed) => seed.map(|seed| SecretKey::from_bytes(&seed)),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    // Start the delivery thread before adapting callbacks that use it
+    let callbacks = if config.dedicated_callback_thread {
+        match Delivery::dedicated() {
+            Ok(delivery) => delivery,
+            Err(e) => {
+                let error = trace::failure(&e);
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    } else {
+        Delivery::inline()
+    };
+
+    let options = NodeOptions {
+        relay_enabled: config.relay_enabled,
+        custom_relay_url,
+        docs_enabled: config.docs_enabled,
+        temp_dir,
+        ttl_prune_interval: (config.ttl_prune_interval_ms > 0)
+            .then(|| Duration::from_millis(config.ttl_prune_interval_ms)),
+        secret_key,
+        max_tls_tickets: (config.max_tls_tickets > 0).then_some(config.max_tls_tickets as usize),
+        idle_timeout: (config.idle_timeout_ms > 0)
+            .then(|| Duration::from_millis(config.idle_timeout_ms)),
+        max_connections: (config.max_connections > 0).then_some(config.max_connections as usize),
+        discovery: app_discovery(&config.discovery, &callbacks),
+        default_discovery: !config.disable_default_discovery,
+        storage_alert: storage_alert(&config.storage_alert, &callbacks),
+        watchdog: watchdog(&config.slow_operation_watchdog, &callbacks),
+        control_socket,
+        gateway_url,
+        blob_access: config.blob_access.into(),
+        request_limits: (&config.request_limits).into(),
+        bandwidth_limits: (&config.bandwidth_limits).into(),
+        content_announcer: content_announcer(&config.content_announcer, &callbacks),
+        test_seed,
+        callbacks,
+        app_id,
+        store_inlining: (&config.store_inlining).into(),
+        worker_threads: (config.worker_threads > 0).then_some(config.worker_threads as usize),
+        max_blocking_threads: (config.max_blocking_threads > 0)
+            .then_some(config.max_blocking_threads as usize),
+        blob_store_recovery: config.blob_store_recovery.into(),
+        docs_store_recovery: config.docs_store_recovery.into(),
+        ephemeral: config.ephemeral,
+    };
+
+    create_node(storage_path, options, callback);
+}
+
+/// Create a new Iroh node from a versioned JSON configuration.
+///
+/// Unlike `IrohNodeConfig`, whose layout changes whenever an option is
+/// added, the JSON config keeps working across releases. It must set
+/// `"version": 1` and `storage_path`, unless `ephemeral` is true; other
+/// options use the `IrohNodeConfig` field names and defaults, for example:
+///
+/// `{"version": 1, "storage_path": "/path/to/store", "docs_enabled": true}`
+///
+/// Unknown options fail node creation instead of being ignored. Options
+/// that take callbacks (entropy, discovery, storage alerts, the slow
+/// operation and content announcement callbacks) are only available
+/// through `iroh_node_create`.
+///
+/// # Safety
+/// - `config_json` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_create_from_json(
+    config_json: *const c_char,
+    callback: IrohNodeCreateCallback,
+) {
+    let _operation = trace::begin("iroh_node_create_from_json");
+    if config_json.is_null() {
+        let error = CString::new("config_json cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let json = match unsafe { CStr::from_ptr(config_json) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid config UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let config = match config::parse(json) {
+        Ok(config) => config,
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    // Start exporting telemetry before the node does any work
+    if let Some(endpoint) = &config.otlp_endpoint
+        && let Err(e) = start_telemetry(endpoint, config.options.app_id.as_deref())
+    {
+        let error = trace::failure(&e);
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let mut options = config.options;
+    if config.dedicated_callback_thread {
+        options.callbacks = match Delivery::dedicated() {
+            Ok(delivery) => delivery,
+            Err(e) => {
+                let error = trace::failure(&e);
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        };
+    }
+
+    create_node(config.storage_path, options, callback);
+}
+
+/// Create the node and hand its handle to `callback`.
+fn create_node(storage_path: PathBuf, options: NodeOptions, callback: IrohNodeCreateCallback) {
+    // Create the node synchronously
+    // Note: Swift should call this from a background thread/task
+    match IrohNode::with_options(storage_path, options) {
+        Ok(node) => {
+            // Box the node and convert to raw pointer
+            let boxed = Box::new(node);
+            let handle = Box::into_raw(boxed) as *mut IrohNodeHandle;
+            handles::register(HandleKind::Node, handle);
+            // Safety: the node is only freed through its gate
+            unsafe { drain::open(handle as *const IrohNode) };
+            (callback.on_success)(callback.userdata, handle);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            match e.downcast_ref::<StoreCorrupt>() {
+                Some(corrupt) => {
+                    let path = CString::new(corrupt.path.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let reason = CString::new(corrupt.reason.clone()).unwrap_or_default();
+                    (callback.on_corrupt)(
+                        callback.userdata,
+                        corrupt.database.into(),
+                        path.into_raw(),
+                        reason.into_raw(),
+                    );
+                }
+                None => (callback.on_failure)(callback.userdata, error.into_raw()),
+            }
+        }
+    }
+}
+
+/// Destroy an Iroh node and free its resources.
+///
+/// This performs a graceful shutdown, ensuring pending writes are flushed.
+/// Subscriptions are cancelled and deliver their `on_complete` first. Calls
+/// still running on other threads finish against the shut down node, which
+/// is freed when the last of them returns; calls made afterwards fail.
+///
+/// # Safety
+/// - `handle` must be a valid pointer returned by `iroh_node_create`
+/// - `handle` must not be used after this call
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_node_destroy(handle: *mut IrohNodeHandle) {
+    if handle.is_null() {
+        return;
+    }
+    let Some(gate) = drain::close(handle) else {
+        return;
+    };
+
+    handles::release(handle);
+    let node = unsafe { &*(handle as *const IrohNode) };
+    // Attempt graceful shutdown, ignore errors
+    let _ = node.close(None);
+    free_node(&gate, handle, true);
+    #[cfg(feature = "otel")]
+    crate::telemetry::flush();
+}
+
+/// Free a closed node once no call is inside it.
+///
+/// A node whose close was not graceful is abandoned rather than waiting
+/// again for its stuck work.
+fn free_node(gate: &drain::Gate, handle: *mut IrohNodeHandle, graceful: bool) {
+    let address = handle as usize;
+    gate.free_when_idle(move || {
+        let node = unsafe { Box::from_raw(address as *mut IrohNode) };
+        if graceful {
+            drop(node);
+        } else {
+            node.abandon();
+        }
+    });
+}
+
+// ============================================================================
+// Core Operations
+// ============================================================================
+
+/// Add bytes to the blob store and get a shareable ticket.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `bytes.data` must point to valid memory for `bytes.len` bytes
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_put(
+    handle: *const IrohNodeHandle,
+    bytes: IrohBytes,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_put");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    // Copy the bytes to own them (Swift memory may not be stable)
+    let data = if bytes.data.is_null() || bytes.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(bytes.data, bytes.len).to_vec() }
+    };
+
+    // Get reference to node (we don't own it)
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    // Perform the put operation
+    // Note: This blocks on the node's runtime, which is intentional
+    match node.put(&data) {
+        Ok(ticket) => {
+            stats::bytes_in(data.len());
+            let ticket_cstr = CString::new(ticket).unwrap();
+            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Add the file at `path` to the blob store and get a shareable ticket.
+///
+/// Like `iroh_put`, but the file is streamed from disk and hashed without
+/// being read into memory, so large media can be shared. The content is
+/// copied into the store, so the file may change or be deleted afterwards.
+/// `on_progress` is called while the file is copied and hashed.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `path` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_put_file(
+    handle: *const IrohNodeHandle,
+    path: *const c_char,
+    callback: IrohPutFileCallback,
+) {
+    let _operation = trace::begin("iroh_put_file");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let path = match unsafe { required_str(path, "path") } {
+        Ok(path) => path,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let mut size = 0;
+    let result = node.put_file(std::path::Path::new(&path), |progress| {
+        size = progress.total;
+        (callback.on_progress)(callback.userdata, progress.into())
+    });
+    match result {
+        Ok(ticket) => {
+            stats::bytes_in(size as usize);
+            let ticket_cstr = CString::new(ticket).unwrap();
+            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Download bytes from a ticket.
+///
+/// A blob already complete in the local store is returned without
+/// contacting the ticket's provider.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_get(
+    handle: *const IrohNodeHandle,
+    ticket: *const c_char,
+    callback: IrohGetCallback,
+) {
+    let _operation = trace::begin("iroh_get");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    // Parse the ticket string
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    // Get reference to node
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    // Perform the get operation
+    match node.get(&ticket_str) {
+        Ok(bytes) => {
+            let mut vec = bytes;
+            let owned = IrohOwnedBytes {
+                data: vec.as_mut_ptr(),
+                len: vec.len(),
+                capacity: vec.capacity(),
+            };
+            std::mem::forget(vec); // Prevent deallocation, Swift will free
+            stats::bytes_out(owned.len);
+            (callback.on_success)(callback.userdata, owned);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+// ============================================================================
+// Memory Management
+// ============================================================================
+
+/// Free a string returned by Iroh functions.
+///
+/// # Safety
+/// - `s` must be a pointer returned by an Iroh function, or null
+/// - `s` must not be used after this call
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        unsafe {
+            drop(CString::from_raw(s));
+        }
+    }
+}
+
+/// Free bytes returned by `iroh_get`.
+///
+/// # Safety
+/// - `bytes` must have been returned by `iroh_get`
+/// - The bytes must not be used after this call
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_bytes_free(bytes: IrohOwnedBytes) {
+    if !bytes.data.is_null() {
+        unsafe {
+            // Reconstruct the Vec and let it drop
+            drop(Vec::from_raw_parts(bytes.data, bytes.len, bytes.capacity));
+        }
+    }
+}
+
+/// Return bytes to the buffer pool for reuse by later results.
+///
+/// Use this instead of `iroh_bytes_free` for small results read in bulk,
+/// such as document content, to avoid an allocation per result. Buffers the
+/// pool cannot keep are freed.
+///
+/// # Safety
+/// - `bytes` must have been returned by an Iroh function
+/// - The bytes must not be used after this call
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_bytes_recycle(bytes: IrohOwnedBytes) {
+    if !bytes.data.is_null() {
+        unsafe {
+            pool::recycle(Vec::from_raw_parts(bytes.data, bytes.len, bytes.capacity));
+        }
+    }
+}
+
+/// Encode a hash as a 64-character hex string.
+///
+/// # Safety
+/// - The returned string must be freed with `iroh_string_free`
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_hash_to_hex(hash: IrohHash) -> *mut c_char {
+    CString::new(Hash::from(hash).to_hex()).unwrap().into_raw()
+}
+
+/// Decode a hex (or base32) hash into `out`.
+///
+/// Returns false, leaving `out` untouched, if `hex` is not a valid hash.
+///
+/// # Safety
+/// - `hex` must be a valid null-terminated string
+/// - `out` must be valid for writes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_hash_from_hex(hex: *const c_char, out: *mut IrohHash) -> bool {
+    if hex.is_null() || out.is_null() {
+        return false;
+    }
+    let Ok(hex) = unsafe { CStr::from_ptr(hex) }.to_str() else {
+        return false;
+    };
+    let Ok(hash) = hex.parse::<Hash>() else {
+        return false;
+    };
+    unsafe { *out = hash.into() };
+    true
+}
+
+/// List node, document and subscription handles that are still live.
+///
+/// Use this while debugging to find handles leaked across the boundary.
+/// Debug builds include the backtrace of the call that created each handle.
+///
+/// # Safety
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_debug_handles(callback: IrohLiveHandlesCallback) {
+    for live in handles::live() {
+        let backtrace = live
+            .backtrace
+            .map(|backtrace| CString::new(backtrace.replace('\0', "")).unwrap());
+        let handle = IrohLiveHandle {
+            kind: match live.kind {
+                HandleKind::Node => IrohHandleKind::NodeHandle,
+                HandleKind::Doc => IrohHandleKind::DocHandle,
+                HandleKind::Subscription => IrohHandleKind::SubscriptionHandle,
+            },
+            address: live.address,
+            created_at: live.created_at,
+            backtrace: backtrace
+                .as_ref()
+                .map_or(std::ptr::null(), |backtrace| backtrace.as_ptr()),
+        };
+        (callback.on_handle)(callback.userdata, handle);
+    }
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Id of the operation most recently started on the calling thread, or 0.
+///
+/// Every call that reports back through a callback starts an operation.
+/// Read this right after such a call, on the same thread, to learn its id.
+/// Download events it causes carry the id in `operation_id`.
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_last_operation_id() -> u64 {
+    trace::last()
+}
+
+/// Id of the operation running on the calling thread, or 0 if none.
+///
+/// A call's success and failure callbacks run inside its operation, so
+/// reading this from either gives the id of the call reporting its result.
+/// Callbacks of subscriptions that run later, on the node's threads, get 0.
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_current_operation_id() -> u64 {
+    trace::current().unwrap_or(0)
+}
+
+/// Read operation counters for all nodes in the process.
+///
+/// The callbacks are called before this returns. Failures are broken down
+/// by the FFI function that failed.
+///
+/// # Safety
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_operation_counters(callback: IrohOperationCountersCallback) {
+    let counters = stats::snapshot();
+    for (operation, count) in counters.failures {
+        let operation = CString::new(operation).unwrap();
+        (callback.on_failure_count)(callback.userdata, operation.as_ptr(), count);
+    }
+    (callback.on_complete)(
+        callback.userdata,
+        IrohOperationCounters {
+            puts: counters.puts,
+            gets: counters.gets,
+            doc_sets: counters.doc_sets,
+            bytes_in: counters.bytes_in,
+            bytes_out: counters.bytes_out,
+        },
+    );
+}
+
+/// Register the process-wide log callback, replacing any earlier one.
+///
+/// From the first call on, every panic in the library, including in tasks
+/// on a node's runtime, is passed to the callback with its location and
+/// backtrace before the panic is contained or the process aborts.
+///
+/// # Safety
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_log_init(callback: IrohLogCallback) {
+    let guard = ReleaseGuard {
+        userdata: callback.userdata as usize,
+        release: callback.release,
+    };
+    let on_log = callback.on_log;
+    logging::init(Box::new(move |message| {
+        let message = CString::new(message.replace('\0', "")).unwrap();
+        on_log(guard.userdata as *mut c_void, message.as_ptr());
+    }));
+}
+
+// ============================================================================
+// Extended Operations
+// ============================================================================
+
+/// Download bytes from a ticket with progress reporting.
+///
+/// Progress reports received and verified bytes. After the last byte
+/// arrives, a `DownloadVerifying` update is sent while the store confirms
+/// the blob, then a `DownloadVerified` update right before `on_success`.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "downloader-progress")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_get_with_progress(
+    handle: *const IrohNodeHandle,
+    ticket: *const c_char,
+    callback: IrohGetProgressCallback,
+) {
+    let _operation = trace::begin("iroh_get_with_progress");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    // Parse the ticket string
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    let userdata = callback.userdata;
+    let on_progress_fn = callback.on_progress;
+
+    // Progress callback closure
+    let progress_fn = move |progress: DownloadProgress| {
+        let progress = IrohDownloadProgress {
+            downloaded: progress.received,
+            total: progress.total,
+            verified: progress.verified,
+            stage: progress.stage.into(),
+        };
+        (on_progress_fn)(userdata, progress);
+    };
+
+    match node.get_with_progress(&ticket_str, progress_fn) {
+        Ok(bytes) => {
+            let mut vec = bytes;
+            let owned = IrohOwnedBytes {
+                data: vec.as_mut_ptr(),
+                len: vec.len(),
+                capacity: vec.capacity(),
+            };
+            std::mem::forget(vec);
+            stats::bytes_out(owned.len);
+            (callback.on_success)(callback.userdata, owned);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Download a blob, pushing verified chunks to `writer` as they arrive.
+///
+/// Content is streamed straight from the provider without being staged in
+/// the store, so it can be fed directly into output streams, sockets or
+/// decryptors. Only raw blob tickets are supported. `writer.write` is called
+/// on the calling thread before `callback` fires.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `writer` and `callback` must have valid function pointers
+#[cfg(feature = "downloader-progress")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_get_to_sink(
+    handle: *const IrohNodeHandle,
+    ticket: *const c_char,
+    writer: IrohSinkWriter,
+    callback: IrohSinkCallback,
+) {
+    let _operation = trace::begin("iroh_get_to_sink");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let result = node.get_to_sink(&ticket_str, |chunk| {
+        let bytes = IrohBytes {
+            data: chunk.as_ptr(),
+            len: chunk.len(),
+        };
+        (writer.write)(writer.userdata, bytes)
+    });
+
+    match result {
+        Ok(total) => {
+            stats::bytes_out(total as usize);
+            (callback.on_success)(callback.userdata, total);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Download content available from several providers of the same hash.
+///
+/// All tickets must refer to the same content. See `IrohProviderStrategy`
+/// for how the providers are used.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `tickets` must point to `tickets_len` valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_get_from_providers(
+    handle: *const IrohNodeHandle,
+    tickets: *const *const c_char,
+    tickets_len: usize,
+    strategy: IrohProviderStrategy,
+    callback: IrohGetCallback,
+) {
+    let _operation = trace::begin("iroh_get_from_providers");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if tickets.is_null() || tickets_len == 0 {
+        let error = CString::new("tickets cannot be empty").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let ticket_ptrs = unsafe { std::slice::from_raw_parts(tickets, tickets_len) };
+    let mut ticket_strs = Vec::with_capacity(tickets_len);
+    for &ptr in ticket_ptrs {
+        if ptr.is_null() {
+            let error = CString::new("ticket cannot be null").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+        match unsafe { CStr::from_ptr(ptr) }.to_str() {
+            Ok(s) => ticket_strs.push(s.to_string()),
+            Err(e) => {
+                let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.get_from_providers(&ticket_strs, strategy.into()) {
+        Ok(bytes) => {
+            let mut vec = std::mem::ManuallyDrop::new(bytes);
+            let owned = IrohOwnedBytes {
+                data: vec.as_mut_ptr(),
+                len: vec.len(),
+                capacity: vec.capacity(),
+            };
+            stats::bytes_out(owned.len);
+            (callback.on_success)(callback.userdata, owned);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Get the node ID as a string, without a callback.
+///
+/// Returns null if `handle` is null or the node is closed.
+///
+/// # Safety
+/// - `handle` must be a valid node handle or null
+/// - The returned string must be freed with `iroh_string_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_id(handle: *const IrohNodeHandle) -> *mut c_char {
+    if handle.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Some(node) = enter_node(handle) else {
+        return std::ptr::null_mut();
+    };
+    CString::new(node.endpoint().id().to_string())
+        .unwrap()
+        .into_raw()
+}
+
+/// Check whether the node was created with docs enabled, without a callback.
+///
+/// Returns false if `handle` is null or the node is closed.
+///
+/// # Safety
+/// - `handle` must be a valid node handle or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_docs_enabled(handle: *const IrohNodeHandle) -> bool {
+    if handle.is_null() {
+        return false;
+    }
+    let Some(node) = enter_node(handle) else {
+        return false;
+    };
+    node.is_docs_enabled()
+}
+
+/// Get the node's storage directory, without a callback.
+///
+/// Returns null if `handle` is null, the node is closed, or the path is not
+/// valid UTF-8.
+///
+/// # Safety
+/// - `handle` must be a valid node handle or null
+/// - The returned string must be freed with `iroh_string_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_storage_path(handle: *const IrohNodeHandle) -> *mut c_char {
+    if handle.is_null() {
+        return std::ptr::null_mut();
+    }
+    let Some(node) = enter_node(handle) else {
+        return std::ptr::null_mut();
+    };
+    node.storage_path()
+        .to_str()
+        .and_then(|path| CString::new(path).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Get information about the node.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_node_info(handle: *const IrohNodeHandle, callback: IrohNodeInfoCallback) {
+    let _operation = trace::begin("iroh_node_info");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.info() {
+        Ok(info) => {
+            let node_id = CString::new(info.node_id).unwrap().into_raw();
+            let relay_url = info
+                .relay_url
+                .map(|url| CString::new(url).unwrap().into_raw())
+                .unwrap_or(std::ptr::null_mut());
+
+            let app_id = info
+                .app_id
+                .map(|id| CString::new(id).unwrap().into_raw())
+                .unwrap_or(std::ptr::null_mut());
+
+            let ffi_info = IrohNodeInfo {
+                node_id,
+                relay_url,
+                is_connected: info.is_connected,
+                app_id,
+                network_condition: info.network_condition.into(),
+            };
+            (callback.on_success)(callback.userdata, ffi_info);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Close all connections a peer has open to this node.
+///
+/// Blob transfers and document syncs served to the peer stop immediately.
+/// The peer is not blocked and may connect again.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `node_id` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_connection_close(
+    handle: *const IrohNodeHandle,
+    node_id: *const c_char,
+    callback: IrohConnectionCloseCallback,
+) {
+    let _operation = trace::begin("iroh_connection_close");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if node_id.is_null() {
+        let error = CString::new("node_id cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let node_id_str = match unsafe { CStr::from_ptr(node_id) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid node ID UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let peer: EndpointId = match node_id_str.parse() {
+        Ok(id) => id,
+        Err(e) => {
+            let error = CString::new(format!("Invalid node ID: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    let closed = node.close_peer_connections(peer);
+    (callback.on_success)(callback.userdata, closed);
+}
+
+/// Dial a peer without transferring content and report how it was reached.
+///
+/// `node_addr` is a node ID or a blob ticket, whose address hints are used.
+/// Use this to warn before a large transfer that is bound to fail.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `node_addr` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_can_reach(
+    handle: *const IrohNodeHandle,
+    node_addr: *const c_char,
+    timeout_ms: u64,
+    callback: IrohCanReachCallback,
+) {
+    let _operation = trace::begin("iroh_can_reach");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if node_addr.is_null() {
+        let error = CString::new("node_addr cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let target = match unsafe { CStr::from_ptr(node_addr) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid node address UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    let timeout = (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms));
+
+    match node.can_reach(target, timeout) {
+        Ok(Reachability::Direct { latency }) => {
+            let result = IrohReachResult {
+                reachability: IrohReachability::Direct,
+                latency_us: latency.as_micros() as u64,
+                reason: std::ptr::null(),
+            };
+            (callback.on_success)(callback.userdata, result);
+        }
+        Ok(Reachability::Relay { latency }) => {
+            let result = IrohReachResult {
+                reachability: IrohReachability::Relay,
+                latency_us: latency.as_micros() as u64,
+                reason: std::ptr::null(),
+            };
+            (callback.on_success)(callback.userdata, result);
+        }
+        Ok(Reachability::Unreachable { reason }) => {
+            let reason = CString::new(reason.replace('\0', "")).unwrap();
+            let result = IrohReachResult {
+                reachability: IrohReachability::Unreachable,
+                latency_us: 0,
+                reason: reason.as_ptr(),
+            };
+            (callback.on_success)(callback.userdata, result);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Send a message to a peer running this library.
+///
+/// `node_addr` is a node ID or a blob ticket, whose address hints are used.
+/// Messages are meant for small control-plane signals, such as telling a
+/// known device to sync a document, and carry at most 64 KiB. `on_complete`
+/// is called once a subscriber on the peer received the message (see
+/// `iroh_node_subscribe_messages`); the call fails if the peer cannot be
+/// reached within 10 seconds or is not subscribed. Messages are not queued
+/// for offline peers.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `node_addr` must be a valid null-terminated UTF-8 string
+/// - `bytes.data` must point to valid memory for `bytes.len` bytes
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_message_send(
+    handle: *const IrohNodeHandle,
+    node_addr: *const c_char,
+    bytes: IrohBytes,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_message_send");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if node_addr.is_null() {
+        let error = CString::new("node_addr cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let target = match unsafe { CStr::from_ptr(node_addr) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid node address UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let payload = if bytes.data.is_null() || bytes.len == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(bytes.data, bytes.len) }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.send_message(target, payload, None) {
+        Ok(()) => (callback.on_complete)(callback.userdata),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Ask providers how much of a blob they have, without downloading it.
+///
+/// Providers are probed concurrently and found by node ID through
+/// discovery. A provider that cannot be reached, refuses the request or
+/// does not answer within `timeout_ms` (0 = 10 seconds) is reported as
+/// `BlobUnknown`; the call only fails on invalid arguments.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `node_ids` must point to `node_ids_len` valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_blob_probe_providers(
+    handle: *const IrohNodeHandle,
+    hash: IrohHash,
+    node_ids: *const *const c_char,
+    node_ids_len: usize,
+    timeout_ms: u64,
+    callback: IrohProviderProbeCallback,
+) {
+    let _operation = trace::begin("iroh_blob_probe_providers");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let id_ptrs = if node_ids.is_null() || node_ids_len == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(node_ids, node_ids_len) }
+    };
+    let mut providers = Vec::with_capacity(id_ptrs.len());
+    for &ptr in id_ptrs {
+        if ptr.is_null() {
+            let error = CString::new("node ID cannot be null").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+        let parsed = unsafe { CStr::from_ptr(ptr) }
+            .to_str()
+            .map_err(anyhow::Error::from)
+            .and_then(|s| s.parse::<EndpointId>().map_err(anyhow::Error::from));
+        match parsed {
+            Ok(provider) => providers.push(provider),
+            Err(e) => {
+                let error = CString::new(format!("Invalid node ID: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    let timeout = (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms));
+
+    for (provider, availability) in node.probe_providers(hash.into(), &providers, timeout) {
+        let node_id = CString::new(provider.to_string()).unwrap();
+        let (availability, size, reason) = match availability {
+            Availability::Complete { size } => (IrohBlobAvailability::BlobComplete, size, None),
+            Availability::Partial { size } => {
+                (IrohBlobAvailability::BlobPartial, size.unwrap_or(0), None)
+            }
+            Availability::Missing => (IrohBlobAvailability::BlobMissing, 0, None),
+            Availability::Unknown { reason } => (
+                IrohBlobAvailability::BlobUnknown,
+                0,
+                Some(CString::new(reason.replace('\0', "")).unwrap()),
+            ),
+        };
+        let probe = IrohProviderProbe {
+            node_id: node_id.as_ptr(),
+            availability,
+            size,
+            reason: reason.as_ref().map_or(std::ptr::null(), |r| r.as_ptr()),
+        };
+        (callback.on_provider)(callback.userdata, probe);
+    }
+    (callback.on_complete)(callback.userdata);
+}
+
+/// List bytes exchanged with each peer since the node started.
+///
+/// Counts connections that peers opened to this node, such as blob fetches
+/// and document syncs they initiated. Connections this node opens, such as
+/// its own downloads and syncs, are not counted.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_peer_traffic_list(
+    handle: *const IrohNodeHandle,
+    callback: IrohPeerTrafficCallback,
+) {
+    let _operation = trace::begin("iroh_peer_traffic_list");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    for (peer, traffic) in node.peer_traffic() {
+        let node_id = CString::new(peer.to_string()).unwrap();
+        let entry = IrohPeerTraffic {
+            node_id: node_id.as_ptr(),
+            bytes_uploaded: traffic.uploaded,
+            bytes_downloaded: traffic.downloaded,
+        };
+        (callback.on_entry)(callback.userdata, entry);
+    }
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Change who may fetch blobs from this node when a peer has no rule.
+///
+/// Applies to new connections; close a peer's connections with
+/// `iroh_connection_close` to stop transfers already in progress.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_blob_access_set_mode(
+    handle: *const IrohNodeHandle,
+    mode: IrohBlobAccessMode,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_blob_access_set_mode");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    node.set_blob_access_mode(mode.into());
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Allow or deny a peer fetching blobs from this node, or clear its rule.
+///
+/// Denying a peer also closes its open connections. Rejected fetch attempts
+/// are reported as `NodeFetchRejected` events.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `node_id` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_blob_access_set(
+    handle: *const IrohNodeHandle,
+    node_id: *const c_char,
+    rule: IrohBlobAccessRule,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_blob_access_set");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if node_id.is_null() {
+        let error = CString::new("node_id cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let node_id_str = match unsafe { CStr::from_ptr(node_id) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid node ID UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let peer: EndpointId = match node_id_str.parse() {
+        Ok(id) => id,
+        Err(e) => {
+            let error = CString::new(format!("Invalid node ID: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    node.set_blob_access(peer, rule.into());
+    (callback.on_complete)(callback.userdata);
+}
+
+/// List the blob access mode and every peer rule.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_blob_access_list(
+    handle: *const IrohNodeHandle,
+    callback: IrohBlobAccessCallback,
+) {
+    let _operation = trace::begin("iroh_blob_access_list");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let (mode, rules) = node.blob_access();
+    for (peer, rule) in rules {
+        let node_id = CString::new(peer.to_string()).unwrap();
+        let entry = IrohBlobAccessEntry {
+            node_id: node_id.as_ptr(),
+            rule: match rule {
+                AccessRule::Allow => IrohBlobAccessRule::BlobAccessAllow,
+                AccessRule::Deny => IrohBlobAccessRule::BlobAccessDeny,
+            },
+        };
+        (callback.on_entry)(callback.userdata, entry);
+    }
+    (callback.on_complete)(callback.userdata, mode.into());
+}
+
+/// Pause or resume serving blobs to peers.
+///
+/// While paused, blob connections from peers are closed, including
+/// transfers in progress, and new ones are turned away. Local reads and
+/// downloads from other nodes keep working. A new node serves blobs.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_set_provider_enabled(
+    handle: *const IrohNodeHandle,
+    enabled: bool,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_node_set_provider_enabled");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    node.set_provider_enabled(enabled);
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Check whether blobs are served to peers, without a callback.
+///
+/// Returns false if `handle` is null or the node is closed.
+///
+/// # Safety
+/// - `handle` must be a valid node handle or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_provider_enabled(handle: *const IrohNodeHandle) -> bool {
+    if handle.is_null() {
+        return false;
+    }
+    let Some(node) = enter_node(handle) else {
+        return false;
+    };
+    node.provider_enabled()
+}
+
+/// Report the latency to each configured relay and the home relay.
+///
+/// Latencies come from the endpoint's most recent network report; relays
+/// are unmeasured until the first report completes.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_relay_report(
+    handle: *const IrohNodeHandle,
+    callback: IrohRelayReportCallback,
+) {
+    let _operation = trace::begin("iroh_node_relay_report");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    let report = node.relay_report();
+
+    for relay in report.relays {
+        let url = CString::new(relay.url.to_string()).unwrap();
+        let entry = IrohRelayLatency {
+            url: url.as_ptr(),
+            is_measured: relay.latency.is_some(),
+            latency_us: relay.latency.map_or(0, |l| l.as_micros() as u64),
+            is_pinned: report.pinned.as_ref() == Some(&relay.url),
+        };
+        (callback.on_relay)(callback.userdata, entry);
+    }
+    let home = report
+        .home
+        .map(|url| CString::new(url.to_string()).unwrap());
+    (callback.on_complete)(
+        callback.userdata,
+        home.as_ref().map_or(std::ptr::null(), |url| url.as_ptr()),
+    );
+}
+
+/// Pin the node to one of its configured relays, or unpin it.
+///
+/// While pinned, the node uses `relay_url` as its home relay whatever the
+/// latency to the others, for example to keep relayed traffic in a region.
+/// The node moves after its next network report, which the change starts
+/// right away. Pass null to let the node choose the fastest relay again.
+/// Fails if `relay_url` is not one of the node's relays. The pin lasts
+/// until the node is closed.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `relay_url` must be null or a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_set_home_relay(
+    handle: *const IrohNodeHandle,
+    relay_url: *const c_char,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_node_set_home_relay");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let relay = if relay_url.is_null() {
+        None
+    } else {
+        let parsed = unsafe { CStr::from_ptr(relay_url) }
+            .to_str()
+            .map_err(anyhow::Error::from)
+            .and_then(|s| s.parse::<RelayUrl>().map_err(anyhow::Error::from));
+        match parsed {
+            Ok(url) => Some(url),
+            Err(e) => {
+                let error = CString::new(format!("Invalid relay URL: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.set_home_relay(relay) {
+        Ok(()) => (callback.on_complete)(callback.userdata),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Tell a node which kind of network the device is on.
+///
+/// The bandwidth caps configured for that network apply to transfers from
+/// then on, including those in progress. Nodes start on Wi-Fi; call this
+/// whenever the device's network path changes.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_set_network_kind(
+    handle: *const IrohNodeHandle,
+    kind: IrohNetworkKind,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_node_set_network_kind");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    node.set_network_kind(kind.into());
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Replace the faults injected into a node, for testing error handling.
+///
+/// Puts fail while `fail_puts` lasts, downloads start `download_delay_ms`
+/// late, and peers starting a document sync are dropped at
+/// `sync_drop_percent`. Pass all zeros to stop injecting faults. Fails
+/// unless the library was built with the `faults` feature.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_inject_faults(
+    handle: *const IrohNodeHandle,
+    faults: IrohFaults,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_node_inject_faults");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match inject_faults(&node, &faults) {
+        Ok(()) => (callback.on_complete)(callback.userdata),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Check that the node's store, runtime and endpoint still work.
+///
+/// Each check gives up after a few seconds, so a wedged node produces an
+/// unhealthy report instead of blocking forever. Apps can recreate the
+/// node when the report is unhealthy.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_health(
+    handle: *const IrohNodeHandle,
+    callback: IrohNodeHealthCallback,
+) {
+    let _operation = trace::begin("iroh_node_health");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    let health = node.health(DEFAULT_HEALTH_CHECK_TIMEOUT);
+
+    let to_cstring = |error: &Option<String>| {
+        error
+            .as_ref()
+            .map(|error| CString::new(error.as_str()).unwrap())
+    };
+    let store_error = to_cstring(&health.store);
+    let runtime_error = to_cstring(&health.runtime);
+    let endpoint_error = to_cstring(&health.endpoint);
+    let as_ptr = |error: &Option<CString>| error.as_ref().map_or(std::ptr::null(), |e| e.as_ptr());
+
+    let report = IrohNodeHealth {
+        is_healthy: health.is_healthy(),
+        store_writable: health.store.is_none(),
+        store_error: as_ptr(&store_error),
+        runtime_responsive: health.runtime.is_none(),
+        runtime_error: as_ptr(&runtime_error),
+        endpoint_bound: health.endpoint.is_none(),
+        endpoint_error: as_ptr(&endpoint_error),
+    };
+    (callback.on_success)(callback.userdata, report);
+}
+
+/// Watch the direct addresses peers can use to reach this node.
+///
+/// The current addresses are delivered first, then the full set again
+/// whenever it changes, for example after a network change or a new STUN
+/// observation. Returns a handle to stop watching with
+/// `iroh_subscription_cancel`.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers that remain valid until
+///   on_complete or on_failure is called
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_watch_direct_addrs(
+    handle: *const IrohNodeHandle,
+    callback: IrohDirectAddrsCallback,
+) -> *mut IrohSubscriptionHandle {
+    let _operation = trace::begin("iroh_node_watch_direct_addrs");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    };
+    let mut addrs = node.watch_direct_addrs();
+    let (cancel, mut cancel_rx) = node.start_subscription();
+
+    // Convert userdata to usize for Send safety
+    let userdata_addr = callback.userdata as usize;
+    let on_addrs = callback.on_addrs;
+    let on_complete = callback.on_complete;
+    let callbacks = node.callbacks().clone();
+
+    node.spawn_subscription(async move {
+        use futures_lite::StreamExt;
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel_rx => break,
+                next = addrs.next() => {
+                    let Some(next) = next else { break };
+                    let strings: Vec<CString> = next
+                        .iter()
+                        .map(|addr| CString::new(addr.to_string()).unwrap())
+                        .collect();
+                    let ptrs: Vec<*const c_char> = strings.iter().map(|s| s.as_ptr()).collect();
+                    unsafe {
+                        callbacks.call(|| {
+                            (on_addrs)(userdata_addr as *mut c_void, ptrs.as_ptr(), ptrs.len())
+                        })
+                    };
+                }
+            }
+        }
+        unsafe { callbacks.call(|| (on_complete)(userdata_addr as *mut c_void)) };
+    });
+
+    SubscriptionWrapper::into_handle(cancel)
+}
+
+/// Subscribe to every node event through one stream.
+///
+/// Delivers connectivity changes, downloads starting and finishing,
+/// expired entry prune passes, profile garbage collection runs, and
+/// neighbor and sync events of documents opened on this node. Only events
+/// after subscribing are delivered; a subscriber that falls far behind
+/// skips the events it missed. Returns a handle to stop with
+/// `iroh_subscription_cancel`.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers that remain valid until
+///   on_complete or on_failure is called
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_subscribe_events(
+    handle: *const IrohNodeHandle,
+    callback: IrohNodeEventCallback,
+) -> *mut IrohSubscriptionHandle {
+    let _operation = trace::begin("iroh_node_subscribe_events");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    };
+    let mut events = node.subscribe_events();
+    let (cancel, mut cancel_rx) = node.start_subscription();
+
+    // Convert userdata to usize for Send safety
+    let userdata_addr = callback.userdata as usize;
+    let on_event = callback.on_event;
+    let on_complete = callback.on_complete;
+    let callbacks = node.callbacks().clone();
+
+    node.spawn_subscription(async move {
+        use futures_lite::StreamExt;
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel_rx => break,
+                next = events.next() => {
+                    let Some(event) = next else { break };
+                    deliver_node_event(event, |ffi_event| unsafe {
+                        callbacks.call(|| (on_event)(userdata_addr as *mut c_void, ffi_event))
+                    });
+                }
+            }
+        }
+        unsafe { callbacks.call(|| (on_complete)(userdata_addr as *mut c_void)) };
+    });
+
+    SubscriptionWrapper::into_handle(cancel)
+}
+
+/// Subscribe to messages peers send to this node with `iroh_message_send`.
+///
+/// Only messages received after subscribing are delivered; while nobody is
+/// subscribed, senders are told the message was not delivered. A
+/// subscriber that falls far behind skips the messages it missed. Returns a
+/// handle to stop with `iroh_subscription_cancel`.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers that remain valid until
+///   on_complete or on_failure is called
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_node_subscribe_messages(
+    handle: *const IrohNodeHandle,
+    callback: IrohMessageCallback,
+) -> *mut IrohSubscriptionHandle {
+    let _operation = trace::begin("iroh_node_subscribe_messages");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    };
+    let mut messages = node.subscribe_messages();
+    let (cancel, mut cancel_rx) = node.start_subscription();
+
+    // Convert userdata to usize for Send safety
+    let userdata_addr = callback.userdata as usize;
+    let on_message = callback.on_message;
+    let on_complete = callback.on_complete;
+    let callbacks = node.callbacks().clone();
+
+    node.spawn_subscription(async move {
+        use futures_lite::StreamExt;
+
+        loop {
+            tokio::select! {
+                _ = &mut cancel_rx => break,
+                next = messages.next() => {
+                    let Some(message) = next else { break };
+                    let from = CString::new(message.from.to_string()).unwrap();
+                    let payload = IrohBytes {
+                        data: message.payload.as_ptr(),
+                        len: message.payload.len(),
+                    };
+                    unsafe {
+                        callbacks.call(|| {
+                            (on_message)(userdata_addr as *mut c_void, from.as_ptr(), payload)
+                        })
+                    };
+                }
+            }
+        }
+        unsafe { callbacks.call(|| (on_complete)(userdata_addr as *mut c_void)) };
+    });
+
+    SubscriptionWrapper::into_handle(cancel)
+}
+
+/// Answer a discovery lookup started by `IrohDiscoveryProvider::resolve`.
+///
+/// Pass a null `relay_url` and no addresses if the node is unknown. Returns
+/// false if an address cannot be parsed, in which case the lookup yields
+/// nothing. The request is consumed either way.
+///
+/// # Safety
+/// - `request` must come from a resolve call and not have been completed yet
+/// - `relay_url` must be null or a valid null-terminated UTF-8 string
+/// - `addrs` must point to `len` valid null-terminated UTF-8 strings
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_discovery_request_complete(
+    request: *mut IrohDiscoveryRequest,
+    relay_url: *const c_char,
+    addrs: *const *const c_char,
+    len: usize,
+) -> bool {
+    if request.is_null() {
+        return false;
+    }
+    let (peer, reply) =
+        *unsafe { Box::from_raw(request as *mut (EndpointId, oneshot::Sender<EndpointAddr>)) };
+
+    let mut transport_addrs = Vec::with_capacity(len + 1);
+    match unsafe { optional_str(relay_url) } {
+        Ok(None) => {}
+        Ok(Some(url)) => match url.parse::<RelayUrl>() {
+            Ok(url) => transport_addrs.push(TransportAddr::Relay(url)),
+            Err(_) => return false,
+        },
+        Err(_) => return false,
+    }
+    if len > 0 {
+        if addrs.is_null() {
+            return false;
+        }
+        for &addr in unsafe { std::slice::from_raw_parts(addrs, len) } {
+            if addr.is_null() {
+                return false;
+            }
+            let Ok(addr) = unsafe { CStr::from_ptr(addr) }.to_str() else {
+                return false;
+            };
+            let Ok(addr) = addr.parse::<SocketAddr>() else {
+                return false;
+            };
+            transport_addrs.push(TransportAddr::Ip(addr));
+        }
+    }
+
+    // Dropping the reply without sending means the node is unknown
+    if !transport_addrs.is_empty() {
+        let _ = reply.send(EndpointAddr::from_parts(peer, transport_addrs));
+    }
+    true
+}
+
+/// Validate and parse a ticket string.
+///
+/// This function always succeeds - check `info.is_valid` for the result.
+/// Valid tickets also report the provider's relay URLs and direct
+/// addresses, so the app can show how content was shared without
+/// downloading it.
+///
+/// # Safety
+/// - `ticket` must be a valid null-terminated UTF-8 string (or null)
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_validate_ticket(
+    ticket: *const c_char,
+    callback: IrohTicketValidateCallback,
+) {
+    let parsed = if ticket.is_null() {
+        None
+    } else {
+        unsafe { CStr::from_ptr(ticket) }
+            .to_str()
+            .ok()
+            .and_then(|ticket_str| ticket_str.parse::<BlobTicket>().ok())
+    };
+    let Some(parsed) = parsed else {
+        (callback.on_complete)(callback.userdata, IrohTicketInfo::invalid());
+        return;
+    };
+
+    let hash = CString::new(parsed.hash().to_string()).unwrap().into_raw();
+    let node_id = CString::new(parsed.addr().id.to_string())
+        .unwrap()
+        .into_raw();
+    let relay_urls: Vec<CString> = parsed
+        .addr()
+        .relay_urls()
+        .map(|url| CString::new(url.to_string()).unwrap())
+        .collect();
+    let direct_addrs: Vec<CString> = parsed
+        .addr()
+        .ip_addrs()
+        .map(|addr| CString::new(addr.to_string()).unwrap())
+        .collect();
+    let relay_ptrs: Vec<*const c_char> = relay_urls.iter().map(|s| s.as_ptr()).collect();
+    let direct_ptrs: Vec<*const c_char> = direct_addrs.iter().map(|s| s.as_ptr()).collect();
+
+    let info = IrohTicketInfo {
+        is_valid: true,
+        hash,
+        node_id,
+        is_recursive: parsed.recursive(),
+        format: parsed.format().into(),
+        relay_urls: relay_ptrs.as_ptr(),
+        relay_urls_len: relay_ptrs.len(),
+        direct_addrs: direct_ptrs.as_ptr(),
+        direct_addrs_len: direct_ptrs.len(),
+    };
+    (callback.on_complete)(callback.userdata, info);
+}
+
+/// Re-encode a blob or doc ticket for QR codes or reading aloud.
+///
+/// On success the encoded string is passed to `on_success`
+/// (caller must free with `iroh_string_free`).
+///
+/// # Safety
+/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_ticket_encode(
+    ticket: *const c_char,
+    encoding: IrohTicketEncoding,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_ticket_encode");
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    match ticket::encode(ticket_str, encoding.into()) {
+        Ok(encoded) => {
+            let encoded_cstr = CString::new(encoded).unwrap();
+            (callback.on_success)(callback.userdata, encoded_cstr.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Decode a ticket in any supported encoding back to the standard form.
+///
+/// On success the standard ticket is passed to `on_success`
+/// (caller must free with `iroh_string_free`).
+///
+/// # Safety
+/// - `encoded` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_ticket_decode(encoded: *const c_char, callback: IrohCallback) {
+    let _operation = trace::begin("iroh_ticket_decode");
+    if encoded.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let encoded_str = match unsafe { CStr::from_ptr(encoded) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    match ticket::decode(encoded_str) {
+        Ok(ticket) => {
+            let ticket_cstr = CString::new(ticket).unwrap();
+            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Encrypt a blob or doc ticket with a passphrase.
+///
+/// The passphrase is stretched with PBKDF2 before encrypting, which takes a
+/// noticeable fraction of a second, so call this off the main thread. On
+/// success the locked ticket is passed to `on_success` (caller must free
+/// with `iroh_string_free`).
+///
+/// # Safety
+/// - `ticket` and `passphrase` must be valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_ticket_lock(
+    ticket: *const c_char,
+    passphrase: *const c_char,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_ticket_lock");
+    if ticket.is_null() || passphrase.is_null() {
+        let error = CString::new("ticket and passphrase cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let (ticket_str, passphrase_str) = match (
+        unsafe { CStr::from_ptr(ticket) }.to_str(),
+        unsafe { CStr::from_ptr(passphrase) }.to_str(),
+    ) {
+        (Ok(t), Ok(p)) => (t, p),
+        (Err(e), _) | (_, Err(e)) => {
+            let error = CString::new(format!("Invalid string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    match passphrase::lock(ticket_str, passphrase_str) {
+        Ok(locked) => {
+            let locked_cstr = CString::new(locked).unwrap();
+            (callback.on_success)(callback.userdata, locked_cstr.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Decrypt a ticket locked with `iroh_ticket_lock`.
+///
+/// Like locking, this stretches the passphrase and should be called off
+/// the main thread. On success the standard ticket is passed to
+/// `on_success` (caller must free with `iroh_string_free`); a wrong
+/// passphrase is reported through `on_failure`.
+///
+/// # Safety
+/// - `locked` and `passphrase` must be valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_ticket_unlock(
+    locked: *const c_char,
+    passphrase: *const c_char,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_ticket_unlock");
+    if locked.is_null() || passphrase.is_null() {
+        let error = CString::new("ticket and passphrase cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let (locked_str, passphrase_str) = match (
+        unsafe { CStr::from_ptr(locked) }.to_str(),
+        unsafe { CStr::from_ptr(passphrase) }.to_str(),
+    ) {
+        (Ok(l), Ok(p)) => (l, p),
+        (Err(e), _) | (_, Err(e)) => {
+            let error = CString::new(format!("Invalid string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    match passphrase::unlock(locked_str, passphrase_str) {
+        Ok(ticket) => {
+            let ticket_cstr = CString::new(ticket).unwrap();
+            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Pack several blob and doc tickets into one shareable bundle string.
+///
+/// `labels` and `tickets` both have `len` entries; `labels` may be null, or
+/// contain nulls, for unlabeled tickets. Labels are at most 255 bytes and a
+/// bundle holds 1 to 255 tickets. On success the bundle is passed to
+/// `on_success` (caller must free with `iroh_string_free`).
+///
+/// # Safety
+/// - `tickets` must point to `len` valid null-terminated UTF-8 strings
+/// - `labels` must be null or point to `len` null or valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_ticket_bundle_encode(
+    labels: *const *const c_char,
+    tickets: *const *const c_char,
+    len: usize,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_ticket_bundle_encode");
+    if tickets.is_null() && len > 0 {
+        let error = CString::new("tickets cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let string_at = |ptrs: *const *const c_char, i: usize| -> anyhow::Result<Option<&str>> {
+        if ptrs.is_null() {
+            return Ok(None);
+        }
+        let ptr = unsafe { *ptrs.add(i) };
+        if ptr.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(unsafe { CStr::from_ptr(ptr) }.to_str()?))
+    };
+    let pairs: anyhow::Result<Vec<(&str, &str)>> = (0..len)
+        .map(|i| {
+            let ticket = string_at(tickets, i)?
+                .ok_or_else(|| anyhow::anyhow!("tickets cannot contain null"))?;
+            Ok((string_at(labels, i)?.unwrap_or(""), ticket))
+        })
+        .collect();
+
+    match pairs.and_then(bundle::encode) {
+        Ok(bundle) => {
+            let bundle_cstr = CString::new(bundle).unwrap();
+            (callback.on_success)(callback.userdata, bundle_cstr.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Unpack the tickets of a bundle made with `iroh_ticket_bundle_encode`.
+///
+/// Each ticket is passed to `on_entry` in bundle order, then `on_complete`
+/// is called. A damaged bundle is reported through `on_failure` without
+/// any entries.
+///
+/// # Safety
+/// - `bundle` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_ticket_bundle_decode(
+    bundle: *const c_char,
+    callback: IrohTicketBundleCallback,
+) {
+    let _operation = trace::begin("iroh_ticket_bundle_decode");
+    if bundle.is_null() {
+        let error = CString::new("bundle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let bundle_str = match unsafe { CStr::from_ptr(bundle) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid bundle string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    match bundle::decode(bundle_str) {
+        Ok(tickets) => {
+            for entry in tickets {
+                // Labels are checked text, but may hold interior nulls
+                let label = CString::new(entry.label.replace('\0', "")).unwrap();
+                let ticket = CString::new(entry.ticket).unwrap();
+                let kind = match entry.kind {
+                    TicketKind::Blob => IrohBundledTicketKind::BundledBlob,
+                    TicketKind::Doc => IrohBundledTicketKind::BundledDoc,
+                };
+                (callback.on_entry)(
+                    callback.userdata,
+                    IrohBundledTicket {
+                        label: label.as_ptr(),
+                        kind,
+                        ticket: ticket.as_ptr(),
+                    },
+                );
+            }
+            (callback.on_complete)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+// ============================================================================
+// Close and Timeout Operations
+// ============================================================================
+
+/// Explicitly close a node and free its resources asynchronously.
+///
+/// This is preferred over `iroh_node_destroy` when you need to await
+/// graceful shutdown completion. Subscriptions are cancelled and deliver
+/// their `on_complete`, and calls still running on other threads are waited
+/// for, before the node is freed; calls made afterwards fail. If this has
+/// not finished after `deadline_ms`, the remaining work is abandoned, the
+/// node is freed when the last running call returns, and `on_complete`
+/// reports that the shutdown was not graceful. A `deadline_ms` of 0 waits
+/// as long as shutdown takes.
+///
+/// # Safety
+/// - `handle` must be a valid pointer returned by `iroh_node_create`
+/// - `handle` must not be used after this call
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_node_close(
+    handle: *mut IrohNodeHandle,
+    deadline_ms: u64,
+    callback: IrohNodeCloseCallback,
+) {
+    let _operation = trace::begin("iroh_node_close");
+    if handle.is_null() {
+        (callback.on_complete)(callback.userdata, true);
+        return;
+    }
+    let Some(gate) = drain::close(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    handles::release(handle);
+    let deadline = (deadline_ms > 0).then(|| Duration::from_millis(deadline_ms));
+    let started = Instant::now();
     let node = unsafe { &*(handle as *const IrohNode) };
+    let result = node.close(deadline);
+    let drained = gate.drain(deadline.map(|deadline| deadline.saturating_sub(started.elapsed())));
+    free_node(&gate, handle, !matches!(result, Ok(false)));
+    // Export what was recorded before the app may be suspended
+    #[cfg(feature = "otel")]
+    crate::telemetry::flush();
+    match result {
+        Ok(graceful) => (callback.on_complete)(callback.userdata, graceful && drained),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Add bytes to the blob store with options (e.g., timeout).
+///
+/// If the timeout runs out, the import is stopped and `on_timeout` is
+/// called instead of `on_failure`.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `bytes.data` must point to valid memory for `bytes.len` bytes
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_put_with_options(
+    handle: *const IrohNodeHandle,
+    bytes: IrohBytes,
+    options: IrohOperationOptions,
+    callback: IrohPutWithOptionsCallback,
+) {
+    let _operation = trace::begin("iroh_put_with_options");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    // Copy the bytes to own them (Swift memory may not be stable)
+    let data = if bytes.data.is_null() || bytes.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(bytes.data, bytes.len).to_vec() }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    let timeout_ms = options.timeout_ms;
+
+    match node.put_with_timeout(&data, timeout_ms) {
+        Ok(ticket) => {
+            stats::bytes_in(data.len());
+            let ticket_cstr = CString::new(ticket).unwrap();
+            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
+        }
+        Err(e) if e.is::<TimedOut>() => {
+            // Still recorded as a failed operation
+            trace::failure(&e);
+            (callback.on_timeout)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Download bytes from a ticket with options (e.g., timeout).
+///
+/// If the timeout runs out, the transfer is stopped and `on_timeout` is
+/// called instead of `on_failure`.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_get_with_options(
+    handle: *const IrohNodeHandle,
+    ticket: *const c_char,
+    options: IrohOperationOptions,
+    callback: IrohGetWithOptionsCallback,
+) {
+    let _operation = trace::begin("iroh_get_with_options");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    let timeout_ms = options.timeout_ms;
+
+    match node.get_with_timeout(&ticket_str, timeout_ms) {
+        Ok(bytes) => {
+            let mut vec = bytes;
+            let owned = IrohOwnedBytes {
+                data: vec.as_mut_ptr(),
+                len: vec.len(),
+                capacity: vec.capacity(),
+            };
+            std::mem::forget(vec);
+            stats::bytes_out(owned.len);
+            (callback.on_success)(callback.userdata, owned);
+        }
+        Err(e) if e.is::<TimedOut>() => {
+            // Still recorded as a failed operation
+            trace::failure(&e);
+            (callback.on_timeout)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+// ============================================================================
+// Author Operations
+// ============================================================================
+
+/// Create a new random author keypair.
+///
+/// The secret key should be stored securely (e.g., in iOS Keychain).
+/// The ID is derived from the secret and can be stored openly.
+///
+/// # Safety
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_author_create(callback: IrohAuthorCreateCallback) {
+    // Generate a new random author
+    let author = Author::new(&mut rand::rng());
+
+    // Get the secret bytes (32 bytes)
+    let secret_bytes = author.to_bytes();
+    let secret = IrohAuthorSecret {
+        bytes: secret_bytes,
+    };
+
+    // Get the public ID bytes (32 bytes)
+    let author_id = author.id();
+    let id_bytes = author_id.as_bytes();
+    let id = IrohAuthorId { bytes: *id_bytes };
+
+    (callback.on_success)(callback.userdata, secret, id);
+}
+
+/// Create a new author keypair from caller-provided entropy.
+///
+/// The 32 bytes produced by `entropy` become the author's secret key, so
+/// keys can come from `SecRandomCopyBytes` or a seed held in an HSM.
+/// With a null `fill` this behaves like `iroh_author_create`.
+///
+/// # Safety
+/// - `entropy.fill` must be null or write `len` bytes to `buf`
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_author_create_with_entropy(
+    entropy: IrohEntropySource,
+    callback: IrohAuthorCreateCallback,
+) {
+    let _operation = trace::begin("iroh_author_create_with_entropy");
+    let author = match entropy_seed(&entropy) {
+        Ok(Some(seed)) => Author::from_bytes(&seed),
+        Ok(None) => Author::new(&mut rand::rng()),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let secret = IrohAuthorSecret {
+        bytes: author.to_bytes(),
+    };
+    let id = IrohAuthorId {
+        bytes: *author.id().as_bytes(),
+    };
+
+    (callback.on_success)(callback.userdata, secret, id);
+}
+
+/// Create the deterministic test author with `seed`.
+///
+/// The same seed always gives the same author, so test suites get
+/// reproducible author IDs. Never use this for real authors.
+///
+/// # Safety
+/// - `seed` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_author_create_from_seed(
+    seed: *const c_char,
+    callback: IrohAuthorCreateCallback,
+) {
+    if seed.is_null() {
+        let error = CString::new("seed cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let seed = match unsafe { CStr::from_ptr(seed) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid seed: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let author = Author::from_bytes(&testing::author_secret(seed));
+    let secret = IrohAuthorSecret {
+        bytes: author.to_bytes(),
+    };
+    let id = IrohAuthorId {
+        bytes: *author.id().as_bytes(),
+    };
+
+    (callback.on_success)(callback.userdata, secret, id);
+}
+
+/// Get the author ID from a secret key.
+///
+/// This is a pure computation - no node required.
+/// Useful for deriving the ID after loading secret from Keychain.
+///
+/// # Safety
+/// - `secret` must contain valid author secret bytes
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_author_id_from_secret(secret: IrohAuthorSecret) -> IrohAuthorId {
+    // Reconstruct the Author from the secret bytes
+    let author = Author::from_bytes(&secret.bytes);
+
+    // Get the public ID bytes
+    let author_id = author.id();
+    let id_bytes = author_id.as_bytes();
+    IrohAuthorId { bytes: *id_bytes }
+}
+
+/// Sign an arbitrary payload with an author key.
+///
+/// This is a pure computation - no node required. The signature is bound
+/// to this API and cannot be mistaken for a document entry signature.
+///
+/// # Safety
+/// - `secret` must contain valid author secret bytes
+/// - `payload.data` must point to valid memory for `payload.len` bytes
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_author_sign(
+    secret: IrohAuthorSecret,
+    payload: IrohBytes,
+) -> IrohAuthorSignature {
+    let author = Author::from_bytes(&secret.bytes);
+    let payload_bytes = if payload.data.is_null() || payload.len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(payload.data, payload.len) }
+    };
+
+    IrohAuthorSignature {
+        bytes: signing::sign(&author, payload_bytes),
+    }
+}
+
+/// Verify a payload signature made with `iroh_author_sign`.
+///
+/// Returns true if `signature` was made by `author_id` over `payload`.
+///
+/// # Safety
+/// - `payload.data` must point to valid memory for `payload.len` bytes
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_author_verify(
+    author_id: IrohAuthorId,
+    payload: IrohBytes,
+    signature: IrohAuthorSignature,
+) -> bool {
+    let payload_bytes = if payload.data.is_null() || payload.len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(payload.data, payload.len) }
+    };
+
+    signing::verify(
+        AuthorId::from(author_id.bytes),
+        payload_bytes,
+        &signature.bytes,
+    )
+    .is_ok()
+}
+
+/// Import an author from a hex-encoded secret key.
+///
+/// Useful for debugging or cross-device sync.
+///
+/// # Safety
+/// - `secret_hex` must be a valid null-terminated UTF-8 string containing 64 hex chars
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_author_from_hex(
+    secret_hex: *const c_char,
+    callback: IrohAuthorCreateCallback,
+) {
+    let _operation = trace::begin("iroh_author_from_hex");
+    if secret_hex.is_null() {
+        let error = CString::new("secret_hex cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let hex_str = match unsafe { CStr::from_ptr(secret_hex) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid UTF-8 in secret_hex: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    // Decode hex to bytes
+    let secret_bytes: [u8; 32] = match hex::decode(hex_str) {
+        Ok(bytes) if bytes.len() == 32 => {
+            let mut arr = [0u8; 32];
+            arr.copy_from_slice(&bytes);
+            arr
+        }
+        Ok(bytes) => {
+            let error = CString::new(format!(
+                "Invalid secret length: expected 32 bytes, got {}",
+                bytes.len()
+            ))
+            .unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+        Err(e) => {
+            let error = CString::new(format!("Invalid hex string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    // Reconstruct the Author
+    let author = Author::from_bytes(&secret_bytes);
+
+    let secret = IrohAuthorSecret {
+        bytes: secret_bytes,
+    };
+    let id = IrohAuthorId {
+        bytes: *author.id().as_bytes(),
+    };
+
+    (callback.on_success)(callback.userdata, secret, id);
+}
+
+/// Export an author secret as a hex string.
+///
+/// Useful for debugging or backup.
+///
+/// # Safety
+/// - The returned string must be freed with `iroh_string_free`
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_author_secret_to_hex(secret: IrohAuthorSecret) -> *mut c_char {
+    let hex_string = hex::encode(secret.bytes);
+    CString::new(hex_string).unwrap().into_raw()
+}
+
+/// Export an author ID as a hex string.
+///
+/// # Safety
+/// - The returned string must be freed with `iroh_string_free`
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_author_id_to_hex(id: IrohAuthorId) -> *mut c_char {
+    let hex_string = hex::encode(id.bytes);
+    CString::new(hex_string).unwrap().into_raw()
+}
+
+/// Import an author into the docs engine.
+///
+/// This must be called before using an author to sign document entries.
+/// The author is registered with the docs engine so it can sign entries.
+///
+/// # Safety
+/// - `handle` must be a valid node handle with docs enabled
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_author_import(
+    handle: *const IrohNodeHandle,
+    author_secret: IrohAuthorSecret,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_author_import");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let docs = match node.docs() {
+        Some(d) => d,
+        None => {
+            let error = CString::new("docs not enabled on this node").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    // Reconstruct the author from secret bytes
+    let author = Author::from_bytes(&author_secret.bytes);
+
+    match node.runtime().block_on(docs.api().author_import(author)) {
+        Ok(()) => {
+            (callback.on_complete)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Make an imported author the node's default author.
+///
+/// Document writes that name no author, such as
+/// `iroh_doc_set_with_default_author`, are signed by the default author, so
+/// its secret only crosses the FFI once, in `iroh_author_import`. The choice
+/// is kept in the storage directory across restarts. Fails if the author
+/// has not been imported.
+///
+/// # Safety
+/// - `handle` must be a valid node handle with docs enabled
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_node_set_default_author(
+    handle: *const IrohNodeHandle,
+    author_id: IrohAuthorId,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_node_set_default_author");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.set_default_author(AuthorId::from(author_id.bytes)) {
+        Ok(()) => {
+            (callback.on_complete)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Get the node's default author as a hex author ID.
+///
+/// This is the author set with `iroh_node_set_default_author`, or else an
+/// author the docs engine created for the node.
+///
+/// # Safety
+/// - `handle` must be a valid node handle with docs enabled
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_node_default_author(handle: *const IrohNodeHandle, callback: IrohCallback) {
+    let _operation = trace::begin("iroh_node_default_author");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.default_author() {
+        Ok(author) => {
+            let hex_string = CString::new(hex::encode(author.as_bytes())).unwrap();
+            (callback.on_success)(callback.userdata, hex_string.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+// ============================================================================
+// Document Operations
+// ============================================================================
+
+/// Create a new document.
+///
+/// # Safety
+/// - `handle` must be a valid node handle with docs enabled
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_create(handle: *const IrohNodeHandle, callback: IrohDocCreateCallback) {
+    let _operation = trace::begin("iroh_doc_create");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let docs = match node.docs() {
+        Some(d) => d,
+        None => {
+            let error = CString::new("docs not enabled on this node").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    match node.runtime().block_on(docs.api().create()) {
+        Ok(doc) => {
+            let namespace_id = doc.id().to_string();
+            let namespace_cstr = CString::new(namespace_id).unwrap().into_raw();
+            node.track_swarm(&doc);
+
+            // Wrap the doc for FFI
+            let wrapper = Box::new(DocWrapper {
+                doc,
+                node_handle: handle,
+                read_only: false,
+            });
+            let doc_handle = Box::into_raw(wrapper) as *mut IrohDocHandle;
+            handles::register(HandleKind::Doc, doc_handle);
+
+            (callback.on_success)(callback.userdata, doc_handle, namespace_cstr);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Inspect a document ticket without joining the document.
+///
+/// This function always succeeds - check `info.is_valid` for the result.
+/// Use `info.capability` to warn before accepting write access to someone
+/// else's document.
+///
+/// # Safety
+/// - `ticket` must be a valid null-terminated UTF-8 string (or null)
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_ticket_inspect(
+    ticket: *const c_char,
+    callback: IrohDocTicketInspectCallback,
+) {
+    let parsed = if ticket.is_null() {
+        None
+    } else {
+        unsafe { CStr::from_ptr(ticket) }
+            .to_str()
+            .ok()
+            .and_then(|ticket_str| ticket_str.parse::<DocTicket>().ok())
+    };
+
+    let info = match parsed {
+        Some(parsed) => IrohDocTicketInfo {
+            is_valid: true,
+            namespace_id: CString::new(parsed.capability.id().to_string())
+                .unwrap()
+                .into_raw(),
+            capability: match parsed.capability.kind() {
+                CapabilityKind::Read => IrohDocShareMode::Read,
+                CapabilityKind::Write => IrohDocShareMode::Write,
+            },
+            peers_len: parsed.nodes.len(),
+        },
+        None => IrohDocTicketInfo {
+            is_valid: false,
+            namespace_id: std::ptr::null(),
+            capability: IrohDocShareMode::Read,
+            peers_len: 0,
+        },
+    };
+
+    (callback.on_complete)(callback.userdata, info);
+}
+
+/// Join an existing document via ticket.
+///
+/// # Safety
+/// - `handle` must be a valid node handle with docs enabled
+/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_join(
+    handle: *const IrohNodeHandle,
+    ticket: *const c_char,
+    callback: IrohDocCreateCallback,
+) {
+    unsafe { iroh_doc_join_with_mode(handle, ticket, IrohDocOpenMode::ReadWrite, callback) }
+}
+
+/// Join an existing document via ticket with the given access mode.
+///
+/// A `ReadOnly` handle rejects every write, even when the ticket grants
+/// write access, so it can be handed to untrusted code.
+///
+/// # Safety
+/// - `handle` must be a valid node handle with docs enabled
+/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_join_with_mode(
+    handle: *const IrohNodeHandle,
+    ticket: *const c_char,
+    mode: IrohDocOpenMode,
+    callback: IrohDocCreateCallback,
+) {
+    let _operation = trace::begin("iroh_doc_join_with_mode");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let doc_ticket: DocTicket = match ticket_str.parse() {
+        Ok(t) => t,
+        Err(e) => {
+            let error = CString::new(format!("Invalid doc ticket: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let docs = match node.docs() {
+        Some(d) => d,
+        None => {
+            let error = CString::new("docs not enabled on this node").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    trace::stage("join");
+    match node.runtime().block_on(docs.api().import(doc_ticket)) {
+        Ok(doc) => {
+            let namespace_id = doc.id().to_string();
+            let namespace_cstr = CString::new(namespace_id).unwrap().into_raw();
+            node.track_swarm(&doc);
+
+            let wrapper = Box::new(DocWrapper {
+                doc,
+                node_handle: handle,
+                read_only: matches!(mode, IrohDocOpenMode::ReadOnly),
+            });
+            let doc_handle = Box::into_raw(wrapper) as *mut IrohDocHandle;
+            handles::register(HandleKind::Doc, doc_handle);
+
+            (callback.on_success)(callback.userdata, doc_handle, namespace_cstr);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Open a document already stored on this node.
+///
+/// # Safety
+/// - `handle` must be a valid node handle with docs enabled
+/// - `namespace_id` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_open(
+    handle: *const IrohNodeHandle,
+    namespace_id: *const c_char,
+    mode: IrohDocOpenMode,
+    callback: IrohDocCreateCallback,
+) {
+    let _operation = trace::begin("iroh_doc_open");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if namespace_id.is_null() {
+        let error = CString::new("namespace_id cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let namespace_str = match unsafe { CStr::from_ptr(namespace_id) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid namespace ID UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let namespace: NamespaceId = match namespace_str.parse() {
+        Ok(id) => id,
+        Err(e) => {
+            let error = CString::new(format!("Invalid namespace ID: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let docs = match node.docs() {
+        Some(d) => d,
+        None => {
+            let error = CString::new("docs not enabled on this node").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    match node.runtime().block_on(docs.api().open(namespace)) {
+        Ok(Some(doc)) => {
+            let namespace_cstr = CString::new(doc.id().to_string()).unwrap().into_raw();
+            node.track_swarm(&doc);
+
+            let wrapper = Box::new(DocWrapper {
+                doc,
+                node_handle: handle,
+                read_only: matches!(mode, IrohDocOpenMode::ReadOnly),
+            });
+            let doc_handle = Box::into_raw(wrapper) as *mut IrohDocHandle;
+            handles::register(HandleKind::Doc, doc_handle);
+
+            (callback.on_success)(callback.userdata, doc_handle, namespace_cstr);
+        }
+        Ok(None) => {
+            let error = CString::new("document not found on this node").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Set a key-value pair in a document.
+///
+/// The write is as durable as the document's durability, set with
+/// `iroh_doc_set_durability`.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_set(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    value: IrohBytes,
+    callback: IrohDocSetCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set");
+    let author = Author::from_bytes(&author_secret.bytes).id();
+    doc_set(doc_handle, Some(author), key, value, None, callback);
+}
+
+/// Set a key-value pair in a document as the node's default author.
+///
+/// Like `iroh_doc_set`, without passing the author secret; see
+/// `iroh_node_set_default_author`.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_set_with_default_author(
+    doc_handle: *const IrohDocHandle,
+    key: IrohBytes,
+    value: IrohBytes,
+    callback: IrohDocSetCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_with_default_author");
+    doc_set(doc_handle, None, key, value, None, callback);
+}
+
+/// Set a key-value pair in a document with a durability of its own.
+///
+/// With `DurabilityImmediate`, `on_success` is only called once the write,
+/// and every write batched before it, is synced to disk.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_set_with_durability(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    value: IrohBytes,
+    durability: IrohDurability,
+    callback: IrohDocSetCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_with_durability");
+    let author = Author::from_bytes(&author_secret.bytes).id();
+    doc_set(
+        doc_handle,
+        Some(author),
+        key,
+        value,
+        Some(durability.into()),
+        callback,
+    );
+}
+
+/// Set a key-value pair in a document, reporting the content hash as an
+/// `IrohHash`.
+///
+/// Same as `iroh_doc_set`, without encoding the hash as hex.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_set_with_hash_callback(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    value: IrohBytes,
+    callback: IrohHashCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_with_hash_callback");
+    let author = Author::from_bytes(&author_secret.bytes).id();
+    match set_entry(doc_handle, Some(author), key, value, None) {
+        Ok(hash) => (callback.on_success)(callback.userdata, hash.into()),
+        Err(error) => (callback.on_failure)(callback.userdata, error.into_raw()),
+    }
+}
+
+/// Set a key in a document to the contents of a file.
+///
+/// The file is imported into the blob store and the entry set to its hash
+/// in one operation, without reading the file into memory, so large media
+/// can be stored from a path. The content is copied, so the file may
+/// change or be deleted afterwards. `on_progress` is called while the file
+/// is copied and hashed. The write is as durable as the document's
+/// durability.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `path` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_set_file(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    path: *const c_char,
+    callback: IrohDocSetFileCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_file");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let path = match unsafe { required_str(path, "path") } {
+        Ok(path) => path,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes).id();
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    };
+
+    let result = node.doc_set_file(
+        &wrapper.doc,
+        author,
+        key_bytes,
+        std::path::Path::new(&path),
+        |progress| (callback.on_progress)(callback.userdata, progress.into()),
+    );
+    match result {
+        Ok(hash) => (callback.on_success)(callback.userdata, hash.into()),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Set the durability of a document's writes that do not choose their
+/// own, for as long as the node runs.
+///
+/// Applies to sets, deletions, compare-and-set, counter and TTL writes
+/// through any handle of the document. Use `DurabilityImmediate` for
+/// records that must survive a crash, and `DurabilityBatched`, the
+/// default, for bulk imports.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_set_durability(
+    doc_handle: *const IrohDocHandle,
+    durability: IrohDurability,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_durability");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    node.doc_set_durability(wrapper.doc.id(), durability.into());
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Commit every batched document write on the node and sync it to disk.
+///
+/// Call after a bulk import with batched durability to make it crash-safe
+/// at once. The docs store is shared by all documents, so writes to other
+/// documents are flushed too.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_flush(doc_handle: *const IrohDocHandle, callback: IrohCloseCallback) {
+    let _operation = trace::begin("iroh_doc_flush");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.doc_flush() {
+        Ok(()) => (callback.on_complete)(callback.userdata),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Write a key-value pair with `durability`, or the document's own if None,
+/// as `author`, or the node's default author if None.
+#[cfg(feature = "docs")]
+fn doc_set(
+    doc_handle: *const IrohDocHandle,
+    author: Option<AuthorId>,
+    key: IrohBytes,
+    value: IrohBytes,
+    durability: Option<Durability>,
+    callback: IrohDocSetCallback,
+) {
+    match set_entry(doc_handle, author, key, value, durability) {
+        Ok(hash) => {
+            let hash_str = CString::new(hash.to_string()).unwrap().into_raw();
+            (callback.on_success)(callback.userdata, hash_str);
+        }
+        Err(error) => (callback.on_failure)(callback.userdata, error.into_raw()),
+    }
+}
+
+/// Write a key-value pair for the `iroh_doc_set` variants, returning its
+/// content hash or the error to report.
+#[cfg(feature = "docs")]
+fn set_entry(
+    doc_handle: *const IrohDocHandle,
+    author: Option<AuthorId>,
+    key: IrohBytes,
+    value: IrohBytes,
+    durability: Option<Durability>,
+) -> Result<Hash, CString> {
+    if doc_handle.is_null() {
+        return Err(CString::new("doc_handle cannot be null").unwrap());
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        return Err(CString::new(DOC_READ_ONLY_ERROR).unwrap());
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        return Err(CString::new(NODE_CLOSED_ERROR).unwrap());
+    };
+
+    // Writes that name no author use the node's default
+    let author = author
+        .map_or_else(|| node.default_author(), Ok)
+        .map_err(|e| trace::failure(&e))?;
+
+    // Copy key and value bytes
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    };
+
+    let value_bytes = if value.data.is_null() || value.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(value.data, value.len).to_vec() }
+    };
+
+    node.doc_set(&wrapper.doc, author, key_bytes, value_bytes, durability)
+        .map_err(|e| trace::failure(&e))
+}
+
+/// Set a key only if its latest entry has the expected content hash.
+///
+/// Pass a null `expected_hash` to require that the key is absent or
+/// deleted. On mismatch nothing is written and `on_conflict` receives the
+/// current hash, so the caller can re-read and retry.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `expected_hash` must be a valid null-terminated hex hash string or null
+/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_set_cas(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    expected_hash: *const c_char,
+    value: IrohBytes,
+    callback: IrohDocSetCasCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_cas");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let expected = match unsafe { optional_str(expected_hash) } {
+        Ok(None) => None,
+        Ok(Some(s)) => match s.parse::<Hash>() {
+            Ok(h) => Some(h),
+            Err(e) => {
+                let error = CString::new(format!("Invalid expected hash: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        },
+        Err(e) => {
+            let error = CString::new(format!("Invalid expected hash UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes).id();
+    match set_entry_if(doc_handle, author, key, expected, value) {
+        Ok(CasOutcome::Written(hash)) => {
+            let hash_str = CString::new(hash.to_string()).unwrap().into_raw();
+            (callback.on_success)(callback.userdata, hash_str);
+        }
+        Ok(CasOutcome::Conflict(current)) => {
+            let current_str = current.map_or(std::ptr::null_mut(), |h| {
+                CString::new(h.to_string()).unwrap().into_raw()
+            });
+            (callback.on_conflict)(callback.userdata, current_str);
+        }
+        Err(error) => (callback.on_failure)(callback.userdata, error.into_raw()),
+    }
+}
+
+/// Set a key only if its latest entry has the expected content hash, with
+/// hashes passed as `IrohHash`.
+///
+/// Same as `iroh_doc_set_cas`, without encoding hashes as hex. Pass a null
+/// `expected_hash` to require that the key is absent or deleted.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `expected_hash` must point to a valid hash or be null
+/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_set_cas_by_hash(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    expected_hash: *const IrohHash,
+    value: IrohBytes,
+    callback: IrohDocSetCasHashCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_cas_by_hash");
+    let expected = unsafe { expected_hash.as_ref() }.map(|&hash| Hash::from(hash));
+    let author = Author::from_bytes(&author_secret.bytes).id();
+    match set_entry_if(doc_handle, author, key, expected, value) {
+        Ok(CasOutcome::Written(hash)) => (callback.on_success)(callback.userdata, hash.into()),
+        Ok(CasOutcome::Conflict(current)) => {
+            let current = current.map(IrohHash::from);
+            let current_ptr = current.as_ref().map_or(std::ptr::null(), |h| h as *const _);
+            (callback.on_conflict)(callback.userdata, current_ptr);
+        }
+        Err(error) => (callback.on_failure)(callback.userdata, error.into_raw()),
+    }
+}
+
+/// Write a key-value pair for the `iroh_doc_set_cas` variants if the key's
+/// latest entry has the `expected` content hash.
+#[cfg(feature = "docs")]
+fn set_entry_if(
+    doc_handle: *const IrohDocHandle,
+    author: AuthorId,
+    key: IrohBytes,
+    expected: Option<Hash>,
+    value: IrohBytes,
+) -> Result<CasOutcome, CString> {
+    if doc_handle.is_null() {
+        return Err(CString::new("doc_handle cannot be null").unwrap());
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        return Err(CString::new(DOC_READ_ONLY_ERROR).unwrap());
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        return Err(CString::new(NODE_CLOSED_ERROR).unwrap());
+    };
+
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    };
+
+    let value_bytes = if value.data.is_null() || value.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(value.data, value.len).to_vec() }
+    };
+
+    node.doc_set_cas(&wrapper.doc, author, key_bytes, expected, value_bytes)
+        .map_err(|e| trace::failure(&e))
+}
+
+/// Add `delta` to a mergeable counter and report its new value.
+///
+/// Each author stores its own running total under `key`, and the counter's
+/// value is the sum across authors, so concurrent increments from different
+/// devices are not lost. Use a negative `delta` to decrement.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_counter_add(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    delta: i64,
+    callback: IrohDocCounterCallback,
+) {
+    let _operation = trace::begin("iroh_doc_counter_add");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes);
+
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    };
+
+    match node.doc_counter_add(&wrapper.doc, author.id(), key_bytes, delta) {
+        Ok(value) => {
+            (callback.on_success)(callback.userdata, value);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Read the value of a mergeable counter (0 if it has never been written).
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_counter_get(
+    doc_handle: *const IrohDocHandle,
+    key: IrohBytes,
+    callback: IrohDocCounterCallback,
+) {
+    let _operation = trace::begin("iroh_doc_counter_get");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len) }
+    };
+
+    match node.doc_counter_get(&wrapper.doc, key_bytes) {
+        Ok(value) => {
+            (callback.on_success)(callback.userdata, value);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Set a key-value pair and track it in the outbox until a peer has it.
+///
+/// The entry is stored locally before `on_success` runs, like with
+/// `iroh_doc_set`. The node then keeps syncing the document with its known
+/// peers, including after restarts, and reports the item's progress as
+/// `NodeOutbox*` events (see `iroh_node_subscribe_events`).
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_outbox_set(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    value: IrohBytes,
+    callback: IrohDocOutboxCallback,
+) {
+    let _operation = trace::begin("iroh_doc_outbox_set");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes);
+
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    };
+
+    let value_bytes = if value.data.is_null() || value.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(value.data, value.len).to_vec() }
+    };
+
+    match node.doc_outbox_set(&wrapper.doc, author.id(), key_bytes, value_bytes) {
+        Ok(item_id) => {
+            (callback.on_success)(callback.userdata, item_id);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// List the document writes still waiting for a peer.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_outbox_list(
+    handle: *const IrohNodeHandle,
+    callback: IrohOutboxCallback,
+) {
+    let _operation = trace::begin("iroh_outbox_list");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    for item in node.outbox_items() {
+        let namespace_id = CString::new(item.namespace.to_string()).unwrap();
+        let author_id = CString::new(item.author.to_string()).unwrap();
+        let entry = IrohOutboxItem {
+            item_id: item.id,
+            namespace_id: namespace_id.as_ptr(),
+            author_id: author_id.as_ptr(),
+            key: IrohBytes {
+                data: item.key.as_ptr(),
+                len: item.key.len(),
+            },
+            content_hash: item.content_hash.into(),
+            timestamp: item.timestamp,
+        };
+        (callback.on_entry)(callback.userdata, entry);
+    }
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Replace the durable peers of a document; none stops tracking it.
+///
+/// Local writes count as backed up once a sync with every durable peer,
+/// started after the write, succeeded. The node syncs with the durable
+/// peers after local writes, retrying with backoff, and reports
+/// confirmations as `NodeDurableSync` events. Designations are persisted
+/// and resume after a restart.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `node_ids` must point to `node_ids_len` valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_set_durable_peers(
+    doc_handle: *const IrohDocHandle,
+    node_ids: *const *const c_char,
+    node_ids_len: usize,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_durable_peers");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let id_ptrs = if node_ids.is_null() || node_ids_len == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(node_ids, node_ids_len) }
+    };
+    let mut peers = Vec::with_capacity(id_ptrs.len());
+    for &ptr in id_ptrs {
+        if ptr.is_null() {
+            let error = CString::new("node ID cannot be null").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+        let parsed = unsafe { CStr::from_ptr(ptr) }
+            .to_str()
+            .map_err(anyhow::Error::from)
+            .and_then(|s| s.parse::<EndpointId>().map_err(anyhow::Error::from));
+        match parsed {
+            Ok(peer) => peers.push(peer),
+            Err(e) => {
+                let error = CString::new(format!("Invalid node ID: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.doc_set_durable_peers(&wrapper.doc, &peers) {
+        Ok(()) => (callback.on_complete)(callback.userdata),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Report how far a document's local writes are backed up on its durable peers.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_durable_status(
+    doc_handle: *const IrohDocHandle,
+    callback: IrohDurableStatusCallback,
+) {
+    let _operation = trace::begin("iroh_doc_durable_status");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.doc_durable_status(&wrapper.doc) {
+        Ok(status) => {
+            for (peer, confirmed_through) in &status.peers {
+                let node_id = CString::new(peer.to_string()).unwrap();
+                let peer = IrohDurablePeer {
+                    node_id: node_id.as_ptr(),
+                    confirmed_through: *confirmed_through,
+                };
+                (callback.on_peer)(callback.userdata, peer);
+            }
+            let summary = IrohDurableStatus {
+                confirmed_through: status.confirmed_through,
+                last_local_write: status.last_local_write,
+                backed_up: status.is_backed_up(),
+            };
+            (callback.on_complete)(callback.userdata, summary);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Store bytes as an asset of the backup manifest kept in a document.
+///
+/// The blob is pinned with a tag and indexed under `asset_id` with its
+/// hash, size and metadata, replacing any previous version of the asset.
+/// `on_success` receives the content hash.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `asset_id` must be a valid null-terminated UTF-8 string
+/// - `data.data` must point to valid memory for `data.len` bytes
+/// - `filename` and `content_type` must be null or valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn iroh_manifest_add(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    asset_id: *const c_char,
+    data: IrohBytes,
+    filename: *const c_char,
+    content_type: *const c_char,
+    created_at: u64,
+    callback: IrohHashCallback,
+) {
+    let _operation = trace::begin("iroh_manifest_add");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let asset_id = match unsafe { optional_str(asset_id) } {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            let error = CString::new("asset_id cannot be null").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+        Err(e) => {
+            let error = CString::new(format!("Invalid asset_id UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let filename = match unsafe { optional_str(filename) } {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid filename UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let content_type = match unsafe { optional_str(content_type) } {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid content_type UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes);
+
+    let bytes = if data.data.is_null() || data.len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(data.data, data.len) }
+    };
+
+    let metadata = BlobMetadata {
+        filename,
+        content_type,
+        created_at,
+        ..Default::default()
+    };
+
+    match node.manifest_add(&wrapper.doc, author.id(), &asset_id, bytes, metadata) {
+        Ok(asset) => {
+            stats::bytes_in(bytes.len());
+            (callback.on_success)(callback.userdata, asset.hash.into());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// List the assets of the backup manifest kept in a document.
+///
+/// Entries whose value has not been synced yet are skipped.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_manifest_list(
+    doc_handle: *const IrohDocHandle,
+    callback: IrohManifestCallback,
+) {
+    let _operation = trace::begin("iroh_manifest_list");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let assets = match node.manifest_assets(&wrapper.doc) {
+        Ok(assets) => assets,
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+    let optional = |s: Option<String>| s.map(|s| CString::new(s).unwrap());
+    let as_ptr = |s: &Option<CString>| s.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+    for asset in assets {
+        let asset_id = CString::new(asset.asset_id).unwrap();
+        let filename = optional(asset.metadata.filename);
+        let content_type = optional(asset.metadata.content_type);
+        let entry = IrohManifestAsset {
+            asset_id: asset_id.as_ptr(),
+            hash: asset.hash.into(),
+            size: asset.size,
+            filename: as_ptr(&filename),
+            content_type: as_ptr(&content_type),
+            created_at: asset.metadata.created_at,
+        };
+        (callback.on_entry)(callback.userdata, entry);
+    }
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Find assets that are not fully backed up on this node.
+///
+/// Reports the IDs in `asset_ids` that the manifest does not list, and
+/// listed assets whose content is not stored on this node. Listed assets
+/// whose content is stored but not yet pinned, such as blobs downloaded
+/// for a restore, are pinned.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `asset_ids` must point to `asset_ids_len` valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_manifest_missing(
+    doc_handle: *const IrohDocHandle,
+    asset_ids: *const *const c_char,
+    asset_ids_len: usize,
+    callback: IrohManifestMissingCallback,
+) {
+    let _operation = trace::begin("iroh_manifest_missing");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let ids = match unsafe { str_array(asset_ids, asset_ids_len, "asset ID") } {
+        Ok(ids) => ids,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.manifest_missing(&wrapper.doc, &ids) {
+        Ok(missing) => {
+            let missing: Vec<CString> = missing
+                .into_iter()
+                .map(|id| CString::new(id).unwrap())
+                .collect();
+            let ptrs: Vec<*const c_char> = missing.iter().map(|s| s.as_ptr()).collect();
+            (callback.on_success)(callback.userdata, ptrs.as_ptr(), ptrs.len());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Remove deleted assets from the backup manifest and unpin their blobs.
+///
+/// Each index entry is removed before its pin, and pins left behind by
+/// interrupted calls are removed as well. `on_success` receives the number
+/// of assets that were listed and are now removed.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `asset_ids` must point to `asset_ids_len` valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_manifest_prune(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    asset_ids: *const *const c_char,
+    asset_ids_len: usize,
+    callback: IrohDocDelCallback,
+) {
+    let _operation = trace::begin("iroh_manifest_prune");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let ids = match unsafe { str_array(asset_ids, asset_ids_len, "asset ID") } {
+        Ok(ids) => ids,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes);
+
+    match node.manifest_prune(&wrapper.doc, author.id(), &ids) {
+        Ok(removed) => {
+            (callback.on_success)(callback.userdata, removed);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Set a key-value pair that is removed once `ttl_ms` has elapsed.
+///
+/// The crate keeps an expiry record next to the entry and tombstones the
+/// key after it expires (see `IrohNodeConfig::ttl_prune_interval_ms`).
+/// Writing the key again without a TTL cancels the expiry. The key must
+/// not be empty.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_set_with_ttl(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    value: IrohBytes,
+    ttl_ms: u64,
+    callback: IrohDocSetCallback,
+) {
+    let _operation = trace::begin("iroh_doc_set_with_ttl");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes);
+
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    };
+
+    let value_bytes = if value.data.is_null() || value.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(value.data, value.len).to_vec() }
+    };
+
+    match node.doc_set_with_ttl(
+        &wrapper.doc,
+        author.id(),
+        key_bytes,
+        value_bytes,
+        Duration::from_millis(ttl_ms),
+    ) {
+        Ok(hash) => {
+            let hash_str = CString::new(hash.to_string()).unwrap().into_raw();
+            (callback.on_success)(callback.userdata, hash_str);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Remove expired document entries now instead of waiting for the
+/// background pass.
+///
+/// Calls `on_success` with the number of keys removed.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_docs_prune_expired(
+    handle: *const IrohNodeHandle,
+    callback: IrohDocDelCallback,
+) {
+    let _operation = trace::begin("iroh_docs_prune_expired");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.prune_expired() {
+        Ok(pruned) => {
+            (callback.on_success)(callback.userdata, pruned);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Get the latest entry for a key.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_get(
+    doc_handle: *const IrohDocHandle,
+    key: IrohBytes,
+    callback: IrohDocGetCallback,
+) {
+    let _operation = trace::begin("iroh_doc_get");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    };
+
+    // Query for the exact key
+    let query = iroh_docs::store::Query::key_exact(key_bytes);
+
+    match node.runtime().block_on(async {
+        use futures_lite::StreamExt;
+        use std::pin::pin;
+        let stream = wrapper.doc.get_many(query).await?;
+        let mut stream = pin!(stream);
+        // Get just the first (latest) entry
+        stream.next().await.transpose()
+    }) {
+        Ok(Some(entry)) => {
+            let ffi_entry = convert_entry_to_ffi(&entry);
+            let entry_ptr = Box::into_raw(Box::new(ffi_entry));
+            (callback.on_success)(callback.userdata, entry_ptr);
+        }
+        Ok(None) => {
+            // No entry found - return null
+            (callback.on_success)(callback.userdata, std::ptr::null());
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Get the latest entries for several keys in one call.
+///
+/// Entries are reported in the order of `keys`; a key with no entry is
+/// reported with a null entry. Nothing is reported if the lookup fails.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `keys` must point to `keys_len` `IrohBytes`, each valid for its `len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_get_batch(
+    doc_handle: *const IrohDocHandle,
+    keys: *const IrohBytes,
+    keys_len: usize,
+    callback: IrohDocGetBatchCallback,
+) {
+    let _operation = trace::begin("iroh_doc_get_batch");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if keys.is_null() && keys_len > 0 {
+        let error = CString::new("keys cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let key_slices = if keys_len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(keys, keys_len) }
+    };
+    let key_bytes: Vec<Vec<u8>> = key_slices
+        .iter()
+        .map(|key| {
+            if key.data.is_null() || key.len == 0 {
+                Vec::new()
+            } else {
+                unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+            }
+        })
+        .collect();
+
+    match node.doc_get_batch(&wrapper.doc, &key_bytes) {
+        Ok(entries) => {
+            for (index, entry) in entries.iter().enumerate() {
+                let entry_ptr = entry.as_ref().map_or(std::ptr::null(), |entry| {
+                    Box::into_raw(Box::new(convert_entry_to_ffi(entry))) as *const IrohDocEntry
+                });
+                (callback.on_entry)(callback.userdata, index, entry_ptr);
+            }
+            (callback.on_complete)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Get entries by key prefix.
+///
+/// This streams entries back via the callback - on_entry is called for each
+/// entry, then on_complete when done.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `prefix.data` must point to valid memory for `prefix.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_get_many(
+    doc_handle: *const IrohDocHandle,
+    prefix: IrohBytes,
+    callback: IrohDocGetManyCallback,
+) {
+    let _operation = trace::begin("iroh_doc_get_many");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let prefix_bytes = if prefix.data.is_null() || prefix.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(prefix.data, prefix.len).to_vec() }
+    };
+
+    // Query by prefix
+    let query = iroh_docs::store::Query::key_prefix(prefix_bytes);
+
+    match node.runtime().block_on(async {
+        use futures_lite::StreamExt;
+        use std::pin::pin;
+        let stream = wrapper.doc.get_many(query).await?;
+        let mut stream = pin!(stream);
+
+        while let Some(result) = stream.next().await {
+            match result {
+                Ok(entry) => {
+                    let ffi_entry = convert_entry_to_ffi(&entry);
+                    let entry_ptr = Box::into_raw(Box::new(ffi_entry));
+                    (callback.on_entry)(callback.userdata, entry_ptr);
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            }
+        }
+        Ok::<_, anyhow::Error>(())
+    }) {
+        Ok(()) => {
+            (callback.on_complete)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Get entries matching a query.
+///
+/// Streams the latest entry per author and key, or only each key's winning
+/// entry with `latest_only`, in key order - on_entry is called for each
+/// matching entry, then on_complete when done.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `query.key_prefix.data` must point to valid memory for `query.key_prefix.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_query(
+    doc_handle: *const IrohDocHandle,
+    query: IrohDocQuery,
+    callback: IrohDocGetManyCallback,
+) {
+    let _operation = trace::begin("iroh_doc_query");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let query = unsafe { doc_query_from_ffi(&query) };
+
+    match node.doc_query(&wrapper.doc, &query, |entry| {
+        let entry_ptr = Box::into_raw(Box::new(convert_entry_to_ffi(entry)));
+        (callback.on_entry)(callback.userdata, entry_ptr);
+    }) {
+        Ok(()) => {
+            (callback.on_complete)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Stream entries matching a query to `writer` as newline-delimited JSON.
+///
+/// Each line is a JSON object for one entry, in the order of
+/// `iroh_doc_query`: `key`, `author`, `content_hash`, `content_len`,
+/// `timestamp` (microseconds since epoch) and `value`, which holds the
+/// content of entries up to `max_value_size` bytes that is stored locally
+/// and is left out otherwise. Keys and values that are not valid UTF-8 are
+/// hex encoded in `key_hex` and `value_hex` instead. `writer.write` gets
+/// chunks of whole lines on the calling thread before `callback` fires, and
+/// returns false to stop the export.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `query.key_prefix.data` must point to valid memory for `query.key_prefix.len` bytes
+/// - `writer` and `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_export_query(
+    doc_handle: *const IrohDocHandle,
+    query: IrohDocQuery,
+    max_value_size: u64,
+    writer: IrohSinkWriter,
+    callback: IrohDocExportCallback,
+) {
+    let _operation = trace::begin("iroh_doc_export_query");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let query = unsafe { doc_query_from_ffi(&query) };
+    let result = node.doc_export_query(&wrapper.doc, &query, max_value_size, |chunk| {
+        let bytes = IrohBytes {
+            data: chunk.as_ptr(),
+            len: chunk.len(),
+        };
+        (writer.write)(writer.userdata, bytes)
+    });
+
+    match result {
+        Ok(count) => {
+            (callback.on_success)(callback.userdata, count);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Convert a query filter from FFI representation.
+///
+/// # Safety
+/// `query.key_prefix.data` must point to valid memory for `query.key_prefix.len` bytes.
+#[cfg(feature = "docs")]
+unsafe fn doc_query_from_ffi(query: &IrohDocQuery) -> DocQuery {
+    let key_prefix = if query.key_prefix.data.is_null() || query.key_prefix.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(query.key_prefix.data, query.key_prefix.len).to_vec() }
+    };
+    DocQuery {
+        key_prefix,
+        timestamp_start: (query.timestamp_start > 0).then_some(query.timestamp_start),
+        timestamp_end: (query.timestamp_end > 0).then_some(query.timestamp_end),
+        latest_only: query.latest_only,
+    }
+}
+
+/// Delete an entry (creates a tombstone).
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_del(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    key: IrohBytes,
+    callback: IrohDocDelCallback,
+) {
+    let _operation = trace::begin("iroh_doc_del");
+    let author = Author::from_bytes(&author_secret.bytes).id();
+    doc_del(doc_handle, Some(author), key, callback);
+}
+
+/// Delete an entry as the node's default author.
+///
+/// Like `iroh_doc_del`, without passing the author secret; see
+/// `iroh_node_set_default_author`.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_del_with_default_author(
+    doc_handle: *const IrohDocHandle,
+    key: IrohBytes,
+    callback: IrohDocDelCallback,
+) {
+    let _operation = trace::begin("iroh_doc_del_with_default_author");
+    doc_del(doc_handle, None, key, callback);
+}
+
+/// Shared body of the delete calls; a None author is the node's default.
+#[cfg(feature = "docs")]
+fn doc_del(
+    doc_handle: *const IrohDocHandle,
+    author: Option<AuthorId>,
+    key: IrohBytes,
+    callback: IrohDocDelCallback,
+) {
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let author = match author.map_or_else(|| node.default_author(), Ok) {
+        Ok(author) => author,
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let key_bytes = if key.data.is_null() || key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    };
+
+    match node.doc_del(&wrapper.doc, author, key_bytes) {
+        Ok(count) => {
+            (callback.on_success)(callback.userdata, count as u64);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Delete all of an author's entries with keys in a lexicographic range.
+///
+/// The range includes `start_key` and excludes `end_key`; an empty
+/// `end_key` means no upper bound. Use this to prune time-bucketed keys
+/// such as `events/2023/...`. The author's keys outside the range are
+/// kept, even when they extend a deleted key; if a key in range can't be
+/// deleted without also deleting an older key outside it, nothing is
+/// deleted and the call fails.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `start_key.data` must point to valid memory for `start_key.len` bytes
+/// - `end_key.data` must point to valid memory for `end_key.len` bytes
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_del_range(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    start_key: IrohBytes,
+    end_key: IrohBytes,
+    callback: IrohDocDelCallback,
+) {
+    let _operation = trace::begin("iroh_doc_del_range");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes);
+
+    let start_bytes = if start_key.data.is_null() || start_key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(start_key.data, start_key.len).to_vec() }
+    };
+
+    let end_bytes = if end_key.data.is_null() || end_key.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(end_key.data, end_key.len).to_vec() }
+    };
+
+    match node.doc_del_range(&wrapper.doc, author.id(), &start_bytes, &end_bytes) {
+        Ok(count) => {
+            (callback.on_success)(callback.userdata, count);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Write the latest entries of a document to a new SQLite file.
+///
+/// The file has a `document` table with the namespace and export time, and
+/// an `entries` table with one row per author and key: `key`, `author`,
+/// `content_hash`, `content_len`, `timestamp` (microseconds since epoch) and
+/// `value`. `value` holds the content of entries up to `max_value_size`
+/// bytes that is stored locally, and is NULL otherwise; pass 0 to export
+/// metadata only. An existing file at `path` is replaced once the export
+/// is complete.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `path` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "sqlite")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_export_sqlite(
+    doc_handle: *const IrohDocHandle,
+    path: *const c_char,
+    max_value_size: u64,
+    callback: IrohDocExportCallback,
+) {
+    let _operation = trace::begin("iroh_doc_export_sqlite");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if path.is_null() {
+        let error = CString::new("path cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(s) => PathBuf::from(s),
+        Err(e) => {
+            let error = CString::new(format!("Invalid path UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.doc_export_sqlite(&wrapper.doc, &path, max_value_size) {
+        Ok(count) => {
+            (callback.on_success)(callback.userdata, count);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Read content bytes by hash.
+///
+/// This fetches the actual content data for an entry (entries only contain the hash).
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `content_hash` must be a valid null-terminated UTF-8 hex string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_read_content(
+    handle: *const IrohNodeHandle,
+    content_hash: *const c_char,
+    callback: IrohGetCallback,
+) {
+    let _operation = trace::begin("iroh_doc_read_content");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if content_hash.is_null() {
+        let error = CString::new("content_hash cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let hash_str = match unsafe { CStr::from_ptr(content_hash) }.to_str() {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid hash UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let hash: iroh_blobs::Hash = match hash_str.parse() {
+        Ok(h) => h,
+        Err(e) => {
+            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    read_content(&node, hash, callback);
+}
+
+/// Read content bytes by binary hash.
+///
+/// Same as `iroh_doc_read_content`, without encoding the hash as hex, for
+/// reading the content of many entries.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_read_content_by_hash(
+    handle: *const IrohNodeHandle,
+    content_hash: IrohHash,
+    callback: IrohGetCallback,
+) {
+    let _operation = trace::begin("iroh_doc_read_content_by_hash");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+    read_content(&node, content_hash.into(), callback);
+}
+
+/// Deliver the stored content for `hash` to `callback`.
+#[cfg(feature = "docs")]
+fn read_content(node: &IrohNode, hash: Hash, callback: IrohGetCallback) {
+    match node.runtime().block_on(node.store().get_bytes(hash)) {
+        Ok(bytes) => {
+            let mut vec = pool::copy(&bytes);
+            let owned = IrohOwnedBytes {
+                data: vec.as_mut_ptr(),
+                len: vec.len(),
+                capacity: vec.capacity(),
+            };
+            std::mem::forget(vec);
+            (callback.on_success)(callback.userdata, owned);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Get a share ticket for a document.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_share(
+    doc_handle: *const IrohDocHandle,
+    mode: IrohDocShareMode,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_doc_share");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let share_mode = match mode {
+        IrohDocShareMode::Read => ShareMode::Read,
+        // Handing out write access would bypass the read-only handle
+        IrohDocShareMode::Write if wrapper.read_only => {
+            let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+        IrohDocShareMode::Write => ShareMode::Write,
+    };
+
+    match node.runtime().block_on(
+        wrapper
+            .doc
+            .share(share_mode, AddrInfoOptions::RelayAndAddresses),
+    ) {
+        Ok(ticket) => {
+            let ticket_str = CString::new(ticket.to_string()).unwrap().into_raw();
+            (callback.on_success)(callback.userdata, ticket_str);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Store a snapshot of a document as a blob and return its blob ticket.
+///
+/// The blob holds every signed entry and the content stored for it, so a
+/// new device can bootstrap the document with `iroh_doc_restore_from_blob`
+/// in a single fetch. Anyone with the ticket can read the document.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_snapshot_to_blob(
+    doc_handle: *const IrohDocHandle,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_doc_snapshot_to_blob");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.doc_snapshot(&wrapper.doc) {
+        Ok(ticket) => {
+            let ticket_str = CString::new(ticket).unwrap().into_raw();
+            (callback.on_success)(callback.userdata, ticket_str);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Join a document, bootstrapping it from a snapshot blob.
+///
+/// Fetches the snapshot from `snapshot_ticket` (from
+/// `iroh_doc_snapshot_to_blob`), inserts its entries, whose signatures are
+/// checked as in a sync, and then starts live sync with the peers in
+/// `doc_ticket` to catch up on later writes. The doc ticket grants access;
+/// the snapshot must be of the same document.
+///
+/// # Safety
+/// - `handle` must be a valid node handle with docs enabled
+/// - `doc_ticket` and `snapshot_ticket` must be valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_restore_from_blob(
+    handle: *const IrohNodeHandle,
+    doc_ticket: *const c_char,
+    snapshot_ticket: *const c_char,
+    callback: IrohDocCreateCallback,
+) {
+    let _operation = trace::begin("iroh_doc_restore_from_blob");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if doc_ticket.is_null() || snapshot_ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let (doc_ticket_str, snapshot_ticket_str) = match (
+        unsafe { CStr::from_ptr(doc_ticket) }.to_str(),
+        unsafe { CStr::from_ptr(snapshot_ticket) }.to_str(),
+    ) {
+        (Ok(doc_ticket), Ok(snapshot_ticket)) => (doc_ticket, snapshot_ticket),
+        (Err(e), _) | (_, Err(e)) => {
+            let error = CString::new(format!("Invalid ticket UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let parsed_doc_ticket: DocTicket = match doc_ticket_str.parse() {
+        Ok(t) => t,
+        Err(e) => {
+            let error = CString::new(format!("Invalid doc ticket: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.doc_restore_snapshot(parsed_doc_ticket, snapshot_ticket_str) {
+        Ok(doc) => {
+            let namespace_id = doc.id().to_string();
+            let namespace_cstr = CString::new(namespace_id).unwrap().into_raw();
+            node.track_swarm(&doc);
+
+            let wrapper = Box::new(DocWrapper {
+                doc,
+                node_handle: handle,
+                read_only: false,
+            });
+            let doc_handle = Box::into_raw(wrapper) as *mut IrohDocHandle;
+            handles::register(HandleKind::Doc, doc_handle);
+
+            (callback.on_success)(callback.userdata, doc_handle, namespace_cstr);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Get swarm statistics for a document's gossip topic.
+///
+/// Counting starts when the document is first created, joined or opened on
+/// this node and continues until the node shuts down.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_swarm_stats(
+    doc_handle: *const IrohDocHandle,
+    callback: IrohDocSwarmStatsCallback,
+) {
+    let _operation = trace::begin("iroh_doc_swarm_stats");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    let stats = node.doc_swarm_stats(wrapper.doc.id());
+    let ffi_stats = IrohDocSwarmStats {
+        neighbors: stats.neighbors,
+        messages_sent: stats.messages_sent,
+        messages_received: stats.messages_received,
+        last_activity: stats.last_activity.unwrap_or(0),
+    };
+    (callback.on_success)(callback.userdata, ffi_stats);
+}
+
+/// Get how syncs of a document with each peer went, for sync health
+/// displays.
+///
+/// Peers are listed once they joined the document's swarm or synced,
+/// counting from when the document was first created, joined or opened on
+/// this node. `NodePeerSyncChanged` events report each change.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_peer_sync_stats(
+    doc_handle: *const IrohDocHandle,
+    callback: IrohPeerSyncStatsCallback,
+) {
+    let _operation = trace::begin("iroh_doc_peer_sync_stats");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    for stats in node.doc_peer_sync_stats(wrapper.doc.id()) {
+        let peer_id = CString::new(stats.peer.to_string()).unwrap();
+        let last_error = stats.last_error.map(|e| CString::new(e).unwrap());
+        let entry = IrohPeerSyncStats {
+            peer_id: peer_id.as_ptr(),
+            last_success: stats.last_success.unwrap_or(0),
+            pending_entries: stats.pending_entries,
+            failure_streak: stats.failure_streak,
+            last_error: last_error.as_ref().map_or(std::ptr::null(), |e| e.as_ptr()),
+        };
+        (callback.on_entry)(callback.userdata, entry);
+    }
+    (callback.on_complete)(callback.userdata);
+}
+
+/// Get a document's namespace ID as a string, without a callback.
+///
+/// Returns null if `doc_handle` is null.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle or null
+/// - The returned string must be freed with `iroh_string_free`
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_namespace_id(doc_handle: *const IrohDocHandle) -> *mut c_char {
+    if doc_handle.is_null() {
+        return std::ptr::null_mut();
+    }
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    CString::new(wrapper.doc.id().to_string())
+        .unwrap()
+        .into_raw()
+}
+
+/// Close a document and free its resources.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle returned by `iroh_doc_create` or `iroh_doc_join`
+/// - `doc_handle` must not be used after this call
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_close(doc_handle: *mut IrohDocHandle) {
+    if doc_handle.is_null() {
+        return;
+    }
+
+    unsafe {
+        // Drop the wrapper, which will drop the Doc
+        handles::release(doc_handle);
+        drop(Box::from_raw(doc_handle as *mut DocWrapper));
+    }
+}
+
+/// Free a document entry.
+///
+/// # Safety
+/// - `entry` must be a valid entry pointer returned by document operations
+/// - `entry` must not be used after this call
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_entry_free(entry: *mut IrohDocEntry) {
+    if entry.is_null() {
+        return;
+    }
+
+    unsafe {
+        let entry = Box::from_raw(entry);
+        // Return the key bytes to the pool
+        if !entry.key.data.is_null() {
+            pool::recycle(Vec::from_raw_parts(
+                entry.key.data,
+                entry.key.len,
+                entry.key.capacity,
+            ));
+        }
+        // Free the content hash string
+        if !entry.content_hash.is_null() {
+            drop(CString::from_raw(entry.content_hash));
+        }
+        // The rest is stack-allocated and drops automatically
+    }
+}
+
+/// Verify a signed entry received outside of sync.
+///
+/// `entry` holds a signed entry in the iroh-docs wire encoding. Its author
+/// and namespace signatures are checked, and if `namespace_id` is not null
+/// the entry must also belong to that document. On success the entry is
+/// passed to `on_success` (caller must free with `iroh_doc_entry_free`).
+///
+/// # Safety
+/// - `entry.data` must point to valid memory for `entry.len` bytes
+/// - `namespace_id` must be a valid null-terminated UTF-8 string or null
+/// - `callback` must have valid function pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_entry_verify(
+    entry: IrohBytes,
+    namespace_id: *const c_char,
+    callback: IrohDocGetCallback,
+) {
+    let _operation = trace::begin("iroh_doc_entry_verify");
+    let entry_bytes = if entry.data.is_null() || entry.len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(entry.data, entry.len) }
+    };
+
+    let namespace = match unsafe { optional_str(namespace_id) } {
+        Ok(None) => None,
+        Ok(Some(s)) => match s.parse::<NamespaceId>() {
+            Ok(id) => Some(id),
+            Err(e) => {
+                let error = CString::new(format!("Invalid namespace ID: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        },
+        Err(e) => {
+            let error = CString::new(format!("Invalid namespace ID UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    match entry::verify(entry_bytes, namespace) {
+        Ok(signed) => {
+            let ffi_entry = convert_entry_to_ffi(signed.entry());
+            let entry_ptr = Box::into_raw(Box::new(ffi_entry));
+            (callback.on_success)(callback.userdata, entry_ptr);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Convert an iroh_docs Entry to FFI representation.
+#[cfg(feature = "docs")]
+fn convert_entry_to_ffi(entry: &iroh_docs::Entry) -> IrohDocEntry {
+    // Get author ID bytes
+    let author_id = IrohAuthorId {
+        bytes: entry.author().to_bytes(),
+    };
+
+    // Get key bytes (owned copy, returned to the pool when freed)
+    let key_vec = pool::copy(entry.key());
+    let mut key_vec = std::mem::ManuallyDrop::new(key_vec);
+    let key = IrohOwnedBytes {
+        data: key_vec.as_mut_ptr(),
+        len: key_vec.len(),
+        capacity: key_vec.capacity(),
+    };
+
+    // Get content hash as string
+    let hash_str = CString::new(entry.content_hash().to_string())
+        .unwrap()
+        .into_raw();
+
+    IrohDocEntry {
+        author_id,
+        key,
+        content_hash: hash_str,
+        content_size: entry.content_len(),
+        timestamp: entry.timestamp(),
+        hash: entry.content_hash().into(),
+    }
+}
+
+// ============================================================================
+// Subscription Operations
+// ============================================================================
+
+/// Subscribe to document events.
+///
+/// Returns a subscription handle that can be used to cancel the subscription.
+/// Events are delivered via the callback until the subscription is cancelled
+/// or the stream ends.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `callback` must have valid function pointers that remain valid for the
+///   duration of the subscription
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_subscribe(
+    doc_handle: *const IrohDocHandle,
+    callback: IrohDocSubscribeCallback,
+) -> *mut IrohSubscriptionHandle {
+    spawn_subscription(doc_handle, None, None, false, callback)
+}
+
+/// Subscribe to document events, replaying entries written since `cursor`.
+///
+/// Every event carries a cursor; pass the last one seen to resume after a
+/// restart. Entries written since then are delivered first as `Replayed`
+/// events, oldest first, followed by live events. Only the latest entry per
+/// author and key is replayed, and deletions are replayed as empty entries.
+/// A null `cursor` starts from the document's current state.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `cursor` must be null or a valid C string returned in an event
+/// - `callback` must have valid function pointers that remain valid for the
+///   duration of the subscription
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_subscribe_from(
+    doc_handle: *const IrohDocHandle,
+    cursor: *const c_char,
+    callback: IrohDocSubscribeCallback,
+) -> *mut IrohSubscriptionHandle {
+    let _operation = trace::begin("iroh_doc_subscribe_from");
+    let resume_from = match unsafe { parse_cursor(cursor) } {
+        Ok(cursor) => cursor,
+        Err(error) => {
+            let error = CString::new(error).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return std::ptr::null_mut();
+        }
+    };
+
+    spawn_subscription(doc_handle, resume_from, None, false, callback)
+}
+
+/// Subscribe to document events with options.
+///
+/// With `fetch_content`, each remote insert matching the document's
+/// download policy is followed by a ContentFetched event carrying the entry
+/// and its content as soon as the content is local: right after the
+/// InsertRemote if it already was, or else once the download finishes,
+/// next to the ContentReady. Content that fails to download is not
+/// reported. The download policy is read when the subscription starts.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `options.cursor` must be null or a valid C string returned in an event
+/// - `callback` must have valid function pointers that remain valid for the
+///   duration of the subscription
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_subscribe_with_options(
+    doc_handle: *const IrohDocHandle,
+    options: IrohDocSubscribeOptions,
+    callback: IrohDocSubscribeCallback,
+) -> *mut IrohSubscriptionHandle {
+    let _operation = trace::begin("iroh_doc_subscribe_with_options");
+    let resume_from = match unsafe { parse_cursor(options.cursor) } {
+        Ok(cursor) => cursor,
+        Err(error) => {
+            let error = CString::new(error).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return std::ptr::null_mut();
+        }
+    };
+    let fetch_content = (options.fetch_content && !options.deletions_only)
+        .then_some((options.max_content_size > 0).then_some(options.max_content_size));
+
+    spawn_subscription(
+        doc_handle,
+        resume_from,
+        fetch_content,
+        options.deletions_only,
+        callback,
+    )
+}
+
+/// Parse an optional cursor returned in an event.
+///
+/// # Safety
+/// `cursor` must be null or a valid C string.
+#[cfg(feature = "docs")]
+unsafe fn parse_cursor(cursor: *const c_char) -> Result<Option<Cursor>, String> {
+    match unsafe { optional_str(cursor) } {
+        Ok(None) => Ok(None),
+        Ok(Some(s)) => Cursor::decode(&s)
+            .map(Some)
+            .map_err(|e| format!("Invalid cursor: {:#}", e)),
+        Err(e) => Err(format!("Invalid cursor UTF-8: {}", e)),
+    }
+}
+
+/// Start the subscription task, replaying from `resume_from` if given.
+///
+/// With `fetch_content`, the content of remote inserts up to the given
+/// size, if any, is delivered in ContentFetched events. With
+/// `deletions_only`, only EntryDeleted events are delivered.
+#[cfg(feature = "docs")]
+fn spawn_subscription(
+    doc_handle: *const IrohDocHandle,
+    resume_from: Option<Cursor>,
+    fetch_content: Option<Option<u64>>,
+    deletions_only: bool,
+    callback: IrohDocSubscribeCallback,
+) -> *mut IrohSubscriptionHandle {
+    let _operation = trace::begin("iroh_doc_subscribe");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    };
+
+    // Create cancellation channel
+    let (cancel, mut cancel_rx) = node.start_subscription();
+
+    // Clone what we need for the spawned task
+    let doc = wrapper.doc.clone();
+    let namespace_id = doc.id().to_string();
+    let store = node.store().clone();
+    // Convert userdata to usize for Send safety (will convert back in async block)
+    let userdata_addr = callback.userdata as usize;
+    let on_event = callback.on_event;
+    let on_complete = callback.on_complete;
+    let on_failure = callback.on_failure;
+    let callbacks = node.callbacks().clone();
+
+    // Helper macro to convert usize back to pointer at point of use
+    macro_rules! ud {
+        ($addr:expr) => {
+            $addr as *mut c_void
+        };
+    }
+
+    // Helper macro to run a callback where the node delivers callbacks
+    macro_rules! deliver {
+        ($call:expr) => {
+            unsafe { callbacks.call(|| $call) }
+        };
+    }
+
+    // Spawn the subscription task on the node's runtime
+    node.spawn_subscription(async move {
+        use futures_lite::StreamExt;
+        use iroh_docs::engine::LiveEvent;
+        use std::pin::pin;
+
+        // Subscribe before reading the replay so nothing falls in between
+        let stream = match doc.subscribe().await {
+            Ok(s) => s,
+            Err(e) => {
+                let error = trace::failure(&e);
+                deliver!((on_failure)(ud!(userdata_addr), error.into_raw()));
+                return;
+            }
+        };
+        let mut stream = pin!(stream);
+
+        let mut fetch = match fetch_content {
+            Some(max_size) => match ContentFetch::new(&doc, store, max_size).await {
+                Ok(fetch) => Some(fetch),
+                Err(e) => {
+                    let error = trace::failure(&e);
+                    deliver!((on_failure)(ud!(userdata_addr), error.into_raw()));
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        let replay = match resume_from {
+            Some(cursor) => cursor
+                .missed_entries(&doc)
+                .await
+                .map(|entries| (cursor, entries)),
+            None => Cursor::current(&doc)
+                .await
+                .map(|cursor| (cursor, Vec::new())),
+        };
+        let (mut cursor, missed) = match replay {
+            Ok(replay) => replay,
+            Err(e) => {
+                let error = trace::failure(&e);
+                deliver!((on_failure)(ud!(userdata_addr), error.into_raw()));
+                return;
+            }
+        };
+        for entry in missed {
+            cursor.observe(entry.author(), entry.timestamp());
+            let event_type = match (deletions_only, entry.content_len() == 0) {
+                (false, _) => IrohDocEventType::Replayed,
+                (true, true) => IrohDocEventType::EntryDeleted,
+                (true, false) => continue,
+            };
+            let ffi_event = IrohDocEvent {
+                event_type,
+                entry: Box::into_raw(Box::new(convert_entry_to_ffi(&entry))),
+                peer_id: std::ptr::null(),
+                content_hash: std::ptr::null(),
+                cursor: CString::new(cursor.encode()).unwrap().into_raw(),
+                namespace_id: CString::new(namespace_id.clone()).unwrap().into_raw(),
+                content: no_bytes(),
+            };
+            deliver!((on_event)(ud!(userdata_addr), ffi_event));
+        }
+
+        loop {
+            tokio::select! {
+                // Check for cancellation
+                _ = &mut cancel_rx => {
+                    deliver!((on_complete)(ud!(userdata_addr)));
+                    break;
+                }
+                // Check for next event
+                event = stream.next() => {
+                    match event {
+                        Some(Ok(live_event)) => {
+                            if let LiveEvent::InsertLocal { entry }
+                            | LiveEvent::InsertRemote { entry, .. } = &live_event
+                            {
+                                cursor.observe(entry.author(), entry.timestamp());
+                            }
+                            let ffi_event = if deletions_only {
+                                convert_deletion_to_ffi(&live_event)
+                            } else {
+                                Some(convert_live_event_to_ffi(&live_event))
+                            };
+                            let Some(mut ffi_event) = ffi_event else {
+                                continue;
+                            };
+                            ffi_event.cursor = CString::new(cursor.encode()).unwrap().into_raw();
+                            ffi_event.namespace_id =
+                                CString::new(namespace_id.clone()).unwrap().into_raw();
+                            deliver!((on_event)(ud!(userdata_addr), ffi_event));
+                            let fetched = match &mut fetch {
+                                Some(fetch) => fetch.observe(&live_event).await,
+                                None => Vec::new(),
+                            };
+                            for fetched in fetched {
+                                let mut ffi_event = convert_fetched_to_ffi(fetched);
+                                ffi_event.cursor =
+                                    CString::new(cursor.encode()).unwrap().into_raw();
+                                ffi_event.namespace_id =
+                                    CString::new(namespace_id.clone()).unwrap().into_raw();
+                                deliver!((on_event)(ud!(userdata_addr), ffi_event));
+                            }
+                        }
+                        Some(Err(e)) => {
+                            let error = trace::failure(&e);
+                            deliver!((on_failure)(ud!(userdata_addr), error.into_raw()));
+                            break;
+                        }
+                        None => {
+                            // Stream ended normally
+                            deliver!((on_complete)(ud!(userdata_addr)));
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    SubscriptionWrapper::into_handle(cancel)
+}
+
+/// Cancel an active subscription.
+///
+/// After calling this, no more events will be delivered and on_complete will be called.
+///
+/// # Safety
+/// - `handle` must be a valid subscription handle returned by `iroh_doc_subscribe`
+/// - `handle` must not be used after this call
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_subscription_cancel(handle: *mut IrohSubscriptionHandle) {
+    if handle.is_null() {
+        return;
+    }
+
+    unsafe {
+        handles::release(handle);
+        let wrapper = Box::from_raw(handle as *mut SubscriptionWrapper);
+        // Send cancellation signal (if not already sent)
+        wrapper.cancel.cancel();
+    }
+}
+
+/// Register a resolver for keys whose authors hold diverging values.
+///
+/// Whenever a remote insert leaves several authors with different latest
+/// values for a key, `resolver.resolve` is called on a background thread
+/// with every candidate; no lock is held while it runs. A value set on the
+/// resolution is written by `author_secret` while compare-and-set writes
+/// are held off, and only if the candidates are still the key's latest
+/// entries. Local writes never invoke the resolver.
+///
+/// Returns a handle to stop the resolver with `iroh_subscription_cancel`.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `resolver` must have valid function pointers that remain valid until
+///   on_complete or on_failure is called
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_set_conflict_resolver(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    resolver: IrohDocConflictResolver,
+) -> *mut IrohSubscriptionHandle {
+    let _operation = trace::begin("iroh_doc_set_conflict_resolver");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (resolver.on_failure)(resolver.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (resolver.on_failure)(resolver.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (resolver.on_failure)(resolver.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    };
+
+    let author = Author::from_bytes(&author_secret.bytes);
+    let (cancel, cancel_rx) = node.start_subscription();
+
+    // Convert userdata to usize for Send safety
+    let userdata_addr = resolver.userdata as usize;
+    let resolve_fn = resolver.resolve;
+    let on_complete = resolver.on_complete;
+    let on_failure = resolver.on_failure;
+    let callbacks = node.callbacks().clone();
+    let resolve_callbacks = callbacks.clone();
+
+    let resolve = move |key: &[u8], candidates: &[ConflictCandidate]| {
+        let strings: Vec<_> = candidates
+            .iter()
+            .map(|c| {
+                (
+                    CString::new(c.author.to_string()).unwrap(),
+                    CString::new(c.content_hash.to_string()).unwrap(),
+                )
+            })
+            .collect();
+        let ffi_candidates: Vec<_> = candidates
+            .iter()
+            .zip(&strings)
+            .map(|(c, (author, hash))| IrohConflictCandidate {
+                author: author.as_ptr(),
+                content_hash: hash.as_ptr(),
+                content: IrohBytes {
+                    data: c.content.as_ptr(),
+                    len: c.content.len(),
+                },
+                timestamp: c.timestamp,
+            })
+            .collect();
+        let mut slot: Option<Vec<u8>> = None;
+        let resolution = &mut slot as *mut Option<Vec<u8>> as *mut IrohConflictResolution;
+        unsafe {
+            resolve_callbacks.call(|| {
+                resolve_fn(
+                    userdata_addr as *mut c_void,
+                    IrohBytes {
+                        data: key.as_ptr(),
+                        len: key.len(),
+                    },
+                    ffi_candidates.as_ptr(),
+                    ffi_candidates.len(),
+                    resolution,
+                )
+            })
+        };
+        slot
+    };
+
+    let task = node.doc_conflict_resolver(
+        wrapper.doc.clone(),
+        author.id(),
+        Box::new(resolve),
+        cancel_rx,
+    );
+    node.spawn_subscription(async move {
+        match task.await {
+            Ok(()) => unsafe { callbacks.call(|| (on_complete)(userdata_addr as *mut c_void)) },
+            Err(e) => {
+                let error = trace::failure(&e);
+                unsafe {
+                    callbacks.call(|| (on_failure)(userdata_addr as *mut c_void, error.into_raw()))
+                };
+            }
+        }
+    });
+
+    SubscriptionWrapper::into_handle(cancel)
+}
+
+/// Filter remote writes to a document by author.
+///
+/// Entries synced from peers are accepted if their author is
+/// `author_secret` or in `filter.allowed_authors`; otherwise `filter.decide`
+/// is called on a background thread, and without it they are rejected.
+/// Rejected and quarantined entries are rolled back by `author_secret`,
+/// restoring the newest allowed value or deleting the key, then reported
+/// to `filter.on_filtered`. Sync cannot be stopped before entries are
+/// stored, so they stay in the replica and are briefly visible.
+///
+/// Returns a handle to stop the filter with `iroh_subscription_cancel`.
+///
+/// # Safety
+/// - `doc_handle` must be a valid document handle
+/// - `filter.allowed_authors` must point to `filter.allowed_authors_len`
+///   valid null-terminated UTF-8 strings
+/// - `filter` must have valid function pointers that remain valid until
+///   on_complete or on_failure is called
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_doc_set_write_filter(
+    doc_handle: *const IrohDocHandle,
+    author_secret: IrohAuthorSecret,
+    filter: IrohDocWriteFilter,
+) -> *mut IrohSubscriptionHandle {
+    let _operation = trace::begin("iroh_doc_set_write_filter");
+    if doc_handle.is_null() {
+        let error = CString::new("doc_handle cannot be null").unwrap();
+        (filter.on_failure)(filter.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    }
+
+    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
+
+    if wrapper.read_only {
+        let error = CString::new(DOC_READ_ONLY_ERROR).unwrap();
+        (filter.on_failure)(filter.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    }
+    let Some(node) = enter_node(wrapper.node_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (filter.on_failure)(filter.userdata, error.into_raw());
+        return std::ptr::null_mut();
+    };
+
+    let mut allowed = HashSet::with_capacity(filter.allowed_authors_len);
+    if !filter.allowed_authors.is_null() {
+        let ptrs = unsafe {
+            std::slice::from_raw_parts(filter.allowed_authors, filter.allowed_authors_len)
+        };
+        for &ptr in ptrs {
+            let parsed = if ptr.is_null() {
+                Err(anyhow::anyhow!("author ID cannot be null"))
+            } else {
+                unsafe { CStr::from_ptr(ptr) }
+                    .to_str()
+                    .map_err(anyhow::Error::from)
+                    .and_then(AuthorId::from_str)
+            };
+            match parsed {
+                Ok(id) => {
+                    allowed.insert(id);
+                }
+                Err(e) => {
+                    let error = CString::new(format!("Invalid author ID: {}", e)).unwrap();
+                    (filter.on_failure)(filter.userdata, error.into_raw());
+                    return std::ptr::null_mut();
+                }
+            }
+        }
+    }
+
+    let author = Author::from_bytes(&author_secret.bytes);
+    let (cancel, cancel_rx) = node.start_subscription();
+
+    // Convert userdata to usize for Send safety
+    let userdata_addr = filter.userdata as usize;
+    let on_complete = filter.on_complete;
+    let on_failure = filter.on_failure;
+    let callbacks = node.callbacks().clone();
+
+    // Hands `entry` to `f` as an FFI entry that borrows its strings
+    fn with_ffi_entry<R>(entry: &FilteredEntry, f: impl FnOnce(IrohFilteredEntry) -> R) -> R {
+        let author = CString::new(entry.author.to_string()).unwrap();
+        let content_hash = CString::new(entry.content_hash.to_string()).unwrap();
+        f(IrohFilteredEntry {
+            key: IrohBytes {
+                data: entry.key.as_ptr(),
+                len: entry.key.len(),
+            },
+            author: author.as_ptr(),
+            content_hash: content_hash.as_ptr(),
+            content_len: entry.content_len,
+            timestamp: entry.timestamp,
+        })
+    }
+
+    let decide = filter.decide.map(|decide_fn| {
+        let callbacks = callbacks.clone();
+        Box::new(move |entry: &FilteredEntry| {
+            with_ffi_entry(entry, |ffi_entry| unsafe {
+                callbacks.call(|| decide_fn(userdata_addr as *mut c_void, ffi_entry).into())
+            })
+        }) as Box<Decide>
+    });
+    let on_filtered = filter.on_filtered.map(|on_filtered_fn| {
+        let callbacks = callbacks.clone();
+        Box::new(
+            move |entry: &FilteredEntry, verdict: Verdict, content: Option<&[u8]>| {
+                let content = content.unwrap_or_default();
+                with_ffi_entry(entry, |ffi_entry| unsafe {
+                    callbacks.call(|| {
+                        on_filtered_fn(
+                            userdata_addr as *mut c_void,
+                            ffi_entry,
+                            verdict.into(),
+                            IrohBytes {
+                                data: content.as_ptr(),
+                                len: content.len(),
+                            },
+                        )
+                    })
+                })
+            },
+        ) as Box<OnFiltered>
+    });
+
+    let task = node.doc_write_filter(
+        wrapper.doc.clone(),
+        author.id(),
+        WritePolicy {
+            allowed,
+            decide,
+            on_filtered,
+        },
+        cancel_rx,
+    );
+    node.spawn_subscription(async move {
+        match task.await {
+            Ok(()) => unsafe { callbacks.call(|| (on_complete)(userdata_addr as *mut c_void)) },
+            Err(e) => {
+                let error = trace::failure(&e);
+                unsafe {
+                    callbacks.call(|| (on_failure)(userdata_addr as *mut c_void, error.into_raw()))
+                };
+            }
+        }
+    });
 
-    // Perform the get operation
-    match node.get(&ticket_str) {
-        Ok(bytes) => {
-            let mut vec = bytes;
-            let owned = IrohOwnedBytes {
-                data: vec.as_mut_ptr(),
-                len: vec.len(),
-                capacity: vec.capacity(),
-            };
-            std::mem::forget(vec); // Prevent deallocation, Swift will free
-            (callback.on_success)(callback.userdata, owned);
+    SubscriptionWrapper::into_handle(cancel)
+}
+
+/// Choose the value a conflict resolver writes for the key.
+///
+/// Calling this again replaces the previous value.
+///
+/// # Safety
+/// - `resolution` must be the pointer passed to the current resolve call
+/// - `value.data` must point to valid memory for `value.len` bytes
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_conflict_resolution_set(
+    resolution: *mut IrohConflictResolution,
+    value: IrohBytes,
+) {
+    if resolution.is_null() {
+        return;
+    }
+    let slot = unsafe { &mut *(resolution as *mut Option<Vec<u8>>) };
+    *slot = Some(if value.data.is_null() || value.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(value.data, value.len).to_vec() }
+    });
+}
+
+/// Free a document event.
+///
+/// # Safety
+/// - `event` fields that are non-null must be valid pointers
+#[cfg(feature = "docs")]
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_doc_event_free(event: IrohDocEvent) {
+    unsafe {
+        // Free entry if present
+        if !event.entry.is_null() {
+            iroh_doc_entry_free(event.entry as *mut IrohDocEntry);
+        }
+        // Free peer_id if present
+        if !event.peer_id.is_null() {
+            drop(CString::from_raw(event.peer_id as *mut c_char));
+        }
+        // Free content_hash if present
+        if !event.content_hash.is_null() {
+            drop(CString::from_raw(event.content_hash as *mut c_char));
+        }
+        // Free cursor if present
+        if !event.cursor.is_null() {
+            drop(CString::from_raw(event.cursor as *mut c_char));
+        }
+        // Free namespace_id if present
+        if !event.namespace_id.is_null() {
+            drop(CString::from_raw(event.namespace_id as *mut c_char));
+        }
+    }
+    iroh_bytes_free(event.content);
+}
+
+/// Bytes for events without content.
+#[cfg(feature = "docs")]
+fn no_bytes() -> IrohOwnedBytes {
+    IrohOwnedBytes {
+        data: std::ptr::null_mut(),
+        len: 0,
+        capacity: 0,
+    }
+}
+
+/// Convert a remote entry with its content to a ContentFetched event.
+#[cfg(feature = "docs")]
+fn convert_fetched_to_ffi(fetched: Fetched) -> IrohDocEvent {
+    let mut content = std::mem::ManuallyDrop::new(fetched.content);
+    IrohDocEvent {
+        event_type: IrohDocEventType::ContentFetched,
+        entry: Box::into_raw(Box::new(convert_entry_to_ffi(&fetched.entry))),
+        peer_id: CString::new(fetched.from.to_string()).unwrap().into_raw(),
+        content_hash: CString::new(fetched.entry.content_hash().to_string())
+            .unwrap()
+            .into_raw(),
+        cursor: std::ptr::null(),
+        namespace_id: std::ptr::null(),
+        content: IrohOwnedBytes {
+            data: content.as_mut_ptr(),
+            len: content.len(),
+            capacity: content.capacity(),
+        },
+    }
+}
+
+/// Convert an insert of a tombstone to an EntryDeleted event, if it is one.
+#[cfg(feature = "docs")]
+fn convert_deletion_to_ffi(event: &iroh_docs::engine::LiveEvent) -> Option<IrohDocEvent> {
+    use iroh_docs::engine::LiveEvent;
+
+    let (entry, peer_id) = match event {
+        LiveEvent::InsertLocal { entry } => (entry, std::ptr::null()),
+        LiveEvent::InsertRemote { from, entry, .. } => (
+            entry,
+            CString::new(from.to_string()).unwrap().into_raw() as *const c_char,
+        ),
+        _ => return None,
+    };
+    if entry.content_len() != 0 {
+        return None;
+    }
+    Some(IrohDocEvent {
+        event_type: IrohDocEventType::EntryDeleted,
+        entry: Box::into_raw(Box::new(convert_entry_to_ffi(entry))),
+        peer_id,
+        content_hash: std::ptr::null(),
+        cursor: std::ptr::null(),
+        namespace_id: std::ptr::null(),
+        content: no_bytes(),
+    })
+}
+
+/// Convert a LiveEvent to FFI representation.
+#[cfg(feature = "docs")]
+fn convert_live_event_to_ffi(event: &iroh_docs::engine::LiveEvent) -> IrohDocEvent {
+    use iroh_docs::engine::LiveEvent;
+
+    match event {
+        LiveEvent::InsertLocal { entry } => {
+            let ffi_entry = convert_entry_to_ffi(entry);
+            let entry_ptr = Box::into_raw(Box::new(ffi_entry));
+            IrohDocEvent {
+                event_type: IrohDocEventType::InsertLocal,
+                entry: entry_ptr,
+                peer_id: std::ptr::null(),
+                content_hash: std::ptr::null(),
+                cursor: std::ptr::null(),
+                namespace_id: std::ptr::null(),
+                content: no_bytes(),
+            }
+        }
+        LiveEvent::InsertRemote { from, entry, .. } => {
+            let ffi_entry = convert_entry_to_ffi(entry);
+            let entry_ptr = Box::into_raw(Box::new(ffi_entry));
+            let peer_id = CString::new(from.to_string()).unwrap().into_raw();
+            IrohDocEvent {
+                event_type: IrohDocEventType::InsertRemote,
+                entry: entry_ptr,
+                peer_id,
+                content_hash: std::ptr::null(),
+                cursor: std::ptr::null(),
+                namespace_id: std::ptr::null(),
+                content: no_bytes(),
+            }
+        }
+        LiveEvent::ContentReady { hash } => {
+            let hash_str = CString::new(hash.to_string()).unwrap().into_raw();
+            IrohDocEvent {
+                event_type: IrohDocEventType::ContentReady,
+                entry: std::ptr::null(),
+                peer_id: std::ptr::null(),
+                content_hash: hash_str,
+                cursor: std::ptr::null(),
+                namespace_id: std::ptr::null(),
+                content: no_bytes(),
+            }
+        }
+        LiveEvent::PendingContentReady => IrohDocEvent {
+            event_type: IrohDocEventType::PendingContentReady,
+            entry: std::ptr::null(),
+            peer_id: std::ptr::null(),
+            content_hash: std::ptr::null(),
+            cursor: std::ptr::null(),
+            namespace_id: std::ptr::null(),
+            content: no_bytes(),
+        },
+        LiveEvent::NeighborUp(peer) => {
+            let peer_id = CString::new(peer.to_string()).unwrap().into_raw();
+            IrohDocEvent {
+                event_type: IrohDocEventType::NeighborUp,
+                entry: std::ptr::null(),
+                peer_id,
+                content_hash: std::ptr::null(),
+                cursor: std::ptr::null(),
+                namespace_id: std::ptr::null(),
+                content: no_bytes(),
+            }
+        }
+        LiveEvent::NeighborDown(peer) => {
+            let peer_id = CString::new(peer.to_string()).unwrap().into_raw();
+            IrohDocEvent {
+                event_type: IrohDocEventType::NeighborDown,
+                entry: std::ptr::null(),
+                peer_id,
+                content_hash: std::ptr::null(),
+                cursor: std::ptr::null(),
+                namespace_id: std::ptr::null(),
+                content: no_bytes(),
+            }
+        }
+        LiveEvent::SyncFinished(sync_event) => {
+            let peer_id = CString::new(sync_event.peer.to_string())
+                .unwrap()
+                .into_raw();
+            IrohDocEvent {
+                event_type: IrohDocEventType::SyncFinished,
+                entry: std::ptr::null(),
+                peer_id,
+                content_hash: std::ptr::null(),
+                cursor: std::ptr::null(),
+                namespace_id: std::ptr::null(),
+                content: no_bytes(),
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Blob Tag Operations
+// ============================================================================
+
+/// Tag (pin) a blob to prevent garbage collection.
+///
+/// Tagged blobs are protected from GC until the tag is removed.
+/// Use this after downloading content you want to keep.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `tag_name` must be a valid null-terminated UTF-8 string
+/// - `hash_str` must be a valid null-terminated hex hash string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_blob_tag_set(
+    handle: *const IrohNodeHandle,
+    tag_name: *const c_char,
+    hash_str: *const c_char,
+    format: IrohBlobFormat,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_blob_tag_set");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if tag_name.is_null() {
+        let error = CString::new("tag_name cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if hash_str.is_null() {
+        let error = CString::new("hash_str cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let tag_name_str = match unsafe { CStr::from_ptr(tag_name) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid tag_name UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let hash_string = match unsafe { CStr::from_ptr(hash_str) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid hash UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let hash: Hash = match hash_string.parse() {
+        Ok(h) => h,
+        Err(e) => {
+            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    tag_set(handle, tag_name_str, hash, format, callback);
+}
+
+/// Tag (pin) a blob given by binary hash to prevent garbage collection.
+///
+/// Same as `iroh_blob_tag_set`, without encoding the hash as hex.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `tag_name` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_blob_tag_set_by_hash(
+    handle: *const IrohNodeHandle,
+    tag_name: *const c_char,
+    hash: IrohHash,
+    format: IrohBlobFormat,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_blob_tag_set_by_hash");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let tag_name = match unsafe { required_str(tag_name, "tag_name") } {
+        Ok(tag_name) => tag_name,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    tag_set(handle, tag_name, hash.into(), format, callback);
+}
+
+/// Set the tag `tag_name` to `hash` for the `iroh_blob_tag_set` variants.
+fn tag_set(
+    handle: *const IrohNodeHandle,
+    tag_name: String,
+    hash: Hash,
+    format: IrohBlobFormat,
+    callback: IrohCloseCallback,
+) {
+    let blob_format = match format {
+        IrohBlobFormat::Raw => BlobFormat::Raw,
+        IrohBlobFormat::HashSeq => BlobFormat::HashSeq,
+    };
+
+    let hash_and_format = HashAndFormat {
+        hash,
+        format: blob_format,
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    // A tag set without an expiry time is kept
+    if let Err(e) = node.clear_tag_expiry(&tag_name) {
+        let error = trace::failure(&e);
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    // Use the store's tags API
+    match node
+        .runtime()
+        .block_on(node.store().tags().set(tag_name, hash_and_format))
+    {
+        Ok(()) => {
+            (callback.on_complete)(callback.userdata);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-// ============================================================================
-// Memory Management
-// ============================================================================
-
-/// Free a string returned by Iroh functions.
+/// Tag (pin) a blob until an expiry time, for time-limited shares.
+///
+/// At `expires_at` (microseconds since epoch) the tag is removed, its
+/// content becomes eligible for garbage collection, and a
+/// `NodeTagExpired` event is emitted. Expiry times survive restarts.
+/// Setting or deleting the tag with `iroh_blob_tag_set` or
+/// `iroh_blob_tag_delete` cancels the expiry.
 ///
 /// # Safety
-/// - `s` must be a pointer returned by an Iroh function, or null
-/// - `s` must not be used after this call
+/// - `handle` must be a valid node handle
+/// - `tag_name` must be a valid null-terminated UTF-8 string
+/// - `hash_str` must be a valid null-terminated hex hash string
+/// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_string_free(s: *mut c_char) {
-    if !s.is_null() {
-        unsafe {
-            drop(CString::from_raw(s));
-        }
+pub unsafe extern "C" fn iroh_blob_tag_set_until(
+    handle: *const IrohNodeHandle,
+    tag_name: *const c_char,
+    hash_str: *const c_char,
+    format: IrohBlobFormat,
+    expires_at: u64,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_blob_tag_set_until");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     }
-}
 
-/// Free bytes returned by `iroh_get`.
-///
-/// # Safety
-/// - `bytes` must have been returned by `iroh_get`
-/// - The bytes must not be used after this call
-#[unsafe(no_mangle)]
-pub extern "C" fn iroh_bytes_free(bytes: IrohOwnedBytes) {
-    if !bytes.data.is_null() {
-        unsafe {
-            // Reconstruct the Vec and let it drop
-            drop(Vec::from_raw_parts(bytes.data, bytes.len, bytes.capacity));
+    let strings = unsafe {
+        required_str(tag_name, "tag_name").and_then(|tag_name| {
+            let hash = required_str(hash_str, "hash_str")?;
+            Ok((tag_name, hash))
+        })
+    };
+    let (tag_name, hash_string) = match strings {
+        Ok(strings) => strings,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let hash: Hash = match hash_string.parse() {
+        Ok(h) => h,
+        Err(e) => {
+            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let content = HashAndFormat {
+        hash,
+        format: match format {
+            IrohBlobFormat::Raw => BlobFormat::Raw,
+            IrohBlobFormat::HashSeq => BlobFormat::HashSeq,
+        },
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.tag_until(&tag_name, content, expires_at) {
+        Ok(()) => {
+            (callback.on_complete)(callback.userdata);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-// ============================================================================
-// Extended Operations
-// ============================================================================
-
-/// Download bytes from a ticket with progress reporting.
+/// Create a shareable ticket for an existing local blob.
+///
+/// The ticket points to this node as the provider.
+/// Use this to "mint" a bootstrap ticket after downloading content.
 ///
 /// # Safety
 /// - `handle` must be a valid node handle
-/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `hash_str` must be a valid null-terminated hex hash string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_get_with_progress(
+pub unsafe extern "C" fn iroh_blob_ticket_create(
     handle: *const IrohNodeHandle,
-    ticket: *const c_char,
-    callback: IrohGetProgressCallback,
+    hash_str: *const c_char,
+    format: IrohBlobFormat,
+    callback: IrohCallback,
 ) {
+    let _operation = trace::begin("iroh_blob_ticket_create");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    if ticket.is_null() {
-        let error = CString::new("ticket cannot be null").unwrap();
+    if hash_str.is_null() {
+        let error = CString::new("hash_str cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    // Parse the ticket string
-    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+    let hash_string = match unsafe { CStr::from_ptr(hash_str) }.to_str() {
         Ok(s) => s.to_string(),
         Err(e) => {
-            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            let error = CString::new(format!("Invalid hash UTF-8: {}", e)).unwrap();
             (callback.on_failure)(callback.userdata, error.into_raw());
             return;
         }
     };
 
-    let node = unsafe { &*(handle as *const IrohNode) };
-    let userdata = callback.userdata;
-    let on_progress_fn = callback.on_progress;
-
-    // Progress callback closure
-    let progress_fn = move |downloaded: u64, total: u64| {
-        let progress = IrohDownloadProgress { downloaded, total };
-        (on_progress_fn)(userdata, progress);
-    };
-
-    match node.get_with_progress(&ticket_str, progress_fn) {
-        Ok(bytes) => {
-            let mut vec = bytes;
-            let owned = IrohOwnedBytes {
-                data: vec.as_mut_ptr(),
-                len: vec.len(),
-                capacity: vec.capacity(),
-            };
-            std::mem::forget(vec);
-            (callback.on_success)(callback.userdata, owned);
-        }
+    let hash: Hash = match hash_string.parse() {
+        Ok(h) => h,
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
             (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
         }
+    };
+
+    ticket_create(handle, hash, format, callback);
+}
+
+/// Create a shareable ticket for an existing local blob given by binary hash.
+///
+/// Same as `iroh_blob_ticket_create`, without encoding the hash as hex.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_blob_ticket_create_by_hash(
+    handle: *const IrohNodeHandle,
+    hash: IrohHash,
+    format: IrohBlobFormat,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_blob_ticket_create_by_hash");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     }
+
+    ticket_create(handle, hash.into(), format, callback);
 }
 
-/// Get information about the node.
+/// Create a ticket for `hash` on this node for the `iroh_blob_ticket_create`
+/// variants.
+fn ticket_create(
+    handle: *const IrohNodeHandle,
+    hash: Hash,
+    format: IrohBlobFormat,
+    callback: IrohCallback,
+) {
+    let blob_format = match format {
+        IrohBlobFormat::Raw => BlobFormat::Raw,
+        IrohBlobFormat::HashSeq => BlobFormat::HashSeq,
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    // Get the node's address and create a ticket
+    let addr = node.ticket_addr();
+    let ticket = BlobTicket::new(addr, hash, blob_format);
+    let ticket_str = CString::new(ticket.to_string()).unwrap().into_raw();
+
+    (callback.on_success)(callback.userdata, ticket_str);
+}
+
+/// Remove a tag (unpin) from a blob, allowing garbage collection.
 ///
 /// # Safety
 /// - `handle` must be a valid node handle
+/// - `tag_name` must be a valid null-terminated UTF-8 string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_node_info(handle: *const IrohNodeHandle, callback: IrohNodeInfoCallback) {
+pub unsafe extern "C" fn iroh_blob_tag_delete(
+    handle: *const IrohNodeHandle,
+    tag_name: *const c_char,
+    callback: IrohCloseCallback,
+) {
+    let _operation = trace::begin("iroh_blob_tag_delete");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let node = unsafe { &*(handle as *const IrohNode) };
+    if tag_name.is_null() {
+        let error = CString::new("tag_name cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
 
-    match node.info() {
-        Ok(info) => {
-            let node_id = CString::new(info.node_id).unwrap().into_raw();
-            let relay_url = info
-                .relay_url
-                .map(|url| CString::new(url).unwrap().into_raw())
-                .unwrap_or(std::ptr::null_mut());
+    let tag_name_str = match unsafe { CStr::from_ptr(tag_name) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid tag_name UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
 
-            let ffi_info = IrohNodeInfo {
-                node_id,
-                relay_url,
-                is_connected: info.is_connected,
-            };
-            (callback.on_success)(callback.userdata, ffi_info);
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    if let Err(e) = node.clear_tag_expiry(&tag_name_str) {
+        let error = trace::failure(&e);
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    // Use the store's tags API to delete the tag
+    match node
+        .runtime()
+        .block_on(node.store().tags().delete(tag_name_str))
+    {
+        Ok(_count) => {
+            (callback.on_complete)(callback.userdata);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Validate and parse a ticket string.
+/// Create a temporary tag that protects a blob from garbage collection.
 ///
-/// This function always succeeds - check `info.is_valid` for the result.
+/// Use this to protect content during a multi-step operation, such as
+/// building a collection, until a permanent tag is set. The protection
+/// lasts until the tag is dropped or the node shuts down.
 ///
 /// # Safety
-/// - `ticket` must be a valid null-terminated UTF-8 string (or null)
+/// - `handle` must be a valid node handle
+/// - `hash_str` must be a valid null-terminated hex hash string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_validate_ticket(
-    ticket: *const c_char,
-    callback: IrohTicketValidateCallback,
+pub unsafe extern "C" fn iroh_blob_temp_tag_create(
+    handle: *const IrohNodeHandle,
+    hash_str: *const c_char,
+    format: IrohBlobFormat,
+    callback: IrohTempTagCallback,
 ) {
-    let result = if ticket.is_null() {
-        IrohTicketInfo {
-            is_valid: false,
-            hash: std::ptr::null(),
-            node_id: std::ptr::null(),
-            is_recursive: false,
+    let _operation = trace::begin("iroh_blob_temp_tag_create");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    if hash_str.is_null() {
+        let error = CString::new("hash_str cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let hash_string = match unsafe { CStr::from_ptr(hash_str) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid hash UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
         }
-    } else {
-        match unsafe { CStr::from_ptr(ticket) }.to_str() {
-            Ok(ticket_str) => match ticket_str.parse::<BlobTicket>() {
-                Ok(parsed) => {
-                    let hash = CString::new(parsed.hash().to_string()).unwrap().into_raw();
-                    let node_id = CString::new(parsed.addr().id.to_string())
-                        .unwrap()
-                        .into_raw();
-
-                    IrohTicketInfo {
-                        is_valid: true,
-                        hash,
-                        node_id,
-                        is_recursive: parsed.recursive(),
-                    }
-                }
-                Err(_) => IrohTicketInfo {
-                    is_valid: false,
-                    hash: std::ptr::null(),
-                    node_id: std::ptr::null(),
-                    is_recursive: false,
-                },
-            },
-            Err(_) => IrohTicketInfo {
-                is_valid: false,
-                hash: std::ptr::null(),
-                node_id: std::ptr::null(),
-                is_recursive: false,
-            },
+    };
+
+    let hash: Hash = match hash_string.parse() {
+        Ok(h) => h,
+        Err(e) => {
+            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
         }
     };
 
-    (callback.on_complete)(callback.userdata, result);
-}
+    let blob_format = match format {
+        IrohBlobFormat::Raw => BlobFormat::Raw,
+        IrohBlobFormat::HashSeq => BlobFormat::HashSeq,
+    };
 
-// ============================================================================
-// Close and Timeout Operations
-// ============================================================================
+    let hash_and_format = HashAndFormat {
+        hash,
+        format: blob_format,
+    };
 
-/// Explicitly close a node and free its resources asynchronously.
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node
+        .runtime()
+        .block_on(node.store().tags().temp_tag(hash_and_format))
+    {
+        Ok(tag) => {
+            let tag_ptr = Box::into_raw(Box::new(tag)) as *mut IrohTempTagHandle;
+            (callback.on_success)(callback.userdata, tag_ptr);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
+}
+
+/// Drop a temporary tag, allowing its content to be garbage collected.
 ///
-/// This is preferred over `iroh_node_destroy` when you need to await
-/// graceful shutdown completion.
+/// Content that has since been given a permanent tag stays protected.
 ///
 /// # Safety
-/// - `handle` must be a valid pointer returned by `iroh_node_create`
-/// - `handle` must not be used after this call
-/// - `callback` must have valid function pointers
+/// - `tag` must be a handle returned by `iroh_blob_temp_tag_create`, or null
+/// - `tag` must not be used after this call
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_node_close(handle: *mut IrohNodeHandle, callback: IrohCloseCallback) {
-    if handle.is_null() {
-        (callback.on_complete)(callback.userdata);
+pub unsafe extern "C" fn iroh_blob_temp_tag_drop(tag: *mut IrohTempTagHandle) {
+    if tag.is_null() {
         return;
     }
 
     unsafe {
-        let node = Box::from_raw(handle as *mut IrohNode);
-        match node.shutdown() {
-            Ok(()) => (callback.on_complete)(callback.userdata),
-            Err(e) => {
-                let error = CString::new(format!("{:#}", e)).unwrap();
-                (callback.on_failure)(callback.userdata, error.into_raw());
-            }
-        }
+        drop(Box::from_raw(tag as *mut TempTag));
     }
 }
 
-/// Add bytes to the blob store with options (e.g., timeout).
+/// Report how much of a tag's content is shared with other references.
+///
+/// `unique_size` is the space that garbage collection would reclaim after
+/// deleting the tag. Other tags, temporary tags and document entries all
+/// keep content.
 ///
 /// # Safety
 /// - `handle` must be a valid node handle
-/// - `bytes.data` must point to valid memory for `bytes.len` bytes
+/// - `tag_name` must be a valid null-terminated UTF-8 string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_put_with_options(
+pub unsafe extern "C" fn iroh_blob_reference_report(
     handle: *const IrohNodeHandle,
-    bytes: IrohBytes,
-    options: IrohOperationOptions,
-    callback: IrohCallback,
+    tag_name: *const c_char,
+    callback: IrohReferenceReportCallback,
 ) {
+    let _operation = trace::begin("iroh_blob_reference_report");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    // Copy the bytes to own them (Swift memory may not be stable)
-    let data = if bytes.data.is_null() || bytes.len == 0 {
-        Vec::new()
-    } else {
-        unsafe { std::slice::from_raw_parts(bytes.data, bytes.len).to_vec() }
+    if tag_name.is_null() {
+        let error = CString::new("tag_name cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let tag_name_str = match unsafe { CStr::from_ptr(tag_name) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid tag_name UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
     };
 
-    let node = unsafe { &*(handle as *const IrohNode) };
-    let timeout_ms = options.timeout_ms;
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-    match node.put_with_timeout(&data, timeout_ms) {
-        Ok(ticket) => {
-            let ticket_cstr = CString::new(ticket).unwrap();
-            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
+    match node.reference_report(&tag_name_str) {
+        Ok(report) => {
+            let ffi_report = IrohReferenceReport {
+                total_blobs: report.total_blobs,
+                shared_blobs: report.shared_blobs,
+                total_size: report.total_size,
+                unique_size: report.unique_size,
+            };
+            (callback.on_success)(callback.userdata, ffi_report);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Download bytes from a ticket with options (e.g., timeout).
+/// Report how much of a collection's content is shared with other references.
+///
+/// Counts the collection and its children. Tags naming the collection
+/// itself are treated as deleted with it.
 ///
 /// # Safety
 /// - `handle` must be a valid node handle
-/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `hash_str` must be a valid null-terminated hex hash string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_get_with_options(
+pub unsafe extern "C" fn iroh_blob_collection_reference_report(
     handle: *const IrohNodeHandle,
-    ticket: *const c_char,
-    options: IrohOperationOptions,
-    callback: IrohGetCallback,
+    hash_str: *const c_char,
+    callback: IrohReferenceReportCallback,
 ) {
+    let _operation = trace::begin("iroh_blob_collection_reference_report");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    if ticket.is_null() {
-        let error = CString::new("ticket cannot be null").unwrap();
-        (callback.on_failure)(callback.userdata, error.into_raw());
-        return;
-    }
+    let hash_string = match unsafe { required_str(hash_str, "hash_str") } {
+        Ok(s) => s,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
 
-    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
-        Ok(s) => s.to_string(),
+    let hash: Hash = match hash_string.parse() {
+        Ok(h) => h,
         Err(e) => {
-            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
             (callback.on_failure)(callback.userdata, error.into_raw());
             return;
         }
     };
 
-    let node = unsafe { &*(handle as *const IrohNode) };
-    let timeout_ms = options.timeout_ms;
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-    match node.get_with_timeout(&ticket_str, timeout_ms) {
-        Ok(bytes) => {
-            let mut vec = bytes;
-            let owned = IrohOwnedBytes {
-                data: vec.as_mut_ptr(),
-                len: vec.len(),
-                capacity: vec.capacity(),
+    match node.collection_reference_report(hash) {
+        Ok(report) => {
+            let ffi_report = IrohReferenceReport {
+                total_blobs: report.total_blobs,
+                shared_blobs: report.shared_blobs,
+                total_size: report.total_size,
+                unique_size: report.unique_size,
             };
-            std::mem::forget(vec);
-            (callback.on_success)(callback.userdata, owned);
+            (callback.on_success)(callback.userdata, ffi_report);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-// ============================================================================
-// Author Operations
-// ============================================================================
-
-/// Create a new random author keypair.
+/// Report identical content referenced by several tags or collections.
 ///
-/// The secret key should be stored securely (e.g., in iOS Keychain).
-/// The ID is derived from the secret and can be stored openly.
+/// Lists at most `limit` duplicates, those saving the most space first,
+/// followed by store-wide totals. Only exact hash matches are counted.
 ///
 /// # Safety
+/// - `handle` must be a valid node handle
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_author_create(callback: IrohAuthorCreateCallback) {
-    // Generate a new random author
-    let author = Author::new(&mut rand::rng());
+pub unsafe extern "C" fn iroh_blob_dedup_report(
+    handle: *const IrohNodeHandle,
+    limit: usize,
+    callback: IrohDedupReportCallback,
+) {
+    let _operation = trace::begin("iroh_blob_dedup_report");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
 
-    // Get the secret bytes (32 bytes)
-    let secret_bytes = author.to_bytes();
-    let secret = IrohAuthorSecret {
-        bytes: secret_bytes,
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     };
 
-    // Get the public ID bytes (32 bytes)
-    let author_id = author.id();
-    let id_bytes = author_id.as_bytes();
-    let id = IrohAuthorId { bytes: *id_bytes };
-
-    (callback.on_success)(callback.userdata, secret, id);
-}
-
-/// Get the author ID from a secret key.
-///
-/// This is a pure computation - no node required.
-/// Useful for deriving the ID after loading secret from Keychain.
-///
-/// # Safety
-/// - `secret` must contain valid author secret bytes
-#[unsafe(no_mangle)]
-pub extern "C" fn iroh_author_id_from_secret(secret: IrohAuthorSecret) -> IrohAuthorId {
-    // Reconstruct the Author from the secret bytes
-    let author = Author::from_bytes(&secret.bytes);
-
-    // Get the public ID bytes
-    let author_id = author.id();
-    let id_bytes = author_id.as_bytes();
-    IrohAuthorId { bytes: *id_bytes }
+    match node.dedup_report(limit) {
+        Ok(report) => {
+            for duplicate in report.duplicates {
+                let hash = CString::new(duplicate.hash.to_string()).unwrap();
+                // Tag names are arbitrary bytes; drop interior nulls rather than fail
+                let tags: Vec<CString> = duplicate
+                    .tags
+                    .iter()
+                    .map(|t| CString::new(t.replace('\0', "")).unwrap())
+                    .collect();
+                let tag_ptrs: Vec<*const c_char> = tags.iter().map(|t| t.as_ptr()).collect();
+                let entry = IrohDuplicateBlob {
+                    hash: hash.as_ptr(),
+                    size: duplicate.size,
+                    references: duplicate.references,
+                    tags: tag_ptrs.as_ptr(),
+                    tags_len: tag_ptrs.len(),
+                };
+                (callback.on_duplicate)(callback.userdata, entry);
+            }
+            let summary = IrohDedupSummary {
+                references: report.references,
+                unique_blobs: report.unique_blobs,
+                duplicate_blobs: report.duplicate_blobs,
+                referenced_size: report.referenced_size,
+                stored_size: report.stored_size,
+            };
+            (callback.on_complete)(callback.userdata, summary);
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
 }
 
-/// Import an author from a hex-encoded secret key.
+/// List tags whose names start with `prefix`, with their stored sizes.
 ///
-/// Useful for debugging or cross-device sync.
+/// An empty prefix lists all tags.
 ///
 /// # Safety
-/// - `secret_hex` must be a valid null-terminated UTF-8 string containing 64 hex chars
+/// - `handle` must be a valid node handle
+/// - `prefix` must be a valid null-terminated UTF-8 string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_author_from_hex(
-    secret_hex: *const c_char,
-    callback: IrohAuthorCreateCallback,
+pub unsafe extern "C" fn iroh_blob_tag_list(
+    handle: *const IrohNodeHandle,
+    prefix: *const c_char,
+    callback: IrohTagListCallback,
 ) {
-    if secret_hex.is_null() {
-        let error = CString::new("secret_hex cannot be null").unwrap();
+    let _operation = trace::begin("iroh_blob_tag_list");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let hex_str = match unsafe { CStr::from_ptr(secret_hex) }.to_str() {
-        Ok(s) => s,
-        Err(e) => {
-            let error = CString::new(format!("Invalid UTF-8 in secret_hex: {}", e)).unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
-        }
-    };
+    if prefix.is_null() {
+        let error = CString::new("prefix cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
 
-    // Decode hex to bytes
-    let secret_bytes: [u8; 32] = match hex::decode(hex_str) {
-        Ok(bytes) if bytes.len() == 32 => {
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(&bytes);
-            arr
-        }
-        Ok(bytes) => {
-            let error = CString::new(format!(
-                "Invalid secret length: expected 32 bytes, got {}",
-                bytes.len()
-            ))
-            .unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
-        }
+    let prefix_str = match unsafe { CStr::from_ptr(prefix) }.to_str() {
+        Ok(s) => s.to_string(),
         Err(e) => {
-            let error = CString::new(format!("Invalid hex string: {}", e)).unwrap();
+            let error = CString::new(format!("Invalid prefix UTF-8: {}", e)).unwrap();
             (callback.on_failure)(callback.userdata, error.into_raw());
             return;
         }
     };
 
-    // Reconstruct the Author
-    let author = Author::from_bytes(&secret_bytes);
-
-    let secret = IrohAuthorSecret {
-        bytes: secret_bytes,
-    };
-    let id = IrohAuthorId {
-        bytes: *author.id().as_bytes(),
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     };
 
-    (callback.on_success)(callback.userdata, secret, id);
-}
-
-/// Export an author secret as a hex string.
-///
-/// Useful for debugging or backup.
-///
-/// # Safety
-/// - The returned string must be freed with `iroh_string_free`
-#[unsafe(no_mangle)]
-pub extern "C" fn iroh_author_secret_to_hex(secret: IrohAuthorSecret) -> *mut c_char {
-    let hex_string = hex::encode(secret.bytes);
-    CString::new(hex_string).unwrap().into_raw()
+    match node.list_tags(&prefix_str) {
+        Ok(listing) => deliver_tag_listing(listing, &callback),
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
 }
 
-/// Export an author ID as a hex string.
-///
-/// # Safety
-/// - The returned string must be freed with `iroh_string_free`
-#[unsafe(no_mangle)]
-pub extern "C" fn iroh_author_id_to_hex(id: IrohAuthorId) -> *mut c_char {
-    let hex_string = hex::encode(id.bytes);
-    CString::new(hex_string).unwrap().into_raw()
+/// Pass each tag of `listing` to `callback`, then its total size.
+fn deliver_tag_listing(listing: TagListing, callback: &IrohTagListCallback) {
+    for tag in listing.tags {
+        // Tag names are arbitrary bytes; drop interior nulls rather than fail
+        let name = CString::new(tag.name.replace('\0', "")).unwrap();
+        let hash = CString::new(tag.hash.to_string()).unwrap();
+        let format = match tag.format {
+            BlobFormat::Raw => IrohBlobFormat::Raw,
+            BlobFormat::HashSeq => IrohBlobFormat::HashSeq,
+        };
+        let entry = IrohTagListEntry {
+            name: name.as_ptr(),
+            hash: hash.as_ptr(),
+            format,
+            size: tag.size,
+        };
+        (callback.on_entry)(callback.userdata, entry);
+    }
+    (callback.on_complete)(callback.userdata, listing.total_size);
 }
 
-/// Import an author into the docs engine.
+/// List blobs in the local store, sorted and paged.
 ///
-/// This must be called before using an author to sign document entries.
-/// The author is registered with the docs engine so it can sign entries.
+/// A `cursor` of 0 starts a new listing: the store is read into a sorted
+/// snapshot and `on_complete` reports the cursor for it. Passing that
+/// cursor cuts later pages from the same snapshot, in its order, so each
+/// caller pages through its own unchanged listing; start a new listing to
+/// see changes. Only the latest 16 listings are kept; older cursors fail.
+/// Add times are recorded for the blobs that puts return tickets for and
+/// content that arrives by download, gateway or document sync; other
+/// blobs report 0 and sort last for the time-based orders.
 ///
 /// # Safety
-/// - `handle` must be a valid node handle with docs enabled
+/// - `handle` must be a valid node handle
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_author_import(
+pub unsafe extern "C" fn iroh_blob_list(
     handle: *const IrohNodeHandle,
-    author_secret: IrohAuthorSecret,
-    callback: IrohCloseCallback,
+    sort: IrohBlobSort,
+    cursor: u64,
+    offset: u64,
+    limit: u64,
+    callback: IrohBlobListCallback,
 ) {
+    let _operation = trace::begin("iroh_blob_list");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let node = unsafe { &*(handle as *const IrohNode) };
-
-    let docs = match node.docs() {
-        Some(d) => d,
-        None => {
-            let error = CString::new("docs not enabled on this node").unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
-        }
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     };
-
-    // Reconstruct the author from secret bytes
-    let author = Author::from_bytes(&author_secret.bytes);
-
-    match node.runtime().block_on(docs.api().author_import(author)) {
-        Ok(()) => {
-            (callback.on_complete)(callback.userdata);
+    let offset = usize::try_from(offset).unwrap_or(usize::MAX);
+    let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+
+    let cursor = (cursor != 0).then_some(cursor);
+
+    match node.list_blobs(sort.into(), cursor, offset, limit) {
+        Ok(page) => {
+            for entry in page.entries {
+                let hash = CString::new(entry.hash.to_string()).unwrap();
+                let ffi_entry = IrohBlobListEntry {
+                    hash: hash.as_ptr(),
+                    size: entry.size,
+                    is_complete: entry.is_complete,
+                    added_at: entry.added_at.unwrap_or(0),
+                };
+                (callback.on_entry)(callback.userdata, ffi_entry);
+            }
+            (callback.on_complete)(callback.userdata, page.total, page.cursor);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
 // ============================================================================
-// Document Operations
+// Profile Operations
 // ============================================================================
 
-/// Create a new document.
+/// Add bytes to the blob store for a profile and get a shareable ticket.
+///
+/// Profiles partition the store between user accounts on one device. The
+/// content is tagged `auto/<hash>` in the profile rather than getting an
+/// automatic tag, so only the profile keeps it. Profile IDs consist of
+/// ASCII letters, digits, `-` and `_`, at most 64 bytes.
 ///
 /// # Safety
-/// - `handle` must be a valid node handle with docs enabled
+/// - `handle` must be a valid node handle
+/// - `profile` must be a valid null-terminated UTF-8 string
+/// - `bytes.data` must point to valid memory for `bytes.len` bytes
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_create(handle: *const IrohNodeHandle, callback: IrohDocCreateCallback) {
+pub unsafe extern "C" fn iroh_profile_put(
+    handle: *const IrohNodeHandle,
+    profile: *const c_char,
+    bytes: IrohBytes,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_profile_put");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let node = unsafe { &*(handle as *const IrohNode) };
-
-    let docs = match node.docs() {
-        Some(d) => d,
-        None => {
-            let error = CString::new("docs not enabled on this node").unwrap();
+    let profile = match unsafe { required_str(profile, "profile") } {
+        Ok(profile) => profile,
+        Err(error) => {
             (callback.on_failure)(callback.userdata, error.into_raw());
             return;
         }
     };
 
-    match node.runtime().block_on(docs.api().create()) {
-        Ok(doc) => {
-            let namespace_id = doc.id().to_string();
-            let namespace_cstr = CString::new(namespace_id).unwrap().into_raw();
+    let data = if bytes.data.is_null() || bytes.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(bytes.data, bytes.len).to_vec() }
+    };
 
-            // Wrap the doc for FFI
-            let wrapper = Box::new(DocWrapper {
-                doc,
-                node_handle: handle,
-            });
-            let doc_handle = Box::into_raw(wrapper) as *mut IrohDocHandle;
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-            (callback.on_success)(callback.userdata, doc_handle, namespace_cstr);
+    match node.profile_put(&profile, &data) {
+        Ok(ticket) => {
+            stats::bytes_in(data.len());
+            let ticket_cstr = CString::new(ticket).unwrap();
+            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Join an existing document via ticket.
+/// Tag a blob in a profile, replacing any profile tag of the same name.
+///
+/// The tag is named `profiles/<profile>/<tag_name>` in the store.
 ///
 /// # Safety
-/// - `handle` must be a valid node handle with docs enabled
-/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `handle` must be a valid node handle
+/// - `profile` and `tag_name` must be valid null-terminated UTF-8 strings
+/// - `hash_str` must be a valid null-terminated hex hash string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_doc_join(
+pub unsafe extern "C" fn iroh_profile_tag_set(
     handle: *const IrohNodeHandle,
-    ticket: *const c_char,
-    callback: IrohDocCreateCallback,
+    profile: *const c_char,
+    tag_name: *const c_char,
+    hash_str: *const c_char,
+    format: IrohBlobFormat,
+    callback: IrohCloseCallback,
 ) {
+    let _operation = trace::begin("iroh_profile_tag_set");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    if ticket.is_null() {
-        let error = CString::new("ticket cannot be null").unwrap();
-        (callback.on_failure)(callback.userdata, error.into_raw());
-        return;
-    }
-
-    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
-        Ok(s) => s,
-        Err(e) => {
-            let error = CString::new(format!("Invalid ticket UTF-8: {}", e)).unwrap();
+    let strings = unsafe {
+        required_str(profile, "profile").and_then(|profile| {
+            let tag_name = required_str(tag_name, "tag_name")?;
+            let hash = required_str(hash_str, "hash_str")?;
+            Ok((profile, tag_name, hash))
+        })
+    };
+    let (profile, tag_name, hash_string) = match strings {
+        Ok(strings) => strings,
+        Err(error) => {
             (callback.on_failure)(callback.userdata, error.into_raw());
             return;
         }
     };
 
-    let doc_ticket: DocTicket = match ticket_str.parse() {
-        Ok(t) => t,
+    let hash: Hash = match hash_string.parse() {
+        Ok(h) => h,
         Err(e) => {
-            let error = CString::new(format!("Invalid doc ticket: {}", e)).unwrap();
+            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
             (callback.on_failure)(callback.userdata, error.into_raw());
             return;
         }
     };
 
-    let node = unsafe { &*(handle as *const IrohNode) };
-
-    let docs = match node.docs() {
-        Some(d) => d,
-        None => {
-            let error = CString::new("docs not enabled on this node").unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
-        }
+    let content = HashAndFormat {
+        hash,
+        format: match format {
+            IrohBlobFormat::Raw => BlobFormat::Raw,
+            IrohBlobFormat::HashSeq => BlobFormat::HashSeq,
+        },
     };
 
-    match node.runtime().block_on(docs.api().import(doc_ticket)) {
-        Ok(doc) => {
-            let namespace_id = doc.id().to_string();
-            let namespace_cstr = CString::new(namespace_id).unwrap().into_raw();
-
-            let wrapper = Box::new(DocWrapper {
-                doc,
-                node_handle: handle,
-            });
-            let doc_handle = Box::into_raw(wrapper) as *mut IrohDocHandle;
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-            (callback.on_success)(callback.userdata, doc_handle, namespace_cstr);
+    match node.profile_tag_set(&profile, &tag_name, content) {
+        Ok(()) => {
+            (callback.on_complete)(callback.userdata);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Set a key-value pair in a document.
+/// Remove a tag from a profile.
+///
+/// Content the profile no longer tags stays stored until
+/// `iroh_profile_gc` reclaims it.
 ///
 /// # Safety
-/// - `doc_handle` must be a valid document handle
-/// - `key.data` must point to valid memory for `key.len` bytes
-/// - `value.data` must point to valid memory for `value.len` bytes
+/// - `handle` must be a valid node handle
+/// - `profile` and `tag_name` must be valid null-terminated UTF-8 strings
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_set(
-    doc_handle: *const IrohDocHandle,
-    author_secret: IrohAuthorSecret,
-    key: IrohBytes,
-    value: IrohBytes,
-    callback: IrohDocSetCallback,
+pub unsafe extern "C" fn iroh_profile_tag_delete(
+    handle: *const IrohNodeHandle,
+    profile: *const c_char,
+    tag_name: *const c_char,
+    callback: IrohCloseCallback,
 ) {
-    if doc_handle.is_null() {
-        let error = CString::new("doc_handle cannot be null").unwrap();
+    let _operation = trace::begin("iroh_profile_tag_delete");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
-    let node = unsafe { &*(wrapper.node_handle as *const IrohNode) };
-
-    // Reconstruct author from secret
-    let author = Author::from_bytes(&author_secret.bytes);
-
-    // Copy key and value bytes
-    let key_bytes = if key.data.is_null() || key.len == 0 {
-        Vec::new()
-    } else {
-        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    let strings = unsafe {
+        required_str(profile, "profile")
+            .and_then(|profile| Ok((profile, required_str(tag_name, "tag_name")?)))
+    };
+    let (profile, tag_name) = match strings {
+        Ok(strings) => strings,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
     };
 
-    let value_bytes = if value.data.is_null() || value.len == 0 {
-        Vec::new()
-    } else {
-        unsafe { std::slice::from_raw_parts(value.data, value.len).to_vec() }
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     };
 
-    // set_bytes takes author_id (AuthorId), not Author
-    let author_id = author.id();
-    match node
-        .runtime()
-        .block_on(wrapper.doc.set_bytes(author_id, key_bytes, value_bytes))
-    {
-        Ok(hash) => {
-            let hash: iroh_blobs::Hash = hash; // type annotation
-            let hash_str = CString::new(hash.to_string()).unwrap().into_raw();
-            (callback.on_success)(callback.userdata, hash_str);
+    match node.profile_tag_delete(&profile, &tag_name) {
+        Ok(()) => {
+            (callback.on_complete)(callback.userdata);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Get the latest entry for a key.
+/// List a profile's tags with their stored sizes.
+///
+/// Names are given without the `profiles/<profile>/` prefix.
 ///
 /// # Safety
-/// - `doc_handle` must be a valid document handle
-/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `handle` must be a valid node handle
+/// - `profile` must be a valid null-terminated UTF-8 string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_get(
-    doc_handle: *const IrohDocHandle,
-    key: IrohBytes,
-    callback: IrohDocGetCallback,
+pub unsafe extern "C" fn iroh_profile_tag_list(
+    handle: *const IrohNodeHandle,
+    profile: *const c_char,
+    callback: IrohTagListCallback,
 ) {
-    if doc_handle.is_null() {
-        let error = CString::new("doc_handle cannot be null").unwrap();
+    let _operation = trace::begin("iroh_profile_tag_list");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
-    let node = unsafe { &*(wrapper.node_handle as *const IrohNode) };
-
-    let key_bytes = if key.data.is_null() || key.len == 0 {
-        Vec::new()
-    } else {
-        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+    let profile = match unsafe { required_str(profile, "profile") } {
+        Ok(profile) => profile,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
     };
 
-    // Query for the exact key
-    let query = iroh_docs::store::Query::key_exact(key_bytes);
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-    match node.runtime().block_on(async {
-        use futures_lite::StreamExt;
-        use std::pin::pin;
-        let stream = wrapper.doc.get_many(query).await?;
-        let mut stream = pin!(stream);
-        // Get just the first (latest) entry
-        stream.next().await.transpose()
-    }) {
-        Ok(Some(entry)) => {
-            let ffi_entry = convert_entry_to_ffi(&entry);
-            let entry_ptr = Box::into_raw(Box::new(ffi_entry));
-            (callback.on_success)(callback.userdata, entry_ptr);
-        }
-        Ok(None) => {
-            // No entry found - return null
-            (callback.on_success)(callback.userdata, std::ptr::null());
-        }
+    match node.profile_tags(&profile) {
+        Ok(listing) => deliver_tag_listing(listing, &callback),
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Get entries by key prefix.
-///
-/// This streams entries back via the callback - on_entry is called for each
-/// entry, then on_complete when done.
+/// Count a profile's content and how much of it no other tag shares.
 ///
 /// # Safety
-/// - `doc_handle` must be a valid document handle
-/// - `prefix.data` must point to valid memory for `prefix.len` bytes
+/// - `handle` must be a valid node handle
+/// - `profile` must be a valid null-terminated UTF-8 string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_get_many(
-    doc_handle: *const IrohDocHandle,
-    prefix: IrohBytes,
-    callback: IrohDocGetManyCallback,
+pub unsafe extern "C" fn iroh_profile_stats(
+    handle: *const IrohNodeHandle,
+    profile: *const c_char,
+    callback: IrohProfileStatsCallback,
 ) {
-    if doc_handle.is_null() {
-        let error = CString::new("doc_handle cannot be null").unwrap();
-        (callback.on_failure)(callback.userdata, error.into_raw());
-        return;
-    }
-
-    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
-    let node = unsafe { &*(wrapper.node_handle as *const IrohNode) };
-
-    let prefix_bytes = if prefix.data.is_null() || prefix.len == 0 {
-        Vec::new()
-    } else {
-        unsafe { std::slice::from_raw_parts(prefix.data, prefix.len).to_vec() }
-    };
-
-    // Query by prefix
-    let query = iroh_docs::store::Query::key_prefix(prefix_bytes);
-
-    match node.runtime().block_on(async {
-        use futures_lite::StreamExt;
-        use std::pin::pin;
-        let stream = wrapper.doc.get_many(query).await?;
-        let mut stream = pin!(stream);
-
-        while let Some(result) = stream.next().await {
-            match result {
-                Ok(entry) => {
-                    let ffi_entry = convert_entry_to_ffi(&entry);
-                    let entry_ptr = Box::into_raw(Box::new(ffi_entry));
-                    (callback.on_entry)(callback.userdata, entry_ptr);
-                }
-                Err(e) => {
-                    return Err(e);
-                }
-            }
+    let _operation = trace::begin("iroh_profile_stats");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let profile = match unsafe { required_str(profile, "profile") } {
+        Ok(profile) => profile,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
         }
-        Ok::<_, anyhow::Error>(())
-    }) {
-        Ok(()) => {
-            (callback.on_complete)(callback.userdata);
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.profile_stats(&profile) {
+        Ok(ProfileStats {
+            tags,
+            blobs,
+            total_size,
+            unique_size,
+        }) => {
+            let stats = IrohProfileStats {
+                tags,
+                blobs,
+                total_size,
+                unique_size,
+            };
+            (callback.on_success)(callback.userdata, stats);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Delete an entry (creates a tombstone).
+/// Delete content added to a profile that no tag references anymore.
+///
+/// Content still used by other profiles, other tags, documents or
+/// temporary tags is kept. Returns after the store's next garbage
+/// collection run, within about a second.
 ///
 /// # Safety
-/// - `doc_handle` must be a valid document handle
-/// - `key.data` must point to valid memory for `key.len` bytes
+/// - `handle` must be a valid node handle
+/// - `profile` must be a valid null-terminated UTF-8 string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_del(
-    doc_handle: *const IrohDocHandle,
-    author_secret: IrohAuthorSecret,
-    key: IrohBytes,
-    callback: IrohDocDelCallback,
+pub unsafe extern "C" fn iroh_profile_gc(
+    handle: *const IrohNodeHandle,
+    profile: *const c_char,
+    callback: IrohReclaimCallback,
 ) {
-    if doc_handle.is_null() {
-        let error = CString::new("doc_handle cannot be null").unwrap();
+    let _operation = trace::begin("iroh_profile_gc");
+    unsafe { reclaim_profile(handle, profile, false, callback) };
+}
+
+/// Delete all of a profile's tags and the content only they referenced.
+///
+/// Use this when a user account is removed from the device. Like
+/// `iroh_profile_gc`, content still used elsewhere is kept.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `profile` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_profile_wipe(
+    handle: *const IrohNodeHandle,
+    profile: *const c_char,
+    callback: IrohReclaimCallback,
+) {
+    let _operation = trace::begin("iroh_profile_wipe");
+    unsafe { reclaim_profile(handle, profile, true, callback) };
+}
+
+/// Shared body of the reclaiming calls; `wipe` deletes the profile's tags first.
+unsafe fn reclaim_profile(
+    handle: *const IrohNodeHandle,
+    profile: *const c_char,
+    wipe: bool,
+    callback: IrohReclaimCallback,
+) {
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
-    let node = unsafe { &*(wrapper.node_handle as *const IrohNode) };
+    let profile = match unsafe { required_str(profile, "profile") } {
+        Ok(profile) => profile,
+        Err(error) => {
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
 
-    let author = Author::from_bytes(&author_secret.bytes);
-    let author_id = author.id();
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-    let key_bytes = if key.data.is_null() || key.len == 0 {
-        Vec::new()
+    let result = if wipe {
+        node.profile_wipe(&profile)
     } else {
-        unsafe { std::slice::from_raw_parts(key.data, key.len).to_vec() }
+        node.profile_gc(&profile)
     };
-
-    match node
-        .runtime()
-        .block_on(wrapper.doc.del(author_id, key_bytes))
-    {
-        Ok(count) => {
-            (callback.on_success)(callback.userdata, count as u64);
+    match result {
+        Ok(Reclaimed { blobs, size }) => {
+            (callback.on_success)(callback.userdata, IrohReclaimed { blobs, size });
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Read content bytes by hash.
+// ============================================================================
+// Temporary File Operations
+// ============================================================================
+
+/// Export a local blob to a crate-managed temporary file.
 ///
-/// This fetches the actual content data for an entry (entries only contain the hash).
+/// The file is created under the node's temp dir (see `IrohNodeConfig`)
+/// and deleted when the returned handle is released. Use this to hand
+/// content to APIs that need a file URL, such as QuickLook.
 ///
 /// # Safety
 /// - `handle` must be a valid node handle
-/// - `content_hash` must be a valid null-terminated UTF-8 hex string
+/// - `hash_str` must be a valid null-terminated hex hash string
+/// - `filename` must be a valid null-terminated UTF-8 string or null (uses the hash)
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_doc_read_content(
+pub unsafe extern "C" fn iroh_blob_export_temp(
     handle: *const IrohNodeHandle,
-    content_hash: *const c_char,
-    callback: IrohGetCallback,
+    hash_str: *const c_char,
+    filename: *const c_char,
+    callback: IrohTempFileCallback,
 ) {
+    let _operation = trace::begin("iroh_blob_export_temp");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    if content_hash.is_null() {
-        let error = CString::new("content_hash cannot be null").unwrap();
+    if hash_str.is_null() {
+        let error = CString::new("hash_str cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let hash_str = match unsafe { CStr::from_ptr(content_hash) }.to_str() {
-        Ok(s) => s,
+    let hash_string = match unsafe { CStr::from_ptr(hash_str) }.to_str() {
+        Ok(s) => s.to_string(),
         Err(e) => {
             let error = CString::new(format!("Invalid hash UTF-8: {}", e)).unwrap();
             (callback.on_failure)(callback.userdata, error.into_raw());
@@ -1467,7 +10144,7 @@ pub unsafe extern "C" fn iroh_doc_read_content(
         }
     };
 
-    let hash: iroh_blobs::Hash = match hash_str.parse() {
+    let hash: Hash = match hash_string.parse() {
         Ok(h) => h,
         Err(e) => {
             let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
@@ -1476,563 +10153,1143 @@ pub unsafe extern "C" fn iroh_doc_read_content(
         }
     };
 
-    let node = unsafe { &*(handle as *const IrohNode) };
+    let filename = match unsafe { optional_str(filename) } {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid filename UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
 
-    match node.runtime().block_on(node.store().get_bytes(hash)) {
-        Ok(bytes) => {
-            let mut vec = bytes.to_vec();
-            let owned = IrohOwnedBytes {
-                data: vec.as_mut_ptr(),
-                len: vec.len(),
-                capacity: vec.capacity(),
-            };
-            std::mem::forget(vec);
-            (callback.on_success)(callback.userdata, owned);
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.export_temp_file(hash, filename.as_deref()) {
+        Ok(file) => {
+            let path = CString::new(file.path().to_string_lossy().into_owned())
+                .unwrap()
+                .into_raw();
+            let file_ptr = Box::into_raw(Box::new(file)) as *mut IrohTempFileHandle;
+            (callback.on_success)(callback.userdata, file_ptr, path);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Get a share ticket for a document.
+/// Release a temporary file, deleting it from disk.
 ///
 /// # Safety
-/// - `doc_handle` must be a valid document handle
+/// - `file` must be a handle returned by `iroh_blob_export_temp`, or null
+/// - `file` must not be used after this call
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_temp_file_release(file: *mut IrohTempFileHandle) {
+    if file.is_null() {
+        return;
+    }
+
+    unsafe {
+        drop(Box::from_raw(file as *mut TempFile));
+    }
+}
+
+// ============================================================================
+// Store Operations
+// ============================================================================
+
+/// Copy local blobs into another store directory.
+///
+/// The destination is opened for the duration of the call, so it must not
+/// be in use by another node. Every chunk is verified against its hash on
+/// import, and each copied blob is tagged in the destination so it
+/// survives garbage collection there.
+///
+/// # Safety
+/// - `src_handle` must be a valid node handle
+/// - `dst_path` must be a valid null-terminated UTF-8 string
+/// - `hashes` must point to `hashes_len` valid null-terminated hex hash strings
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_share(
-    doc_handle: *const IrohDocHandle,
-    mode: IrohDocShareMode,
-    callback: IrohCallback,
+pub unsafe extern "C" fn iroh_store_copy(
+    src_handle: *const IrohNodeHandle,
+    dst_path: *const c_char,
+    hashes: *const *const c_char,
+    hashes_len: usize,
+    callback: IrohStoreCopyCallback,
 ) {
-    if doc_handle.is_null() {
-        let error = CString::new("doc_handle cannot be null").unwrap();
+    let _operation = trace::begin("iroh_store_copy");
+    if src_handle.is_null() {
+        let error = CString::new("src_handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
-    let node = unsafe { &*(wrapper.node_handle as *const IrohNode) };
+    if dst_path.is_null() {
+        let error = CString::new("dst_path cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
 
-    let share_mode = match mode {
-        IrohDocShareMode::Read => ShareMode::Read,
-        IrohDocShareMode::Write => ShareMode::Write,
+    if hashes.is_null() && hashes_len > 0 {
+        let error = CString::new("hashes cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let dst = match unsafe { CStr::from_ptr(dst_path) }.to_str() {
+        Ok(s) => PathBuf::from(s),
+        Err(e) => {
+            let error = CString::new(format!("Invalid dst_path UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
     };
 
-    match node.runtime().block_on(
-        wrapper
-            .doc
-            .share(share_mode, AddrInfoOptions::RelayAndAddresses),
-    ) {
-        Ok(ticket) => {
-            let ticket_str = CString::new(ticket.to_string()).unwrap().into_raw();
-            (callback.on_success)(callback.userdata, ticket_str);
+    let hash_ptrs = if hashes_len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(hashes, hashes_len) }
+    };
+    let mut parsed = Vec::with_capacity(hashes_len);
+    for &ptr in hash_ptrs {
+        if ptr.is_null() {
+            let error = CString::new("hash cannot be null").unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+        let hash = unsafe { CStr::from_ptr(ptr) }
+            .to_str()
+            .map_err(anyhow::Error::from)
+            .and_then(|s| s.parse::<Hash>().map_err(anyhow::Error::from));
+        match hash {
+            Ok(h) => parsed.push(h),
+            Err(e) => {
+                let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
+                (callback.on_failure)(callback.userdata, error.into_raw());
+                return;
+            }
+        }
+    }
+
+    let Some(node) = enter_node(src_handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.copy_to_store(&dst, &parsed) {
+        Ok(bytes_copied) => {
+            (callback.on_success)(callback.userdata, bytes_copied);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Close a document and free its resources.
+// ============================================================================
+// Chunked File Operations
+// ============================================================================
+
+/// Import a file split into content-defined chunks and get a shareable ticket.
+///
+/// The ticket has the `HashSeq` format. Chunks already stored from an
+/// earlier version of the file are reused, so peers holding that version
+/// only download the changed chunks with `iroh_get_chunked_file`.
 ///
 /// # Safety
-/// - `doc_handle` must be a valid document handle returned by `iroh_doc_create` or `iroh_doc_join`
-/// - `doc_handle` must not be used after this call
+/// - `handle` must be a valid node handle
+/// - `path` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[cfg(feature = "chunking")]
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_close(doc_handle: *mut IrohDocHandle) {
-    if doc_handle.is_null() {
+pub unsafe extern "C" fn iroh_put_chunked_file(
+    handle: *const IrohNodeHandle,
+    path: *const c_char,
+    callback: IrohChunkedPutCallback,
+) {
+    let _operation = trace::begin("iroh_put_chunked_file");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    unsafe {
-        // Drop the wrapper, which will drop the Doc
-        drop(Box::from_raw(doc_handle as *mut DocWrapper));
+    if path.is_null() {
+        let error = CString::new("path cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let path = match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(s) => PathBuf::from(s),
+        Err(e) => {
+            let error = CString::new(format!("Invalid path UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
+
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
+
+    match node.put_chunked_file(&path) {
+        Ok((ticket, chunk_stats)) => {
+            stats::bytes_in(chunk_stats.size as usize);
+            let ticket_cstr = CString::new(ticket).unwrap();
+            (callback.on_success)(
+                callback.userdata,
+                ticket_cstr.into_raw(),
+                convert_chunk_stats(chunk_stats),
+            );
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
     }
 }
 
-/// Free a document entry.
+/// Download a chunked file ticket and write the file to `dst_path`.
+///
+/// Only chunks missing from the local store are downloaded. The file is
+/// written next to `dst_path` and moved into place once complete.
 ///
 /// # Safety
-/// - `entry` must be a valid entry pointer returned by document operations
-/// - `entry` must not be used after this call
+/// - `handle` must be a valid node handle
+/// - `ticket` and `dst_path` must be valid null-terminated UTF-8 strings
+/// - `callback` must have valid function pointers
+#[cfg(feature = "chunking")]
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_doc_entry_free(entry: *mut IrohDocEntry) {
-    if entry.is_null() {
+pub unsafe extern "C" fn iroh_get_chunked_file(
+    handle: *const IrohNodeHandle,
+    ticket: *const c_char,
+    dst_path: *const c_char,
+    callback: IrohChunkedGetCallback,
+) {
+    let _operation = trace::begin("iroh_get_chunked_file");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    unsafe {
-        let entry = Box::from_raw(entry);
-        // Free the key bytes
-        if !entry.key.data.is_null() {
-            drop(Vec::from_raw_parts(
-                entry.key.data,
-                entry.key.len,
-                entry.key.capacity,
-            ));
-        }
-        // Free the content hash string
-        if !entry.content_hash.is_null() {
-            drop(CString::from_raw(entry.content_hash));
-        }
-        // The rest is stack-allocated and drops automatically
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     }
-}
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
+    if dst_path.is_null() {
+        let error = CString::new("dst_path cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
 
-/// Convert an iroh_docs Entry to FFI representation.
-fn convert_entry_to_ffi(entry: &iroh_docs::Entry) -> IrohDocEntry {
-    // Get author ID bytes
-    let author_id = IrohAuthorId {
-        bytes: entry.author().to_bytes(),
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
     };
 
-    // Get key bytes (owned copy)
-    let key_vec = entry.key().to_vec();
-    let mut key_vec = std::mem::ManuallyDrop::new(key_vec);
-    let key = IrohOwnedBytes {
-        data: key_vec.as_mut_ptr(),
-        len: key_vec.len(),
-        capacity: key_vec.capacity(),
+    let dst = match unsafe { CStr::from_ptr(dst_path) }.to_str() {
+        Ok(s) => PathBuf::from(s),
+        Err(e) => {
+            let error = CString::new(format!("Invalid dst_path UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
     };
 
-    // Get content hash as string
-    let hash_str = CString::new(entry.content_hash().to_string())
-        .unwrap()
-        .into_raw();
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-    IrohDocEntry {
-        author_id,
-        key,
-        content_hash: hash_str,
-        content_size: entry.content_len(),
-        timestamp: entry.timestamp(),
+    match node.get_chunked_file(&ticket_str, &dst) {
+        Ok(chunk_stats) => {
+            stats::bytes_out(chunk_stats.size as usize);
+            (callback.on_success)(callback.userdata, convert_chunk_stats(chunk_stats));
+        }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
     }
 }
 
 // ============================================================================
-// Subscription Operations
+// Blob Metadata Operations
 // ============================================================================
 
-/// Subscribe to document events.
+/// Add bytes with a metadata record and get a shareable ticket.
 ///
-/// Returns a subscription handle that can be used to cancel the subscription.
-/// Events are delivered via the callback until the subscription is cancelled
-/// or the stream ends.
+/// The content and metadata are wrapped in a collection, so the returned
+/// ticket has the `HashSeq` format. Pass null for absent string fields and
+/// 0 for `created_at` to use the current time. With `Zstd` compression the
+/// content is stored compressed if that makes it smaller, and
+/// `iroh_get_with_metadata` returns it decompressed.
 ///
 /// # Safety
-/// - `doc_handle` must be a valid document handle
-/// - `callback` must have valid function pointers that remain valid for the
-///   duration of the subscription
+/// - `handle` must be a valid node handle
+/// - `bytes.data` must point to valid memory for `bytes.len` bytes
+/// - `filename` and `content_type` must be valid null-terminated UTF-8 strings or null
+/// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_subscribe(
-    doc_handle: *const IrohDocHandle,
-    callback: IrohDocSubscribeCallback,
-) -> *mut IrohSubscriptionHandle {
-    if doc_handle.is_null() {
-        let error = CString::new("doc_handle cannot be null").unwrap();
+pub unsafe extern "C" fn iroh_put_with_metadata(
+    handle: *const IrohNodeHandle,
+    bytes: IrohBytes,
+    filename: *const c_char,
+    content_type: *const c_char,
+    created_at: u64,
+    compression: IrohCompression,
+    callback: IrohCallback,
+) {
+    let _operation = trace::begin("iroh_put_with_metadata");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
-        return std::ptr::null_mut();
+        return;
     }
 
-    let wrapper = unsafe { &*(doc_handle as *const DocWrapper) };
-    let node = unsafe { &*(wrapper.node_handle as *const IrohNode) };
-
-    // Create cancellation channel
-    let (cancel_tx, mut cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    let filename = match unsafe { optional_str(filename) } {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid filename UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
 
-    // Clone what we need for the spawned task
-    let doc = wrapper.doc.clone();
-    // Convert userdata to usize for Send safety (will convert back in async block)
-    let userdata_addr = callback.userdata as usize;
-    let on_event = callback.on_event;
-    let on_complete = callback.on_complete;
-    let on_failure = callback.on_failure;
+    let content_type = match unsafe { optional_str(content_type) } {
+        Ok(s) => s,
+        Err(e) => {
+            let error = CString::new(format!("Invalid content_type UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
+        }
+    };
 
-    // Helper macro to convert usize back to pointer at point of use
-    macro_rules! ud {
-        ($addr:expr) => {
-            $addr as *mut c_void
-        };
-    }
+    let data = if bytes.data.is_null() || bytes.len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(bytes.data, bytes.len).to_vec() }
+    };
 
-    // Spawn the subscription task on the node's runtime
-    node.runtime().spawn(async move {
-        use futures_lite::StreamExt;
-        use std::pin::pin;
+    let metadata = BlobMetadata {
+        filename,
+        content_type,
+        created_at,
+        compression: compression.into(),
+    };
 
-        // Get the subscription stream
-        let stream = match doc.subscribe().await {
-            Ok(s) => s,
-            Err(e) => {
-                let error = CString::new(format!("{:#}", e)).unwrap();
-                (on_failure)(ud!(userdata_addr), error.into_raw());
-                return;
-            }
-        };
-        let mut stream = pin!(stream);
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-        loop {
-            tokio::select! {
-                // Check for cancellation
-                _ = &mut cancel_rx => {
-                    (on_complete)(ud!(userdata_addr));
-                    break;
-                }
-                // Check for next event
-                event = stream.next() => {
-                    match event {
-                        Some(Ok(live_event)) => {
-                            let ffi_event = convert_live_event_to_ffi(&live_event);
-                            (on_event)(ud!(userdata_addr), ffi_event);
-                        }
-                        Some(Err(e)) => {
-                            let error = CString::new(format!("{:#}", e)).unwrap();
-                            (on_failure)(ud!(userdata_addr), error.into_raw());
-                            break;
-                        }
-                        None => {
-                            // Stream ended normally
-                            (on_complete)(ud!(userdata_addr));
-                            break;
-                        }
-                    }
-                }
-            }
+    match node.put_with_metadata(&data, &metadata) {
+        Ok(ticket) => {
+            stats::bytes_in(data.len());
+            let ticket_cstr = CString::new(ticket).unwrap();
+            (callback.on_success)(callback.userdata, ticket_cstr.into_raw());
         }
-    });
-
-    // Create subscription handle
-    let sub_wrapper = Box::new(SubscriptionWrapper {
-        cancel_tx: Some(cancel_tx),
-    });
-    Box::into_raw(sub_wrapper) as *mut IrohSubscriptionHandle
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
+        }
+    }
 }
 
-/// Cancel an active subscription.
+/// Download bytes from a ticket along with its metadata record.
 ///
-/// After calling this, no more events will be delivered and on_complete will be called.
+/// Raw tickets succeed with a null metadata pointer.
 ///
 /// # Safety
-/// - `handle` must be a valid subscription handle returned by `iroh_doc_subscribe`
-/// - `handle` must not be used after this call
+/// - `handle` must be a valid node handle
+/// - `ticket` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub extern "C" fn iroh_subscription_cancel(handle: *mut IrohSubscriptionHandle) {
+pub unsafe extern "C" fn iroh_get_with_metadata(
+    handle: *const IrohNodeHandle,
+    ticket: *const c_char,
+    callback: IrohGetWithMetadataCallback,
+) {
+    let _operation = trace::begin("iroh_get_with_metadata");
     if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    unsafe {
-        let mut wrapper = Box::from_raw(handle as *mut SubscriptionWrapper);
-        // Send cancellation signal (if not already sent)
-        if let Some(tx) = wrapper.cancel_tx.take() {
-            let _ = tx.send(());
-        }
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     }
-}
 
-/// Free a document event.
-///
-/// # Safety
-/// - `event` fields that are non-null must be valid pointers
-#[unsafe(no_mangle)]
-pub extern "C" fn iroh_doc_event_free(event: IrohDocEvent) {
-    unsafe {
-        // Free entry if present
-        if !event.entry.is_null() {
-            iroh_doc_entry_free(event.entry as *mut IrohDocEntry);
-        }
-        // Free peer_id if present
-        if !event.peer_id.is_null() {
-            drop(CString::from_raw(event.peer_id as *mut c_char));
-        }
-        // Free content_hash if present
-        if !event.content_hash.is_null() {
-            drop(CString::from_raw(event.content_hash as *mut c_char));
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
         }
-    }
-}
+    };
 
-/// Convert a LiveEvent to FFI representation.
-fn convert_live_event_to_ffi(event: &iroh_docs::engine::LiveEvent) -> IrohDocEvent {
-    use iroh_docs::engine::LiveEvent;
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-    match event {
-        LiveEvent::InsertLocal { entry } => {
-            let ffi_entry = convert_entry_to_ffi(entry);
-            let entry_ptr = Box::into_raw(Box::new(ffi_entry));
-            IrohDocEvent {
-                event_type: IrohDocEventType::InsertLocal,
-                entry: entry_ptr,
-                peer_id: std::ptr::null(),
-                content_hash: std::ptr::null(),
-            }
-        }
-        LiveEvent::InsertRemote { from, entry, .. } => {
-            let ffi_entry = convert_entry_to_ffi(entry);
-            let entry_ptr = Box::into_raw(Box::new(ffi_entry));
-            let peer_id = CString::new(from.to_string()).unwrap().into_raw();
-            IrohDocEvent {
-                event_type: IrohDocEventType::InsertRemote,
-                entry: entry_ptr,
-                peer_id,
-                content_hash: std::ptr::null(),
-            }
-        }
-        LiveEvent::ContentReady { hash } => {
-            let hash_str = CString::new(hash.to_string()).unwrap().into_raw();
-            IrohDocEvent {
-                event_type: IrohDocEventType::ContentReady,
-                entry: std::ptr::null(),
-                peer_id: std::ptr::null(),
-                content_hash: hash_str,
-            }
-        }
-        LiveEvent::PendingContentReady => IrohDocEvent {
-            event_type: IrohDocEventType::PendingContentReady,
-            entry: std::ptr::null(),
-            peer_id: std::ptr::null(),
-            content_hash: std::ptr::null(),
-        },
-        LiveEvent::NeighborUp(peer) => {
-            let peer_id = CString::new(peer.to_string()).unwrap().into_raw();
-            IrohDocEvent {
-                event_type: IrohDocEventType::NeighborUp,
-                entry: std::ptr::null(),
-                peer_id,
-                content_hash: std::ptr::null(),
-            }
-        }
-        LiveEvent::NeighborDown(peer) => {
-            let peer_id = CString::new(peer.to_string()).unwrap().into_raw();
-            IrohDocEvent {
-                event_type: IrohDocEventType::NeighborDown,
-                entry: std::ptr::null(),
-                peer_id,
-                content_hash: std::ptr::null(),
-            }
+    match node.get_with_metadata(&ticket_str) {
+        Ok((bytes, metadata)) => {
+            let mut vec = std::mem::ManuallyDrop::new(bytes);
+            let owned = IrohOwnedBytes {
+                data: vec.as_mut_ptr(),
+                len: vec.len(),
+                capacity: vec.capacity(),
+            };
+            let metadata_ptr = metadata.map_or(std::ptr::null_mut(), convert_metadata_to_ffi);
+            stats::bytes_out(owned.len);
+            (callback.on_success)(callback.userdata, owned, metadata_ptr);
         }
-        LiveEvent::SyncFinished(sync_event) => {
-            let peer_id = CString::new(sync_event.peer.to_string())
-                .unwrap()
-                .into_raw();
-            IrohDocEvent {
-                event_type: IrohDocEventType::SyncFinished,
-                entry: std::ptr::null(),
-                peer_id,
-                content_hash: std::ptr::null(),
-            }
+        Err(e) => {
+            let error = trace::failure(&e);
+            (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-// ============================================================================
-// Blob Tag Operations
-// ============================================================================
-
-/// Tag (pin) a blob to prevent garbage collection.
+/// Fetch the metadata record for a ticket without downloading its content.
 ///
-/// Tagged blobs are protected from GC until the tag is removed.
-/// Use this after downloading content you want to keep.
+/// Succeeds with a null pointer for raw tickets and collections that have
+/// no metadata record.
 ///
 /// # Safety
 /// - `handle` must be a valid node handle
-/// - `tag_name` must be a valid null-terminated UTF-8 string
-/// - `hash_str` must be a valid null-terminated hex hash string
+/// - `ticket` must be a valid null-terminated UTF-8 string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_blob_tag_set(
+pub unsafe extern "C" fn iroh_ticket_metadata(
     handle: *const IrohNodeHandle,
-    tag_name: *const c_char,
-    hash_str: *const c_char,
-    format: IrohBlobFormat,
-    callback: IrohCloseCallback,
+    ticket: *const c_char,
+    callback: IrohBlobMetadataCallback,
 ) {
+    let _operation = trace::begin("iroh_ticket_metadata");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    if tag_name.is_null() {
-        let error = CString::new("tag_name cannot be null").unwrap();
-        (callback.on_failure)(callback.userdata, error.into_raw());
-        return;
-    }
-
-    if hash_str.is_null() {
-        let error = CString::new("hash_str cannot be null").unwrap();
+    if ticket.is_null() {
+        let error = CString::new("ticket cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    let tag_name_str = match unsafe { CStr::from_ptr(tag_name) }.to_str() {
+    let ticket_str = match unsafe { CStr::from_ptr(ticket) }.to_str() {
         Ok(s) => s.to_string(),
         Err(e) => {
-            let error = CString::new(format!("Invalid tag_name UTF-8: {}", e)).unwrap();
+            let error = CString::new(format!("Invalid ticket string: {}", e)).unwrap();
             (callback.on_failure)(callback.userdata, error.into_raw());
             return;
         }
     };
 
-    let hash_string = match unsafe { CStr::from_ptr(hash_str) }.to_str() {
-        Ok(s) => s.to_string(),
-        Err(e) => {
-            let error = CString::new(format!("Invalid hash UTF-8: {}", e)).unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
-        }
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
     };
 
-    let hash: Hash = match hash_string.parse() {
-        Ok(h) => h,
+    match node.ticket_metadata(&ticket_str) {
+        Ok(metadata) => {
+            let metadata_ptr = metadata.map_or(std::ptr::null_mut(), convert_metadata_to_ffi);
+            (callback.on_success)(callback.userdata, metadata_ptr);
+        }
         Err(e) => {
-            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
         }
+    }
+}
+
+/// Free a metadata record returned by Iroh functions.
+///
+/// # Safety
+/// - `metadata` must be a pointer returned by a metadata operation, or null
+/// - `metadata` must not be used after this call
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_blob_metadata_free(metadata: *mut IrohBlobMetadata) {
+    if metadata.is_null() {
+        return;
+    }
+
+    unsafe {
+        let metadata = Box::from_raw(metadata);
+        if !metadata.filename.is_null() {
+            drop(CString::from_raw(metadata.filename));
+        }
+        if !metadata.content_type.is_null() {
+            drop(CString::from_raw(metadata.content_type));
+        }
+    }
+}
+
+/// Read an optional C string, treating null as absent.
+unsafe fn optional_str(ptr: *const c_char) -> Result<Option<String>, std::str::Utf8Error> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(|s| Some(s.to_string()))
+}
+
+/// Read a C string that must be present, naming it `what` in errors.
+unsafe fn required_str(ptr: *const c_char, what: &str) -> Result<String, CString> {
+    match unsafe { optional_str(ptr) } {
+        Ok(Some(s)) => Ok(s),
+        Ok(None) => Err(CString::new(format!("{what} cannot be null")).unwrap()),
+        Err(e) => Err(CString::new(format!("Invalid {what} UTF-8: {e}")).unwrap()),
+    }
+}
+
+/// Read `len` C strings, naming them `what` in errors.
+#[cfg(feature = "docs")]
+unsafe fn str_array(
+    ptrs: *const *const c_char,
+    len: usize,
+    what: &str,
+) -> Result<Vec<String>, CString> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    if ptrs.is_null() {
+        return Err(CString::new(format!("{what} list cannot be null")).unwrap());
+    }
+    let ptrs = unsafe { std::slice::from_raw_parts(ptrs, len) };
+    let mut strings = Vec::with_capacity(len);
+    for &ptr in ptrs {
+        strings.push(unsafe { required_str(ptr, what) }?);
+    }
+    Ok(strings)
+}
+
+/// Read a 32-byte key seed from `source`, or None if it has no fill function.
+fn entropy_seed(source: &IrohEntropySource) -> anyhow::Result<Option<[u8; 32]>> {
+    let Some(fill) = source.fill else {
+        return Ok(None);
     };
+    let mut seed = [0u8; 32];
+    if !fill(source.userdata, seed.as_mut_ptr(), seed.len()) {
+        anyhow::bail!("Entropy source failed to provide bytes");
+    }
+    // An all-zero seed almost certainly means the source wrote nothing
+    if seed == [0u8; 32] {
+        anyhow::bail!("Entropy source returned only zero bytes");
+    }
+    Ok(Some(seed))
+}
 
-    let blob_format = match format {
-        IrohBlobFormat::Raw => BlobFormat::Raw,
-        IrohBlobFormat::HashSeq => BlobFormat::HashSeq,
+/// Releases Swift userdata once no callback can use it.
+struct ReleaseGuard {
+    userdata: usize,
+    release: Option<extern "C" fn(userdata: *mut c_void)>,
+}
+
+impl Drop for ReleaseGuard {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            release(self.userdata as *mut c_void);
+        }
+    }
+}
+
+/// Adapt the provider's callbacks, or None if it has none.
+fn app_discovery(provider: &IrohDiscoveryProvider, callbacks: &Delivery) -> Option<AppDiscovery> {
+    let guard = Arc::new(ReleaseGuard {
+        userdata: provider.userdata as usize,
+        release: provider.release,
+    });
+    if provider.resolve.is_none() && provider.publish.is_none() {
+        return None;
+    }
+
+    let resolve = provider.resolve.map(|resolve_fn| {
+        let guard = guard.clone();
+        let callbacks = callbacks.clone();
+        Box::new(
+            move |peer: EndpointId, reply: oneshot::Sender<EndpointAddr>| {
+                let node_id = CString::new(peer.to_string()).unwrap();
+                let request = Box::into_raw(Box::new((peer, reply))) as *mut IrohDiscoveryRequest;
+                unsafe {
+                    callbacks.call(|| {
+                        resolve_fn(guard.userdata as *mut c_void, node_id.as_ptr(), request)
+                    })
+                };
+            },
+        ) as Box<Resolve>
+    });
+    let publish = provider.publish.map(|publish_fn| {
+        let guard = guard.clone();
+        let callbacks = callbacks.clone();
+        Box::new(move |addr: &EndpointAddr| {
+            let node_id = CString::new(addr.id.to_string()).unwrap();
+            let relay_url = addr
+                .relay_urls()
+                .next()
+                .map(|url| CString::new(url.to_string()).unwrap());
+            let direct: Vec<CString> = addr
+                .ip_addrs()
+                .map(|ip| CString::new(ip.to_string()).unwrap())
+                .collect();
+            let ptrs: Vec<*const c_char> = direct.iter().map(|s| s.as_ptr()).collect();
+            let ffi_addr = IrohNodeAddr {
+                node_id: node_id.as_ptr(),
+                relay_url: relay_url
+                    .as_ref()
+                    .map_or(std::ptr::null(), |url| url.as_ptr()),
+                direct_addrs: ptrs.as_ptr(),
+                direct_addrs_len: ptrs.len(),
+            };
+            unsafe { callbacks.call(|| publish_fn(guard.userdata as *mut c_void, ffi_addr)) };
+        }) as Box<Publish>
+    });
+    Some(AppDiscovery { resolve, publish })
+}
+
+/// Start exporting telemetry to the collector at `endpoint`.
+#[cfg(feature = "otel")]
+fn start_telemetry(endpoint: &str, app_id: Option<&str>) -> anyhow::Result<()> {
+    crate::telemetry::init(endpoint, app_id)
+}
+
+/// Telemetry export is compiled out without the `otel` feature.
+#[cfg(not(feature = "otel"))]
+fn start_telemetry(_endpoint: &str, _app_id: Option<&str>) -> anyhow::Result<()> {
+    anyhow::bail!("OTLP export requires building with the otel feature")
+}
+
+/// Inject `faults` into `node`.
+#[cfg(feature = "faults")]
+fn inject_faults(node: &IrohNode, faults: &IrohFaults) -> anyhow::Result<()> {
+    node.inject_faults(crate::faults::FaultConfig {
+        fail_puts: faults.fail_puts,
+        download_delay: Duration::from_millis(faults.download_delay_ms),
+        sync_drop_percent: faults.sync_drop_percent,
+    })
+}
+
+/// Fault injection is compiled out without the `faults` feature.
+#[cfg(not(feature = "faults"))]
+fn inject_faults(_node: &IrohNode, _faults: &IrohFaults) -> anyhow::Result<()> {
+    anyhow::bail!("Fault injection requires building with the faults feature")
+}
+
+/// Convert a node event and pass it to `deliver` while its strings are alive.
+fn deliver_node_event(event: NodeEvent, deliver: impl FnOnce(IrohNodeEvent)) {
+    let to_cstring = |s: String| CString::new(s).unwrap();
+    let mut relay_url = None;
+    let mut direct_addrs = Vec::new();
+    let mut hash = IrohHash { bytes: [0; 32] };
+    let mut error = None;
+    let mut peer_id = None;
+    // Only set by document events
+    #[cfg_attr(not(feature = "docs"), allow(unused_mut))]
+    let (mut namespace_id, mut removed) = (None, 0);
+    let mut operation_id = 0;
+    let mut operation_name = None;
+    let mut stage = None;
+    let mut elapsed_ms = 0;
+    // Only set by outbox events
+    #[cfg_attr(not(feature = "docs"), allow(unused_mut))]
+    let (mut item_id, mut key, mut author_id) = (0, Vec::new(), None);
+    // Only set by durable sync events
+    #[cfg_attr(not(feature = "docs"), allow(unused_mut))]
+    let (mut confirmed_through, mut backed_up) = (0, false);
+    // Only set by peer sync events
+    #[cfg_attr(not(feature = "docs"), allow(unused_mut))]
+    let (mut last_success, mut pending_entries, mut failure_streak) = (0, 0, 0);
+    let mut announced_tags = 0;
+    let mut network_condition = None;
+    let mut tag_name = None;
+    let (mut profile_id, mut gc_blobs, mut gc_size) = (None, 0, 0);
+
+    let event_type = match event {
+        NodeEvent::Connectivity {
+            relay_url: url,
+            direct_addrs: addrs,
+        } => {
+            relay_url = url.map(|url| to_cstring(url.to_string()));
+            direct_addrs = addrs
+                .iter()
+                .map(|addr| to_cstring(addr.to_string()))
+                .collect();
+            IrohNodeEventType::NodeConnectivityChanged
+        }
+        NodeEvent::DownloadStarted { hash: h, operation } => {
+            hash = h.into();
+            operation_id = operation.unwrap_or(0);
+            IrohNodeEventType::NodeDownloadStarted
+        }
+        NodeEvent::DownloadFinished {
+            hash: h,
+            operation,
+            error: e,
+        } => {
+            hash = h.into();
+            operation_id = operation.unwrap_or(0);
+            error = e.map(to_cstring);
+            IrohNodeEventType::NodeDownloadFinished
+        }
+        NodeEvent::FetchRejected { peer } => {
+            peer_id = Some(to_cstring(peer.to_string()));
+            IrohNodeEventType::NodeFetchRejected
+        }
+        NodeEvent::FetchLimited { peer } => {
+            peer_id = Some(to_cstring(peer.to_string()));
+            IrohNodeEventType::NodeFetchLimited
+        }
+        #[cfg(feature = "docs")]
+        NodeEvent::ExpiredPruned { removed: count } => {
+            removed = count;
+            IrohNodeEventType::NodeExpiredPruned
+        }
+        #[cfg(feature = "docs")]
+        NodeEvent::NeighborUp { namespace, peer } => {
+            namespace_id = Some(to_cstring(namespace.to_string()));
+            peer_id = Some(to_cstring(peer.to_string()));
+            IrohNodeEventType::NodeNeighborUp
+        }
+        #[cfg(feature = "docs")]
+        NodeEvent::NeighborDown { namespace, peer } => {
+            namespace_id = Some(to_cstring(namespace.to_string()));
+            peer_id = Some(to_cstring(peer.to_string()));
+            IrohNodeEventType::NodeNeighborDown
+        }
+        #[cfg(feature = "docs")]
+        NodeEvent::SyncFinished {
+            namespace,
+            peer,
+            error: e,
+        } => {
+            namespace_id = Some(to_cstring(namespace.to_string()));
+            peer_id = Some(to_cstring(peer.to_string()));
+            error = e.map(to_cstring);
+            IrohNodeEventType::NodeSyncFinished
+        }
+        #[cfg(feature = "docs")]
+        NodeEvent::PeerSync { namespace, stats } => {
+            namespace_id = Some(to_cstring(namespace.to_string()));
+            peer_id = Some(to_cstring(stats.peer.to_string()));
+            error = stats.last_error.map(to_cstring);
+            last_success = stats.last_success.unwrap_or(0);
+            pending_entries = stats.pending_entries;
+            failure_streak = stats.failure_streak;
+            IrohNodeEventType::NodePeerSyncChanged
+        }
+        #[cfg(feature = "docs")]
+        NodeEvent::Outbox {
+            id,
+            namespace,
+            key: k,
+            status,
+        } => {
+            item_id = id;
+            namespace_id = Some(to_cstring(namespace.to_string()));
+            key = k;
+            match status {
+                OutboxStatus::Queued => IrohNodeEventType::NodeOutboxQueued,
+                OutboxStatus::Delivered => IrohNodeEventType::NodeOutboxDelivered,
+                OutboxStatus::Superseded { by } => {
+                    author_id = Some(to_cstring(by.to_string()));
+                    IrohNodeEventType::NodeOutboxSuperseded
+                }
+                OutboxStatus::Failed(e) => {
+                    error = Some(to_cstring(e));
+                    IrohNodeEventType::NodeOutboxFailed
+                }
+            }
+        }
+        #[cfg(feature = "docs")]
+        NodeEvent::DurableSync {
+            namespace,
+            peer,
+            confirmed_through: through,
+            backed_up: complete,
+        } => {
+            namespace_id = Some(to_cstring(namespace.to_string()));
+            peer_id = Some(to_cstring(peer.to_string()));
+            confirmed_through = through;
+            backed_up = complete;
+            IrohNodeEventType::NodeDurableSync
+        }
+        NodeEvent::SlowOperation(slow) => {
+            operation_id = slow.id;
+            operation_name = Some(to_cstring(slow.name.to_string()));
+            stage = Some(to_cstring(slow.stage.to_string()));
+            elapsed_ms = slow.elapsed.as_millis() as u64;
+            IrohNodeEventType::NodeSlowOperation
+        }
+        NodeEvent::TagExpired { name, hash: h } => {
+            tag_name = Some(to_cstring(name));
+            hash = h.into();
+            IrohNodeEventType::NodeTagExpired
+        }
+        NodeEvent::ContentAnnounced { tags } => {
+            announced_tags = tags;
+            IrohNodeEventType::NodeContentAnnounced
+        }
+        NodeEvent::NetworkCondition(condition) => {
+            network_condition = Some(condition);
+            IrohNodeEventType::NodeNetworkConditionChanged
+        }
+        NodeEvent::GcStarted {
+            profile,
+            blobs,
+            size,
+        } => {
+            profile_id = Some(to_cstring(profile));
+            (gc_blobs, gc_size) = (blobs, size);
+            IrohNodeEventType::NodeGcStarted
+        }
+        NodeEvent::GcFinished {
+            profile,
+            blobs,
+            size,
+        } => {
+            profile_id = Some(to_cstring(profile));
+            (gc_blobs, gc_size) = (blobs, size);
+            IrohNodeEventType::NodeGcFinished
+        }
     };
 
-    let hash_and_format = HashAndFormat {
+    let as_ptr = |s: &Option<CString>| s.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+    let addr_ptrs: Vec<*const c_char> = direct_addrs.iter().map(|s| s.as_ptr()).collect();
+    deliver(IrohNodeEvent {
+        event_type,
+        relay_url: as_ptr(&relay_url),
+        direct_addrs: addr_ptrs.as_ptr(),
+        direct_addrs_len: addr_ptrs.len(),
         hash,
-        format: blob_format,
+        namespace_id: as_ptr(&namespace_id),
+        peer_id: as_ptr(&peer_id),
+        error: as_ptr(&error),
+        removed,
+        operation_id,
+        operation_name: as_ptr(&operation_name),
+        stage: as_ptr(&stage),
+        elapsed_ms,
+        item_id,
+        key: IrohBytes {
+            data: key.as_ptr(),
+            len: key.len(),
+        },
+        author_id: as_ptr(&author_id),
+        confirmed_through,
+        backed_up,
+        announced_tags,
+        network_condition: network_condition.into(),
+        tag_name: as_ptr(&tag_name),
+        last_success,
+        pending_entries,
+        failure_streak,
+        profile_id: as_ptr(&profile_id),
+        gc_blobs,
+        gc_size,
+    });
+}
+
+/// Adapt the storage alert's callback, or None if it has none.
+fn storage_alert(alert: &IrohStorageAlert, callbacks: &Delivery) -> Option<StorageAlert> {
+    let guard = ReleaseGuard {
+        userdata: alert.userdata as usize,
+        release: alert.release,
+    };
+    let on_threshold = alert.on_threshold?;
+    let callbacks = callbacks.clone();
+    Some(StorageAlert {
+        threshold: alert.threshold_bytes,
+        interval: match alert.check_interval_ms {
+            0 => DEFAULT_STORAGE_CHECK_INTERVAL,
+            ms => Duration::from_millis(ms),
+        },
+        on_threshold: Box::new(move |exceeded, size| {
+            unsafe {
+                callbacks.call(|| on_threshold(guard.userdata as *mut c_void, exceeded, size))
+            };
+        }),
+    })
+}
+
+/// Adapt the content announcer's configuration, or None if it is disabled.
+fn content_announcer(
+    config: &IrohContentAnnouncer,
+    callbacks: &Delivery,
+) -> Option<ContentAnnouncer> {
+    let guard = ReleaseGuard {
+        userdata: config.userdata as usize,
+        release: config.release,
+    };
+    if config.interval_ms == 0 {
+        return None;
+    }
+    let tag_prefix = if config.tag_prefix.is_null() {
+        Vec::new()
+    } else {
+        unsafe { CStr::from_ptr(config.tag_prefix) }
+            .to_bytes()
+            .to_vec()
     };
+    let callbacks = callbacks.clone();
+    let on_announce = config.on_announce.map(|on_announce| {
+        Box::new(move |tag: &str, ticket: &BlobTicket| {
+            // Tag names are arbitrary bytes; drop interior nulls rather than fail
+            let tag = CString::new(tag.replace('\0', "")).unwrap();
+            let ticket = CString::new(ticket.to_string()).unwrap();
+            unsafe {
+                callbacks.call(|| {
+                    on_announce(guard.userdata as *mut c_void, tag.as_ptr(), ticket.as_ptr())
+                })
+            };
+        }) as Box<OnAnnounce>
+    });
+    Some(ContentAnnouncer {
+        interval: Duration::from_millis(config.interval_ms),
+        tag_prefix,
+        on_announce,
+    })
+}
 
-    let node = unsafe { &*(handle as *const IrohNode) };
+/// Adapt the watchdog's configuration, or None if it is disabled.
+fn watchdog(config: &IrohSlowOperationWatchdog, callbacks: &Delivery) -> Option<Watchdog> {
+    let guard = ReleaseGuard {
+        userdata: config.userdata as usize,
+        release: config.release,
+    };
+    if config.threshold_ms == 0 {
+        return None;
+    }
+    let callbacks = callbacks.clone();
+    let on_slow = config.on_slow.map(|on_slow| {
+        Box::new(move |slow: &SlowOperation| {
+            let name = CString::new(slow.name).unwrap();
+            let stage = CString::new(slow.stage).unwrap();
+            let slow = IrohSlowOperation {
+                operation_id: slow.id,
+                name: name.as_ptr(),
+                stage: stage.as_ptr(),
+                elapsed_ms: slow.elapsed.as_millis() as u64,
+            };
+            unsafe { callbacks.call(|| on_slow(guard.userdata as *mut c_void, slow)) };
+        }) as Box<OnSlow>
+    });
+    Some(Watchdog {
+        threshold: Duration::from_millis(config.threshold_ms),
+        emit_events: config.emit_events,
+        on_slow,
+    })
+}
 
-    // Use the store's tags API (FsStore derefs to Store which has tags())
-    match node
-        .runtime()
-        .block_on(node.store().tags().set(tag_name_str, hash_and_format))
-    {
-        Ok(()) => {
-            (callback.on_complete)(callback.userdata);
+/// Convert chunk stats to their FFI representation.
+#[cfg(feature = "chunking")]
+fn convert_chunk_stats(stats: ChunkStats) -> IrohChunkStats {
+    IrohChunkStats {
+        size: stats.size,
+        chunks: stats.chunks,
+        new_chunks: stats.new_chunks,
+    }
+}
+
+/// Convert a metadata record to a heap-allocated FFI representation.
+fn convert_metadata_to_ffi(metadata: BlobMetadata) -> *mut IrohBlobMetadata {
+    let to_raw = |s: Option<String>| {
+        s.and_then(|s| CString::new(s).ok())
+            .map_or(std::ptr::null_mut(), CString::into_raw)
+    };
+    Box::into_raw(Box::new(IrohBlobMetadata {
+        filename: to_raw(metadata.filename),
+        content_type: to_raw(metadata.content_type),
+        created_at: metadata.created_at,
+        compression: metadata.compression.into(),
+    }))
+}
+
+// ============================================================================
+// Background Operations
+// ============================================================================
+
+/// Report the pending work recorded in the store at `storage_path`.
+///
+/// Reads the record a node keeps next to its store of document writes no
+/// peer has synced and downloads that have not finished. No store is opened
+/// and no endpoint is bound, so a silent push handler can call this before
+/// deciding whether to start a node at all. The record is updated a moment
+/// after changes, so a node that was killed may have lost its latest ones.
+///
+/// # Safety
+/// - `storage_path` must be a valid null-terminated UTF-8 string
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn iroh_pending_work(
+    storage_path: *const c_char,
+    callback: IrohPendingWorkCallback,
+) {
+    let _operation = trace::begin("iroh_pending_work");
+    if storage_path.is_null() {
+        let error = CString::new("storage_path cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
+
+    let path = match unsafe { CStr::from_ptr(storage_path) }.to_str() {
+        Ok(s) => PathBuf::from(s),
+        Err(e) => {
+            let error = CString::new(format!("Invalid storage_path UTF-8: {}", e)).unwrap();
+            (callback.on_failure)(callback.userdata, error.into_raw());
+            return;
         }
+    };
+
+    match pending::read(&path) {
+        Ok(work) => (callback.on_success)(callback.userdata, work.into()),
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
 }
 
-/// Create a shareable ticket for an existing local blob.
+/// Report the pending work of a running node.
 ///
-/// The ticket points to this node as the provider.
-/// Use this to "mint" a bootstrap ticket after downloading content.
+/// Reads counters the node keeps up to date, so it never scans the stores
+/// or dials peers. Use `iroh_pending_work` when no node is running.
 ///
 /// # Safety
 /// - `handle` must be a valid node handle
-/// - `hash_str` must be a valid null-terminated hex hash string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_blob_ticket_create(
+pub extern "C" fn iroh_node_pending_work(
     handle: *const IrohNodeHandle,
-    hash_str: *const c_char,
-    format: IrohBlobFormat,
-    callback: IrohCallback,
+    callback: IrohPendingWorkCallback,
 ) {
+    let _operation = trace::begin("iroh_node_pending_work");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    if hash_str.is_null() {
-        let error = CString::new("hash_str cannot be null").unwrap();
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
-    }
-
-    let hash_string = match unsafe { CStr::from_ptr(hash_str) }.to_str() {
-        Ok(s) => s.to_string(),
-        Err(e) => {
-            let error = CString::new(format!("Invalid hash UTF-8: {}", e)).unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
-        }
-    };
-
-    let hash: Hash = match hash_string.parse() {
-        Ok(h) => h,
-        Err(e) => {
-            let error = CString::new(format!("Invalid hash: {}", e)).unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
-        }
     };
 
-    let blob_format = match format {
-        IrohBlobFormat::Raw => BlobFormat::Raw,
-        IrohBlobFormat::HashSeq => BlobFormat::HashSeq,
-    };
+    (callback.on_success)(callback.userdata, node.pending_work().into());
+}
 
-    let node = unsafe { &*(handle as *const IrohNode) };
+/// Report what the node is holding on to: the size of the store's
+/// databases, live runtime tasks, buffered events and callbacks, and open
+/// peer connections.
+///
+/// Only reads counters and file sizes, so it can be called whenever the
+/// app sees a memory spike to tell whether the P2P layer is behind it.
+///
+/// # Safety
+/// - `handle` must be a valid node handle
+/// - `callback` must have valid function pointers
+#[unsafe(no_mangle)]
+pub extern "C" fn iroh_node_memory_report(
+    handle: *const IrohNodeHandle,
+    callback: IrohMemoryReportCallback,
+) {
+    let _operation = trace::begin("iroh_node_memory_report");
+    if handle.is_null() {
+        let error = CString::new("handle cannot be null").unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    }
 
-    // Get the node's address and create a ticket
-    let addr = node.endpoint().addr();
-    let ticket = BlobTicket::new(addr, hash, blob_format);
-    let ticket_str = CString::new(ticket.to_string()).unwrap().into_raw();
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
+        (callback.on_failure)(callback.userdata, error.into_raw());
+        return;
+    };
 
-    (callback.on_success)(callback.userdata, ticket_str);
+    let report = node.memory_report();
+    let ffi_report = IrohMemoryReport {
+        store_disk_bytes: report.store_disk_bytes,
+        runtime_tasks: report.runtime_tasks,
+        queued_events: report.queued_events,
+        queued_callbacks: report.queued_callbacks,
+        open_connections: report.open_connections,
+    };
+    (callback.on_success)(callback.userdata, ffi_report);
 }
 
-/// Remove a tag (unpin) from a blob, allowing garbage collection.
+/// Run sync and download work for at most `budget_ms` milliseconds.
+///
+/// Designed for short background windows (e.g., `BGAppRefreshTask`):
+/// unfinished downloads are resumed, docs are synced with their known peers
+/// and missing content is fetched until the budget runs out, after which any
+/// sync started by this call is stopped. The docs store and the pending work
+/// record are flushed before the callback runs; `completed` reports whether
+/// everything finished in time and no pending work is left.
 ///
 /// # Safety
 /// - `handle` must be a valid node handle
-/// - `tag_name` must be a valid null-terminated UTF-8 string
 /// - `callback` must have valid function pointers
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn iroh_blob_tag_delete(
+pub extern "C" fn iroh_node_sync_step(
     handle: *const IrohNodeHandle,
-    tag_name: *const c_char,
-    callback: IrohCloseCallback,
+    budget_ms: u64,
+    callback: IrohSyncStepCallback,
 ) {
+    let _operation = trace::begin("iroh_node_sync_step");
     if handle.is_null() {
         let error = CString::new("handle cannot be null").unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
     }
 
-    if tag_name.is_null() {
-        let error = CString::new("tag_name cannot be null").unwrap();
+    let Some(node) = enter_node(handle) else {
+        let error = CString::new(NODE_CLOSED_ERROR).unwrap();
         (callback.on_failure)(callback.userdata, error.into_raw());
         return;
-    }
-
-    let tag_name_str = match unsafe { CStr::from_ptr(tag_name) }.to_str() {
-        Ok(s) => s.to_string(),
-        Err(e) => {
-            let error = CString::new(format!("Invalid tag_name UTF-8: {}", e)).unwrap();
-            (callback.on_failure)(callback.userdata, error.into_raw());
-            return;
-        }
     };
 
-    let node = unsafe { &*(handle as *const IrohNode) };
-
-    // Use the store's tags API to delete the tag
-    match node
-        .runtime()
-        .block_on(node.store().tags().delete(tag_name_str))
-    {
-        Ok(_count) => {
-            (callback.on_complete)(callback.userdata);
+    match node.sync_step(Duration::from_millis(budget_ms)) {
+        Ok(report) => {
+            let ffi_report = IrohSyncStepReport {
+                completed: report.completed,
+                docs_synced: report.docs_synced,
+                entries_received: report.entries_received,
+                blobs_downloaded: report.blobs_downloaded,
+            };
+            (callback.on_success)(callback.userdata, ffi_report);
         }
         Err(e) => {
-            let error = CString::new(format!("{:#}", e)).unwrap();
+            let error = trace::failure(&e);
             (callback.on_failure)(callback.userdata, error.into_raw());
         }
     }
@@ -0,0 +1,547 @@
+//! Filtering of remote document writes by author.
+//!
+//! Anyone holding a write ticket can sync entries into a document. A write
+//! filter watches remote inserts and checks their author against an
+//! allow-list and, optionally, an app-provided predicate. Entries that are
+//! turned away are rolled back: the newest value from a trusted author is
+//! written again by the filtering author, or the key is deleted if no
+//! trusted author wrote it.
+//!
+//! Sync has no hook before entries are stored, so a rejected entry is
+//! briefly visible and stays in the replica, where peers may still fetch
+//! it; the rollback only keeps it from being the value reads return.
+//! Deletes are timestamped just past the rejected entry (see
+//! [`Tombstones`]), so a key whose trusted value is restored is the only
+//! kind that may need a second rollback, when a peer's clock runs fast.
+//! Those wait in one queue per filter, bounded in size and in how far
+//! ahead they can be scheduled, which is dropped with the filter.
+//! A key can't be deleted while the filtering author has older entries
+//! under longer keys starting with it, since the tombstone would remove
+//! them too; that rollback fails and the entry is still reported.
+
+use crate::clock::now_micros;
+use anyhow::{Context, Result, ensure};
+use futures_lite::StreamExt;
+use iroh::EndpointId;
+use iroh_blobs::Hash;
+use iroh_blobs::api::Store;
+use iroh_docs::actor::SyncHandle;
+use iroh_docs::api::Doc;
+use iroh_docs::engine::LiveEvent;
+use iroh_docs::store::Query;
+use iroh_docs::{AuthorId, ContentStatus, NamespaceId, Record, SignedEntry};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, oneshot};
+use tokio::time::Instant;
+
+/// Most keys waiting for a second rollback; further keys are rolled back
+/// again on their next remote insert instead.
+const MAX_RETRIES: usize = 1024;
+
+/// Furthest ahead a second rollback is scheduled. iroh-docs refuses
+/// entries timestamped further than this into the future.
+const MAX_RETRY_AHEAD: Duration = Duration::from_secs(10 * 60);
+
+/// What to do with a remote entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// Keep the entry.
+    Accept,
+    /// Roll the entry back.
+    Reject,
+    /// Roll the entry back and hand its content to the app for review.
+    Quarantine,
+}
+
+/// A remote entry being filtered.
+#[derive(Debug, Clone)]
+pub struct FilteredEntry {
+    /// Entry key.
+    pub key: Vec<u8>,
+    /// Author of the entry.
+    pub author: AuthorId,
+    /// Content hash of the entry.
+    pub content_hash: Hash,
+    /// Content length in bytes.
+    pub content_len: u64,
+    /// Entry timestamp in microseconds since epoch.
+    pub timestamp: u64,
+}
+
+/// Predicate deciding entries from authors outside the allow-list.
+pub type Decide = dyn Fn(&FilteredEntry) -> Verdict + Send + Sync;
+
+/// Called for every entry that was not accepted; quarantined entries come
+/// with their content.
+pub type OnFiltered = dyn Fn(&FilteredEntry, Verdict, Option<&[u8]>) + Send + Sync;
+
+/// Which remote writes a document accepts.
+pub struct WritePolicy {
+    /// Authors whose entries are always accepted.
+    pub allowed: HashSet<AuthorId>,
+    /// Decides entries from other authors; without it they are rejected.
+    pub decide: Option<Box<Decide>>,
+    /// Told about rejected and quarantined entries.
+    pub on_filtered: Option<Box<OnFiltered>>,
+}
+
+impl WritePolicy {
+    fn verdict(&self, entry: &FilteredEntry) -> Verdict {
+        if self.allowed.contains(&entry.author) {
+            return Verdict::Accept;
+        }
+        match &self.decide {
+            Some(decide) => decide(entry),
+            None => Verdict::Reject,
+        }
+    }
+
+    fn report(&self, entry: &FilteredEntry, verdict: Verdict, content: Option<&[u8]>) {
+        if let Some(on_filtered) = &self.on_filtered {
+            on_filtered(entry, verdict, content);
+        }
+    }
+}
+
+/// Undo an untrusted entry for `key` if it is what reads currently return.
+///
+/// Entries by `author` or an author in `allowed` are trusted. Returns the
+/// timestamp of the newest untrusted entry if a restored trusted value is
+/// still behind it, which happens when a peer's clock runs fast.
+pub async fn roll_back(
+    doc: &Doc,
+    lock: &Mutex<()>,
+    tombstones: &Tombstones,
+    author: AuthorId,
+    allowed: &HashSet<AuthorId>,
+    key: &[u8],
+) -> Result<Option<u64>> {
+    let _guard = lock.lock().await;
+    let entries = doc
+        .get_many(Query::key_exact(key).include_empty())
+        .await
+        .context("Failed to read entries")?
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+    let trusted = |a: &AuthorId| *a == author || allowed.contains(a);
+
+    let Some(newest) = entries.iter().max_by_key(|e| e.timestamp()) else {
+        return Ok(None);
+    };
+    if trusted(&newest.author()) {
+        return Ok(None);
+    }
+    let newest_timestamp = newest.timestamp();
+
+    match entries
+        .iter()
+        .filter(|e| trusted(&e.author()))
+        .max_by_key(|e| e.timestamp())
+    {
+        Some(entry) if !entry.is_empty() => {
+            doc.set_hash(
+                author,
+                key.to_vec(),
+                entry.content_hash(),
+                entry.content_len(),
+            )
+            .await
+            .context("Failed to restore trusted value")?;
+            Ok((newest_timestamp >= now_micros()).then_some(newest_timestamp))
+        }
+        // The tombstone is newer than the rejected entry, however fast the
+        // peer's clock
+        _ => {
+            tombstones
+                .delete_key(doc, author, key, newest_timestamp)
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Deletes exact keys without touching longer keys.
+///
+/// A delete in iroh-docs is an empty entry, which also removes the author's
+/// older entries under longer keys that start with the deleted key. The
+/// author's longer keys were written after their entry for the key itself,
+/// so a tombstone timestamped just past that entry removes it alone. `Doc`
+/// only writes at the current time, so tombstones are signed here and
+/// inserted through the replica actor as if synced from this node;
+/// subscribers see them as remote inserts from the node itself.
+///
+/// Keys deleted together are checked with [`Tombstones::plan`] before any
+/// tombstone is written, so an older longer key may be swept by the
+/// tombstone of a shorter one when both are being deleted.
+#[derive(Debug, Clone)]
+pub struct Tombstones {
+    replicas: SyncHandle,
+    node_id: EndpointId,
+}
+
+/// Tombstones checked by [`Tombstones::plan`], not yet written.
+#[derive(Debug, Default)]
+pub struct Deletion {
+    /// Keys with their tombstone timestamps, longest keys first so no
+    /// tombstone is written after one for a prefix of its key.
+    tombstones: Vec<(Vec<u8>, u64)>,
+    /// Keys left out because their tombstone would also delete an older
+    /// key that is kept.
+    pub blocked: Vec<Vec<u8>>,
+}
+
+impl Deletion {
+    /// Keys that will be deleted.
+    pub fn keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.tombstones.iter().map(|(key, _)| key.as_slice())
+    }
+}
+
+impl Tombstones {
+    /// Write tombstones through `replicas`, as if synced from `node_id`.
+    pub fn new(replicas: SyncHandle, node_id: EndpointId) -> Self {
+        Self { replicas, node_id }
+    }
+
+    /// Delete exactly `key` for `author`, and hide entries of `key` by other
+    /// authors up to the timestamp `after` (0 for none).
+    ///
+    /// Newer writes of `key` by other authors stay what reads return, and
+    /// no entry is written again. Fails if one of the author's longer keys
+    /// is not newer than the tombstone, since it would be deleted as well.
+    /// Returns false if there was nothing to delete.
+    pub async fn delete_key(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        key: &[u8],
+        after: u64,
+    ) -> Result<bool> {
+        let deletion = self.plan(doc, author, [key.to_vec()], after).await?;
+        ensure!(
+            deletion.blocked.is_empty(),
+            "Deleting the key would also delete older keys that start with it"
+        );
+        Ok(self.apply(doc, author, &deletion).await? > 0)
+    }
+
+    /// Check which of `keys` can be deleted exactly for `author`, hiding
+    /// entries of them by other authors up to the timestamp `after`.
+    ///
+    /// A key is blocked when one of the author's longer keys is not newer
+    /// than its tombstone and is not in `keys` itself; keys that only
+    /// sweep other keys being deleted are fine. Keys with nothing to delete
+    /// get no tombstone but may still be swept. Nothing is written until
+    /// [`Tombstones::apply`].
+    pub async fn plan(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        keys: impl IntoIterator<Item = Vec<u8>>,
+        after: u64,
+    ) -> Result<Deletion> {
+        let mut pending: BTreeSet<Vec<u8>> = keys.into_iter().collect();
+        let mut covered = pending.clone();
+        // Each key with the longer keys its tombstone would sweep
+        let mut candidates = Vec::new();
+        while let Some(key) = pending.pop_first() {
+            let current = doc
+                .get_exact(author, key.clone(), true)
+                .await
+                .context("Failed to read entry")?;
+            let current_timestamp = current.as_ref().map_or(0, |e| e.timestamp());
+            if current.is_none_or(|e| e.is_empty()) && current_timestamp >= after {
+                continue;
+            }
+            let timestamp = current_timestamp.max(after) + 1;
+            let record = Record::new(Hash::EMPTY, 0, timestamp);
+
+            let swept = doc
+                .get_many(Query::author(author).key_prefix(&key).include_empty())
+                .await
+                .context("Failed to read entries")?
+                .try_collect::<_, _, Vec<_>>()
+                .await?
+                .into_iter()
+                .filter(|e| e.key() != key.as_slice() && e.record() <= &record)
+                .map(|e| e.key().to_vec())
+                .collect::<Vec<_>>();
+            candidates.push((key, timestamp, swept));
+        }
+
+        // Leaving a key out keeps its entries, which may block a shorter key
+        let mut blocked = Vec::new();
+        loop {
+            let (ok, newly_blocked): (Vec<_>, Vec<_>) = candidates
+                .into_iter()
+                .partition(|(_, _, swept)| swept.iter().all(|k| covered.contains(k)));
+            candidates = ok;
+            if newly_blocked.is_empty() {
+                break;
+            }
+            for (key, _, _) in newly_blocked {
+                covered.remove(&key);
+                blocked.push(key);
+            }
+        }
+
+        candidates.sort_by(|(a, ..), (b, ..)| b.len().cmp(&a.len()).then(a.cmp(b)));
+        Ok(Deletion {
+            tombstones: candidates
+                .into_iter()
+                .map(|(key, timestamp, _)| (key, timestamp))
+                .collect(),
+            blocked,
+        })
+    }
+
+    /// Write the tombstones of `deletion`, planned for `author`.
+    ///
+    /// Returns the number of keys deleted.
+    pub async fn apply(&self, doc: &Doc, author: AuthorId, deletion: &Deletion) -> Result<u64> {
+        if deletion.tombstones.is_empty() {
+            return Ok(0);
+        }
+        let namespace = self
+            .replicas
+            .export_secret_key(doc.id())
+            .await
+            .context("Document is read-only")?;
+        let author = self
+            .replicas
+            .export_author(author)
+            .await?
+            .context("Author not found")?;
+
+        // Remote inserts need the replica to accept syncs, which documents
+        // that aren't syncing do just for the tombstones
+        let sync = SyncOverride::enable(&self.replicas, doc.id()).await?;
+        for (key, timestamp) in &deletion.tombstones {
+            let record = Record::new(Hash::EMPTY, 0, *timestamp);
+            let tombstone = SignedEntry::from_parts(&namespace, &author, key, record);
+            self.replicas
+                .insert_remote(
+                    doc.id(),
+                    tombstone,
+                    *self.node_id.as_bytes(),
+                    ContentStatus::Complete,
+                )
+                .await
+                .context("Failed to delete entry")?;
+        }
+        sync.restore().await?;
+        Ok(deletion.tombstones.len() as u64)
+    }
+}
+
+/// Sync turned on for a document that wasn't syncing, turned off again by
+/// [`SyncOverride::restore`] or, should the write fail or be cancelled,
+/// when dropped.
+struct SyncOverride {
+    replicas: SyncHandle,
+    namespace: NamespaceId,
+    enabled: bool,
+}
+
+impl SyncOverride {
+    async fn enable(replicas: &SyncHandle, namespace: NamespaceId) -> Result<Self> {
+        let enabled = !replicas.get_state(namespace).await?.sync;
+        if enabled {
+            replicas.set_sync(namespace, true).await?;
+        }
+        Ok(Self {
+            replicas: replicas.clone(),
+            namespace,
+            enabled,
+        })
+    }
+
+    async fn restore(mut self) -> Result<()> {
+        if std::mem::take(&mut self.enabled) {
+            self.replicas.set_sync(self.namespace, false).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SyncOverride {
+    fn drop(&mut self) {
+        if !self.enabled {
+            return;
+        }
+        let (replicas, namespace) = (self.replicas.clone(), self.namespace);
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = replicas.set_sync(namespace, false).await;
+            });
+        }
+    }
+}
+
+/// Filter remote inserts until `cancel` fires.
+///
+/// Entries by `author` itself are always accepted. Quarantined entries are
+/// reported once their content has downloaded.
+pub async fn run(
+    doc: Doc,
+    store: Store,
+    lock: Arc<Mutex<()>>,
+    tombstones: Tombstones,
+    author: AuthorId,
+    mut policy: WritePolicy,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<()> {
+    policy.allowed.insert(author);
+    let mut events = doc.subscribe().await?;
+    let mut pending: HashMap<Hash, Vec<FilteredEntry>> = HashMap::new();
+    let mut retries = Retries::default();
+    loop {
+        let event = tokio::select! {
+            _ = &mut cancel => return Ok(()),
+            event = events.next() => event,
+            _ = retries.next_due() => {
+                for key in retries.take_due() {
+                    retries.roll_back(&doc, &lock, &tombstones, author, &policy.allowed, key).await;
+                }
+                continue;
+            }
+        };
+        match event.transpose()? {
+            None => return Ok(()),
+            Some(LiveEvent::InsertRemote {
+                entry,
+                content_status,
+                ..
+            }) => {
+                let entry = FilteredEntry {
+                    key: entry.key().to_vec(),
+                    author: entry.author(),
+                    content_hash: entry.content_hash(),
+                    content_len: entry.content_len(),
+                    timestamp: entry.timestamp(),
+                };
+                let verdict = policy.verdict(&entry);
+                if verdict == Verdict::Accept {
+                    continue;
+                }
+                retries
+                    .roll_back(
+                        &doc,
+                        &lock,
+                        &tombstones,
+                        author,
+                        &policy.allowed,
+                        entry.key.clone(),
+                    )
+                    .await;
+                if verdict == Verdict::Reject {
+                    policy.report(&entry, verdict, None);
+                } else if matches!(content_status, ContentStatus::Complete) {
+                    report_quarantined(&store, &policy, &entry).await;
+                } else {
+                    pending.entry(entry.content_hash).or_default().push(entry);
+                }
+            }
+            Some(LiveEvent::ContentReady { hash }) => {
+                for entry in pending.remove(&hash).unwrap_or_default() {
+                    report_quarantined(&store, &policy, &entry).await;
+                }
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Keys to roll back again once the clock has passed the rejected entry.
+#[derive(Default)]
+struct Retries {
+    /// When each key is due.
+    due: HashMap<Vec<u8>, Instant>,
+    /// The same keys ordered by when they are due.
+    queue: BTreeSet<(Instant, Vec<u8>)>,
+}
+
+impl Retries {
+    /// Roll `key` back, queueing it if the rejected entry is still ahead.
+    ///
+    /// A failed rollback is retried on the key's next remote insert.
+    async fn roll_back(
+        &mut self,
+        doc: &Doc,
+        lock: &Mutex<()>,
+        tombstones: &Tombstones,
+        author: AuthorId,
+        allowed: &HashSet<AuthorId>,
+        key: Vec<u8>,
+    ) {
+        if let Ok(Some(ahead)) = roll_back(doc, lock, tombstones, author, allowed, &key).await {
+            let delay = Duration::from_micros(ahead.saturating_sub(now_micros()) + 1);
+            self.insert(key, Instant::now() + delay.min(MAX_RETRY_AHEAD));
+        }
+    }
+
+    fn insert(&mut self, key: Vec<u8>, at: Instant) {
+        if let Some(previous) = self.due.get(&key) {
+            self.queue.remove(&(*previous, key.clone()));
+        } else if self.due.len() >= MAX_RETRIES {
+            return;
+        }
+        self.due.insert(key.clone(), at);
+        self.queue.insert((at, key));
+    }
+
+    /// Wait until the first key is due; never resolves while empty.
+    async fn next_due(&self) {
+        match self.queue.first() {
+            Some((at, _)) => tokio::time::sleep_until(*at).await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Remove and return the keys that are due.
+    fn take_due(&mut self) -> Vec<Vec<u8>> {
+        let now = Instant::now();
+        let mut keys = Vec::new();
+        while let Some((at, _)) = self.queue.first()
+            && *at <= now
+        {
+            let (_, key) = self.queue.pop_first().expect("queue is not empty");
+            self.due.remove(&key);
+            keys.push(key);
+        }
+        keys
+    }
+}
+
+async fn report_quarantined(store: &Store, policy: &WritePolicy, entry: &FilteredEntry) {
+    let content = store.get_bytes(entry.content_hash).await.ok();
+    policy.report(entry, Verdict::Quarantine, content.as_deref());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retries_bounded() {
+        let mut retries = Retries::default();
+        let now = Instant::now();
+        let later = now + MAX_RETRY_AHEAD;
+
+        // A key queued again moves instead of being queued twice
+        retries.insert(b"a".to_vec(), later);
+        retries.insert(b"a".to_vec(), now);
+        assert_eq!(retries.queue.len(), 1);
+        assert_eq!(retries.take_due(), vec![b"a".to_vec()]);
+        assert!(retries.due.is_empty());
+
+        for i in 0..=MAX_RETRIES {
+            retries.insert(i.to_string().into_bytes(), later);
+        }
+        assert_eq!(retries.due.len(), MAX_RETRIES);
+        assert_eq!(retries.queue.len(), MAX_RETRIES);
+        assert!(retries.take_due().is_empty());
+    }
+}
@@ -0,0 +1,114 @@
+//! Fallback fetches from an HTTPS gateway.
+//!
+//! Some networks block QUIC and the relays entirely. When a node is
+//! configured with a gateway, raw blobs that cannot be downloaded from peers
+//! are requested over plain HTTPS as `<gateway>/<hash>` instead. The gateway
+//! is not trusted: its response is hashed and rejected unless it matches,
+//! and reading stops once it is larger than the caller allows.
+
+use anyhow::{Context, Result, bail};
+use bytes::Bytes;
+use iroh_blobs::Hash;
+use reqwest::{Client, Url};
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// How long to wait for the gateway to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the gateway may go without sending anything before the request
+/// fails, so a stalled response cannot hang `get`.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest response accepted for a blob; gateway content is held in memory
+/// until it is verified.
+pub(crate) const MAX_RESPONSE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Most memory reserved up front for a response. The declared length is
+/// only a claim, so larger bodies grow the buffer as they arrive.
+const MAX_PREALLOC: u64 = 1024 * 1024;
+
+/// An HTTP gateway serving blob content by hash.
+#[derive(Debug, Clone)]
+pub struct Gateway {
+    client: Client,
+    base: Url,
+}
+
+impl Gateway {
+    /// Use the gateway at `url`.
+    ///
+    /// The URL must use HTTPS; plain HTTP is accepted for loopback hosts
+    /// so gateways can be tested locally.
+    pub fn new(url: &str) -> Result<Self> {
+        Self::with_read_timeout(url, READ_TIMEOUT)
+    }
+
+    /// Use the gateway at `url`, failing requests once it has sent nothing
+    /// for `read_timeout`.
+    pub(crate) fn with_read_timeout(url: &str, read_timeout: Duration) -> Result<Self> {
+        let mut base: Url = url.parse().context("Invalid gateway URL")?;
+        match base.scheme() {
+            "https" => {}
+            "http" if base.host_str().is_some_and(is_loopback) => {}
+            _ => bail!("Gateway URL must use HTTPS: {url}"),
+        }
+        // Hashes are appended as the last path segment
+        if !base.path().ends_with('/') {
+            base.set_path(&format!("{}/", base.path()));
+        }
+        let client = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(read_timeout)
+            .build()
+            .context("Failed to create gateway client")?;
+        Ok(Self { client, base })
+    }
+
+    /// Fetch the content of `hash`, verified against the hash.
+    ///
+    /// Fails without reading further once the response is larger than
+    /// `max_size` bytes, or up front if its Content-Length is, and when the
+    /// gateway stops sending before the response is complete.
+    pub async fn fetch(&self, hash: Hash, max_size: u64) -> Result<Bytes> {
+        let url = self.base.join(&hash.to_hex())?;
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("Gateway request failed")?;
+        let too_large = || anyhow::anyhow!("Gateway response is larger than {max_size} bytes");
+        let expected = response.content_length().unwrap_or(0);
+        if expected > max_size {
+            return Err(too_large());
+        }
+        let mut bytes = Vec::with_capacity(expected.min(MAX_PREALLOC) as usize);
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .context("Failed to read gateway response")?
+        {
+            if (bytes.len() + chunk.len()) as u64 > max_size {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        let bytes = Bytes::from(bytes);
+        let received = Hash::new(&bytes);
+        if received != hash {
+            bail!("Gateway returned content for {received} instead of {hash}");
+        }
+        Ok(bytes)
+    }
+}
+
+fn is_loopback(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
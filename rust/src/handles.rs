@@ -0,0 +1,84 @@
+//! Registry of handles held across the FFI boundary.
+//!
+//! Every node, document and subscription handle given to Swift is recorded
+//! here until it is destroyed, closed or cancelled, so that leaked handles
+//! can be listed while debugging. Debug builds also keep the backtrace of
+//! the call that created each handle.
+
+use crate::clock::now_micros;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Kind of resource a handle refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandleKind {
+    Node,
+    #[cfg_attr(not(feature = "docs"), allow(dead_code))]
+    Doc,
+    Subscription,
+}
+
+/// A handle that has been created and not yet released.
+#[derive(Debug, Clone)]
+pub struct LiveHandle {
+    /// What the handle refers to.
+    pub kind: HandleKind,
+    /// The handle's address.
+    pub address: usize,
+    /// Creation time in microseconds since epoch.
+    pub created_at: u64,
+    /// Where the handle was created, in debug builds.
+    pub backtrace: Option<String>,
+}
+
+static LIVE: Mutex<BTreeMap<usize, LiveHandle>> = Mutex::new(BTreeMap::new());
+
+/// Record a handle given out across the boundary.
+pub fn register<T>(kind: HandleKind, handle: *const T) {
+    let address = handle as usize;
+    let backtrace =
+        cfg!(debug_assertions).then(|| std::backtrace::Backtrace::force_capture().to_string());
+    LIVE.lock().unwrap().insert(
+        address,
+        LiveHandle {
+            kind,
+            address,
+            created_at: now_micros(),
+            backtrace,
+        },
+    );
+}
+
+/// Forget a handle that has been destroyed.
+pub fn release<T>(handle: *const T) {
+    LIVE.lock().unwrap().remove(&(handle as usize));
+}
+
+/// Handles that are still live, oldest first.
+pub fn live() -> Vec<LiveHandle> {
+    let mut handles: Vec<_> = LIVE.lock().unwrap().values().cloned().collect();
+    handles.sort_by_key(|handle| (handle.created_at, handle.address));
+    handles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_and_release() {
+        let doc = Box::into_raw(Box::new(0u8));
+        register(HandleKind::Doc, doc);
+
+        let handle = live()
+            .into_iter()
+            .find(|handle| handle.address == doc as usize)
+            .expect("registered handle is live");
+        assert_eq!(handle.kind, HandleKind::Doc);
+        assert_eq!(handle.backtrace.is_some(), cfg!(debug_assertions));
+
+        release(doc);
+        assert!(live().iter().all(|handle| handle.address != doc as usize));
+        drop(unsafe { Box::from_raw(doc) });
+    }
+}
@@ -6,7 +6,79 @@
 //! - `get(ticket) -> bytes`
 //! - Node lifecycle management
 
+mod access;
+mod announce;
+mod bandwidth;
+mod bundle;
+mod catalog;
+#[cfg(feature = "chunking")]
+mod chunking;
+mod clock;
+mod config;
+#[cfg(feature = "control")]
+mod control;
+#[cfg(feature = "docs")]
+mod counter;
+#[cfg(feature = "docs")]
+mod cursor;
+mod delivery;
+mod discovery;
+mod drain;
+#[cfg(feature = "docs")]
+mod durable;
+#[cfg(feature = "docs")]
+mod entry;
+mod events;
+#[cfg(feature = "docs")]
+mod expiry;
+#[cfg(feature = "faults")]
+mod faults;
+#[cfg(feature = "docs")]
+mod fetch;
 mod ffi;
+#[cfg(feature = "docs")]
+mod filter;
+mod gateway;
+mod handles;
+mod limits;
+mod logging;
+#[cfg(feature = "docs")]
+mod manifest;
+mod messages;
+mod metadata;
+#[cfg(feature = "docs")]
+mod ndjson;
+mod network;
 mod node;
+#[cfg(feature = "docs")]
+mod outbox;
+mod passphrase;
+mod peers;
+mod pending;
+mod pool;
+mod profiles;
+mod recovery;
+#[cfg(feature = "docs")]
+mod resolver;
+mod shares;
+#[cfg(feature = "docs")]
+mod signing;
+#[cfg(feature = "docs")]
+mod snapshot;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stats;
+mod storage;
+mod subscriptions;
+#[cfg(feature = "docs")]
+mod swarm;
+#[cfg(feature = "otel")]
+mod telemetry;
+mod testing;
+mod ticket;
+mod trace;
+mod watchdog;
 
 pub use ffi::*;
+pub use node::{IrohNode, NodeOptions, Reachability, TimedOut};
+pub use recovery::{StoreCorrupt, StoreDatabase};
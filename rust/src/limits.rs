@@ -0,0 +1,383 @@
+//! Per-peer limits on blob requests served by this node.
+//!
+//! A device acting as a provider can be drained by a single abusive or
+//! buggy peer. When limits are configured, the blobs protocol reports each
+//! request and every chunk it sends to a limiter task, which turns away
+//! requests over a peer's request rate or concurrent transfer budget and
+//! paces chunks to the peer's bandwidth budget. Turned away requests are
+//! published as [`NodeEvent::FetchLimited`].
+//!
+//! Budgets are per peer, shared by all of its connections, and outlive
+//! reconnects until they have recovered. Chunks are also paced to the
+//! node-wide upload cap, if one is set.
+
+use crate::bandwidth::Bandwidth;
+use crate::events::{EventBus, NodeEvent};
+use iroh::EndpointId;
+use iroh_blobs::provider::events::{
+    AbortReason, ConnectMode, EventMask, EventResult, EventSender, ObserveMode, ProviderMessage,
+    RequestMode, ThrottleMode,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Messages from the blobs protocol buffered before it waits on the limiter.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Limits applied to each peer's blob requests. None means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestLimits {
+    /// Requests a peer may start per second, with bursts of up to one
+    /// second's worth.
+    pub requests_per_sec: Option<u32>,
+    /// Transfers a peer may have in progress at once.
+    pub max_concurrent: Option<u32>,
+    /// Bytes per second sent to a peer across all its transfers.
+    pub bytes_per_sec: Option<u64>,
+}
+
+impl RequestLimits {
+    /// Whether no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Events the blobs protocol has to report to enforce these limits,
+    /// and the upload cap if `caps_uploads`.
+    fn mask(&self, caps_uploads: bool) -> EventMask {
+        let requests = self.requests_per_sec.is_some() || self.max_concurrent.is_some();
+        EventMask {
+            connected: ConnectMode::Notify,
+            // iroh-blobs applies the get mode to every request type
+            get: if requests {
+                RequestMode::Intercept
+            } else {
+                RequestMode::None
+            },
+            get_many: RequestMode::None,
+            push: RequestMode::Disabled,
+            observe: ObserveMode::None,
+            throttle: if self.bytes_per_sec.is_some() || caps_uploads {
+                ThrottleMode::Intercept
+            } else {
+                ThrottleMode::None
+            },
+        }
+    }
+
+    /// Start enforcing the limits and the upload cap of `bandwidth`,
+    /// returning the sender for the blobs protocol.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn(self, events: EventBus, bandwidth: Bandwidth, caps_uploads: bool) -> EventSender {
+        let (sender, rx) = EventSender::channel(CHANNEL_CAPACITY, self.mask(caps_uploads));
+        tokio::spawn(run(Limiter::new(self), bandwidth, events, rx));
+        sender
+    }
+}
+
+/// One peer's remaining budget.
+#[derive(Debug)]
+struct Budget {
+    /// Requests that may start right now.
+    tokens: f64,
+    /// When `tokens` was last refilled.
+    refilled: Instant,
+    /// Transfers in progress.
+    active: u32,
+    /// When the next chunk may be sent.
+    next_send: Instant,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Peer of each open connection.
+    connections: HashMap<u64, EndpointId>,
+    budgets: HashMap<EndpointId, Budget>,
+}
+
+/// Tracks every peer's budget against the limits.
+#[derive(Debug, Clone)]
+struct Limiter {
+    limits: RequestLimits,
+    state: Arc<Mutex<State>>,
+}
+
+/// A transfer counted against its peer's concurrency budget until dropped.
+struct Permit {
+    limiter: Limiter,
+    peer: EndpointId,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut state = self.limiter.state.lock().unwrap();
+        if let Some(budget) = state.budgets.get_mut(&self.peer) {
+            budget.active = budget.active.saturating_sub(1);
+        }
+    }
+}
+
+impl Limiter {
+    fn new(limits: RequestLimits) -> Self {
+        Self {
+            limits,
+            state: Default::default(),
+        }
+    }
+
+    fn connected(&self, connection_id: u64, peer: EndpointId) {
+        self.state
+            .lock()
+            .unwrap()
+            .connections
+            .insert(connection_id, peer);
+    }
+
+    /// Forget a connection, and its peer's budget once it has recovered.
+    fn closed(&self, connection_id: u64, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        let Some(peer) = state.connections.remove(&connection_id) else {
+            return;
+        };
+        if state.connections.values().any(|p| *p == peer) {
+            return;
+        }
+        let limits = self.limits;
+        if let Some(budget) = state.budgets.get_mut(&peer) {
+            refill(budget, &limits, now);
+            let recovered = budget.active == 0
+                && budget.next_send <= now
+                && limits
+                    .requests_per_sec
+                    .is_none_or(|rate| budget.tokens >= f64::from(rate.max(1)));
+            if recovered {
+                state.budgets.remove(&peer);
+            }
+        }
+    }
+
+    /// Admit a request on `connection_id`, or return the peer it was refused to.
+    fn admit(&self, connection_id: u64, now: Instant) -> Result<Option<Permit>, EndpointId> {
+        let mut state = self.state.lock().unwrap();
+        // Requests on connections we were not told about are not limited
+        let Some(peer) = state.connections.get(&connection_id).copied() else {
+            return Ok(None);
+        };
+        let limits = self.limits;
+        let budget = state
+            .budgets
+            .entry(peer)
+            .or_insert_with(|| Budget::new(&limits, now));
+        refill(budget, &limits, now);
+
+        if limits
+            .max_concurrent
+            .is_some_and(|max| budget.active >= max)
+        {
+            return Err(peer);
+        }
+        if limits.requests_per_sec.is_some() {
+            if budget.tokens < 1.0 {
+                return Err(peer);
+            }
+            budget.tokens -= 1.0;
+        }
+        budget.active += 1;
+        Ok(Some(Permit {
+            limiter: self.clone(),
+            peer,
+        }))
+    }
+
+    /// When a chunk of `size` bytes on `connection_id` may be sent.
+    fn schedule(&self, connection_id: u64, size: u64, now: Instant) -> Instant {
+        let Some(rate) = self.limits.bytes_per_sec.filter(|rate| *rate > 0) else {
+            return now;
+        };
+        let mut state = self.state.lock().unwrap();
+        let Some(peer) = state.connections.get(&connection_id).copied() else {
+            return now;
+        };
+        let limits = self.limits;
+        let budget = state
+            .budgets
+            .entry(peer)
+            .or_insert_with(|| Budget::new(&limits, now));
+        let start = budget.next_send.max(now);
+        budget.next_send = start + Duration::from_secs_f64(size as f64 / rate as f64);
+        start
+    }
+}
+
+impl Budget {
+    fn new(limits: &RequestLimits, now: Instant) -> Self {
+        Self {
+            tokens: limits
+                .requests_per_sec
+                .map_or(0.0, |rate| f64::from(rate.max(1))),
+            refilled: now,
+            active: 0,
+            next_send: now,
+        }
+    }
+}
+
+fn refill(budget: &mut Budget, limits: &RequestLimits, now: Instant) {
+    if let Some(rate) = limits.requests_per_sec {
+        let rate = f64::from(rate.max(1));
+        let elapsed = now.saturating_duration_since(budget.refilled).as_secs_f64();
+        budget.tokens = (budget.tokens + elapsed * rate).min(rate);
+    }
+    budget.refilled = now;
+}
+
+async fn run(
+    limiter: Limiter,
+    bandwidth: Bandwidth,
+    events: EventBus,
+    mut rx: mpsc::Receiver<ProviderMessage>,
+) {
+    // Decide whether a request may start; admitted ones hold their permit
+    // until the blobs protocol drops the request's update channel
+    let admit = |connection_id: u64| -> (EventResult, Option<Permit>) {
+        match limiter.admit(connection_id, Instant::now()) {
+            Ok(permit) => (Ok(()), permit),
+            Err(peer) => {
+                events.emit(NodeEvent::FetchLimited { peer });
+                (Err(AbortReason::RateLimited), None)
+            }
+        }
+    };
+
+    while let Some(message) = rx.recv().await {
+        match message {
+            ProviderMessage::ClientConnected(msg) => {
+                if let Some(peer) = msg.inner.endpoint_id {
+                    limiter.connected(msg.inner.connection_id, peer);
+                }
+                msg.tx.send(Ok(())).await.ok();
+            }
+            ProviderMessage::ClientConnectedNotify(msg) => {
+                if let Some(peer) = msg.inner.endpoint_id {
+                    limiter.connected(msg.inner.connection_id, peer);
+                }
+            }
+            ProviderMessage::ConnectionClosed(msg) => {
+                limiter.closed(msg.inner.connection_id, Instant::now());
+            }
+            ProviderMessage::GetRequestReceived(mut msg) => {
+                let (result, permit) = admit(msg.inner.connection_id);
+                msg.tx.send(result).await.ok();
+                if let Some(permit) = permit {
+                    tokio::spawn(async move {
+                        while let Ok(Some(_)) = msg.rx.recv().await {}
+                        drop(permit);
+                    });
+                }
+            }
+            ProviderMessage::GetManyRequestReceived(mut msg) => {
+                let (result, permit) = admit(msg.inner.connection_id);
+                msg.tx.send(result).await.ok();
+                if let Some(permit) = permit {
+                    tokio::spawn(async move {
+                        while let Ok(Some(_)) = msg.rx.recv().await {}
+                        drop(permit);
+                    });
+                }
+            }
+            // Peers may not write to our store
+            ProviderMessage::PushRequestReceived(msg) => {
+                msg.tx.send(Err(AbortReason::Permission)).await.ok();
+            }
+            // Observing is cheap and long-lived, so it is not limited
+            ProviderMessage::ObserveRequestReceived(msg) => {
+                msg.tx.send(Ok(())).await.ok();
+            }
+            ProviderMessage::Throttle(msg) => {
+                let now = Instant::now();
+                let start = limiter
+                    .schedule(msg.inner.connection_id, msg.inner.size, now)
+                    .max(bandwidth.schedule_upload(msg.inner.size, now));
+                if start <= now {
+                    msg.tx.send(Ok(())).await.ok();
+                } else {
+                    tokio::spawn(async move {
+                        tokio::time::sleep_until(start).await;
+                        msg.tx.send(Ok(())).await.ok();
+                    });
+                }
+            }
+            ProviderMessage::GetRequestReceivedNotify(_)
+            | ProviderMessage::GetManyRequestReceivedNotify(_)
+            | ProviderMessage::PushRequestReceivedNotify(_)
+            | ProviderMessage::ObserveRequestReceivedNotify(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::SecretKey;
+
+    fn limiter(limits: RequestLimits) -> (Limiter, EndpointId) {
+        let peer = SecretKey::from_bytes(&[3u8; 32]).public();
+        let limiter = Limiter::new(limits);
+        limiter.connected(1, peer);
+        (limiter, peer)
+    }
+
+    #[test]
+    fn test_request_rate_and_concurrency() {
+        let (limiter, peer) = limiter(RequestLimits {
+            requests_per_sec: Some(2),
+            max_concurrent: Some(1),
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        let first = limiter.admit(1, now).unwrap();
+        // Only one transfer at a time
+        assert_eq!(limiter.admit(1, now).err(), Some(peer));
+        drop(first);
+        // The burst of two is used up after the second request
+        drop(limiter.admit(1, now).unwrap());
+        assert_eq!(limiter.admit(1, now).err(), Some(peer));
+        // Half a second buys another request
+        assert!(limiter.admit(1, now + Duration::from_millis(500)).is_ok());
+
+        // Unknown connections are not limited
+        assert!(limiter.admit(2, now).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bandwidth_pacing_survives_reconnect() {
+        let (limiter, peer) = limiter(RequestLimits {
+            requests_per_sec: Some(1),
+            bytes_per_sec: Some(1000),
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        assert_eq!(limiter.schedule(1, 500, now), now);
+        assert_eq!(
+            limiter.schedule(1, 500, now),
+            now + Duration::from_millis(500)
+        );
+
+        // A spent budget is kept when the peer reconnects
+        drop(limiter.admit(1, now).unwrap());
+        limiter.closed(1, now);
+        limiter.connected(2, peer);
+        assert_eq!(limiter.admit(2, now).err(), Some(peer));
+        assert_eq!(limiter.schedule(2, 1000, now), now + Duration::from_secs(1));
+
+        // Recovered budgets are forgotten
+        limiter.closed(2, now + Duration::from_secs(5));
+        assert!(limiter.state.lock().unwrap().budgets.is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! Forwarding of panics to the host app's logs.
+//!
+//! A panic in a task on a node's runtime is caught by the runtime and only
+//! printed to stderr, which apps never see, so the failure vanishes apart
+//! from its effects. Once the host registers a log callback, a panic hook
+//! hands the message, location and backtrace of every panic to the callback
+//! before unwinding starts, then runs the previously installed hook.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::panic::{self, PanicHookInfo};
+use std::sync::{Arc, Once, PoisonError, RwLock};
+
+/// Called with each log message, on the thread that logged it.
+pub type OnLog = dyn Fn(&str) + Send + Sync;
+
+/// The registered callback, if any.
+static LOGGER: RwLock<Option<Arc<OnLog>>> = RwLock::new(None);
+
+/// Installs the panic hook the first time a callback is registered.
+static HOOK: Once = Once::new();
+
+/// Register `on_log` for the whole process, replacing any earlier callback,
+/// and forward panics to it from now on.
+pub fn init(on_log: Box<OnLog>) {
+    *LOGGER.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::from(on_log));
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            log(&describe(info, &Backtrace::force_capture()));
+            previous(info);
+        }));
+    });
+}
+
+/// Pass `message` to the registered callback, if any.
+pub fn log(message: &str) {
+    // Released before calling, so the callback may log or panic itself
+    let on_log = LOGGER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Some(on_log) = on_log {
+        on_log(message);
+    }
+}
+
+/// Describe a panic the way the default hook prints it, with the backtrace.
+fn describe(info: &PanicHookInfo, backtrace: &Backtrace) -> String {
+    let thread = std::thread::current();
+    let location = info
+        .location()
+        .map(|location| format!(" at {location}"))
+        .unwrap_or_default();
+    format!(
+        "thread '{}' panicked{location}: {}\nstack backtrace:\n{backtrace}",
+        thread.name().unwrap_or("<unnamed>"),
+        payload_message(info.payload())
+    )
+}
+
+/// The message a panic was raised with.
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_panic_in_task_is_logged() {
+        let logged = Arc::new(Mutex::new(Vec::new()));
+        let sink = logged.clone();
+        init(Box::new(move |message| {
+            sink.lock().unwrap().push(message.to_string())
+        }));
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("panic-test")
+            .build()
+            .unwrap();
+        let result = runtime.block_on(runtime.spawn(async {
+            panic!("task failed: {}", 42);
+        }));
+        assert!(result.unwrap_err().is_panic());
+
+        let logged = logged.lock().unwrap();
+        let message = logged
+            .iter()
+            .find(|message| message.contains("task failed: 42"))
+            .expect("panic was not logged");
+        assert!(message.starts_with("thread 'panic-test' panicked at "));
+        assert!(message.contains("logging.rs"));
+        assert!(message.contains("stack backtrace:"));
+    }
+}
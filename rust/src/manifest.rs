@@ -0,0 +1,266 @@
+//! Backup manifests: a document indexing assets stored as blobs.
+//!
+//! Photo and file backups share one pattern: every asset is stored as a
+//! blob, pinned with a tag, and listed in a document under its asset ID so
+//! other devices learn what exists. Each index entry holds the blob's hash
+//! and size followed by a [`BlobMetadata`] record.
+//!
+//! Adding pins the blob before indexing it and pruning removes the index
+//! entry before the pin, so an interrupted call leaves at most an unused
+//! tag, which the next prune removes. No index entry ever points at a blob
+//! that may have been garbage collected.
+
+use crate::clock::now_micros;
+use crate::filter::Tombstones;
+use crate::metadata::{BlobMetadata, Compression};
+use anyhow::{Context, Result, ensure};
+use futures_lite::StreamExt;
+use iroh_blobs::api::Store;
+use iroh_blobs::{BlobFormat, Hash, HashAndFormat};
+use iroh_docs::api::Doc;
+use iroh_docs::store::Query;
+use iroh_docs::{AuthorId, NamespaceId};
+use std::collections::BTreeSet;
+use tokio::sync::Mutex;
+
+/// Prefix of the tags pinning manifest blobs.
+pub const TAG_PREFIX: &str = "manifest/";
+
+/// Bytes before the metadata record in an index entry.
+const HEADER_LEN: usize = 32 + 8;
+
+/// An asset listed in a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestAsset {
+    /// App-defined asset ID; the entry key.
+    pub asset_id: String,
+    /// Hash of the asset's content.
+    pub hash: Hash,
+    /// Content size in bytes.
+    pub size: u64,
+    pub metadata: BlobMetadata,
+}
+
+impl ManifestAsset {
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut value = Vec::with_capacity(HEADER_LEN);
+        value.extend_from_slice(self.hash.as_bytes());
+        value.extend_from_slice(&self.size.to_be_bytes());
+        value.extend_from_slice(&self.metadata.to_bytes()?);
+        Ok(value)
+    }
+
+    fn decode(asset_id: String, value: &[u8]) -> Result<Self> {
+        ensure!(value.len() >= HEADER_LEN, "Invalid manifest entry");
+        let (hash, rest) = value.split_at(32);
+        let (size, record) = rest.split_at(8);
+        Ok(Self {
+            asset_id,
+            hash: Hash::from_bytes(hash.try_into()?),
+            size: u64::from_be_bytes(size.try_into()?),
+            metadata: BlobMetadata::from_bytes(record)?,
+        })
+    }
+}
+
+/// Name of the tag pinning `asset_id` of the manifest in `namespace`.
+fn tag_name(namespace: NamespaceId, asset_id: &str) -> String {
+    format!("{TAG_PREFIX}{namespace}/{asset_id}")
+}
+
+fn check_asset_id(asset_id: &str) -> Result<()> {
+    ensure!(!asset_id.is_empty(), "Asset ID cannot be empty");
+    ensure!(!asset_id.contains('\0'), "Asset ID cannot contain NUL");
+    Ok(())
+}
+
+/// Store `data` as an asset, pin it and index it under `asset_id`.
+///
+/// Replaces any previous version of the asset, whose blob is no longer
+/// pinned by the manifest. A `created_at` of 0 is replaced with the
+/// current time. Assets are stored uncompressed so the indexed hash is the
+/// hash of the asset itself.
+pub async fn add(
+    doc: &Doc,
+    store: &Store,
+    lock: &Mutex<()>,
+    author: AuthorId,
+    asset_id: &str,
+    data: &[u8],
+    mut metadata: BlobMetadata,
+) -> Result<ManifestAsset> {
+    check_asset_id(asset_id)?;
+    metadata.compression = Compression::None;
+    if metadata.created_at == 0 {
+        metadata.created_at = now_micros() / 1_000_000;
+    }
+    let _guard = lock.lock().await;
+    let content = store
+        .add_slice(data)
+        .temp_tag()
+        .await
+        .context("Failed to add asset to store")?;
+    store
+        .tags()
+        .set(tag_name(doc.id(), asset_id), content.hash_and_format())
+        .await
+        .context("Failed to pin asset")?;
+    let asset = ManifestAsset {
+        asset_id: asset_id.to_string(),
+        hash: content.hash(),
+        size: data.len() as u64,
+        metadata,
+    };
+    doc.set_bytes(author, asset_id.as_bytes().to_vec(), asset.encode()?)
+        .await
+        .context("Failed to index asset")?;
+    Ok(asset)
+}
+
+/// Every asset in the manifest, sorted by asset ID.
+///
+/// Entries whose value has not been synced yet are skipped.
+pub async fn assets(doc: &Doc, store: &Store) -> Result<Vec<ManifestAsset>> {
+    let entries = doc
+        .get_many(Query::single_latest_per_key())
+        .await
+        .context("Failed to read manifest")?
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+    let mut assets = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let Ok(asset_id) = String::from_utf8(entry.key().to_vec()) else {
+            continue;
+        };
+        let Ok(value) = store.get_bytes(entry.content_hash()).await else {
+            continue;
+        };
+        assets.push(ManifestAsset::decode(asset_id, &value)?);
+    }
+    Ok(assets)
+}
+
+/// Asset IDs that are not fully backed up on this node, sorted.
+///
+/// These are the IDs in `asset_ids` that are not indexed, and indexed
+/// assets whose content is not complete in the store, such as assets added
+/// on another device. Indexed assets whose content is complete but not
+/// pinned here, because it was downloaded for a restore, are pinned.
+pub async fn missing(
+    doc: &Doc,
+    store: &Store,
+    lock: &Mutex<()>,
+    asset_ids: &[String],
+) -> Result<Vec<String>> {
+    let _guard = lock.lock().await;
+    let assets = assets(doc, store).await?;
+    let indexed: BTreeSet<&str> = assets.iter().map(|a| a.asset_id.as_str()).collect();
+    let mut missing: BTreeSet<String> = asset_ids
+        .iter()
+        .filter(|id| !indexed.contains(id.as_str()))
+        .cloned()
+        .collect();
+    for asset in &assets {
+        if !store.blobs().has(asset.hash).await? {
+            missing.insert(asset.asset_id.clone());
+            continue;
+        }
+        let name = tag_name(doc.id(), &asset.asset_id);
+        if store.tags().get(name.as_str()).await?.is_none() {
+            store
+                .tags()
+                .set(name, HashAndFormat::new(asset.hash, BlobFormat::Raw))
+                .await
+                .context("Failed to pin asset")?;
+        }
+    }
+    Ok(missing.into_iter().collect())
+}
+
+/// Remove `asset_ids` from the manifest and unpin their blobs.
+///
+/// Pins left behind by interrupted calls are removed too. Index entries
+/// are deleted with exact deletes, so asset IDs that extend a pruned one
+/// stay indexed. Returns the number of assets that were indexed and are
+/// now removed.
+pub async fn prune(
+    doc: &Doc,
+    store: &Store,
+    lock: &Mutex<()>,
+    tombstones: &Tombstones,
+    author: AuthorId,
+    asset_ids: &[String],
+) -> Result<u64> {
+    let _guard = lock.lock().await;
+    let mut removed = 0;
+    for asset_id in asset_ids {
+        check_asset_id(asset_id)?;
+        let indexed = doc
+            .get_one(Query::single_latest_per_key().key_exact(asset_id))
+            .await
+            .context("Failed to read manifest")?;
+        if let Some(indexed) = indexed {
+            tombstones
+                .delete_key(doc, author, asset_id.as_bytes(), indexed.timestamp())
+                .await?;
+            removed += 1;
+        }
+        store
+            .tags()
+            .delete(tag_name(doc.id(), asset_id))
+            .await
+            .context("Failed to unpin asset")?;
+    }
+
+    // Unpin blobs the index no longer lists, such as versions replaced on
+    // other devices
+    let indexed: Vec<ManifestAsset> = assets(doc, store).await?;
+    let prefix = tag_name(doc.id(), "");
+    let tags = store
+        .tags()
+        .list_prefix(prefix.as_str())
+        .await
+        .context("Failed to list tags")?
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+    for tag in tags {
+        let asset_id = String::from_utf8_lossy(&tag.name.as_ref()[prefix.len()..]);
+        let current = indexed
+            .iter()
+            .any(|a| a.asset_id == asset_id && a.hash == tag.hash);
+        if !current {
+            store
+                .tags()
+                .delete(tag.name)
+                .await
+                .context("Failed to unpin asset")?;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_entry_roundtrip() {
+        let asset = ManifestAsset {
+            asset_id: "IMG_0001".to_string(),
+            hash: Hash::new(b"photo"),
+            size: 5,
+            metadata: BlobMetadata {
+                filename: Some("IMG_0001.HEIC".to_string()),
+                content_type: Some("image/heic".to_string()),
+                created_at: 1_700_000_000,
+                ..Default::default()
+            },
+        };
+        let value = asset.encode().unwrap();
+        assert_eq!(
+            ManifestAsset::decode(asset.asset_id.clone(), &value).unwrap(),
+            asset
+        );
+        assert!(ManifestAsset::decode(String::new(), &value[..HEADER_LEN - 1]).is_err());
+    }
+}
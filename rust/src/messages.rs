@@ -0,0 +1,146 @@
+//! One-to-one messages between nodes.
+//!
+//! A small protocol for control-plane signals between known devices, such
+//! as "I just updated the doc, wake up and sync". Each message travels on
+//! its own stream: the sender writes the payload and finishes the stream,
+//! and the receiver hands it to every subscriber before answering with one
+//! byte saying whether anyone was listening. Messages are not stored or
+//! retried; a peer that is offline or not subscribed does not get them.
+
+use anyhow::{Context, Result};
+use futures_lite::StreamExt;
+use futures_lite::stream::{self, Boxed};
+use iroh::endpoint::{Connection, VarInt};
+use iroh::protocol::{AcceptError, ProtocolHandler};
+use iroh::{Endpoint, EndpointAddr, EndpointId};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+
+/// ALPN of the message protocol.
+pub const MESSAGE_ALPN: &[u8] = b"/iroh-swift/message/0";
+
+/// Largest payload a message may carry.
+pub const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+/// Messages buffered per subscriber before the oldest are dropped.
+const MESSAGE_BUFFER: usize = 64;
+
+/// Answer sent when at least one subscriber received the message.
+const DELIVERED: u8 = 1;
+
+/// Answer sent when nobody on the receiving node is subscribed.
+const NOT_DELIVERED: u8 = 0;
+
+/// A message received from a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// Node that sent the message.
+    pub from: EndpointId,
+    pub payload: Vec<u8>,
+}
+
+/// Accepts messages from peers and broadcasts them to subscribers.
+#[derive(Debug, Clone)]
+pub struct Messages {
+    sender: broadcast::Sender<Message>,
+    /// Ends every subscription when the node shuts down.
+    closed: CancellationToken,
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(MESSAGE_BUFFER).0,
+            closed: CancellationToken::new(),
+        }
+    }
+}
+
+impl Messages {
+    /// Number of messages some subscriber has yet to receive.
+    pub fn queued(&self) -> usize {
+        self.sender.len()
+    }
+
+    /// Messages received from now on, until the node shuts down.
+    ///
+    /// Slow subscribers skip messages they fell too far behind on.
+    pub fn subscribe(&self) -> Boxed<Message> {
+        let closed = self.closed.clone();
+        stream::unfold(self.sender.subscribe(), move |mut rx| {
+            let closed = closed.clone();
+            async move {
+                loop {
+                    tokio::select! {
+                        _ = closed.cancelled() => return None,
+                        next = rx.recv() => match next {
+                            Ok(message) => return Some((message, rx)),
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => return None,
+                        },
+                    }
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// End every subscription.
+    pub fn close(&self) {
+        self.closed.cancel();
+    }
+}
+
+/// Send `payload` to `addr` and wait until one of its subscribers has it.
+///
+/// Fails if the peer cannot be reached, does not speak the protocol, or
+/// has nobody subscribed to messages.
+pub async fn send(endpoint: &Endpoint, addr: EndpointAddr, payload: &[u8]) -> Result<()> {
+    anyhow::ensure!(
+        payload.len() <= MAX_MESSAGE_SIZE,
+        "Message is {} bytes, more than the limit of {} bytes",
+        payload.len(),
+        MAX_MESSAGE_SIZE
+    );
+
+    let connection = endpoint
+        .connect(addr, MESSAGE_ALPN)
+        .await
+        .context("Failed to connect to peer")?;
+    let result = async {
+        let (mut send, mut recv) = connection.open_bi().await?;
+        send.write_all(payload).await?;
+        send.finish()?;
+        let answer = recv.read_to_end(1).await?;
+        anyhow::ensure!(
+            answer.first() == Some(&DELIVERED),
+            "Peer is not subscribed to messages"
+        );
+        Ok(())
+    }
+    .await;
+    connection.close(VarInt::from_u32(0), b"message sent");
+    result.context("Failed to send message")
+}
+
+impl ProtocolHandler for Messages {
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        let from = connection.remote_id();
+        // Serve streams until the sender closes the connection
+        while let Ok((mut send, mut recv)) = connection.accept_bi().await {
+            let payload = recv
+                .read_to_end(MAX_MESSAGE_SIZE)
+                .await
+                .map_err(AcceptError::from_err)?;
+            let answer = match self.sender.send(Message { from, payload }) {
+                Ok(_) => DELIVERED,
+                Err(_) => NOT_DELIVERED,
+            };
+            send.write_all(&[answer])
+                .await
+                .map_err(AcceptError::from_err)?;
+            send.finish()?;
+        }
+        Ok(())
+    }
+}
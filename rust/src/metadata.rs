@@ -0,0 +1,182 @@
+//! Blob metadata records.
+//!
+//! Metadata is stored alongside a blob by wrapping both in a collection:
+//! the first entry holds the encoded metadata record and the second holds
+//! the content itself, named after the original filename. The record also
+//! says whether the content was compressed, so readers can restore it.
+
+#[cfg(feature = "compression")]
+use anyhow::Context;
+use anyhow::{Result, bail};
+
+/// Collection entry name for the metadata record.
+pub const METADATA_ENTRY: &str = ".iroh-metadata";
+
+/// Entry name used for the content when no filename is given.
+pub const DEFAULT_CONTENT_ENTRY: &str = "data";
+
+/// zstd level for compressed content; favors speed on phones.
+#[cfg(feature = "compression")]
+const ZSTD_LEVEL: i32 = 3;
+
+/// Error for zstd content in builds without the compression feature.
+#[cfg(not(feature = "compression"))]
+const NO_ZSTD: &str = "zstd compression is not included in this build";
+
+/// How the stored content is encoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Stored as given.
+    #[default]
+    None,
+    /// Compressed with zstd.
+    Zstd,
+}
+
+impl Compression {
+    fn name(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Zstd => Some("zstd"),
+        }
+    }
+
+    /// Compress `data`, or return `None` if that does not make it smaller.
+    #[cfg_attr(not(feature = "compression"), allow(unused_variables))]
+    pub fn compress(self, data: &[u8]) -> Result<Option<Vec<u8>>> {
+        match self {
+            Compression::None => Ok(None),
+            #[cfg(feature = "compression")]
+            Compression::Zstd => {
+                let compressed =
+                    zstd::encode_all(data, ZSTD_LEVEL).context("Failed to compress content")?;
+                Ok((compressed.len() < data.len()).then_some(compressed))
+            }
+            #[cfg(not(feature = "compression"))]
+            Compression::Zstd => bail!(NO_ZSTD),
+        }
+    }
+
+    /// Restore content stored with this compression.
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data.to_vec()),
+            #[cfg(feature = "compression")]
+            Compression::Zstd => zstd::decode_all(data).context("Failed to decompress content"),
+            #[cfg(not(feature = "compression"))]
+            Compression::Zstd => bail!(NO_ZSTD),
+        }
+    }
+}
+
+/// Optional descriptive metadata attached to a blob at put time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlobMetadata {
+    /// Original filename.
+    pub filename: Option<String>,
+    /// MIME type of the content.
+    pub content_type: Option<String>,
+    /// Creation date as seconds since the Unix epoch.
+    pub created_at: u64,
+    /// Encoding of the stored content.
+    pub compression: Compression,
+}
+
+impl BlobMetadata {
+    /// Name of the collection entry that holds the content.
+    pub fn content_entry(&self) -> &str {
+        self.filename.as_deref().unwrap_or(DEFAULT_CONTENT_ENTRY)
+    }
+
+    /// Encode the record as `key=value` lines.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut out = String::new();
+        if let Some(filename) = &self.filename {
+            push_field(&mut out, "filename", filename)?;
+        }
+        if let Some(content_type) = &self.content_type {
+            push_field(&mut out, "content_type", content_type)?;
+        }
+        push_field(&mut out, "created_at", &self.created_at.to_string())?;
+        if let Some(name) = self.compression.name() {
+            push_field(&mut out, "compression", name)?;
+        }
+        Ok(out.into_bytes())
+    }
+
+    /// Decode a record produced by [`BlobMetadata::to_bytes`].
+    ///
+    /// Unknown keys are ignored so newer writers stay readable, but an
+    /// unknown compression is an error since the content cannot be restored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let text = std::str::from_utf8(bytes)?;
+        let mut metadata = BlobMetadata::default();
+        for line in text.lines().filter(|l| !l.is_empty()) {
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("malformed metadata line: {line}");
+            };
+            match key {
+                "filename" => metadata.filename = Some(value.to_string()),
+                "content_type" => metadata.content_type = Some(value.to_string()),
+                "created_at" => metadata.created_at = value.parse()?,
+                "compression" => {
+                    metadata.compression = match value {
+                        "zstd" => Compression::Zstd,
+                        _ => bail!("unsupported compression: {value}"),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+fn push_field(out: &mut String, key: &str, value: &str) -> Result<()> {
+    if value.contains(['\n', '\r']) {
+        bail!("metadata {key} cannot contain line breaks");
+    }
+    out.push_str(key);
+    out.push('=');
+    out.push_str(value);
+    out.push('\n');
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_roundtrip() {
+        let metadata = BlobMetadata {
+            filename: Some("photo=1.jpg".to_string()),
+            content_type: Some("image/jpeg".to_string()),
+            created_at: 1_700_000_000,
+            compression: Compression::Zstd,
+        };
+        let bytes = metadata.to_bytes().unwrap();
+        assert_eq!(BlobMetadata::from_bytes(&bytes).unwrap(), metadata);
+        assert!(BlobMetadata::from_bytes(b"compression=brotli\n").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn test_compression_roundtrip() {
+        let data = br#"{"name":"profile","tags":["a","a","a","a","a","a","a","a"]}"#.repeat(20);
+        let compressed = Compression::Zstd.compress(&data).unwrap().unwrap();
+        assert!(compressed.len() < data.len());
+        assert_eq!(Compression::Zstd.decompress(&compressed).unwrap(), data);
+        // Content that does not shrink is kept as is
+        assert_eq!(Compression::Zstd.compress(b"x").unwrap(), None);
+    }
+
+    #[test]
+    fn test_metadata_rejects_line_breaks() {
+        let metadata = BlobMetadata {
+            filename: Some("a\nb".to_string()),
+            ..Default::default()
+        };
+        assert!(metadata.to_bytes().is_err());
+    }
+}
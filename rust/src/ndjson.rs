@@ -0,0 +1,94 @@
+//! Export of document entries as newline-delimited JSON.
+//!
+//! Large exports, such as a user's data download, are streamed to the app
+//! in chunks of whole lines instead of being collected into one array on
+//! either side of the FFI boundary. Each line describes one entry:
+//!
+//! ```json
+//! {"key":"notes/1","author":"…","content_hash":"…","content_len":5,"timestamp":1700000000000000,"value":"hello"}
+//! ```
+//!
+//! Timestamps are microseconds since epoch. Keys and values that are not
+//! valid UTF-8 are written hex encoded as `key_hex` and `value_hex`
+//! instead. Values are included for entries no larger than the requested
+//! limit whose content is stored locally, and left out otherwise.
+
+use crate::node::DocQuery;
+use anyhow::{Context, Result};
+use futures_lite::StreamExt;
+use iroh_blobs::api::Store;
+use iroh_docs::Entry;
+use iroh_docs::api::Doc;
+use serde_json::{Map, Value};
+
+/// Lines are handed to the writer once this many bytes are buffered.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Stream the entries of `doc` matching `query` to `write` as NDJSON.
+///
+/// Values up to `max_value_size` bytes are included (0 for none). `write`
+/// gets chunks of whole lines and returns false to stop the export.
+/// Returns the number of entries exported.
+pub async fn export(
+    doc: &Doc,
+    store: &Store,
+    query: &DocQuery,
+    max_value_size: u64,
+    mut write: impl FnMut(&[u8]) -> bool,
+) -> Result<u64> {
+    let entries = doc
+        .get_many(query.store_query())
+        .await
+        .context("Failed to query entries")?;
+    let mut entries = std::pin::pin!(entries);
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+    let mut exported = 0;
+    while let Some(entry) = entries.next().await {
+        let entry = entry?;
+        if !query.contains_timestamp(entry.timestamp()) {
+            continue;
+        }
+        let value = if entry.content_len() > 0 && entry.content_len() <= max_value_size {
+            // Content synced from peers may not have arrived yet
+            store.get_bytes(entry.content_hash()).await.ok()
+        } else {
+            None
+        };
+        serde_json::to_writer(&mut chunk, &line(&entry, value.as_deref()))?;
+        chunk.push(b'\n');
+        exported += 1;
+        if chunk.len() >= CHUNK_SIZE {
+            anyhow::ensure!(write(&chunk), "Export cancelled");
+            chunk.clear();
+        }
+    }
+    if !chunk.is_empty() {
+        anyhow::ensure!(write(&chunk), "Export cancelled");
+    }
+    Ok(exported)
+}
+
+/// The JSON object for one entry.
+fn line(entry: &Entry, value: Option<&[u8]>) -> Value {
+    let mut line = Map::new();
+    insert_bytes(&mut line, "key", entry.key());
+    line.insert("author".into(), entry.author().to_string().into());
+    line.insert(
+        "content_hash".into(),
+        entry.content_hash().to_string().into(),
+    );
+    line.insert("content_len".into(), entry.content_len().into());
+    line.insert("timestamp".into(), entry.timestamp().into());
+    if let Some(value) = value {
+        insert_bytes(&mut line, "value", value);
+    }
+    Value::Object(line)
+}
+
+/// Insert `bytes` as a string under `name`, or hex encoded under `name_hex`.
+fn insert_bytes(line: &mut Map<String, Value>, name: &str, bytes: &[u8]) {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => line.insert(name.into(), s.into()),
+        Err(_) => line.insert(format!("{name}_hex"), hex::encode(bytes).into()),
+    };
+}
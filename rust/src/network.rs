@@ -0,0 +1,128 @@
+//! Detection of networks that restrict peer-to-peer traffic.
+//!
+//! Transfers slow down for reasons users cannot see: UDP may be blocked so
+//! every byte goes through a relay, the NAT may defeat hole punching, or a
+//! captive portal may hold traffic until someone signs in. The endpoint's
+//! network reports reveal these conditions; the node classifies each report
+//! and publishes [`NodeEvent::NetworkCondition`] when the result changes, so
+//! apps can explain degraded speeds instead of silently being slow.
+
+use crate::events::{EventBus, NodeEvent};
+use futures_lite::StreamExt;
+use iroh::net_report::Report;
+use iroh::{Endpoint, Watcher};
+
+/// How well the current network supports direct connections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkCondition {
+    /// UDP works and hole punching should succeed.
+    Open,
+    /// UDP works, but the NAT maps each destination to a different port,
+    /// so direct connections often fail and fall back to a relay.
+    HardNat,
+    /// UDP is blocked; all traffic goes through a relay.
+    RelayOnly,
+    /// A captive portal intercepts traffic until the user signs in.
+    CaptivePortal,
+    /// Neither UDP nor any relay could be reached.
+    Offline,
+}
+
+impl NetworkCondition {
+    /// Classify a network report.
+    pub fn of(report: &Report) -> Self {
+        Self::classify(
+            report.has_udp(),
+            report.relay_latency.iter().next().is_some(),
+            report.mapping_varies_by_dest(),
+            report.captive_portal,
+        )
+    }
+
+    /// The most severe condition the measurements show.
+    fn classify(
+        udp: bool,
+        relay_reached: bool,
+        mapping_varies: Option<bool>,
+        captive_portal: Option<bool>,
+    ) -> Self {
+        if captive_portal == Some(true) {
+            Self::CaptivePortal
+        } else if !udp && !relay_reached {
+            Self::Offline
+        } else if !udp {
+            Self::RelayOnly
+        } else if mapping_varies == Some(true) {
+            Self::HardNat
+        } else {
+            Self::Open
+        }
+    }
+}
+
+/// The condition shown by `endpoint`'s latest network report, or None
+/// before the first report completes.
+pub fn current(endpoint: &Endpoint) -> Option<NetworkCondition> {
+    endpoint
+        .net_report()
+        .get()
+        .as_ref()
+        .map(NetworkCondition::of)
+}
+
+/// Publish changes of `endpoint`'s network condition until it closes.
+pub async fn watch(endpoint: Endpoint, events: EventBus) {
+    let mut last = None;
+    let mut reports = endpoint.net_report().stream();
+    while let Some(report) = reports.next().await {
+        let Some(condition) = report.as_ref().map(NetworkCondition::of) else {
+            continue;
+        };
+        if last != Some(condition) {
+            last = Some(condition);
+            events.emit(NodeEvent::NetworkCondition(condition));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        use NetworkCondition::*;
+        let classify = NetworkCondition::classify;
+
+        assert_eq!(classify(true, true, Some(false), Some(false)), Open);
+        assert_eq!(classify(true, false, None, None), Open);
+        assert_eq!(classify(true, true, Some(true), None), HardNat);
+        assert_eq!(classify(false, true, Some(true), None), RelayOnly);
+        assert_eq!(classify(false, false, None, None), Offline);
+        // A captive portal explains everything else
+        assert_eq!(classify(false, false, None, Some(true)), CaptivePortal);
+        assert_eq!(classify(true, true, Some(true), Some(true)), CaptivePortal);
+    }
+
+    #[test]
+    fn test_of_report() {
+        let report = Report::default();
+        assert_eq!(NetworkCondition::of(&report), NetworkCondition::Offline);
+
+        let report = Report {
+            udp_v4: true,
+            mapping_varies_by_dest_ipv4: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(NetworkCondition::of(&report), NetworkCondition::HardNat);
+
+        let report = Report {
+            captive_portal: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            NetworkCondition::of(&report),
+            NetworkCondition::CaptivePortal
+        );
+    }
+}
@@ -3,18 +3,132 @@
 //! Provides a minimal interface for blob storage and retrieval,
 //! with optional Docs (syncing key-value documents) support.
 
+use crate::access::{AccessList, AccessMode, AccessRule};
+use crate::announce::ContentAnnouncer;
+use crate::bandwidth::{Bandwidth, BandwidthLimits, NetworkKind};
+use crate::catalog::AddedTimes;
+#[cfg(feature = "chunking")]
+use crate::chunking::{self, ChunkStats};
+use crate::clock::now_micros;
+#[cfg(feature = "control")]
+use crate::control::{ControlAddr, ControlServer};
+#[cfg(feature = "docs")]
+use crate::counter;
+use crate::delivery::Delivery;
+use crate::discovery::AppDiscovery;
+#[cfg(feature = "docs")]
+use crate::durable::{DurablePeers, DurableStatus};
+use crate::events::{EventBus, NodeEvent};
+#[cfg(feature = "docs")]
+use crate::expiry;
+#[cfg(feature = "faults")]
+use crate::faults::{FaultConfig, Faults};
+#[cfg(feature = "docs")]
+use crate::filter::{self, WritePolicy};
+use crate::gateway::{self, Gateway};
+use crate::limits::RequestLimits;
+#[cfg(feature = "docs")]
+use crate::manifest::{self, ManifestAsset};
+use crate::messages::{self, MESSAGE_ALPN, Message, Messages};
+use crate::metadata::{BlobMetadata, Compression, METADATA_ENTRY};
+#[cfg(feature = "docs")]
+use crate::ndjson;
+use crate::network::{self, NetworkCondition};
+#[cfg(feature = "docs")]
+use crate::outbox::{Outbox, OutboxItem};
+use crate::peers::{PeerConnections, PeerTraffic};
+use crate::pending::{PendingTracker, PendingWork};
+use crate::profiles::{self, Ledger, ProfileStats, Reclaimed, Reclaimer};
+use crate::recovery::{self, BlobStoreRecovery, DocsStoreRecovery, StoreDatabase};
+#[cfg(feature = "docs")]
+use crate::resolver::{self, Resolve};
+use crate::shares::ExpiringTags;
+#[cfg(feature = "docs")]
+use crate::snapshot;
+#[cfg(feature = "sqlite")]
+use crate::sqlite;
+use crate::storage::{StorageAlert, StoreInlining};
+use crate::subscriptions::{CancelSwitch, Subscriptions};
+#[cfg(feature = "docs")]
+use crate::swarm::{PeerSyncStats, SwarmStats, SwarmTracker};
+use crate::testing;
+use crate::trace;
+use crate::watchdog::Watchdog;
 use anyhow::{Context, Result};
-use futures_lite::StreamExt;
-use iroh::endpoint::RelayMode;
-use iroh::{Endpoint, RelayMap, RelayUrl, protocol::Router};
+use bao_tree::io::BaoContentItem;
+use bao_tree::io::mixed::EncodedItem;
+use futures_lite::stream::Boxed;
+use futures_lite::{Stream, StreamExt};
+use iroh::discovery::UserData;
+use iroh::endpoint::{
+    Connection, ConnectionType, RelayMode, TransportConfig, VarInt, default_relay_mode,
+};
+use iroh::{
+    Endpoint, EndpointAddr, EndpointId, RelayMap, RelayUrl, SecretKey, Watcher, protocol::Router,
+};
+use iroh_blobs::api::Store;
+#[cfg(feature = "downloader-progress")]
 use iroh_blobs::api::downloader::DownloadProgressItem;
-use iroh_blobs::{ALPN as BLOBS_ALPN, BlobsProtocol, store::fs::FsStore, ticket::BlobTicket};
-use iroh_docs::protocol::Docs;
-use iroh_gossip::ALPN as GOSSIP_ALPN;
-use iroh_gossip::net::Gossip;
+use iroh_blobs::api::downloader::{DownloadOptions, SplitStrategy};
+use iroh_blobs::api::proto::{BlobStatus, TagInfo};
+use iroh_blobs::api::{TempTag, proto::AddProgressItem};
+use iroh_blobs::format::collection::Collection;
+#[cfg(feature = "downloader-progress")]
+use iroh_blobs::get::request::{GetBlobItem, get_blob};
+use iroh_blobs::hashseq::HashSeq;
+use iroh_blobs::protocol::{ChunkRanges, GetRequest, ObserveRequest};
+use iroh_blobs::store::fs::{FsStore, options::Options as StoreOptions};
+use iroh_blobs::store::mem::{MemStore, Options as MemStoreOptions};
+use iroh_blobs::{ALPN as BLOBS_ALPN, BlobsProtocol, ticket::BlobTicket};
+use iroh_blobs::{BlobFormat, Hash, HashAndFormat};
+#[cfg(feature = "docs")]
+use iroh_docs::engine::{DefaultAuthorStorage, Engine, LiveEvent, ProtectCallbackHandler};
+#[cfg(feature = "docs")]
+use iroh_docs::{
+    AuthorId, DocTicket, NamespaceId, actor::SyncHandle, api::Doc, protocol::Docs, store::Query,
+};
+#[cfg(feature = "docs")]
+use iroh_gossip::{ALPN as GOSSIP_ALPN, net::Gossip};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::net::SocketAddr;
+use std::num::NonZeroU64;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::time::Duration;
 use tokio::runtime::Runtime;
+use tokio::task::JoinSet;
+
+/// Chunk size used when streaming locally stored content to a sink.
+#[cfg(feature = "downloader-progress")]
+const SINK_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Default interval between background passes that remove expired entries.
+pub const DEFAULT_TTL_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Worker threads of a node's runtime unless configured (None for one per
+/// core). Phones mostly run nodes in the background, where a worker per
+/// core costs memory and wakeups without speeding anything up.
+#[cfg(any(target_os = "ios", target_os = "android"))]
+pub const DEFAULT_WORKER_THREADS: Option<usize> = Some(2);
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub const DEFAULT_WORKER_THREADS: Option<usize> = None;
+
+/// Blocking threads a node's runtime may start for file I/O unless
+/// configured (None for Tokio's default of 512).
+#[cfg(any(target_os = "ios", target_os = "android"))]
+pub const DEFAULT_MAX_BLOCKING_THREADS: Option<usize> = Some(16);
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub const DEFAULT_MAX_BLOCKING_THREADS: Option<usize> = None;
+
+/// How long a provider probe waits for an answer by default.
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long sending a message waits for the peer by default.
+pub const DEFAULT_MESSAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long each health check waits before reporting the node as wedged.
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Information about an Iroh node.
 pub struct NodeInfo {
@@ -24,6 +138,625 @@ pub struct NodeInfo {
     pub relay_url: Option<String>,
     /// Whether the node is connected to the network.
     pub is_connected: bool,
+    /// Identifier of the app running the node, if one was configured.
+    pub app_id: Option<String>,
+    /// How well the network supports direct connections, or None before
+    /// the first network report and on nodes without relays.
+    pub network_condition: Option<NetworkCondition>,
+}
+
+/// Error of an operation that ran out of time.
+///
+/// The operation's work has been dropped by the time this is returned, so
+/// it no longer transfers or stores anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut(pub Duration);
+
+impl std::fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Operation timed out after {} ms", self.0.as_millis())
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// Run `work` for at most `timeout_ms` milliseconds (0 = no limit).
+///
+/// Past the limit `work` is dropped before returning [`TimedOut`]. Each
+/// download runs on a downloader of its own, which aborts its transfers as
+/// soon as it is dropped with the work.
+async fn within<T>(timeout_ms: u64, work: impl Future<Output = Result<T>>) -> Result<T> {
+    if timeout_ms == 0 {
+        return work.await;
+    }
+    let timeout = Duration::from_millis(timeout_ms);
+    match tokio::time::timeout(timeout, work).await {
+        Ok(result) => result,
+        Err(_) => Err(TimedOut(timeout).into()),
+    }
+}
+
+/// How a peer was reached by a pre-flight dial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reachability {
+    /// Reached over a direct UDP path.
+    Direct {
+        /// Round-trip time of the connection.
+        latency: Duration,
+    },
+    /// Reached only through a relay so far.
+    Relay {
+        /// Round-trip time of the connection.
+        latency: Duration,
+    },
+    /// Not reached.
+    Unreachable {
+        /// Why the dial failed.
+        reason: String,
+    },
+}
+
+/// How much of a blob a provider has, as reported by a probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Availability {
+    /// The provider has the whole blob.
+    Complete {
+        /// Size of the blob in bytes.
+        size: u64,
+    },
+    /// The provider has part of the blob.
+    Partial {
+        /// Size of the blob in bytes, if the provider has verified it.
+        size: Option<u64>,
+    },
+    /// The provider has none of the blob.
+    Missing,
+    /// The provider could not be asked.
+    Unknown {
+        /// Why the probe failed.
+        reason: String,
+    },
+}
+
+/// Stage of a download reporting progress.
+#[cfg(feature = "downloader-progress")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DownloadStage {
+    /// Bytes are arriving and being verified as they do.
+    #[default]
+    Downloading,
+    /// Every byte arrived; the store is confirming the whole blob.
+    Verifying,
+    /// The blob is verified and read; the download is about to return.
+    Verified,
+}
+
+/// Progress of a download.
+#[cfg(feature = "downloader-progress")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DownloadProgress {
+    /// Bytes received from the provider so far.
+    pub received: u64,
+    /// Bytes verified against the hash and stored so far.
+    pub verified: u64,
+    /// Size of the blob in bytes (0 if unknown).
+    pub total: u64,
+    pub stage: DownloadStage,
+}
+
+/// Progress of a file import.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportProgress {
+    /// Bytes copied into the store so far.
+    pub copied: u64,
+    /// Bytes hashed so far.
+    pub hashed: u64,
+    /// Size of the file in bytes (0 until known).
+    pub total: u64,
+}
+
+/// Measured latency to one configured relay.
+pub struct RelayLatency {
+    /// The relay's URL.
+    pub url: RelayUrl,
+    /// Fastest probe to the relay, or None if it has not been reached.
+    pub latency: Option<Duration>,
+}
+
+/// Latencies to the configured relays and the relay chosen as home.
+pub struct RelayReport {
+    /// The relay this node is reachable through, if any.
+    pub home: Option<RelayUrl>,
+    /// The relay the node is pinned to, if any.
+    pub pinned: Option<RelayUrl>,
+    /// Every configured relay, fastest first, unreached relays last.
+    pub relays: Vec<RelayLatency>,
+}
+
+/// Outcome of a node health check.
+///
+/// Each field is None if the check passed, or describes what went wrong.
+#[derive(Debug, Default)]
+pub struct NodeHealth {
+    /// Problem writing to the blob store.
+    pub store: Option<String>,
+    /// Problem running tasks on the node's runtime.
+    pub runtime: Option<String>,
+    /// Problem with the endpoint's sockets.
+    pub endpoint: Option<String>,
+}
+
+impl NodeHealth {
+    /// Whether every check passed.
+    pub fn is_healthy(&self) -> bool {
+        self.store.is_none() && self.runtime.is_none() && self.endpoint.is_none()
+    }
+}
+
+/// What the node is holding on to, for matching app memory use to it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryReport {
+    /// Size on disk of the blob and document database files. This is not
+    /// memory use: it only bounds how much of the databases the store and
+    /// the OS page cache can hold in memory.
+    pub store_disk_bytes: u64,
+    /// Tasks alive on the node's runtime.
+    pub runtime_tasks: u64,
+    /// Events and messages buffered until every subscriber has them.
+    pub queued_events: u64,
+    /// Callbacks waiting for the delivery thread.
+    pub queued_callbacks: u64,
+    /// Connections peers have open to this node.
+    pub open_connections: u64,
+}
+
+/// Outcome of a time-bounded sync step.
+#[derive(Default)]
+pub struct SyncStepReport {
+    /// Whether all work finished before the budget ran out.
+    pub completed: bool,
+    /// Number of documents whose sync with known peers finished.
+    pub docs_synced: u64,
+    /// Number of entries received from peers.
+    pub entries_received: u64,
+    /// Number of missing content blobs that were downloaded.
+    pub blobs_downloaded: u64,
+}
+
+/// How much of a tag's content is shared with other tags.
+#[derive(Default)]
+pub struct ReferenceReport {
+    /// Number of distinct blobs reachable from the tag.
+    pub total_blobs: u64,
+    /// Number of those blobs also reachable from other tags.
+    pub shared_blobs: u64,
+    /// Total stored size of the tag's blobs in bytes.
+    pub total_size: u64,
+    /// Bytes that would be freed if the tag were deleted.
+    pub unique_size: u64,
+}
+
+/// A blob referenced more than once by tags or collections.
+pub struct DuplicateBlob {
+    pub hash: Hash,
+    /// Stored size in bytes.
+    pub size: u64,
+    /// Number of references, counting repeats inside one collection.
+    pub references: u64,
+    /// Names of the tags the blob is reachable from, sorted.
+    pub tags: Vec<String>,
+}
+
+impl DuplicateBlob {
+    /// Bytes that storing each reference separately would take on top.
+    pub fn saved_size(&self) -> u64 {
+        self.size * (self.references - 1)
+    }
+}
+
+/// How much identical content the store keeps only once.
+///
+/// Content is identical when it has the same hash; similar but different
+/// content is not detected.
+#[derive(Default)]
+pub struct DedupReport {
+    /// References from tags to blobs, counting repeats inside collections.
+    pub references: u64,
+    /// Distinct blobs reachable from any tag.
+    pub unique_blobs: u64,
+    /// Distinct blobs referenced more than once.
+    pub duplicate_blobs: u64,
+    /// Size of all references, as if each were stored separately.
+    pub referenced_size: u64,
+    /// Size of the distinct blobs, as stored.
+    pub stored_size: u64,
+    /// Blobs saving the most space, largest saving first.
+    pub duplicates: Vec<DuplicateBlob>,
+}
+
+impl DedupReport {
+    /// Bytes saved by storing identical content once.
+    pub fn saved_size(&self) -> u64 {
+        self.referenced_size - self.stored_size
+    }
+}
+
+/// A tag and the stored size of the content it keeps alive.
+pub struct TagSummary {
+    /// Tag name.
+    pub name: String,
+    /// Hash the tag points to.
+    pub hash: Hash,
+    /// Format of the tagged content.
+    pub format: BlobFormat,
+    /// Stored size of all blobs reachable from the tag in bytes.
+    pub size: u64,
+}
+
+/// Tags matching a prefix, with their combined size.
+#[derive(Default)]
+pub struct TagListing {
+    /// Matching tags in name order.
+    pub tags: Vec<TagSummary>,
+    /// Stored size of all blobs reachable from any matching tag, each counted once.
+    pub total_size: u64,
+}
+
+/// How to use several providers of the same content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderStrategy {
+    /// Download from whichever provider connects first, falling back to the others.
+    Race,
+    /// Split collection downloads across all providers in parallel.
+    Split,
+}
+
+/// Sort order for blob listings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobSort {
+    /// By hash, which is stable but otherwise arbitrary.
+    Hash,
+    /// Smallest blobs first.
+    SizeAscending,
+    /// Largest blobs first.
+    SizeDescending,
+    /// Most recently added first. Blobs with an unknown time come last.
+    NewestFirst,
+    /// Least recently added first. Blobs with an unknown time come last.
+    OldestFirst,
+}
+
+/// A blob in the local store.
+#[derive(Debug, Clone, Copy)]
+pub struct BlobListEntry {
+    /// Hash of the blob.
+    pub hash: Hash,
+    /// Stored size in bytes.
+    pub size: u64,
+    /// Whether the blob is fully stored.
+    pub is_complete: bool,
+    /// When the blob was added to this node (microseconds since epoch), if
+    /// known.
+    pub added_at: Option<u64>,
+}
+
+/// One page of a blob listing.
+pub struct BlobPage {
+    /// Entries in the requested order.
+    pub entries: Vec<BlobListEntry>,
+    /// Number of blobs in the store, across all pages.
+    pub total: u64,
+    /// Cursor for fetching more pages of the same listing.
+    pub cursor: u64,
+}
+
+/// Sorted snapshots of the store, kept for paging through listings.
+#[derive(Debug, Default)]
+struct BlobListings {
+    /// Cursor of the latest listing.
+    last: u64,
+    listings: BTreeMap<u64, Arc<[BlobListEntry]>>,
+}
+
+impl BlobListings {
+    /// Keep `entries` as a new listing and return its cursor, dropping the
+    /// oldest listing beyond [`MAX_BLOB_LISTINGS`].
+    fn insert(&mut self, entries: Arc<[BlobListEntry]>) -> u64 {
+        self.last += 1;
+        self.listings.insert(self.last, entries);
+        if self.listings.len() > MAX_BLOB_LISTINGS {
+            self.listings.pop_first();
+        }
+        self.last
+    }
+}
+
+/// Result of a compare-and-set document write.
+#[cfg(feature = "docs")]
+#[derive(Debug, PartialEq, Eq)]
+pub enum CasOutcome {
+    /// The value was written; holds the new content hash.
+    Written(Hash),
+    /// The current entry did not match; holds its content hash, if any.
+    Conflict(Option<Hash>),
+}
+
+/// How a document write is stored before it is reported done.
+#[cfg(feature = "docs")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Durability {
+    /// Committed together with other writes within half a second, so a
+    /// crash can lose the latest writes. Fastest for bulk imports.
+    #[default]
+    Batched,
+    /// Committed and synced to disk before the write returns.
+    Immediate,
+}
+
+/// Filter for document entry queries.
+#[cfg(feature = "docs")]
+#[derive(Debug, Default, Clone)]
+pub struct DocQuery {
+    /// Only keys starting with these bytes (empty for all keys).
+    pub key_prefix: Vec<u8>,
+    /// Only entries written at or after this time (microseconds since epoch).
+    pub timestamp_start: Option<u64>,
+    /// Only entries written before this time (microseconds since epoch).
+    pub timestamp_end: Option<u64>,
+    /// Only the winning entry per key: the latest across authors, ties
+    /// going to the lowest author ID. Keys whose winning entry is a deletion
+    /// are left out, and the timestamp range applies to the winning entry.
+    pub latest_only: bool,
+}
+
+#[cfg(feature = "docs")]
+impl DocQuery {
+    /// Whether an entry timestamp falls in the query's range.
+    pub fn contains_timestamp(&self, timestamp: u64) -> bool {
+        self.timestamp_start.is_none_or(|start| timestamp >= start)
+            && self.timestamp_end.is_none_or(|end| timestamp < end)
+    }
+
+    /// The docs store query for the key prefix; timestamps are not indexed
+    /// and must be checked with [`Self::contains_timestamp`].
+    pub fn store_query(&self) -> Query {
+        if self.latest_only {
+            Query::single_latest_per_key()
+                .key_prefix(&self.key_prefix)
+                .build()
+        } else {
+            Query::key_prefix(&self.key_prefix).build()
+        }
+    }
+}
+
+/// Options for creating a node.
+pub struct NodeOptions {
+    /// Whether to use relay servers.
+    pub relay_enabled: bool,
+    /// Custom relay URL (if None, uses n0's public relays).
+    pub custom_relay_url: Option<String>,
+    /// Whether to enable the Docs engine for syncing documents.
+    pub docs_enabled: bool,
+    /// Directory for temporary files (if None, uses the system temp dir).
+    pub temp_dir: Option<PathBuf>,
+    /// How often to remove expired document entries (if None, only on demand).
+    pub ttl_prune_interval: Option<Duration>,
+    /// Node secret key (if None, one is generated from the system RNG).
+    pub secret_key: Option<SecretKey>,
+    /// TLS session tickets to cache for resuming TLS sessions with peers
+    /// connected to before (if None, uses iroh's default of 256).
+    pub max_tls_tickets: Option<usize>,
+    /// How long a silent connection survives before it is dropped
+    /// (if None, uses QUIC's default of 30 seconds).
+    pub idle_timeout: Option<Duration>,
+    /// Maximum number of connections served to peers at once; the longest
+    /// idle one is closed to admit another (if None, unlimited).
+    pub max_connections: Option<usize>,
+    /// App-provided discovery, used next to or instead of the default.
+    pub discovery: Option<AppDiscovery>,
+    /// Whether to use n0's DNS/pkarr discovery.
+    pub default_discovery: bool,
+    /// Alert when the storage directory grows past a size (if None, no alerts).
+    pub storage_alert: Option<StorageAlert>,
+    /// Report operations running longer than a threshold (if None, none).
+    pub watchdog: Option<Watchdog>,
+    /// Serve the debug control server on a loopback `ip:port` or Unix
+    /// socket path (if None, none).
+    pub control_socket: Option<String>,
+    /// HTTPS gateway serving content by hash, used by `get` when peers
+    /// cannot be reached (if None, none).
+    pub gateway_url: Option<String>,
+    /// Who may fetch blobs from this node before any peer rules are set.
+    pub blob_access: AccessMode,
+    /// Per-peer limits on blob requests served by this node.
+    pub request_limits: RequestLimits,
+    /// Node-wide transfer bandwidth caps on Wi-Fi and cellular.
+    pub bandwidth_limits: BandwidthLimits,
+    /// Where background callbacks into the app run.
+    pub callbacks: Delivery,
+    /// Identifies the app running this node in telemetry, the control
+    /// server and discovery records (if None, none).
+    pub app_id: Option<String>,
+    /// Which blobs the store keeps in its database instead of files.
+    pub store_inlining: StoreInlining,
+    /// Periodically republish discovery records and announce selected
+    /// content (if None, records are only published when addresses change).
+    pub content_announcer: Option<ContentAnnouncer>,
+    /// Run as a deterministic test node whose key derives from this seed:
+    /// loopback only, without relays or network discovery, found by the
+    /// other test nodes of the process, with blobs and documents in memory
+    /// (if None, a normal node).
+    pub test_seed: Option<String>,
+    /// Worker threads of the node's runtime (if None,
+    /// [`DEFAULT_WORKER_THREADS`]).
+    pub worker_threads: Option<usize>,
+    /// Blocking threads the node's runtime may start for file I/O (if None,
+    /// [`DEFAULT_MAX_BLOCKING_THREADS`]).
+    pub max_blocking_threads: Option<usize>,
+    /// What to do when the blob store database is corrupt.
+    pub blob_store_recovery: BlobStoreRecovery,
+    /// What to do when the docs database is corrupt.
+    pub docs_store_recovery: DocsStoreRecovery,
+    /// Keep all of the node's data in a fresh directory under the temp dir,
+    /// removed when the node is freed; the storage path is not used.
+    pub ephemeral: bool,
+}
+
+impl Default for NodeOptions {
+    fn default() -> Self {
+        Self {
+            relay_enabled: true,
+            custom_relay_url: None,
+            docs_enabled: false,
+            temp_dir: None,
+            ttl_prune_interval: Some(DEFAULT_TTL_PRUNE_INTERVAL),
+            secret_key: None,
+            max_tls_tickets: None,
+            idle_timeout: None,
+            max_connections: None,
+            discovery: None,
+            default_discovery: true,
+            storage_alert: None,
+            watchdog: None,
+            control_socket: None,
+            gateway_url: None,
+            blob_access: AccessMode::Open,
+            request_limits: RequestLimits::default(),
+            bandwidth_limits: BandwidthLimits::default(),
+            callbacks: Delivery::default(),
+            app_id: None,
+            store_inlining: StoreInlining::default(),
+            content_announcer: None,
+            test_seed: None,
+            worker_threads: None,
+            max_blocking_threads: None,
+            blob_store_recovery: BlobStoreRecovery::default(),
+            docs_store_recovery: DocsStoreRecovery::default(),
+            ephemeral: false,
+        }
+    }
+}
+
+/// A file exported for temporary use, deleted when dropped.
+pub struct TempFile {
+    /// Directory created for this export.
+    dir: PathBuf,
+    /// Path of the exported file inside `dir`.
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Path of the exported file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Storage directory of an ephemeral node, removed when dropped.
+struct EphemeralDir(PathBuf);
+
+impl EphemeralDir {
+    /// Create a fresh directory under `temp_dir`.
+    fn create(temp_dir: &std::path::Path) -> Result<Self> {
+        let dir = temp_dir.join(format!(
+            "iroh-ephemeral-{}",
+            hex::encode(rand::random::<[u8; 8]>())
+        ));
+        std::fs::create_dir_all(&dir).context("Failed to create ephemeral storage directory")?;
+        Ok(Self(dir))
+    }
+}
+
+impl Drop for EphemeralDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Content written by health checks to prove the store accepts writes.
+const HEALTH_CHECK_CONTENT: &[u8] = b"iroh-swift health check";
+
+/// Number of blob listings kept for paging; starting another drops the
+/// oldest.
+const MAX_BLOB_LISTINGS: usize = 16;
+
+/// Parse a node ID, or a blob ticket whose address hints are used.
+fn parse_node_addr(target: &str) -> Result<EndpointAddr> {
+    match target.parse::<BlobTicket>() {
+        Ok(ticket) => Ok(ticket.addr().clone()),
+        Err(_) => Ok(EndpointAddr::new(
+            target
+                .parse::<EndpointId>()
+                .context("Expected a node ID or blob ticket")?,
+        )),
+    }
+}
+
+/// Check an app identifier and turn it into discovery user data.
+fn app_user_data(app_id: &str) -> Result<UserData> {
+    anyhow::ensure!(!app_id.is_empty(), "App identifier cannot be empty");
+    anyhow::ensure!(
+        !app_id.chars().any(char::is_control),
+        "App identifier cannot contain control characters"
+    );
+    UserData::try_from(app_id.to_string()).map_err(|_| {
+        anyhow::anyhow!(
+            "App identifier is longer than {} bytes",
+            UserData::MAX_LENGTH
+        )
+    })
+}
+
+/// Keep-alive interval of the transport settings iroh's endpoint builder
+/// starts from, which keeps NAT mappings and relay paths open.
+const IROH_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Transport settings for connections that survive `idle_timeout` of
+/// silence.
+///
+/// A transport config given to the endpoint builder replaces iroh's, so
+/// this starts from the same settings iroh uses and only changes the idle
+/// timeout. Keep-alives stay at iroh's interval, or more often for idle
+/// timeouts so short that they would otherwise drop live connections.
+fn transport_config(idle_timeout: Duration) -> Result<TransportConfig> {
+    anyhow::ensure!(!idle_timeout.is_zero(), "Idle timeout must be positive");
+    let mut transport = TransportConfig::default();
+    transport.keep_alive_interval(Some(IROH_KEEP_ALIVE_INTERVAL.min(idle_timeout / 2)));
+    transport.max_idle_timeout(Some(
+        idle_timeout.try_into().context("Invalid idle timeout")?,
+    ));
+    Ok(transport)
+}
+
+/// Build a node's runtime, with Tokio's defaults for unset thread counts.
+fn build_runtime(
+    worker_threads: Option<usize>,
+    max_blocking_threads: Option<usize>,
+) -> Result<Runtime> {
+    anyhow::ensure!(
+        worker_threads != Some(0),
+        "Worker thread count must be positive"
+    );
+    anyhow::ensure!(
+        max_blocking_threads != Some(0),
+        "Blocking thread count must be positive"
+    );
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder.enable_all();
+    if let Some(threads) = worker_threads {
+        builder.worker_threads(threads);
+    }
+    if let Some(threads) = max_blocking_threads {
+        builder.max_blocking_threads(threads);
+    }
+    builder.build().context("Failed to create Tokio runtime")
 }
 
 /// Minimal Iroh node for blob operations.
@@ -36,13 +769,80 @@ pub struct NodeInfo {
 pub struct IrohNode {
     runtime: Runtime,
     endpoint: Endpoint,
-    store: FsStore,
+    store: Store,
     router: Router,
     /// Gossip protocol for docs sync (must be kept alive for router).
+    #[cfg(feature = "docs")]
     #[allow(dead_code)]
     gossip: Option<Gossip>,
     /// Docs protocol (only if docs_enabled).
+    #[cfg(feature = "docs")]
     docs: Option<Docs>,
+    /// Replica actor behind `docs`, for signed entries the docs API hides.
+    #[cfg(feature = "docs")]
+    replicas: Option<SyncHandle>,
+    /// Directory holding the blob store and node state.
+    storage_path: PathBuf,
+    /// Directory for crate-managed temporary files.
+    temp_dir: PathBuf,
+    /// Deletes content that profiles reclaim.
+    reclaimer: Arc<Reclaimer>,
+    /// Serializes changes to profile ledgers on this node.
+    profile_lock: tokio::sync::Mutex<()>,
+    /// Expiry times of time-limited tags.
+    expiring_tags: ExpiringTags,
+    /// When blobs were added to this node.
+    added_times: Arc<AddedTimes>,
+    /// Unsynced document writes and unfinished downloads.
+    pending: PendingTracker,
+    /// Sorted snapshots of the store that later pages of listings are cut
+    /// from.
+    blob_listings: Mutex<BlobListings>,
+    /// Serializes conditional document writes on this node.
+    #[cfg(feature = "docs")]
+    doc_write_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Durability of writes to documents that do not use the default.
+    #[cfg(feature = "docs")]
+    doc_durability: Mutex<HashMap<NamespaceId, Durability>>,
+    /// Swarm statistics for documents opened on this node.
+    #[cfg(feature = "docs")]
+    swarm: SwarmTracker,
+    /// Document writes waiting for a peer (only if docs_enabled).
+    #[cfg(feature = "docs")]
+    outbox: Option<Outbox>,
+    /// Durable peers of documents; None when docs are disabled.
+    #[cfg(feature = "docs")]
+    durable: Option<DurablePeers>,
+    /// Inbound connections by remote peer.
+    peer_connections: PeerConnections,
+    /// Relays this node may choose as home.
+    relay_map: RelayMap,
+    /// Relay the node is pinned to, if any.
+    pinned_relay: Mutex<Option<RelayUrl>>,
+    /// Node-wide events for subscribers.
+    events: EventBus,
+    /// Messages received from peers.
+    messages: Messages,
+    /// Subscriptions started through the FFI, stopped when the node closes.
+    subscriptions: Subscriptions,
+    /// Gateway for fetches that fail over the network.
+    gateway: Option<Gateway>,
+    /// Peers allowed to fetch blobs from this node.
+    access: AccessList,
+    /// Paces transfers to the bandwidth caps of the current network.
+    bandwidth: Bandwidth,
+    /// Where background callbacks into the app run.
+    callbacks: Delivery,
+    /// Identifies the app running this node.
+    app_id: Option<String>,
+    /// Whether this is a deterministic test node.
+    test_node: bool,
+    /// Faults injected by tests.
+    #[cfg(feature = "faults")]
+    faults: Arc<Faults>,
+    /// Storage of an ephemeral node; last, so it is removed once the
+    /// runtime and store are gone.
+    _ephemeral: Option<EphemeralDir>,
 }
 
 impl IrohNode {
@@ -53,32 +853,209 @@ impl IrohNode {
     /// * `relay_enabled` - Whether to use relay servers
     /// * `custom_relay_url` - Optional custom relay URL (if None, uses n0's public relays)
     /// * `docs_enabled` - Whether to enable the Docs engine for syncing documents
+    #[allow(dead_code)]
     pub fn new(
         storage_path: PathBuf,
         relay_enabled: bool,
         custom_relay_url: Option<String>,
         docs_enabled: bool,
     ) -> Result<Self> {
+        Self::with_options(
+            storage_path,
+            NodeOptions {
+                relay_enabled,
+                custom_relay_url,
+                docs_enabled,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Create a new Iroh node with persistent storage and extended options.
+    pub fn with_options(storage_path: PathBuf, options: NodeOptions) -> Result<Self> {
+        let NodeOptions {
+            relay_enabled,
+            custom_relay_url,
+            docs_enabled,
+            temp_dir,
+            ttl_prune_interval,
+            secret_key,
+            max_tls_tickets,
+            idle_timeout,
+            max_connections,
+            discovery,
+            default_discovery,
+            storage_alert,
+            watchdog,
+            control_socket,
+            gateway_url,
+            blob_access,
+            request_limits,
+            bandwidth_limits,
+            callbacks,
+            app_id,
+            store_inlining,
+            content_announcer,
+            test_seed,
+            worker_threads,
+            max_blocking_threads,
+            blob_store_recovery,
+            docs_store_recovery,
+            ephemeral,
+        } = options;
+        let temp_dir = temp_dir.unwrap_or_else(std::env::temp_dir);
+        // Created first, so a node that fails to start removes it too
+        let ephemeral = ephemeral
+            .then(|| EphemeralDir::create(&temp_dir))
+            .transpose()?;
+        let storage_path = match &ephemeral {
+            Some(dir) => dir.0.clone(),
+            None => storage_path,
+        };
+        #[cfg(not(feature = "docs"))]
+        {
+            anyhow::ensure!(!docs_enabled, "Docs require building with the docs feature");
+            // Only document entries expire
+            let _ = ttl_prune_interval;
+        }
+        #[cfg(not(feature = "control"))]
+        anyhow::ensure!(
+            control_socket.is_none(),
+            "The control server requires building with the control feature"
+        );
+
+        let secret_key = match &test_seed {
+            Some(seed) => {
+                anyhow::ensure!(
+                    secret_key.is_none(),
+                    "A test node derives its secret key from the seed"
+                );
+                Some(testing::secret_key(seed))
+            }
+            None => secret_key,
+        };
+        let relay_enabled = relay_enabled && test_seed.is_none();
+        let default_discovery = default_discovery && test_seed.is_none();
+
+        let relay_mode = if !relay_enabled {
+            RelayMode::Disabled
+        } else if let Some(url) = custom_relay_url {
+            // Parse and use custom relay
+            let relay_url: RelayUrl = url.parse().context("Invalid relay URL")?;
+            RelayMode::Custom(RelayMap::from(relay_url))
+        } else {
+            // n0 public relays are default when relay_enabled=true
+            default_relay_mode()
+        };
+        // A copy, so relays removed from the endpoint by pinning stay known
+        let relay_map: RelayMap = relay_mode
+            .relay_map()
+            .relays::<Vec<_>>()
+            .into_iter()
+            .collect();
+        let gateway = gateway_url.as_deref().map(Gateway::new).transpose()?;
+        let app_user_data = app_id.as_deref().map(app_user_data).transpose()?;
+
         // Create dedicated runtime for this node
-        let runtime = Runtime::new().context("Failed to create Tokio runtime")?;
+        let runtime = build_runtime(
+            worker_threads.or(DEFAULT_WORKER_THREADS),
+            max_blocking_threads.or(DEFAULT_MAX_BLOCKING_THREADS),
+        )?;
+        let peer_connections = PeerConnections::with_limit(max_connections);
+        let events = EventBus::default();
+        let access = AccessList::new(blob_access);
+        let bandwidth = Bandwidth::new(bandwidth_limits);
+        let messages = Messages::default();
+        #[cfg(feature = "faults")]
+        let faults = Arc::new(Faults::default());
 
-        let (endpoint, store, router, gossip, docs) = runtime.block_on(async {
-            // Create or load the persistent store
-            let store = FsStore::load(&storage_path)
-                .await
-                .context("Failed to load blob store")?;
+        // A corrupt database can keep the store from ever finishing loading
+        let blobs_db = storage_path.join("blobs.db");
+        let mut reindex_from = None;
+        if let Err(corrupt) = recovery::check(StoreDatabase::Blobs, &blobs_db) {
+            let data = storage_path.join("data");
+            match blob_store_recovery {
+                BlobStoreRecovery::Fail => return Err(corrupt.into()),
+                BlobStoreRecovery::Reindex => {
+                    let dir = recovery::quarantine(&storage_path, &[&blobs_db, &data])?;
+                    reindex_from = Some(dir.join("data"));
+                }
+                BlobStoreRecovery::Quarantine => {
+                    recovery::quarantine(&storage_path, &[&blobs_db, &data])?;
+                }
+            }
+        }
+        #[cfg(feature = "docs")]
+        if docs_enabled {
+            let docs_db = storage_path.join("docs").join("docs.redb");
+            if let Err(corrupt) = recovery::check(StoreDatabase::Docs, &docs_db) {
+                match docs_store_recovery {
+                    DocsStoreRecovery::Fail => return Err(corrupt.into()),
+                    DocsStoreRecovery::Discard => {
+                        // The default author's key was kept in the database
+                        let default_author = storage_path.join("docs").join("default-author");
+                        recovery::quarantine(&storage_path, &[&docs_db, &default_author])?;
+                    }
+                }
+            }
+        }
+        #[cfg(not(feature = "docs"))]
+        let _ = docs_store_recovery;
+
+        let reclaimer = Reclaimer::new();
+        #[cfg_attr(not(feature = "docs"), allow(unused_variables))]
+        let (endpoint, store, router, gossip, docs, replicas) = runtime.block_on(async {
+            // Test nodes keep their blobs in memory; others create or load
+            // the persistent store
+            let store: Store = if test_seed.is_some() {
+                MemStore::new_with_opts(MemStoreOptions {
+                    gc_config: Some(reclaimer.gc_config()),
+                })
+                .into()
+            } else {
+                let mut store_options = StoreOptions::new(&storage_path);
+                store_options.inline = store_inlining.options();
+                store_options.gc = Some(reclaimer.gc_config());
+                FsStore::load_with_opts(blobs_db, store_options)
+                    .await
+                    .context("Failed to load blob store")?
+                    .into()
+            };
+            reclaimer.attach(store.clone());
+            if let Some(data) = &reindex_from {
+                recovery::reindex(&store, data)
+                    .await
+                    .context("Failed to reindex blob store")?;
+            }
 
             // Build endpoint with relay configuration
             let mut builder = Endpoint::builder();
-            if !relay_enabled {
-                builder = builder.relay_mode(RelayMode::Disabled);
-            } else if let Some(url) = custom_relay_url {
-                // Parse and use custom relay
-                let relay_url: RelayUrl = url.parse().context("Invalid relay URL")?;
-                let relay_map = RelayMap::from(relay_url);
-                builder = builder.relay_mode(RelayMode::Custom(relay_map));
+            if let Some(secret_key) = secret_key {
+                builder = builder.secret_key(secret_key);
+            }
+            builder = builder.relay_mode(relay_mode);
+            if !default_discovery {
+                builder = builder.clear_discovery();
+            }
+            if let Some(discovery) = discovery {
+                builder = builder.discovery(discovery);
+            }
+            if test_seed.is_some() {
+                builder = builder
+                    .discovery(testing::registry())
+                    .bind_addr_v4(testing::BIND_V4)
+                    .bind_addr_v6(testing::BIND_V6);
+            }
+            if let Some(max_tls_tickets) = max_tls_tickets {
+                builder = builder.max_tls_tickets(max_tls_tickets);
+            }
+            if let Some(user_data) = &app_user_data {
+                // Peers resolving this node see which app runs it
+                builder = builder.user_data_for_discovery(user_data.clone());
+            }
+            if let Some(idle_timeout) = idle_timeout {
+                builder = builder.transport_config(transport_config(idle_timeout)?);
             }
-            // else: n0 public relays are default when relay_enabled=true
 
             let endpoint = builder.bind().await.context("Failed to bind endpoint")?;
 
@@ -88,10 +1065,14 @@ impl IrohNode {
             }
 
             // Set up the blobs protocol handler
-            let blobs = BlobsProtocol::new(&store, None);
+            let caps_uploads = bandwidth_limits.caps_uploads();
+            let limiter = (!request_limits.is_unlimited() || caps_uploads)
+                .then(|| request_limits.spawn(events.clone(), bandwidth.clone(), caps_uploads));
+            let blobs = BlobsProtocol::new(&store, limiter);
 
             // Conditionally set up Docs protocol
-            let (gossip, docs) = if docs_enabled {
+            #[cfg(feature = "docs")]
+            let (gossip, docs, replicas) = if docs_enabled {
                 // Create gossip protocol (synchronous - returns Gossip directly)
                 let gossip = Gossip::builder().spawn(endpoint.clone());
 
@@ -104,50 +1085,291 @@ impl IrohNode {
                         .context("Failed to create docs directory")?;
                 }
 
-                // Create docs protocol using the builder pattern
-                let docs = Docs::persistent(docs_path)
-                    .spawn(endpoint.clone(), store.clone().into(), gossip.clone())
-                    .await
-                    .context("Failed to spawn docs protocol")?;
+                // Spawn the engine like `Docs::persistent` does, keeping its
+                // replica actor. Test nodes keep their documents in memory.
+                let (replica_store, author_storage) = if test_seed.is_some() {
+                    (iroh_docs::store::Store::memory(), DefaultAuthorStorage::Mem)
+                } else {
+                    (
+                        iroh_docs::store::Store::persistent(docs_path.join("docs.redb"))
+                            .context("Failed to open docs store")?,
+                        DefaultAuthorStorage::Persistent(docs_path.join("default-author")),
+                    )
+                };
+                // Profile reclaims must keep content that documents reference
+                let (protect_handler, protect) = ProtectCallbackHandler::new();
+                reclaimer.protect_docs(protect);
+                let engine = Engine::spawn(
+                    endpoint.clone(),
+                    gossip.clone(),
+                    replica_store,
+                    store.clone(),
+                    store.downloader(&endpoint),
+                    author_storage,
+                    Some(protect_handler),
+                )
+                .await
+                .context("Failed to spawn docs protocol")?;
+                let replicas = engine.sync.clone();
 
-                (Some(gossip), Some(docs))
+                (Some(gossip), Some(Docs::new(engine)), Some(replicas))
             } else {
-                (None, None)
+                (None, None, None)
             };
+            // Nothing to keep alive without docs
+            #[cfg(not(feature = "docs"))]
+            let (gossip, docs, replicas) = ((), (), ());
 
             // Build router with all protocols
-            let mut router_builder = Router::builder(endpoint.clone()).accept(BLOBS_ALPN, blobs);
+            // Each handler is tracked so connections can be closed per peer;
+            // blobs are only served to peers the access list permits
+            let router_builder = Router::builder(endpoint.clone())
+                .accept(
+                    BLOBS_ALPN,
+                    access.guard(peer_connections.track(blobs), events.clone()),
+                )
+                .accept(MESSAGE_ALPN, peer_connections.track(messages.clone()));
+
+            #[cfg(feature = "docs")]
+            let router_builder = match (&gossip, &docs) {
+                (Some(g), Some(d)) => {
+                    let d = d.clone();
+                    #[cfg(feature = "faults")]
+                    let d = faults.wrap_sync(d);
+                    router_builder
+                        .accept(GOSSIP_ALPN, peer_connections.track(g.clone()))
+                        .accept(iroh_docs::ALPN, peer_connections.track(d))
+                }
+                _ => router_builder,
+            };
+
+            let router = router_builder.spawn();
+
+            Ok::<_, anyhow::Error>((endpoint, store, router, gossip, docs, replicas))
+        })?;
+
+        runtime.spawn(events.clone().watch_connectivity(endpoint.clone()));
+        if test_seed.is_some() {
+            runtime.spawn(testing::register(endpoint.clone()));
+        }
+        // Without relays the endpoint cannot probe UDP, so reports say nothing
+        if !relay_map.is_empty() {
+            runtime.spawn(network::watch(endpoint.clone(), events.clone()));
+        }
+
+        // Remove expired entries in the background; stops with the runtime
+        #[cfg(feature = "docs")]
+        if let (Some(docs), Some(replicas), Some(interval)) =
+            (docs.clone(), replicas.clone(), ttl_prune_interval)
+        {
+            let store = store.clone();
+            let tombstones = filter::Tombstones::new(replicas, endpoint.id());
+            let events = events.clone();
+            runtime.spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticker.tick().await;
+                    // Failures are retried on the next tick
+                    if let Ok(removed) =
+                        expiry::prune_expired(&docs, &store, &tombstones, now_micros()).await
+                    {
+                        events.emit(NodeEvent::ExpiredPruned { removed });
+                    }
+                }
+            });
+        }
 
-            if let Some(ref g) = gossip {
-                router_builder = router_builder.accept(GOSSIP_ALPN, g.clone());
+        // Keep delivering writes left in the outbox; stops with the runtime
+        #[cfg(feature = "docs")]
+        let outbox = match &docs {
+            Some(docs) => {
+                let outbox = Outbox::load(storage_path.join("outbox"), events.clone())?;
+                let _guard = runtime.enter();
+                outbox.resume(docs);
+                Some(outbox)
             }
+            None => None,
+        };
 
-            if let Some(ref d) = docs {
-                router_builder = router_builder.accept(iroh_docs::ALPN, d.clone());
+        // Keep syncing with durable peers; stops with the runtime
+        #[cfg(feature = "docs")]
+        let durable = match &docs {
+            Some(docs) => {
+                let durable = DurablePeers::load(storage_path.join("durable"), events.clone())?;
+                let _guard = runtime.enter();
+                durable.resume(docs);
+                Some(durable)
             }
+            None => None,
+        };
 
-            let router = router_builder.spawn();
+        // Measure the store in the background; stops with the runtime
+        if let Some(alert) = storage_alert {
+            runtime.spawn(alert.run(storage_path.clone()));
+        }
 
-            Ok::<_, anyhow::Error>((endpoint, store, router, gossip, docs))
-        })?;
+        // Watch for slow operations; stops with the runtime
+        if let Some(watchdog) = watchdog {
+            runtime.spawn(watchdog.run(events.clone()));
+        }
+
+        // Remove time-limited tags as they expire; stops with the runtime
+        let expiring_tags = ExpiringTags::load(storage_path.join("tag-expiry"))?;
+        runtime.spawn(expiring_tags.clone().run(store.clone(), events.clone()));
+        let added_times = Arc::new(AddedTimes::load(storage_path.join("blob-added"))?);
+        let pending = PendingTracker::load(&storage_path)?;
+
+        // Keep content discoverable; stops with the runtime
+        if let Some(announcer) = content_announcer {
+            runtime.spawn(announcer.run(
+                endpoint.clone(),
+                store.clone(),
+                app_user_data,
+                events.clone(),
+            ));
+        }
+
+        // Serve the debug control server; stops with the runtime
+        #[cfg(feature = "control")]
+        if let Some(addr) = control_socket {
+            let addr = ControlAddr::parse(&addr)?;
+            let server = ControlServer::new(
+                endpoint.clone(),
+                store.clone(),
+                #[cfg(feature = "docs")]
+                docs.clone(),
+                app_id.clone(),
+            );
+            runtime.block_on(server.spawn(&addr, &events))?;
+        }
 
         Ok(Self {
             runtime,
             endpoint,
             store,
             router,
+            #[cfg(feature = "docs")]
             gossip,
+            #[cfg(feature = "docs")]
             docs,
+            #[cfg(feature = "docs")]
+            replicas,
+            storage_path,
+            temp_dir,
+            reclaimer,
+            profile_lock: tokio::sync::Mutex::new(()),
+            expiring_tags,
+            added_times,
+            pending,
+            blob_listings: Mutex::default(),
+            #[cfg(feature = "docs")]
+            doc_write_lock: Arc::new(tokio::sync::Mutex::new(())),
+            #[cfg(feature = "docs")]
+            doc_durability: Mutex::default(),
+            #[cfg(feature = "docs")]
+            swarm: SwarmTracker::default(),
+            #[cfg(feature = "docs")]
+            outbox,
+            #[cfg(feature = "docs")]
+            durable,
+            peer_connections,
+            relay_map,
+            pinned_relay: Mutex::new(None),
+            events,
+            messages,
+            subscriptions: Subscriptions::default(),
+            gateway,
+            access,
+            bandwidth,
+            callbacks,
+            app_id,
+            test_node: test_seed.is_some(),
+            #[cfg(feature = "faults")]
+            faults,
+            _ephemeral: ephemeral,
         })
     }
 
+    /// Directory holding the blob store and node state.
+    pub fn storage_path(&self) -> &std::path::Path {
+        &self.storage_path
+    }
+
+    /// Identifier of the app running this node, if one was configured.
+    pub fn app_id(&self) -> Option<&str> {
+        self.app_id.as_deref()
+    }
+
+    /// Address to put in tickets for content on this node.
+    ///
+    /// Test nodes leave out their addresses, which change between runs;
+    /// other test nodes resolve them.
+    pub fn ticket_addr(&self) -> EndpointAddr {
+        if self.test_node {
+            testing::ticket_addr(self.endpoint.id())
+        } else {
+            self.endpoint.addr()
+        }
+    }
+
+    /// Replace the faults injected into this node.
+    ///
+    /// Puts fail while `fail_puts` lasts, downloads start after
+    /// `download_delay`, and peers starting a document sync are dropped at
+    /// `sync_drop_percent`. The default config injects nothing.
+    #[cfg(feature = "faults")]
+    pub fn inject_faults(&self, config: FaultConfig) -> Result<()> {
+        self.faults.set(config)
+    }
+
+    /// Fail if a put failure has been injected.
+    fn check_put(&self) -> Result<()> {
+        #[cfg(feature = "faults")]
+        self.faults.check_put()?;
+        Ok(())
+    }
+
+    /// Run `download` for `content` from `providers`, publishing when it
+    /// starts and ends.
+    ///
+    /// The download counts as pending work until it succeeds, so
+    /// [`IrohNode::sync_step`] resumes it if the node stops first. Content
+    /// that arrives gets an add time for blob listings.
+    async fn download<T, E: std::fmt::Display>(
+        &self,
+        content: HashAndFormat,
+        providers: &[EndpointId],
+        download: impl IntoFuture<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        self.pending.download_started(content, providers);
+        let result = self
+            .events
+            .download(content.hash, async {
+                #[cfg(feature = "faults")]
+                self.faults.delay_download().await;
+                download.await
+            })
+            .await;
+        if result.is_ok() {
+            self.pending.download_finished(content.hash);
+            // A lost add time only moves the blob to the end of listings
+            let _ = self.added_times.record(content.hash);
+        }
+        result
+    }
+
     /// Check if docs support is enabled.
     #[allow(dead_code)]
     pub fn is_docs_enabled(&self) -> bool {
-        self.docs.is_some()
+        #[cfg(feature = "docs")]
+        return self.docs.is_some();
+        #[cfg(not(feature = "docs"))]
+        false
     }
 
     /// Get the docs protocol if enabled.
+    #[cfg(feature = "docs")]
     pub fn docs(&self) -> Option<&Docs> {
         self.docs.as_ref()
     }
@@ -157,8 +1379,13 @@ impl IrohNode {
         &self.runtime
     }
 
+    /// Where background callbacks into the app run.
+    pub fn callbacks(&self) -> &Delivery {
+        &self.callbacks
+    }
+
     /// Get a reference to the store for content operations.
-    pub fn store(&self) -> &FsStore {
+    pub fn store(&self) -> &Store {
         &self.store
     }
 
@@ -172,15 +1399,18 @@ impl IrohNode {
     /// The ticket can be used by other nodes to download the blob.
     pub fn put(&self, data: &[u8]) -> Result<String> {
         self.runtime.block_on(async {
+            self.check_put()?;
+            trace::stage("import");
             // Add the bytes to the store
             let tag = self
                 .store
                 .add_slice(data)
                 .await
                 .context("Failed to add bytes to store")?;
+            self.added_times.record(tag.hash)?;
 
             // Get our network address for the ticket
-            let addr = self.endpoint.addr();
+            let addr = self.ticket_addr();
 
             // Create a ticket that others can use to download
             let ticket = BlobTicket::new(addr, tag.hash, tag.format);
@@ -189,23 +1419,77 @@ impl IrohNode {
         })
     }
 
+    /// Import the file at `path` and return a shareable ticket, like
+    /// [`Self::put`].
+    ///
+    /// The file is streamed from disk rather than read into memory, so large
+    /// media can be shared. The content is copied into the store, so the
+    /// file may change or be deleted afterwards. Progress is reported while
+    /// the file is copied and hashed.
+    pub fn put_file<F>(&self, path: &std::path::Path, on_progress: F) -> Result<String>
+    where
+        F: FnMut(ImportProgress),
+    {
+        self.runtime.block_on(async {
+            let (tag, _) = self.import_file(path, on_progress).await?;
+            // Keep the content once the temporary tag is dropped, as puts do
+            self.store
+                .tags()
+                .create(tag.hash_and_format())
+                .await
+                .context("Failed to tag imported file")?;
+            self.added_times.record(tag.hash())?;
+            let ticket = BlobTicket::new(self.ticket_addr(), tag.hash(), tag.format());
+            Ok(ticket.to_string())
+        })
+    }
+
+    /// Import the file at `path` as a raw blob, reporting progress, and
+    /// return its temporary tag and size.
+    async fn import_file<F>(
+        &self,
+        path: &std::path::Path,
+        mut on_progress: F,
+    ) -> Result<(TempTag, u64)>
+    where
+        F: FnMut(ImportProgress),
+    {
+        self.check_put()?;
+        trace::stage("import");
+        // The store only imports absolute paths
+        let path = std::path::absolute(path).context("Invalid file path")?;
+        let stream = self.store.add_path(path).stream().await;
+        let mut stream = std::pin::pin!(stream);
+        let mut progress = ImportProgress::default();
+        while let Some(item) = stream.next().await {
+            match item {
+                AddProgressItem::Size(size) => progress.total = size,
+                AddProgressItem::CopyProgress(offset) => progress.copied = offset,
+                AddProgressItem::CopyDone => progress.copied = progress.total,
+                AddProgressItem::OutboardProgress(offset) => progress.hashed = offset,
+                AddProgressItem::Done(tag) => {
+                    progress.hashed = progress.total;
+                    on_progress(progress);
+                    return Ok((tag, progress.total));
+                }
+                AddProgressItem::Error(e) => return Err(e).context("Failed to import file"),
+            }
+            on_progress(progress);
+        }
+        anyhow::bail!("File import ended without a result")
+    }
+
     /// Download bytes from a ticket.
     ///
-    /// This fetches the blob from the remote peer specified in the ticket.
+    /// This fetches the blob from the remote peer specified in the ticket,
+    /// unless the blob is already complete in the local store.
     pub fn get(&self, ticket_str: &str) -> Result<Vec<u8>> {
         self.runtime.block_on(async {
             // Parse the ticket
             let ticket: BlobTicket = ticket_str.parse().context("Failed to parse ticket")?;
 
-            // Create a downloader for fetching from remote peers
-            let downloader = self.store.downloader(&self.endpoint);
-
             // Download the blob (if not already present locally)
-            // ContentDiscovery is implemented for sequences of NodeId
-            downloader
-                .download(ticket.hash(), [ticket.addr().id])
-                .await
-                .context("Failed to download blob")?;
+            self.download_ticket(&ticket).await?;
 
             // Read the bytes from local store
             let bytes = self
@@ -220,52 +1504,99 @@ impl IrohNode {
 
     /// Download bytes from a ticket with progress reporting.
     ///
-    /// The progress callback is called with (downloaded, total) byte counts.
-    /// Note: total may be 0 if the size is unknown.
+    /// Progress is reported as bytes arrive and as the store verifies them
+    /// against the hash. Once the transfer ends, a [`DownloadStage::Verifying`]
+    /// update is sent while the store confirms the whole blob, and a final
+    /// [`DownloadStage::Verified`] update once the bytes are read back.
+    #[cfg(feature = "downloader-progress")]
     pub fn get_with_progress<F>(&self, ticket_str: &str, mut on_progress: F) -> Result<Vec<u8>>
     where
-        F: FnMut(u64, u64),
+        F: FnMut(DownloadProgress),
     {
         self.runtime.block_on(async {
             // Parse the ticket
             let ticket: BlobTicket = ticket_str.parse().context("Failed to parse ticket")?;
+            let hash = ticket.hash();
 
             // Create a downloader for fetching from remote peers
             let downloader = self.store.downloader(&self.endpoint);
 
             // Download the blob with progress tracking
-            let download = downloader.download(ticket.hash(), [ticket.addr().id]);
-            let mut stream = download
-                .stream()
-                .await
-                .context("Failed to start download")?;
+            let download = downloader.download(hash, [ticket.addr().id]);
+            let mut progress = DownloadProgress::default();
+            self.download(HashAndFormat::raw(hash), &[ticket.addr().id], async {
+                let mut stream = download
+                    .stream()
+                    .await
+                    .context("Failed to start download")?;
+                // Chunks only reach the store once verified, so its
+                // bitfield tracks the verified bytes
+                let verified = self
+                    .store
+                    .observe(hash)
+                    .stream()
+                    .await
+                    .context("Failed to observe download")?;
+                let mut verified = std::pin::pin!(verified);
+                let mut observing = true;
 
-            // Process progress events
-            while let Some(item) = stream.next().await {
-                match item {
-                    DownloadProgressItem::Progress(bytes) => {
-                        // Total is not directly available from progress events
-                        on_progress(bytes, 0);
-                    }
-                    DownloadProgressItem::PartComplete { .. } => {
-                        // Part of the download completed
-                    }
-                    DownloadProgressItem::Error(e) => {
-                        return Err(anyhow::anyhow!("Download error: {:?}", e));
+                loop {
+                    tokio::select! {
+                        item = stream.next() => match item {
+                            Some(DownloadProgressItem::Progress(bytes)) => {
+                                self.bandwidth
+                                    .pace_download(bytes.saturating_sub(progress.received))
+                                    .await;
+                                progress.received = bytes;
+                            }
+                            Some(DownloadProgressItem::Error(e)) => {
+                                return Err(anyhow::anyhow!("Download error: {:?}", e));
+                            }
+                            Some(DownloadProgressItem::DownloadError) => {
+                                return Err(anyhow::anyhow!("Download failed"));
+                            }
+                            Some(_) => continue,
+                            None => break,
+                        },
+                        bitfield = verified.next(), if observing => match bitfield {
+                            Some(bitfield) => {
+                                progress.verified = bitfield.total_bytes();
+                                progress.total = bitfield.size();
+                            }
+                            None => {
+                                observing = false;
+                                continue;
+                            }
+                        },
                     }
-                    DownloadProgressItem::DownloadError => {
-                        return Err(anyhow::anyhow!("Download failed"));
-                    }
-                    _ => {}
+                    on_progress(progress);
                 }
-            }
+                Ok(())
+            })
+            .await?;
+
+            // Every byte has arrived; wait for the store to confirm the blob
+            progress.stage = DownloadStage::Verifying;
+            on_progress(progress);
+            self.store
+                .observe(hash)
+                .await_completion()
+                .await
+                .context("Failed to verify download")?;
 
             // Read the bytes from local store
             let bytes = self
                 .store
-                .get_bytes(ticket.hash())
+                .get_bytes(hash)
                 .await
                 .context("Failed to read bytes from store")?;
+            let size = bytes.len() as u64;
+            on_progress(DownloadProgress {
+                received: progress.received.max(size),
+                verified: size,
+                total: size,
+                stage: DownloadStage::Verified,
+            });
 
             Ok(bytes.to_vec())
         })
@@ -273,129 +1604,5952 @@ impl IrohNode {
 
     /// Add bytes to the blob store with an optional timeout.
     ///
+    /// Fails with [`TimedOut`] if the import does not finish in time.
+    ///
     /// # Arguments
     /// * `data` - The bytes to store
     /// * `timeout_ms` - Timeout in milliseconds (0 = no timeout)
     pub fn put_with_timeout(&self, data: &[u8], timeout_ms: u64) -> Result<String> {
-        self.runtime.block_on(async {
-            let fut = async {
-                let tag = self
-                    .store
-                    .add_slice(data)
-                    .await
-                    .context("Failed to add bytes to store")?;
-
-                let addr = self.endpoint.addr();
-                let ticket = BlobTicket::new(addr, tag.hash, tag.format);
-                Ok::<_, anyhow::Error>(ticket.to_string())
-            };
+        self.runtime.block_on(within(timeout_ms, async {
+            self.check_put()?;
+            trace::stage("import");
+            let tag = self
+                .store
+                .add_slice(data)
+                .await
+                .context("Failed to add bytes to store")?;
+            self.added_times.record(tag.hash)?;
 
-            if timeout_ms == 0 {
-                fut.await
-            } else {
-                tokio::time::timeout(Duration::from_millis(timeout_ms), fut)
-                    .await
-                    .context("Operation timed out")?
-            }
-        })
+            let addr = self.ticket_addr();
+            let ticket = BlobTicket::new(addr, tag.hash, tag.format);
+            Ok(ticket.to_string())
+        }))
     }
 
     /// Download bytes from a ticket with an optional timeout.
     ///
+    /// Fails with [`TimedOut`] if the download does not finish in time,
+    /// after stopping the transfer.
+    ///
     /// # Arguments
     /// * `ticket_str` - The ticket string
     /// * `timeout_ms` - Timeout in milliseconds (0 = no timeout)
     pub fn get_with_timeout(&self, ticket_str: &str, timeout_ms: u64) -> Result<Vec<u8>> {
+        self.runtime.block_on(within(timeout_ms, async {
+            let ticket: BlobTicket = ticket_str.parse().context("Failed to parse ticket")?;
+            self.download_ticket(&ticket).await?;
+
+            let bytes = self
+                .store
+                .get_bytes(ticket.hash())
+                .await
+                .context("Failed to read bytes from store")?;
+
+            Ok(bytes.to_vec())
+        }))
+    }
+
+    /// Download a ticket's content into the store from its provider.
+    ///
+    /// Raw blobs already complete in the store are served as they are,
+    /// without dialing the provider, so they stay readable offline. If the
+    /// download fails and a gateway is configured, raw blobs are fetched
+    /// from the gateway instead and verified against the ticket's hash.
+    async fn download_ticket(&self, ticket: &BlobTicket) -> Result<()> {
+        if ticket.format() == BlobFormat::Raw && self.store.blobs().has(ticket.hash()).await? {
+            return Ok(());
+        }
+
+        // ContentDiscovery is implemented for sequences of NodeId
+        let downloader = self.store.downloader(&self.endpoint);
+        let result = self
+            .download(
+                HashAndFormat::raw(ticket.hash()),
+                &[ticket.addr().id],
+                self.bandwidth
+                    .paced(downloader.download(ticket.hash(), [ticket.addr().id])),
+            )
+            .await
+            .context("Failed to download blob");
+        let (Err(e), Some(gateway)) = (&result, &self.gateway) else {
+            return result;
+        };
+        if ticket.format() != BlobFormat::Raw {
+            return result;
+        }
+
+        trace::stage("gateway");
+        let bytes = gateway
+            .fetch(ticket.hash(), gateway::MAX_RESPONSE_SIZE)
+            .await
+            .map_err(|gateway_error| anyhow::anyhow!("{e:#}; {gateway_error:#}"))?;
+        self.store
+            .add_bytes(bytes)
+            .await
+            .context("Failed to add gateway content to store")?;
+        self.pending.download_finished(ticket.hash());
+        let _ = self.added_times.record(ticket.hash());
+        Ok(())
+    }
+
+    /// Stream a blob's verified content to `on_chunk` as it arrives.
+    ///
+    /// Chunks are delivered in order. Content already stored locally is read
+    /// from the store; otherwise it is fetched from the ticket's provider
+    /// without being written to the store. Returning `false` from `on_chunk`
+    /// aborts the download. Returns the total number of bytes delivered.
+    #[cfg(feature = "downloader-progress")]
+    pub fn get_to_sink<F>(&self, ticket_str: &str, mut on_chunk: F) -> Result<u64>
+    where
+        F: FnMut(&[u8]) -> bool,
+    {
         self.runtime.block_on(async {
-            let fut = async {
-                let ticket: BlobTicket = ticket_str.parse().context("Failed to parse ticket")?;
-                let downloader = self.store.downloader(&self.endpoint);
+            let ticket: BlobTicket = ticket_str.parse().context("Failed to parse ticket")?;
+            if ticket.format() != BlobFormat::Raw {
+                anyhow::bail!("Sink downloads only support raw blobs");
+            }
 
-                downloader
-                    .download(ticket.hash(), [ticket.addr().id])
-                    .await
-                    .context("Failed to download blob")?;
+            let mut written = 0u64;
 
+            if self.store.blobs().has(ticket.hash()).await? {
                 let bytes = self
                     .store
                     .get_bytes(ticket.hash())
                     .await
                     .context("Failed to read bytes from store")?;
+                for chunk in bytes.chunks(SINK_CHUNK_SIZE) {
+                    if !on_chunk(chunk) {
+                        anyhow::bail!("Download cancelled by sink");
+                    }
+                    written += chunk.len() as u64;
+                }
+                return Ok(written);
+            }
 
-                Ok::<_, anyhow::Error>(bytes.to_vec())
-            };
-
-            if timeout_ms == 0 {
-                fut.await
-            } else {
-                tokio::time::timeout(Duration::from_millis(timeout_ms), fut)
+            let conn = self
+                .endpoint
+                .connect(ticket.addr().clone(), BLOBS_ALPN)
+                .await
+                .context("Failed to connect to provider")?;
+            let mut stream = get_blob(conn, ticket.hash());
+            while let Some(item) = stream.next().await {
+                match item {
+                    GetBlobItem::Item(BaoContentItem::Leaf(leaf)) => {
+                        self.bandwidth.pace_download(leaf.data.len() as u64).await;
+                        if !on_chunk(&leaf.data) {
+                            anyhow::bail!("Download cancelled by sink");
+                        }
+                        written += leaf.data.len() as u64;
+                    }
+                    GetBlobItem::Item(_) => {}
+                    GetBlobItem::Done(_) => return Ok(written),
+                    GetBlobItem::Error(e) => {
+                        return Err(anyhow::Error::new(e).context("Download error"));
+                    }
+                }
+            }
+            anyhow::bail!("Download ended unexpectedly")
+        })
+    }
+
+    /// Export a local blob to a temporary file that is deleted on drop.
+    ///
+    /// Each export gets its own directory under the node's temp dir so the
+    /// file can keep a meaningful name (e.g., for QuickLook). If `filename`
+    /// is None, the hash is used as the name.
+    pub fn export_temp_file(&self, hash: Hash, filename: Option<&str>) -> Result<TempFile> {
+        let name = filename.unwrap_or_default();
+        let name = std::path::Path::new(name)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| hash.to_string());
+
+        let dir = self.temp_dir.join(format!(
+            "iroh-export-{}",
+            hex::encode(rand::random::<[u8; 8]>())
+        ));
+        std::fs::create_dir_all(&dir).context("Failed to create temp directory")?;
+        let file = TempFile {
+            path: dir.join(name),
+            dir,
+        };
+
+        self.runtime.block_on(async {
+            self.store
+                .blobs()
+                .export(hash, &file.path)
+                .await
+                .context("Failed to export blob")
+        })?;
+
+        Ok(file)
+    }
+
+    /// Copy local blobs into another store directory.
+    ///
+    /// The blobs are streamed in bao format so the destination verifies
+    /// every chunk against the hash. Each copied blob gets an automatic tag
+    /// in the destination, as if it had been put there. Returns the number
+    /// of bytes copied.
+    pub fn copy_to_store(&self, dst_path: &std::path::Path, hashes: &[Hash]) -> Result<u64> {
+        self.runtime.block_on(async {
+            let dst = FsStore::load(dst_path)
+                .await
+                .context("Failed to open destination store")?;
+            let result = self.copy_blobs(&dst, hashes).await;
+            dst.shutdown()
+                .await
+                .context("Failed to close destination store")?;
+            result
+        })
+    }
+
+    /// Stream each blob into `dst` and tag it there.
+    async fn copy_blobs(&self, dst: &Store, hashes: &[Hash]) -> Result<u64> {
+        let mut copied = 0;
+        for &hash in hashes {
+            // The store does not track the empty blob, which needs no data anyway
+            let size = match self.store.blobs().status(hash).await? {
+                _ if hash == Hash::EMPTY => 0,
+                BlobStatus::Complete { size } => size,
+                _ => anyhow::bail!("Blob not complete in source store: {hash}"),
+            };
+            if let Some(size) = NonZeroU64::new(size) {
+                let import = dst
+                    .blobs()
+                    .import_bao(hash, size, 32)
+                    .await
+                    .context("Failed to start import")?;
+                let mut items = self
+                    .store
+                    .blobs()
+                    .export_bao(hash, ChunkRanges::all())
+                    .stream();
+                while let Some(item) = items.next().await {
+                    let item = match item {
+                        EncodedItem::Size(_) => continue,
+                        EncodedItem::Parent(parent) => BaoContentItem::Parent(parent),
+                        EncodedItem::Leaf(leaf) => BaoContentItem::Leaf(leaf),
+                        EncodedItem::Done => break,
+                        EncodedItem::Error(e) => {
+                            return Err(anyhow::Error::new(e).context("Failed to read blob"));
+                        }
+                    };
+                    import.tx.send(item).await.context("Failed to write blob")?;
+                }
+                drop(import.tx);
+                import
+                    .rx
                     .await
-                    .context("Operation timed out")?
+                    .context("Failed to write blob")?
+                    .with_context(|| format!("Verification failed for {hash}"))?;
+            }
+            dst.tags()
+                .create(HashAndFormat::raw(hash))
+                .await
+                .context("Failed to tag copied blob")?;
+            copied += size;
+        }
+        Ok(copied)
+    }
+
+    /// Download content that is available from several providers.
+    ///
+    /// All tickets must refer to the same hash. With [`ProviderStrategy::Race`]
+    /// every provider is dialed at once and the content is fetched over the
+    /// first connection accepted, with the rest kept as fallbacks should it
+    /// fail. With [`ProviderStrategy::Split`]
+    /// the children of a collection are fetched from the providers in parallel;
+    /// raw blobs fall back to trying providers in order.
+    pub fn get_from_providers(
+        &self,
+        ticket_strs: &[String],
+        strategy: ProviderStrategy,
+    ) -> Result<Vec<u8>> {
+        self.runtime.block_on(async {
+            let tickets = ticket_strs
+                .iter()
+                .map(|t| t.parse::<BlobTicket>().context("Failed to parse ticket"))
+                .collect::<Result<Vec<_>>>()?;
+            let first = tickets.first().context("At least one ticket is required")?;
+            let content = first.hash_and_format();
+            if let Some(other) = tickets.iter().find(|t| t.hash() != content.hash) {
+                anyhow::bail!(
+                    "Tickets refer to different content: {} and {}",
+                    content.hash,
+                    other.hash()
+                );
             }
+
+            let mut addrs: Vec<EndpointAddr> = Vec::with_capacity(tickets.len());
+            for ticket in &tickets {
+                if !addrs.iter().any(|a| a.id == ticket.addr().id) {
+                    addrs.push(ticket.addr().clone());
+                }
+            }
+
+            let (providers, split, winner) = match strategy {
+                ProviderStrategy::Race => {
+                    let (providers, winner) = self.race_providers(addrs).await;
+                    (providers, SplitStrategy::None, winner)
+                }
+                ProviderStrategy::Split => (
+                    addrs.iter().map(|a| a.id).collect(),
+                    SplitStrategy::Split,
+                    None,
+                ),
+            };
+
+            let downloader = self.store.downloader(&self.endpoint);
+            self.download(content, &providers, async {
+                // Use the connection that won the race instead of dialing again
+                if let Some(connection) = winner {
+                    let fetch = self.store.remote().fetch(connection, content);
+                    if self.bandwidth.paced_get(fetch).await.is_ok() {
+                        return Ok(());
+                    }
+                }
+                self.bandwidth
+                    .paced(downloader.download_with_opts(DownloadOptions::new(
+                        content,
+                        providers.clone(),
+                        split,
+                    )))
+                    .await
+            })
+            .await
+            .context("Failed to download blob")?;
+
+            let bytes = self
+                .store
+                .get_bytes(content.hash)
+                .await
+                .context("Failed to read bytes from store")?;
+
+            Ok(bytes.to_vec())
         })
     }
 
-    /// Get information about this node.
-    pub fn info(&self) -> Result<NodeInfo> {
+    /// Dial a peer without transferring content and report how it was reached.
+    ///
+    /// `target` is a node ID or a blob ticket, whose address hints are used.
+    /// The connection is closed right after the handshake. Fails only if
+    /// `target` cannot be parsed; dial failures and timeouts are reported
+    /// as `Reachability::Unreachable`.
+    pub fn can_reach(&self, target: &str, timeout: Option<Duration>) -> Result<Reachability> {
+        let addr = parse_node_addr(target)?;
+
         self.runtime.block_on(async {
-            // Get node ID from endpoint
-            let node_id = self.endpoint.id().to_string();
+            let dial = self.endpoint.connect(addr, BLOBS_ALPN);
+            let result = match timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, dial).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        return Ok(Reachability::Unreachable {
+                            reason: format!("No connection within {} ms", timeout.as_millis()),
+                        });
+                    }
+                },
+                None => dial.await,
+            };
+            let connection = match result {
+                Ok(connection) => connection,
+                Err(e) => {
+                    return Ok(Reachability::Unreachable {
+                        reason: format!("{:#}", anyhow::Error::new(e)),
+                    });
+                }
+            };
 
-            // Get address info which includes relay
-            let addr = self.endpoint.addr();
-            // Get the first relay URL if any
-            let relay_url = addr.relay_urls().next().map(|url| url.to_string());
+            let latency = connection.rtt();
+            let path = self
+                .endpoint
+                .conn_type(connection.remote_id())
+                .map(|mut conn_type| conn_type.get());
+            connection.close(VarInt::from_u32(0), b"reachability check");
+            // Mixed paths have not confirmed the direct address yet
+            Ok(match path {
+                Some(ConnectionType::Direct(_)) => Reachability::Direct { latency },
+                _ => Reachability::Relay { latency },
+            })
+        })
+    }
 
-            // A node is considered connected if it has a relay URL or IP addresses
-            let is_connected = relay_url.is_some() || addr.ip_addrs().next().is_some();
+    /// Ask each provider how much of `hash` it has, without downloading it.
+    ///
+    /// Providers are probed concurrently; results are in the order of
+    /// `providers`. A provider that cannot be reached, refuses the request
+    /// or does not answer within `timeout` (default 10 seconds) is reported
+    /// as [`Availability::Unknown`].
+    pub fn probe_providers(
+        &self,
+        hash: Hash,
+        providers: &[EndpointId],
+        timeout: Option<Duration>,
+    ) -> Vec<(EndpointId, Availability)> {
+        let timeout = timeout.unwrap_or(DEFAULT_PROBE_TIMEOUT);
+        self.runtime.block_on(async {
+            let mut probes = JoinSet::new();
+            for (index, &provider) in providers.iter().enumerate() {
+                let endpoint = self.endpoint.clone();
+                let remote = self.store.remote().clone();
+                probes.spawn(async move {
+                    let probe = async {
+                        let connection = endpoint
+                            .connect(EndpointAddr::new(provider), BLOBS_ALPN)
+                            .await?;
+                        // The first item is the provider's current bitfield
+                        let mut updates = std::pin::pin!(
+                            remote.observe(connection.clone(), ObserveRequest::new(hash))
+                        );
+                        let bitfield = updates
+                            .next()
+                            .await
+                            .context("Provider closed the request")??;
+                        connection.close(VarInt::from_u32(0), b"probe");
+                        anyhow::Ok(bitfield)
+                    };
+                    let availability = match tokio::time::timeout(timeout, probe).await {
+                        Ok(Ok(bitfield)) if bitfield.is_complete() => Availability::Complete {
+                            size: bitfield.size(),
+                        },
+                        Ok(Ok(bitfield)) if bitfield.ranges.is_empty() => Availability::Missing,
+                        Ok(Ok(bitfield)) => Availability::Partial {
+                            size: bitfield.validated_size(),
+                        },
+                        Ok(Err(e)) => Availability::Unknown {
+                            reason: format!("{e:#}"),
+                        },
+                        Err(_) => Availability::Unknown {
+                            reason: format!("No answer within {} ms", timeout.as_millis()),
+                        },
+                    };
+                    (index, availability)
+                });
+            }
 
-            Ok(NodeInfo {
-                node_id,
-                relay_url,
-                is_connected,
+            let mut results = vec![None; providers.len()];
+            while let Some(Ok((index, availability))) = probes.join_next().await {
+                results[index] = Some(availability);
+            }
+            providers
+                .iter()
+                .zip(results)
+                .map(|(&provider, availability)| {
+                    let availability = availability.unwrap_or_else(|| Availability::Unknown {
+                        reason: "Probe failed".to_string(),
+                    });
+                    (provider, availability)
+                })
+                .collect()
+        })
+    }
+
+    /// Order providers so the first to accept a blobs connection comes first,
+    /// and return that connection.
+    ///
+    /// The remaining providers keep their original order as fallbacks.
+    async fn race_providers(
+        &self,
+        addrs: Vec<EndpointAddr>,
+    ) -> (Vec<EndpointId>, Option<Connection>) {
+        let mut ids: Vec<EndpointId> = addrs.iter().map(|a| a.id).collect();
+        if ids.len() < 2 {
+            return (ids, None);
+        }
+
+        let mut attempts = JoinSet::new();
+        for addr in addrs {
+            let endpoint = self.endpoint.clone();
+            attempts.spawn(async move {
+                let id = addr.id;
+                endpoint
+                    .connect(addr, BLOBS_ALPN)
+                    .await
+                    .map(|connection| (id, connection))
+            });
+        }
+
+        // Dropping the set aborts the slower attempts
+        while let Some(result) = attempts.join_next().await {
+            if let Ok(Ok((winner, connection))) = result {
+                ids.retain(|id| *id != winner);
+                ids.insert(0, winner);
+                return (ids, Some(connection));
+            }
+        }
+        (ids, None)
+    }
+
+    /// Add bytes together with a metadata record and return a shareable ticket.
+    ///
+    /// The content and metadata are wrapped in a collection, so the ticket
+    /// has the `HashSeq` format. A `created_at` of 0 is replaced with the
+    /// current time. Content is compressed as `metadata.compression` asks,
+    /// unless that would not make it smaller, in which case the record
+    /// says it is stored as is.
+    pub fn put_with_metadata(&self, data: &[u8], metadata: &BlobMetadata) -> Result<String> {
+        let mut metadata = metadata.clone();
+        if metadata.created_at == 0 {
+            metadata.created_at = now_micros() / 1_000_000;
+        }
+        let compressed = metadata.compression.compress(data)?;
+        let data = match &compressed {
+            Some(compressed) => compressed.as_slice(),
+            None => {
+                metadata.compression = Compression::None;
+                data
+            }
+        };
+        let record = metadata.to_bytes()?;
+
+        self.runtime.block_on(async {
+            self.check_put()?;
+            trace::stage("import");
+            // Temp tags keep the parts alive until the collection is tagged
+            let content = self
+                .store
+                .add_slice(data)
+                .temp_tag()
+                .await
+                .context("Failed to add bytes to store")?;
+            let record = self
+                .store
+                .add_bytes(record)
+                .temp_tag()
+                .await
+                .context("Failed to add metadata to store")?;
+
+            let collection: Collection = [
+                (METADATA_ENTRY.to_string(), record.hash()),
+                (metadata.content_entry().to_string(), content.hash()),
+            ]
+            .into_iter()
+            .collect();
+            let root = collection
+                .store(&self.store)
+                .await
+                .context("Failed to store metadata collection")?;
+            self.store
+                .tags()
+                .create(root.hash_and_format())
+                .await
+                .context("Failed to tag metadata collection")?;
+            self.added_times.record(root.hash())?;
+
+            let ticket = BlobTicket::new(self.ticket_addr(), root.hash(), BlobFormat::HashSeq);
+            Ok(ticket.to_string())
+        })
+    }
+
+    /// Download a ticket and return its content plus any metadata record.
+    ///
+    /// Raw tickets return their bytes with no metadata. Collection tickets
+    /// must have been created by [`IrohNode::put_with_metadata`]; compressed
+    /// content is returned decompressed.
+    pub fn get_with_metadata(&self, ticket_str: &str) -> Result<(Vec<u8>, Option<BlobMetadata>)> {
+        self.runtime.block_on(async {
+            let ticket: BlobTicket = ticket_str.parse().context("Failed to parse ticket")?;
+
+            let downloader = self.store.downloader(&self.endpoint);
+            self.download(
+                ticket.hash_and_format(),
+                &[ticket.addr().id],
+                self.bandwidth
+                    .paced(downloader.download(ticket.hash_and_format(), [ticket.addr().id])),
+            )
+            .await
+            .context("Failed to download blob")?;
+
+            if ticket.format() == BlobFormat::Raw {
+                let bytes = self
+                    .store
+                    .get_bytes(ticket.hash())
+                    .await
+                    .context("Failed to read bytes from store")?;
+                return Ok((bytes.to_vec(), None));
+            }
+
+            let (collection, metadata) = self
+                .load_metadata(ticket.hash())
+                .await?
+                .context("Collection has no metadata record")?;
+            let (_, content_hash) = collection
+                .iter()
+                .find(|(name, _)| name != METADATA_ENTRY)
+                .context("Collection has no content entry")?;
+            let bytes = self
+                .store
+                .get_bytes(*content_hash)
+                .await
+                .context("Failed to read bytes from store")?;
+
+            Ok((metadata.compression.decompress(&bytes)?, Some(metadata)))
+        })
+    }
+
+    /// Fetch only the metadata record for a ticket.
+    ///
+    /// Downloads the collection header and metadata entry without the
+    /// content. Returns `None` for raw tickets and collections without a
+    /// metadata record.
+    pub fn ticket_metadata(&self, ticket_str: &str) -> Result<Option<BlobMetadata>> {
+        self.runtime.block_on(async {
+            let ticket: BlobTicket = ticket_str.parse().context("Failed to parse ticket")?;
+            if ticket.format() == BlobFormat::Raw {
+                return Ok(None);
+            }
+
+            // Child 0 holds the collection names, child 1 the metadata record
+            let request = GetRequest::builder()
+                .root(ChunkRanges::all())
+                .child(0, ChunkRanges::all())
+                .child(1, ChunkRanges::all())
+                .build(ticket.hash());
+            let downloader = self.store.downloader(&self.endpoint);
+            self.bandwidth
+                .paced(downloader.download(request, [ticket.addr().id]))
+                .await
+                .context("Failed to download metadata")?;
+
+            Ok(self
+                .load_metadata(ticket.hash())
+                .await?
+                .map(|(_, metadata)| metadata))
+        })
+    }
+
+    /// Import a file split into content-defined chunks and return a
+    /// shareable ticket.
+    ///
+    /// The ticket has the `HashSeq` format and lists the file's chunks.
+    /// Chunks already in the store from an earlier version of the file are
+    /// reused, which the returned stats report.
+    #[cfg(feature = "chunking")]
+    pub fn put_chunked_file(&self, path: &std::path::Path) -> Result<(String, ChunkStats)> {
+        self.runtime.block_on(async {
+            self.check_put()?;
+            trace::stage("import");
+            let (root, stats) = chunking::import(&self.store, path).await?;
+            self.added_times.record(root)?;
+            let ticket = BlobTicket::new(self.ticket_addr(), root, BlobFormat::HashSeq);
+            Ok((ticket.to_string(), stats))
+        })
+    }
+
+    /// Download a ticket from [`IrohNode::put_chunked_file`] and write the
+    /// file to `dst`.
+    ///
+    /// Only chunks missing from the local store are downloaded, so fetching
+    /// a new version of a file after an earlier one transfers just the
+    /// changed chunks.
+    #[cfg(feature = "chunking")]
+    pub fn get_chunked_file(&self, ticket_str: &str, dst: &std::path::Path) -> Result<ChunkStats> {
+        self.runtime.block_on(async {
+            let ticket: BlobTicket = ticket_str.parse().context("Failed to parse ticket")?;
+            anyhow::ensure!(
+                ticket.format() == BlobFormat::HashSeq,
+                "Ticket is not a chunked file"
+            );
+
+            // Fetch the chunk list first to learn which chunks are new
+            let downloader = self.store.downloader(&self.endpoint);
+            let root = HashAndFormat::raw(ticket.hash());
+            self.download(
+                root,
+                &[ticket.addr().id],
+                self.bandwidth
+                    .paced(downloader.download(root, [ticket.addr().id])),
+            )
+            .await
+            .context("Failed to download chunk list")?;
+            let new_chunks = chunking::missing(&self.store, ticket.hash()).await?;
+            // The downloader only requests what the store is missing
+            self.download(
+                ticket.hash_and_format(),
+                &[ticket.addr().id],
+                self.bandwidth
+                    .paced(downloader.download(ticket.hash_and_format(), [ticket.addr().id])),
+            )
+            .await
+            .context("Failed to download chunks")?;
+            self.store
+                .tags()
+                .create(ticket.hash_and_format())
+                .await
+                .context("Failed to tag chunked file")?;
+
+            trace::stage("export");
+            let stats = chunking::export(&self.store, ticket.hash(), dst).await?;
+            Ok(ChunkStats {
+                new_chunks,
+                ..stats
             })
         })
     }
 
-    /// Gracefully shut down the node.
+    /// List blobs in the local store, sorted and paged.
     ///
-    /// This ensures all pending writes are flushed to disk.
-    pub fn shutdown(self) -> Result<()> {
+    /// Without a cursor, the store is read into a sorted snapshot and the
+    /// page carries a new cursor for it. Pages fetched with that cursor are
+    /// cut from the same snapshot in its order, whatever `sort` says, so
+    /// paging through a listing reads the store once and sees it unchanged
+    /// while other callers page through their own. Start a new listing to
+    /// see blobs added or removed since. Only the latest 16 listings are
+    /// kept; older cursors fail. See [`catalog`] for which blobs have an
+    /// add time.
+    pub fn list_blobs(
+        &self,
+        sort: BlobSort,
+        cursor: Option<u64>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<BlobPage> {
+        let (cursor, entries) = match cursor {
+            Some(cursor) => {
+                let listings = self.blob_listings.lock().unwrap();
+                let entries = listings
+                    .listings
+                    .get(&cursor)
+                    .cloned()
+                    .context("Blob listing expired; start a new listing")?;
+                (cursor, entries)
+            }
+            None => {
+                let entries: Arc<[BlobListEntry]> =
+                    self.runtime.block_on(self.sorted_blobs(sort))?.into();
+                let cursor = self.blob_listings.lock().unwrap().insert(entries.clone());
+                (cursor, entries)
+            }
+        };
+        Ok(BlobPage {
+            entries: entries.iter().skip(offset).take(limit).copied().collect(),
+            total: entries.len() as u64,
+            cursor,
+        })
+    }
+
+    /// Every blob in the local store, in `sort` order.
+    async fn sorted_blobs(&self, sort: BlobSort) -> Result<Vec<BlobListEntry>> {
+        let started = now_micros();
+        let hashes = self
+            .store
+            .blobs()
+            .list()
+            .hashes()
+            .await
+            .context("Failed to list blobs")?;
+        let mut entries = Vec::with_capacity(hashes.len());
+        for hash in &hashes {
+            let (size, is_complete) = match self.store.blobs().status(*hash).await? {
+                BlobStatus::Complete { size } => (size, true),
+                BlobStatus::Partial { size } => (size.unwrap_or(0), false),
+                BlobStatus::NotFound => continue,
+            };
+            entries.push(BlobListEntry {
+                hash: *hash,
+                size,
+                is_complete,
+                added_at: self.added_times.get(hash),
+            });
+        }
+        self.added_times
+            .retain(&hashes.into_iter().collect(), started)?;
+
+        // Break ties by hash so pages stay consistent between calls
+        entries.sort_by(|a, b| {
+            let order = match sort {
+                BlobSort::Hash => std::cmp::Ordering::Equal,
+                BlobSort::SizeAscending => a.size.cmp(&b.size),
+                BlobSort::SizeDescending => b.size.cmp(&a.size),
+                BlobSort::NewestFirst => match (a.added_at, b.added_at) {
+                    (Some(x), Some(y)) => y.cmp(&x),
+                    (x, y) => y.is_some().cmp(&x.is_some()),
+                },
+                BlobSort::OldestFirst => match (a.added_at, b.added_at) {
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    (x, y) => y.is_some().cmp(&x.is_some()),
+                },
+            };
+            order.then_with(|| a.hash.cmp(&b.hash))
+        });
+        Ok(entries)
+    }
+
+    /// Report how many of a tag's blobs are shared with other references.
+    ///
+    /// Blobs reachable only from this tag count towards `unique_size`, which
+    /// is the space a garbage collection would reclaim after deleting it.
+    /// Other tags, temporary tags and document entries all keep blobs.
+    pub fn reference_report(&self, tag_name: &str) -> Result<ReferenceReport> {
         self.runtime.block_on(async {
-            self.router
-                .shutdown()
+            let target = self
+                .store
+                .tags()
+                .get(tag_name)
+                .await
+                .context("Failed to read tag")?
+                .with_context(|| format!("Tag not found: {tag_name}"))?;
+            self.report_references(target.hash_and_format(), |tag| tag.name == target.name)
                 .await
-                .context("Failed to shutdown router")
         })
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::tempdir;
+    /// Report how many of a collection's blobs are shared with other
+    /// references, like [`Self::reference_report`].
+    ///
+    /// The collection and its children are counted. Tags naming the
+    /// collection itself would be deleted with it, so they don't count as
+    /// other references.
+    pub fn collection_reference_report(&self, hash: Hash) -> Result<ReferenceReport> {
+        let root = HashAndFormat::hash_seq(hash);
+        self.runtime.block_on(async {
+            // Children can only be listed once the collection is stored
+            anyhow::ensure!(
+                matches!(
+                    self.store.blobs().status(hash).await?,
+                    BlobStatus::Complete { .. }
+                ),
+                "Collection not found: {hash}"
+            );
+            self.report_references(root, |tag| tag.hash_and_format() == root)
+                .await
+        })
+    }
 
-    #[test]
-    fn test_put_roundtrip() {
-        let dir = tempdir().unwrap();
-        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+    /// Compare the blobs reachable from `target` with those kept by every
+    /// tag `is_target` rejects, temporary tags and document entries.
+    async fn report_references(
+        &self,
+        target: HashAndFormat,
+        is_target: impl Fn(&TagInfo) -> bool,
+    ) -> Result<ReferenceReport> {
+        let tags = self.store.tags();
+        let target_blobs = self.reachable_blobs(target).await?;
 
-        let data = b"Hello, Iroh!";
-        let ticket = node.put(data).unwrap();
+        let mut other_blobs = HashSet::new();
+        let mut stream = tags.list().await.context("Failed to list tags")?;
+        while let Some(tag) = stream.next().await {
+            let tag = tag.context("Failed to list tags")?;
+            if !is_target(&tag) {
+                other_blobs.extend(self.reachable_blobs(tag.hash_and_format()).await?);
+            }
+        }
+        let mut temp_tags = tags
+            .list_temp_tags()
+            .await
+            .context("Failed to list temporary tags")?;
+        while let Some(content) = temp_tags.next().await {
+            other_blobs.extend(self.reachable_blobs(content).await?);
+        }
+        other_blobs.extend(self.reclaimer.docs_protected().await?);
 
-        assert!(!ticket.is_empty());
-        assert!(ticket.starts_with("blob")); // BlobTicket format
+        let mut report = ReferenceReport::default();
+        for hash in target_blobs {
+            let size = self.stored_size(hash).await?;
+            report.total_blobs += 1;
+            report.total_size += size;
+            if other_blobs.contains(&hash) {
+                report.shared_blobs += 1;
+            } else {
+                report.unique_size += size;
+            }
+        }
+        Ok(report)
+    }
 
-        node.shutdown().unwrap();
+    /// Report identical content referenced by several tags or collections.
+    ///
+    /// Every tag is followed into its collection, and each time a blob is
+    /// reached counts as a reference. At most `limit` duplicates are
+    /// listed, those saving the most space first.
+    pub fn dedup_report(&self, limit: usize) -> Result<DedupReport> {
+        self.runtime.block_on(async {
+            let mut references: HashMap<Hash, (u64, BTreeSet<String>)> = HashMap::new();
+            let mut stream = self
+                .store
+                .tags()
+                .list()
+                .await
+                .context("Failed to list tags")?;
+            while let Some(tag) = stream.next().await {
+                let tag = tag.context("Failed to list tags")?;
+                let name = String::from_utf8_lossy(tag.name.as_ref()).into_owned();
+                for hash in self.referenced_blobs(tag.hash_and_format()).await? {
+                    let (count, tags) = references.entry(hash).or_default();
+                    *count += 1;
+                    tags.insert(name.clone());
+                }
+            }
+
+            let mut report = DedupReport::default();
+            for (hash, (count, tags)) in references {
+                let size = self.stored_size(hash).await?;
+                report.references += count;
+                report.unique_blobs += 1;
+                report.referenced_size += size * count;
+                report.stored_size += size;
+                if count > 1 {
+                    report.duplicate_blobs += 1;
+                    report.duplicates.push(DuplicateBlob {
+                        hash,
+                        size,
+                        references: count,
+                        tags: tags.into_iter().collect(),
+                    });
+                }
+            }
+            report.duplicates.sort_by(|a, b| {
+                b.saved_size()
+                    .cmp(&a.saved_size())
+                    .then_with(|| a.hash.cmp(&b.hash))
+            });
+            report.duplicates.truncate(limit);
+            Ok(report)
+        })
     }
 
-    #[test]
-    fn test_node_with_docs_enabled() {
-        let dir = tempdir().unwrap();
-        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+    /// Tag `content` as `name` until `expires_at` (microseconds since epoch),
+    /// then remove the tag and emit [`NodeEvent::TagExpired`].
+    ///
+    /// Setting or removing the tag otherwise cancels the expiry, see
+    /// [`Self::clear_tag_expiry`].
+    pub fn tag_until(&self, name: &str, content: HashAndFormat, expires_at: u64) -> Result<()> {
+        self.runtime.block_on(
+            self.expiring_tags
+                .set(&self.store, name, content, expires_at),
+        )
+    }
 
-        assert!(node.is_docs_enabled());
-        assert!(node.docs().is_some());
+    /// Drop the expiry time of the tag `name`, if it has one.
+    pub fn clear_tag_expiry(&self, name: &str) -> Result<()> {
+        self.expiring_tags.forget(name)
+    }
+
+    /// List tags starting with `prefix`, with the stored size of each.
+    ///
+    /// A tag's size covers everything reachable from it. The listing total
+    /// counts blobs shared between the matched tags only once.
+    pub fn list_tags(&self, prefix: &str) -> Result<TagListing> {
+        self.runtime.block_on(async {
+            let mut listing = TagListing::default();
+            let mut seen = HashSet::new();
+            let mut stream = self
+                .store
+                .tags()
+                .list_prefix(prefix)
+                .await
+                .context("Failed to list tags")?;
+            while let Some(tag) = stream.next().await {
+                let tag = tag.context("Failed to list tags")?;
+                let mut size = 0;
+                for hash in self.reachable_blobs(tag.hash_and_format()).await? {
+                    let blob_size = self.stored_size(hash).await?;
+                    size += blob_size;
+                    if seen.insert(hash) {
+                        listing.total_size += blob_size;
+                    }
+                }
+                listing.tags.push(TagSummary {
+                    name: String::from_utf8_lossy(tag.name.as_ref()).into_owned(),
+                    hash: tag.hash,
+                    format: tag.format,
+                    size,
+                });
+            }
+            Ok(listing)
+        })
+    }
+
+    /// Add bytes to the store for `profile` and return a shareable ticket.
+    ///
+    /// The content gets the profile tag `auto/<hash>` instead of an
+    /// automatic tag, so only the profile keeps it.
+    pub fn profile_put(&self, profile: &str, data: &[u8]) -> Result<String> {
+        profiles::check_id(profile)?;
+        self.runtime.block_on(async {
+            self.check_put()?;
+            trace::stage("import");
+            let tag = self
+                .store
+                .add_slice(data)
+                .temp_tag()
+                .await
+                .context("Failed to add bytes to store")?;
+            let content = tag.hash_and_format();
+            let name = format!("auto/{}", content.hash);
+            self.set_profile_tag(profile, &name, content).await?;
+            self.added_times.record(content.hash)?;
+
+            let ticket = BlobTicket::new(self.ticket_addr(), content.hash, content.format);
+            Ok(ticket.to_string())
+        })
+    }
+
+    /// Tag content as `name` in `profile`, replacing any tag of that name.
+    pub fn profile_tag_set(&self, profile: &str, name: &str, content: HashAndFormat) -> Result<()> {
+        profiles::check_id(profile)?;
+        self.runtime
+            .block_on(self.set_profile_tag(profile, name, content))
+    }
+
+    /// Remove the tag `name` of `profile`. Its content stays stored until
+    /// [`Self::profile_gc`] reclaims it.
+    pub fn profile_tag_delete(&self, profile: &str, name: &str) -> Result<()> {
+        profiles::check_id(profile)?;
+        let tag = format!("{}{name}", profiles::tag_prefix(profile));
+        self.runtime
+            .block_on(self.store.tags().delete(tag))
+            .context("Failed to delete profile tag")?;
+        Ok(())
+    }
+
+    /// List the tags of `profile`, named without the profile prefix.
+    pub fn profile_tags(&self, profile: &str) -> Result<TagListing> {
+        profiles::check_id(profile)?;
+        let prefix = profiles::tag_prefix(profile);
+        let mut listing = self.list_tags(&prefix)?;
+        for tag in &mut listing.tags {
+            tag.name.replace_range(..prefix.len(), "");
+        }
+        Ok(listing)
+    }
+
+    /// Count the content of `profile` and how much of it is its alone.
+    pub fn profile_stats(&self, profile: &str) -> Result<ProfileStats> {
+        profiles::check_id(profile)?;
+        let prefix = profiles::tag_prefix(profile);
+        self.runtime.block_on(async {
+            let mut stats = ProfileStats::default();
+            let mut own = HashSet::new();
+            let mut others = HashSet::new();
+            let mut stream = self
+                .store
+                .tags()
+                .list()
+                .await
+                .context("Failed to list tags")?;
+            while let Some(tag) = stream.next().await {
+                let tag = tag.context("Failed to list tags")?;
+                let reachable = self.reachable_blobs(tag.hash_and_format()).await?;
+                if tag.name.as_ref().starts_with(prefix.as_bytes()) {
+                    stats.tags += 1;
+                    own.extend(reachable);
+                } else {
+                    others.extend(reachable);
+                }
+            }
+            for hash in own {
+                let size = self.stored_size(hash).await?;
+                stats.blobs += 1;
+                stats.total_size += size;
+                if !others.contains(&hash) {
+                    stats.unique_size += size;
+                }
+            }
+            Ok(stats)
+        })
+    }
+
+    /// Delete content added to `profile` that no tag references anymore.
+    ///
+    /// Content still used by other profiles, other tags, documents or
+    /// temporary tags is kept. Waits for a garbage collection run, which
+    /// is published as [`NodeEvent::GcStarted`] and [`NodeEvent::GcFinished`].
+    pub fn profile_gc(&self, profile: &str) -> Result<Reclaimed> {
+        profiles::check_id(profile)?;
+        self.runtime.block_on(self.reclaim_profile(profile))
+    }
+
+    /// Delete all tags of `profile` and the content only they referenced.
+    pub fn profile_wipe(&self, profile: &str) -> Result<Reclaimed> {
+        profiles::check_id(profile)?;
+        self.runtime.block_on(async {
+            self.store
+                .tags()
+                .delete_prefix(profiles::tag_prefix(profile))
+                .await
+                .context("Failed to delete profile tags")?;
+            self.reclaim_profile(profile).await
+        })
+    }
+
+    /// Record `content` in the profile's ledger, then tag it.
+    async fn set_profile_tag(
+        &self,
+        profile: &str,
+        name: &str,
+        content: HashAndFormat,
+    ) -> Result<()> {
+        let _ledger = self.profile_lock.lock().await;
+        Ledger::new(&self.storage_path, profile).record(content)?;
+        self.store
+            .tags()
+            .set(format!("{}{name}", profiles::tag_prefix(profile)), content)
+            .await
+            .context("Failed to tag profile content")
+    }
+
+    /// Reclaim the ledger's content that no tag references, and drop the
+    /// roots the profile no longer tags from the ledger.
+    async fn reclaim_profile(&self, profile: &str) -> Result<Reclaimed> {
+        // Content recorded meanwhile could otherwise be dropped from the ledger
+        let _ledger = self.profile_lock.lock().await;
+        let ledger = Ledger::new(&self.storage_path, profile);
+        let roots = ledger.load()?;
+        let prefix = profiles::tag_prefix(profile);
+
+        let mut referenced = HashSet::new();
+        let mut own = HashSet::new();
+        let mut stream = self
+            .store
+            .tags()
+            .list()
+            .await
+            .context("Failed to list tags")?;
+        while let Some(tag) = stream.next().await {
+            let tag = tag.context("Failed to list tags")?;
+            let reachable = self.reachable_blobs(tag.hash_and_format()).await?;
+            if tag.name.as_ref().starts_with(prefix.as_bytes()) {
+                own.extend(reachable.iter().copied());
+            }
+            referenced.extend(reachable);
+        }
+
+        let mut candidates = HashMap::new();
+        for root in roots.iter().filter(|root| !referenced.contains(&root.hash)) {
+            for hash in self.reachable_blobs(*root).await? {
+                if !referenced.contains(&hash) {
+                    candidates.insert(hash, self.stored_size(hash).await?);
+                }
+            }
+        }
+        self.events.emit(NodeEvent::GcStarted {
+            profile: profile.to_string(),
+            blobs: candidates.len() as u64,
+            size: candidates.values().sum(),
+        });
+        self.reclaimer
+            .reclaim(candidates.keys().copied().collect())
+            .await;
+
+        let mut reclaimed = Reclaimed::default();
+        for (hash, size) in candidates {
+            if matches!(self.store.blobs().status(hash).await?, BlobStatus::NotFound) {
+                reclaimed.blobs += 1;
+                reclaimed.size += size;
+            }
+        }
+        self.events.emit(NodeEvent::GcFinished {
+            profile: profile.to_string(),
+            blobs: reclaimed.blobs,
+            size: reclaimed.size,
+        });
+        ledger.retain(
+            &roots
+                .into_iter()
+                .filter(|root| own.contains(&root.hash))
+                .collect(),
+        )?;
+        Ok(reclaimed)
+    }
+
+    /// Stored size of a blob, counting partial blobs by their known size.
+    async fn stored_size(&self, hash: Hash) -> Result<u64> {
+        Ok(match self.store.blobs().status(hash).await? {
+            BlobStatus::Complete { size } => size,
+            BlobStatus::Partial { size } => size.unwrap_or(0),
+            BlobStatus::NotFound => 0,
+        })
+    }
+
+    /// Collect the root hash and, for hash sequences, all child hashes.
+    async fn reachable_blobs(&self, root: HashAndFormat) -> Result<HashSet<Hash>> {
+        Ok(self.referenced_blobs(root).await?.into_iter().collect())
+    }
+
+    /// The root hash followed by the child hashes of a hash sequence, in
+    /// order and including repeats.
+    async fn referenced_blobs(&self, root: HashAndFormat) -> Result<Vec<Hash>> {
+        let mut blobs = vec![root.hash];
+        // Children are only known once the hash sequence itself is stored
+        if root.format == BlobFormat::HashSeq
+            && let Ok(bytes) = self.store.get_bytes(root.hash).await
+        {
+            let seq = HashSeq::try_from(bytes).context("Invalid hash sequence")?;
+            blobs.extend(seq.iter());
+        }
+        Ok(blobs)
+    }
+
+    /// Load a collection and decode its metadata record, if it has one.
+    async fn load_metadata(&self, root: Hash) -> Result<Option<(Collection, BlobMetadata)>> {
+        let collection = Collection::load(root, &self.store)
+            .await
+            .context("Failed to load collection")?;
+        let Some((_, record_hash)) = collection
+            .iter()
+            .next()
+            .filter(|(name, _)| name == METADATA_ENTRY)
+        else {
+            return Ok(None);
+        };
+        let record = self
+            .store
+            .get_bytes(*record_hash)
+            .await
+            .context("Failed to read metadata record")?;
+        let metadata = BlobMetadata::from_bytes(&record).context("Invalid metadata record")?;
+        Ok(Some((collection, metadata)))
+    }
+
+    /// Make an imported author the author of document writes that name
+    /// none. The choice is kept in the storage directory across restarts.
+    #[cfg(feature = "docs")]
+    pub fn set_default_author(&self, author: AuthorId) -> Result<()> {
+        let docs = self.docs.as_ref().context("Docs not enabled")?;
+        self.runtime
+            .block_on(docs.api().author_set_default(author))
+            .context("Author must be imported before it becomes the default")
+    }
+
+    /// Author of document writes that name none: the one set with
+    /// [`Self::set_default_author`], or else one the docs engine created.
+    #[cfg(feature = "docs")]
+    pub fn default_author(&self) -> Result<AuthorId> {
+        let docs = self.docs.as_ref().context("Docs not enabled")?;
+        self.runtime.block_on(docs.api().author_default())
+    }
+
+    /// Write a document key with `durability`, or the document's own if None.
+    #[cfg(feature = "docs")]
+    pub fn doc_set(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        durability: Option<Durability>,
+    ) -> Result<Hash> {
+        self.runtime.block_on(async {
+            let hash = doc.set_bytes(author, key, value).await?;
+            self.settle_doc_write(doc, durability).await?;
+            Ok(hash)
+        })
+    }
+
+    /// Delete an author's entries under `key`, as durably as the document's
+    /// writes. Returns the number of entries deleted.
+    #[cfg(feature = "docs")]
+    pub fn doc_del(&self, doc: &Doc, author: AuthorId, key: Vec<u8>) -> Result<usize> {
+        self.runtime.block_on(async {
+            let deleted = doc.del(author, key).await?;
+            self.settle_doc_write(doc, None).await?;
+            Ok(deleted)
+        })
+    }
+
+    /// Import the file at `path` and set `key` to its content in one step.
+    ///
+    /// The file is copied into the store, so it can change or go away
+    /// afterwards. Progress is reported while the file is copied and hashed.
+    /// The write is as durable as the document's writes.
+    #[cfg(feature = "docs")]
+    pub fn doc_set_file<F>(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        key: Vec<u8>,
+        path: &std::path::Path,
+        on_progress: F,
+    ) -> Result<Hash>
+    where
+        F: FnMut(ImportProgress),
+    {
+        self.runtime.block_on(async {
+            // The temporary tag keeps the content until the entry references it
+            let (tag, size) = self.import_file(path, on_progress).await?;
+            doc.set_hash(author, key, tag.hash(), size)
+                .await
+                .context("Failed to set entry")?;
+            self.settle_doc_write(doc, None).await?;
+            Ok(tag.hash())
+        })
+    }
+
+    /// Set the durability of writes to a document that do not choose their
+    /// own, until the node closes.
+    #[cfg(feature = "docs")]
+    pub fn doc_set_durability(&self, namespace: NamespaceId, durability: Durability) {
+        let mut durabilities = self.doc_durability.lock().unwrap();
+        if durability == Durability::default() {
+            durabilities.remove(&namespace);
+        } else {
+            durabilities.insert(namespace, durability);
+        }
+    }
+
+    /// Durability of writes to a document that do not choose their own.
+    #[cfg(feature = "docs")]
+    pub fn doc_durability(&self, namespace: NamespaceId) -> Durability {
+        let durabilities = self.doc_durability.lock().unwrap();
+        durabilities.get(&namespace).copied().unwrap_or_default()
+    }
+
+    /// Commit every batched document write and sync it to disk.
+    #[cfg(feature = "docs")]
+    pub fn doc_flush(&self) -> Result<()> {
+        let replicas = self.replicas.as_ref().context("Docs not enabled")?;
+        self.runtime
+            .block_on(replicas.flush_store())
+            .context("Failed to flush docs store")
+    }
+
+    /// Writer of exact-key deletes for this node's documents.
+    #[cfg(feature = "docs")]
+    fn tombstones(&self) -> Result<filter::Tombstones> {
+        let replicas = self.replicas.clone().context("Docs not enabled")?;
+        Ok(filter::Tombstones::new(replicas, self.endpoint.id()))
+    }
+
+    /// Make a completed write to `doc` as durable as `durability`, or the
+    /// document's own if None.
+    #[cfg(feature = "docs")]
+    async fn settle_doc_write(&self, doc: &Doc, durability: Option<Durability>) -> Result<()> {
+        self.pending.local_write(*doc.id().as_bytes(), now_micros());
+        let durability = durability.unwrap_or_else(|| self.doc_durability(doc.id()));
+        if durability == Durability::Immediate
+            && let Some(replicas) = &self.replicas
+        {
+            replicas
+                .flush_store()
+                .await
+                .context("Failed to flush docs store")?;
+        }
+        Ok(())
+    }
+
+    /// Latest entry for each key, in the order given (None where absent).
+    #[cfg(feature = "docs")]
+    pub fn doc_get_batch(
+        &self,
+        doc: &Doc,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<iroh_docs::Entry>>> {
+        self.runtime.block_on(async {
+            let mut entries = Vec::with_capacity(keys.len());
+            for key in keys {
+                let entry = doc
+                    .get_one(Query::single_latest_per_key().key_exact(key))
+                    .await
+                    .context("Failed to read entry")?;
+                entries.push(entry);
+            }
+            Ok(entries)
+        })
+    }
+
+    /// Stream the latest entry per author and key matching `query`, in key
+    /// order, or only each key's winning entry with `latest_only`.
+    ///
+    /// Timestamps are filtered here as the docs store only indexes keys and
+    /// authors, so a time range still scans every key under the prefix.
+    #[cfg(feature = "docs")]
+    pub fn doc_query(
+        &self,
+        doc: &Doc,
+        query: &DocQuery,
+        mut on_entry: impl FnMut(&iroh_docs::Entry),
+    ) -> Result<()> {
+        self.runtime.block_on(async {
+            let entries = doc
+                .get_many(query.store_query())
+                .await
+                .context("Failed to query entries")?;
+            let mut entries = std::pin::pin!(entries);
+            while let Some(entry) = entries.next().await {
+                let entry = entry?;
+                if query.contains_timestamp(entry.timestamp()) {
+                    on_entry(&entry);
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Stream the entries matching `query` to `write` as newline-delimited
+    /// JSON, in chunks of whole lines.
+    ///
+    /// Values up to `max_value_size` bytes are included. `write` returns
+    /// false to stop the export. Returns the number of entries exported;
+    /// see [`crate::ndjson`] for the format.
+    #[cfg(feature = "docs")]
+    pub fn doc_export_query(
+        &self,
+        doc: &Doc,
+        query: &DocQuery,
+        max_value_size: u64,
+        write: impl FnMut(&[u8]) -> bool,
+    ) -> Result<u64> {
+        self.runtime.block_on(ndjson::export(
+            doc,
+            &self.store,
+            query,
+            max_value_size,
+            write,
+        ))
+    }
+
+    /// Write a document key only if its latest entry has the expected content hash.
+    ///
+    /// `expected` of None means the key must be absent or deleted. The check
+    /// and write are atomic with respect to other compare-and-set calls on
+    /// this node; writes from peers can still arrive in between and are
+    /// resolved by the usual latest-wins rule.
+    #[cfg(feature = "docs")]
+    pub fn doc_set_cas(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        key: Vec<u8>,
+        expected: Option<Hash>,
+        value: Vec<u8>,
+    ) -> Result<CasOutcome> {
+        self.runtime.block_on(async {
+            let _guard = self.doc_write_lock.lock().await;
+            let current = doc
+                .get_one(Query::single_latest_per_key().key_exact(&key))
+                .await
+                .context("Failed to read current entry")?
+                .map(|entry| entry.content_hash());
+            if current != expected {
+                return Ok(CasOutcome::Conflict(current));
+            }
+            let hash = doc
+                .set_bytes(author, key, value)
+                .await
+                .context("Failed to write entry")?;
+            self.settle_doc_write(doc, None).await?;
+            Ok(CasOutcome::Written(hash))
+        })
+    }
+
+    /// Add `delta` to a mergeable counter and return its new value.
+    ///
+    /// The author's own contribution is rewritten, so increments made
+    /// concurrently by other authors are summed rather than lost. Updates by
+    /// the same author are serialized on this node.
+    #[cfg(feature = "docs")]
+    pub fn doc_counter_add(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        key: Vec<u8>,
+        delta: i64,
+    ) -> Result<i64> {
+        anyhow::ensure!(!key.is_empty(), "Counter key cannot be empty");
+        self.runtime.block_on(async {
+            let _guard = self.doc_write_lock.lock().await;
+            let (total, own) = counter::read(doc, &self.store, &key, Some(author)).await?;
+            doc.set_bytes(
+                author,
+                key,
+                counter::encode_contribution(own.wrapping_add(delta)),
+            )
+            .await
+            .context("Failed to write counter")?;
+            self.settle_doc_write(doc, None).await?;
+            Ok(total.wrapping_add(delta))
+        })
+    }
+
+    /// Read the value of a mergeable counter (0 if no author has written it).
+    #[cfg(feature = "docs")]
+    pub fn doc_counter_get(&self, doc: &Doc, key: &[u8]) -> Result<i64> {
+        self.runtime
+            .block_on(async { Ok(counter::read(doc, &self.store, key, None).await?.0) })
+    }
+
+    /// Build the task that resolves diverging keys of `doc` until `cancel` fires.
+    ///
+    /// `resolve` runs on a blocking thread; its values are written by
+    /// `author` while holding the same lock as compare-and-set writes, and
+    /// only if the entries it was shown are still the latest. The caller
+    /// spawns the returned future.
+    #[cfg(feature = "docs")]
+    pub fn doc_conflict_resolver(
+        &self,
+        doc: Doc,
+        author: AuthorId,
+        resolve: Box<Resolve>,
+        cancel: tokio::sync::oneshot::Receiver<()>,
+    ) -> impl Future<Output = Result<()>> + Send + 'static {
+        resolver::run(
+            doc,
+            self.store.clone(),
+            self.doc_write_lock.clone(),
+            author,
+            resolve,
+            cancel,
+        )
+    }
+
+    /// Build the task that filters remote writes to `doc` until `cancel` fires.
+    ///
+    /// Untrusted entries are rolled back by `author` while holding the same
+    /// lock as compare-and-set writes. The caller spawns the returned future.
+    #[cfg(feature = "docs")]
+    pub fn doc_write_filter(
+        &self,
+        doc: Doc,
+        author: AuthorId,
+        policy: WritePolicy,
+        cancel: tokio::sync::oneshot::Receiver<()>,
+    ) -> impl Future<Output = Result<()>> + Send + 'static {
+        let tombstones = self.tombstones();
+        let (store, lock) = (self.store.clone(), self.doc_write_lock.clone());
+        async move { filter::run(doc, store, lock, tombstones?, author, policy, cancel).await }
+    }
+
+    /// Write a document entry and track it in the outbox until a peer has it.
+    ///
+    /// The entry is stored locally before this returns, like any write, and
+    /// the item is persisted so delivery resumes after a restart. Progress
+    /// is reported as [`NodeEvent::Outbox`] events. Returns the item ID.
+    #[cfg(feature = "docs")]
+    pub fn doc_outbox_set(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<u64> {
+        let (Some(docs), Some(outbox)) = (&self.docs, &self.outbox) else {
+            anyhow::bail!("Docs not enabled");
+        };
+        self.runtime.block_on(async {
+            let _guard = self.doc_write_lock.lock().await;
+            doc.set_bytes(author, key.clone(), value)
+                .await
+                .context("Failed to write entry")?;
+            let entry = doc
+                .get_exact(author, key.clone(), false)
+                .await
+                .context("Failed to read entry")?
+                .context("Entry missing after write")?;
+            let id = outbox.push(
+                doc.id(),
+                author,
+                key,
+                entry.content_hash(),
+                entry.timestamp(),
+            )?;
+            outbox.watch(docs, doc.id());
+            Ok(id)
+        })
+    }
+
+    /// Document writes still waiting for a peer, oldest first.
+    #[cfg(feature = "docs")]
+    pub fn outbox_items(&self) -> Vec<OutboxItem> {
+        self.outbox.as_ref().map(Outbox::items).unwrap_or_default()
+    }
+
+    /// Replace the durable peers of `doc`; an empty list stops tracking it.
+    ///
+    /// Local writes count as backed up once every durable peer synced them,
+    /// reported as [`NodeEvent::DurableSync`] events. The node syncs with
+    /// the durable peers after local writes until they have. Designations
+    /// are persisted and resume after a restart.
+    #[cfg(feature = "docs")]
+    pub fn doc_set_durable_peers(&self, doc: &Doc, peers: &[EndpointId]) -> Result<()> {
+        let (Some(docs), Some(durable)) = (&self.docs, &self.durable) else {
+            anyhow::bail!("Docs not enabled");
+        };
+        let _guard = self.runtime.enter();
+        durable.set(docs, doc.id(), peers)
+    }
+
+    /// How far the local writes of `doc` are backed up on its durable peers.
+    #[cfg(feature = "docs")]
+    pub fn doc_durable_status(&self, doc: &Doc) -> Result<DurableStatus> {
+        let (Some(docs), Some(durable)) = (&self.docs, &self.durable) else {
+            anyhow::bail!("Docs not enabled");
+        };
+        self.runtime.block_on(durable.status(docs, doc))
+    }
+
+    /// Store `data` as a backup asset of the manifest in `doc`.
+    ///
+    /// The blob is pinned and indexed under `asset_id` with its hash, size
+    /// and `metadata`, replacing any previous version of the asset.
+    #[cfg(feature = "docs")]
+    pub fn manifest_add(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        asset_id: &str,
+        data: &[u8],
+        metadata: BlobMetadata,
+    ) -> Result<ManifestAsset> {
+        self.runtime.block_on(manifest::add(
+            doc,
+            &self.store,
+            &self.doc_write_lock,
+            author,
+            asset_id,
+            data,
+            metadata,
+        ))
+    }
+
+    /// Every asset in the manifest in `doc`, sorted by asset ID.
+    #[cfg(feature = "docs")]
+    pub fn manifest_assets(&self, doc: &Doc) -> Result<Vec<ManifestAsset>> {
+        self.runtime.block_on(manifest::assets(doc, &self.store))
+    }
+
+    /// Asset IDs of the manifest in `doc` that are not fully backed up here.
+    ///
+    /// Covers the IDs in `asset_ids` that are not indexed and indexed
+    /// assets whose content is not stored on this node.
+    #[cfg(feature = "docs")]
+    pub fn manifest_missing(&self, doc: &Doc, asset_ids: &[String]) -> Result<Vec<String>> {
+        self.runtime.block_on(manifest::missing(
+            doc,
+            &self.store,
+            &self.doc_write_lock,
+            asset_ids,
+        ))
+    }
+
+    /// Remove deleted assets from the manifest in `doc` and unpin their blobs.
+    #[cfg(feature = "docs")]
+    pub fn manifest_prune(&self, doc: &Doc, author: AuthorId, asset_ids: &[String]) -> Result<u64> {
+        self.runtime.block_on(manifest::prune(
+            doc,
+            &self.store,
+            &self.doc_write_lock,
+            &self.tombstones()?,
+            author,
+            asset_ids,
+        ))
+    }
+
+    /// Store a snapshot of `doc` as a blob and return its ticket.
+    ///
+    /// The blob holds every signed entry and the content stored for it, so
+    /// [`IrohNode::doc_restore_snapshot`] can bootstrap the document on
+    /// another node with a single fetch. Anyone with the ticket can read the
+    /// document's content.
+    #[cfg(feature = "docs")]
+    pub fn doc_snapshot(&self, doc: &Doc) -> Result<String> {
+        let replicas = self.replicas.as_ref().context("Docs not enabled")?;
+        let bytes = self
+            .runtime
+            .block_on(snapshot::create(doc, replicas, &self.store))?;
+        self.put(&bytes)
+    }
+
+    /// Join the document of `doc_ticket`, bootstrapping it from a snapshot.
+    ///
+    /// The snapshot blob is fetched first and its entries are inserted as if
+    /// synced from the snapshot's provider; live sync with the ticket's
+    /// peers then starts and catches up on later writes.
+    #[cfg(feature = "docs")]
+    pub fn doc_restore_snapshot(
+        &self,
+        doc_ticket: DocTicket,
+        snapshot_ticket: &str,
+    ) -> Result<Doc> {
+        let (Some(docs), Some(replicas)) = (&self.docs, &self.replicas) else {
+            anyhow::bail!("Docs not enabled");
+        };
+        let ticket: BlobTicket = snapshot_ticket
+            .parse()
+            .context("Failed to parse snapshot ticket")?;
+        self.runtime.block_on(async {
+            trace::stage("fetch");
+            self.download_ticket(&ticket).await?;
+            let bytes = self
+                .store
+                .get_bytes(ticket.hash())
+                .await
+                .context("Failed to read snapshot from store")?;
+
+            trace::stage("restore");
+            let doc = docs
+                .import_namespace(doc_ticket.capability)
+                .await
+                .context("Failed to import document")?;
+            // Remote entries are only accepted while the replica syncs
+            doc.start_sync(Vec::new())
+                .await
+                .context("Failed to start sync")?;
+            snapshot::restore(&bytes, doc.id(), replicas, &self.store, ticket.addr().id).await?;
+            doc.start_sync(doc_ticket.nodes)
+                .await
+                .context("Failed to start sync")?;
+            Ok(doc)
+        })
+    }
+
+    /// Start collecting swarm statistics for a document.
+    ///
+    /// Does nothing if the document is already tracked. Tracking continues
+    /// after the document's handles are closed, until the node shuts down.
+    /// The document's events also keep [`IrohNode::pending_work`] and the
+    /// add times of synced content current.
+    #[cfg(feature = "docs")]
+    pub fn track_swarm(&self, doc: &Doc) {
+        let namespace = doc.id();
+        if !self.swarm.begin(namespace) {
+            return;
+        }
+        match self.runtime.block_on(doc.subscribe()) {
+            Ok(events) => {
+                let bus = self.events.clone();
+                let pending = self.pending.clone();
+                let added_times = self.added_times.clone();
+                let node_id = self.endpoint.id();
+                let events = events.inspect(move |event| {
+                    if let Ok(event) = event {
+                        bus.observe(namespace, event);
+                        pending.observe(namespace, event, node_id);
+                        if let LiveEvent::ContentReady { hash } = event {
+                            let _ = added_times.record(*hash);
+                        }
+                    }
+                });
+                self.runtime.spawn(
+                    self.swarm
+                        .clone()
+                        .run(namespace, events, self.events.clone()),
+                );
+            }
+            Err(_) => self.swarm.forget(namespace),
+        }
+    }
+
+    /// Get swarm statistics for a document tracked with [`IrohNode::track_swarm`].
+    #[cfg(feature = "docs")]
+    pub fn doc_swarm_stats(&self, namespace: NamespaceId) -> SwarmStats {
+        self.swarm.stats(namespace)
+    }
+
+    /// Get how syncs with each peer of a document tracked with
+    /// [`IrohNode::track_swarm`] went, sorted by node ID.
+    ///
+    /// Peers are listed once they joined the document's swarm or synced.
+    #[cfg(feature = "docs")]
+    pub fn doc_peer_sync_stats(&self, namespace: NamespaceId) -> Vec<PeerSyncStats> {
+        self.swarm.peer_stats(namespace)
+    }
+
+    /// Write a document entry that is removed once `ttl` has elapsed.
+    ///
+    /// Expired keys are tombstoned by the background prune task or by
+    /// [`IrohNode::prune_expired`]; until then reads still return them.
+    /// Only `key` itself is removed; the author's longer keys that start
+    /// with it are kept, and it stays while any of them doesn't expire.
+    /// Those keys are written again after `key`, since an iroh-docs write
+    /// removes the author's older keys that extend it.
+    #[cfg(feature = "docs")]
+    pub fn doc_set_with_ttl(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> Result<Hash> {
+        // An empty key would tombstone everything the author wrote
+        anyhow::ensure!(
+            !key.is_empty(),
+            "Key cannot be empty for entries with a TTL"
+        );
+        anyhow::ensure!(
+            !key.starts_with(expiry::TTL_PREFIX),
+            "Key cannot use the reserved expiry prefix"
+        );
+        let expires_at = now_micros().saturating_add(ttl.as_micros() as u64);
+        self.runtime.block_on(async {
+            // Writing a key removes the author's older keys that extend it,
+            // so those and their expiry records are written again after it.
+            // Values go before the new record, which keeps the key from
+            // expiring while they are there.
+            let mut nested = BTreeMap::new();
+            for prefix in [key.clone(), expiry::record_key(&key)] {
+                let entries = doc
+                    .get_many(Query::author(author).key_prefix(&prefix))
+                    .await?
+                    .try_collect::<_, _, Vec<_>>()
+                    .await?;
+                for entry in entries {
+                    if entry.key() != prefix.as_slice() {
+                        nested.insert(entry.key().to_vec(), entry);
+                    }
+                }
+            }
+            let record_prefix = expiry::record_key(&key);
+            let (mut records, mut values): (Vec<_>, Vec<_>) = nested
+                .into_values()
+                .partition(|e| e.key().starts_with(&record_prefix));
+            // In their original order, so records stay newer than values
+            records.sort_by_key(|e| e.timestamp());
+            values.sort_by_key(|e| e.timestamp());
+            let rewrite = async |entries: Vec<iroh_docs::Entry>| {
+                for entry in entries {
+                    doc.set_hash(
+                        author,
+                        entry.key().to_vec(),
+                        entry.content_hash(),
+                        entry.content_len(),
+                    )
+                    .await
+                    .context("Failed to write nested entry")?;
+                }
+                anyhow::Ok(())
+            };
+
+            let hash = doc
+                .set_bytes(author, key.clone(), value)
+                .await
+                .context("Failed to write entry")?;
+            rewrite(values).await?;
+            doc.set_bytes(
+                author,
+                expiry::record_key(&key),
+                expiry::encode_expiry(expires_at),
+            )
+            .await
+            .context("Failed to write expiry record")?;
+            rewrite(records).await?;
+            self.settle_doc_write(doc, None).await?;
+            Ok(hash)
+        })
+    }
+
+    /// Delete an author's entries with keys in `start..end`.
+    ///
+    /// An empty `end` means no upper bound. Each key in range is deleted on
+    /// its own, so the author's keys outside the range are left as they are
+    /// (see [`filter::Tombstones`]). Fails without deleting anything if that
+    /// is not possible for some key in range. Returns the number of entries
+    /// deleted.
+    #[cfg(feature = "docs")]
+    pub fn doc_del_range(
+        &self,
+        doc: &Doc,
+        author: AuthorId,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<u64> {
+        let in_range = |key: &[u8]| key >= start && (end.is_empty() || key < end);
+        // Only keys sharing the common prefix of the bounds can be in range
+        let prefix_len = if end.is_empty() {
+            0
+        } else {
+            start.iter().zip(end).take_while(|(a, b)| a == b).count()
+        };
+        let tombstones = self.tombstones()?;
+        self.runtime.block_on(async {
+            // Deleted keys are included, since their tombstones may be
+            // swept by those of shorter keys in range
+            let query = Query::author(author)
+                .key_prefix(&start[..prefix_len])
+                .include_empty();
+            let keys = doc
+                .get_many(query)
+                .await?
+                .try_collect::<_, _, Vec<_>>()
+                .await?
+                .into_iter()
+                .map(|e| e.key().to_vec())
+                .filter(|key| in_range(key))
+                .collect::<Vec<_>>();
+
+            let deletion = tombstones.plan(doc, author, keys, 0).await?;
+            anyhow::ensure!(
+                deletion.blocked.is_empty(),
+                "Deleting keys in range would also delete older keys outside it"
+            );
+            let deleted = tombstones.apply(doc, author, &deletion).await?;
+            self.settle_doc_write(doc, None).await?;
+            Ok(deleted)
+        })
+    }
+
+    /// Write the latest entries of a document to a new SQLite file.
+    ///
+    /// Values up to `max_value_size` bytes are included. Returns the number
+    /// of entries exported; see [`crate::sqlite`] for the schema.
+    #[cfg(feature = "sqlite")]
+    pub fn doc_export_sqlite(
+        &self,
+        doc: &Doc,
+        path: &std::path::Path,
+        max_value_size: u64,
+    ) -> Result<u64> {
+        self.runtime
+            .block_on(sqlite::export(doc, &self.store, path, max_value_size))
+    }
+
+    /// Remove expired document entries now.
+    ///
+    /// Returns the number of keys removed.
+    #[cfg(feature = "docs")]
+    pub fn prune_expired(&self) -> Result<u64> {
+        let docs = self.docs.as_ref().context("Docs not enabled")?;
+        let removed = self.runtime.block_on(expiry::prune_expired(
+            docs,
+            &self.store,
+            &self.tombstones()?,
+            now_micros(),
+        ))?;
+        self.events.emit(NodeEvent::ExpiredPruned { removed });
+        Ok(removed)
+    }
+
+    /// Close the connections a peer has open to this node.
+    ///
+    /// Transfers to the peer stop immediately. Returns how many connections
+    /// were closed; the peer is not prevented from connecting again.
+    pub fn close_peer_connections(&self, peer: EndpointId) -> usize {
+        self.peer_connections.close(peer)
+    }
+
+    /// Change who may fetch blobs when a peer has no rule of its own.
+    ///
+    /// Applies to new connections; use `close_peer_connections` to stop
+    /// transfers already in progress.
+    pub fn set_blob_access_mode(&self, mode: AccessMode) {
+        self.access.set_mode(mode);
+    }
+
+    /// Allow or deny `peer` fetching blobs, or with None remove its rule.
+    ///
+    /// Denying a peer also closes its open connections, so transfers in
+    /// progress stop immediately.
+    pub fn set_blob_access(&self, peer: EndpointId, rule: Option<AccessRule>) {
+        self.access.set(peer, rule);
+        if rule == Some(AccessRule::Deny) {
+            self.peer_connections.close(peer);
+        }
+    }
+
+    /// The blob access mode and every peer rule.
+    pub fn blob_access(&self) -> (AccessMode, Vec<(EndpointId, AccessRule)>) {
+        (self.access.mode(), self.access.rules())
+    }
+
+    /// Pause or resume serving blobs to peers.
+    ///
+    /// While paused, blob connections from peers are closed, including
+    /// transfers in progress, and new ones are turned away. Local reads and
+    /// downloads from other nodes keep working. Not persisted; a new node
+    /// serves blobs.
+    pub fn set_provider_enabled(&self, enabled: bool) {
+        self.access.set_serving(enabled);
+    }
+
+    /// Whether blobs are served to peers.
+    pub fn provider_enabled(&self) -> bool {
+        self.access.serving()
+    }
+
+    /// Bytes exchanged with each peer since the node started.
+    ///
+    /// Counts connections that peers opened to this node, such as blob
+    /// fetches and document syncs they initiated; connections this node
+    /// opens, such as its own downloads, are not counted. Sorted by bytes
+    /// uploaded, largest first.
+    pub fn peer_traffic(&self) -> Vec<(EndpointId, PeerTraffic)> {
+        self.peer_connections.traffic()
+    }
+
+    /// Watch the direct addresses peers can use to reach this node.
+    ///
+    /// Yields the current addresses first, then the full set again whenever
+    /// it changes, for example after a network change or a new STUN
+    /// observation. The stream ends when the node shuts down.
+    pub fn watch_direct_addrs(&self) -> impl Stream<Item = Vec<SocketAddr>> + Send + Unpin + use<> {
+        let mut last = None;
+        self.endpoint.watch_addr().stream().filter_map(move |addr| {
+            let mut addrs: Vec<SocketAddr> = addr.ip_addrs().copied().collect();
+            addrs.sort();
+            // Relay changes also update the address; skip them
+            if last.as_ref() == Some(&addrs) {
+                return None;
+            }
+            last = Some(addrs.clone());
+            Some(addrs)
+        })
+    }
+
+    /// Follow connectivity, download, expiry and document swarm events.
+    ///
+    /// Only events published after subscribing are delivered. The stream
+    /// ends when the node shuts down.
+    pub fn subscribe_events(&self) -> Boxed<NodeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Send a message to a peer running this library.
+    ///
+    /// `target` is a node ID or a blob ticket, whose address hints are
+    /// used. Returns once a subscriber on the peer received the message;
+    /// fails if the peer cannot be reached within `timeout` (default 10
+    /// seconds), is not subscribed to messages, or the payload is larger
+    /// than [`messages::MAX_MESSAGE_SIZE`].
+    pub fn send_message(
+        &self,
+        target: &str,
+        payload: &[u8],
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let addr = parse_node_addr(target)?;
+        let timeout = timeout.unwrap_or(DEFAULT_MESSAGE_TIMEOUT);
+        self.runtime.block_on(async {
+            tokio::time::timeout(timeout, messages::send(&self.endpoint, addr, payload))
+                .await
+                .map_err(|_| {
+                    anyhow::anyhow!("Message not delivered within {} ms", timeout.as_millis())
+                })?
+        })
+    }
+
+    /// Follow messages peers send to this node.
+    ///
+    /// Only messages received after subscribing are delivered. The stream
+    /// ends when the node shuts down.
+    pub fn subscribe_messages(&self) -> Boxed<Message> {
+        self.messages.subscribe()
+    }
+
+    /// Get information about this node.
+    pub fn info(&self) -> Result<NodeInfo> {
+        self.runtime.block_on(async {
+            // Get node ID from endpoint
+            let node_id = self.endpoint.id().to_string();
+
+            // Get address info which includes relay
+            let addr = self.endpoint.addr();
+            // Get the first relay URL if any
+            let relay_url = addr.relay_urls().next().map(|url| url.to_string());
+
+            // A node is considered connected if it has a relay URL or IP addresses
+            let is_connected = relay_url.is_some() || addr.ip_addrs().next().is_some();
+
+            Ok(NodeInfo {
+                node_id,
+                relay_url,
+                is_connected,
+                app_id: self.app_id.clone(),
+                network_condition: if self.relay_map.is_empty() {
+                    None
+                } else {
+                    network::current(&self.endpoint)
+                },
+            })
+        })
+    }
+
+    /// Report the latency to each configured relay and the home relay.
+    ///
+    /// Latencies come from the most recent network report, which the
+    /// endpoint refreshes periodically and after network changes. Relays
+    /// are unmeasured until the first report completes.
+    pub fn relay_report(&self) -> RelayReport {
+        let mut latencies: HashMap<RelayUrl, Option<Duration>> = self
+            .relay_map
+            .urls::<Vec<RelayUrl>>()
+            .into_iter()
+            .map(|url| (url, None))
+            .collect();
+        if let Some(report) = self.endpoint.net_report().get() {
+            // Keep the fastest of the HTTPS and QUIC probes
+            for (_, url, latency) in report.relay_latency.iter() {
+                let fastest = latencies.entry(url.clone()).or_default();
+                *fastest = Some(fastest.map_or(latency, |l| l.min(latency)));
+            }
+        }
+
+        let mut relays: Vec<RelayLatency> = latencies
+            .into_iter()
+            .map(|(url, latency)| RelayLatency { url, latency })
+            .collect();
+        relays.sort_by(|a, b| match (a.latency, b.latency) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.url.cmp(&b.url),
+        });
+
+        RelayReport {
+            home: self.endpoint.addr().relay_urls().next().cloned(),
+            pinned: self.pinned_relay.lock().unwrap().clone(),
+            relays,
+        }
+    }
+
+    /// Pin the node to one of its configured relays, or with None let it
+    /// choose the fastest again.
+    ///
+    /// While pinned, the other relays are removed from the endpoint, so the
+    /// node neither picks them as home nor measures them, whatever their
+    /// latency. The node moves to the new home relay after its next network
+    /// report, which the change starts right away. The pin is not persisted.
+    pub fn set_home_relay(&self, relay: Option<RelayUrl>) -> Result<()> {
+        if let Some(relay) = &relay {
+            anyhow::ensure!(
+                self.relay_map.contains(relay),
+                "Relay {relay} is not configured on this node"
+            );
+        }
+
+        let mut pinned = self.pinned_relay.lock().unwrap();
+        self.runtime.block_on(async {
+            for config in self.relay_map.relays::<Vec<_>>() {
+                if relay.as_ref().is_none_or(|relay| *relay == config.url) {
+                    self.endpoint.insert_relay(config.url.clone(), config).await;
+                } else {
+                    self.endpoint.remove_relay(&config.url).await;
+                }
+            }
+        });
+        *pinned = relay;
+        Ok(())
+    }
+
+    /// Tell the node which kind of network the device is on, so the
+    /// bandwidth caps configured for it apply to transfers from now on.
+    ///
+    /// Nodes start on Wi-Fi. Transfers in progress switch to the new caps
+    /// right away.
+    pub fn set_network_kind(&self, network: NetworkKind) {
+        self.bandwidth.set_network(network);
+    }
+
+    /// The kind of network the node applies bandwidth caps for.
+    pub fn network_kind(&self) -> NetworkKind {
+        self.bandwidth.network()
+    }
+
+    /// Check that the node can still do work.
+    ///
+    /// Verifies that the runtime runs tasks, that the store accepts writes
+    /// and that the endpoint is bound. Each check gives up after `timeout`,
+    /// so a wedged node is reported instead of hanging the caller.
+    pub fn health(&self, timeout: Duration) -> NodeHealth {
+        let mut health = NodeHealth::default();
+
+        // A spawned task that never runs means every worker is stuck
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.runtime.spawn(async move {
+            let _ = tx.send(());
+        });
+        if rx.recv_timeout(timeout).is_err() {
+            health.runtime = Some(format!("Runtime did not run a task within {timeout:?}"));
+            health.store = Some("Skipped because the runtime is unresponsive".to_string());
+        } else {
+            // Fixed content so repeated checks do not grow the store
+            let write = self.runtime.block_on(async {
+                let write = self.store.add_slice(HEALTH_CHECK_CONTENT).temp_tag();
+                tokio::time::timeout(timeout, write).await
+            });
+            health.store = match write {
+                Ok(Ok(_)) => None,
+                Ok(Err(e)) => Some(format!("Failed to write to store: {e:#}")),
+                Err(_) => Some(format!("Store write did not finish within {timeout:?}")),
+            };
+        }
+
+        if self.endpoint.is_closed() {
+            health.endpoint = Some("Endpoint is closed".to_string());
+        } else if self.endpoint.bound_sockets().is_empty() {
+            health.endpoint = Some("Endpoint has no bound sockets".to_string());
+        }
+
+        health
+    }
+
+    /// Report what the node is holding on to right now.
+    ///
+    /// Only reads counters and file sizes, so it is cheap enough to call
+    /// whenever the app notices memory pressure.
+    pub fn memory_report(&self) -> MemoryReport {
+        let file_len = |path: PathBuf| std::fs::metadata(path).map_or(0, |m| m.len());
+        MemoryReport {
+            store_disk_bytes: file_len(self.storage_path.join("blobs.db"))
+                + file_len(self.storage_path.join("docs").join("docs.redb")),
+            runtime_tasks: self.runtime.metrics().num_alive_tasks() as u64,
+            queued_events: (self.events.queued() + self.messages.queued()) as u64,
+            queued_callbacks: self.callbacks.queued() as u64,
+            open_connections: self.peer_connections.count() as u64,
+        }
+    }
+
+    /// Summarize the work this node has left pending.
+    ///
+    /// Reads counters the node keeps up to date, so it never scans the
+    /// stores or touches the network. Use [`pending::read`] to check a
+    /// store without starting a node.
+    ///
+    /// [`pending::read`]: crate::pending::read
+    pub fn pending_work(&self) -> PendingWork {
+        self.pending.work()
+    }
+
+    /// Run sync and download work for at most `budget`, then stop cleanly.
+    ///
+    /// Downloads left unfinished, including those of a node that stopped
+    /// before they completed, are resumed from the peers they were started
+    /// from. If docs are enabled, every local document is then synced with
+    /// its previously known peers and any entry content missing locally is
+    /// fetched from them. Documents that were not already live-syncing are
+    /// left again afterwards, so nothing keeps running once the step
+    /// returns. The docs store and the pending work record are flushed
+    /// before returning, so work done in the step survives the app being
+    /// suspended. `completed` is set once the step finished in time and no
+    /// pending work is left.
+    pub fn sync_step(&self, budget: Duration) -> Result<SyncStepReport> {
+        self.runtime.block_on(async {
+            let mut report = SyncStepReport::default();
+            let deadline = tokio::time::Instant::now() + budget;
+            #[cfg(feature = "docs")]
+            let mut opened = Vec::new();
+            #[cfg(feature = "docs")]
+            let mut started = Vec::new();
+
+            let work = async {
+                self.resume_downloads(&mut report).await;
+                #[cfg(feature = "docs")]
+                self.sync_docs(&mut report, &mut opened, &mut started)
+                    .await?;
+                Ok::<_, anyhow::Error>(())
+            };
+            let outcome = tokio::time::timeout_at(deadline, work).await;
+
+            // Checkpoint: stop syncs we started and release our doc handles
+            #[cfg(feature = "docs")]
+            {
+                for doc in &started {
+                    let _ = doc.leave().await;
+                }
+                for doc in &opened {
+                    let _ = doc.close().await;
+                }
+                if let Some(replicas) = &self.replicas {
+                    replicas
+                        .flush_store()
+                        .await
+                        .context("Failed to flush docs store")?;
+                }
+            }
+            self.pending.flush()?;
+
+            if let Ok(result) = outcome {
+                result?;
+                report.completed = self.pending.work().is_empty();
+            }
+            Ok(report)
+        })
+    }
+
+    /// Resume the downloads left unfinished, counting those that complete.
+    async fn resume_downloads(&self, report: &mut SyncStepReport) {
+        let downloader = self.store.downloader(&self.endpoint);
+        for (content, providers) in self.pending.downloads() {
+            let download = downloader.download(content, providers.clone());
+            if self
+                .download(content, &providers, self.bandwidth.paced(download))
+                .await
+                .is_ok()
+            {
+                report.blobs_downloaded += 1;
+            }
+        }
+    }
+
+    /// Sync every local document with its known peers and fetch entry
+    /// content missing locally, for [`IrohNode::sync_step`].
+    ///
+    /// Documents opened here are pushed to `opened` and those whose sync
+    /// was started here to `started`, for the caller to release.
+    #[cfg(feature = "docs")]
+    async fn sync_docs(
+        &self,
+        report: &mut SyncStepReport,
+        opened: &mut Vec<Doc>,
+        started: &mut Vec<Doc>,
+    ) -> Result<()> {
+        let Some(docs) = &self.docs else {
+            return Ok(());
+        };
+        let mut namespaces = docs.api().list().await.context("Failed to list docs")?;
+        while let Some(item) = namespaces.next().await {
+            let (namespace_id, _) = item?;
+            let Some(doc) = docs.api().open(namespace_id).await? else {
+                continue;
+            };
+            opened.push(doc.clone());
+
+            let peers: Vec<EndpointId> = doc
+                .get_sync_peers()
+                .await?
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|bytes| EndpointId::from_bytes(bytes).ok())
+                .collect();
+            if peers.is_empty() {
+                continue;
+            }
+
+            // Sync with known peers unless the app is already live-syncing this doc
+            if !doc.status().await?.sync {
+                let mut events = doc.subscribe().await?;
+                let addrs = peers.iter().copied().map(EndpointAddr::new).collect();
+                doc.start_sync(addrs).await?;
+                started.push(doc.clone());
+
+                // Wait until every peer finished and queued content has settled
+                let mut finished = 0;
+                let mut succeeded = false;
+                let mut content_ready = false;
+                while let Some(event) = events.next().await {
+                    let event = event?;
+                    self.pending
+                        .observe(namespace_id, &event, self.endpoint.id());
+                    match event {
+                        LiveEvent::ContentReady { hash } => {
+                            let _ = self.added_times.record(hash);
+                        }
+                        LiveEvent::SyncFinished(sync) => {
+                            finished += 1;
+                            if let Ok(details) = sync.result {
+                                succeeded = true;
+                                report.entries_received += details.entries_received as u64;
+                            }
+                        }
+                        LiveEvent::PendingContentReady => content_ready = true,
+                        _ => {}
+                    }
+                    if finished >= peers.len() && (content_ready || !succeeded) {
+                        break;
+                    }
+                }
+                if succeeded {
+                    report.docs_synced += 1;
+                }
+            }
+
+            // Fetch content for older entries that never arrived
+            let downloader = self.store.downloader(&self.endpoint);
+            let entries = doc.get_many(Query::all()).await?;
+            let mut entries = std::pin::pin!(entries);
+            while let Some(entry) = entries.next().await {
+                let entry = entry?;
+                if entry.content_len() == 0 || self.store.blobs().has(entry.content_hash()).await? {
+                    continue;
+                }
+                let content = HashAndFormat::raw(entry.content_hash());
+                let download = downloader.download(content, peers.clone());
+                if self
+                    .download(content, &peers, self.bandwidth.paced(download))
+                    .await
+                    .is_ok()
+                {
+                    report.blobs_downloaded += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Start a subscription, returning its switch and the receiver that
+    /// fires when it is cancelled or the node closes.
+    pub fn start_subscription(&self) -> (Arc<CancelSwitch>, tokio::sync::oneshot::Receiver<()>) {
+        self.subscriptions.start()
+    }
+
+    /// Run a subscription's task, which closing the node waits for.
+    pub fn spawn_subscription<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.subscriptions.spawn(&self.runtime, task);
+    }
+
+    /// Close the node, giving up on a graceful close after `deadline`.
+    ///
+    /// Cancels every subscription and waits for their final callbacks,
+    /// then shuts down the router. Returns whether all of it finished in
+    /// time. A deadline of None waits as long as closing takes.
+    pub fn close(&self, deadline: Option<Duration>) -> Result<bool> {
+        self.events.close();
+        self.messages.close();
+        self.subscriptions.cancel_all();
+        if self.test_node {
+            testing::unregister(self.endpoint.id());
+        }
+        let close = async {
+            self.subscriptions.finished().await;
+            self.router.shutdown().await
+        };
+        let finished = match deadline {
+            Some(deadline) => self
+                .runtime
+                .block_on(async { tokio::time::timeout(deadline, close).await }),
+            None => Ok(self.runtime.block_on(close)),
+        };
+        self.pending.flush()?;
+        match finished {
+            Ok(result) => {
+                result.context("Failed to shutdown router")?;
+                Ok(true)
+            }
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Gracefully shut down the node.
+    ///
+    /// This ensures all pending writes are flushed to disk.
+    pub fn shutdown(self) -> Result<()> {
+        self.close(None).map(|_| ())
+    }
+
+    /// Shut down the node, giving up on a graceful shutdown after `deadline`.
+    ///
+    /// Returns whether the shutdown finished in time. Past the deadline the
+    /// remaining shutdown work is abandoned so the caller can return, and
+    /// the store relies on its transactional writes to keep committed data
+    /// intact. A deadline of None waits as long as shutdown takes.
+    pub fn shutdown_within(self, deadline: Option<Duration>) -> Result<bool> {
+        let graceful = self.close(deadline)?;
+        if !graceful {
+            self.abandon();
+        }
+        Ok(graceful)
+    }
+
+    /// Free a node whose close did not finish, without waiting for the
+    /// work still running on it.
+    pub fn abandon(self) {
+        // Dropping the runtime would otherwise wait for the stuck tasks
+        self.runtime.shutdown_background();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recovery::StoreCorrupt;
+    use iroh::endpoint::ConnectionError;
+    use tempfile::tempdir;
+
+    /// A node in test mode, with keys derived from `seed`.
+    fn test_node(dir: &tempfile::TempDir, seed: &str) -> IrohNode {
+        let options = NodeOptions {
+            test_seed: Some(seed.to_string()),
+            ..Default::default()
+        };
+        IrohNode::with_options(dir.path().to_path_buf(), options).unwrap()
+    }
+
+    #[test]
+    fn test_put_roundtrip() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let data = b"Hello, Iroh!";
+        let ticket = node.put(data).unwrap();
+
+        assert!(!ticket.is_empty());
+        assert!(ticket.starts_with("blob")); // BlobTicket format
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_put_file() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().join("node"), false, None, false).unwrap();
+        let data = vec![3u8; 200_000];
+        let path = dir.path().join("video.mov");
+        std::fs::write(&path, &data).unwrap();
+
+        let mut updates = Vec::new();
+        let ticket = node.put_file(&path, |p| updates.push(p)).unwrap();
+        let hash = ticket.parse::<BlobTicket>().unwrap().hash();
+        assert_eq!(hash, Hash::new(&data));
+        assert_eq!(updates.last().unwrap().hashed, 200_000);
+
+        // The content is tagged like a put and outlives the file
+        std::fs::remove_file(&path).unwrap();
+        let tagged = node
+            .runtime()
+            .block_on(async {
+                let tags = node.store.tags().list().await?;
+                let tags: Vec<_> = tags.try_collect().await?;
+                anyhow::Ok(tags.iter().any(|tag| tag.hash == hash))
+            })
+            .unwrap();
+        assert!(tagged);
+        assert_eq!(node.get(&ticket).unwrap(), data);
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_get_local_content_offline() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+        let hash = node
+            .put(b"on this device")
+            .unwrap()
+            .parse::<BlobTicket>()
+            .unwrap()
+            .hash();
+        let mut events = node.subscribe_events();
+
+        // The ticket's provider cannot be reached, but the content is local
+        let unreachable = SecretKey::generate(&mut rand::rng()).public();
+        let ticket = BlobTicket::new(unreachable.into(), hash, BlobFormat::Raw).to_string();
+        assert_eq!(node.get(&ticket).unwrap(), b"on this device");
+        assert_eq!(
+            node.get_with_timeout(&ticket, 1000).unwrap(),
+            b"on this device"
+        );
+
+        // No download was attempted
+        let next = node.runtime.block_on(async {
+            tokio::time::timeout(Duration::from_millis(200), events.next()).await
+        });
+        assert!(!matches!(next, Ok(Some(NodeEvent::DownloadStarted { .. }))));
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_node_with_docs_enabled() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+
+        assert!(node.is_docs_enabled());
+        assert!(node.docs().is_some());
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(not(feature = "docs"))]
+    fn test_docs_require_docs_feature() {
+        let dir = tempdir().unwrap();
+        let result = IrohNode::new(dir.path().to_path_buf(), false, None, true);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_node_uses_provided_secret_key() {
+        let dir = tempdir().unwrap();
+        let secret_key = SecretKey::from_bytes(&[7; 32]);
+        let options = NodeOptions {
+            relay_enabled: false,
+            secret_key: Some(secret_key.clone()),
+            ..Default::default()
+        };
+        let node = IrohNode::with_options(dir.path().to_path_buf(), options).unwrap();
+
+        assert_eq!(
+            node.info().unwrap().node_id,
+            secret_key.public().to_string()
+        );
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_test_nodes_are_deterministic() {
+        let first_dir = tempdir().unwrap();
+        let first = test_node(&first_dir, "provider");
+        assert!(first.relay_map.is_empty());
+        assert!(
+            first
+                .endpoint
+                .addr()
+                .ip_addrs()
+                .all(|addr| addr.ip().is_loopback())
+        );
+        let ticket = first.put(b"hello").unwrap();
+        // The blob store is in memory
+        assert!(!first_dir.path().join("blobs.db").exists());
+        first.shutdown().unwrap();
+
+        // Same seed, fresh store: same node ID and ticket
+        let provider_dir = tempdir().unwrap();
+        let provider = test_node(&provider_dir, "provider");
+        assert_eq!(provider.put(b"hello").unwrap(), ticket);
+        assert_eq!(
+            provider.endpoint.id(),
+            testing::secret_key("provider").public()
+        );
+
+        // The ticket has no addresses; the fetcher finds the provider anyway
+        let fetcher_dir = tempdir().unwrap();
+        let fetcher = test_node(&fetcher_dir, "fetcher");
+        assert_eq!(fetcher.get(&ticket).unwrap(), b"hello");
+
+        // A test node's key comes from its seed only
+        let dir = tempdir().unwrap();
+        let options = NodeOptions {
+            test_seed: Some("provider".to_string()),
+            secret_key: Some(SecretKey::from_bytes(&[7; 32])),
+            ..Default::default()
+        };
+        assert!(IrohNode::with_options(dir.path().to_path_buf(), options).is_err());
+
+        fetcher.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_get_timeout_stops_transfer() {
+        let provider_dir = tempdir().unwrap();
+        let provider = test_node(&provider_dir, "slow-provider");
+        let fetcher_dir = tempdir().unwrap();
+        let fetcher = test_node(&fetcher_dir, "impatient-fetcher");
+
+        let data = vec![3u8; 32 * 1024 * 1024];
+        let ticket = provider.put_with_timeout(&data, 60_000).unwrap();
+        let hash = ticket.parse::<BlobTicket>().unwrap().hash();
+        fetcher
+            .can_reach(&ticket, Some(Duration::from_secs(10)))
+            .unwrap();
+
+        let error = fetcher.get_with_timeout(&ticket, 50).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<TimedOut>(),
+            Some(&TimedOut(Duration::from_millis(50)))
+        );
+
+        // Nothing keeps downloading once the call has returned
+        let verified = || {
+            fetcher
+                .runtime
+                .block_on(async { fetcher.store.observe(hash).await })
+                .unwrap()
+                .total_bytes()
+        };
+        // Writes already in flight may still land, slowly on a busy machine
+        std::thread::sleep(Duration::from_secs(1));
+        let stopped_at = verified();
+        std::thread::sleep(Duration::from_secs(1));
+        assert_eq!(verified(), stopped_at);
+        assert!(stopped_at < data.len() as u64);
+
+        fetcher.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[cfg(feature = "faults")]
+    #[test]
+    fn test_injected_faults() {
+        let provider_dir = tempdir().unwrap();
+        let provider = test_node(&provider_dir, "faulty-provider");
+        let fetcher_dir = tempdir().unwrap();
+        let fetcher = test_node(&fetcher_dir, "faulty-fetcher");
+
+        provider
+            .inject_faults(FaultConfig {
+                fail_puts: 1,
+                ..Default::default()
+            })
+            .unwrap();
+        let error = provider.put(b"hello").unwrap_err();
+        assert_eq!(error.to_string(), "Injected put failure");
+        let ticket = provider.put(b"hello").unwrap();
+
+        let delay = Duration::from_millis(300);
+        fetcher
+            .inject_faults(FaultConfig {
+                download_delay: delay,
+                ..Default::default()
+            })
+            .unwrap();
+        let started = std::time::Instant::now();
+        assert_eq!(fetcher.get(&ticket).unwrap(), b"hello");
+        assert!(started.elapsed() >= delay);
+
+        fetcher.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_close_peer_connections() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let provider =
+            IrohNode::new(provider_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client_id = client.endpoint.id();
+
+        let connection = client
+            .runtime()
+            .block_on(
+                client
+                    .endpoint
+                    .connect(provider.endpoint.addr(), BLOBS_ALPN),
+            )
+            .unwrap();
+
+        // The provider registers the connection once its handler accepts it
+        let mut closed = 0;
+        for _ in 0..50 {
+            closed = provider.close_peer_connections(client_id);
+            if closed > 0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(closed, 1);
+        client.runtime().block_on(connection.closed());
+        assert_eq!(provider.close_peer_connections(client_id), 0);
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_blob_access_list() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let provider = IrohNode::with_options(
+            provider_dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                blob_access: AccessMode::AllowList,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client_id = client.endpoint.id();
+        let mut events = provider.subscribe_events();
+        let ticket = provider.put(b"private").unwrap();
+
+        // Peers without a rule are turned away in allow-list mode
+        let connected = client.runtime.block_on(
+            client
+                .endpoint
+                .connect(provider.endpoint.addr(), BLOBS_ALPN),
+        );
+        // The close may race the end of the handshake
+        if let Ok(connection) = connected {
+            let reason = client.runtime.block_on(connection.closed());
+            assert!(
+                matches!(&reason, ConnectionError::ApplicationClosed(close) if close.error_code == VarInt::from_u32(3)),
+                "{reason:?}"
+            );
+        }
+        let rejected_peer = provider.runtime.block_on(async {
+            loop {
+                match tokio::time::timeout(Duration::from_secs(10), events.next()).await {
+                    Ok(Some(NodeEvent::FetchRejected { peer })) => break Some(peer),
+                    Ok(Some(_)) => {}
+                    _ => break None,
+                }
+            }
+        });
+        assert_eq!(rejected_peer, Some(client_id));
+
+        provider.set_blob_access(client_id, Some(AccessRule::Allow));
+        assert_eq!(client.get(&ticket).unwrap(), b"private");
+
+        // Deny rules win even when everyone else may fetch
+        provider.set_blob_access(client_id, Some(AccessRule::Deny));
+        provider.set_blob_access_mode(AccessMode::Open);
+        assert!(!provider.access.permits(client_id));
+        assert_eq!(
+            provider.blob_access(),
+            (AccessMode::Open, vec![(client_id, AccessRule::Deny)])
+        );
+        provider.set_blob_access(client_id, None);
+        assert!(provider.access.permits(client_id));
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_request_limits() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let provider = IrohNode::with_options(
+            provider_dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                request_limits: RequestLimits {
+                    requests_per_sec: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client_id = client.endpoint.id();
+        let mut events = provider.subscribe_events();
+        let first = provider.put(b"first").unwrap();
+        let second = provider.put(b"second").unwrap();
+        // Dial first so the client knows the provider's address
+        let _connection = client
+            .runtime
+            .block_on(
+                client
+                    .endpoint
+                    .connect(provider.endpoint.addr(), BLOBS_ALPN),
+            )
+            .unwrap();
+
+        assert_eq!(client.get(&first).unwrap(), b"first");
+        // The burst of one request is used up
+        assert!(client.get(&second).is_err());
+        let limited_peer = provider.runtime.block_on(async {
+            loop {
+                match tokio::time::timeout(Duration::from_secs(10), events.next()).await {
+                    Ok(Some(NodeEvent::FetchLimited { peer })) => break Some(peer),
+                    Ok(Some(_)) => {}
+                    _ => break None,
+                }
+            }
+        });
+        assert_eq!(limited_peer, Some(client_id));
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_bandwidth_caps_follow_network() {
+        let cellular = BandwidthLimits {
+            cellular: crate::bandwidth::BandwidthCaps {
+                upload_bytes_per_sec: Some(200_000),
+                download_bytes_per_sec: Some(200_000),
+            },
+            ..Default::default()
+        };
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let node = |dir: &std::path::Path| {
+            IrohNode::with_options(
+                dir.to_path_buf(),
+                NodeOptions {
+                    relay_enabled: false,
+                    bandwidth_limits: cellular,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+        let provider = node(provider_dir.path());
+        let client = node(client_dir.path());
+        let tickets: Vec<_> = (1u8..=3)
+            .map(|n| provider.put(&vec![n; 300_000]).unwrap())
+            .collect();
+        let _connection = client
+            .runtime
+            .block_on(
+                client
+                    .endpoint
+                    .connect(provider.endpoint.addr(), BLOBS_ALPN),
+            )
+            .unwrap();
+        let timed_get = |ticket: &str| {
+            let start = std::time::Instant::now();
+            assert_eq!(client.get(ticket).unwrap().len(), 300_000);
+            start.elapsed()
+        };
+
+        // Nothing is capped on Wi-Fi
+        assert_eq!(client.network_kind(), NetworkKind::Wifi);
+        assert!(timed_get(&tickets[0]) < Duration::from_secs(1));
+
+        // Either side on cellular paces the transfer
+        provider.set_network_kind(NetworkKind::Cellular);
+        assert!(timed_get(&tickets[1]) >= Duration::from_secs(1));
+        provider.set_network_kind(NetworkKind::Wifi);
+        client.set_network_kind(NetworkKind::Cellular);
+        assert!(timed_get(&tickets[2]) >= Duration::from_secs(1));
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_bandwidth_caps_pace_doc_sync() {
+        use iroh_docs::engine::LiveEvent;
+        use iroh_docs::{Capability, DocTicket};
+
+        let dir = tempdir().unwrap();
+        let peer_dir = tempdir().unwrap();
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                docs_enabled: true,
+                bandwidth_limits: BandwidthLimits {
+                    wifi: crate::bandwidth::BandwidthCaps {
+                        upload_bytes_per_sec: Some(200_000),
+                        download_bytes_per_sec: None,
+                    },
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let peer = IrohNode::new(peer_dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let doc = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "video", vec![7u8; 400_000]).await?;
+                doc.start_sync(vec![]).await?;
+                anyhow::Ok(doc)
+            })
+            .unwrap();
+        let ticket = DocTicket {
+            capability: Capability::Read(doc.id()),
+            nodes: vec![node.endpoint.addr()],
+        };
+
+        // Content the docs engine downloads is served at the upload cap
+        let peer_docs = peer.docs().unwrap().clone();
+        let elapsed = peer.runtime().block_on(async {
+            let start = std::time::Instant::now();
+            let (peer_doc, events) = peer_docs.api().import_and_subscribe(ticket).await?;
+            let mut events = std::pin::pin!(events);
+            loop {
+                let event = tokio::time::timeout(Duration::from_secs(20), events.next())
+                    .await?
+                    .context("Subscription ended")??;
+                if matches!(event, LiveEvent::ContentReady { .. }) {
+                    break;
+                }
+            }
+            peer_doc.close().await?;
+            anyhow::Ok(start.elapsed())
+        });
+        assert!(elapsed.unwrap() >= Duration::from_secs(1));
+
+        node.runtime().block_on(doc.close()).unwrap();
+        peer.shutdown().unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_provider_pause() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let provider =
+            IrohNode::new(provider_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+        let first = provider.put(b"first").unwrap();
+        let second = client.put(b"second").unwrap();
+        // Dial first so the client knows the provider's address
+        let _connection = client
+            .runtime
+            .block_on(
+                client
+                    .endpoint
+                    .connect(provider.endpoint.addr(), BLOBS_ALPN),
+            )
+            .unwrap();
+
+        provider.set_provider_enabled(false);
+        assert!(!provider.provider_enabled());
+        assert!(client.get(&first).is_err());
+        // Local reads and downloads from others still work
+        assert_eq!(provider.get(&first).unwrap(), b"first");
+        assert_eq!(provider.get(&second).unwrap(), b"second");
+
+        provider.set_provider_enabled(true);
+        assert_eq!(client.get(&first).unwrap(), b"first");
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_peer_traffic() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let provider =
+            IrohNode::new(provider_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client_id = client.endpoint.id();
+        assert!(provider.peer_traffic().is_empty());
+
+        let _connection = client
+            .runtime()
+            .block_on(
+                client
+                    .endpoint
+                    .connect(provider.endpoint.addr(), BLOBS_ALPN),
+            )
+            .unwrap();
+
+        // Traffic is counted once the provider's handler accepts the connection
+        let mut traffic = Vec::new();
+        for _ in 0..50 {
+            traffic = provider.peer_traffic();
+            if !traffic.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(traffic.len(), 1);
+        assert_eq!(traffic[0].0, client_id);
+        assert!(traffic[0].1.downloaded > 0);
+
+        // Totals survive the connection being closed
+        let before = traffic[0].1;
+        provider.close_peer_connections(client_id);
+        let after = provider.peer_traffic();
+        assert_eq!(after.len(), 1);
+        assert!(after[0].1.downloaded >= before.downloaded);
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_subscribe_events() {
+        let provider_dir = tempdir().unwrap();
+        let provider =
+            IrohNode::new(provider_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client_dir = tempdir().unwrap();
+        let client = IrohNode::with_options(
+            client_dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                docs_enabled: true,
+                ttl_prune_interval: None,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let mut events = client.subscribe_events();
+        let ticket = provider.put(b"evented").unwrap();
+        // Dial first so the client knows the provider's address
+        let _connection = client
+            .runtime
+            .block_on(
+                client
+                    .endpoint
+                    .connect(provider.endpoint.addr(), BLOBS_ALPN),
+            )
+            .unwrap();
+        let get = crate::trace::begin("iroh_get");
+        let operation = crate::trace::current();
+        client.get(&ticket).unwrap();
+        drop(get);
+        client.prune_expired().unwrap();
+
+        let hash = ticket.parse::<BlobTicket>().unwrap().hash();
+        let received = client.runtime.block_on(async {
+            let mut received = Vec::new();
+            while received.len() < 3 {
+                let next = tokio::time::timeout(Duration::from_secs(10), events.next()).await;
+                match next {
+                    Ok(Some(NodeEvent::Connectivity { .. })) => {}
+                    Ok(Some(event)) => received.push(event),
+                    _ => break,
+                }
+            }
+            received
+        });
+        assert_eq!(
+            received,
+            vec![
+                NodeEvent::DownloadStarted { hash, operation },
+                NodeEvent::DownloadFinished {
+                    hash,
+                    operation,
+                    error: None
+                },
+                NodeEvent::ExpiredPruned { removed: 0 },
+            ]
+        );
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_storage_alert_fires_when_threshold_crossed() {
+        let dir = tempdir().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx = std::sync::Mutex::new(tx);
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                storage_alert: Some(StorageAlert {
+                    threshold: 4 * 1024 * 1024,
+                    interval: Duration::from_millis(50),
+                    on_threshold: Box::new(move |exceeded, size| {
+                        let _ = tx.lock().unwrap().send((exceeded, size));
+                    }),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Stays quiet while the store is below the threshold
+        assert!(rx.recv_timeout(Duration::from_millis(300)).is_err());
+
+        node.put(&vec![7u8; 8 * 1024 * 1024]).unwrap();
+        let (exceeded, size) = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(exceeded);
+        assert!(size >= 4 * 1024 * 1024);
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_content_announcer_announces_tagged_content() {
+        let dir = tempdir().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx = std::sync::Mutex::new(tx);
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                content_announcer: Some(ContentAnnouncer {
+                    interval: Duration::from_millis(100),
+                    tag_prefix: b"pinned/".to_vec(),
+                    on_announce: Some(Box::new(move |tag, ticket| {
+                        let _ = tx.lock().unwrap().send((tag.to_string(), ticket.clone()));
+                    })),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Content under other tags, like the automatic one, is not announced
+        let ticket = node.put(b"long-lived content").unwrap();
+        let hash = ticket.parse::<BlobTicket>().unwrap().hash();
+        node.runtime()
+            .block_on(
+                node.store()
+                    .tags()
+                    .set("pinned/a", HashAndFormat::raw(hash)),
+            )
+            .unwrap();
+
+        let (tag, announced) = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(tag, "pinned/a");
+        assert_eq!(announced.hash(), hash);
+        assert_eq!(announced.addr().id, node.endpoint().id());
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_watchdog_reports_slow_operation() {
+        let dir = tempdir().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let tx = std::sync::Mutex::new(tx);
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                watchdog: Some(Watchdog {
+                    threshold: Duration::from_millis(100),
+                    emit_events: true,
+                    on_slow: Some(Box::new(move |slow| {
+                        let _ = tx.lock().unwrap().send(slow.clone());
+                    })),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut events = node.subscribe_events();
+
+        let operation = trace::begin("iroh_test_stuck");
+        let id = trace::current().unwrap();
+        trace::stage("stuck");
+        let slow = loop {
+            let slow = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+            if slow.id == id {
+                break slow;
+            }
+        };
+        assert_eq!((slow.name, slow.stage), ("iroh_test_stuck", "stuck"));
+        assert!(slow.elapsed >= Duration::from_millis(100));
+
+        let event = node.runtime.block_on(async {
+            loop {
+                match events.next().await {
+                    Some(NodeEvent::SlowOperation(event)) if event.id == id => break event,
+                    Some(_) => continue,
+                    None => panic!("event stream ended"),
+                }
+            }
+        });
+        assert_eq!(event, slow);
+
+        // Reported only once
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(rx.try_iter().all(|slow| slow.id != id));
+        drop(operation);
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_max_connections_closes_idle() {
+        let provider_dir = tempdir().unwrap();
+        let provider = IrohNode::with_options(
+            provider_dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                max_connections: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let wait_for_connection = || {
+            for _ in 0..50 {
+                if provider.peer_connections.count() == 1 {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            panic!("connection was not accepted");
+        };
+
+        let first_dir = tempdir().unwrap();
+        let first = IrohNode::new(first_dir.path().to_path_buf(), false, None, false).unwrap();
+        let first_connection = first
+            .runtime()
+            .block_on(first.endpoint.connect(provider.endpoint.addr(), BLOBS_ALPN))
+            .unwrap();
+        wait_for_connection();
+
+        // A second peer takes the only slot from the idle first one
+        let second_dir = tempdir().unwrap();
+        let second = IrohNode::new(second_dir.path().to_path_buf(), false, None, false).unwrap();
+        let _second_connection = second
+            .runtime()
+            .block_on(
+                second
+                    .endpoint
+                    .connect(provider.endpoint.addr(), BLOBS_ALPN),
+            )
+            .unwrap();
+        first.runtime().block_on(first_connection.closed());
+        wait_for_connection();
+        assert_eq!(provider.close_peer_connections(first.endpoint.id()), 0);
+        assert_eq!(provider.close_peer_connections(second.endpoint.id()), 1);
+
+        first.shutdown().unwrap();
+        second.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_watch_direct_addrs() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let mut addrs = node.watch_direct_addrs();
+        let current = node
+            .runtime()
+            .block_on(async { tokio::time::timeout(Duration::from_secs(10), addrs.next()).await })
+            .unwrap()
+            .unwrap();
+        assert!(!current.is_empty());
+        assert!(current.windows(2).all(|pair| pair[0] < pair[1]));
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_app_discovery() {
+        let published: std::sync::Arc<std::sync::Mutex<Option<EndpointAddr>>> = Default::default();
+
+        let provider_dir = tempdir().unwrap();
+        let sink = published.clone();
+        let provider = IrohNode::with_options(
+            provider_dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                default_discovery: false,
+                discovery: Some(AppDiscovery {
+                    resolve: None,
+                    publish: Some(Box::new(move |addr: &EndpointAddr| {
+                        *sink.lock().unwrap() = Some(addr.clone());
+                    })),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let client_dir = tempdir().unwrap();
+        let source = published.clone();
+        let client = IrohNode::with_options(
+            client_dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                default_discovery: false,
+                discovery: Some(AppDiscovery {
+                    resolve: Some(Box::new(move |_, reply| {
+                        if let Some(addr) = source.lock().unwrap().clone() {
+                            let _ = reply.send(addr);
+                        }
+                    })),
+                    publish: None,
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        for _ in 0..50 {
+            if published.lock().unwrap().is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(
+            published.lock().unwrap().as_ref().map(|addr| addr.id),
+            Some(provider.endpoint.id())
+        );
+
+        // Dial by ID alone; the address comes from the app's resolver
+        let connection = client.runtime().block_on(
+            client
+                .endpoint
+                .connect(EndpointAddr::new(provider.endpoint.id()), BLOBS_ALPN),
+        );
+        assert!(connection.is_ok());
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_relay_report_without_relays() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let report = node.relay_report();
+        assert!(report.home.is_none());
+        assert!(report.relays.is_empty());
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_set_home_relay() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+        // Configure relays without dialing them
+        let eu: RelayUrl = "https://eu.relay.example".parse().unwrap();
+        let us: RelayUrl = "https://us.relay.example".parse().unwrap();
+        for url in [&eu, &us] {
+            node.relay_map
+                .insert(url.clone(), Arc::new(url.clone().into()));
+        }
+
+        let endpoint_has = |url: &RelayUrl| {
+            // Removing reports whether the endpoint had the relay; put it back
+            let config = node.runtime.block_on(node.endpoint.remove_relay(url));
+            if let Some(config) = &config {
+                node.runtime
+                    .block_on(node.endpoint.insert_relay(url.clone(), config.clone()));
+            }
+            config.is_some()
+        };
+
+        node.set_home_relay(Some(eu.clone())).unwrap();
+        assert!(endpoint_has(&eu));
+        assert!(!endpoint_has(&us));
+        let report = node.relay_report();
+        assert_eq!(report.pinned, Some(eu.clone()));
+        // Relays pinned away are still reported
+        assert_eq!(report.relays.len(), 2);
+
+        node.set_home_relay(None).unwrap();
+        assert!(endpoint_has(&eu));
+        assert!(endpoint_has(&us));
+        assert!(node.relay_report().pinned.is_none());
+
+        let unknown = "https://elsewhere.example".parse().unwrap();
+        assert!(node.set_home_relay(Some(unknown)).is_err());
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_shutdown_within_deadline() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+        node.put(b"flushed").unwrap();
+        assert!(node.shutdown_within(Some(Duration::from_secs(10))).unwrap());
+
+        // A deadline that cannot be met returns without waiting
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+        assert!(!node.shutdown_within(Some(Duration::ZERO)).unwrap());
+    }
+
+    #[test]
+    fn test_close_ends_subscriptions() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let (_switch, cancelled) = node.start_subscription();
+        let (completed_tx, completed_rx) = std::sync::mpsc::channel();
+        node.spawn_subscription(async move {
+            let _ = cancelled.await;
+            completed_tx.send(()).unwrap();
+        });
+
+        assert!(node.close(Some(Duration::from_secs(10))).unwrap());
+        // The subscription finished before close returned
+        assert!(completed_rx.try_recv().is_ok());
+        // Subscriptions started on a closed node end at once
+        let (_switch, mut late) = node.start_subscription();
+        assert!(late.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_health() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let health = node.health(Duration::from_secs(5));
+        assert!(health.is_healthy(), "{health:?}");
+        // Repeated checks write the same content
+        assert!(node.health(Duration::from_secs(5)).is_healthy());
+
+        node.runtime.block_on(node.endpoint.close());
+        let health = node.health(Duration::from_secs(5));
+        assert!(!health.is_healthy());
+        assert_eq!(health.endpoint.as_deref(), Some("Endpoint is closed"));
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_can_reach() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let provider =
+            IrohNode::new(provider_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let ticket = provider.put(b"reachable").unwrap();
+        let reach = client
+            .can_reach(&ticket, Some(Duration::from_secs(10)))
+            .unwrap();
+        assert!(matches!(reach, Reachability::Direct { .. }), "{reach:?}");
+
+        // No address hints and no discovery to find any
+        let unknown = SecretKey::from_bytes(&[7; 32]).public().to_string();
+        let reach = client
+            .can_reach(&unknown, Some(Duration::from_millis(500)))
+            .unwrap();
+        assert!(
+            matches!(reach, Reachability::Unreachable { .. }),
+            "{reach:?}"
+        );
+
+        assert!(client.can_reach("not a peer", None).is_err());
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_send_message() {
+        let receiver_dir = tempdir().unwrap();
+        let sender_dir = tempdir().unwrap();
+        let receiver =
+            IrohNode::new(receiver_dir.path().to_path_buf(), false, None, false).unwrap();
+        let sender = IrohNode::new(sender_dir.path().to_path_buf(), false, None, false).unwrap();
+        // The ticket carries the receiver's address hints
+        let target = receiver.put(b"address").unwrap();
+        let timeout = Some(Duration::from_secs(10));
+
+        // Nobody is listening yet
+        let err = sender
+            .send_message(&target, b"wake up", timeout)
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("not subscribed"), "{err:#}");
+
+        let mut messages = receiver.subscribe_messages();
+        sender.send_message(&target, b"wake up", timeout).unwrap();
+        let message = receiver.runtime.block_on(messages.next()).unwrap();
+        assert_eq!(
+            message,
+            Message {
+                from: sender.endpoint.id(),
+                payload: b"wake up".to_vec(),
+            }
+        );
+
+        let oversized = vec![0; messages::MAX_MESSAGE_SIZE + 1];
+        assert!(sender.send_message(&target, &oversized, timeout).is_err());
+        assert!(sender.send_message("not a peer", b"", timeout).is_err());
+
+        sender.shutdown().unwrap();
+        receiver.shutdown().unwrap();
+        // Subscriptions end with the node
+        assert!(futures_lite::future::block_on(messages.next()).is_none());
+    }
+
+    #[test]
+    fn test_probe_providers() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let provider =
+            IrohNode::new(provider_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let ticket = provider.put(b"available").unwrap();
+        let hash = ticket.parse::<BlobTicket>().unwrap().hash();
+        // Dial with the ticket's address hints so the probe can find the provider
+        client
+            .can_reach(&ticket, Some(Duration::from_secs(10)))
+            .unwrap();
+
+        let provider_id = provider.endpoint.id();
+        let unknown = SecretKey::from_bytes(&[7; 32]).public();
+        let timeout = Some(Duration::from_secs(2));
+        let probes = client.probe_providers(hash, &[unknown, provider_id], timeout);
+        assert_eq!(probes.len(), 2);
+        assert_eq!(probes[0].0, unknown);
+        assert!(
+            matches!(probes[0].1, Availability::Unknown { .. }),
+            "{:?}",
+            probes[0].1
+        );
+        assert_eq!(probes[1], (provider_id, Availability::Complete { size: 9 }));
+
+        let absent = Hash::new(b"not stored");
+        let probes = client.probe_providers(absent, &[provider_id], timeout);
+        assert_eq!(probes, vec![(provider_id, Availability::Missing)]);
+        // Nothing was downloaded
+        assert!(!client.runtime.block_on(client.store.has(hash)).unwrap());
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[cfg(feature = "downloader-progress")]
+    #[test]
+    fn test_get_with_progress_reports_verification() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let provider =
+            IrohNode::new(provider_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let data = vec![9u8; 1024 * 1024];
+        let ticket = provider.put(&data).unwrap();
+        // Dial with the ticket's address hints so the downloader can find the provider
+        client
+            .can_reach(&ticket, Some(Duration::from_secs(10)))
+            .unwrap();
+        let mut updates = Vec::new();
+        let fetched = client
+            .get_with_progress(&ticket, |progress| updates.push(progress))
+            .unwrap();
+        assert_eq!(fetched, data);
+
+        let size = data.len() as u64;
+        let last = updates.pop().unwrap();
+        assert_eq!(
+            last,
+            DownloadProgress {
+                received: last.received,
+                verified: size,
+                total: size,
+                stage: DownloadStage::Verified,
+            }
+        );
+        assert!(last.received >= size);
+        assert_eq!(updates.pop().unwrap().stage, DownloadStage::Verifying);
+        // Verification never runs ahead of the bytes the store has
+        assert!(updates.windows(2).all(|w| w[0].verified <= w[1].verified));
+        assert!(updates.iter().all(|p| p.verified <= size));
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    /// Serve `body` to every HTTP request on a loopback port, announcing
+    /// its length if `sized`.
+    fn serve_http(body: &'static [u8], sized: bool) -> String {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/blobs", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let length = match sized {
+                    true => format!("Content-Length: {}\r\n", body.len()),
+                    false => String::new(),
+                };
+                let header = format!("HTTP/1.1 200 OK\r\n{length}Connection: close\r\n\r\n");
+                let _ = stream.write_all(header.as_bytes());
+                let _ = stream.write_all(body);
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_get_falls_back_to_gateway() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                default_discovery: false,
+                gateway_url: Some(serve_http(b"via gateway", true)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // The provider has no address hints and cannot be found
+        let provider = SecretKey::from_bytes(&[7; 32]).public();
+        let ticket = |data: &[u8]| {
+            BlobTicket::new(provider.into(), Hash::new(data), BlobFormat::Raw).to_string()
+        };
+
+        assert_eq!(node.get(&ticket(b"via gateway")).unwrap(), b"via gateway");
+        let stored = node
+            .runtime()
+            .block_on(node.store().blobs().has(Hash::new(b"via gateway")))
+            .unwrap();
+        assert!(stored);
+        let listing = node.list_blobs(BlobSort::Hash, None, 0, 1).unwrap();
+        assert!(listing.entries[0].added_at.is_some());
+
+        // Content that does not match the ticket's hash is rejected
+        let err = node.get(&ticket(b"something else")).unwrap_err();
+        assert!(format!("{err:#}").contains("instead of"), "{err:#}");
+
+        assert!(Gateway::new("http://gateway.example.com").is_err());
+
+        // Reading stops at the size limit, whether or not the gateway
+        // announces a Content-Length
+        let body = b"larger than allowed";
+        for url in [serve_http(body, true), serve_http(body, false)] {
+            let err = node
+                .runtime()
+                .block_on(Gateway::new(&url).unwrap().fetch(Hash::new(body), 8))
+                .unwrap_err();
+            assert!(
+                format!("{err:#}").contains("larger than 8 bytes"),
+                "{err:#}"
+            );
+        }
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_gateway_fetch_fails_when_response_stalls() {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/blobs", listener.local_addr().unwrap());
+        let body = b"never finished";
+        // Announce the whole body, send part of it, then go quiet while
+        // keeping the connection open
+        std::thread::spawn(move || {
+            let mut stream = listener.incoming().flatten().next().unwrap();
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf);
+            let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len());
+            let _ = stream.write_all(header.as_bytes());
+            let _ = stream.write_all(&body[..5]);
+            std::thread::sleep(Duration::from_secs(30));
+        });
+
+        let runtime = Runtime::new().unwrap();
+        let gateway = Gateway::with_read_timeout(&url, Duration::from_millis(200)).unwrap();
+        let started = std::time::Instant::now();
+        let result = runtime.block_on(gateway.fetch(Hash::new(body), 1024));
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_pending_work_tracks_doc_writes() {
+        let dir = tempdir().unwrap();
+        assert!(crate::pending::read(dir.path()).unwrap().is_empty());
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+
+        node.put(b"complete blob").unwrap();
+        assert!(node.pending_work().is_empty());
+
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+        node.doc_set(&doc, author, b"note".to_vec(), b"x".to_vec(), None)
+            .unwrap();
+        assert_eq!(node.pending_work().unsynced_docs, 1);
+        node.shutdown().unwrap();
+
+        // The write stays pending until a peer syncs it, without a node
+        let pending = crate::pending::read(dir.path()).unwrap();
+        assert_eq!(pending.unsynced_docs, 1);
+        assert_eq!(pending.incomplete_downloads, 0);
+    }
+
+    #[test]
+    fn test_runtime_threads() {
+        let dir = tempdir().unwrap();
+        let options = NodeOptions {
+            relay_enabled: false,
+            worker_threads: Some(1),
+            max_blocking_threads: Some(2),
+            ..Default::default()
+        };
+        let node = IrohNode::with_options(dir.path().to_path_buf(), options).unwrap();
+        assert_eq!(node.runtime.metrics().num_workers(), 1);
+        // One worker is enough to serve the node
+        let ticket = node.put(b"hello").unwrap();
+        assert_eq!(node.get(&ticket).unwrap(), b"hello");
+        node.shutdown().unwrap();
+
+        let dir = tempdir().unwrap();
+        let options = NodeOptions {
+            relay_enabled: false,
+            worker_threads: Some(0),
+            ..Default::default()
+        };
+        assert!(IrohNode::with_options(dir.path().to_path_buf(), options).is_err());
+    }
+
+    #[test]
+    fn test_memory_report() {
+        let dir = tempdir().unwrap();
+        let docs = cfg!(feature = "docs");
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, docs).unwrap();
+        node.put(b"some blob").unwrap();
+
+        let report = node.memory_report();
+        let disk_bytes = std::fs::metadata(dir.path().join("blobs.db"))
+            .unwrap()
+            .len()
+            + std::fs::metadata(dir.path().join("docs").join("docs.redb")).map_or(0, |m| m.len());
+        assert!(disk_bytes > 0);
+        assert_eq!(report.store_disk_bytes, disk_bytes);
+        // The router and background watchers run as tasks
+        assert!(report.runtime_tasks > 0);
+        assert_eq!(report.queued_callbacks, 0);
+        assert_eq!(report.open_connections, 0);
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_sync_step_without_peers_completes() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+
+        let report = node.sync_step(Duration::from_secs(5)).unwrap();
+
+        assert!(report.completed);
+        assert_eq!(report.docs_synced, 0);
+        assert_eq!(report.blobs_downloaded, 0);
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_sync_step_resumes_downloads() {
+        let provider_dir = tempdir().unwrap();
+        let provider = test_node(&provider_dir, "resume-provider");
+        let ticket: BlobTicket = provider.put(b"left behind").unwrap().parse().unwrap();
+
+        // A download an earlier run of the fetcher never finished
+        let fetcher_dir = tempdir().unwrap();
+        let tracker = PendingTracker::load(fetcher_dir.path()).unwrap();
+        tracker.download_started(ticket.hash_and_format(), &[provider.endpoint.id()]);
+        tracker.flush().unwrap();
+
+        let fetcher = test_node(&fetcher_dir, "resume-fetcher");
+        assert_eq!(fetcher.pending_work().incomplete_downloads, 1);
+        let report = fetcher.sync_step(Duration::from_secs(10)).unwrap();
+
+        assert!(report.completed);
+        assert_eq!(report.blobs_downloaded, 1);
+        assert!(
+            fetcher
+                .runtime()
+                .block_on(fetcher.store().blobs().has(ticket.hash()))
+                .unwrap()
+        );
+        // The record was written before the step returned
+        assert!(crate::pending::read(fetcher_dir.path()).unwrap().is_empty());
+
+        fetcher.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_put_with_metadata_roundtrip() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let metadata = BlobMetadata {
+            filename: Some("notes.txt".to_string()),
+            content_type: Some("text/plain".to_string()),
+            created_at: 0,
+            compression: Compression::None,
+        };
+        let ticket = node
+            .put_with_metadata(b"metadata content", &metadata)
+            .unwrap();
+
+        let (bytes, fetched) = node.get_with_metadata(&ticket).unwrap();
+        let fetched = fetched.unwrap();
+        assert_eq!(bytes, b"metadata content");
+        assert_eq!(fetched.filename.as_deref(), Some("notes.txt"));
+        assert_eq!(fetched.content_type.as_deref(), Some("text/plain"));
+        assert!(fetched.created_at > 0);
+
+        let inspected = node.ticket_metadata(&ticket).unwrap();
+        assert_eq!(inspected, Some(fetched));
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn test_put_with_compression() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+        let metadata = BlobMetadata {
+            compression: Compression::Zstd,
+            ..Default::default()
+        };
+
+        let log = b"2024-01-01 INFO request served\n".repeat(100);
+        let ticket = node.put_with_metadata(&log, &metadata).unwrap();
+        let (bytes, fetched) = node.get_with_metadata(&ticket).unwrap();
+        assert_eq!(bytes, log);
+        assert_eq!(fetched.unwrap().compression, Compression::Zstd);
+        let listing = node
+            .list_blobs(BlobSort::SizeDescending, None, 0, 1)
+            .unwrap();
+        assert!(listing.entries[0].size < log.len() as u64);
+
+        // Content that does not shrink is stored as is
+        let ticket = node.put_with_metadata(b"x", &metadata).unwrap();
+        let (bytes, fetched) = node.get_with_metadata(&ticket).unwrap();
+        assert_eq!(bytes, b"x");
+        assert_eq!(fetched.unwrap().compression, Compression::None);
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "chunking")]
+    fn test_chunked_file_fetches_only_changed_chunks() {
+        let provider_dir = tempdir().unwrap();
+        let client_dir = tempdir().unwrap();
+        let files = tempdir().unwrap();
+        let provider =
+            IrohNode::new(provider_dir.path().to_path_buf(), false, None, false).unwrap();
+        let client = IrohNode::new(client_dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let mut data = vec![0u8; 2 * 1024 * 1024];
+        rand::Rng::fill(&mut rand::rng(), &mut data[..]);
+        let src = files.path().join("db.sqlite");
+        let dst = files.path().join("copy.sqlite");
+        std::fs::write(&src, &data).unwrap();
+        // Dial first so the client knows the provider's address
+        let _connection = client
+            .runtime()
+            .block_on(
+                client
+                    .endpoint
+                    .connect(provider.endpoint.addr(), BLOBS_ALPN),
+            )
+            .unwrap();
+
+        let (ticket, stats) = provider.put_chunked_file(&src).unwrap();
+        assert_eq!(stats.size, data.len() as u64);
+        assert!(stats.chunks > 8);
+        assert_eq!(stats.new_chunks, stats.chunks);
+        let fetched = client.get_chunked_file(&ticket, &dst).unwrap();
+        assert_eq!(fetched.new_chunks, stats.chunks);
+        assert_eq!(std::fs::read(&dst).unwrap(), data);
+
+        // A small edit in the middle only changes the chunks around it
+        data[1024 * 1024..1024 * 1024 + 16].fill(0xff);
+        std::fs::write(&src, &data).unwrap();
+        let (ticket, stats) = provider.put_chunked_file(&src).unwrap();
+        assert!(stats.new_chunks <= 2);
+        let fetched = client.get_chunked_file(&ticket, &dst).unwrap();
+        assert_eq!(fetched.new_chunks, stats.new_chunks);
+        assert_eq!(fetched.chunks, stats.chunks);
+        assert_eq!(std::fs::read(&dst).unwrap(), data);
+
+        assert!(
+            client
+                .get_chunked_file(&client.put(b"raw").unwrap(), &dst)
+                .is_err()
+        );
+
+        client.shutdown().unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_reference_report_shared_blob() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let ticket = node.put(b"shared content").unwrap();
+        let hash = ticket.parse::<BlobTicket>().unwrap().hash();
+        node.runtime()
+            .block_on(async {
+                let tags = node.store().tags();
+                tags.set("album/a", HashAndFormat::raw(hash)).await?;
+                tags.set("album/b", HashAndFormat::raw(hash)).await
+            })
+            .unwrap();
+
+        let report = node.reference_report("album/a").unwrap();
+        assert_eq!(report.total_blobs, 1);
+        assert_eq!(report.shared_blobs, 1);
+        assert_eq!(report.total_size, b"shared content".len() as u64);
+        assert_eq!(report.unique_size, 0);
+
+        assert!(node.reference_report("missing").is_err());
+
+        // A temporary tag keeps content as well
+        let temp_tag = node
+            .runtime()
+            .block_on(async {
+                let pending = node
+                    .store()
+                    .add_bytes(b"pending".to_vec())
+                    .with_named_tag("album/c")
+                    .await?;
+                anyhow::Ok(node.store().tags().temp_tag(pending).await?)
+            })
+            .unwrap();
+        assert_eq!(node.reference_report("album/c").unwrap().unique_size, 0);
+        drop(temp_tag);
+        assert_eq!(node.reference_report("album/c").unwrap().unique_size, 7);
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_collection_reference_report() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let ticket = node
+            .put_with_metadata(b"photo bytes", &BlobMetadata::default())
+            .unwrap();
+        let root = ticket.parse::<BlobTicket>().unwrap().hash();
+        // The collection's own tag goes with it
+        let report = node.collection_reference_report(root).unwrap();
+        assert_eq!(report.total_blobs, 4);
+        assert_eq!(report.shared_blobs, 0);
+        assert_eq!(report.unique_size, report.total_size);
+
+        let content = node
+            .runtime()
+            .block_on(Collection::load(root, node.store()))
+            .unwrap()
+            .iter()
+            .last()
+            .unwrap()
+            .1;
+        node.runtime()
+            .block_on(
+                node.store()
+                    .tags()
+                    .set("photos/1", HashAndFormat::raw(content)),
+            )
+            .unwrap();
+        let report = node.collection_reference_report(root).unwrap();
+        assert_eq!(report.shared_blobs, 1);
+        assert_eq!(
+            report.unique_size,
+            report.total_size - b"photo bytes".len() as u64
+        );
+
+        assert!(
+            node.collection_reference_report(Hash::new(b"missing"))
+                .is_err()
+        );
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_reference_report_counts_doc_entries() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+
+        let content = node
+            .runtime()
+            .block_on(
+                node.store()
+                    .add_bytes(b"doc content".to_vec())
+                    .with_named_tag("album/a"),
+            )
+            .unwrap();
+        assert_eq!(node.reference_report("album/a").unwrap().unique_size, 11);
+
+        let doc = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_hash(author, b"photo".to_vec(), content.hash, 11)
+                    .await?;
+                anyhow::Ok(doc)
+            })
+            .unwrap();
+        assert_eq!(node.reference_report("album/a").unwrap().unique_size, 0);
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_dedup_report() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        // The same attachment saved by two chats, plus a unique blob
+        let ticket = node.put(b"attachment").unwrap();
+        let hash = ticket.parse::<BlobTicket>().unwrap().hash();
+        node.put(b"unique").unwrap();
+        node.runtime()
+            .block_on(async {
+                let tags = node.store().tags();
+                tags.set("chat/a", HashAndFormat::raw(hash)).await?;
+                tags.set("chat/b", HashAndFormat::raw(hash)).await
+            })
+            .unwrap();
+
+        let report = node.dedup_report(10).unwrap();
+        let size = b"attachment".len() as u64;
+        assert_eq!(report.unique_blobs, 2);
+        assert_eq!(report.duplicate_blobs, 1);
+        // The put's automatic tag is a third reference
+        assert_eq!(report.references, 4);
+        assert_eq!(report.saved_size(), 2 * size);
+        assert_eq!(report.duplicates.len(), 1);
+        let duplicate = &report.duplicates[0];
+        assert_eq!((duplicate.hash, duplicate.references), (hash, 3));
+        assert!(duplicate.tags.contains(&"chat/a".to_string()));
+        assert_eq!(duplicate.tags.len(), 3);
+
+        assert!(node.dedup_report(0).unwrap().duplicates.is_empty());
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_list_blobs_sorted_and_paged() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        node.put(b"a").unwrap();
+        node.put(b"ccc").unwrap();
+        node.put(b"bb").unwrap();
+
+        let page = node
+            .list_blobs(BlobSort::SizeDescending, None, 0, 2)
+            .unwrap();
+        assert_eq!(page.total, 3);
+        let sizes: Vec<u64> = page.entries.iter().map(|e| e.size).collect();
+        assert_eq!(sizes, vec![3, 2]);
+
+        // Later pages come from the snapshot taken by the first one, even
+        // while another listing pages through the changed store
+        node.put(b"dddd").unwrap();
+        let other = node
+            .list_blobs(BlobSort::SizeAscending, None, 0, 1)
+            .unwrap();
+        assert_eq!((other.total, other.entries[0].size), (4, 1));
+        let cursor = Some(page.cursor);
+        let page = node.list_blobs(BlobSort::Hash, cursor, 2, 2).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].size, 1);
+        let other = node
+            .list_blobs(BlobSort::SizeAscending, Some(other.cursor), 3, 1)
+            .unwrap();
+        assert_eq!(other.entries[0].size, 4);
+
+        // Only the latest listings are kept
+        for _ in 0..MAX_BLOB_LISTINGS {
+            node.list_blobs(BlobSort::Hash, None, 0, 0).unwrap();
+        }
+        assert!(node.list_blobs(BlobSort::Hash, cursor, 0, 1).is_err());
+
+        // Content added past the node has no add time and comes last
+        let untimed = node
+            .runtime()
+            .block_on(
+                node.store()
+                    .add_bytes(b"downloaded".to_vec())
+                    .with_named_tag("shares/downloaded"),
+            )
+            .unwrap()
+            .hash;
+        let page = node.list_blobs(BlobSort::OldestFirst, None, 0, 10).unwrap();
+        assert_eq!(page.total, 5);
+        let last = page.entries.last().unwrap();
+        assert_eq!((last.hash, last.added_at), (untimed, None));
+        let times: Vec<u64> = page.entries.iter().filter_map(|e| e.added_at).collect();
+        assert_eq!(times.len(), 4);
+        assert!(times.windows(2).all(|w| w[0] <= w[1]));
+        node.shutdown().unwrap();
+
+        // Add times survive restarts
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+        let page = node.list_blobs(BlobSort::NewestFirst, None, 0, 10).unwrap();
+        let restarted: Vec<u64> = page.entries.iter().filter_map(|e| e.added_at).collect();
+        assert_eq!(restarted, times.into_iter().rev().collect::<Vec<_>>());
+        assert_eq!(page.entries.last().unwrap().hash, untimed);
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_list_tags_by_prefix() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let shared = node.put(b"shared").unwrap().parse::<BlobTicket>().unwrap();
+        let own = node.put(b"own").unwrap().parse::<BlobTicket>().unwrap();
+        node.runtime()
+            .block_on(async {
+                let tags = node.store().tags();
+                tags.set("chat/a", HashAndFormat::raw(shared.hash()))
+                    .await?;
+                tags.set("chat/b", HashAndFormat::raw(shared.hash()))
+                    .await?;
+                tags.set("chat/c", HashAndFormat::raw(own.hash())).await?;
+                tags.set("profiles/a", HashAndFormat::raw(own.hash())).await
+            })
+            .unwrap();
+
+        let listing = node.list_tags("chat/").unwrap();
+        let names: Vec<&str> = listing.tags.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["chat/a", "chat/b", "chat/c"]);
+        assert_eq!(listing.tags[0].size, b"shared".len() as u64);
+        assert_eq!(listing.total_size, (b"shared".len() + b"own".len()) as u64);
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_tags_expire() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+        let hash = node
+            .put(b"shared for a while")
+            .unwrap()
+            .parse::<BlobTicket>()
+            .unwrap()
+            .hash();
+        let content = HashAndFormat::raw(hash);
+        let tag = |node: &IrohNode, name: &str| {
+            node.runtime()
+                .block_on(node.store().tags().get(name))
+                .unwrap()
+        };
+        let mut events = node.subscribe_events();
+
+        node.tag_until("shares/brief", content, now_micros() + 200_000)
+            .unwrap();
+        node.tag_until("shares/kept", content, now_micros() + 200_000)
+            .unwrap();
+        node.clear_tag_expiry("shares/kept").unwrap();
+        node.tag_until("shares/later", content, now_micros() + 800_000)
+            .unwrap();
+
+        let expired = node.runtime().block_on(async {
+            loop {
+                match events.next().await {
+                    Some(NodeEvent::TagExpired { name, hash }) => break (name, hash),
+                    Some(_) => continue,
+                    None => panic!("event stream ended"),
+                }
+            }
+        });
+        assert_eq!(expired, ("shares/brief".to_string(), hash));
+        assert!(tag(&node, "shares/brief").is_none());
+        assert!(tag(&node, "shares/kept").is_some());
+        node.shutdown().unwrap();
+
+        // Tags that expired while the node was stopped are removed on start
+        std::thread::sleep(Duration::from_secs(1));
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while tag(&node, "shares/later").is_some() {
+            assert!(std::time::Instant::now() < deadline, "tag did not expire");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(tag(&node, "shares/kept").is_some());
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_profiles_partition_store() {
+        let dir = tempdir().unwrap();
+        let docs = cfg!(feature = "docs");
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, docs).unwrap();
+        let status = |hash| node.runtime().block_on(node.store().blobs().status(hash));
+        let hash = |ticket: String| ticket.parse::<BlobTicket>().unwrap().hash();
+
+        let own = hash(node.profile_put("alice", b"alice only").unwrap());
+        let shared = hash(node.profile_put("alice", b"shared").unwrap());
+        assert_eq!(hash(node.profile_put("bob", b"shared").unwrap()), shared);
+        assert!(node.profile_put("../alice", b"escaped").is_err());
+
+        let stats = node.profile_stats("alice").unwrap();
+        assert_eq!(stats.tags, 2);
+        assert_eq!(stats.blobs, 2);
+        assert_eq!(
+            stats.total_size,
+            (b"alice only".len() + b"shared".len()) as u64
+        );
+        assert_eq!(stats.unique_size, b"alice only".len() as u64);
+        let listing = node.profile_tags("alice").unwrap();
+        assert!(listing.tags.iter().all(|t| t.name.starts_with("auto/")));
+
+        // Only content no other profile keeps is deleted
+        let mut events = node.subscribe_events();
+        let reclaimed = node.profile_wipe("alice").unwrap();
+        assert_eq!(reclaimed.blobs, 1);
+        assert_eq!(reclaimed.size, b"alice only".len() as u64);
+        let gc = node.runtime().block_on(async {
+            let mut gc = Vec::new();
+            while gc.len() < 2 {
+                match events.next().await {
+                    Some(event @ (NodeEvent::GcStarted { .. } | NodeEvent::GcFinished { .. })) => {
+                        gc.push(event)
+                    }
+                    Some(_) => continue,
+                    None => panic!("event stream ended"),
+                }
+            }
+            gc
+        });
+        assert!(matches!(
+            &gc[..],
+            [
+                NodeEvent::GcStarted { profile, blobs: 1, size: started },
+                NodeEvent::GcFinished { profile: finished, blobs: 1, size },
+            ] if profile == "alice" && finished == "alice"
+                && *started == reclaimed.size && *size == reclaimed.size
+        ));
+        assert!(matches!(status(own).unwrap(), BlobStatus::NotFound));
+        assert!(matches!(
+            status(shared).unwrap(),
+            BlobStatus::Complete { .. }
+        ));
+        assert_eq!(
+            node.profile_stats("alice").unwrap(),
+            ProfileStats::default()
+        );
+
+        node.profile_tag_delete("bob", &format!("auto/{shared}"))
+            .unwrap();
+        assert!(matches!(
+            status(shared).unwrap(),
+            BlobStatus::Complete { .. }
+        ));
+        assert_eq!(node.profile_gc("bob").unwrap().blobs, 1);
+        assert!(matches!(status(shared).unwrap(), BlobStatus::NotFound));
+        assert_eq!(node.profile_gc("bob").unwrap(), Reclaimed::default());
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_copy_to_store() {
+        let dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let data = vec![7u8; 100_000];
+        let hash = node
+            .put(&data)
+            .unwrap()
+            .parse::<BlobTicket>()
+            .unwrap()
+            .hash();
+        let empty = node.put(b"").unwrap().parse::<BlobTicket>().unwrap().hash();
+
+        let copied = node.copy_to_store(dst_dir.path(), &[hash, empty]).unwrap();
+        assert_eq!(copied, data.len() as u64);
+        assert!(
+            node.copy_to_store(dst_dir.path(), &[Hash::new(b"missing")])
+                .is_err()
+        );
+        node.shutdown().unwrap();
+
+        let other = IrohNode::new(dst_dir.path().to_path_buf(), false, None, false).unwrap();
+        let bytes = other
+            .runtime()
+            .block_on(other.store().get_bytes(hash))
+            .unwrap();
+        assert_eq!(bytes.as_ref(), data.as_slice());
+        other.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_set_cas() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+        let key = b"counter".to_vec();
+
+        let first = node
+            .doc_set_cas(&doc, author, key.clone(), None, b"1".to_vec())
+            .unwrap();
+        let CasOutcome::Written(first_hash) = first else {
+            panic!("expected write, got {first:?}");
+        };
+
+        // A stale expectation is rejected and reports the current hash
+        let stale = node
+            .doc_set_cas(&doc, author, key.clone(), None, b"2".to_vec())
+            .unwrap();
+        assert_eq!(stale, CasOutcome::Conflict(Some(first_hash)));
+
+        let second = node
+            .doc_set_cas(&doc, author, key, Some(first_hash), b"2".to_vec())
+            .unwrap();
+        assert_eq!(second, CasOutcome::Written(Hash::new(b"2")));
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_set_file() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().join("node"), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+        let data = vec![7u8; 100_000];
+        let path = dir.path().join("photo.jpg");
+        std::fs::write(&path, &data).unwrap();
+
+        let mut updates = Vec::new();
+        let hash = node
+            .doc_set_file(&doc, author, b"photo".to_vec(), &path, |p| updates.push(p))
+            .unwrap();
+        assert_eq!(hash, Hash::new(&data));
+        let last = updates.last().unwrap();
+        assert_eq!(
+            (last.copied, last.hashed, last.total),
+            (100_000, 100_000, 100_000)
+        );
+
+        // The entry survives the file going away, as the content was copied
+        std::fs::remove_file(&path).unwrap();
+        let entry = node
+            .runtime()
+            .block_on(doc.get_exact(author, b"photo".to_vec(), false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.content_hash(), hash);
+        assert_eq!(entry.content_len(), 100_000);
+        let stored = node
+            .runtime()
+            .block_on(node.store.blobs().get_bytes(hash))
+            .unwrap();
+        assert_eq!(stored.as_ref(), data.as_slice());
+
+        assert!(
+            node.doc_set_file(&doc, author, b"missing".to_vec(), &path, |_| {})
+                .is_err()
+        );
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_default_author_persists() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let author = iroh_docs::Author::new(&mut rand::rng());
+
+        // Only imported authors can become the default
+        assert!(node.set_default_author(author.id()).is_err());
+        let docs = node.docs().unwrap().clone();
+        node.runtime()
+            .block_on(docs.api().author_import(author.clone()))
+            .unwrap();
+        node.set_default_author(author.id()).unwrap();
+        assert_eq!(node.default_author().unwrap(), author.id());
+        node.shutdown().unwrap();
+
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        assert_eq!(node.default_author().unwrap(), author.id());
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_immediate_writes_are_on_disk() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+        // What a crash right now would leave behind
+        let on_disk = |key: &[u8]| {
+            let copy = dir.path().join("crash.redb");
+            std::fs::copy(dir.path().join("docs").join("docs.redb"), &copy).unwrap();
+            let mut store = iroh_docs::store::Store::persistent(&copy).unwrap();
+            let entry = store.get_exact(doc.id(), author, key, false).unwrap();
+            std::fs::remove_file(copy).unwrap();
+            entry.is_some()
+        };
+
+        assert_eq!(node.doc_durability(doc.id()), Durability::Batched);
+        node.doc_set(
+            &doc,
+            author,
+            b"payment".to_vec(),
+            b"42".to_vec(),
+            Some(Durability::Immediate),
+        )
+        .unwrap();
+        assert!(on_disk(b"payment"));
+
+        node.doc_set_durability(doc.id(), Durability::Immediate);
+        node.doc_set_cas(&doc, author, b"receipt".to_vec(), None, b"42".to_vec())
+            .unwrap();
+        assert!(on_disk(b"receipt"));
+
+        node.doc_set_durability(doc.id(), Durability::Batched);
+        node.doc_set(&doc, author, b"import".to_vec(), b"bulk".to_vec(), None)
+            .unwrap();
+        node.doc_flush().unwrap();
+        assert!(on_disk(b"import"));
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_counter_merges_authors() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, alice, bob) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let alice = docs.api().author_default().await?;
+                let bob = docs.api().author_create().await?;
+                anyhow::Ok((doc, alice, bob))
+            })
+            .unwrap();
+        let key = b"likes".to_vec();
+
+        assert_eq!(node.doc_counter_get(&doc, &key).unwrap(), 0);
+        assert_eq!(
+            node.doc_counter_add(&doc, alice, key.clone(), 2).unwrap(),
+            2
+        );
+        assert_eq!(node.doc_counter_add(&doc, bob, key.clone(), 5).unwrap(), 7);
+        assert_eq!(
+            node.doc_counter_add(&doc, alice, key.clone(), -1).unwrap(),
+            6
+        );
+        assert_eq!(node.doc_counter_get(&doc, &key).unwrap(), 6);
+
+        // A contribution synced without its content can't be added to
+        node.runtime()
+            .block_on(doc.set_hash(bob, key.clone(), Hash::new(b"12"), 2))
+            .unwrap();
+        assert!(node.doc_counter_add(&doc, bob, key.clone(), 1).is_err());
+        assert_eq!(node.doc_counter_get(&doc, &key).unwrap(), 1);
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_conflict_resolved() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, alice, bob) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let alice = docs.api().author_default().await?;
+                let bob = docs.api().author_create().await?;
+                anyhow::Ok((doc, alice, bob))
+            })
+            .unwrap();
+        let merge: Arc<Resolve> =
+            Arc::new(|_: &[u8], candidates: &[resolver::ConflictCandidate]| {
+                let mut values: Vec<_> = candidates.iter().map(|c| c.content.clone()).collect();
+                values.sort();
+                Some(values.join(&b'|'))
+            });
+        let resolve = |doc: &Doc| {
+            node.runtime().block_on(resolver::resolve_key(
+                doc,
+                &node.store,
+                &node.doc_write_lock,
+                alice,
+                b"title",
+                &merge,
+            ))
+        };
+
+        node.runtime()
+            .block_on(doc.set_bytes(alice, "title", "draft"))
+            .unwrap();
+        assert_eq!(resolve(&doc).unwrap(), resolver::Resolution::NoConflict);
+
+        node.runtime()
+            .block_on(doc.set_bytes(bob, "title", "final"))
+            .unwrap();
+        assert_eq!(
+            resolve(&doc).unwrap(),
+            resolver::Resolution::Written(Hash::new(b"draft|final"))
+        );
+
+        // A write reaching the key while the resolver runs wins over it
+        let (racing_doc, handle) = (doc.clone(), node.runtime().handle().clone());
+        let racing: Arc<Resolve> = Arc::new(move |_: &[u8], _: &[resolver::ConflictCandidate]| {
+            handle
+                .block_on(racing_doc.set_bytes(bob, "title", "later"))
+                .unwrap();
+            Some(b"stale".to_vec())
+        });
+        let outcome = node.runtime().block_on(resolver::resolve_key(
+            &doc,
+            &node.store,
+            &node.doc_write_lock,
+            alice,
+            b"title",
+            &racing,
+        ));
+        assert_eq!(outcome.unwrap(), resolver::Resolution::Unchanged);
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_write_filter_rolls_back() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, owner, friend, stranger) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let owner = docs.api().author_default().await?;
+                let friend = docs.api().author_create().await?;
+                let stranger = docs.api().author_create().await?;
+                anyhow::Ok((doc, owner, friend, stranger))
+            })
+            .unwrap();
+        let allowed = HashSet::from([friend]);
+        let read = |key: &str| {
+            node.runtime()
+                .block_on(doc.get_one(Query::single_latest_per_key().key_exact(key)))
+                .unwrap()
+                .map(|entry| entry.author())
+        };
+        let roll_back = |key: &str| {
+            node.runtime()
+                .block_on(filter::roll_back(
+                    &doc,
+                    &node.doc_write_lock,
+                    &node.tombstones().unwrap(),
+                    owner,
+                    &allowed,
+                    key.as_bytes(),
+                ))
+                .unwrap()
+        };
+
+        // A trusted value is restored by the filtering author
+        node.runtime().block_on(async {
+            doc.set_bytes(friend, "title", "draft").await.unwrap();
+            doc.set_bytes(stranger, "title", "spam").await.unwrap();
+        });
+        assert_eq!(read("title"), Some(stranger));
+        assert_eq!(roll_back("title"), None);
+        assert_eq!(read("title"), Some(owner));
+        let restored = node
+            .runtime()
+            .block_on(doc.get_exact(owner, "title", false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(restored.content_hash(), Hash::new(b"draft"));
+        // Nothing to do once a trusted entry is newest
+        assert_eq!(roll_back("title"), None);
+
+        // A key only the stranger wrote is deleted without touching the
+        // filtering author's newer nested keys
+        let nested = node.runtime().block_on(async {
+            doc.set_bytes(stranger, "notes", "spam").await.unwrap();
+            doc.set_bytes(owner, "notes/1", "keep").await.unwrap();
+            doc.get_exact(owner, "notes/1", false)
+                .await
+                .unwrap()
+                .unwrap()
+        });
+        assert_eq!(roll_back("notes"), None);
+        assert_eq!(read("notes"), None);
+        let kept = node
+            .runtime()
+            .block_on(doc.get_exact(owner, "notes/1", false))
+            .unwrap()
+            .unwrap();
+        assert_eq!(kept.timestamp(), nested.timestamp());
+
+        // Deleting over older nested keys would lose them, so it fails
+        node.runtime().block_on(async {
+            doc.set_bytes(owner, "drafts/1", "keep").await.unwrap();
+            doc.set_bytes(stranger, "drafts", "spam").await.unwrap();
+        });
+        let failed = node.runtime().block_on(filter::roll_back(
+            &doc,
+            &node.doc_write_lock,
+            &node.tombstones().unwrap(),
+            owner,
+            &allowed,
+            b"drafts",
+        ));
+        assert!(failed.is_err());
+        assert_eq!(read("drafts/1"), Some(owner));
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_fetch_delivers_remote_content() {
+        use crate::fetch::ContentFetch;
+        use iroh_docs::engine::LiveEvent;
+        use iroh_docs::{Capability, DocTicket};
+
+        let dir = tempdir().unwrap();
+        let peer_dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let peer = IrohNode::new(peer_dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let doc = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "photo", vec![7u8; 4096]).await?;
+                doc.set_bytes(author, "caption", "beach").await?;
+                // Only documents in live sync accept peers
+                doc.start_sync(vec![]).await?;
+                anyhow::Ok(doc)
+            })
+            .unwrap();
+        let ticket = DocTicket {
+            capability: Capability::Read(doc.id()),
+            nodes: vec![node.endpoint.addr()],
+        };
+
+        let peer_docs = peer.docs().unwrap().clone();
+        let fetched = peer.runtime().block_on(async {
+            let (peer_doc, events) = peer_docs.api().import_and_subscribe(ticket).await?;
+            let mut events = std::pin::pin!(events);
+            let mut fetch = ContentFetch::new(&peer_doc, peer.store().clone(), Some(1024)).await?;
+            let mut fetched = Vec::new();
+            let mut remote = 0;
+            // The photo is over the size limit, so only the caption arrives
+            while remote < 2 || fetched.is_empty() {
+                let event = tokio::time::timeout(Duration::from_secs(20), events.next())
+                    .await?
+                    .context("Subscription ended")??;
+                if matches!(event, LiveEvent::InsertRemote { .. }) {
+                    remote += 1;
+                }
+                fetched.extend(fetch.observe(&event).await);
+            }
+            peer_doc.close().await?;
+            anyhow::Ok(fetched)
+        });
+        let fetched = fetched.unwrap();
+        assert_eq!(fetched.len(), 1);
+        assert_eq!(fetched[0].entry.key(), b"caption");
+        assert_eq!(fetched[0].from, node.endpoint.id());
+        assert_eq!(fetched[0].content, b"beach");
+
+        node.runtime().block_on(doc.close()).unwrap();
+        peer.shutdown().unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_outbox_delivers_after_restart() {
+        use crate::outbox::OutboxStatus;
+        use iroh_docs::{Capability, DocTicket};
+
+        let dir = tempdir().unwrap();
+        let peer_dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+        let namespace = doc.id();
+        let mut events = node.subscribe_events();
+        let id = node
+            .doc_outbox_set(&doc, author, b"title".to_vec(), b"draft".to_vec())
+            .unwrap();
+        let queued = node.runtime().block_on(events.next());
+        assert_eq!(
+            queued,
+            Some(NodeEvent::Outbox {
+                id,
+                namespace,
+                key: b"title".to_vec(),
+                status: OutboxStatus::Queued,
+            })
+        );
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+
+        // The item survives a restart and is delivered once a peer syncs
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        assert_eq!(node.outbox_items().len(), 1);
+        let mut events = node.subscribe_events();
+        let peer = IrohNode::new(peer_dir.path().to_path_buf(), false, None, true).unwrap();
+        let ticket = DocTicket {
+            capability: Capability::Read(namespace),
+            nodes: vec![node.endpoint.addr()],
+        };
+        let peer_docs = peer.docs().unwrap().clone();
+        let peer_doc = peer
+            .runtime()
+            .block_on(peer_docs.api().import(ticket))
+            .unwrap();
+
+        let delivered = node.runtime().block_on(async {
+            loop {
+                match tokio::time::timeout(Duration::from_secs(20), events.next()).await {
+                    Ok(Some(NodeEvent::Outbox { id, status, .. })) => break Some((id, status)),
+                    Ok(Some(_)) => {}
+                    _ => break None,
+                }
+            }
+        });
+        assert_eq!(delivered, Some((id, OutboxStatus::Delivered)));
+        assert!(node.outbox_items().is_empty());
+
+        peer.runtime().block_on(peer_doc.close()).unwrap();
+        peer.shutdown().unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_durable_peer_confirms_writes() {
+        use iroh_docs::{Capability, DocTicket};
+
+        let dir = tempdir().unwrap();
+        let peer_dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let peer = IrohNode::new(peer_dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let doc = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "note", "saved").await?;
+                anyhow::Ok(doc)
+            })
+            .unwrap();
+        let namespace = doc.id();
+        let peer_id = peer.endpoint.id();
+
+        let mut events = node.subscribe_events();
+        node.doc_set_durable_peers(&doc, &[peer_id]).unwrap();
+        let status = node.doc_durable_status(&doc).unwrap();
+        assert_eq!(status.peers, vec![(peer_id, 0)]);
+        assert!(status.last_local_write > 0);
+        assert!(!status.is_backed_up());
+
+        // The peer joining the document syncs the write
+        let ticket = DocTicket {
+            capability: Capability::Read(namespace),
+            nodes: vec![node.endpoint.addr()],
+        };
+        let peer_docs = peer.docs().unwrap().clone();
+        let peer_doc = peer
+            .runtime()
+            .block_on(peer_docs.api().import(ticket))
+            .unwrap();
+        let confirmed = node.runtime().block_on(async {
+            loop {
+                match tokio::time::timeout(Duration::from_secs(20), events.next()).await {
+                    Ok(Some(NodeEvent::DurableSync {
+                        peer, backed_up, ..
+                    })) => break Some((peer, backed_up)),
+                    Ok(Some(_)) => {}
+                    _ => break None,
+                }
+            }
+        });
+        assert_eq!(confirmed, Some((peer_id, true)));
+        assert!(node.doc_durable_status(&doc).unwrap().is_backed_up());
+
+        node.doc_set_durable_peers(&doc, &[]).unwrap();
+        assert!(node.doc_durable_status(&doc).unwrap().peers.is_empty());
+
+        peer.runtime().block_on(peer_doc.close()).unwrap();
+        peer.shutdown().unwrap();
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_snapshot_bootstraps_peer() {
+        use iroh_docs::{Capability, DocTicket, NamespaceSecret};
+
+        let dir = tempdir().unwrap();
+        let peer_dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let peer = IrohNode::new(peer_dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let doc = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "note", "saved").await?;
+                doc.set_bytes(author, "draft", "discarded").await?;
+                doc.del(author, "draft").await?;
+                anyhow::Ok(doc)
+            })
+            .unwrap();
+        let snapshot = node.doc_snapshot(&doc).unwrap();
+        // Relays are off, so let the peer learn the node's direct addresses
+        peer.runtime()
+            .block_on(peer.endpoint.connect(node.endpoint.addr(), BLOBS_ALPN))
+            .unwrap();
+
+        // No peers in the ticket, so everything comes from the snapshot
+        let ticket = DocTicket {
+            capability: Capability::Read(doc.id()),
+            nodes: Vec::new(),
+        };
+        let restored = peer.doc_restore_snapshot(ticket, &snapshot).unwrap();
+        assert_eq!(restored.id(), doc.id());
+        let (entries, note) = peer
+            .runtime()
+            .block_on(async {
+                let entries = restored
+                    .get_many(Query::all().include_empty())
+                    .await?
+                    .try_collect::<_, _, Vec<_>>()
+                    .await?;
+                let note = restored
+                    .get_one(Query::single_latest_per_key().key_exact("note"))
+                    .await?
+                    .context("note missing")?;
+                let note = peer.store.get_bytes(note.content_hash()).await?;
+                anyhow::Ok((entries, note))
+            })
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(
+            entries
+                .iter()
+                .any(|e| e.key() == b"draft" && e.content_len() == 0)
+        );
+        assert_eq!(&note[..], b"saved");
+
+        // A snapshot only restores the document it was taken of
+        let other = DocTicket {
+            capability: Capability::Read(NamespaceSecret::new(&mut rand::rng()).id()),
+            nodes: Vec::new(),
+        };
+        let error = peer.doc_restore_snapshot(other, &snapshot).err().unwrap();
+        assert!(format!("{error:#}").contains("Snapshot is of document"));
+
+        peer.runtime().block_on(restored.close()).unwrap();
+        peer.shutdown().unwrap();
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_manifest_add_missing_prune() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+        let metadata = BlobMetadata {
+            content_type: Some("image/jpeg".to_string()),
+            ..Default::default()
+        };
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let pinned = || node.list_tags(manifest::TAG_PREFIX).unwrap().tags.len();
+
+        let photo = node
+            .manifest_add(&doc, author, "IMG_1", b"photo one", metadata.clone())
+            .unwrap();
+        node.manifest_add(&doc, author, "IMG_10", b"photo ten", metadata)
+            .unwrap();
+        let assets = node.manifest_assets(&doc).unwrap();
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0], photo);
+        assert_eq!(pinned(), 2);
+
+        assert_eq!(
+            node.manifest_missing(&doc, &ids(&["IMG_1", "IMG_2"]))
+                .unwrap(),
+            ids(&["IMG_2"])
+        );
+
+        // Pruning one asset keeps others whose IDs it prefixes
+        assert_eq!(
+            node.manifest_prune(&doc, author, &ids(&["IMG_1", "IMG_3"]))
+                .unwrap(),
+            1
+        );
+        let assets = node.manifest_assets(&doc).unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].asset_id, "IMG_10");
+        assert_eq!(pinned(), 1);
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_cursor_replays_missed_entries() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        node.runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "seen", "1").await?;
+                let cursor = crate::cursor::Cursor::current(&doc).await?;
+
+                doc.set_bytes(author, "missed", "2").await?;
+                doc.del(author, "seen").await?;
+                let missed = cursor.missed_entries(&doc).await?;
+                let keys: Vec<_> = missed.iter().map(|e| e.key().to_vec()).collect();
+                assert_eq!(keys, vec![b"missed".to_vec(), b"seen".to_vec()]);
+                // The deletion is replayed as an empty entry
+                assert_eq!(missed[1].content_len(), 0);
+
+                doc.close().await
+            })
+            .unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_query_timestamp_range() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+
+        let mut timestamps = Vec::new();
+        for key in ["feed/1", "feed/2", "feed/3", "other/4"] {
+            node.runtime()
+                .block_on(doc.set_bytes(author, key, key))
+                .unwrap();
+            let entry = node
+                .runtime()
+                .block_on(doc.get_exact(author, key, false))
+                .unwrap()
+                .unwrap();
+            timestamps.push(entry.timestamp());
+        }
+
+        let query = DocQuery {
+            key_prefix: b"feed/".to_vec(),
+            timestamp_start: Some(timestamps[1]),
+            timestamp_end: None,
+            latest_only: false,
+        };
+        let mut keys = Vec::new();
+        node.doc_query(&doc, &query, |entry| keys.push(entry.key().to_vec()))
+            .unwrap();
+        assert_eq!(keys, vec![b"feed/2".to_vec(), b"feed/3".to_vec()]);
+
+        let query = DocQuery {
+            timestamp_end: Some(timestamps[1]),
+            ..Default::default()
+        };
+        let mut keys = Vec::new();
+        node.doc_query(&doc, &query, |entry| keys.push(entry.key().to_vec()))
+            .unwrap();
+        assert_eq!(keys, vec![b"feed/1".to_vec()]);
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_query_latest_only() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, alice, bob) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let alice = docs.api().author_default().await?;
+                let bob = docs.api().author_create().await?;
+                doc.set_bytes(alice, "list/a", "alice a").await?;
+                doc.set_bytes(bob, "list/a", "bob a").await?;
+                doc.set_bytes(alice, "list/b", "alice b").await?;
+                doc.set_bytes(bob, "list/c", "bob c").await?;
+                doc.del(bob, "list/c").await?;
+                anyhow::Ok((doc, alice, bob))
+            })
+            .unwrap();
+
+        let entries = |latest_only| {
+            let query = DocQuery {
+                key_prefix: b"list/".to_vec(),
+                latest_only,
+                ..Default::default()
+            };
+            let mut entries = Vec::new();
+            node.doc_query(&doc, &query, |entry| {
+                entries.push((entry.key().to_vec(), entry.author()))
+            })
+            .unwrap();
+            entries
+        };
+
+        assert_eq!(entries(false).len(), 3);
+        assert_eq!(
+            entries(true),
+            vec![(b"list/a".to_vec(), bob), (b"list/b".to_vec(), alice)]
+        );
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_get_batch() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "settings/theme", "dark").await?;
+                doc.set_bytes(author, "settings/lang", "en").await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+
+        let keys = [
+            b"settings/lang".to_vec(),
+            b"settings/missing".to_vec(),
+            b"settings/theme".to_vec(),
+        ];
+        let entries = node.doc_get_batch(&doc, &keys).unwrap();
+        let hashes: Vec<_> = entries
+            .iter()
+            .map(|e| e.as_ref().map(|e| e.content_hash()))
+            .collect();
+        assert_eq!(
+            hashes,
+            vec![Some(Hash::new(b"en")), None, Some(Hash::new(b"dark"))]
+        );
+        assert!(entries.iter().flatten().all(|e| e.author() == author));
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "sqlite")]
+    fn test_doc_export_sqlite() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let doc = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "notes/a", "short").await?;
+                doc.set_bytes(author, "notes/b", "a longer value").await?;
+                anyhow::Ok(doc)
+            })
+            .unwrap();
+
+        let path = dir.path().join("export.sqlite");
+        assert_eq!(node.doc_export_sqlite(&doc, &path, 8).unwrap(), 2);
+        // Exporting again replaces the file
+        assert_eq!(node.doc_export_sqlite(&doc, &path, 8).unwrap(), 2);
+
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        let rows: Vec<(Vec<u8>, i64, Option<Vec<u8>>)> = conn
+            .prepare("SELECT key, content_len, value FROM entries ORDER BY key")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (b"notes/a".to_vec(), 5, Some(b"short".to_vec())),
+                (b"notes/b".to_vec(), 14, None),
+            ]
+        );
+        let namespace: String = conn
+            .query_row("SELECT namespace FROM document", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(namespace, doc.id().to_string());
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_export_query() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let doc = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "notes/a", "short").await?;
+                doc.set_bytes(author, "notes/b", vec![0xff, 0xfe]).await?;
+                doc.set_bytes(author, "notes/c", "a longer value").await?;
+                doc.set_bytes(author, "other", "skipped").await?;
+                anyhow::Ok(doc)
+            })
+            .unwrap();
+
+        let query = DocQuery {
+            key_prefix: b"notes/".to_vec(),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let exported = node
+            .doc_export_query(&doc, &query, 8, |chunk| {
+                output.extend_from_slice(chunk);
+                true
+            })
+            .unwrap();
+        assert_eq!(exported, 3);
+
+        let lines: Vec<serde_json::Value> = output
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["key"], "notes/a");
+        assert_eq!(lines[0]["value"], "short");
+        assert_eq!(lines[1]["value_hex"], "fffe");
+        assert_eq!(lines[2]["content_len"], 14);
+        assert!(lines[2].get("value").is_none());
+
+        // A writer returning false stops the export
+        assert!(node.doc_export_query(&doc, &query, 8, |_| false).is_err());
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_swarm_stats_counts_local_inserts() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let doc = node.runtime().block_on(docs.api().create()).unwrap();
+        node.track_swarm(&doc);
+        node.track_swarm(&doc);
+
+        node.runtime()
+            .block_on(async {
+                let author = docs.api().author_default().await?;
+                doc.set_bytes(author, "presence", "online").await
+            })
+            .unwrap();
+
+        let mut stats = node.doc_swarm_stats(doc.id());
+        for _ in 0..50 {
+            if stats.messages_sent > 0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+            stats = node.doc_swarm_stats(doc.id());
+        }
+        assert_eq!(stats.messages_sent, 1);
+        assert_eq!(stats.messages_received, 0);
+        assert_eq!(stats.neighbors, 0);
+        assert!(stats.last_activity.is_some());
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_node_with_reconnect_options() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                max_tls_tickets: Some(16),
+                idle_timeout: Some(Duration::from_secs(120)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let ticket = node.put(b"resumable").unwrap();
+        assert_eq!(node.get(&ticket).unwrap(), b"resumable");
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_transport_config_rejects_invalid_idle_timeouts() {
+        assert!(transport_config(Duration::from_millis(500)).is_ok());
+        assert!(transport_config(Duration::ZERO).is_err());
+        assert!(transport_config(Duration::MAX).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_entry_expires() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                docs_enabled: true,
+                ttl_prune_interval: None,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+
+        node.doc_set_with_ttl(
+            &doc,
+            author,
+            b"invite/a".to_vec(),
+            b"x".to_vec(),
+            Duration::ZERO,
+        )
+        .unwrap();
+        node.doc_set_with_ttl(
+            &doc,
+            author,
+            b"invite/b".to_vec(),
+            b"y".to_vec(),
+            Duration::from_secs(3600),
+        )
+        .unwrap();
+        // Rewriting without a TTL cancels the expiry
+        node.doc_set_with_ttl(
+            &doc,
+            author,
+            b"keep".to_vec(),
+            b"1".to_vec(),
+            Duration::ZERO,
+        )
+        .unwrap();
+        node.runtime()
+            .block_on(doc.set_bytes(author, b"keep".to_vec(), b"2".to_vec()))
+            .unwrap();
+
+        assert_eq!(node.prune_expired().unwrap(), 1);
+
+        let remaining: Vec<Vec<u8>> = node
+            .runtime()
+            .block_on(async {
+                let entries = doc.get_many(Query::single_latest_per_key()).await?;
+                let entries = entries.try_collect::<_, _, Vec<_>>().await?;
+                anyhow::Ok(entries.into_iter().map(|e| e.key().to_vec()).collect())
+            })
+            .unwrap();
+        assert!(!remaining.contains(&b"invite/a".to_vec()));
+        assert!(remaining.contains(&b"invite/b".to_vec()));
+        assert!(remaining.contains(&b"keep".to_vec()));
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_expiry_keeps_longer_keys() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                docs_enabled: true,
+                ttl_prune_interval: None,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+
+        let set = |key: &[u8], ttl| {
+            node.doc_set_with_ttl(&doc, author, key.to_vec(), b"x".to_vec(), ttl)
+                .unwrap();
+        };
+        set(b"invites/1", Duration::ZERO);
+        set(b"invites/10", Duration::from_secs(3600));
+        // A cancelled expiry under the pruned key must stay cancelled
+        set(b"invites/100", Duration::ZERO);
+        node.runtime()
+            .block_on(doc.set_bytes(author, b"invites/100".to_vec(), b"y".to_vec()))
+            .unwrap();
+
+        let timestamp = |key: &[u8]| {
+            node.runtime()
+                .block_on(doc.get_exact(author, key.to_vec(), false))
+                .unwrap()
+                .map(|e| e.timestamp())
+        };
+        let kept = [timestamp(b"invites/10"), timestamp(b".iroh-ttl/invites/10")];
+
+        assert_eq!(node.prune_expired().unwrap(), 1);
+        assert_eq!(node.prune_expired().unwrap(), 0);
+
+        // Longer keys are not written again
+        assert_eq!(
+            [timestamp(b"invites/10"), timestamp(b".iroh-ttl/invites/10"),],
+            kept
+        );
+
+        let remaining: Vec<Vec<u8>> = node
+            .runtime()
+            .block_on(async {
+                let entries = doc.get_many(Query::single_latest_per_key()).await?;
+                let entries = entries.try_collect::<_, _, Vec<_>>().await?;
+                anyhow::Ok(entries.into_iter().map(|e| e.key().to_vec()).collect())
+            })
+            .unwrap();
+        assert!(!remaining.contains(&b"invites/1".to_vec()));
+        assert!(!remaining.contains(&b".iroh-ttl/invites/1".to_vec()));
+        assert!(remaining.contains(&b"invites/10".to_vec()));
+        assert!(remaining.contains(&b".iroh-ttl/invites/10".to_vec()));
+        assert!(remaining.contains(&b"invites/100".to_vec()));
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_expiry_nested_keys() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                docs_enabled: true,
+                ttl_prune_interval: None,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+
+        let set = |key: &[u8], ttl| {
+            node.doc_set_with_ttl(&doc, author, key.to_vec(), b"x".to_vec(), ttl)
+                .unwrap();
+        };
+        // Shorter keys written after the longer keys they extend
+        set(b"session/1", Duration::ZERO);
+        set(b"session", Duration::ZERO);
+        // An expired key can't sweep an older key that doesn't expire
+        node.runtime()
+            .block_on(doc.set_bytes(author, b"cache/a".to_vec(), b"y".to_vec()))
+            .unwrap();
+        set(b"cache", Duration::ZERO);
+        set(b"tmp", Duration::ZERO);
+
+        assert_eq!(node.prune_expired().unwrap(), 3);
+        assert_eq!(node.prune_expired().unwrap(), 0);
+
+        let remaining: Vec<Vec<u8>> = node
+            .runtime()
+            .block_on(async {
+                let entries = doc.get_many(Query::single_latest_per_key()).await?;
+                let entries = entries.try_collect::<_, _, Vec<_>>().await?;
+                anyhow::Ok(entries.into_iter().map(|e| e.key().to_vec()).collect())
+            })
+            .unwrap();
+        assert_eq!(
+            remaining,
+            vec![
+                b".iroh-ttl/cache".to_vec(),
+                b"cache".to_vec(),
+                b"cache/a".to_vec(),
+            ]
+        );
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_del_range() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                for key in [
+                    "events/2022/12",
+                    "events/2023/01",
+                    "events/2023/06",
+                    "events/2024/01",
+                ] {
+                    doc.set_bytes(author, key.as_bytes().to_vec(), b"x".to_vec())
+                        .await?;
+                }
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+
+        let deleted = node
+            .doc_del_range(&doc, author, b"events/2023/", b"events/2024/")
+            .unwrap();
+        assert_eq!(deleted, 2);
+
+        let remaining: Vec<Vec<u8>> = node
+            .runtime()
+            .block_on(async {
+                let entries = doc.get_many(Query::all()).await?;
+                let entries = entries.try_collect::<_, _, Vec<_>>().await?;
+                anyhow::Ok(entries.into_iter().map(|e| e.key().to_vec()).collect())
+            })
+            .unwrap();
+        assert_eq!(
+            remaining,
+            vec![b"events/2022/12".to_vec(), b"events/2024/01".to_vec()]
+        );
+
+        // Keys past the end of the range survive even if they extend a
+        // deleted key
+        node.runtime()
+            .block_on(async {
+                for key in ["a", "ab", "abc"] {
+                    doc.set_bytes(author, key.as_bytes().to_vec(), b"x".to_vec())
+                        .await?;
+                }
+                anyhow::Ok(())
+            })
+            .unwrap();
+        assert_eq!(node.doc_del_range(&doc, author, b"a", b"ab").unwrap(), 1);
+        let remaining: Vec<Vec<u8>> = node
+            .runtime()
+            .block_on(async {
+                let entries = doc.get_many(Query::key_prefix("a")).await?;
+                let entries = entries.try_collect::<_, _, Vec<_>>().await?;
+                anyhow::Ok(entries.into_iter().map(|e| e.key().to_vec()).collect())
+            })
+            .unwrap();
+        assert_eq!(remaining, vec![b"ab".to_vec(), b"abc".to_vec()]);
+
+        // Entries outside the range are not written again, so a newer
+        // write by another author stays the latest
+        let (bob, before) = node
+            .runtime()
+            .block_on(async {
+                let bob = docs.api().author_create().await?;
+                doc.set_bytes(author, "n", "x").await?;
+                doc.set_bytes(author, "n/1", "mine").await?;
+                doc.set_bytes(bob, "n/1", "bob's").await?;
+                let before = doc.get_exact(author, "n/1", false).await?.unwrap();
+                anyhow::Ok((bob, before))
+            })
+            .unwrap();
+        assert_eq!(node.doc_del_range(&doc, author, b"n", b"n/").unwrap(), 1);
+        node.runtime()
+            .block_on(async {
+                let after = doc.get_exact(author, "n/1", false).await?.unwrap();
+                assert_eq!(after.timestamp(), before.timestamp());
+                assert!(doc.get_exact(author, "n", false).await?.is_none());
+                let newest = doc
+                    .get_one(Query::single_latest_per_key().key_exact("n/1"))
+                    .await?
+                    .unwrap();
+                assert_eq!(newest.author(), bob);
+                assert_eq!(node.store.get_bytes(newest.content_hash()).await?, "bob's");
+                anyhow::Ok(())
+            })
+            .unwrap();
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_doc_del_range_nested_keys() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, true).unwrap();
+        let docs = node.docs().unwrap().clone();
+        let (doc, author) = node
+            .runtime()
+            .block_on(async {
+                let doc = docs.api().create().await?;
+                let author = docs.api().author_default().await?;
+                // A write removes the author's older keys that extend it, so
+                // nested keys are written shorter first
+                for key in ["events/2023", "events/2023/01", "other", "other/1"] {
+                    doc.set_bytes(author, key.as_bytes().to_vec(), b"x".to_vec())
+                        .await?;
+                }
+                anyhow::Ok((doc, author))
+            })
+            .unwrap();
+        let keys = |prefix: &'static str| {
+            node.runtime()
+                .block_on(async {
+                    let entries = doc.get_many(Query::key_prefix(prefix)).await?;
+                    let entries = entries.try_collect::<_, _, Vec<_>>().await?;
+                    anyhow::Ok(
+                        entries
+                            .into_iter()
+                            .map(|e| e.key().to_vec())
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .unwrap()
+        };
+
+        // Both keys are in range, so sweeping the longer one is fine
+        let deleted = node
+            .doc_del_range(&doc, author, b"events/", b"events0")
+            .unwrap();
+        assert_eq!(deleted, 2);
+        assert!(keys("events").is_empty());
+
+        // The newer longer key is outside the range and stays
+        assert_eq!(
+            node.doc_del_range(&doc, author, b"other", b"other/")
+                .unwrap(),
+            1
+        );
+        assert_eq!(keys("other"), vec![b"other/1".to_vec()]);
+
+        node.runtime().block_on(doc.close()).unwrap();
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_store_inlining_limits() {
+        let data_files = |dir: &std::path::Path| {
+            std::fs::read_dir(dir.join("data"))
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| entry.path().extension() == Some("data".as_ref()))
+                        .count()
+                })
+                .unwrap_or(0)
+        };
+        let put_small = |store_inlining| {
+            let dir = tempdir().unwrap();
+            let node = IrohNode::with_options(
+                dir.path().to_path_buf(),
+                NodeOptions {
+                    relay_enabled: false,
+                    store_inlining,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            node.put(&[7u8; 4096]).unwrap();
+            node.shutdown().unwrap();
+            data_files(dir.path())
+        };
+
+        assert_eq!(put_small(StoreInlining::default()), 0);
+        let files = put_small(StoreInlining {
+            max_data: Some(1024),
+            max_outboard: None,
+        });
+        assert_eq!(files, 1);
+    }
+
+    #[test]
+    fn test_corrupt_blob_store_recovery() {
+        let open = |dir: &std::path::Path, blob_store_recovery| {
+            IrohNode::with_options(
+                dir.to_path_buf(),
+                NodeOptions {
+                    relay_enabled: false,
+                    blob_store_recovery,
+                    ..Default::default()
+                },
+            )
+        };
+        let has =
+            |node: &IrohNode, hash| node.runtime.block_on(node.store.blobs().has(hash)).unwrap();
+        let dir = tempdir().unwrap();
+        let node = open(dir.path(), BlobStoreRecovery::Fail).unwrap();
+        let hash = node
+            .put(&[7u8; 64 * 1024])
+            .unwrap()
+            .parse::<BlobTicket>()
+            .unwrap()
+            .hash();
+        node.shutdown().unwrap();
+
+        // The index is unreadable after a bad shutdown
+        let db = dir.path().join("blobs.db");
+        std::fs::write(&db, vec![0x5a; 8192]).unwrap();
+        let error = open(dir.path(), BlobStoreRecovery::Fail).err().unwrap();
+        let corrupt = error.downcast_ref::<StoreCorrupt>().unwrap();
+        assert_eq!(corrupt.database, StoreDatabase::Blobs);
+        assert_eq!(corrupt.path, db);
+
+        // Blob files are added back unless they no longer match their hash
+        let garbled = format!("{}.data", Hash::new(b"original").to_hex());
+        std::fs::write(dir.path().join("data").join(&garbled), b"garbled").unwrap();
+        let node = open(dir.path(), BlobStoreRecovery::Reindex).unwrap();
+        assert!(has(&node, hash));
+        node.shutdown().unwrap();
+        let quarantined = std::fs::read_dir(dir.path().join("quarantine"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert!(quarantined.join("blobs.db").exists());
+        assert!(quarantined.join("data").join(garbled).exists());
+        assert!(
+            !quarantined
+                .join("data")
+                .join(format!("{}.data", hash.to_hex()))
+                .exists()
+        );
+
+        // Quarantining starts over with an empty store
+        std::fs::write(&db, vec![0x5a; 8192]).unwrap();
+        let node = open(dir.path(), BlobStoreRecovery::Quarantine).unwrap();
+        assert!(!has(&node, hash));
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "docs")]
+    fn test_corrupt_docs_store_recovery() {
+        let open = |dir: &std::path::Path, docs_store_recovery| {
+            IrohNode::with_options(
+                dir.to_path_buf(),
+                NodeOptions {
+                    relay_enabled: false,
+                    docs_enabled: true,
+                    docs_store_recovery,
+                    ..Default::default()
+                },
+            )
+        };
+        let dir = tempdir().unwrap();
+        open(dir.path(), DocsStoreRecovery::Fail)
+            .unwrap()
+            .shutdown()
+            .unwrap();
+
+        let db = dir.path().join("docs").join("docs.redb");
+        std::fs::write(&db, vec![0x5a; 8192]).unwrap();
+        let error = open(dir.path(), DocsStoreRecovery::Fail).err().unwrap();
+        let corrupt = error.downcast_ref::<StoreCorrupt>().unwrap();
+        assert_eq!(corrupt.database, StoreDatabase::Docs);
+        assert_eq!(corrupt.path, db);
+
+        let node = open(dir.path(), DocsStoreRecovery::Discard).unwrap();
+        assert!(node.is_docs_enabled());
+        node.shutdown().unwrap();
+        let quarantined = std::fs::read_dir(dir.path().join("quarantine"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert!(quarantined.join("docs.redb").exists());
+    }
+
+    #[test]
+    fn test_ephemeral_node_removes_its_data() {
+        let temp = tempdir().unwrap();
+        let unused = temp.path().join("unused");
+        let node = IrohNode::with_options(
+            unused.clone(),
+            NodeOptions {
+                relay_enabled: false,
+                temp_dir: Some(temp.path().to_path_buf()),
+                ephemeral: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let storage = node.storage_path().to_path_buf();
+        assert!(storage.starts_with(temp.path()));
+        let ticket = node.put(b"preview").unwrap();
+        assert_eq!(node.get(&ticket).unwrap(), b"preview");
+        assert!(storage.join("blobs.db").exists());
+
+        node.shutdown().unwrap();
+        assert!(!storage.exists());
+        assert!(!unused.exists());
+    }
+
+    #[test]
+    fn test_app_id_reported_and_checked() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::with_options(
+            dir.path().to_path_buf(),
+            NodeOptions {
+                relay_enabled: false,
+                app_id: Some("com.example.photos/2.1".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(node.app_id(), Some("com.example.photos/2.1"));
+        assert_eq!(
+            node.info().unwrap().app_id.as_deref(),
+            Some("com.example.photos/2.1")
+        );
+        node.shutdown().unwrap();
+
+        assert!(app_user_data("").is_err());
+        assert!(app_user_data("bad\nid").is_err());
+        assert!(app_user_data(&"x".repeat(UserData::MAX_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_get_from_providers_rejects_mismatched_hashes() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let first = node.put(b"first").unwrap();
+        let second = node.put(b"second").unwrap();
+
+        let result = node.get_from_providers(&[first.clone(), second], ProviderStrategy::Race);
+        assert!(result.is_err());
+
+        let bytes = node
+            .get_from_providers(&[first], ProviderStrategy::Race)
+            .unwrap();
+        assert_eq!(bytes, b"first");
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "downloader-progress")]
+    fn test_get_to_sink_local_content() {
+        let dir = tempdir().unwrap();
+        let node = IrohNode::new(dir.path().to_path_buf(), false, None, false).unwrap();
+
+        let data = vec![7u8; SINK_CHUNK_SIZE * 2 + 10];
+        let ticket = node.put(&data).unwrap();
+
+        let mut received = Vec::new();
+        let written = node
+            .get_to_sink(&ticket, |chunk| {
+                received.extend_from_slice(chunk);
+                true
+            })
+            .unwrap();
+        assert_eq!(written, data.len() as u64);
+        assert_eq!(received, data);
+
+        assert!(node.get_to_sink(&ticket, |_| false).is_err());
+
+        node.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_export_temp_file_deleted_on_drop() {
+        let dir = tempdir().unwrap();
+        let temp = tempdir().unwrap();
+        let options = NodeOptions {
+            relay_enabled: false,
+            temp_dir: Some(temp.path().to_path_buf()),
+            ..Default::default()
+        };
+        let node = IrohNode::with_options(dir.path().to_path_buf(), options).unwrap();
+
+        let ticket = node.put(b"export me").unwrap();
+        let hash = ticket.parse::<BlobTicket>().unwrap().hash();
+
+        let file = node.export_temp_file(hash, Some("../report.pdf")).unwrap();
+        let path = file.path().to_path_buf();
+        assert!(path.starts_with(temp.path()));
+        assert_eq!(path.file_name().unwrap(), "report.pdf");
+        assert_eq!(std::fs::read(&path).unwrap(), b"export me");
+
+        drop(file);
+        assert!(!path.exists());
 
         node.shutdown().unwrap();
     }
@@ -0,0 +1,371 @@
+//! Outbox for document writes that have to reach a peer.
+//!
+//! Document writes are stored locally right away and synced whenever a peer
+//! is reachable, but apps cannot tell when a write has left the device.
+//! Writes made through the outbox are recorded in a file next to the store
+//! until a successful sync with a peer started after them, and the node
+//! keeps asking the document's known peers to sync in the meantime, with
+//! backoff and again whenever connectivity changes. Pending writes survive
+//! restarts.
+//!
+//! Every item reports its progress as [`NodeEvent::Outbox`]. An item is
+//! superseded instead of delivered when another author's newer write to the
+//! same key won, so the app can merge it rather than lose the update.
+
+use crate::clock::micros;
+use crate::events::{EventBus, NodeEvent};
+use anyhow::{Context, Result};
+use futures_lite::StreamExt;
+use iroh::{EndpointAddr, EndpointId};
+use iroh_blobs::Hash;
+use iroh_docs::api::Doc;
+use iroh_docs::engine::LiveEvent;
+use iroh_docs::protocol::Docs;
+use iroh_docs::store::Query;
+use iroh_docs::{AuthorId, NamespaceId};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Delay before the first sync retry, doubled after every attempt.
+const RETRY_MIN: Duration = Duration::from_secs(5);
+
+/// Longest delay between sync retries.
+const RETRY_MAX: Duration = Duration::from_secs(300);
+
+/// Where an outbox item stands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutboxStatus {
+    /// Written locally and waiting for a sync.
+    Queued,
+    /// A peer synced the write.
+    Delivered,
+    /// A peer synced the write, but a newer write by another author won.
+    Superseded {
+        /// Author of the winning entry.
+        by: AuthorId,
+    },
+    /// The write can never be delivered.
+    Failed(String),
+}
+
+/// A write waiting for delivery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxItem {
+    /// Identifier, unique for the node's storage directory.
+    pub id: u64,
+    pub namespace: NamespaceId,
+    pub author: AuthorId,
+    pub key: Vec<u8>,
+    pub content_hash: Hash,
+    /// Entry timestamp in microseconds since epoch.
+    pub timestamp: u64,
+}
+
+impl OutboxItem {
+    /// One line of the outbox file.
+    fn to_record(&self) -> String {
+        format!(
+            "{} {} {} {} {} {}",
+            self.id,
+            self.namespace,
+            self.author,
+            hex::encode(&self.key),
+            self.content_hash.to_hex(),
+            self.timestamp
+        )
+    }
+
+    fn from_record(line: &str) -> Result<Self> {
+        let fields: Vec<&str> = line.split(' ').collect();
+        let [id, namespace, author, key, content_hash, timestamp] = fields[..] else {
+            anyhow::bail!("Invalid outbox record");
+        };
+        Ok(Self {
+            id: id.parse()?,
+            namespace: NamespaceId::from_str(namespace)?,
+            author: AuthorId::from_str(author)?,
+            key: hex::decode(key)?,
+            content_hash: Hash::from_str(content_hash)?,
+            timestamp: timestamp.parse()?,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: u64,
+    items: BTreeMap<u64, OutboxItem>,
+    /// Namespaces with a delivery task running.
+    watching: HashSet<NamespaceId>,
+}
+
+/// Pending document writes and their delivery tasks.
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    path: PathBuf,
+    events: EventBus,
+    state: Arc<Mutex<State>>,
+}
+
+impl Outbox {
+    /// Load the outbox stored at `path`, or start an empty one.
+    ///
+    /// Unreadable records are skipped rather than keeping the node from
+    /// starting.
+    pub fn load(path: PathBuf, events: EventBus) -> Result<Self> {
+        let mut state = State::default();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let mut lines = contents.lines();
+                state.next_id = lines.next().and_then(|l| l.parse().ok()).unwrap_or(0);
+                for item in lines.filter_map(|l| OutboxItem::from_record(l).ok()) {
+                    state.next_id = state.next_id.max(item.id + 1);
+                    state.items.insert(item.id, item);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context("Failed to read outbox"),
+        }
+        Ok(Self {
+            path,
+            events,
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Every pending item, oldest first.
+    pub fn items(&self) -> Vec<OutboxItem> {
+        self.state.lock().unwrap().items.values().cloned().collect()
+    }
+
+    fn pending(&self, namespace: NamespaceId) -> Vec<OutboxItem> {
+        let state = self.state.lock().unwrap();
+        state
+            .items
+            .values()
+            .filter(|item| item.namespace == namespace)
+            .cloned()
+            .collect()
+    }
+
+    /// Record a write that was stored locally, returning its ID.
+    ///
+    /// The item is persisted before this returns.
+    pub fn push(
+        &self,
+        namespace: NamespaceId,
+        author: AuthorId,
+        key: Vec<u8>,
+        content_hash: Hash,
+        timestamp: u64,
+    ) -> Result<u64> {
+        let item = {
+            let mut state = self.state.lock().unwrap();
+            let item = OutboxItem {
+                id: state.next_id,
+                namespace,
+                author,
+                key,
+                content_hash,
+                timestamp,
+            };
+            state.next_id += 1;
+            state.items.insert(item.id, item.clone());
+            if let Err(e) = self.persist(&state) {
+                state.items.remove(&item.id);
+                return Err(e);
+            }
+            item
+        };
+        self.emit(&item, OutboxStatus::Queued);
+        Ok(item.id)
+    }
+
+    /// Remove a settled item and report its final status.
+    fn finish(&self, item: &OutboxItem, status: OutboxStatus) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.items.remove(&item.id).is_none() {
+                return;
+            }
+            // If this fails the item is reported again after a restart
+            let _ = self.persist(&state);
+        }
+        self.emit(item, status);
+    }
+
+    fn emit(&self, item: &OutboxItem, status: OutboxStatus) {
+        self.events.emit(NodeEvent::Outbox {
+            id: item.id,
+            namespace: item.namespace,
+            key: item.key.clone(),
+            status,
+        });
+    }
+
+    /// Write the outbox file, replacing it atomically.
+    fn persist(&self, state: &State) -> Result<()> {
+        let mut contents = format!("{}\n", state.next_id);
+        for item in state.items.values() {
+            contents.push_str(&item.to_record());
+            contents.push('\n');
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, contents).context("Failed to write outbox")?;
+        std::fs::rename(&tmp, &self.path).context("Failed to write outbox")
+    }
+
+    /// Start delivering the pending items of `namespace` unless that is
+    /// already under way.
+    ///
+    /// Must be called from within the node's runtime.
+    pub fn watch(&self, docs: &Docs, namespace: NamespaceId) {
+        if !self.state.lock().unwrap().watching.insert(namespace) {
+            return;
+        }
+        let (outbox, docs) = (self.clone(), docs.clone());
+        tokio::spawn(async move {
+            // Items left over after an error are retried on the next write
+            // to the document or the next start
+            let _ = outbox.deliver(&docs, namespace).await;
+            outbox.state.lock().unwrap().watching.remove(&namespace);
+        });
+    }
+
+    /// Start delivering every pending item.
+    ///
+    /// Must be called from within the node's runtime.
+    pub fn resume(&self, docs: &Docs) {
+        let namespaces: HashSet<NamespaceId> =
+            self.items().iter().map(|item| item.namespace).collect();
+        for namespace in namespaces {
+            self.watch(docs, namespace);
+        }
+    }
+
+    /// Sync `namespace` until all its items are settled or the node shuts down.
+    async fn deliver(&self, docs: &Docs, namespace: NamespaceId) -> Result<()> {
+        let Some(doc) = docs.api().open(namespace).await? else {
+            for item in self.pending(namespace) {
+                self.finish(&item, OutboxStatus::Failed("Document not found".into()));
+            }
+            return Ok(());
+        };
+        let result = self.deliver_doc(&doc, namespace).await;
+        let _ = doc.close().await;
+        result
+    }
+
+    async fn deliver_doc(&self, doc: &Doc, namespace: NamespaceId) -> Result<()> {
+        let mut live = doc.subscribe().await?;
+        let mut node_events = self.events.subscribe();
+        let mut backoff = RETRY_MIN;
+        let retry = tokio::time::sleep(Duration::ZERO);
+        tokio::pin!(retry);
+
+        while !self.pending(namespace).is_empty() {
+            tokio::select! {
+                _ = &mut retry => {
+                    // A failed attempt is retried after the backoff
+                    let _ = request_sync(doc).await;
+                    retry.as_mut().reset(Instant::now() + backoff);
+                    backoff = (backoff * 2).min(RETRY_MAX);
+                }
+                event = live.next() => match event.transpose()? {
+                    None => return Ok(()),
+                    Some(LiveEvent::SyncFinished(sync)) if sync.result.is_ok() => {
+                        self.settle(doc, namespace, micros(sync.started)).await?;
+                    }
+                    Some(_) => {}
+                },
+                event = node_events.next() => match event {
+                    // The node is shutting down
+                    None => return Ok(()),
+                    Some(NodeEvent::Connectivity { .. }) => {
+                        backoff = RETRY_MIN;
+                        retry.as_mut().reset(Instant::now());
+                    }
+                    Some(_) => {}
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Settle the items of `namespace` written before a sync that started at
+    /// `started` and succeeded.
+    async fn settle(&self, doc: &Doc, namespace: NamespaceId, started: u64) -> Result<()> {
+        for item in self.pending(namespace) {
+            if item.timestamp >= started {
+                continue;
+            }
+            let latest = doc
+                .get_one(
+                    Query::single_latest_per_key()
+                        .key_exact(&item.key)
+                        .include_empty(),
+                )
+                .await
+                .context("Failed to read entry")?;
+            let status = match latest {
+                Some(entry)
+                    if entry.author() != item.author && entry.timestamp() > item.timestamp =>
+                {
+                    OutboxStatus::Superseded { by: entry.author() }
+                }
+                _ => OutboxStatus::Delivered,
+            };
+            self.finish(&item, status);
+        }
+        Ok(())
+    }
+}
+
+/// Ask the document's known peers to sync, joining its swarm if needed.
+async fn request_sync(doc: &Doc) -> Result<()> {
+    let peers = doc
+        .get_sync_peers()
+        .await?
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|bytes| EndpointId::from_bytes(bytes).ok())
+        .map(EndpointAddr::new)
+        .collect();
+    doc.start_sync(peers).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh_docs::{Author, NamespaceSecret};
+
+    #[test]
+    fn test_outbox_survives_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("outbox");
+        let namespace = NamespaceSecret::new(&mut rand::rng()).id();
+        let author = Author::new(&mut rand::rng()).id();
+
+        let outbox = Outbox::load(path.clone(), EventBus::default()).unwrap();
+        let first = outbox
+            .push(namespace, author, b"a".to_vec(), Hash::new(b"1"), 10)
+            .unwrap();
+        let second = outbox
+            .push(namespace, author, b"b".to_vec(), Hash::new(b"2"), 20)
+            .unwrap();
+        outbox.finish(&outbox.items()[0], OutboxStatus::Delivered);
+
+        let reloaded = Outbox::load(path, EventBus::default()).unwrap();
+        assert_eq!(reloaded.items(), outbox.items());
+        assert_eq!(reloaded.items()[0].id, second);
+        // IDs are not reused after the first item was removed
+        let third = reloaded
+            .push(namespace, author, Vec::new(), Hash::new(b""), 30)
+            .unwrap();
+        assert!(third > second && second > first);
+    }
+}
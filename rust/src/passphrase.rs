@@ -0,0 +1,170 @@
+//! Passphrase-protected tickets.
+//!
+//! Tickets pasted into chat apps can be used by anyone who sees them. A
+//! locked ticket is encrypted with a key stretched from a passphrase that
+//! is shared out of band, so an intercepted message alone is useless.
+//!
+//! A locked ticket is `locked` followed by lowercase base32 of:
+//!
+//! | bytes | field |
+//! |-------|-------|
+//! | 1 | version (1) |
+//! | 4 | PBKDF2-HMAC-SHA256 iterations, big endian |
+//! | 16 | salt |
+//! | 12 | nonce |
+//! | .. | ChaCha20-Poly1305 ciphertext and tag of the standard ticket |
+//!
+//! The header before the ciphertext is authenticated as associated data.
+
+use crate::ticket;
+use anyhow::{Context, Result, anyhow, bail, ensure};
+use data_encoding::BASE32_NOPAD;
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, Nonce, UnboundKey};
+use ring::pbkdf2;
+use std::num::NonZeroU32;
+
+/// Prefix that marks a locked ticket.
+const LOCKED_PREFIX: &str = "locked";
+
+/// Format version written by [`lock`].
+const VERSION: u8 = 1;
+
+/// Key stretching work for new tickets, following OWASP's recommendation
+/// for PBKDF2-HMAC-SHA256.
+const ITERATIONS: u32 = 600_000;
+
+/// Upper bound on the work a locked ticket may ask for, so a crafted
+/// ticket cannot stall the app.
+const MAX_ITERATIONS: u32 = 10_000_000;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = 1 + 4 + SALT_LEN + NONCE_LEN;
+
+/// Encrypt a blob or doc ticket with `passphrase`.
+///
+/// The ticket may be in any supported encoding; the standard form is what
+/// gets encrypted and what [`unlock`] returns.
+pub fn lock(ticket: &str, passphrase: &str) -> Result<String> {
+    ensure!(!passphrase.is_empty(), "passphrase cannot be empty");
+    let ticket = ticket::decode(ticket)?;
+
+    let salt: [u8; SALT_LEN] = rand::random();
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.push(VERSION);
+    header.extend_from_slice(&ITERATIONS.to_be_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let key = derive_key(passphrase, &salt, ITERATIONS)?;
+    let mut sealed = ticket.into_bytes();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(&header),
+        &mut sealed,
+    )
+    .map_err(|_| anyhow!("failed to encrypt ticket"))?;
+
+    header.extend_from_slice(&sealed);
+    Ok(format!(
+        "{LOCKED_PREFIX}{}",
+        BASE32_NOPAD.encode(&header).to_ascii_lowercase()
+    ))
+}
+
+/// Decrypt a ticket produced by [`lock`], returning the standard ticket.
+pub fn unlock(locked: &str, passphrase: &str) -> Result<String> {
+    let payload = locked
+        .trim()
+        .to_ascii_lowercase()
+        .strip_prefix(LOCKED_PREFIX)
+        .context("not a locked ticket")?
+        .to_ascii_uppercase();
+    let mut data = BASE32_NOPAD
+        .decode(payload.as_bytes())
+        .context("invalid locked ticket")?;
+    ensure!(data.len() > HEADER_LEN, "locked ticket is truncated");
+    if data[0] != VERSION {
+        bail!("unsupported locked ticket version {}", data[0]);
+    }
+    let iterations = u32::from_be_bytes(data[1..5].try_into()?);
+    ensure!(
+        iterations <= MAX_ITERATIONS,
+        "locked ticket asks for too many iterations"
+    );
+
+    let mut sealed = data.split_off(HEADER_LEN);
+    let (salt, nonce) = data[5..].split_at(SALT_LEN);
+    let key = derive_key(passphrase, salt, iterations)?;
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("invalid nonce"))?;
+    let ticket = key
+        .open_in_place(nonce, Aad::from(&data), &mut sealed)
+        .map_err(|_| anyhow!("wrong passphrase or damaged ticket"))?;
+    let ticket = std::str::from_utf8(ticket).context("locked ticket is not text")?;
+    ticket::decode(ticket)
+}
+
+/// Stretch `passphrase` into an encryption key.
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations).context("invalid iteration count")?;
+    let mut key = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&CHACHA20_POLY1305, &key).map_err(|_| anyhow!("invalid key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::{EndpointAddr, SecretKey};
+    use iroh_blobs::ticket::BlobTicket;
+    use iroh_blobs::{BlobFormat, Hash};
+
+    fn sample_ticket() -> String {
+        let key = SecretKey::from_bytes(&[7u8; 32]);
+        let addr = EndpointAddr::new(key.public());
+        BlobTicket::new(addr, Hash::new(b"hello"), BlobFormat::Raw).to_string()
+    }
+
+    #[test]
+    fn test_lock_roundtrip() {
+        let ticket = sample_ticket();
+        let locked = lock(&ticket, "correct horse").unwrap();
+        assert!(locked.starts_with(LOCKED_PREFIX));
+        assert!(!locked.contains(&ticket));
+        assert_eq!(unlock(&locked, "correct horse").unwrap(), ticket);
+        // Survives being uppercased, e.g. for QR codes
+        assert_eq!(
+            unlock(&locked.to_ascii_uppercase(), "correct horse").unwrap(),
+            ticket
+        );
+    }
+
+    #[test]
+    fn test_unlock_rejects_wrong_passphrase_and_tampering() {
+        let locked = lock(&sample_ticket(), "correct horse").unwrap();
+        assert!(unlock(&locked, "battery staple").is_err());
+
+        // Flip a bit of the authenticated iteration count
+        let mut data = BASE32_NOPAD
+            .decode(
+                locked[LOCKED_PREFIX.len()..]
+                    .to_ascii_uppercase()
+                    .as_bytes(),
+            )
+            .unwrap();
+        data[4] ^= 1;
+        let tampered = format!("{LOCKED_PREFIX}{}", BASE32_NOPAD.encode(&data));
+        assert!(unlock(&tampered, "correct horse").is_err());
+
+        assert!(lock(&sample_ticket(), "").is_err());
+        assert!(unlock(&sample_ticket(), "correct horse").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Inbound connections grouped by remote peer.
+//!
+//! Protocol handlers registered on the router are wrapped so that the
+//! connections they are serving can be looked up by the remote endpoint ID
+//! and closed, for example after the remote account has been blocked.
+//! Bytes sent and received on those connections are accumulated per peer
+//! for the lifetime of the node.
+//!
+//! An optional limit caps how many connections are served at once. When a
+//! new connection would exceed it, the connection that has been idle the
+//! longest is closed to make room.
+
+use iroh::EndpointId;
+use iroh::endpoint::{Connection, VarInt};
+use iroh::protocol::{AcceptError, ProtocolHandler};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// QUIC application error code sent to peers whose connections are closed.
+const CLOSED_BY_APP: u32 = 1;
+
+/// QUIC application error code sent when an idle connection makes room.
+const CLOSED_IDLE: u32 = 2;
+
+/// Bytes exchanged with one peer on the connections it opened, as counted
+/// on the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerTraffic {
+    /// Bytes sent to the peer.
+    pub uploaded: u64,
+    /// Bytes received from the peer; downloads this node starts are not
+    /// counted.
+    pub downloaded: u64,
+}
+
+impl PeerTraffic {
+    fn of(connection: &Connection) -> Self {
+        let stats = connection.stats();
+        Self {
+            uploaded: stats.udp_tx.bytes,
+            downloaded: stats.udp_rx.bytes,
+        }
+    }
+
+    fn add(&mut self, other: PeerTraffic) {
+        self.uploaded += other.uploaded;
+        self.downloaded += other.downloaded;
+    }
+
+    fn total(&self) -> u64 {
+        self.uploaded + self.downloaded
+    }
+}
+
+/// A connection being served, with the last time it moved any bytes.
+#[derive(Debug)]
+struct Open {
+    connection: Connection,
+    last_active: Instant,
+    last_bytes: u64,
+}
+
+impl Open {
+    fn new(connection: Connection) -> Self {
+        Self {
+            last_bytes: PeerTraffic::of(&connection).total(),
+            connection,
+            last_active: Instant::now(),
+        }
+    }
+
+    /// Note any traffic since the last call and return when it last happened.
+    fn refresh(&mut self, now: Instant) -> Instant {
+        let bytes = PeerTraffic::of(&self.connection).total();
+        if bytes != self.last_bytes {
+            self.last_bytes = bytes;
+            self.last_active = now;
+        }
+        self.last_active
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Open connections by peer and stable connection ID.
+    open: HashMap<EndpointId, HashMap<usize, Open>>,
+    /// Traffic of connections that have ended.
+    finished: HashMap<EndpointId, PeerTraffic>,
+    /// Maximum number of open connections, if capped.
+    limit: Option<usize>,
+}
+
+impl State {
+    fn len(&self) -> usize {
+        self.open.values().map(HashMap::len).sum()
+    }
+
+    /// Close the connection that has been idle the longest.
+    fn evict_idle(&mut self) {
+        let now = Instant::now();
+        let oldest = self
+            .open
+            .iter_mut()
+            .flat_map(|(peer, connections)| {
+                connections
+                    .iter_mut()
+                    .map(move |(id, open)| (open.refresh(now), *peer, *id))
+            })
+            .min();
+        if let Some((_, peer, id)) = oldest
+            && let Some(open) = self.take(peer, id)
+        {
+            open.connection
+                .close(VarInt::from_u32(CLOSED_IDLE), b"too many connections");
+        }
+    }
+
+    /// Stop tracking a connection and count its traffic as finished.
+    fn take(&mut self, peer: EndpointId, id: usize) -> Option<Open> {
+        let connections = self.open.get_mut(&peer)?;
+        let open = connections.remove(&id)?;
+        if connections.is_empty() {
+            self.open.remove(&peer);
+        }
+        self.finished
+            .entry(peer)
+            .or_default()
+            .add(PeerTraffic::of(&open.connection));
+        Some(open)
+    }
+}
+
+/// Connections currently served by tracked protocol handlers.
+#[derive(Debug, Clone, Default)]
+pub struct PeerConnections(Arc<Mutex<State>>);
+
+impl PeerConnections {
+    /// Track connections, serving at most `limit` at once if set.
+    pub fn with_limit(limit: Option<usize>) -> Self {
+        Self(Arc::new(Mutex::new(State {
+            limit,
+            ..Default::default()
+        })))
+    }
+
+    /// Wrap `handler` so its connections are tracked here.
+    pub fn track<P: ProtocolHandler>(&self, handler: P) -> Tracked<P> {
+        Tracked {
+            inner: handler,
+            connections: self.clone(),
+        }
+    }
+
+    /// Close every tracked connection from `peer`, returning how many were open.
+    ///
+    /// The peer may connect again afterwards.
+    pub fn close(&self, peer: EndpointId) -> usize {
+        let mut state = self.0.lock().unwrap();
+        let ids: Vec<usize> = state
+            .open
+            .get(&peer)
+            .map(|connections| connections.keys().copied().collect())
+            .unwrap_or_default();
+        for id in &ids {
+            if let Some(open) = state.take(peer, *id) {
+                open.connection
+                    .close(VarInt::from_u32(CLOSED_BY_APP), b"closed by application");
+            }
+        }
+        ids.len()
+    }
+
+    /// Number of connections currently being served.
+    pub fn count(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    /// Traffic with every peer since the node started, largest upload first.
+    ///
+    /// Includes connections that are still open.
+    pub fn traffic(&self) -> Vec<(EndpointId, PeerTraffic)> {
+        let state = self.0.lock().unwrap();
+        let mut totals = state.finished.clone();
+        for (peer, connections) in &state.open {
+            let total = totals.entry(*peer).or_default();
+            for open in connections.values() {
+                total.add(PeerTraffic::of(&open.connection));
+            }
+        }
+        let mut traffic: Vec<_> = totals.into_iter().collect();
+        traffic.sort_by(|a, b| b.1.uploaded.cmp(&a.1.uploaded).then(a.0.cmp(&b.0)));
+        traffic
+    }
+
+    fn insert(&self, peer: EndpointId, connection: Connection) {
+        let mut state = self.0.lock().unwrap();
+        if let Some(limit) = state.limit {
+            while state.len() >= limit.max(1) {
+                state.evict_idle();
+            }
+        }
+        state
+            .open
+            .entry(peer)
+            .or_default()
+            .insert(connection.stable_id(), Open::new(connection));
+    }
+
+    fn remove(&self, peer: EndpointId, id: usize) {
+        // No-op for connections that were closed here and already counted
+        self.0.lock().unwrap().take(peer, id);
+    }
+}
+
+/// Protocol handler whose connections are tracked while it serves them.
+#[derive(Debug)]
+pub struct Tracked<P> {
+    inner: P,
+    connections: PeerConnections,
+}
+
+impl<P: ProtocolHandler> ProtocolHandler for Tracked<P> {
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        let peer = connection.remote_id();
+        let id = connection.stable_id();
+        self.connections.insert(peer, connection.clone());
+        let result = self.inner.accept(connection).await;
+        self.connections.remove(peer, id);
+        result
+    }
+
+    async fn shutdown(&self) {
+        self.inner.shutdown().await
+    }
+}
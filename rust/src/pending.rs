@@ -0,0 +1,342 @@
+//! Work a node left pending, readable without starting the node.
+//!
+//! A silent push handler has a few seconds to decide whether starting a
+//! node is worth it. The node keeps a record of documents with local writes
+//! that no peer has synced yet and of downloads that have not finished, in
+//! a file next to the store, and [`read`] answers from that file alone: no
+//! store is opened and no endpoint is bound.
+//!
+//! A document's writes count as synced once a sync with a peer that started
+//! after its latest local write succeeds. Downloads are those started for
+//! the app and the content of remote document entries that has not arrived
+//! yet, until they complete. Changes are written to the file a moment after
+//! they happen, so bursts of writes share one update and a crash may lose
+//! the latest of them.
+
+#[cfg(feature = "docs")]
+use crate::clock::micros;
+use crate::clock::now_micros;
+use anyhow::{Context, Result};
+use iroh::EndpointId;
+use iroh_blobs::{BlobFormat, Hash, HashAndFormat};
+#[cfg(feature = "docs")]
+use iroh_docs::engine::LiveEvent;
+#[cfg(feature = "docs")]
+use iroh_docs::{ContentStatus, NamespaceId};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Name of the record in the store directory.
+const FILE_NAME: &str = "pending-work";
+
+/// Delay before writing changes, so bursts of them share one write.
+const PERSIST_DELAY: Duration = Duration::from_secs(1);
+
+/// Summary of pending work.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingWork {
+    /// Documents with local writes no peer has synced yet.
+    pub unsynced_docs: u64,
+    /// Downloads that have not finished.
+    pub incomplete_downloads: u64,
+}
+
+impl PendingWork {
+    /// Whether there is nothing left to do.
+    pub fn is_empty(&self) -> bool {
+        self.unsynced_docs == 0 && self.incomplete_downloads == 0
+    }
+}
+
+/// Read the pending work recorded in the store directory `storage_path`.
+///
+/// Only reads the record, so it works while no node is running; a store
+/// that never ran a node has no pending work.
+pub fn read(storage_path: &Path) -> Result<PendingWork> {
+    let mut work = PendingWork::default();
+    match std::fs::read_to_string(storage_path.join(FILE_NAME)) {
+        Ok(contents) => {
+            for record in contents.lines().filter_map(parse_record) {
+                match record {
+                    Record::Doc(_) => work.unsynced_docs += 1,
+                    Record::Download(..) => work.incomplete_downloads += 1,
+                }
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).context("Failed to read pending work"),
+    }
+    Ok(work)
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Namespaces of documents with unsynced local writes, with the time of
+    /// the latest one in microseconds since epoch.
+    docs: BTreeMap<[u8; 32], u64>,
+    /// Content of unfinished downloads and the peers to get it from.
+    downloads: BTreeMap<Hash, (BlobFormat, Vec<EndpointId>)>,
+    /// Whether a write of the record is already scheduled.
+    persist_scheduled: bool,
+}
+
+/// Pending work of a running node, kept in step with its record.
+#[derive(Debug, Clone)]
+pub struct PendingTracker {
+    path: PathBuf,
+    state: Arc<Mutex<State>>,
+}
+
+impl PendingTracker {
+    /// Load the pending work recorded in `storage_path`, or start with none.
+    ///
+    /// Unreadable records are skipped rather than keeping the node from
+    /// starting. Writes from before the node started count as made now.
+    pub fn load(storage_path: &Path) -> Result<Self> {
+        let path = storage_path.join(FILE_NAME);
+        let mut state = State::default();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let now = now_micros();
+                for record in contents.lines().filter_map(parse_record) {
+                    match record {
+                        Record::Doc(namespace) => {
+                            state.docs.insert(namespace, now);
+                        }
+                        Record::Download(content, providers) => {
+                            state
+                                .downloads
+                                .insert(content.hash, (content.format, providers));
+                        }
+                    }
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context("Failed to read pending work"),
+        }
+        Ok(Self {
+            path,
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Current pending work.
+    pub fn work(&self) -> PendingWork {
+        let state = self.state.lock().unwrap();
+        PendingWork {
+            unsynced_docs: state.docs.len() as u64,
+            incomplete_downloads: state.downloads.len() as u64,
+        }
+    }
+
+    /// Record that `content` is being downloaded from `providers`.
+    pub fn download_started(&self, content: HashAndFormat, providers: &[EndpointId]) {
+        let mut state = self.state.lock().unwrap();
+        let entry = (content.format, providers.to_vec());
+        if state.downloads.get(&content.hash) != Some(&entry) {
+            state.downloads.insert(content.hash, entry);
+            self.schedule_persist(state);
+        }
+    }
+
+    /// Record that the content of `hash` is complete.
+    pub fn download_finished(&self, hash: Hash) {
+        let mut state = self.state.lock().unwrap();
+        if state.downloads.remove(&hash).is_some() {
+            self.schedule_persist(state);
+        }
+    }
+
+    /// Unfinished downloads and the peers to get them from.
+    pub fn downloads(&self) -> Vec<(HashAndFormat, Vec<EndpointId>)> {
+        let state = self.state.lock().unwrap();
+        state
+            .downloads
+            .iter()
+            .map(|(hash, (format, providers))| {
+                (
+                    HashAndFormat {
+                        hash: *hash,
+                        format: *format,
+                    },
+                    providers.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Write the record now, replacing it atomically.
+    pub fn flush(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.persist_scheduled = false;
+        let mut contents = String::new();
+        for namespace in state.docs.keys() {
+            contents.push_str(&format!("doc {}\n", hex::encode(namespace)));
+        }
+        for (hash, (format, providers)) in &state.downloads {
+            let format = match format {
+                BlobFormat::Raw => "raw",
+                BlobFormat::HashSeq => "hashseq",
+            };
+            contents.push_str(&format!("download {} {format}", hash.to_hex()));
+            for provider in providers {
+                contents.push_str(&format!(" {provider}"));
+            }
+            contents.push('\n');
+        }
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, contents).context("Failed to write pending work")?;
+        std::fs::rename(&tmp, &self.path).context("Failed to write pending work")
+    }
+
+    /// Write the record shortly, unless that is already scheduled.
+    ///
+    /// Outside a runtime the record is written right away.
+    fn schedule_persist(&self, mut state: std::sync::MutexGuard<'_, State>) {
+        if state.persist_scheduled {
+            return;
+        }
+        state.persist_scheduled = true;
+        drop(state);
+        let tracker = self.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(async move {
+                    tokio::time::sleep(PERSIST_DELAY).await;
+                    // If this fails the next change writes the record again
+                    let _ = tracker.flush();
+                });
+            }
+            Err(_) => {
+                let _ = tracker.flush();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "docs")]
+impl PendingTracker {
+    /// Record a local write to the document `namespace` at `timestamp`.
+    pub fn local_write(&self, namespace: [u8; 32], timestamp: u64) {
+        let mut state = self.state.lock().unwrap();
+        let added = !state.docs.contains_key(&namespace);
+        let latest = state.docs.entry(namespace).or_default();
+        *latest = (*latest).max(timestamp);
+        if added {
+            self.schedule_persist(state);
+        }
+    }
+
+    /// Record that a sync of `namespace` that started at `started` succeeded,
+    /// which covers the local writes made before it.
+    pub fn synced(&self, namespace: [u8; 32], started: u64) {
+        let mut state = self.state.lock().unwrap();
+        if state
+            .docs
+            .get(&namespace)
+            .is_some_and(|latest| *latest < started)
+        {
+            state.docs.remove(&namespace);
+            self.schedule_persist(state);
+        }
+    }
+
+    /// Update the pending work of `namespace` from one of its live events.
+    ///
+    /// Tombstones this node writes arrive as remote inserts from `node_id`
+    /// and count as local writes.
+    pub fn observe(&self, namespace: NamespaceId, event: &LiveEvent, node_id: EndpointId) {
+        let namespace = *namespace.as_bytes();
+        match event {
+            LiveEvent::InsertLocal { entry } => self.local_write(namespace, entry.timestamp()),
+            LiveEvent::InsertRemote { from, entry, .. } if *from == node_id => {
+                self.local_write(namespace, entry.timestamp())
+            }
+            LiveEvent::InsertRemote {
+                from,
+                entry,
+                content_status,
+            } if !matches!(content_status, ContentStatus::Complete) && entry.content_len() > 0 => {
+                self.download_started(HashAndFormat::raw(entry.content_hash()), &[*from])
+            }
+            LiveEvent::ContentReady { hash } => self.download_finished(*hash),
+            LiveEvent::SyncFinished(sync) if sync.result.is_ok() => {
+                self.synced(namespace, micros(sync.started))
+            }
+            _ => {}
+        }
+    }
+}
+
+enum Record {
+    Doc([u8; 32]),
+    Download(HashAndFormat, Vec<EndpointId>),
+}
+
+fn parse_record(line: &str) -> Option<Record> {
+    let mut fields = line.split(' ');
+    match fields.next()? {
+        "doc" => {
+            let mut namespace = [0; 32];
+            hex::decode_to_slice(fields.next()?, &mut namespace).ok()?;
+            Some(Record::Doc(namespace))
+        }
+        "download" => {
+            let hash = Hash::from_str(fields.next()?).ok()?;
+            let format = match fields.next()? {
+                "raw" => BlobFormat::Raw,
+                "hashseq" => BlobFormat::HashSeq,
+                _ => return None,
+            };
+            let providers = fields
+                .map(EndpointId::from_str)
+                .collect::<Result<_, _>>()
+                .ok()?;
+            Some(Record::Download(HashAndFormat { hash, format }, providers))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "docs"))]
+mod tests {
+    use super::*;
+    use iroh::SecretKey;
+
+    #[test]
+    fn test_pending_work_survives_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let provider = SecretKey::from_bytes(&[3; 32]).public();
+        let (doc, other) = ([1; 32], [2; 32]);
+        let (blob, done) = (Hash::new(b"blob"), Hash::new(b"done"));
+
+        let tracker = PendingTracker::load(dir.path()).unwrap();
+        tracker.local_write(doc, 10);
+        tracker.local_write(other, 10);
+        // A sync that started before the latest write doesn't cover it
+        tracker.synced(doc, 5);
+        tracker.synced(other, 20);
+        tracker.download_started(HashAndFormat::raw(blob), &[provider]);
+        tracker.download_started(HashAndFormat::hash_seq(done), &[provider]);
+        tracker.download_finished(done);
+        tracker.flush().unwrap();
+
+        let expected = PendingWork {
+            unsynced_docs: 1,
+            incomplete_downloads: 1,
+        };
+        assert_eq!(tracker.work(), expected);
+        assert_eq!(read(dir.path()).unwrap(), expected);
+        let reloaded = PendingTracker::load(dir.path()).unwrap();
+        assert_eq!(reloaded.work(), expected);
+        assert_eq!(
+            reloaded.downloads(),
+            vec![(HashAndFormat::raw(blob), vec![provider])]
+        );
+
+        assert!(read(&dir.path().join("missing")).unwrap().is_empty());
+    }
+}
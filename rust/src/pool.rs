@@ -0,0 +1,94 @@
+//! Reusable buffers for small and medium results.
+//!
+//! Bulk reads such as `get_many` hand every entry key to the app in a buffer
+//! of its own, and reading each entry's value adds another. Buffers the app
+//! gives back are kept by size class and reused for later results, so a
+//! stream of entries stops allocating once the pool is warm. Larger results
+//! are allocated and freed as usual.
+
+use std::sync::Mutex;
+
+/// Smallest size class; shorter results still get a buffer this big.
+const MIN_POOLED_SIZE: usize = 64;
+
+/// Largest result copied into a pooled buffer.
+pub(crate) const MAX_POOLED_SIZE: usize = 64 * 1024;
+
+/// Size classes, powers of two from `MIN_POOLED_SIZE` to `MAX_POOLED_SIZE`.
+const CLASSES: usize =
+    (MAX_POOLED_SIZE.trailing_zeros() - MIN_POOLED_SIZE.trailing_zeros()) as usize + 1;
+
+/// Free buffers kept per size class; more are freed.
+const MAX_FREE_PER_CLASS: usize = 32;
+
+static FREE: Mutex<[Vec<Vec<u8>>; CLASSES]> = Mutex::new([const { Vec::new() }; CLASSES]);
+
+/// Size class of a buffer holding `len` bytes, or None if it is too large.
+fn class_for_len(len: usize) -> Option<usize> {
+    if len > MAX_POOLED_SIZE {
+        return None;
+    }
+    let size = len.max(MIN_POOLED_SIZE).next_power_of_two();
+    Some((size.trailing_zeros() - MIN_POOLED_SIZE.trailing_zeros()) as usize)
+}
+
+/// Size class a buffer with `capacity` can be kept in, if any.
+fn class_for_capacity(capacity: usize) -> Option<usize> {
+    let class = class_for_len(capacity)?;
+    (capacity == MIN_POOLED_SIZE << class).then_some(class)
+}
+
+/// Copy `bytes` into a buffer, reusing a pooled one if it fits.
+#[cfg_attr(not(feature = "docs"), allow(dead_code))]
+pub fn copy(bytes: &[u8]) -> Vec<u8> {
+    let Some(class) = class_for_len(bytes.len()) else {
+        return bytes.to_vec();
+    };
+    let reused = FREE.lock().unwrap()[class].pop();
+    let mut buf = reused.unwrap_or_else(|| Vec::with_capacity(MIN_POOLED_SIZE << class));
+    buf.extend_from_slice(bytes);
+    buf
+}
+
+/// Give a buffer back for reuse, or free it if the pool cannot keep it.
+pub fn recycle(mut buf: Vec<u8>) {
+    let Some(class) = class_for_capacity(buf.capacity()) else {
+        return;
+    };
+    let mut free = FREE.lock().unwrap();
+    if free[class].len() < MAX_FREE_PER_CLASS {
+        buf.clear();
+        free[class].push(buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recycled_buffer_is_reused() {
+        // A size no other test uses, so the class starts empty
+        let first = copy(&[7u8; 3000]);
+        assert_eq!(first.capacity(), 4096);
+        let ptr = first.as_ptr();
+        recycle(first);
+
+        let second = copy(b"reused");
+        let third = copy(&[1u8; 2500]);
+        assert_eq!(third.as_ptr(), ptr);
+        assert_eq!(third, vec![1u8; 2500]);
+        assert_eq!(second, b"reused");
+    }
+
+    #[test]
+    fn test_large_and_foreign_buffers_are_not_pooled() {
+        let large = copy(&vec![0u8; MAX_POOLED_SIZE + 1]);
+        assert_eq!(large.len(), MAX_POOLED_SIZE + 1);
+        assert_eq!(class_for_capacity(large.capacity()), None);
+        assert_eq!(class_for_capacity(100), None);
+        assert_eq!(class_for_capacity(128), Some(1));
+        assert_eq!(class_for_len(0), Some(0));
+        assert_eq!(class_for_len(MAX_POOLED_SIZE), Some(CLASSES - 1));
+    }
+}
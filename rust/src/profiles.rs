@@ -0,0 +1,355 @@
+//! Partitioning of the blob store between profiles.
+//!
+//! Apps with several user accounts on one device keep each account's
+//! content under its own profile. A profile's tags are named
+//! `profiles/<id>/<name>`, and content put for a profile is protected only
+//! by those tags, so it can be listed, measured and reclaimed without
+//! touching other profiles. Identical content of several profiles is
+//! stored once and only reclaimed when no tag references it anymore.
+//!
+//! Once untagged, content no longer shows which profile it came from, so
+//! each profile keeps a ledger file in `profiles/` of the content added to
+//! it. iroh-blobs only deletes content from its garbage collector, which
+//! runs in the background; a [`Reclaimer`] hooks into it so that it only
+//! runs when a profile asks to reclaim content, and then deletes exactly
+//! that content.
+
+use anyhow::{Context, Result, ensure};
+use iroh_blobs::api::Store;
+use iroh_blobs::store::{GcConfig, ProtectCb, ProtectOutcome};
+use iroh_blobs::{BlobFormat, Hash, HashAndFormat};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{Notify, watch};
+
+/// Prefix of the names of all profile tags.
+pub const PROFILE_TAG_PREFIX: &str = "profiles/";
+
+/// Directory in the storage directory holding the ledgers.
+const LEDGER_DIR: &str = "profiles";
+
+/// Shortest time between two garbage collection runs.
+const RECLAIM_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Check a profile ID, which also names the profile's ledger file.
+pub fn check_id(profile: &str) -> Result<()> {
+    ensure!(!profile.is_empty(), "Profile ID cannot be empty");
+    ensure!(
+        profile.len() <= 64,
+        "Profile ID cannot be longer than 64 bytes"
+    );
+    ensure!(
+        profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        "Profile ID can only contain ASCII letters, digits, '-' and '_'"
+    );
+    Ok(())
+}
+
+/// Prefix of the tags of `profile`.
+pub fn tag_prefix(profile: &str) -> String {
+    format!("{PROFILE_TAG_PREFIX}{profile}/")
+}
+
+/// Content of a profile and its size.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileStats {
+    /// Tags of the profile.
+    pub tags: u64,
+    /// Blobs reachable from the profile's tags.
+    pub blobs: u64,
+    /// Stored size of those blobs.
+    pub total_size: u64,
+    /// Stored size of the blobs no tag outside the profile reaches, which
+    /// wiping the profile would reclaim.
+    pub unique_size: u64,
+}
+
+/// Content deleted from the store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Reclaimed {
+    /// Blobs deleted.
+    pub blobs: u64,
+    /// Stored size of the deleted blobs.
+    pub size: u64,
+}
+
+/// Content added to one profile, kept in a file with a line per root.
+pub struct Ledger {
+    path: PathBuf,
+}
+
+impl Ledger {
+    /// Ledger of `profile` in `storage_path`.
+    pub fn new(storage_path: &Path, profile: &str) -> Self {
+        Self {
+            path: storage_path.join(LEDGER_DIR).join(profile),
+        }
+    }
+
+    /// Roots added to the profile and not yet reclaimed.
+    pub fn load(&self) -> Result<HashSet<HashAndFormat>> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashSet::new()),
+            Err(e) => return Err(e).context("Failed to read profile ledger"),
+        };
+        contents
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(parse_line)
+            .collect()
+    }
+
+    /// Record that `content` was added to the profile.
+    pub fn record(&self, content: HashAndFormat) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).context("Failed to create profile ledger directory")?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .context("Failed to open profile ledger")?;
+        // One write per line, so concurrent records never interleave
+        file.write_all(format_line(content).as_bytes())
+            .context("Failed to write profile ledger")
+    }
+
+    /// Keep only `roots`, removing the ledger once it is empty.
+    pub fn retain(&self, roots: &HashSet<HashAndFormat>) -> Result<()> {
+        if roots.is_empty() {
+            return match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(e).context("Failed to remove profile ledger")
+                }
+                _ => Ok(()),
+            };
+        }
+        let contents: String = roots.iter().map(|root| format_line(*root)).collect();
+        let temp = self.path.with_extension("tmp");
+        std::fs::write(&temp, contents).context("Failed to write profile ledger")?;
+        std::fs::rename(&temp, &self.path).context("Failed to replace profile ledger")
+    }
+}
+
+fn format_line(content: HashAndFormat) -> String {
+    let format = match content.format {
+        BlobFormat::Raw => "raw",
+        BlobFormat::HashSeq => "hashseq",
+    };
+    format!("{} {format}\n", content.hash)
+}
+
+fn parse_line(line: &str) -> Result<HashAndFormat> {
+    let (hash, format) = line
+        .split_once(' ')
+        .context("Invalid profile ledger line")?;
+    let format = match format {
+        "raw" => BlobFormat::Raw,
+        "hashseq" => BlobFormat::HashSeq,
+        other => anyhow::bail!("Invalid blob format in profile ledger: {other}"),
+    };
+    Ok(HashAndFormat {
+        hash: hash.parse().context("Invalid hash in profile ledger")?,
+        format,
+    })
+}
+
+/// Deletes requested content in the store's garbage collection runs.
+///
+/// The garbage collector waits in [`Reclaimer::gc_config`]'s callback
+/// until content is requested, so an idle node never runs it. A run then
+/// protects every stored blob except the requested ones, so it only
+/// deletes requested content that no tag, temporary tag or document
+/// references.
+pub struct Reclaimer {
+    store: OnceLock<Store>,
+    /// Protects content referenced by documents, if docs are enabled.
+    docs: OnceLock<ProtectCb>,
+    requests: Mutex<Requests>,
+    /// Signalled when content is requested.
+    wake: Notify,
+    /// The latest batch whose run has finished.
+    finished: watch::Sender<u64>,
+}
+
+/// Requested content, numbered by the run that handles it.
+#[derive(Default)]
+struct Requests {
+    /// Content to delete in the next run.
+    hashes: HashSet<Hash>,
+    /// Batch number of `hashes`.
+    batch: u64,
+    /// Batch taken by the run in progress.
+    running: Option<u64>,
+}
+
+impl Reclaimer {
+    /// A reclaimer that skips every run until attached to a store.
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            store: OnceLock::new(),
+            docs: OnceLock::new(),
+            requests: Mutex::new(Requests {
+                batch: 1,
+                ..Default::default()
+            }),
+            wake: Notify::new(),
+            finished: watch::Sender::new(0),
+        })
+    }
+
+    /// Garbage collection options for the store.
+    pub fn gc_config(self: &Arc<Self>) -> GcConfig {
+        let reclaimer = self.clone();
+        let add_protected: ProtectCb = Arc::new(move |live| {
+            let reclaimer = reclaimer.clone();
+            Box::pin(async move { reclaimer.protect(live).await })
+        });
+        GcConfig {
+            interval: RECLAIM_INTERVAL,
+            add_protected: Some(add_protected),
+        }
+    }
+
+    /// Start reclaiming content from `store`.
+    pub fn attach(&self, store: Store) {
+        let _ = self.store.set(store);
+    }
+
+    /// Keep the content `docs` protects, which documents reference.
+    #[cfg(feature = "docs")]
+    pub fn protect_docs(&self, docs: ProtectCb) {
+        let _ = self.docs.set(docs);
+    }
+
+    /// Content referenced by documents (none if docs are disabled).
+    pub async fn docs_protected(&self) -> anyhow::Result<HashSet<Hash>> {
+        let mut live = HashSet::new();
+        if let Some(docs) = self.docs.get()
+            && matches!(docs(&mut live).await, ProtectOutcome::Abort)
+        {
+            anyhow::bail!("Failed to list content referenced by documents");
+        }
+        Ok(live)
+    }
+
+    /// Delete `hashes` unless something still references them, returning
+    /// once the garbage collection run that took them has finished.
+    pub async fn reclaim(&self, hashes: HashSet<Hash>) {
+        if hashes.is_empty() {
+            return;
+        }
+        let mut finished = self.finished.subscribe();
+        let batch = {
+            let mut requests = self.requests.lock().unwrap();
+            requests.hashes.extend(hashes);
+            requests.batch
+        };
+        self.wake.notify_one();
+        let _ = finished.wait_for(|&run| run >= batch).await;
+    }
+
+    /// Wait for requested content, then take it for the next run.
+    async fn next_batch(&self) -> HashSet<Hash> {
+        loop {
+            {
+                let mut requests = self.requests.lock().unwrap();
+                // The garbage collector asks again once its last run finished
+                if let Some(batch) = requests.running.take() {
+                    self.finished.send_replace(batch);
+                }
+                if !requests.hashes.is_empty() {
+                    requests.running = Some(requests.batch);
+                    requests.batch += 1;
+                    return std::mem::take(&mut requests.hashes);
+                }
+            }
+            self.wake.notified().await;
+        }
+    }
+
+    async fn protect(&self, live: &mut HashSet<Hash>) -> ProtectOutcome {
+        let requested = self.next_batch().await;
+        let Some(store) = self.store.get() else {
+            return ProtectOutcome::Abort;
+        };
+        // Listed on a task of its own, as the callback's future must be Sync
+        let store = store.clone();
+        let hashes = tokio::spawn(async move { store.blobs().list().hashes().await }).await;
+        let Ok(Ok(hashes)) = hashes else {
+            return ProtectOutcome::Abort;
+        };
+        live.extend(hashes.into_iter().filter(|hash| !requested.contains(hash)));
+        match self.docs.get() {
+            Some(docs) => docs(live).await,
+            None => ProtectOutcome::Continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_id() {
+        assert!(check_id("alice_1").is_ok());
+        assert!(check_id("").is_err());
+        assert!(check_id("../alice").is_err());
+        assert!(check_id("a/b").is_err());
+        assert!(check_id(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_ledger_records_and_retains() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = Ledger::new(dir.path(), "alice");
+        assert!(ledger.load().unwrap().is_empty());
+
+        let raw = HashAndFormat::raw(Hash::new(b"raw"));
+        let seq = HashAndFormat::hash_seq(Hash::new(b"seq"));
+        ledger.record(raw).unwrap();
+        ledger.record(seq).unwrap();
+        ledger.record(raw).unwrap();
+        assert_eq!(ledger.load().unwrap(), HashSet::from([raw, seq]));
+
+        ledger.retain(&HashSet::from([seq])).unwrap();
+        assert_eq!(ledger.load().unwrap(), HashSet::from([seq]));
+
+        ledger.retain(&HashSet::new()).unwrap();
+        assert!(!dir.path().join(LEDGER_DIR).join("alice").exists());
+    }
+
+    #[test]
+    fn test_reclaim_waits_for_the_run_taking_its_hashes() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let reclaimer = Reclaimer::new();
+        let request = |data: &'static [u8]| {
+            let reclaimer = reclaimer.clone();
+            runtime.spawn(async move { reclaimer.reclaim(HashSet::from([Hash::new(data)])).await })
+        };
+
+        let first = request(b"first");
+        let taken = runtime.block_on(reclaimer.next_batch());
+        assert_eq!(taken, HashSet::from([Hash::new(b"first")]));
+
+        // Content requested during a run waits for the next one
+        let second = request(b"second");
+        let taken = runtime.block_on(reclaimer.next_batch());
+        assert_eq!(taken, HashSet::from([Hash::new(b"second")]));
+        runtime.block_on(first).unwrap();
+        assert!(!second.is_finished());
+
+        runtime.spawn({
+            let reclaimer = reclaimer.clone();
+            async move { reclaimer.next_batch().await }
+        });
+        runtime.block_on(second).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+//! Detection of corrupt store databases, and recovery from them.
+//!
+//! A crash or power loss in the middle of a write can leave the blob or
+//! docs database unreadable, and the store then fails to load, or never
+//! finishes loading, with nothing telling the app which file is at fault.
+//! Both databases are opened on their own before the node loads them, so
+//! corruption is reported as a [`StoreCorrupt`] naming the database. Apps
+//! can instead opt into a recovery mode per database; whatever recovery
+//! sets aside is moved to `quarantine/<time>` in the storage directory
+//! rather than deleted.
+
+use crate::clock::now_micros;
+use anyhow::{Context, Result};
+use iroh_blobs::api::Store;
+use redb::{DatabaseError, StorageError};
+use std::io::ErrorKind;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+
+/// Name of the directory holding what recovery set aside.
+const QUARANTINE_DIR: &str = "quarantine";
+
+/// One of a node's databases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreDatabase {
+    /// The blob store's index of blobs and tags.
+    Blobs,
+    /// The docs engine's replicas, authors and entries.
+    Docs,
+}
+
+impl std::fmt::Display for StoreDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StoreDatabase::Blobs => "blob store",
+            StoreDatabase::Docs => "docs",
+        })
+    }
+}
+
+/// Error of a node whose database is corrupt and not set to be recovered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreCorrupt {
+    /// Which database is corrupt.
+    pub database: StoreDatabase,
+    /// Path of the database file.
+    pub path: PathBuf,
+    /// What the database reported.
+    pub reason: String,
+}
+
+impl std::fmt::Display for StoreCorrupt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The {} database at {} is corrupt: {}",
+            self.database,
+            self.path.display(),
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for StoreCorrupt {}
+
+/// What a node does when its blob store database is corrupt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlobStoreRecovery {
+    /// Fail node creation with [`StoreCorrupt`].
+    #[default]
+    Fail,
+    /// Rebuild the database from the blob files. Each complete file whose
+    /// content matches its hash is added back under an automatic tag;
+    /// tag names and blobs kept inside the database are lost.
+    Reindex,
+    /// Set the database and blob files aside and start with an empty store.
+    Quarantine,
+}
+
+/// What a node does when its docs database is corrupt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocsStoreRecovery {
+    /// Fail node creation with [`StoreCorrupt`].
+    #[default]
+    Fail,
+    /// Set the database aside and start without documents or authors;
+    /// documents must be joined again to sync them back.
+    Discard,
+}
+
+/// Open the database at `path` on its own and report whether it is corrupt.
+///
+/// A missing database is not corrupt, and neither is one that fails to open
+/// for other reasons, such as being in use; loading the store reports those.
+pub fn check(database: StoreDatabase, path: &Path) -> Result<(), StoreCorrupt> {
+    if !path.exists() {
+        return Ok(());
+    }
+    // Damaged pages can trip assertions inside redb rather than return errors
+    let opened =
+        std::panic::catch_unwind(AssertUnwindSafe(|| redb::Database::create(path).map(drop)));
+    let reason = match opened {
+        Ok(Ok(())) => return Ok(()),
+        Ok(Err(DatabaseError::Storage(StorageError::Corrupted(reason)))) => reason,
+        Ok(Err(DatabaseError::Storage(StorageError::Io(e))))
+            if e.kind() == ErrorKind::InvalidData =>
+        {
+            e.to_string()
+        }
+        Ok(Err(_)) => return Ok(()),
+        Err(panic) => panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "the database could not be read".to_string()),
+    };
+    Err(StoreCorrupt {
+        database,
+        path: path.to_path_buf(),
+        reason,
+    })
+}
+
+/// Move `paths` in the storage directory to a new quarantine directory.
+///
+/// Paths that do not exist are skipped. Returns the quarantine directory.
+pub fn quarantine(storage_path: &Path, paths: &[&Path]) -> Result<PathBuf> {
+    let now = now_micros() / 1000;
+    let dir = storage_path.join(QUARANTINE_DIR).join(now.to_string());
+    std::fs::create_dir_all(&dir).context("Failed to create quarantine directory")?;
+    for path in paths.iter().filter(|path| path.exists()) {
+        let name = path
+            .file_name()
+            .context("Cannot quarantine a path without a name")?;
+        std::fs::rename(path, dir.join(name))
+            .with_context(|| format!("Failed to quarantine {}", path.display()))?;
+    }
+    Ok(dir)
+}
+
+/// Add the blob files in a quarantined `data` directory back to `store`.
+///
+/// Files are only added if complete and matching the hash in their name;
+/// added files are removed from quarantine and the rest left there.
+/// Returns how many blobs were added back.
+pub async fn reindex(store: &Store, data_dir: &Path) -> Result<u64> {
+    let mut restored = 0;
+    let Ok(files) = std::fs::read_dir(data_dir) else {
+        return Ok(0);
+    };
+    for file in files {
+        let path = file
+            .context("Failed to list quarantined blob files")?
+            .path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".data"))
+            .map(str::to_owned)
+        else {
+            continue;
+        };
+        // A bitfield marks a blob that was still being downloaded
+        if data_dir.join(format!("{name}.bitfield")).exists() {
+            continue;
+        }
+        let hashed = path.clone();
+        let hash = tokio::task::spawn_blocking(move || -> std::io::Result<_> {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update_reader(std::fs::File::open(hashed)?)?;
+            Ok(hasher.finalize())
+        })
+        .await??;
+        if hash.to_hex().as_str() != name {
+            continue;
+        }
+        let tag = store
+            .blobs()
+            .add_path(&path)
+            .temp_tag()
+            .await
+            .context("Failed to add quarantined blob")?;
+        store
+            .tags()
+            .create(tag.hash_and_format())
+            .await
+            .context("Failed to tag quarantined blob")?;
+        for extension in ["data", "obao4", "sizes4"] {
+            let _ = std::fs::remove_file(data_dir.join(format!("{name}.{extension}")));
+        }
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_reports_unreadable_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blobs.db");
+        assert!(check(StoreDatabase::Blobs, &path).is_ok());
+
+        redb::Database::create(&path).unwrap();
+        assert!(check(StoreDatabase::Blobs, &path).is_ok());
+
+        std::fs::write(&path, vec![0x5a; 8192]).unwrap();
+        let corrupt = check(StoreDatabase::Blobs, &path).unwrap_err();
+        assert_eq!(corrupt.database, StoreDatabase::Blobs);
+        assert_eq!(corrupt.path, path);
+    }
+
+    #[test]
+    fn test_quarantine_moves_existing_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("blobs.db");
+        std::fs::write(&db, b"db").unwrap();
+        let missing = dir.path().join("data");
+
+        let quarantined = quarantine(dir.path(), &[&db, &missing]).unwrap();
+        assert!(quarantined.starts_with(dir.path().join(QUARANTINE_DIR)));
+        assert!(!db.exists());
+        assert_eq!(std::fs::read(quarantined.join("blobs.db")).unwrap(), b"db");
+    }
+}
@@ -0,0 +1,214 @@
+//! App-provided conflict resolution for document keys.
+//!
+//! Documents keep one latest entry per author and key, and reads return the
+//! newest of them. When a remote insert leaves several authors with
+//! different values for a key, a registered resolver is shown every
+//! candidate and may pick or merge a value, which is then written by the
+//! resolving author so it becomes the newest entry.
+//!
+//! Resolvers are app code that may block, so they run on the runtime's
+//! blocking threads without any lock held. Their value is written only if
+//! the entries they were shown are still the latest ones for the key.
+
+use anyhow::{Context, Result};
+use futures_lite::StreamExt;
+use iroh_blobs::Hash;
+use iroh_blobs::api::Store;
+use iroh_docs::api::Doc;
+use iroh_docs::engine::LiveEvent;
+use iroh_docs::store::Query;
+use iroh_docs::{AuthorId, ContentStatus};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, oneshot};
+
+/// One author's latest entry for a conflicting key.
+pub struct ConflictCandidate {
+    /// Author of the entry.
+    pub author: AuthorId,
+    /// Content hash of the entry.
+    pub content_hash: Hash,
+    /// Entry content.
+    pub content: Vec<u8>,
+    /// Entry timestamp in microseconds since epoch.
+    pub timestamp: u64,
+}
+
+/// Resolver invoked with a key and its diverging candidates.
+///
+/// Returns the value to write, or None to leave the entries as they are.
+pub type Resolve = dyn Fn(&[u8], &[ConflictCandidate]) -> Option<Vec<u8>> + Send + Sync;
+
+/// Outcome of checking one key.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// Fewer than two distinct values; nothing to resolve.
+    NoConflict,
+    /// Some candidate content is not available locally yet.
+    Pending,
+    /// The resolver declined, chose the current value, or the entries
+    /// changed while it ran.
+    Unchanged,
+    /// The resolved value was written; holds its content hash.
+    Written(Hash),
+}
+
+/// Resolve `key` if the latest entries of its authors hold different values.
+///
+/// The resolver runs on a blocking thread. Its value is written while
+/// `lock` is held, and only if the key's latest entries are still the
+/// candidates it was shown; a remote insert arriving meanwhile gets its own
+/// turn instead.
+pub async fn resolve_key(
+    doc: &Doc,
+    store: &Store,
+    lock: &Mutex<()>,
+    author: AuthorId,
+    key: &[u8],
+    resolve: &Arc<Resolve>,
+) -> Result<Resolution> {
+    let Some(candidates) = load_candidates(doc, store, key).await? else {
+        return Ok(Resolution::Pending);
+    };
+    let first = candidates.first().map(|c| c.content_hash);
+    if candidates.iter().all(|c| Some(c.content_hash) == first) {
+        return Ok(Resolution::NoConflict);
+    }
+
+    let (resolve, resolved_key) = (resolve.clone(), key.to_vec());
+    let (candidates, value) = tokio::task::spawn_blocking(move || {
+        let value = resolve(&resolved_key, &candidates);
+        (candidates, value)
+    })
+    .await
+    .context("Conflict resolver panicked")?;
+    let Some(value) = value else {
+        return Ok(Resolution::Unchanged);
+    };
+    // Writing the value reads already return would only trigger peers again
+    let newest = candidates.iter().max_by_key(|c| c.timestamp);
+    if newest.is_some_and(|c| c.content_hash == Hash::new(&value)) {
+        return Ok(Resolution::Unchanged);
+    }
+
+    let _guard = lock.lock().await;
+    let resolved: Vec<_> = candidates.iter().map(EntryId::of).collect();
+    if latest_entries(doc, key).await? != resolved {
+        return Ok(Resolution::Unchanged);
+    }
+    let hash = doc
+        .set_bytes(author, key.to_vec(), value)
+        .await
+        .context("Failed to write resolved value")?;
+    Ok(Resolution::Written(hash))
+}
+
+/// What identifies an author's latest entry for a key.
+#[derive(Debug, PartialEq, Eq)]
+struct EntryId {
+    author: AuthorId,
+    timestamp: u64,
+    content_hash: Hash,
+}
+
+impl EntryId {
+    fn of(candidate: &ConflictCandidate) -> Self {
+        Self {
+            author: candidate.author,
+            timestamp: candidate.timestamp,
+            content_hash: candidate.content_hash,
+        }
+    }
+}
+
+/// Every author's latest entry for `key`, in the order candidates are loaded.
+async fn latest_entries(doc: &Doc, key: &[u8]) -> Result<Vec<EntryId>> {
+    let entries = doc
+        .get_many(Query::key_exact(key))
+        .await
+        .context("Failed to read entries")?
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+    Ok(entries
+        .iter()
+        .map(|entry| EntryId {
+            author: entry.author(),
+            timestamp: entry.timestamp(),
+            content_hash: entry.content_hash(),
+        })
+        .collect())
+}
+
+/// Load every author's latest entry for `key`, or None if some content is missing.
+async fn load_candidates(
+    doc: &Doc,
+    store: &Store,
+    key: &[u8],
+) -> Result<Option<Vec<ConflictCandidate>>> {
+    let entries = doc
+        .get_many(Query::key_exact(key))
+        .await
+        .context("Failed to read entries")?
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+
+    let mut candidates = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let Ok(content) = store.get_bytes(entry.content_hash()).await else {
+            return Ok(None);
+        };
+        candidates.push(ConflictCandidate {
+            author: entry.author(),
+            content_hash: entry.content_hash(),
+            content: content.to_vec(),
+            timestamp: entry.timestamp(),
+        });
+    }
+    Ok(Some(candidates))
+}
+
+/// Resolve conflicts caused by remote inserts until `cancel` fires.
+///
+/// Keys whose new content is still downloading are checked once it arrives.
+/// Local writes never trigger the resolver, so its own writes do not loop.
+pub async fn run(
+    doc: Doc,
+    store: Store,
+    lock: std::sync::Arc<Mutex<()>>,
+    author: AuthorId,
+    resolve: Box<Resolve>,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<()> {
+    let resolve: Arc<Resolve> = resolve.into();
+    let mut events = doc.subscribe().await?;
+    let mut pending: HashMap<Hash, Vec<Vec<u8>>> = HashMap::new();
+    loop {
+        let event = tokio::select! {
+            _ = &mut cancel => return Ok(()),
+            event = events.next() => event,
+        };
+        let keys = match event.transpose()? {
+            None => return Ok(()),
+            Some(LiveEvent::InsertRemote {
+                entry,
+                content_status,
+                ..
+            }) => {
+                if !matches!(content_status, ContentStatus::Complete) {
+                    pending
+                        .entry(entry.content_hash())
+                        .or_default()
+                        .push(entry.key().to_vec());
+                    continue;
+                }
+                vec![entry.key().to_vec()]
+            }
+            Some(LiveEvent::ContentReady { hash }) => pending.remove(&hash).unwrap_or_default(),
+            Some(_) => continue,
+        };
+        for key in keys {
+            // A failed key is retried on its next remote insert
+            let _ = resolve_key(&doc, &store, &lock, author, &key, &resolve).await;
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Time-limited shares.
+//!
+//! A tag can be given an expiry time, after which it is removed and its
+//! content is no longer kept by it, so content can be shared "for 24
+//! hours". Expiry times are recorded in a file next to the store, so they
+//! survive restarts; tags that expired while the node was stopped are
+//! removed right after it starts. Each removal is published as
+//! [`NodeEvent::TagExpired`].
+
+use crate::clock::now_micros;
+use crate::events::{EventBus, NodeEvent};
+use anyhow::{Context, Result};
+use iroh_blobs::api::Store;
+use iroh_blobs::{BlobFormat, HashAndFormat};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Longest sleep between checks, so clock changes are noticed.
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// An expiring tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Share {
+    /// Content the tag was set to; the tag is only removed if it still is.
+    content: HashAndFormat,
+    /// Microseconds since epoch.
+    expires_at: u64,
+}
+
+/// Expiry times of tags and the task removing expired tags.
+#[derive(Debug, Clone)]
+pub struct ExpiringTags {
+    path: PathBuf,
+    shares: Arc<Mutex<BTreeMap<String, Share>>>,
+    /// Wakes the expiry task when the earliest expiry may have changed.
+    changed: Arc<Notify>,
+}
+
+impl ExpiringTags {
+    /// Load the expiry times stored at `path`, or start with none.
+    ///
+    /// Unreadable records are skipped rather than keeping the node from
+    /// starting.
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let shares = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents.lines().filter_map(parse_record).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e).context("Failed to read tag expiry times"),
+        };
+        Ok(Self {
+            path,
+            shares: Arc::new(Mutex::new(shares)),
+            changed: Arc::new(Notify::new()),
+        })
+    }
+
+    /// Tag `content` as `name` until `expires_at` (microseconds since epoch),
+    /// replacing any tag of that name.
+    pub async fn set(
+        &self,
+        store: &Store,
+        name: &str,
+        content: HashAndFormat,
+        expires_at: u64,
+    ) -> Result<()> {
+        // Recorded first, so a tag is never left without its expiry
+        self.update(
+            name,
+            Some(Share {
+                content,
+                expires_at,
+            }),
+        )?;
+        store
+            .tags()
+            .set(name, content)
+            .await
+            .context("Failed to tag blob")?;
+        self.changed.notify_one();
+        Ok(())
+    }
+
+    /// Drop the expiry time of `name`, if it has one, so the tag stays.
+    pub fn forget(&self, name: &str) -> Result<()> {
+        if self.shares.lock().unwrap().contains_key(name) {
+            self.update(name, None)?;
+        }
+        Ok(())
+    }
+
+    /// Remove tags as they expire until the runtime stops.
+    pub async fn run(self, store: Store, events: EventBus) {
+        loop {
+            let now = now_micros();
+            let next = self
+                .shares
+                .lock()
+                .unwrap()
+                .values()
+                .map(|s| s.expires_at)
+                .min();
+            match next {
+                Some(expires_at) if expires_at <= now => {
+                    // Failures are retried on the next check
+                    if self.expire(&store, &events, now).await.is_err() {
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+                Some(expires_at) => {
+                    let wait = Duration::from_micros(expires_at - now).min(MAX_SLEEP);
+                    tokio::select! {
+                        _ = tokio::time::sleep(wait) => {}
+                        _ = self.changed.notified() => {}
+                    }
+                }
+                None => self.changed.notified().await,
+            }
+        }
+    }
+
+    /// Remove the tags that expired by `now`.
+    async fn expire(&self, store: &Store, events: &EventBus, now: u64) -> Result<()> {
+        let expired: Vec<(String, Share)> = self
+            .shares
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, share)| share.expires_at <= now)
+            .map(|(name, share)| (name.clone(), *share))
+            .collect();
+        for (name, share) in expired {
+            // A tag set to other content since has outlived the share
+            let current = store.tags().get(&name).await?;
+            let removed = current.is_some_and(|tag| tag.hash_and_format() == share.content);
+            if removed {
+                store.tags().delete(&name).await?;
+            }
+            {
+                let mut shares = self.shares.lock().unwrap();
+                // Only drop the record if it was not replaced meanwhile
+                if shares.get(&name) == Some(&share) {
+                    shares.remove(&name);
+                    self.persist(&shares)?;
+                }
+            }
+            if removed {
+                events.emit(NodeEvent::TagExpired {
+                    name,
+                    hash: share.content.hash,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Set or clear the record of `name` and write the file.
+    fn update(&self, name: &str, share: Option<Share>) -> Result<()> {
+        let mut shares = self.shares.lock().unwrap();
+        let previous = match share {
+            Some(share) => shares.insert(name.to_string(), share),
+            None => shares.remove(name),
+        };
+        if let Err(e) = self.persist(&shares) {
+            match previous {
+                Some(previous) => shares.insert(name.to_string(), previous),
+                None => shares.remove(name),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Write the expiry times file, replacing it atomically.
+    fn persist(&self, shares: &BTreeMap<String, Share>) -> Result<()> {
+        let contents: String = shares
+            .iter()
+            .map(|(name, share)| format_record(name, share))
+            .collect();
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, contents).context("Failed to write tag expiry times")?;
+        std::fs::rename(&tmp, &self.path).context("Failed to write tag expiry times")
+    }
+}
+
+/// A record line, with the tag name hex encoded as it may contain spaces.
+fn format_record(name: &str, share: &Share) -> String {
+    let format = match share.content.format {
+        BlobFormat::Raw => "raw",
+        BlobFormat::HashSeq => "hashseq",
+    };
+    format!(
+        "{} {} {format} {}\n",
+        share.expires_at,
+        share.content.hash,
+        hex::encode(name)
+    )
+}
+
+fn parse_record(line: &str) -> Option<(String, Share)> {
+    let mut fields = line.split(' ');
+    let expires_at = fields.next()?.parse().ok()?;
+    let hash = fields.next()?.parse().ok()?;
+    let format = match fields.next()? {
+        "raw" => BlobFormat::Raw,
+        "hashseq" => BlobFormat::HashSeq,
+        _ => return None,
+    };
+    let name = String::from_utf8(hex::decode(fields.next()?).ok()?).ok()?;
+    Some((
+        name,
+        Share {
+            content: HashAndFormat { hash, format },
+            expires_at,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh_blobs::Hash;
+
+    #[test]
+    fn test_record_roundtrip() {
+        let share = Share {
+            content: HashAndFormat::hash_seq(Hash::new(b"shared")),
+            expires_at: 1_700_000_000_000_000,
+        };
+        let line = format_record("shares/holiday photos", &share);
+        assert_eq!(
+            parse_record(line.trim_end()),
+            Some(("shares/holiday photos".to_string(), share))
+        );
+        assert_eq!(parse_record("soon"), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Document snapshots stored as a single blob.
+//!
+//! A device joining a large document normally learns every entry through
+//! range sync and then downloads each entry's content on its own. A
+//! snapshot bundles the document's signed entries with their stored content
+//! into one blob, so a new device fetches that blob, inserts the entries as
+//! if they had been synced, and live sync only catches up on later writes.
+//! Entries keep their authors' signatures and are checked like synced
+//! entries, so a snapshot cannot add writes its provider did not receive.
+//!
+//! A snapshot holds every entry, including deletions, and the content of
+//! every entry stored on the node taking it; it is built in memory, so it
+//! suits documents whose content fits there.
+
+use anyhow::{Context, Result, ensure};
+use futures_lite::StreamExt;
+use iroh::EndpointId;
+use iroh_blobs::api::Store;
+use iroh_docs::actor::SyncHandle;
+use iroh_docs::api::Doc;
+use iroh_docs::store::Query;
+use iroh_docs::{ContentStatus, NamespaceId, SignedEntry};
+
+/// Version of the snapshot encoding, stored as its first byte.
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// An entry with its content, if the snapshotting node had it.
+type SnapshotEntry = (SignedEntry, Option<Vec<u8>>);
+
+/// Encode every entry of `doc` and the content stored for it.
+pub async fn create(doc: &Doc, replicas: &SyncHandle, store: &Store) -> Result<Vec<u8>> {
+    let namespace = doc.id();
+    let entries = doc
+        .get_many(Query::all().include_empty())
+        .await
+        .context("Failed to read document")?
+        .try_collect::<_, _, Vec<_>>()
+        .await?;
+
+    let mut snapshot: Vec<SnapshotEntry> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        // The docs API drops signatures, so read each entry again from the replica
+        let Some(signed) = replicas
+            .get_exact(namespace, entry.author(), entry.key().to_vec().into(), true)
+            .await
+            .context("Failed to read document entry")?
+        else {
+            // Removed since the listing
+            continue;
+        };
+        let content = if signed.content_len() == 0 {
+            None
+        } else {
+            store
+                .get_bytes(signed.content_hash())
+                .await
+                .ok()
+                .map(|bytes| bytes.to_vec())
+        };
+        snapshot.push((signed, content));
+    }
+
+    let mut bytes = vec![SNAPSHOT_VERSION];
+    bytes.extend(
+        postcard::to_allocvec(&(namespace, snapshot)).context("Failed to encode snapshot")?,
+    );
+    Ok(bytes)
+}
+
+/// Insert the entries of a snapshot into the open, syncing replica `namespace`.
+///
+/// Content is stored before its entry, so entries never point at content
+/// this node lacks while the snapshot has it; missing content is
+/// downloaded from `provider` like content of a synced entry. Returns the
+/// number of entries in the snapshot.
+pub async fn restore(
+    bytes: &[u8],
+    namespace: NamespaceId,
+    replicas: &SyncHandle,
+    store: &Store,
+    provider: EndpointId,
+) -> Result<u64> {
+    let (&version, encoded) = bytes.split_first().context("Snapshot is empty")?;
+    ensure!(
+        version == SNAPSHOT_VERSION,
+        "Unsupported snapshot version {version}"
+    );
+    let (snapshot_namespace, entries): (NamespaceId, Vec<SnapshotEntry>) =
+        postcard::from_bytes(encoded).context("Malformed snapshot")?;
+    ensure!(
+        snapshot_namespace == namespace,
+        "Snapshot is of document {snapshot_namespace}, not {namespace}"
+    );
+
+    let count = entries.len() as u64;
+    for (entry, content) in entries {
+        let status = match content {
+            Some(content) => {
+                ensure!(
+                    content.len() as u64 == entry.content_len(),
+                    "Snapshot content does not match its entry"
+                );
+                let added = store
+                    .add_slice(&content)
+                    .temp_tag()
+                    .await
+                    .context("Failed to add snapshot content to store")?;
+                ensure!(
+                    added.hash() == entry.content_hash(),
+                    "Snapshot content does not match its entry"
+                );
+                ContentStatus::Complete
+            }
+            None if entry.content_len() == 0 => ContentStatus::Complete,
+            None => ContentStatus::Missing,
+        };
+        replicas
+            .insert_remote(namespace, entry, *provider.as_bytes(), status)
+            .await
+            .context("Failed to insert snapshot entry")?;
+    }
+    Ok(count)
+}
@@ -0,0 +1,118 @@
+//! Export of documents to SQLite files.
+//!
+//! Apps that need complex offline queries over a document can export it
+//! once and query the file with any SQLite library, instead of streaming
+//! every entry across the FFI boundary. The file holds the latest entry per
+//! author and key:
+//!
+//! ```sql
+//! CREATE TABLE document (namespace TEXT NOT NULL, exported_at INTEGER NOT NULL);
+//! CREATE TABLE entries (
+//!     key BLOB NOT NULL,
+//!     author TEXT NOT NULL,
+//!     content_hash TEXT NOT NULL,
+//!     content_len INTEGER NOT NULL,
+//!     timestamp INTEGER NOT NULL,
+//!     value BLOB,
+//!     PRIMARY KEY (key, author)
+//! ) WITHOUT ROWID;
+//! CREATE INDEX entries_timestamp ON entries (timestamp);
+//! ```
+//!
+//! Timestamps are microseconds since epoch. `value` holds the content of
+//! entries no larger than the requested limit whose content is stored
+//! locally, and is NULL otherwise.
+
+use crate::clock::now_micros;
+use anyhow::{Context, Result};
+use futures_lite::StreamExt;
+use iroh_blobs::api::Store;
+use iroh_docs::api::Doc;
+use iroh_docs::store::Query;
+use rusqlite::{Connection, params};
+use std::path::Path;
+
+const SCHEMA: &str = "
+    CREATE TABLE document (namespace TEXT NOT NULL, exported_at INTEGER NOT NULL);
+    CREATE TABLE entries (
+        key BLOB NOT NULL,
+        author TEXT NOT NULL,
+        content_hash TEXT NOT NULL,
+        content_len INTEGER NOT NULL,
+        timestamp INTEGER NOT NULL,
+        value BLOB,
+        PRIMARY KEY (key, author)
+    ) WITHOUT ROWID;
+    CREATE INDEX entries_timestamp ON entries (timestamp);
+";
+
+/// Write the latest entries of `doc` to a new SQLite file at `path`.
+///
+/// Values up to `max_value_size` bytes are included (0 for none). The file
+/// is written next to `path` and moved into place when complete, replacing
+/// any existing file. Returns the number of entries exported.
+pub async fn export(doc: &Doc, store: &Store, path: &Path, max_value_size: u64) -> Result<u64> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = Path::new(&partial);
+    if partial.exists() {
+        std::fs::remove_file(partial).context("Failed to remove stale partial export")?;
+    }
+
+    let result = write(doc, store, partial, max_value_size).await;
+    match result {
+        Ok(exported) => {
+            std::fs::rename(partial, path).context("Failed to move export into place")?;
+            Ok(exported)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(partial);
+            Err(e)
+        }
+    }
+}
+
+/// Write the export to `path`, which must not exist yet.
+async fn write(doc: &Doc, store: &Store, path: &Path, max_value_size: u64) -> Result<u64> {
+    let mut conn = Connection::open(path).context("Failed to create SQLite file")?;
+    conn.execute_batch(SCHEMA)
+        .context("Failed to create SQLite schema")?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO document (namespace, exported_at) VALUES (?1, ?2)",
+        params![doc.id().to_string(), now_micros() as i64],
+    )?;
+
+    let mut exported = 0;
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO entries (key, author, content_hash, content_len, timestamp, value)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        let entries = doc
+            .get_many(Query::all())
+            .await
+            .context("Failed to query entries")?;
+        let mut entries = std::pin::pin!(entries);
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            let value = if entry.content_len() > 0 && entry.content_len() <= max_value_size {
+                // Content synced from peers may not have arrived yet
+                store.get_bytes(entry.content_hash()).await.ok()
+            } else {
+                None
+            };
+            insert.execute(params![
+                entry.key(),
+                entry.author().to_string(),
+                entry.content_hash().to_string(),
+                entry.content_len() as i64,
+                entry.timestamp() as i64,
+                value.as_deref(),
+            ])?;
+            exported += 1;
+        }
+    }
+    tx.commit().context("Failed to write SQLite file")?;
+    Ok(exported)
+}
@@ -0,0 +1,121 @@
+//! Process-wide operation counters.
+//!
+//! Cheap totals an app can read at any time, for example to attach to
+//! analytics events. Operations are counted when they end; failures are
+//! broken down by the FFI function that failed.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Operations counted as puts.
+const PUT_OPERATIONS: &[&str] = &[
+    "iroh_put",
+    "iroh_put_with_options",
+    "iroh_put_with_metadata",
+    "iroh_put_file",
+    "iroh_profile_put",
+];
+/// Operations counted as gets.
+const GET_OPERATIONS: &[&str] = &[
+    "iroh_get",
+    "iroh_get_with_progress",
+    "iroh_get_to_sink",
+    "iroh_get_from_providers",
+    "iroh_get_with_options",
+    "iroh_get_with_metadata",
+];
+/// Operations counted as document writes.
+const DOC_SET_OPERATIONS: &[&str] = &[
+    "iroh_doc_set",
+    "iroh_doc_set_cas",
+    "iroh_doc_set_with_ttl",
+    "iroh_doc_set_with_durability",
+    "iroh_doc_set_with_default_author",
+    "iroh_doc_set_file",
+];
+
+static PUTS: AtomicU64 = AtomicU64::new(0);
+static GETS: AtomicU64 = AtomicU64::new(0);
+static DOC_SETS: AtomicU64 = AtomicU64::new(0);
+static BYTES_IN: AtomicU64 = AtomicU64::new(0);
+static BYTES_OUT: AtomicU64 = AtomicU64::new(0);
+static FAILURES: Mutex<BTreeMap<&'static str, u64>> = Mutex::new(BTreeMap::new());
+
+/// Totals since the process started.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Counters {
+    /// Successful puts.
+    pub puts: u64,
+    /// Successful gets.
+    pub gets: u64,
+    /// Successful document writes.
+    pub doc_sets: u64,
+    /// Bytes passed in by puts.
+    pub bytes_in: u64,
+    /// Bytes returned by gets.
+    pub bytes_out: u64,
+    /// Failed operations by FFI function name, sorted by name.
+    pub failures: Vec<(&'static str, u64)>,
+}
+
+/// Count operation `name` as it ends.
+pub fn operation_ended(name: &'static str, failed: bool) {
+    if failed {
+        *FAILURES.lock().unwrap().entry(name).or_default() += 1;
+    } else if PUT_OPERATIONS.contains(&name) {
+        PUTS.fetch_add(1, Ordering::Relaxed);
+    } else if GET_OPERATIONS.contains(&name) {
+        GETS.fetch_add(1, Ordering::Relaxed);
+    } else if DOC_SET_OPERATIONS.contains(&name) {
+        DOC_SETS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Count `len` bytes stored by a put.
+pub fn bytes_in(len: usize) {
+    BYTES_IN.fetch_add(len as u64, Ordering::Relaxed);
+}
+
+/// Count `len` bytes returned by a get.
+pub fn bytes_out(len: usize) {
+    BYTES_OUT.fetch_add(len as u64, Ordering::Relaxed);
+}
+
+/// Current totals.
+pub fn snapshot() -> Counters {
+    Counters {
+        puts: PUTS.load(Ordering::Relaxed),
+        gets: GETS.load(Ordering::Relaxed),
+        doc_sets: DOC_SETS.load(Ordering::Relaxed),
+        bytes_in: BYTES_IN.load(Ordering::Relaxed),
+        bytes_out: BYTES_OUT.load(Ordering::Relaxed),
+        failures: FAILURES
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&name, &count)| (name, count))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace;
+
+    #[test]
+    fn test_operations_are_counted_when_they_end() {
+        let before = snapshot();
+        drop(trace::begin("iroh_put"));
+        bytes_in(5);
+        let failing = trace::begin("iroh_test_failing");
+        let _ = trace::failure("boom");
+        drop(failing);
+
+        let after = snapshot();
+        assert!(after.puts > before.puts);
+        assert!(after.bytes_in >= before.bytes_in + 5);
+        assert!(after.failures.contains(&("iroh_test_failing", 1)));
+    }
+}
@@ -0,0 +1,120 @@
+//! Alerts when the store's size on disk crosses a threshold, and how the
+//! store lays blobs out on disk.
+//!
+//! The storage directory is measured periodically so apps can prompt users
+//! to clean up before the device fills. The app is told when the size
+//! reaches the threshold and again when it drops back below.
+
+use iroh_blobs::store::fs::options::InlineOptions;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How often the store is measured if no interval is given.
+pub const DEFAULT_STORAGE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Called with whether the threshold is exceeded and the measured size in bytes.
+pub type OnThreshold = dyn Fn(bool, u64) + Send + Sync;
+
+/// Threshold on the store's size and the callback told when it is crossed.
+pub struct StorageAlert {
+    /// Size in bytes at which the store counts as full.
+    pub threshold: u64,
+    /// Time between measurements.
+    pub interval: Duration,
+    /// Told when the store reaches the threshold and when it recovers.
+    pub on_threshold: Box<OnThreshold>,
+}
+
+impl std::fmt::Debug for StorageAlert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageAlert")
+            .field("threshold", &self.threshold)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+
+impl StorageAlert {
+    /// Measure `path` every interval until the runtime stops.
+    ///
+    /// The first alert is sent once the store is measured at or above the
+    /// threshold; a store that starts below it is not reported.
+    pub async fn run(self, path: PathBuf) {
+        let mut exceeded = false;
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let path = path.clone();
+            let Ok(size) = tokio::task::spawn_blocking(move || dir_size(&path)).await else {
+                continue;
+            };
+            if (size >= self.threshold) != exceeded {
+                exceeded = !exceeded;
+                (self.on_threshold)(exceeded, size);
+            }
+        }
+    }
+}
+
+/// Size limits for keeping blobs inside the store's database.
+///
+/// Complete blobs and outboards up to these sizes are stored in the
+/// database instead of files of their own. Raising the limits suits many
+/// tiny values, such as document entries, by creating fewer files; lowering
+/// them suits large media, which is then read from files as it streams.
+/// Only content stored afterwards is affected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StoreInlining {
+    /// Largest blob kept in the database (if None, 16 KiB).
+    pub max_data: Option<u64>,
+    /// Largest outboard kept in the database (if None, 16 KiB). A blob's
+    /// outboard is 64 bytes per 16 KiB of data.
+    pub max_outboard: Option<u64>,
+}
+
+impl StoreInlining {
+    /// The store options these limits translate to.
+    pub fn options(&self) -> InlineOptions {
+        let default = InlineOptions::default();
+        InlineOptions {
+            max_data_inlined: self.max_data.unwrap_or(default.max_data_inlined),
+            max_outboard_inlined: self.max_outboard.unwrap_or(default.max_outboard_inlined),
+        }
+    }
+}
+
+/// Total size of the files under `path`, skipping entries that cannot be read.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?)))
+        .map(|(path, metadata)| {
+            if metadata.is_dir() {
+                dir_size(&path)
+            } else {
+                metadata.len()
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_dir_size_counts_nested_files() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), [0u8; 100]).unwrap();
+        std::fs::create_dir(dir.path().join("nested")).unwrap();
+        std::fs::write(dir.path().join("nested").join("b"), [0u8; 50]).unwrap();
+
+        assert_eq!(dir_size(dir.path()), 150);
+        assert_eq!(dir_size(&dir.path().join("missing")), 0);
+    }
+}
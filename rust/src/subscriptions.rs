@@ -0,0 +1,101 @@
+//! Subscriptions running on a node.
+//!
+//! Subscription handles are cancelled by the app, but a node being closed
+//! must also stop every subscription it still runs, so that each delivers
+//! its final callback before the node is freed. The node keeps a cancel
+//! switch for each subscription alongside the handle, and tracks their
+//! tasks so closing can wait for them to finish.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::runtime::Runtime;
+use tokio::sync::oneshot;
+use tokio_util::task::TaskTracker;
+
+/// Stops one subscription; shared by its handle and its node.
+#[derive(Debug)]
+pub struct CancelSwitch(Mutex<Option<oneshot::Sender<()>>>);
+
+impl CancelSwitch {
+    /// Stop the subscription, if it has not been stopped already.
+    pub fn cancel(&self) {
+        if let Some(tx) = self.0.lock().unwrap().take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// Subscriptions started on a node.
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    switches: Mutex<Vec<Weak<CancelSwitch>>>,
+    tasks: TaskTracker,
+}
+
+impl Subscriptions {
+    /// Start a subscription, returning its switch and the receiver that
+    /// fires when it is cancelled by its handle or by closing the node.
+    pub fn start(&self) -> (Arc<CancelSwitch>, oneshot::Receiver<()>) {
+        let (tx, rx) = oneshot::channel();
+        let switch = Arc::new(CancelSwitch(Mutex::new(Some(tx))));
+        let mut switches = self.switches.lock().unwrap();
+        switches.retain(|switch| switch.strong_count() > 0);
+        switches.push(Arc::downgrade(&switch));
+        if self.tasks.is_closed() {
+            switch.cancel();
+        }
+        (switch, rx)
+    }
+
+    /// Run a subscription's task on `runtime`.
+    pub fn spawn<F>(&self, runtime: &Runtime, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        runtime.spawn(self.tasks.track_future(task));
+    }
+
+    /// Cancel every subscription and refuse new ones.
+    pub fn cancel_all(&self) {
+        let switches = {
+            let mut switches = self.switches.lock().unwrap();
+            self.tasks.close();
+            std::mem::take(&mut *switches)
+        };
+        for switch in switches.iter().filter_map(Weak::upgrade) {
+            switch.cancel();
+        }
+    }
+
+    /// Wait until every subscription task has finished.
+    pub async fn finished(&self) {
+        self.tasks.wait().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn test_cancel_all_stops_running_and_new_subscriptions() {
+        let runtime = Runtime::new().unwrap();
+        let subscriptions = Subscriptions::default();
+
+        let (_switch, cancelled) = subscriptions.start();
+        let completed = Arc::new(AtomicBool::new(false));
+        let done = completed.clone();
+        subscriptions.spawn(&runtime, async move {
+            let _ = cancelled.await;
+            done.store(true, Ordering::SeqCst);
+        });
+
+        subscriptions.cancel_all();
+        runtime.block_on(subscriptions.finished());
+        assert!(completed.load(Ordering::SeqCst));
+
+        let (_switch, mut late) = subscriptions.start();
+        assert!(late.try_recv().is_ok());
+    }
+}
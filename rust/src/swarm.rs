@@ -0,0 +1,254 @@
+//! Swarm statistics for document gossip topics.
+//!
+//! Each document syncs over a gossip topic derived from its namespace. The
+//! node follows the live events of every document opened on it and keeps
+//! counters that apps can poll to judge the health of the swarm, for
+//! example to decide when to bootstrap again from a known server peer.
+//! It also follows how syncs with each peer went, published as
+//! [`NodeEvent::PeerSync`] whenever one finishes.
+
+use crate::clock::{micros, now_micros};
+use crate::events::{EventBus, NodeEvent};
+use futures_lite::{Stream, StreamExt};
+use iroh::PublicKey;
+use iroh_docs::NamespaceId;
+use iroh_docs::engine::LiveEvent;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// Snapshot of one document's swarm activity.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SwarmStats {
+    /// Peers currently connected on the document's topic.
+    pub neighbors: u64,
+    /// Local inserts announced to the swarm.
+    pub messages_sent: u64,
+    /// Inserts received from peers.
+    pub messages_received: u64,
+    /// Time of the last event in microseconds since epoch, if any.
+    pub last_activity: Option<u64>,
+}
+
+/// How syncs of one document with one peer went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerSyncStats {
+    pub peer: PublicKey,
+    /// Time the last successful sync finished in microseconds since epoch,
+    /// if any.
+    pub last_success: Option<u64>,
+    /// Local inserts since the last successful sync, which the peer may
+    /// not have yet.
+    pub pending_entries: u64,
+    /// Syncs that failed since the last successful one.
+    pub failure_streak: u64,
+    /// Why the last sync failed, if it did.
+    pub last_error: Option<String>,
+}
+
+impl PeerSyncStats {
+    fn new(peer: PublicKey) -> Self {
+        Self {
+            peer,
+            last_success: None,
+            pending_entries: 0,
+            failure_streak: 0,
+            last_error: None,
+        }
+    }
+}
+
+/// Counters for one topic.
+#[derive(Debug, Default)]
+struct TopicState {
+    neighbors: HashSet<PublicKey>,
+    messages_sent: u64,
+    messages_received: u64,
+    last_activity: Option<u64>,
+    /// Peers that were neighbors or synced, by node ID.
+    peers: BTreeMap<PublicKey, PeerSyncStats>,
+    /// Local inserts before the first peer showed up.
+    unsynced_inserts: u64,
+}
+
+impl TopicState {
+    /// Count `event`, returning the peer's new sync statistics if it
+    /// finished a sync.
+    fn observe(&mut self, event: &LiveEvent, now: u64) -> Option<PeerSyncStats> {
+        self.last_activity = Some(now);
+        match event {
+            LiveEvent::InsertLocal { .. } => {
+                self.messages_sent += 1;
+                self.unsynced_inserts += 1;
+                for stats in self.peers.values_mut() {
+                    stats.pending_entries += 1;
+                }
+            }
+            LiveEvent::InsertRemote { .. } => self.messages_received += 1,
+            LiveEvent::NeighborUp(peer) => {
+                self.neighbors.insert(*peer);
+                self.peer(*peer);
+            }
+            LiveEvent::NeighborDown(peer) => {
+                self.neighbors.remove(peer);
+            }
+            LiveEvent::SyncFinished(sync) => {
+                let error = sync.result.as_ref().err().cloned();
+                return Some(self.synced(sync.peer, micros(sync.finished), error));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Record a sync with `peer` that finished at `finished`.
+    fn synced(&mut self, peer: PublicKey, finished: u64, error: Option<String>) -> PeerSyncStats {
+        let stats = self.peer(peer);
+        match error {
+            None => {
+                stats.last_success = Some(finished);
+                stats.pending_entries = 0;
+                stats.failure_streak = 0;
+                stats.last_error = None;
+            }
+            Some(e) => {
+                stats.failure_streak += 1;
+                stats.last_error = Some(e);
+            }
+        }
+        stats.clone()
+    }
+
+    /// Sync statistics of `peer`, which starts out missing every local insert.
+    fn peer(&mut self, peer: PublicKey) -> &mut PeerSyncStats {
+        let pending = self.unsynced_inserts;
+        self.peers.entry(peer).or_insert_with(|| PeerSyncStats {
+            pending_entries: pending,
+            ..PeerSyncStats::new(peer)
+        })
+    }
+
+    fn stats(&self) -> SwarmStats {
+        SwarmStats {
+            neighbors: self.neighbors.len() as u64,
+            messages_sent: self.messages_sent,
+            messages_received: self.messages_received,
+            last_activity: self.last_activity,
+        }
+    }
+}
+
+/// Swarm statistics for every tracked document on a node.
+#[derive(Debug, Clone, Default)]
+pub struct SwarmTracker(Arc<Mutex<HashMap<NamespaceId, TopicState>>>);
+
+impl SwarmTracker {
+    /// Claim `namespace` for tracking; false if it is already tracked.
+    pub fn begin(&self, namespace: NamespaceId) -> bool {
+        let mut topics = self.0.lock().unwrap();
+        if topics.contains_key(&namespace) {
+            return false;
+        }
+        topics.insert(namespace, TopicState::default());
+        true
+    }
+
+    /// Stop tracking `namespace` and drop its counters.
+    pub fn forget(&self, namespace: NamespaceId) {
+        self.0.lock().unwrap().remove(&namespace);
+    }
+
+    /// Current statistics for `namespace`; zero if it is not tracked.
+    pub fn stats(&self, namespace: NamespaceId) -> SwarmStats {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&namespace)
+            .map(TopicState::stats)
+            .unwrap_or_default()
+    }
+
+    /// Sync statistics of every peer of `namespace`, sorted by node ID;
+    /// empty if it is not tracked.
+    pub fn peer_stats(&self, namespace: NamespaceId) -> Vec<PeerSyncStats> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&namespace)
+            .map(|state| state.peers.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Count `events` for `namespace` until the stream ends, publishing
+    /// peer sync statistics on `bus`.
+    ///
+    /// The stream outlives document handles and ends when the node shuts
+    /// down, after which the namespace may be tracked again.
+    pub async fn run(
+        self,
+        namespace: NamespaceId,
+        mut events: impl Stream<Item = anyhow::Result<LiveEvent>> + Unpin,
+        bus: EventBus,
+    ) {
+        while let Some(Ok(event)) = events.next().await {
+            let synced = match self.0.lock().unwrap().get_mut(&namespace) {
+                Some(state) => state.observe(&event, now_micros()),
+                None => None,
+            };
+            if let Some(stats) = synced {
+                bus.emit(NodeEvent::PeerSync { namespace, stats });
+            }
+        }
+        self.forget(namespace);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::SecretKey;
+    use iroh_docs::engine::{Origin, SyncEvent, SyncReason};
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_topic_state_counts_neighbors() {
+        let alice = SecretKey::from_bytes(&[1; 32]).public();
+        let bob = SecretKey::from_bytes(&[2; 32]).public();
+        let mut state = TopicState::default();
+        state.observe(&LiveEvent::NeighborUp(alice), 10);
+        state.observe(&LiveEvent::NeighborUp(bob), 20);
+        state.observe(&LiveEvent::NeighborUp(bob), 30);
+        state.observe(&LiveEvent::NeighborDown(alice), 40);
+
+        let stats = state.stats();
+        assert_eq!(stats.neighbors, 1);
+        assert_eq!(stats.last_activity, Some(40));
+    }
+
+    #[test]
+    fn test_topic_state_tracks_peer_syncs() {
+        let alice = SecretKey::from_bytes(&[1; 32]).public();
+        let finished = UNIX_EPOCH + std::time::Duration::from_micros(50);
+        let failed = |error: &str| {
+            LiveEvent::SyncFinished(SyncEvent {
+                peer: alice,
+                origin: Origin::Connect(SyncReason::DirectJoin),
+                finished,
+                started: finished,
+                result: Err(error.to_string()),
+            })
+        };
+        let mut state = TopicState::default();
+        state.observe(&LiveEvent::NeighborUp(alice), 10);
+
+        state.observe(&failed("timed out"), 20);
+        let stats = state.observe(&failed("refused"), 30).unwrap();
+        assert_eq!(stats.failure_streak, 2);
+        assert_eq!(stats.last_error.as_deref(), Some("refused"));
+        assert_eq!(stats.last_success, None);
+
+        let synced = state.synced(alice, 50, None);
+        assert_eq!(synced.last_success, Some(50));
+        assert_eq!((synced.failure_streak, synced.last_error), (0, None));
+        assert_eq!(state.peers[&alice].pending_entries, 0);
+    }
+}
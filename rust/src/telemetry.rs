@@ -0,0 +1,182 @@
+//! OpenTelemetry export of FFI operations.
+//!
+//! With the `otel` feature, every operation started by [`crate::trace`] is
+//! exported to an OTLP/HTTP collector as a span, and counted in metrics
+//! broken down by operation name and outcome. Export is process-wide and
+//! starts with the first node configured with a collector endpoint; that
+//! node's app identifier is attached to everything exported, so apps
+//! sharing a collector can be told apart.
+
+use anyhow::{Result, bail};
+use opentelemetry::metrics::{Counter, Histogram, MeterProvider};
+use opentelemetry::trace::{Span as _, Status, Tracer, TracerProvider};
+use opentelemetry::{KeyValue, StringValue};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Name reported to the collector as `service.name`.
+const SERVICE_NAME: &str = "iroh-swift";
+
+/// Resource attribute holding the app identifier.
+const APP_ID_ATTRIBUTE: &str = "iroh.app_id";
+
+/// Providers and instruments of a running export.
+struct Exporter {
+    endpoint: String,
+    app_id: Option<String>,
+    tracer_provider: SdkTracerProvider,
+    meter_provider: SdkMeterProvider,
+    tracer: SdkTracer,
+    operations: Counter<u64>,
+    duration: Histogram<f64>,
+}
+
+static EXPORTER: Mutex<Option<Exporter>> = Mutex::new(None);
+
+thread_local! {
+    /// Why the operation running on this thread failed, if it did.
+    static FAILURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Start exporting to the collector at `endpoint`, e.g.
+/// "https://otel.example.com:4318".
+///
+/// Does nothing if already exporting to `endpoint` for `app_id`; a
+/// different endpoint or app is an error.
+pub fn init(endpoint: &str, app_id: Option<&str>) -> Result<()> {
+    let endpoint = endpoint.trim_end_matches('/');
+    let mut exporter = EXPORTER.lock().unwrap();
+    if let Some(exporter) = exporter.as_ref() {
+        if exporter.endpoint != endpoint {
+            bail!("already exporting telemetry to {}", exporter.endpoint);
+        }
+        if exporter.app_id.as_deref() != app_id {
+            bail!(
+                "already exporting telemetry for app {}",
+                exporter.app_id.as_deref().unwrap_or("(none)")
+            );
+        }
+        return Ok(());
+    }
+
+    let mut resource = Resource::builder().with_service_name(SERVICE_NAME);
+    if let Some(app_id) = app_id {
+        resource = resource.with_attribute(KeyValue::new(APP_ID_ATTRIBUTE, app_id.to_string()));
+    }
+    let resource = resource.build();
+    let spans = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}/v1/traces"))
+        .build()?;
+    let tracer_provider = SdkTracerProvider::builder()
+        .with_batch_exporter(spans)
+        .with_resource(resource.clone())
+        .build();
+    let metrics = MetricExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}/v1/metrics"))
+        .build()?;
+    let meter_provider = SdkMeterProvider::builder()
+        .with_periodic_exporter(metrics)
+        .with_resource(resource)
+        .build();
+
+    let meter = meter_provider.meter(SERVICE_NAME);
+    *exporter = Some(Exporter {
+        endpoint: endpoint.to_string(),
+        app_id: app_id.map(str::to_string),
+        tracer: tracer_provider.tracer(SERVICE_NAME),
+        operations: meter
+            .u64_counter("iroh.operations")
+            .with_description("FFI operations by name and outcome")
+            .build(),
+        duration: meter
+            .f64_histogram("iroh.operation.duration")
+            .with_description("Time taken by FFI operations")
+            .with_unit("s")
+            .build(),
+        tracer_provider,
+        meter_provider,
+    });
+    Ok(())
+}
+
+/// Send buffered spans and metrics to the collector.
+pub fn flush() {
+    if let Some(exporter) = EXPORTER.lock().unwrap().as_ref() {
+        let _ = exporter.tracer_provider.force_flush();
+        let _ = exporter.meter_provider.force_flush();
+    }
+}
+
+/// Span of an operation; exported when dropped.
+#[derive(Debug)]
+pub struct OperationSpan {
+    name: &'static str,
+    span: opentelemetry_sdk::trace::Span,
+    started: Instant,
+}
+
+/// Start the span of operation `id`, or None if nothing is exported.
+pub fn start(name: &'static str, id: u64) -> Option<OperationSpan> {
+    let exporter = EXPORTER.lock().unwrap();
+    let exporter = exporter.as_ref()?;
+    let mut span = exporter.tracer.start(name);
+    span.set_attribute(KeyValue::new("iroh.operation_id", id as i64));
+    FAILURE.set(None);
+    Some(OperationSpan {
+        name,
+        span,
+        started: Instant::now(),
+    })
+}
+
+/// Mark the operation running on this thread as failed.
+pub fn fail(message: &str) {
+    FAILURE.set(Some(message.to_string()));
+}
+
+impl Drop for OperationSpan {
+    fn drop(&mut self) {
+        let failure = FAILURE.take();
+        let outcome = if failure.is_some() { "error" } else { "ok" };
+        if let Some(message) = failure {
+            self.span.set_status(Status::error(message));
+        }
+        self.span.end();
+
+        if let Some(exporter) = EXPORTER.lock().unwrap().as_ref() {
+            let attributes = [
+                KeyValue::new("operation", StringValue::from(self.name)),
+                KeyValue::new("outcome", outcome),
+            ];
+            exporter.operations.add(1, &attributes);
+            exporter
+                .duration
+                .record(self.started.elapsed().as_secs_f64(), &attributes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_is_shared_by_endpoint() {
+        init("http://127.0.0.1:4318/", Some("com.example.app")).unwrap();
+        init("http://127.0.0.1:4318", Some("com.example.app")).unwrap();
+        assert!(init("http://127.0.0.1:4319", Some("com.example.app")).is_err());
+        assert!(init("http://127.0.0.1:4318", Some("com.example.other")).is_err());
+
+        let span = start("iroh_test", 1).expect("exporting");
+        fail("boom");
+        drop(span);
+        assert!(FAILURE.take().is_none());
+    }
+}
@@ -0,0 +1,91 @@
+//! Deterministic nodes for test suites.
+//!
+//! Test suites want the same node IDs and tickets on every run, and several
+//! nodes in one process that reach each other without a network. A test
+//! node derives its secret key from a seed, binds to loopback only and uses
+//! neither relays nor network discovery. Instead, test nodes publish their
+//! addresses in a registry shared by the process, so their tickets carry
+//! only the node ID and depend on nothing but the seed and the content.
+//!
+//! Test nodes keep blobs and documents in memory, so nothing of a test
+//! survives the node. The rest of their state, such as pending work, is
+//! still written to the storage directory, which an ephemeral node removes
+//! again when it closes.
+
+use futures_lite::StreamExt;
+use iroh::discovery::static_provider::StaticProvider;
+use iroh::{Endpoint, EndpointAddr, EndpointId, SecretKey, Watcher};
+use iroh_blobs::Hash;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use std::sync::LazyLock;
+
+/// Addresses of the test nodes in this process.
+static REGISTRY: LazyLock<StaticProvider> =
+    LazyLock::new(|| StaticProvider::with_provenance("test_registry"));
+
+/// Address a test node binds to for IPv4.
+pub const BIND_V4: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0);
+
+/// Address a test node binds to for IPv6.
+pub const BIND_V6: SocketAddrV6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 0, 0, 0);
+
+/// Derive 32 key bytes for `purpose` from `seed`.
+fn derive(purpose: &str, seed: &str) -> [u8; 32] {
+    *Hash::new(format!("iroh-swift test {purpose}\0{seed}")).as_bytes()
+}
+
+/// Secret key of the test node with `seed`.
+pub fn secret_key(seed: &str) -> SecretKey {
+    SecretKey::from_bytes(&derive("node", seed))
+}
+
+/// Secret key bytes of the test author with `seed`.
+#[cfg(feature = "docs")]
+pub fn author_secret(seed: &str) -> [u8; 32] {
+    derive("author", seed)
+}
+
+/// Discovery resolving the test nodes of this process.
+pub fn registry() -> StaticProvider {
+    REGISTRY.clone()
+}
+
+/// Publish `endpoint`'s addresses in the registry now, then keep them
+/// current until the endpoint closes.
+pub fn register(endpoint: Endpoint) -> impl Future<Output = ()> {
+    REGISTRY.set_endpoint_info(endpoint.addr());
+    async move {
+        let mut addrs = endpoint.watch_addr().stream_updates_only();
+        while let Some(addr) = addrs.next().await {
+            REGISTRY.set_endpoint_info(addr);
+        }
+    }
+}
+
+/// Remove a test node from the registry.
+pub fn unregister(id: EndpointId) {
+    REGISTRY.remove_endpoint_info(id);
+}
+
+/// Address put in a test node's tickets: just its ID, resolved through the
+/// registry.
+pub fn ticket_addr(id: EndpointId) -> EndpointAddr {
+    EndpointAddr::new(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_depend_only_on_seed() {
+        assert_eq!(secret_key("alice").public(), secret_key("alice").public());
+        assert_ne!(secret_key("alice").public(), secret_key("bob").public());
+        #[cfg(feature = "docs")]
+        {
+            assert_eq!(author_secret("alice"), author_secret("alice"));
+            // A node and an author with the same seed do not share a key
+            assert_ne!(author_secret("alice"), secret_key("alice").to_bytes());
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Alternate ticket encodings.
+//!
+//! Standard tickets are a kind prefix (`blob`, `doc`) followed by lowercase
+//! base32. These helpers convert them into forms that are easier to put into
+//! QR codes or to read aloud, and back again.
+
+use anyhow::{Result, bail};
+use iroh_blobs::ticket::BlobTicket;
+#[cfg(feature = "docs")]
+use iroh_docs::DocTicket;
+
+/// Standard base32 alphabet used by iroh tickets (lowercased).
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Human-oriented z-base-32 alphabet.
+const ZBASE32_ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+
+/// Marker prepended to z-base-32 encoded tickets.
+const ZBASE32_MARKER: &str = "z";
+
+/// Ticket kinds that can be re-encoded.
+const TICKET_KINDS: [&str; 2] = ["blob", "doc"];
+
+/// Alternate encodings for ticket strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketEncoding {
+    /// The standard lowercase ticket string.
+    Standard,
+    /// Uppercase ticket, which fits the QR alphanumeric mode.
+    QrAlphanumeric,
+    /// z-base-32 payload, avoiding easily confused characters.
+    ZBase32,
+}
+
+/// Re-encode a standard ticket string.
+///
+/// The ticket must parse as a blob or doc ticket.
+pub fn encode(ticket: &str, encoding: TicketEncoding) -> Result<String> {
+    let standard = normalize(ticket)?;
+    match encoding {
+        TicketEncoding::Standard => Ok(standard),
+        TicketEncoding::QrAlphanumeric => Ok(standard.to_ascii_uppercase()),
+        TicketEncoding::ZBase32 => {
+            let (kind, payload) = split_kind(&standard)?;
+            let payload = translate(payload, BASE32_ALPHABET, ZBASE32_ALPHABET)?;
+            Ok(format!("{ZBASE32_MARKER}{kind}{payload}"))
+        }
+    }
+}
+
+/// Decode a ticket in any supported encoding back to the standard form.
+pub fn decode(encoded: &str) -> Result<String> {
+    let trimmed = encoded.trim();
+    if let Some(rest) = trimmed.strip_prefix(ZBASE32_MARKER)
+        && let Ok((kind, payload)) = split_kind(rest)
+    {
+        let payload = translate(payload, ZBASE32_ALPHABET, BASE32_ALPHABET)?;
+        return normalize(&format!("{kind}{payload}"));
+    }
+    normalize(&trimmed.to_ascii_lowercase())
+}
+
+/// Validate a ticket and return its canonical string form.
+fn normalize(ticket: &str) -> Result<String> {
+    if let Ok(t) = ticket.parse::<BlobTicket>() {
+        return Ok(t.to_string());
+    }
+    #[cfg(feature = "docs")]
+    if let Ok(t) = ticket.parse::<DocTicket>() {
+        return Ok(t.to_string());
+    }
+    bail!("not a blob or doc ticket")
+}
+
+/// Split a ticket into its kind prefix and payload.
+fn split_kind(ticket: &str) -> Result<(&'static str, &str)> {
+    for kind in TICKET_KINDS {
+        if let Some(payload) = ticket.strip_prefix(kind) {
+            return Ok((kind, payload));
+        }
+    }
+    bail!("unknown ticket kind")
+}
+
+/// Map each character from one 32-symbol alphabet to another.
+fn translate(payload: &str, from: &[u8; 32], to: &[u8; 32]) -> Result<String> {
+    payload
+        .bytes()
+        .map(|c| {
+            from.iter()
+                .position(|&f| f == c)
+                .map(|i| to[i] as char)
+                .ok_or_else(|| anyhow::anyhow!("invalid character '{}' in ticket", c as char))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iroh::{EndpointAddr, SecretKey};
+    use iroh_blobs::{BlobFormat, Hash};
+
+    fn sample_ticket() -> String {
+        let key = SecretKey::from_bytes(&[7u8; 32]);
+        let addr = EndpointAddr::new(key.public());
+        BlobTicket::new(addr, Hash::new(b"hello"), BlobFormat::Raw).to_string()
+    }
+
+    #[test]
+    fn test_encodings_roundtrip() {
+        let ticket = sample_ticket();
+        for encoding in [
+            TicketEncoding::Standard,
+            TicketEncoding::QrAlphanumeric,
+            TicketEncoding::ZBase32,
+        ] {
+            let encoded = encode(&ticket, encoding).unwrap();
+            assert_eq!(decode(&encoded).unwrap(), ticket);
+        }
+    }
+
+    #[test]
+    fn test_qr_encoding_is_alphanumeric() {
+        let encoded = encode(&sample_ticket(), TicketEncoding::QrAlphanumeric).unwrap();
+        assert!(
+            encoded
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert!(decode("not-a-ticket").is_err());
+        assert!(encode("blobxyz", TicketEncoding::ZBase32).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Ids correlating FFI operations with their failures and events.
+//!
+//! Each FFI call that reports back through a callback is given an id when
+//! it starts. Its callbacks run inside the operation, where they can read
+//! the id whether the call succeeded or failed, and the download events
+//! the call causes carry it, so a failure shown to a user can be matched
+//! with the node's logs and event stream. Operations run in a `tracing`
+//! span with the id as its `operation_id` field, and with the `otel`
+//! feature they are also exported as spans.
+//!
+//! Running operations are recorded with the stage they are in, so the
+//! watchdog can report those that take too long, and are counted in
+//! [`crate::stats`] when they end.
+
+use crate::stats;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::fmt::Display;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Operations that have started and not yet ended, by id.
+static RUNNING: Mutex<BTreeMap<u64, Running>> = Mutex::new(BTreeMap::new());
+
+/// A running operation.
+#[derive(Debug)]
+struct Running {
+    name: &'static str,
+    stage: &'static str,
+    started: Instant,
+    /// Whether it has been reported as slow.
+    reported: bool,
+    /// Whether it reported a failure.
+    failed: bool,
+}
+
+/// An operation that has run longer than a threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowOperation {
+    pub id: u64,
+    /// Name of the FFI function that started it.
+    pub name: &'static str,
+    /// What it was doing when it was reported.
+    pub stage: &'static str,
+    /// How long it had been running.
+    pub elapsed: Duration,
+}
+
+thread_local! {
+    /// The operation running on this thread, if any.
+    static CURRENT: Cell<Option<u64>> = const { Cell::new(None) };
+    /// The operation most recently started on this thread, or 0 if none.
+    static LAST: Cell<u64> = const { Cell::new(0) };
+}
+
+/// An operation running on the current thread; ends when dropped.
+#[derive(Debug)]
+pub struct Operation {
+    previous: Option<u64>,
+    /// Entered until the operation ends.
+    _tracing: tracing::span::EnteredSpan,
+    /// Exported when the operation ends.
+    #[cfg(feature = "otel")]
+    _span: Option<crate::telemetry::OperationSpan>,
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        let Some(id) = CURRENT.replace(self.previous) else {
+            return;
+        };
+        let running = RUNNING.lock().unwrap().remove(&id);
+        if let Some(running) = running {
+            stats::operation_ended(running.name, running.failed);
+        }
+    }
+}
+
+/// Start operation `name` on the current thread.
+///
+/// Ids are unique for the lifetime of the process and never 0.
+pub fn begin(name: &'static str) -> Operation {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    LAST.set(id);
+    RUNNING.lock().unwrap().insert(
+        id,
+        Running {
+            name,
+            stage: "started",
+            started: Instant::now(),
+            reported: false,
+            failed: false,
+        },
+    );
+    Operation {
+        previous: CURRENT.replace(Some(id)),
+        _tracing: tracing::info_span!("operation", name, operation_id = id).entered(),
+        #[cfg(feature = "otel")]
+        _span: crate::telemetry::start(name, id),
+    }
+}
+
+/// The operation running on the current thread, if any.
+pub fn current() -> Option<u64> {
+    CURRENT.get()
+}
+
+/// The operation most recently started on the current thread, or 0 if none.
+pub fn last() -> u64 {
+    LAST.get()
+}
+
+/// Record that the operation running on this thread entered `stage`.
+pub fn stage(stage: &'static str) {
+    let Some(id) = current() else {
+        return;
+    };
+    if let Some(running) = RUNNING.lock().unwrap().get_mut(&id) {
+        running.stage = stage;
+    }
+}
+
+/// Operations running for at least `threshold` that were not reported yet.
+///
+/// Each operation is returned at most once.
+pub fn overdue(threshold: Duration) -> Vec<SlowOperation> {
+    let mut running = RUNNING.lock().unwrap();
+    running
+        .iter_mut()
+        .filter(|(_, op)| !op.reported && op.started.elapsed() >= threshold)
+        .map(|(&id, op)| {
+            op.reported = true;
+            SlowOperation {
+                id,
+                name: op.name,
+                stage: op.stage,
+                elapsed: op.started.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// Error message for `error`, recording that the current operation failed.
+pub fn failure(error: impl Display) -> CString {
+    let message = format!("{error:#}");
+    if let Some(id) = current() {
+        if let Some(running) = RUNNING.lock().unwrap().get_mut(&id) {
+            running.failed = true;
+        }
+        tracing::debug!(operation_id = id, error = %message, "operation failed");
+    }
+    #[cfg(feature = "otel")]
+    crate::telemetry::fail(&message);
+    CString::new(message).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_operations() {
+        assert_eq!(current(), None);
+        let operation = begin("test");
+        let outer = current().unwrap();
+        assert_eq!(last(), outer);
+        {
+            let _inner = begin("test");
+            assert!(current().unwrap() > outer);
+            assert_eq!(last(), current().unwrap());
+        }
+        assert_eq!(current(), Some(outer));
+        assert!(last() > outer);
+
+        let message = failure(anyhow::anyhow!("boom"));
+        assert_eq!(message.to_str().unwrap(), "boom");
+        assert!(RUNNING.lock().unwrap()[&outer].failed);
+
+        drop(operation);
+        assert_eq!(current(), None);
+        assert_eq!(failure("boom").to_str().unwrap(), "boom");
+    }
+}
@@ -0,0 +1,59 @@
+//! Reports operations that run longer than a threshold.
+//!
+//! Field reports of operations "spinning forever" are hard to diagnose after
+//! the fact. The watchdog checks running FFI operations periodically and
+//! reports each one that exceeds the threshold once, with its operation id
+//! and the stage it is stuck in. Operations of every node in the process
+//! are watched, and each is reported by only one watchdog.
+
+use crate::events::{EventBus, NodeEvent};
+use crate::trace::{self, SlowOperation};
+use std::time::Duration;
+
+/// Shortest time between checks for slow operations.
+const MIN_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Called for each operation that exceeds the threshold.
+pub type OnSlow = dyn Fn(&SlowOperation) + Send + Sync;
+
+/// Threshold for slow operations and how they are reported.
+pub struct Watchdog {
+    /// Running time after which an operation counts as slow.
+    pub threshold: Duration,
+    /// Whether slow operations are also published as node events.
+    pub emit_events: bool,
+    /// Told about each slow operation, if set.
+    pub on_slow: Option<Box<OnSlow>>,
+}
+
+impl std::fmt::Debug for Watchdog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("threshold", &self.threshold)
+            .field("emit_events", &self.emit_events)
+            .finish()
+    }
+}
+
+impl Watchdog {
+    /// Check for slow operations until the runtime stops.
+    ///
+    /// Operations are checked four times per threshold, so one is reported
+    /// at most a quarter of the threshold after it becomes slow.
+    pub async fn run(self, events: EventBus) {
+        let interval = (self.threshold / 4).max(MIN_CHECK_INTERVAL);
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            for slow in trace::overdue(self.threshold) {
+                if let Some(on_slow) = &self.on_slow {
+                    on_slow(&slow);
+                }
+                if self.emit_events {
+                    events.emit(NodeEvent::SlowOperation(slow));
+                }
+            }
+        }
+    }
+}
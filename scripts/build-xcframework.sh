@@ -7,9 +7,24 @@ PROJECT_ROOT="$(dirname "$SCRIPT_DIR")"
 RUST_DIR="$PROJECT_ROOT/rust"
 OUT_DIR="$PROJECT_ROOT/build"
 XCFRAMEWORK="$PROJECT_ROOT/IrohSwiftFFI.xcframework"
+MODULE_NAME="IrohSwiftFFI"
 
 echo "Building iroh-swift FFI..."
 
+# Optional Cargo features, e.g. IROH_FEATURES=otel for OTLP telemetry export
+FEATURE_ARGS=()
+if [ -n "$IROH_FEATURES" ]; then
+    FEATURE_ARGS=(--features "$IROH_FEATURES")
+fi
+
+# IROH_MINIMAL=1 leaves out docs, SQLite export and download progress, for
+# targets such as share extensions that only put and get blobs
+if [ -n "$IROH_MINIMAL" ]; then
+    FEATURE_ARGS+=(--no-default-features)
+    XCFRAMEWORK="$PROJECT_ROOT/IrohSwiftFFIMinimal.xcframework"
+    MODULE_NAME="IrohSwiftFFIMinimal"
+fi
+
 # Rust targets for Apple platforms (aarch64 only)
 TARGETS=(
     "aarch64-apple-ios"
@@ -39,8 +54,10 @@ for target in "${TARGETS[@]}"; do
     esac
 
     cargo build --manifest-path "$RUST_DIR/Cargo.toml" \
+        --lib \
         --target "$target" \
-        --release
+        --release \
+        "${FEATURE_ARGS[@]}"
 done
 
 # Generate C header
@@ -49,12 +66,40 @@ cd "$RUST_DIR"
 cbindgen --config cbindgen.toml \
     --crate iroh-swift-ffi \
     --output "$PROJECT_ROOT/include/iroh_swift.h"
+
+# Record the features this build has, so the header only declares the C
+# exports the library contains
+FEATURES=$(cargo rustc --lib --release --target "${TARGETS[0]}" "${FEATURE_ARGS[@]}" \
+    -- --print cfg | sed -n 's/^feature="\(.*\)"$/\1/p')
 cd "$PROJECT_ROOT"
 
 # Create output directories
 echo "Creating output directories..."
 rm -rf "$OUT_DIR"
-mkdir -p "$OUT_DIR"/{ios-device,ios-simulator,macos}
+mkdir -p "$OUT_DIR"/{ios-device,ios-simulator,macos,headers}
+
+# Headers shipped in the XCFramework
+echo "Writing headers for: $(echo $FEATURES)"
+cp "$PROJECT_ROOT/include/iroh_swift.h" "$OUT_DIR/headers/"
+{
+    echo "/* Cargo features the library was built with. */"
+    echo ""
+    echo "#ifndef IROH_SWIFT_FEATURES_H"
+    echo "#define IROH_SWIFT_FEATURES_H"
+    echo ""
+    for feature in $FEATURES; do
+        echo "#define IROH_FEATURE_$(echo "$feature" | tr 'a-z-' 'A-Z_')"
+    done
+    echo ""
+    echo "#endif /* IROH_SWIFT_FEATURES_H */"
+} > "$OUT_DIR/headers/iroh_swift_features.h"
+cat > "$OUT_DIR/headers/module.modulemap" <<EOF
+module $MODULE_NAME {
+    header "iroh_swift.h"
+    header "iroh_swift_features.h"
+    export *
+}
+EOF
 
 # iOS Device (arm64)
 echo "Creating iOS device library..."
@@ -76,11 +121,11 @@ echo "Creating XCFramework..."
 rm -rf "$XCFRAMEWORK"
 xcodebuild -create-xcframework \
     -library "$OUT_DIR/ios-device/libiroh_swift.a" \
-    -headers "$PROJECT_ROOT/include" \
+    -headers "$OUT_DIR/headers" \
     -library "$OUT_DIR/ios-simulator/libiroh_swift.a" \
-    -headers "$PROJECT_ROOT/include" \
+    -headers "$OUT_DIR/headers" \
     -library "$OUT_DIR/macos/libiroh_swift.a" \
-    -headers "$PROJECT_ROOT/include" \
+    -headers "$OUT_DIR/headers" \
     -output "$XCFRAMEWORK"
 
 echo ""
@@ -1,10 +1,10 @@
 #!/bin/bash
 # Release automation script for iroh-swift
 # This script:
-# 1. Builds the XCFramework
-# 2. Creates a zip archive
-# 3. Computes SHA256 checksum
-# 4. Updates Package.swift with the checksum
+# 1. Builds the full and minimal XCFrameworks
+# 2. Creates zip archives
+# 3. Computes SHA256 checksums
+# 4. Updates Package.swift with the checksums
 
 set -e
 
@@ -20,6 +20,7 @@ echo "Releasing version: $VERSION"
 # Build the XCFramework
 echo "Building XCFramework..."
 ./scripts/build-xcframework.sh
+IROH_MINIMAL=1 ./scripts/build-xcframework.sh
 
 # Create zip archive
 XCFRAMEWORK_PATH="IrohSwiftFFI.xcframework"
@@ -43,14 +44,23 @@ echo "Checksum: $CHECKSUM"
 echo "Updating Package.swift..."
 sed -i '' "s/let checksum = \".*\"/let checksum = \"$CHECKSUM\"/" Package.swift
 
+# Same for the minimal XCFramework
+MINIMAL_ZIP_NAME="IrohSwiftFFIMinimal.xcframework.zip"
+rm -f "$MINIMAL_ZIP_NAME"
+zip -r -X "$MINIMAL_ZIP_NAME" "IrohSwiftFFIMinimal.xcframework"
+MINIMAL_CHECKSUM=$(shasum -a 256 "$MINIMAL_ZIP_NAME" | awk '{print $1}')
+echo "Minimal checksum: $MINIMAL_CHECKSUM"
+sed -i '' "s/let minimalChecksum = \".*\"/let minimalChecksum = \"$MINIMAL_CHECKSUM\"/" Package.swift
+
 echo ""
 echo "Release preparation complete!"
 echo "  Version: $VERSION"
 echo "  Checksum: $CHECKSUM"
-echo "  Artifact: $ZIP_NAME"
+echo "  Minimal checksum: $MINIMAL_CHECKSUM"
+echo "  Artifacts: $ZIP_NAME, $MINIMAL_ZIP_NAME"
 echo ""
 echo "Next steps:"
 echo "  1. Commit the updated Package.swift"
 echo "  2. Create a git tag: git tag v$VERSION"
 echo "  3. Push the tag: git push origin v$VERSION"
-echo "  4. Create a GitHub release and upload $ZIP_NAME"
+echo "  4. Create a GitHub release and upload $ZIP_NAME and $MINIMAL_ZIP_NAME"